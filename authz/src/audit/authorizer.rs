@@ -0,0 +1,329 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::{AuditEvent, AuditOutcome, AuditSink};
+use crate::{Authorizer, Error, Permission, Resource};
+
+/// An auditing decorator over an [`Authorizer`] implementation.
+///
+/// An [`AuditEvent`] is recorded for each permission requested from the
+/// decorated [`Authorizer::permissions()`] call, with an outcome of
+/// [`AuditOutcome::Allowed`] or [`AuditOutcome::Denied`], or
+/// [`AuditOutcome::Failed`] if the permissions could not be verified.
+///
+/// A call requesting a namespace permission alongside the same action on
+/// tables of that namespace is recorded as a single decision: if the
+/// namespace permission is granted only it is recorded, as it implies the
+/// table permissions, otherwise only the table permissions are recorded.
+///
+/// The subject of the events is the identity of a token verified by the
+/// decorated [`Authorizer`] (see [`Authorizer::verified_subject()`]), or the
+/// fingerprint of the token otherwise.
+///
+/// Requests presenting the [exempt token](Self::with_exempt_token) are not
+/// recorded.
+#[derive(Debug)]
+pub struct AuditingAuthorizer<T> {
+    inner: T,
+    sink: Arc<dyn AuditSink>,
+    exempt_token: Option<Vec<u8>>,
+}
+
+impl<T> AuditingAuthorizer<T> {
+    /// Record the decisions of `inner` to `sink`.
+    pub fn new(inner: T, sink: Arc<dyn AuditSink>) -> Self {
+        Self {
+            inner,
+            sink,
+            exempt_token: None,
+        }
+    }
+
+    /// Do not record the decisions for requests presenting `token`.
+    ///
+    /// This must be set to the token an [`AuditSink`] uses to write events
+    /// through the audited service, as otherwise each write of an event
+    /// records another event.
+    pub fn with_exempt_token(mut self, token: impl Into<Vec<u8>>) -> Self {
+        self.exempt_token = Some(token.into());
+        self
+    }
+}
+
+#[async_trait]
+impl<T> Authorizer for AuditingAuthorizer<T>
+where
+    T: Authorizer,
+{
+    async fn permissions(
+        &self,
+        token: Option<Vec<u8>>,
+        perms: &[Permission],
+    ) -> Result<Vec<Permission>, Error> {
+        let res = self.inner.permissions(token.clone(), perms).await;
+        if token.is_some() && token == self.exempt_token {
+            return res;
+        }
+
+        // Only a token accepted by the authorizer has a verified identity.
+        let subject = match (&token, &res) {
+            (Some(token), Ok(_) | Err(Error::Forbidden)) => {
+                self.inner.verified_subject(token).await
+            }
+            _ => None,
+        };
+
+        let granted = |perm: &Permission| matches!(&res, Ok(granted) if granted.contains(perm));
+        for perm in perms {
+            let Permission::ResourceAction(resource, action) = perm;
+            let superseded = match resource {
+                Resource::Database(namespace) => {
+                    !granted(perm)
+                        && perms.iter().any(|p| {
+                            matches!(
+                                p,
+                                Permission::ResourceAction(Resource::Table { database, .. }, a)
+                                    if database == namespace && a == action
+                            )
+                        })
+                }
+                Resource::Table { database, .. } => granted(&Permission::ResourceAction(
+                    Resource::Database(database.clone()),
+                    *action,
+                )),
+            };
+            if superseded {
+                continue;
+            }
+
+            let outcome = match &res {
+                Ok(_) if granted(perm) => AuditOutcome::Allowed,
+                Ok(_) | Err(Error::Forbidden | Error::InvalidToken | Error::NoToken) => {
+                    AuditOutcome::Denied
+                }
+                Err(Error::Verification { .. }) => AuditOutcome::Failed,
+            };
+
+            let event = AuditEvent::new(format!("authz:{}", action.as_str()), outcome);
+            let event = match &subject {
+                Some(subject) => event.with_verified_subject(subject),
+                None => event.with_token(token.as_deref()),
+            };
+            let event = match resource {
+                Resource::Database(namespace) => event.with_namespace(namespace),
+                Resource::Table { database, table } => {
+                    event.with_namespace(database).with_table(table)
+                }
+            };
+            self.sink.record(event).await;
+        }
+
+        res
+    }
+
+    async fn verified_subject(&self, token: &[u8]) -> Option<String> {
+        self.inner.verified_subject(token).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+    use crate::{audit::mock::MockAuditSink, Action};
+
+    /// Grants write access to the "bananas" namespace for the token "GOOD",
+    /// and to the "cpu" table of any namespace for the token "CPU".
+    #[derive(Debug)]
+    struct MockAuthorizer;
+
+    #[async_trait]
+    impl Authorizer for MockAuthorizer {
+        async fn permissions(
+            &self,
+            token: Option<Vec<u8>>,
+            perms: &[Permission],
+        ) -> Result<Vec<Permission>, Error> {
+            match token.as_deref() {
+                Some(b"GOOD") => {
+                    let got = perms
+                        .iter()
+                        .filter(|p| {
+                            matches!(
+                                p,
+                                Permission::ResourceAction(Resource::Database(db), Action::Write)
+                                    if db == "bananas"
+                            )
+                        })
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    if got.is_empty() {
+                        return Err(Error::Forbidden);
+                    }
+                    Ok(got)
+                }
+                Some(b"CPU") => {
+                    let got = perms
+                        .iter()
+                        .filter(|p| {
+                            matches!(
+                                p,
+                                Permission::ResourceAction(Resource::Table { table, .. }, _)
+                                    if table == "cpu"
+                            )
+                        })
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    if got.is_empty() {
+                        return Err(Error::Forbidden);
+                    }
+                    Ok(got)
+                }
+                Some(b"UGLY") => Err(Error::verification("test", "test error")),
+                Some(_) => Err(Error::InvalidToken),
+                None => Err(Error::NoToken),
+            }
+        }
+
+        async fn verified_subject(&self, token: &[u8]) -> Option<String> {
+            match token {
+                b"GOOD" => Some("mock:good".to_string()),
+                _ => None,
+            }
+        }
+    }
+
+    fn perm(resource: Resource, action: Action) -> Permission {
+        Permission::ResourceAction(resource, action)
+    }
+
+    #[tokio::test]
+    async fn test_audit_decisions() {
+        let sink = Arc::new(MockAuditSink::default());
+        let authz = AuditingAuthorizer::new(MockAuthorizer, Arc::clone(&sink) as _);
+
+        let perms = [
+            perm(Resource::Database("bananas".to_string()), Action::Write),
+            perm(
+                Resource::Table {
+                    database: "platanos".to_string(),
+                    table: "cpu".to_string(),
+                },
+                Action::Write,
+            ),
+        ];
+
+        let got = authz
+            .permissions(Some(b"GOOD".to_vec()), &perms)
+            .await
+            .unwrap();
+        assert_eq!(got, perms[..1]);
+
+        let events = sink.events();
+        assert_matches!(events.as_slice(), [allowed, denied] => {
+            assert_eq!(allowed.operation, "authz:write");
+            assert_eq!(allowed.namespace.as_deref(), Some("bananas"));
+            assert_eq!(allowed.table, None);
+            assert_eq!(allowed.outcome, AuditOutcome::Allowed);
+            assert_eq!(allowed.subject.as_deref(), Some("mock:good"));
+
+            assert_eq!(denied.namespace.as_deref(), Some("platanos"));
+            assert_eq!(denied.subject.as_deref(), Some("mock:good"));
+            assert_eq!(denied.table.as_deref(), Some("cpu"));
+            assert_eq!(denied.outcome, AuditOutcome::Denied);
+        });
+
+        authz.permissions(None, &perms[..1]).await.unwrap_err();
+        authz
+            .permissions(Some(b"UGLY".to_vec()), &perms[..1])
+            .await
+            .unwrap_err();
+        authz
+            .permissions(Some(b"BAD".to_vec()), &perms[..1])
+            .await
+            .unwrap_err();
+
+        let events = sink.events();
+        assert_matches!(&events[2..], [no_token, failed, invalid] => {
+            assert_eq!(no_token.subject, None);
+            assert_eq!(no_token.outcome, AuditOutcome::Denied);
+            assert_eq!(failed.outcome, AuditOutcome::Failed);
+            assert_eq!(failed.subject, Some(crate::audit::token_subject(b"UGLY")));
+            // An invalid token is only recorded by its fingerprint.
+            assert_eq!(invalid.outcome, AuditOutcome::Denied);
+            assert_eq!(invalid.subject, Some(crate::audit::token_subject(b"BAD")));
+        });
+    }
+
+    fn table(database: &str, table: &str) -> Resource {
+        Resource::Table {
+            database: database.to_string(),
+            table: table.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audit_namespace_and_tables_as_one_decision() {
+        let sink = Arc::new(MockAuditSink::default());
+        let authz = AuditingAuthorizer::new(MockAuthorizer, Arc::clone(&sink) as _);
+
+        // The namespace permission is granted, implying the table permissions.
+        let perms = [
+            perm(Resource::Database("bananas".to_string()), Action::Write),
+            perm(table("bananas", "cpu"), Action::Write),
+        ];
+        authz
+            .permissions(Some(b"GOOD".to_vec()), &perms)
+            .await
+            .unwrap();
+        assert_matches!(sink.events().as_slice(), [allowed] => {
+            assert_eq!(allowed.namespace.as_deref(), Some("bananas"));
+            assert_eq!(allowed.table, None);
+            assert_eq!(allowed.outcome, AuditOutcome::Allowed);
+        });
+
+        // The namespace permission is refused, so only the table decisions
+        // are recorded.
+        let perms = [
+            perm(Resource::Database("platanos".to_string()), Action::Write),
+            perm(table("platanos", "cpu"), Action::Write),
+            perm(table("platanos", "mem"), Action::Write),
+        ];
+        authz
+            .permissions(Some(b"CPU".to_vec()), &perms)
+            .await
+            .unwrap();
+        assert_matches!(&sink.events()[1..], [cpu, mem] => {
+            assert_eq!(cpu.table.as_deref(), Some("cpu"));
+            assert_eq!(cpu.outcome, AuditOutcome::Allowed);
+            assert_eq!(mem.table.as_deref(), Some("mem"));
+            assert_eq!(mem.outcome, AuditOutcome::Denied);
+        });
+    }
+
+    #[tokio::test]
+    async fn test_exempt_token() {
+        let sink = Arc::new(MockAuditSink::default());
+        let authz = AuditingAuthorizer::new(MockAuthorizer, Arc::clone(&sink) as _)
+            .with_exempt_token(b"GOOD".to_vec());
+
+        let perms = [perm(
+            Resource::Database("bananas".to_string()),
+            Action::Write,
+        )];
+        authz
+            .permissions(Some(b"GOOD".to_vec()), &perms)
+            .await
+            .unwrap();
+        assert!(sink.events().is_empty());
+
+        authz.permissions(None, &perms).await.unwrap_err();
+        authz
+            .permissions(Some(b"BAD".to_vec()), &perms)
+            .await
+            .unwrap_err();
+        assert_eq!(sink.events().len(), 2);
+    }
+}
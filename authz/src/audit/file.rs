@@ -0,0 +1,159 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use observability_deps::tracing::warn;
+use tokio::{
+    fs::File,
+    io::{AsyncWriteExt, BufWriter},
+    sync::mpsc,
+    task::JoinHandle,
+    time::MissedTickBehavior,
+};
+
+use super::{AuditEvent, AuditSink};
+
+/// The maximum number of events buffered by a [`FileAuditSink`] before
+/// further events are dropped.
+const MAX_BUFFERED_EVENTS: usize = 10_000;
+
+/// The interval at which written events are flushed to the file.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// An [`AuditSink`] appending each [`AuditEvent`] to a local file as a line
+/// of JSON.
+///
+/// Events are written by a background task, so that recording an event never
+/// blocks the audited operation, and flushed to the file every second. If
+/// the file cannot keep up, or a write fails, the affected events are logged
+/// and discarded.
+#[derive(Debug)]
+pub struct FileAuditSink {
+    path: PathBuf,
+    tx: mpsc::Sender<AuditEvent>,
+    writer: JoinHandle<()>,
+}
+
+impl FileAuditSink {
+    /// Open `path` for appending, creating it if it does not exist.
+    pub async fn open(path: impl Into<PathBuf>) -> Result<Self, std::io::Error> {
+        let path = path.into();
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await?;
+
+        let (tx, rx) = mpsc::channel(MAX_BUFFERED_EVENTS);
+        let writer = tokio::spawn(write_loop(path.clone(), BufWriter::new(file), rx));
+
+        Ok(Self { path, tx, writer })
+    }
+
+    /// The path of the audit log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Stop accepting events, returning once all recorded events are written
+    /// and flushed to the file.
+    pub async fn close(self) {
+        drop(self.tx);
+        if let Err(e) = self.writer.await {
+            warn!(error=%e, path=%self.path.display(), "audit log writer failed");
+        }
+    }
+}
+
+#[async_trait]
+impl AuditSink for FileAuditSink {
+    async fn record(&self, event: AuditEvent) {
+        if let Err(e) = self.tx.try_send(event) {
+            warn!(error=%e, path=%self.path.display(), "dropping audit event");
+        }
+    }
+}
+
+/// Write the events received from `rx` to `file`, flushing it every
+/// [`FLUSH_INTERVAL`].
+///
+/// Exits once all senders are dropped and the remaining events are flushed.
+async fn write_loop(path: PathBuf, mut file: BufWriter<File>, mut rx: mpsc::Receiver<AuditEvent>) {
+    let mut ticker = tokio::time::interval(FLUSH_INTERVAL);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            event = rx.recv() => match event {
+                Some(event) => write(&path, &mut file, &event).await,
+                None => {
+                    flush(&path, &mut file).await;
+                    return;
+                }
+            },
+            _ = ticker.tick() => flush(&path, &mut file).await,
+        }
+    }
+}
+
+/// Append `event` to the buffered `file`.
+async fn write(path: &Path, file: &mut BufWriter<File>, event: &AuditEvent) {
+    let mut line = match serde_json::to_vec(event) {
+        Ok(v) => v,
+        Err(e) => {
+            warn!(error=%e, ?event, "failed to serialise audit event");
+            return;
+        }
+    };
+    line.push(b'\n');
+
+    if let Err(e) = file.write_all(&line).await {
+        warn!(error=%e, path=%path.display(), ?event, "failed to write audit event");
+    }
+}
+
+async fn flush(path: &Path, file: &mut BufWriter<File>) {
+    if let Err(e) = file.flush().await {
+        warn!(error=%e, path=%path.display(), "failed to flush audit events");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::AuditOutcome;
+
+    #[tokio::test]
+    async fn test_append_json_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        std::fs::write(&path, "{\"existing\":true}\n").unwrap();
+
+        let sink = FileAuditSink::open(&path).await.unwrap();
+        sink.record(
+            AuditEvent::new("create_namespace", AuditOutcome::Succeeded).with_namespace("bananas"),
+        )
+        .await;
+        sink.record(AuditEvent::new("delete_namespace", AuditOutcome::Failed))
+            .await;
+        sink.close().await;
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines = contents.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3, "{contents}");
+
+        // Existing content is preserved.
+        assert_eq!(lines[0], "{\"existing\":true}");
+
+        let event: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(event["operation"], "create_namespace");
+        assert_eq!(event["namespace"], "bananas");
+        assert_eq!(event["outcome"], "succeeded");
+
+        let event: serde_json::Value = serde_json::from_str(lines[2]).unwrap();
+        assert_eq!(event["operation"], "delete_namespace");
+        assert_eq!(event["outcome"], "failed");
+    }
+}
@@ -35,6 +35,15 @@ pub trait Authorizer: std::fmt::Debug + Send + Sync {
         perms: &[Permission],
     ) -> Result<Vec<Permission>, Error>;
 
+    /// A loggable identity of the bearer of `token`, if this authorizer
+    /// verifies `token` and it names one.
+    ///
+    /// The identity is recorded in the audit trail in place of the token
+    /// fingerprint, so it must never be read from an unverified token.
+    async fn verified_subject(&self, _token: &[u8]) -> Option<String> {
+        None
+    }
+
     /// Make a test request that determines if end-to-end communication
     /// with the service is working.
     ///
@@ -74,6 +83,10 @@ impl<T: Authorizer> Authorizer for Option<T> {
             None => Ok(perms.to_vec()),
         }
     }
+
+    async fn verified_subject(&self, token: &[u8]) -> Option<String> {
+        self.as_ref()?.verified_subject(token).await
+    }
 }
 
 #[async_trait]
@@ -85,4 +98,8 @@ impl<T: AsRef<dyn Authorizer> + std::fmt::Debug + Send + Sync> Authorizer for T
     ) -> Result<Vec<Permission>, Error> {
         self.as_ref().permissions(token, perms).await
     }
+
+    async fn verified_subject(&self, token: &[u8]) -> Option<String> {
+        self.as_ref().verified_subject(token).await
+    }
 }
@@ -0,0 +1,215 @@
+use serde::Deserialize;
+
+use super::{Action, Error, Permission, Resource};
+
+/// The namespace name that matches every namespace.
+const WILDCARD_NAMESPACE: &str = "*";
+
+/// A set of actions permitted on a namespace, as configured for a static
+/// token or carried in the claims of a JWT.
+///
+/// ```json
+/// { "namespace": "bananas", "actions": ["read", "write"] }
+/// ```
+///
+/// A `namespace` of `"*"` grants the actions on all namespaces.
+///
+/// A grant may optionally be limited to a set of tables within the
+/// namespace, in which case it permits the actions on only those tables and
+/// not on the namespace as a whole:
+///
+/// ```json
+/// { "namespace": "bananas", "tables": ["cpu", "mem"], "actions": ["read"] }
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Grant {
+    namespace: String,
+    #[serde(default)]
+    tables: Option<Vec<String>>,
+    actions: Vec<Action>,
+}
+
+impl Grant {
+    /// Returns true if `perm` is allowed by this grant.
+    fn permits(&self, perm: &Permission) -> bool {
+        let Permission::ResourceAction(resource, action) = perm;
+        if !self.actions.contains(action) {
+            return false;
+        }
+
+        match resource {
+            Resource::Database(name) => self.tables.is_none() && self.matches_namespace(name),
+            Resource::Table { database, table } => {
+                self.matches_namespace(database)
+                    && self
+                        .tables
+                        .as_ref()
+                        .map_or(true, |tables| tables.iter().any(|t| t == table))
+            }
+        }
+    }
+
+    fn matches_namespace(&self, name: &str) -> bool {
+        self.namespace == WILDCARD_NAMESPACE || self.namespace == name
+    }
+}
+
+/// Return the subset of `requested` permitted by `grants`, following the
+/// [`Authorizer`](crate::Authorizer) contract of returning
+/// [`Error::Forbidden`] when no requested permission is granted.
+pub(crate) fn intersect(
+    grants: &[Grant],
+    requested: &[Permission],
+) -> Result<Vec<Permission>, Error> {
+    let perms: Vec<Permission> = requested
+        .iter()
+        .filter(|p| grants.iter().any(|g| g.permits(p)))
+        .cloned()
+        .collect();
+
+    if perms.is_empty() {
+        return Err(Error::Forbidden);
+    }
+    Ok(perms)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    fn grant(namespace: &str, actions: &[Action]) -> Grant {
+        Grant {
+            namespace: namespace.to_string(),
+            tables: None,
+            actions: actions.to_vec(),
+        }
+    }
+
+    fn table_grant(namespace: &str, tables: &[&str], actions: &[Action]) -> Grant {
+        Grant {
+            namespace: namespace.to_string(),
+            tables: Some(tables.iter().map(ToString::to_string).collect()),
+            actions: actions.to_vec(),
+        }
+    }
+
+    fn perm(namespace: &str, action: Action) -> Permission {
+        Permission::ResourceAction(Resource::Database(namespace.to_string()), action)
+    }
+
+    fn table_perm(namespace: &str, table: &str, action: Action) -> Permission {
+        Permission::ResourceAction(
+            Resource::Table {
+                database: namespace.to_string(),
+                table: table.to_string(),
+            },
+            action,
+        )
+    }
+
+    #[test]
+    fn test_deserialise() {
+        let got: Grant = serde_json::from_str(
+            r#"{"namespace": "bananas", "actions": ["read_schema", "write"]}"#,
+        )
+        .unwrap();
+        assert_eq!(got, grant("bananas", &[Action::ReadSchema, Action::Write]));
+
+        serde_json::from_str::<Grant>(r#"{"namespace": "bananas", "actions": ["eat"]}"#)
+            .expect_err("unknown action must be rejected");
+
+        let got: Grant = serde_json::from_str(
+            r#"{"namespace": "bananas", "tables": ["cpu"], "actions": ["read"]}"#,
+        )
+        .unwrap();
+        assert_eq!(got, table_grant("bananas", &["cpu"], &[Action::Read]));
+    }
+
+    #[test]
+    fn test_intersect() {
+        let grants = [
+            grant("bananas", &[Action::Read, Action::ReadSchema]),
+            grant("platanos", &[Action::Write]),
+        ];
+
+        let got = intersect(
+            &grants,
+            &[
+                perm("bananas", Action::Read),
+                perm("bananas", Action::Write),
+                perm("platanos", Action::Write),
+                perm("apples", Action::Read),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            got,
+            [
+                perm("bananas", Action::Read),
+                perm("platanos", Action::Write)
+            ]
+        );
+
+        assert_matches!(
+            intersect(&grants, &[perm("apples", Action::Read)]),
+            Err(Error::Forbidden)
+        );
+        assert_matches!(intersect(&grants, &[]), Err(Error::Forbidden));
+    }
+
+    #[test]
+    fn test_wildcard_namespace() {
+        let grants = [grant("*", &[Action::Read])];
+
+        let got = intersect(
+            &grants,
+            &[perm("bananas", Action::Read), perm("apples", Action::Read)],
+        )
+        .unwrap();
+        assert_eq!(
+            got,
+            [perm("bananas", Action::Read), perm("apples", Action::Read)]
+        );
+
+        assert_matches!(
+            intersect(&grants, &[perm("bananas", Action::Delete)]),
+            Err(Error::Forbidden)
+        );
+    }
+
+    #[test]
+    fn test_table_grants() {
+        let grants = [
+            table_grant("bananas", &["cpu", "mem"], &[Action::Read]),
+            grant("platanos", &[Action::Write]),
+        ];
+
+        // A table-scoped grant does not permit access to the whole namespace.
+        assert_matches!(
+            intersect(&grants, &[perm("bananas", Action::Read)]),
+            Err(Error::Forbidden)
+        );
+
+        let got = intersect(
+            &grants,
+            &[
+                table_perm("bananas", "cpu", Action::Read),
+                table_perm("bananas", "disk", Action::Read),
+                table_perm("bananas", "mem", Action::Write),
+                // Namespace grants extend to all tables within it.
+                table_perm("platanos", "disk", Action::Write),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            got,
+            [
+                table_perm("bananas", "cpu", Action::Read),
+                table_perm("platanos", "disk", Action::Write),
+            ]
+        );
+    }
+}
@@ -74,6 +74,10 @@ where
 
         res
     }
+
+    async fn verified_subject(&self, token: &[u8]) -> Option<String> {
+        self.inner.verified_subject(token).await
+    }
 }
 
 #[cfg(test)]
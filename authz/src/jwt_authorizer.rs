@@ -0,0 +1,425 @@
+use std::{path::PathBuf, str::FromStr};
+
+use async_trait::async_trait;
+use jsonwebtoken::{decode, decode_header, jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use observability_deps::tracing::debug;
+use serde::Deserialize;
+
+use super::{
+    grant::{intersect, Grant},
+    watched_file::{LoadError, WatchedFile, DEFAULT_RELOAD_INTERVAL},
+    Authorizer, Error, Permission,
+};
+
+/// A verification key loaded from a JWKS file.
+struct Key {
+    id: Option<String>,
+    /// The only algorithm tokens verified with this key may be signed with.
+    algorithm: Algorithm,
+    key: DecodingKey,
+}
+
+/// The verification keys loaded from a JWKS file.
+struct Keys(Vec<Key>);
+
+impl Keys {
+    /// Select the key identified by `kid`.
+    ///
+    /// Tokens without a `kid` header are only accepted when the key set
+    /// contains exactly one key.
+    fn find(&self, kid: Option<&str>) -> Option<&Key> {
+        match (kid, self.0.as_slice()) {
+            (Some(kid), keys) => keys.iter().find(|key| key.id.as_deref() == Some(kid)),
+            (None, [key]) => Some(key),
+            (None, _) => None,
+        }
+    }
+}
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+fn parse_jwks(contents: &[u8]) -> Result<Keys, BoxError> {
+    let set: JwkSet = serde_json::from_slice(contents)?;
+    let keys = set
+        .keys
+        .iter()
+        .map(|jwk| {
+            let id = jwk.common.key_id.clone();
+            // The algorithm is pinned by the key, never by the token.
+            let algorithm = jwk
+                .common
+                .key_algorithm
+                .ok_or_else(|| format!("key {id:?} has no \"alg\""))?;
+            let algorithm = Algorithm::from_str(&algorithm.to_string())
+                .map_err(|_| format!("key {id:?} has unsupported \"alg\" {algorithm}"))?;
+            Ok(Key {
+                id,
+                algorithm,
+                key: DecodingKey::from_jwk(jwk)?,
+            })
+        })
+        .collect::<Result<Vec<_>, BoxError>>()?;
+
+    if keys.is_empty() {
+        return Err("no keys in key set".into());
+    }
+    Ok(Keys(keys))
+}
+
+/// The claims of interest in a JWT presented to IOx.
+#[derive(Debug, Deserialize)]
+struct Claims {
+    /// The identity of the bearer.
+    sub: Option<String>,
+
+    /// The namespace permissions granted to the bearer.
+    #[serde(default, rename = "iox_permissions")]
+    permissions: Vec<Grant>,
+}
+
+/// An [`Authorizer`] accepting JSON Web Tokens signed by a key from a local
+/// JWKS file.
+///
+/// The permissions of a token are read from its `iox_permissions` claim,
+/// which uses the same structure as the [`TokenFileAuthorizer`] file:
+///
+/// ```json
+/// {
+///   "sub": "telegraf",
+///   "exp": 1700000000,
+///   "iox_permissions": [
+///     { "namespace": "bananas", "actions": ["write"] }
+///   ]
+/// }
+/// ```
+///
+/// Every key in the JWKS file must name its `alg`, and tokens must be signed
+/// with the algorithm of the key that verifies them.
+///
+/// Tokens must carry an `exp` claim, and are additionally checked against
+/// the configured issuer and audience, if any. Tokens with an invalid
+/// signature or failing validation are rejected as
+/// [`Error::InvalidToken`].
+///
+/// The `sub` claim of a verified token is its
+/// [verified subject](Authorizer::verified_subject), recorded as `jwt:<sub>`.
+///
+/// The JWKS file is periodically re-read, allowing signing keys to be
+/// rotated without a restart.
+///
+/// [`TokenFileAuthorizer`]: crate::TokenFileAuthorizer
+#[derive(Debug)]
+pub struct JwtAuthorizer {
+    keys: WatchedFile<Keys>,
+    validation: Validation,
+}
+
+impl JwtAuthorizer {
+    /// Load the JWKS file at `jwks_path`, watching it for changes.
+    ///
+    /// If `issuer` or `audience` are provided, tokens must contain a
+    /// matching `iss` or `aud` claim respectively.
+    ///
+    /// # Panics
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn new(
+        jwks_path: impl Into<PathBuf>,
+        issuer: Option<String>,
+        audience: Option<String>,
+    ) -> Result<Self, LoadError> {
+        let keys = WatchedFile::new(jwks_path, DEFAULT_RELOAD_INTERVAL, parse_jwks)?;
+
+        let mut validation = Validation::default();
+        if let Some(issuer) = issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        match audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+
+        Ok(Self { keys, validation })
+    }
+
+    /// Verify `token`, returning its claims.
+    fn verify(&self, token: &[u8]) -> Result<Claims, Error> {
+        let token = std::str::from_utf8(token).map_err(|_| Error::InvalidToken)?;
+
+        let header = decode_header(token).map_err(|_| Error::InvalidToken)?;
+        let keys = self.keys.get();
+        let key = keys
+            .find(header.kid.as_deref())
+            .ok_or(Error::InvalidToken)?;
+
+        // Only the algorithm of the key is accepted, whatever the untrusted
+        // header claims.
+        if header.alg != key.algorithm {
+            debug!(alg=?header.alg, expected=?key.algorithm, "rejected jwt algorithm");
+            return Err(Error::InvalidToken);
+        }
+        let mut validation = self.validation.clone();
+        validation.algorithms = vec![key.algorithm];
+
+        decode::<Claims>(token, &key.key, &validation)
+            .map(|v| v.claims)
+            .map_err(|e| {
+                debug!(error=%e, "rejected jwt");
+                Error::InvalidToken
+            })
+    }
+}
+
+#[async_trait]
+impl Authorizer for JwtAuthorizer {
+    async fn permissions(
+        &self,
+        token: Option<Vec<u8>>,
+        requested_perms: &[Permission],
+    ) -> Result<Vec<Permission>, Error> {
+        let token = token.ok_or(Error::NoToken)?;
+        let claims = self.verify(&token)?;
+
+        intersect(&claims.permissions, requested_perms)
+    }
+
+    async fn verified_subject(&self, token: &[u8]) -> Option<String> {
+        let sub = self.verify(token).ok()?.sub?;
+        Some(format!("jwt:{sub}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use assert_matches::assert_matches;
+    use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+    use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+    use serde_json::json;
+
+    use super::*;
+    use crate::{Action, Resource};
+
+    const SECRET: &[u8] = b"bananas-are-not-a-good-secret";
+
+    fn perm(namespace: &str, action: Action) -> Permission {
+        Permission::ResourceAction(Resource::Database(namespace.to_string()), action)
+    }
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    fn jwks(kid: &str) -> String {
+        json!({
+            "keys": [{
+                "kty": "oct",
+                "kid": kid,
+                "alg": "HS256",
+                "k": BASE64_URL_SAFE_NO_PAD.encode(SECRET),
+            }]
+        })
+        .to_string()
+    }
+
+    fn sign(kid: Option<&str>, secret: &[u8], claims: serde_json::Value) -> Vec<u8> {
+        sign_with(Algorithm::HS256, kid, secret, claims)
+    }
+
+    fn sign_with(
+        alg: Algorithm,
+        kid: Option<&str>,
+        secret: &[u8],
+        claims: serde_json::Value,
+    ) -> Vec<u8> {
+        let header = Header {
+            kid: kid.map(ToString::to_string),
+            ..Header::new(alg)
+        };
+        encode(&header, &claims, &EncodingKey::from_secret(secret))
+            .unwrap()
+            .into_bytes()
+    }
+
+    fn authorizer(
+        issuer: Option<&str>,
+        audience: Option<&str>,
+    ) -> (tempfile::TempDir, JwtAuthorizer) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jwks.json");
+        std::fs::write(&path, jwks("key-1")).unwrap();
+        let authz = JwtAuthorizer::new(
+            path,
+            issuer.map(ToString::to_string),
+            audience.map(ToString::to_string),
+        )
+        .expect("valid jwks file");
+        (dir, authz)
+    }
+
+    #[tokio::test]
+    async fn test_permissions() {
+        let (_dir, authz) = authorizer(None, None);
+
+        let token = sign(
+            Some("key-1"),
+            SECRET,
+            json!({
+                "exp": now() + 3600,
+                "iox_permissions": [
+                    { "namespace": "bananas", "actions": ["read", "write"] }
+                ]
+            }),
+        );
+
+        let got = authz
+            .permissions(
+                Some(token.clone()),
+                &[
+                    perm("bananas", Action::Write),
+                    perm("platanos", Action::Write),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(got, [perm("bananas", Action::Write)]);
+
+        assert_matches!(
+            authz
+                .permissions(Some(token), &[perm("bananas", Action::Delete)])
+                .await,
+            Err(Error::Forbidden)
+        );
+
+        // A single key may be used without a kid header.
+        let token = sign(
+            None,
+            SECRET,
+            json!({
+                "exp": now() + 3600,
+                "iox_permissions": [{ "namespace": "*", "actions": ["read"] }]
+            }),
+        );
+        let got = authz
+            .permissions(Some(token), &[perm("platanos", Action::Read)])
+            .await
+            .unwrap();
+        assert_eq!(got, [perm("platanos", Action::Read)]);
+
+        assert_matches!(
+            authz
+                .permissions(None, &[perm("bananas", Action::Read)])
+                .await,
+            Err(Error::NoToken)
+        );
+
+        // The probe request must be answered without retrying.
+        authz.probe().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_verified_subject() {
+        let (_dir, authz) = authorizer(None, None);
+        let claims = json!({ "sub": "telegraf", "exp": now() + 3600 });
+
+        let token = sign(Some("key-1"), SECRET, claims.clone());
+        assert_eq!(
+            authz.verified_subject(&token).await.as_deref(),
+            Some("jwt:telegraf")
+        );
+
+        // The subject of a forged token is not trusted.
+        let forged = sign(Some("key-1"), b"platanos", claims);
+        assert_eq!(authz.verified_subject(&forged).await, None);
+
+        // Nor is there a subject without a "sub" claim.
+        let token = sign(Some("key-1"), SECRET, json!({ "exp": now() + 3600 }));
+        assert_eq!(authz.verified_subject(&token).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_tokens() {
+        let (_dir, authz) = authorizer(Some("iox-issuer"), Some("iox"));
+
+        let claims = |exp: u64, iss: &str, aud: &str| {
+            json!({
+                "exp": exp,
+                "iss": iss,
+                "aud": aud,
+                "iox_permissions": [{ "namespace": "bananas", "actions": ["read"] }]
+            })
+        };
+        let read = [perm("bananas", Action::Read)];
+
+        let valid = sign(
+            Some("key-1"),
+            SECRET,
+            claims(now() + 3600, "iox-issuer", "iox"),
+        );
+        authz.permissions(Some(valid), &read).await.unwrap();
+
+        for token in [
+            // Not a JWT.
+            b"bananas".to_vec(),
+            // Wrong signing key.
+            sign(
+                Some("key-1"),
+                b"platanos",
+                claims(now() + 3600, "iox-issuer", "iox"),
+            ),
+            // Unknown key ID.
+            sign(
+                Some("key-2"),
+                SECRET,
+                claims(now() + 3600, "iox-issuer", "iox"),
+            ),
+            // Expired.
+            sign(
+                Some("key-1"),
+                SECRET,
+                claims(now() - 3600, "iox-issuer", "iox"),
+            ),
+            // Wrong issuer.
+            sign(
+                Some("key-1"),
+                SECRET,
+                claims(now() + 3600, "bananas", "iox"),
+            ),
+            // Wrong audience.
+            sign(
+                Some("key-1"),
+                SECRET,
+                claims(now() + 3600, "iox-issuer", "bananas"),
+            ),
+            // Signed with another algorithm than the key's.
+            sign_with(
+                Algorithm::HS512,
+                Some("key-1"),
+                SECRET,
+                claims(now() + 3600, "iox-issuer", "iox"),
+            ),
+        ] {
+            assert_matches!(
+                authz.permissions(Some(token), &read).await,
+                Err(Error::InvalidToken)
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_jwks_errors() {
+        assert!(parse_jwks(br#"{"keys": []}"#).is_err());
+        assert!(parse_jwks(b"bananas").is_err());
+
+        // Every key must pin its algorithm.
+        let no_alg = json!({
+            "keys": [{ "kty": "oct", "kid": "key-1", "k": BASE64_URL_SAFE_NO_PAD.encode(SECRET) }]
+        });
+        let err = parse_jwks(no_alg.to_string().as_bytes()).err().unwrap();
+        assert!(err.to_string().contains("alg"), "{err}");
+    }
+}
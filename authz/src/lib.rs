@@ -2,6 +2,14 @@
 //!
 //! Authorization client interface to be used by IOx components to
 //! restrict access to authorized requests where required.
+//!
+//! Permissions may be checked against an external authorization service
+//! ([`IoxAuthorizer`]), a local file of static tokens
+//! ([`TokenFileAuthorizer`]), or JSON Web Tokens verified against a local
+//! key set ([`JwtAuthorizer`]).
+//!
+//! Authorization decisions and administrative operations may be recorded to
+//! an audit trail using the types in the [`audit`] module.
 
 #![deny(rustdoc::broken_intra_doc_links, rust_2018_idioms)]
 #![warn(
@@ -25,14 +33,22 @@ use base64::{prelude::BASE64_STANDARD, Engine};
 use generated_types::influxdata::iox::authz::v1::{self as proto};
 use observability_deps::tracing::warn;
 
+pub mod audit;
 mod authorizer;
 pub use authorizer::Authorizer;
+mod grant;
 mod iox_authorizer;
 pub use iox_authorizer::{Error, IoxAuthorizer};
 mod instrumentation;
 pub use instrumentation::AuthorizerInstrumentation;
+mod jwt_authorizer;
+pub use jwt_authorizer::JwtAuthorizer;
 mod permission;
 pub use permission::{Action, Permission, Resource};
+mod token_file_authorizer;
+pub use token_file_authorizer::TokenFileAuthorizer;
+mod watched_file;
+pub use watched_file::LoadError;
 
 #[cfg(feature = "http")]
 pub mod http;
@@ -1,8 +1,13 @@
 use super::proto;
+use serde::Deserialize;
 use snafu::Snafu;
 
 /// Action is the type of operation being attempted on a resource.
-#[derive(Clone, Copy, Debug, PartialEq)]
+///
+/// When read from a token file or JWT claim, actions are named in
+/// `snake_case` (i.e. `"read_schema"`).
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Action {
     /// The create action is used when a new instance of the resource will
     /// be created.
@@ -19,6 +24,19 @@ pub enum Action {
     Write,
 }
 
+impl Action {
+    /// The `snake_case` name of this action.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Delete => "delete",
+            Self::Read => "read",
+            Self::ReadSchema => "read_schema",
+            Self::Write => "write",
+        }
+    }
+}
+
 impl TryFrom<proto::resource_action_permission::Action> for Action {
     type Error = IncompatiblePermissionError;
 
@@ -78,6 +96,7 @@ impl TryFrom<proto::Permission> for Permission {
                     proto::resource_action_permission::ResourceType::try_from(ra.resource_type)
                         .map_err(|_| IncompatiblePermissionError {})?,
                     ra.resource_id,
+                    ra.table_name,
                 )?;
                 let a = Action::try_from(
                     proto::resource_action_permission::Action::try_from(ra.action)
@@ -96,7 +115,7 @@ impl TryFrom<Permission> for proto::Permission {
     fn try_from(value: Permission) -> Result<Self, Self::Error> {
         match value {
             Permission::ResourceAction(r, a) => {
-                let (rt, ri) = r.try_into_proto()?;
+                let (rt, ri, tn) = r.try_into_proto()?;
                 let a: proto::resource_action_permission::Action = a.into();
                 Ok(Self {
                     permission_one_of: Some(proto::permission::PermissionOneOf::ResourceAction(
@@ -104,6 +123,7 @@ impl TryFrom<Permission> for proto::Permission {
                             resource_type: rt as i32,
                             resource_id: ri,
                             action: a as i32,
+                            table_name: tn,
                         },
                     )),
                 })
@@ -117,17 +137,36 @@ impl TryFrom<Permission> for proto::Permission {
 pub enum Resource {
     /// A database is a named IOx database.
     Database(String),
+
+    /// A table is a single named table within an IOx database.
+    ///
+    /// Permission for an action on a [`Resource::Database`] implies the same
+    /// permission on every table within it, so callers typically request
+    /// table permissions only when the database permission is not granted.
+    Table {
+        /// The name of the database containing the table.
+        database: String,
+        /// The name of the table.
+        table: String,
+    },
 }
 
 impl Resource {
     fn try_from_proto(
         rt: proto::resource_action_permission::ResourceType,
         ri: Option<String>,
+        tn: Option<String>,
     ) -> Result<Self, IncompatiblePermissionError> {
-        match (rt, ri) {
-            (proto::resource_action_permission::ResourceType::Database, Some(s)) => {
+        match (rt, ri, tn) {
+            (proto::resource_action_permission::ResourceType::Database, Some(s), None) => {
                 Ok(Self::Database(s))
             }
+            (proto::resource_action_permission::ResourceType::Table, Some(s), Some(t)) => {
+                Ok(Self::Table {
+                    database: s,
+                    table: t,
+                })
+            }
             _ => Err(IncompatiblePermissionError {}),
         }
     }
@@ -138,6 +177,7 @@ impl Resource {
         (
             proto::resource_action_permission::ResourceType,
             Option<String>,
+            Option<String>,
         ),
         IncompatiblePermissionError,
     > {
@@ -145,6 +185,12 @@ impl Resource {
             Self::Database(s) => Ok((
                 proto::resource_action_permission::ResourceType::Database,
                 Some(s),
+                None,
+            )),
+            Self::Table { database, table } => Ok((
+                proto::resource_action_permission::ResourceType::Table,
+                Some(database),
+                Some(table),
             )),
         }
     }
@@ -212,14 +258,37 @@ mod tests {
             Resource::Database("ns1".into()),
             Resource::try_from_proto(
                 proto::resource_action_permission::ResourceType::Database,
-                Some("ns1".into())
+                Some("ns1".into()),
+                None
             )
             .unwrap()
         );
+        assert_eq!(
+            Resource::Table {
+                database: "ns1".into(),
+                table: "cpu".into()
+            },
+            Resource::try_from_proto(
+                proto::resource_action_permission::ResourceType::Table,
+                Some("ns1".into()),
+                Some("cpu".into())
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            IncompatiblePermissionError {},
+            Resource::try_from_proto(
+                proto::resource_action_permission::ResourceType::Table,
+                Some("ns1".into()),
+                None
+            )
+            .unwrap_err()
+        );
         assert_eq!(
             IncompatiblePermissionError {},
             Resource::try_from_proto(
                 proto::resource_action_permission::ResourceType::Database,
+                None,
                 None
             )
             .unwrap_err()
@@ -228,7 +297,8 @@ mod tests {
             IncompatiblePermissionError {},
             Resource::try_from_proto(
                 proto::resource_action_permission::ResourceType::Unspecified,
-                Some("ns1".into())
+                Some("ns1".into()),
+                None
             )
             .unwrap_err()
         );
@@ -239,10 +309,24 @@ mod tests {
         assert_eq!(
             (
                 proto::resource_action_permission::ResourceType::Database,
-                Some("ns1".into())
+                Some("ns1".into()),
+                None
             ),
             Resource::Database("ns1".into()).try_into_proto().unwrap(),
         );
+        assert_eq!(
+            (
+                proto::resource_action_permission::ResourceType::Table,
+                Some("ns1".into()),
+                Some("cpu".into())
+            ),
+            Resource::Table {
+                database: "ns1".into(),
+                table: "cpu".into()
+            }
+            .try_into_proto()
+            .unwrap(),
+        );
     }
 
     #[test]
@@ -255,6 +339,7 @@ mod tests {
                         resource_type: 1,
                         resource_id: Some("ns2".into()),
                         action: 4,
+                        table_name: None,
                     }
                 ))
             })
@@ -268,6 +353,7 @@ mod tests {
                         resource_type: 0,
                         resource_id: Some("ns2".into()),
                         action: 4,
+                        table_name: None,
                     }
                 ))
             })
@@ -281,6 +367,7 @@ mod tests {
                         resource_type: 1,
                         resource_id: Some("ns2".into()),
                         action: 0,
+                        table_name: None,
                     }
                 ))
             })
@@ -297,6 +384,7 @@ mod tests {
                         resource_type: 1,
                         resource_id: Some("ns3".into()),
                         action: 4,
+                        table_name: None,
                     }
                 ))
             },
@@ -0,0 +1,204 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use super::{
+    grant::{intersect, Grant},
+    watched_file::{LoadError, WatchedFile, DEFAULT_RELOAD_INTERVAL},
+    Authorizer, Error, Permission,
+};
+
+/// The on-disk format of a token file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TokenFile {
+    tokens: Vec<TokenEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TokenEntry {
+    token: String,
+    permissions: Vec<Grant>,
+}
+
+/// Parse a token file into a map of token bytes to the grants they hold.
+fn parse(
+    contents: &[u8],
+) -> Result<HashMap<Vec<u8>, Vec<Grant>>, Box<dyn std::error::Error + Send + Sync>> {
+    let file: TokenFile = serde_json::from_slice(contents)?;
+
+    let mut tokens = HashMap::with_capacity(file.tokens.len());
+    for entry in file.tokens {
+        if entry.token.is_empty() {
+            return Err("empty token".into());
+        }
+        if tokens
+            .insert(entry.token.into_bytes(), entry.permissions)
+            .is_some()
+        {
+            return Err("duplicate token".into());
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// An [`Authorizer`] backed by a static set of tokens read from a local JSON
+/// file.
+///
+/// The file lists each accepted token along with the namespace actions it
+/// permits:
+///
+/// ```json
+/// {
+///   "tokens": [
+///     {
+///       "token": "s3cr3t",
+///       "permissions": [
+///         { "namespace": "bananas", "actions": ["read", "read_schema", "write"] },
+///         { "namespace": "*", "actions": ["read_schema"] }
+///       ]
+///     }
+///   ]
+/// }
+/// ```
+///
+/// A `namespace` of `"*"` matches every namespace, and a permission may be
+/// limited to specific tables within a namespace by adding a `tables` list.
+/// Valid actions are `create`, `delete`, `read`, `read_schema` and `write`.
+///
+/// The file is periodically re-read and changes take effect without a
+/// restart. If an updated file is invalid, the previous set of tokens remains
+/// in use.
+#[derive(Debug)]
+pub struct TokenFileAuthorizer {
+    tokens: WatchedFile<HashMap<Vec<u8>, Vec<Grant>>>,
+}
+
+impl TokenFileAuthorizer {
+    /// Load the token file at `path`, watching it for changes.
+    ///
+    /// # Panics
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self, LoadError> {
+        let tokens = WatchedFile::new(path, DEFAULT_RELOAD_INTERVAL, parse)?;
+        Ok(Self { tokens })
+    }
+}
+
+#[async_trait]
+impl Authorizer for TokenFileAuthorizer {
+    async fn permissions(
+        &self,
+        token: Option<Vec<u8>>,
+        requested_perms: &[Permission],
+    ) -> Result<Vec<Permission>, Error> {
+        let token = token.ok_or(Error::NoToken)?;
+        let tokens = self.tokens.get();
+        let grants = tokens.get(&token).ok_or(Error::InvalidToken)?;
+        intersect(grants, requested_perms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+    use crate::{Action, Resource};
+
+    const TOKEN_FILE: &str = r#"{
+        "tokens": [
+            {
+                "token": "bananas-rw",
+                "permissions": [
+                    { "namespace": "bananas", "actions": ["read", "write"] }
+                ]
+            },
+            {
+                "token": "admin",
+                "permissions": [
+                    { "namespace": "*", "actions": ["create", "delete", "read", "read_schema", "write"] }
+                ]
+            }
+        ]
+    }"#;
+
+    fn perm(namespace: &str, action: Action) -> Permission {
+        Permission::ResourceAction(Resource::Database(namespace.to_string()), action)
+    }
+
+    fn authorizer(contents: &str) -> (tempfile::TempDir, TokenFileAuthorizer) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tokens.json");
+        std::fs::write(&path, contents).unwrap();
+        let authz = TokenFileAuthorizer::new(path).expect("valid token file");
+        (dir, authz)
+    }
+
+    #[tokio::test]
+    async fn test_permissions() {
+        let (_dir, authz) = authorizer(TOKEN_FILE);
+
+        let got = authz
+            .permissions(
+                Some(b"bananas-rw".to_vec()),
+                &[
+                    perm("bananas", Action::Write),
+                    perm("bananas", Action::Delete),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(got, [perm("bananas", Action::Write)]);
+
+        assert_matches!(
+            authz
+                .permissions(
+                    Some(b"bananas-rw".to_vec()),
+                    &[perm("platanos", Action::Write)]
+                )
+                .await,
+            Err(Error::Forbidden)
+        );
+
+        let got = authz
+            .permissions(Some(b"admin".to_vec()), &[perm("platanos", Action::Delete)])
+            .await
+            .unwrap();
+        assert_eq!(got, [perm("platanos", Action::Delete)]);
+
+        assert_matches!(
+            authz
+                .permissions(Some(b"bananas".to_vec()), &[perm("bananas", Action::Read)])
+                .await,
+            Err(Error::InvalidToken)
+        );
+        assert_matches!(
+            authz
+                .permissions(None, &[perm("bananas", Action::Read)])
+                .await,
+            Err(Error::NoToken)
+        );
+
+        // The probe request must be answered without retrying.
+        authz.probe().await.unwrap();
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        parse(br#"{"tokens": [{"token": "", "permissions": []}]}"#)
+            .expect_err("empty token must be rejected");
+        parse(
+            br#"{"tokens": [
+                {"token": "a", "permissions": []},
+                {"token": "a", "permissions": []}
+            ]}"#,
+        )
+        .expect_err("duplicate token must be rejected");
+        parse(br#"{"tokens": [{"token": "a"}]}"#).expect_err("permissions are required");
+    }
+}
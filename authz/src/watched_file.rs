@@ -0,0 +1,207 @@
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use observability_deps::tracing::{info, warn};
+use parking_lot::RwLock;
+use snafu::{ResultExt, Snafu};
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+/// The default interval at which a [`WatchedFile`] is checked for changes.
+pub(crate) const DEFAULT_RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Error loading the configuration file backing a file-based
+/// [`Authorizer`](crate::Authorizer).
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub enum LoadError {
+    /// The file could not be read.
+    #[snafu(display("failed to read {}: {source}", path.display()))]
+    Read {
+        /// The path of the file.
+        path: PathBuf,
+        /// Source of the error.
+        source: std::io::Error,
+    },
+
+    /// The file contents are invalid.
+    #[snafu(display("failed to parse {}: {source}", path.display()))]
+    Parse {
+        /// The path of the file.
+        path: PathBuf,
+        /// Source of the error.
+        source: BoxError,
+    },
+}
+
+/// A value parsed from a file on disk, periodically re-read in the
+/// background so that changes take effect without a restart.
+///
+/// The file must be valid when the [`WatchedFile`] is constructed. If a later
+/// version fails to parse, a warning is logged and the last valid value is
+/// retained.
+pub(crate) struct WatchedFile<T> {
+    path: PathBuf,
+    current: Arc<RwLock<Arc<T>>>,
+    reloader: JoinHandle<()>,
+}
+
+impl<T> WatchedFile<T>
+where
+    T: Send + Sync + 'static,
+{
+    /// Load `path` using `parse`, re-reading it every `interval`.
+    ///
+    /// # Panics
+    ///
+    /// Must be called from within a tokio runtime.
+    pub(crate) fn new<F>(
+        path: impl Into<PathBuf>,
+        interval: Duration,
+        parse: F,
+    ) -> Result<Self, LoadError>
+    where
+        F: Fn(&[u8]) -> Result<T, BoxError> + Send + Sync + 'static,
+    {
+        let path = path.into();
+        let contents = std::fs::read(&path).context(ReadSnafu { path: path.clone() })?;
+        let value = parse(&contents).context(ParseSnafu { path: path.clone() })?;
+        let current = Arc::new(RwLock::new(Arc::new(value)));
+
+        let reloader = tokio::spawn(reload(
+            path.clone(),
+            contents,
+            interval,
+            Arc::clone(&current),
+            parse,
+        ));
+
+        Ok(Self {
+            path,
+            current,
+            reloader,
+        })
+    }
+
+    /// Return the most recently loaded value.
+    pub(crate) fn get(&self) -> Arc<T> {
+        Arc::clone(&self.current.read())
+    }
+}
+
+// The contents may be secret, so only the path is printed.
+impl<T> std::fmt::Debug for WatchedFile<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WatchedFile")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Drop for WatchedFile<T> {
+    fn drop(&mut self) {
+        self.reloader.abort();
+    }
+}
+
+async fn reload<T, F>(
+    path: PathBuf,
+    mut last: Vec<u8>,
+    interval: Duration,
+    current: Arc<RwLock<Arc<T>>>,
+    parse: F,
+) where
+    T: Send + Sync,
+    F: Fn(&[u8]) -> Result<T, BoxError> + Send + Sync,
+{
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // The first tick completes immediately, and the file was just read.
+    ticker.tick().await;
+
+    loop {
+        ticker.tick().await;
+
+        let contents = match tokio::fs::read(&path).await {
+            Ok(v) => v,
+            Err(e) => {
+                warn!(error=%e, path=%path.display(), "failed to read authz file");
+                continue;
+            }
+        };
+        if contents == last {
+            continue;
+        }
+
+        match parse(&contents) {
+            Ok(v) => {
+                *current.write() = Arc::new(v);
+                info!(path=%path.display(), "reloaded authz file");
+            }
+            Err(e) => {
+                warn!(
+                    error=%e,
+                    path=%path.display(),
+                    "invalid authz file, retaining previous version"
+                );
+            }
+        }
+        last = contents;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    fn parse_u64(b: &[u8]) -> Result<u64, BoxError> {
+        Ok(std::str::from_utf8(b)?.trim().parse()?)
+    }
+
+    async fn wait_for(file: &WatchedFile<u64>, want: u64) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while *file.get() != want {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("timeout waiting for reload");
+    }
+
+    #[tokio::test]
+    async fn test_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("value");
+        std::fs::write(&path, "42").unwrap();
+
+        let file = WatchedFile::new(&path, Duration::from_millis(10), parse_u64).unwrap();
+        assert_eq!(*file.get(), 42);
+
+        std::fs::write(&path, "24").unwrap();
+        wait_for(&file, 24).await;
+
+        // An invalid update is ignored.
+        std::fs::write(&path, "bananas").unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(*file.get(), 24);
+
+        // And subsequent valid updates are picked up.
+        std::fs::write(&path, "4242").unwrap();
+        wait_for(&file, 4242).await;
+    }
+
+    #[tokio::test]
+    async fn test_initial_load_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("value");
+
+        let got = WatchedFile::new(&path, DEFAULT_RELOAD_INTERVAL, parse_u64);
+        assert_matches!(got, Err(LoadError::Read { .. }));
+
+        std::fs::write(&path, "bananas").unwrap();
+        let got = WatchedFile::new(&path, DEFAULT_RELOAD_INTERVAL, parse_u64);
+        assert_matches!(got, Err(LoadError::Parse { .. }));
+    }
+}
@@ -0,0 +1,667 @@
+//! Local-disk cache tier.
+//!
+//! A [`DiskCache`] stores immutable byte blobs in a local directory (ideally on fast NVMe storage). It is meant to sit
+//! between the in-memory caches and a slow remote store: a RAM miss checks the disk before going remote, and data that
+//! had to be fetched remotely is written back to disk.
+//!
+//! # File Format
+//! Every entry is stored in its own file, named after the SHA-256 hash of its key:
+//!
+//! ```text
+//! magic (4 bytes) | version (1 byte) | key len (u32) | metadata len (u32) | data len (u64) | CRC32 (u32)
+//! key | metadata | data
+//! ```
+//!
+//! All integers are little endian. The CRC32 covers key, metadata and data and is verified on every read. Entries are
+//! written to a temporary file first and then renamed, so a crash never leaves a half-written entry behind.
+//!
+//! # Restarts
+//! On startup the directory is scanned and all valid entries are registered. The modification time of an entry file
+//! is set whenever the entry is written or read, so it is used to restore the LRU order.
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use bytes::Bytes;
+use filetime::FileTime;
+use metric::{U64Counter, U64Gauge};
+use observability_deps::tracing::{debug, info, warn};
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::addressable_heap::AddressableHeap;
+
+const MAGIC: &[u8; 4] = b"IOXD";
+const VERSION: u8 = 1;
+const HEADER_SIZE: usize = 4 + 1 + 4 + 4 + 8 + 4;
+const ENTRY_EXTENSION: &str = "entry";
+const TMP_EXTENSION: &str = "tmp";
+
+/// Value stored in a [`DiskCache`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiskCacheEntry {
+    /// Small, user-defined metadata.
+    pub metadata: Bytes,
+
+    /// Payload.
+    pub data: Bytes,
+}
+
+/// Size of an entry on disk.
+fn entry_size(key: &str, entry: &DiskCacheEntry) -> u64 {
+    (HEADER_SIZE + key.len() + entry.metadata.len() + entry.data.len()) as u64
+}
+
+/// Checksum of an entry.
+fn checksum(key: &[u8], metadata: &[u8], data: &[u8]) -> u32 {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(key);
+    hasher.update(metadata);
+    hasher.update(data);
+    hasher.finalize()
+}
+
+/// Parsed header of an entry file.
+#[derive(Debug, Clone, Copy)]
+struct Header {
+    key_len: usize,
+    metadata_len: usize,
+    data_len: usize,
+    crc: u32,
+}
+
+impl Header {
+    fn encode(key: &str, entry: &DiskCacheEntry) -> [u8; HEADER_SIZE] {
+        let mut buf = [0u8; HEADER_SIZE];
+        buf[0..4].copy_from_slice(MAGIC);
+        buf[4] = VERSION;
+        buf[5..9].copy_from_slice(&(key.len() as u32).to_le_bytes());
+        buf[9..13].copy_from_slice(&(entry.metadata.len() as u32).to_le_bytes());
+        buf[13..21].copy_from_slice(&(entry.data.len() as u64).to_le_bytes());
+        buf[21..25]
+            .copy_from_slice(&checksum(key.as_bytes(), &entry.metadata, &entry.data).to_le_bytes());
+        buf
+    }
+
+    fn decode(buf: &[u8]) -> Option<Self> {
+        if buf.len() < HEADER_SIZE || &buf[0..4] != MAGIC || buf[4] != VERSION {
+            return None;
+        }
+
+        let u32_at = |pos: usize| u32::from_le_bytes(buf[pos..pos + 4].try_into().unwrap());
+        Some(Self {
+            key_len: u32_at(5) as usize,
+            metadata_len: u32_at(9) as usize,
+            data_len: usize::try_from(u64::from_le_bytes(buf[13..21].try_into().unwrap())).ok()?,
+            crc: u32_at(21),
+        })
+    }
+
+    fn file_size(&self) -> u64 {
+        (HEADER_SIZE + self.key_len + self.metadata_len + self.data_len) as u64
+    }
+}
+
+/// Decode and verify a full entry file.
+fn decode_entry(key: &str, buf: Bytes) -> Option<DiskCacheEntry> {
+    let header = Header::decode(&buf)?;
+    if header.file_size() != buf.len() as u64 {
+        return None;
+    }
+
+    let key_end = HEADER_SIZE + header.key_len;
+    let metadata_end = key_end + header.metadata_len;
+    let stored_key = &buf[HEADER_SIZE..key_end];
+    let metadata = buf.slice(key_end..metadata_end);
+    let data = buf.slice(metadata_end..);
+
+    if checksum(stored_key, &metadata, &data) != header.crc || stored_key != key.as_bytes() {
+        return None;
+    }
+
+    Some(DiskCacheEntry { metadata, data })
+}
+
+/// Book-keeping for a single entry.
+#[derive(Debug, Clone, Copy)]
+struct EntryState {
+    /// File size in bytes.
+    size: u64,
+
+    /// Set once the file was completely written.
+    ready: bool,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Entries by file name, ordered by last usage.
+    entries: AddressableHeap<String, EntryState, u64>,
+
+    /// Sum of all entry sizes.
+    used_bytes: u64,
+
+    /// Next usage counter.
+    next_order: u64,
+
+    /// Last modification time assigned to an entry file.
+    last_mtime: Option<SystemTime>,
+}
+
+impl State {
+    fn next_order(&mut self) -> u64 {
+        let o = self.next_order;
+        self.next_order += 1;
+        o
+    }
+
+    /// Modification time for an entry file that was just used.
+    ///
+    /// Strictly increasing, so the LRU order can be restored from the files even if they were used faster than the
+    /// resolution of the clock.
+    fn next_mtime(&mut self) -> SystemTime {
+        let now = SystemTime::now();
+        let mtime = match self.last_mtime {
+            Some(last) if last >= now => last + Duration::from_micros(1),
+            _ => now,
+        };
+        self.last_mtime = Some(mtime);
+        mtime
+    }
+
+    fn remove(&mut self, name: &String) -> Option<EntryState> {
+        let (entry, _order) = self.entries.remove(name)?;
+        self.used_bytes -= entry.size;
+        Some(entry)
+    }
+}
+
+#[derive(Debug)]
+struct Metrics {
+    hit: U64Counter,
+    miss: U64Counter,
+    corrupt: U64Counter,
+    evicted: U64Counter,
+    used_bytes: U64Gauge,
+}
+
+impl Metrics {
+    fn new(name: &'static str, metric_registry: &metric::Registry) -> Self {
+        let get = metric_registry
+            .register_metric::<U64Counter>("iox_cache_disk_get", "Disk cache GET requests");
+
+        Self {
+            hit: get.recorder(&[("name", name), ("status", "hit")]),
+            miss: get.recorder(&[("name", name), ("status", "miss")]),
+            corrupt: get.recorder(&[("name", name), ("status", "corrupt")]),
+            evicted: metric_registry
+                .register_metric::<U64Counter>(
+                    "iox_cache_disk_evicted",
+                    "Number of entries evicted from the disk cache",
+                )
+                .recorder(&[("name", name)]),
+            used_bytes: metric_registry
+                .register_metric::<U64Gauge>(
+                    "iox_cache_disk_used_bytes",
+                    "Bytes used by the disk cache",
+                )
+                .recorder(&[("name", name)]),
+        }
+    }
+}
+
+/// Size-bounded, LRU-evicted blob cache on local disk.
+///
+/// See [module-level docs](self) for details.
+#[derive(Debug)]
+pub struct DiskCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    state: Mutex<State>,
+    metrics: Metrics,
+}
+
+impl DiskCache {
+    /// Open cache in the given directory, registering all entries that survived a previous run.
+    ///
+    /// The directory is created if it does not exist. Invalid and temporary files are removed.
+    pub async fn new(
+        name: &'static str,
+        dir: impl Into<PathBuf>,
+        max_bytes: u64,
+        metric_registry: &metric::Registry,
+    ) -> std::io::Result<Arc<Self>> {
+        let dir = dir.into();
+        tokio::fs::create_dir_all(&dir).await?;
+
+        let mut found = vec![];
+        let mut read_dir = tokio::fs::read_dir(&dir).await?;
+        while let Some(dir_entry) = read_dir.next_entry().await? {
+            let path = dir_entry.path();
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some(ENTRY_EXTENSION) => {}
+                Some(TMP_EXTENSION) => {
+                    remove_file(&path).await;
+                    continue;
+                }
+                _ => continue,
+            }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()).map(String::from) else {
+                continue;
+            };
+
+            match scan_entry(&path).await {
+                Some((size, mtime)) => found.push((mtime, name, size)),
+                None => {
+                    warn!(path=%path.display(), "removing invalid disk cache entry");
+                    remove_file(&path).await;
+                }
+            }
+        }
+
+        // oldest first, so they get the lowest LRU order
+        found.sort();
+
+        let metrics = Metrics::new(name, metric_registry);
+        let this = Arc::new(Self {
+            dir,
+            max_bytes,
+            state: Default::default(),
+            metrics,
+        });
+
+        let victims = {
+            let mut state = this.state.lock();
+            state.last_mtime = found.last().map(|(mtime, _name, _size)| *mtime);
+            for (_mtime, name, size) in found {
+                let order = state.next_order();
+                state
+                    .entries
+                    .insert(name, EntryState { size, ready: true }, order);
+                state.used_bytes += size;
+            }
+            let victims = this.evict(&mut state, 0);
+            this.metrics.used_bytes.set(state.used_bytes);
+            info!(
+                entries = state.entries.iter().count(),
+                used_bytes = state.used_bytes,
+                dir=%this.dir.display(),
+                "disk cache opened",
+            );
+            victims
+        };
+        this.remove_files(victims).await;
+
+        Ok(this)
+    }
+
+    /// Get entry.
+    ///
+    /// Corrupted entries are removed and reported as a miss.
+    pub async fn get(&self, key: &str) -> Option<DiskCacheEntry> {
+        let name = file_name(key);
+
+        let mtime = {
+            let mut state = self.state.lock();
+            let ready = state
+                .entries
+                .get(&name)
+                .map(|(entry, _order)| entry.ready)
+                .unwrap_or_default();
+            if !ready {
+                self.metrics.miss.inc(1);
+                return None;
+            }
+            let order = state.next_order();
+            state.entries.update_order(&name, order);
+            state.next_mtime()
+        };
+
+        let path = self.path(&name, ENTRY_EXTENSION);
+        let buf = match tokio::fs::read(&path).await {
+            Ok(buf) => Bytes::from(buf),
+            Err(e) => {
+                // the file might have been evicted concurrently
+                debug!(%e, path=%path.display(), "cannot read disk cache entry");
+                self.metrics.miss.inc(1);
+                return None;
+            }
+        };
+
+        match decode_entry(key, buf) {
+            Some(entry) => {
+                self.metrics.hit.inc(1);
+                set_mtime(&path, mtime).await;
+                Some(entry)
+            }
+            None => {
+                warn!(path=%path.display(), "corrupted disk cache entry");
+                self.metrics.corrupt.inc(1);
+                self.forget(&name);
+                remove_file(&path).await;
+                None
+            }
+        }
+    }
+
+    /// Store entry.
+    ///
+    /// Entries that already exist or that are larger than the whole cache are ignored. IO errors are logged but not
+    /// returned since the cache is only an optimization.
+    pub async fn put(&self, key: &str, entry: DiskCacheEntry) {
+        let size = entry_size(key, &entry);
+        if size > self.max_bytes {
+            return;
+        }
+        let name = file_name(key);
+
+        let (victims, mtime) = {
+            let mut state = self.state.lock();
+            if state.entries.get(&name).is_some() {
+                return;
+            }
+            let victims = self.evict(&mut state, size);
+            let order = state.next_order();
+            state
+                .entries
+                .insert(name.clone(), EntryState { size, ready: false }, order);
+            state.used_bytes += size;
+            self.metrics.used_bytes.set(state.used_bytes);
+            (victims, state.next_mtime())
+        };
+        self.remove_files(victims).await;
+
+        let tmp_path = self.path(&name, TMP_EXTENSION);
+        let path = self.path(&name, ENTRY_EXTENSION);
+        if let Err(e) = write_entry(&tmp_path, &path, key, &entry).await {
+            warn!(%e, path=%path.display(), "cannot write disk cache entry");
+            self.forget(&name);
+            remove_file(&tmp_path).await;
+            return;
+        }
+        set_mtime(&path, mtime).await;
+
+        let evicted_while_writing = {
+            let mut state = self.state.lock();
+            match state.entries.get(&name) {
+                Some((e, order)) => {
+                    let order = *order;
+                    let e = EntryState { ready: true, ..*e };
+                    state.entries.insert(name.clone(), e, order);
+                    false
+                }
+                None => true,
+            }
+        };
+        if evicted_while_writing {
+            remove_file(&path).await;
+        }
+    }
+
+    /// Number of bytes used.
+    pub fn used_bytes(&self) -> u64 {
+        self.state.lock().used_bytes
+    }
+
+    /// Evict least recently used entries until `additional` bytes fit into the cache.
+    ///
+    /// Returns the file names of the evicted entries. The files must be removed by the caller.
+    fn evict(&self, state: &mut State, additional: u64) -> Vec<String> {
+        let mut victims = vec![];
+        while state.used_bytes + additional > self.max_bytes {
+            let Some((name, entry, _order)) = state.entries.pop() else {
+                break;
+            };
+            state.used_bytes -= entry.size;
+            self.metrics.evicted.inc(1);
+            victims.push(name);
+        }
+        self.metrics.used_bytes.set(state.used_bytes);
+        victims
+    }
+
+    fn forget(&self, name: &String) {
+        let mut state = self.state.lock();
+        state.remove(name);
+        self.metrics.used_bytes.set(state.used_bytes);
+    }
+
+    async fn remove_files(&self, names: Vec<String>) {
+        for name in names {
+            remove_file(&self.path(&name, ENTRY_EXTENSION)).await;
+        }
+    }
+
+    fn path(&self, name: &str, extension: &str) -> PathBuf {
+        self.dir.join(format!("{name}.{extension}"))
+    }
+}
+
+/// File name (without extension) for the given key.
+fn file_name(key: &str) -> String {
+    Sha256::digest(key.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Read header of an entry during startup.
+///
+/// Returns file size and modification time if the header is valid.
+async fn scan_entry(path: &Path) -> Option<(u64, SystemTime)> {
+    let mut file = tokio::fs::File::open(path).await.ok()?;
+    let meta = file.metadata().await.ok()?;
+
+    let mut buf = [0u8; HEADER_SIZE];
+    file.read_exact(&mut buf).await.ok()?;
+    let header = Header::decode(&buf)?;
+    if header.file_size() != meta.len() {
+        return None;
+    }
+
+    Some((meta.len(), meta.modified().ok()?))
+}
+
+async fn write_entry(
+    tmp_path: &Path,
+    path: &Path,
+    key: &str,
+    entry: &DiskCacheEntry,
+) -> std::io::Result<()> {
+    let mut file = tokio::fs::File::create(tmp_path).await?;
+    file.write_all(&Header::encode(key, entry)).await?;
+    file.write_all(key.as_bytes()).await?;
+    file.write_all(&entry.metadata).await?;
+    file.write_all(&entry.data).await?;
+    file.sync_all().await?;
+    drop(file);
+
+    tokio::fs::rename(tmp_path, path).await
+}
+
+/// Set the modification time of an entry file, recording its last usage.
+async fn set_mtime(path: &Path, mtime: SystemTime) {
+    let res = tokio::task::spawn_blocking({
+        let path = path.to_owned();
+        move || filetime::set_file_mtime(path, FileTime::from_system_time(mtime))
+    })
+    .await;
+
+    match res.map_err(std::io::Error::from).and_then(|res| res) {
+        Ok(()) => {}
+        // the file might have been evicted concurrently
+        Err(e) if e.kind() == ErrorKind::NotFound => {}
+        Err(e) => warn!(%e, path=%path.display(), "cannot touch disk cache entry"),
+    }
+}
+
+async fn remove_file(path: &Path) {
+    match tokio::fs::remove_file(path).await {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::NotFound => {}
+        Err(e) => warn!(%e, path=%path.display(), "cannot remove disk cache file"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use metric::{Attributes, Metric};
+
+    use super::*;
+
+    fn entry(metadata: &'static str, data: &'static str) -> DiskCacheEntry {
+        DiskCacheEntry {
+            metadata: Bytes::from(metadata),
+            data: Bytes::from(data),
+        }
+    }
+
+    fn get_count(metric_registry: &metric::Registry, status: &'static str) -> u64 {
+        metric_registry
+            .get_instrument::<Metric<U64Counter>>("iox_cache_disk_get")
+            .unwrap()
+            .get_observer(&Attributes::from(&[("name", "test"), ("status", status)]))
+            .unwrap()
+            .fetch()
+    }
+
+    #[tokio::test]
+    async fn test_get_put() {
+        let dir = tempfile::tempdir().unwrap();
+        let metric_registry = metric::Registry::new();
+        let cache = DiskCache::new("test", dir.path(), 1_000, &metric_registry)
+            .await
+            .unwrap();
+
+        assert_eq!(cache.get("a").await, None);
+        assert_eq!(get_count(&metric_registry, "miss"), 1);
+
+        cache.put("a", entry("m", "data_a")).await;
+        assert_eq!(cache.get("a").await, Some(entry("m", "data_a")));
+        assert_eq!(get_count(&metric_registry, "hit"), 1);
+        assert_eq!(cache.used_bytes(), entry_size("a", &entry("m", "data_a")));
+
+        // existing entries are not overwritten
+        cache.put("a", entry("m", "other")).await;
+        assert_eq!(cache.get("a").await, Some(entry("m", "data_a")));
+    }
+
+    #[tokio::test]
+    async fn test_lru_eviction() {
+        let dir = tempfile::tempdir().unwrap();
+        let metric_registry = metric::Registry::new();
+        let size = entry_size("a", &entry("", "0123456789"));
+        let cache = DiskCache::new("test", dir.path(), 2 * size, &metric_registry)
+            .await
+            .unwrap();
+
+        cache.put("a", entry("", "0123456789")).await;
+        cache.put("b", entry("", "0123456789")).await;
+
+        // use "a" so that "b" is the least recently used one
+        cache.get("a").await.unwrap();
+
+        cache.put("c", entry("", "0123456789")).await;
+        assert!(cache.get("a").await.is_some());
+        assert!(cache.get("b").await.is_none());
+        assert!(cache.get("c").await.is_some());
+        assert_eq!(cache.used_bytes(), 2 * size);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+
+        // too large for the whole cache
+        cache
+            .put("d", entry("", "0123456789012345678901234567890"))
+            .await;
+        assert!(cache.get("d").await.is_none());
+        assert!(cache.get("a").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_restart() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let cache = DiskCache::new("test", dir.path(), 1_000, &metric::Registry::new())
+            .await
+            .unwrap();
+        cache.put("a", entry("m", "data_a")).await;
+        cache.put("b", entry("m", "data_b")).await;
+        let used_bytes = cache.used_bytes();
+        drop(cache);
+
+        // leftovers of a crash
+        std::fs::write(dir.path().join("foo.tmp"), b"xxx").unwrap();
+        std::fs::write(dir.path().join("bar.entry"), b"xxx").unwrap();
+
+        let cache = DiskCache::new("test", dir.path(), 1_000, &metric::Registry::new())
+            .await
+            .unwrap();
+        assert_eq!(cache.used_bytes(), used_bytes);
+        assert_eq!(cache.get("a").await, Some(entry("m", "data_a")));
+        assert_eq!(cache.get("b").await, Some(entry("m", "data_b")));
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+
+        // shrinking the cache evicts entries on startup
+        drop(cache);
+        let cache = DiskCache::new("test", dir.path(), used_bytes - 1, &metric::Registry::new())
+            .await
+            .unwrap();
+        assert_eq!(cache.used_bytes(), used_bytes / 2);
+    }
+
+    #[tokio::test]
+    async fn test_restart_keeps_lru_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let size = entry_size("a", &entry("", "0123456789"));
+
+        let cache = DiskCache::new("test", dir.path(), 3 * size, &metric::Registry::new())
+            .await
+            .unwrap();
+        cache.put("a", entry("", "0123456789")).await;
+        cache.put("b", entry("", "0123456789")).await;
+        cache.put("c", entry("", "0123456789")).await;
+
+        // use "a" so that "b" is the least recently used one, even though "a" was written first
+        cache.get("a").await.unwrap();
+        drop(cache);
+
+        // shrinking the cache evicts the least recently used entry on startup
+        let cache = DiskCache::new("test", dir.path(), 2 * size, &metric::Registry::new())
+            .await
+            .unwrap();
+        assert_eq!(cache.used_bytes(), 2 * size);
+        assert!(cache.get("b").await.is_none());
+
+        // "c" is now the least recently used one
+        drop(cache);
+        let cache = DiskCache::new("test", dir.path(), size, &metric::Registry::new())
+            .await
+            .unwrap();
+        assert!(cache.get("c").await.is_none());
+        assert_eq!(cache.get("a").await, Some(entry("", "0123456789")));
+    }
+
+    #[tokio::test]
+    async fn test_corruption() {
+        let dir = tempfile::tempdir().unwrap();
+        let metric_registry = metric::Registry::new();
+        let cache = DiskCache::new("test", dir.path(), 1_000, &metric_registry)
+            .await
+            .unwrap();
+        cache.put("a", entry("m", "data_a")).await;
+
+        // flip a payload byte
+        let path = cache.path(&file_name("a"), ENTRY_EXTENSION);
+        let mut buf = std::fs::read(&path).unwrap();
+        let last = buf.len() - 1;
+        buf[last] ^= 0xff;
+        std::fs::write(&path, buf).unwrap();
+
+        assert_eq!(cache.get("a").await, None);
+        assert_eq!(get_count(&metric_registry, "corrupt"), 1);
+        assert_eq!(cache.used_bytes(), 0);
+        assert!(!path.exists());
+    }
+}
@@ -22,6 +22,7 @@ pub mod addressable_heap;
 pub mod backend;
 pub mod cache;
 mod cancellation_safe_future;
+pub mod disk;
 pub mod loader;
 pub mod resource_consumption;
 #[cfg(test)]
@@ -0,0 +1,117 @@
+//! CLI config for the audit trail of authorization decisions and
+//! administrative operations.
+
+use std::path::PathBuf;
+
+/// Configuration of the destinations to which audit events are written.
+///
+/// Auditing is disabled unless at least one destination is configured. If
+/// both are configured, every event is written to each.
+#[derive(Debug, Clone, PartialEq, Eq, clap::Parser)]
+pub struct AuditConfig {
+    /// Append audit events to this file, one JSON object per line.
+    #[clap(long = "audit-log-file", env = "INFLUXDB_IOX_AUDIT_LOG_FILE", action)]
+    pub audit_log_file: Option<PathBuf>,
+
+    /// Write audit events to an IOx table, via the HTTP write API of the
+    /// router at this address (i.e. `http://router:8080`).
+    ///
+    /// Events are written to the `audit` table of the namespace identified by
+    /// `--audit-org` and `--audit-bucket`.
+    #[clap(
+        long = "audit-router-addr",
+        env = "INFLUXDB_IOX_AUDIT_ROUTER_ADDR",
+        action
+    )]
+    pub audit_router_addr: Option<String>,
+
+    /// The org of the namespace audit events are written to.
+    ///
+    /// Ignored by single tenant routers.
+    #[clap(
+        long = "audit-org",
+        env = "INFLUXDB_IOX_AUDIT_ORG",
+        default_value = "iox",
+        action
+    )]
+    pub audit_org: String,
+
+    /// The bucket of the namespace audit events are written to.
+    #[clap(
+        long = "audit-bucket",
+        env = "INFLUXDB_IOX_AUDIT_BUCKET",
+        default_value = "audit",
+        action
+    )]
+    pub audit_bucket: String,
+
+    /// The token presented to the router when writing audit events.
+    ///
+    /// A router auditing its own authorization decisions does not record
+    /// those of requests presenting this token, so that writing an event does
+    /// not record another. It is therefore required when such a router writes
+    /// audit events through a router, and should only be granted write access
+    /// to the audit namespace.
+    #[clap(
+        long = "audit-token",
+        env = "INFLUXDB_IOX_AUDIT_TOKEN",
+        requires = "audit_router_addr",
+        action
+    )]
+    pub audit_token: Option<String>,
+}
+
+impl AuditConfig {
+    /// Returns true if an audit destination has been configured.
+    pub fn is_enabled(&self) -> bool {
+        self.audit_log_file.is_some() || self.audit_router_addr.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use test_helpers::assert_contains;
+
+    #[test]
+    fn test_default() {
+        let actual = AuditConfig::try_parse_from(["my_binary"]).unwrap();
+        assert!(!actual.is_enabled());
+        assert_eq!(actual.audit_org, "iox");
+        assert_eq!(actual.audit_bucket, "audit");
+    }
+
+    #[test]
+    fn test_destinations() {
+        let actual = AuditConfig::try_parse_from([
+            "my_binary",
+            "--audit-log-file",
+            "/var/log/iox/audit.log",
+            "--audit-router-addr",
+            "http://router:8080",
+            "--audit-bucket",
+            "bananas",
+        ])
+        .unwrap();
+
+        assert!(actual.is_enabled());
+        assert_eq!(
+            actual.audit_log_file,
+            Some(PathBuf::from("/var/log/iox/audit.log"))
+        );
+        assert_eq!(
+            actual.audit_router_addr.as_deref(),
+            Some("http://router:8080")
+        );
+        assert_eq!(actual.audit_bucket, "bananas");
+    }
+
+    #[test]
+    fn test_token_requires_router() {
+        let err = AuditConfig::try_parse_from(["my_binary", "--audit-token", "s3cr3t"])
+            .unwrap_err()
+            .to_string();
+        assert_contains!(err, "--audit-router-addr");
+    }
+}
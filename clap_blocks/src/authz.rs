@@ -0,0 +1,142 @@
+//! CLI config for selecting the request authorizer.
+
+use std::path::PathBuf;
+
+use crate::single_tenant::{CONFIG_AUTHZ_ENV_NAME, CONFIG_AUTHZ_FLAG};
+
+/// Configuration of the authorizer used to check request permissions.
+///
+/// At most one authorizer source may be specified: an external authz
+/// service, a static token file, or a JWKS file used to verify JSON Web
+/// Tokens.
+///
+/// The arguments form the `authz_source` group, which may be used by the
+/// containing config to require authorization be configured.
+#[derive(Debug, Clone, Default, PartialEq, Eq, clap::Parser)]
+#[group(id = "authz_source", multiple = true)]
+pub struct AuthzConfig {
+    /// Addr for connection to authz
+    #[clap(
+        long = CONFIG_AUTHZ_FLAG,
+        env = CONFIG_AUTHZ_ENV_NAME,
+        conflicts_with_all = ["authz_token_file", "authz_jwks_file"],
+    )]
+    pub authz_address: Option<String>,
+
+    /// Path to a JSON file of static tokens and the namespace permissions
+    /// each is granted.
+    ///
+    /// The file is re-read periodically, and changes take effect without a
+    /// restart.
+    #[clap(
+        long = "authz-token-file",
+        env = "INFLUXDB_IOX_AUTHZ_TOKEN_FILE",
+        conflicts_with = "authz_jwks_file",
+        action
+    )]
+    pub authz_token_file: Option<PathBuf>,
+
+    /// Path to a JSON Web Key Set file used to verify the signature of
+    /// JWT bearer tokens.
+    ///
+    /// Every key must name the `alg` that tokens it verifies are signed
+    /// with. Namespace permissions are read from the `iox_permissions` claim
+    /// of each token. The file is re-read periodically, allowing keys to be
+    /// rotated without a restart.
+    #[clap(long = "authz-jwks-file", env = "INFLUXDB_IOX_AUTHZ_JWKS_FILE", action)]
+    pub authz_jwks_file: Option<PathBuf>,
+
+    /// Require JWT bearer tokens to contain this `iss` claim.
+    #[clap(
+        long = "authz-jwt-issuer",
+        env = "INFLUXDB_IOX_AUTHZ_JWT_ISSUER",
+        requires = "authz_jwks_file",
+        action
+    )]
+    pub authz_jwt_issuer: Option<String>,
+
+    /// Require JWT bearer tokens to contain this `aud` claim.
+    #[clap(
+        long = "authz-jwt-audience",
+        env = "INFLUXDB_IOX_AUTHZ_JWT_AUDIENCE",
+        requires = "authz_jwks_file",
+        action
+    )]
+    pub authz_jwt_audience: Option<String>,
+}
+
+impl AuthzConfig {
+    /// Returns true if an authorizer source has been configured.
+    pub fn is_enabled(&self) -> bool {
+        self.authz_address.is_some()
+            || self.authz_token_file.is_some()
+            || self.authz_jwks_file.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use test_helpers::assert_contains;
+
+    #[test]
+    fn test_default() {
+        let actual = AuthzConfig::try_parse_from(["my_binary"]).unwrap();
+        assert_eq!(actual, AuthzConfig::default());
+        assert!(!actual.is_enabled());
+    }
+
+    #[test]
+    fn test_jwt() {
+        let actual = AuthzConfig::try_parse_from([
+            "my_binary",
+            "--authz-jwks-file",
+            "/etc/iox/jwks.json",
+            "--authz-jwt-issuer",
+            "bananas",
+        ])
+        .unwrap();
+
+        assert!(actual.is_enabled());
+        assert_eq!(
+            actual.authz_jwks_file,
+            Some(PathBuf::from("/etc/iox/jwks.json"))
+        );
+        assert_eq!(actual.authz_jwt_issuer.as_deref(), Some("bananas"));
+        assert_eq!(actual.authz_jwt_audience, None);
+    }
+
+    #[test]
+    fn test_sources_conflict() {
+        let err = AuthzConfig::try_parse_from([
+            "my_binary",
+            "--authz-addr",
+            "http://authz:8080",
+            "--authz-token-file",
+            "/etc/iox/tokens.json",
+        ])
+        .unwrap_err()
+        .to_string();
+        assert_contains!(err, "cannot be used with");
+
+        let err = AuthzConfig::try_parse_from([
+            "my_binary",
+            "--authz-token-file",
+            "/etc/iox/tokens.json",
+            "--authz-jwks-file",
+            "/etc/iox/jwks.json",
+        ])
+        .unwrap_err()
+        .to_string();
+        assert_contains!(err, "cannot be used with");
+    }
+
+    #[test]
+    fn test_jwt_options_require_jwks() {
+        let err = AuthzConfig::try_parse_from(["my_binary", "--authz-jwt-audience", "iox"])
+            .unwrap_err()
+            .to_string();
+        assert_contains!(err, "--authz-jwks-file");
+    }
+}
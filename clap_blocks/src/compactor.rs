@@ -1,6 +1,6 @@
 //! CLI config for compactor-related commands
 
-use std::num::NonZeroUsize;
+use std::{num::NonZeroUsize, time::Duration};
 
 use crate::{gossip::GossipConfig, memory_size::MemorySize};
 
@@ -269,4 +269,30 @@ pub struct CompactorConfig {
         action
     )]
     pub max_partition_fetch_queries_per_second: Option<usize>,
+
+    /// Cache the namespace, table, column and partition rows read from the
+    /// catalog in up to this many bytes of memory.
+    ///
+    /// If not specified, the catalog is not cached. Cached rows are
+    /// invalidated by the schema changes gossiped by the routers (if gossip
+    /// is enabled) and are otherwise refreshed after
+    /// `--compaction-catalog-cache-ttl`.
+    #[clap(
+        long = "compaction-catalog-cache-bytes",
+        env = "INFLUXDB_IOX_COMPACTION_CATALOG_CACHE_BYTES",
+        action
+    )]
+    pub catalog_cache_bytes: Option<usize>,
+
+    /// Duration after which a cached catalog row is refreshed.
+    ///
+    /// Only used if `--compaction-catalog-cache-bytes` is specified.
+    /// Parsed with <https://docs.rs/humantime/latest/humantime/fn.parse_duration.html>
+    #[clap(
+        long = "compaction-catalog-cache-ttl",
+        env = "INFLUXDB_IOX_COMPACTION_CATALOG_CACHE_TTL",
+        default_value = "1m",
+        value_parser = humantime::parse_duration,
+    )]
+    pub catalog_cache_ttl: Duration,
 }
@@ -1,9 +1,14 @@
 //! CLI config for cluster gossip communication.
 
+use gossip::{FrameProtection, SharedKey};
+
 use crate::socket_addr::SocketAddr;
 
+/// The minimum length of a gossip shared key.
+const MIN_SHARED_KEY_LEN: usize = 16;
+
 /// Configuration parameters for the cluster gossip communication mechanism.
-#[derive(Debug, Clone, clap::Parser)]
+#[derive(Clone, PartialEq, Eq, clap::Parser)]
 #[allow(missing_copy_implementations)]
 pub struct GossipConfig {
     /// A comma-delimited set of seed gossip peer addresses.
@@ -36,6 +41,79 @@ pub struct GossipConfig {
         action
     )]
     pub gossip_bind_address: Option<SocketAddr>,
+
+    /// A comma-delimited set of secret keys shared by all gossip peers, used
+    /// to authenticate gossip frames.
+    ///
+    /// Frames are signed with the first key, and frames signed with any of the
+    /// keys are accepted. Frames from peers without a common key are dropped.
+    /// Each key must be at least 16 bytes long.
+    ///
+    /// To rotate keys, append the new key on all nodes, then move it to the
+    /// front of the list on all nodes, and finally remove the old key.
+    ///
+    /// If not provided, gossip frames are unauthenticated.
+    #[clap(
+        long = "gossip-shared-keys",
+        env = "INFLUXDB_IOX_GOSSIP_SHARED_KEYS",
+        required = false,
+        num_args=1..,
+        value_delimiter = ',',
+        value_parser = parse_shared_key,
+        requires = "gossip_bind_address", // Field name, not flag
+        hide_env_values = true,
+    )]
+    pub shared_keys: Vec<String>,
+
+    /// Encrypt gossip frames in addition to authenticating them.
+    ///
+    /// Requires shared keys to be configured.
+    #[clap(
+        long = "gossip-encrypt",
+        env = "INFLUXDB_IOX_GOSSIP_ENCRYPT",
+        requires = "shared_keys", // Field name, not flag
+        action
+    )]
+    pub encrypt: bool,
+
+    /// The gRPC address this node advertises to its peers via gossip
+    /// cluster membership announcements.
+    ///
+    /// Example: "http://ingester-0.ingester:8082"
+    ///
+    /// If not provided, "http://<grpc bind address>" is advertised, which is
+    /// unlikely to be reachable by peers when binding to a wildcard address.
+    #[clap(
+        long = "gossip-advertise-rpc-address",
+        env = "INFLUXDB_IOX_GOSSIP_ADVERTISE_RPC_ADDRESS",
+        requires = "gossip_bind_address", // Field name, not flag
+        action
+    )]
+    pub advertise_rpc_address: Option<String>,
+}
+
+impl std::fmt::Debug for GossipConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GossipConfig")
+            .field("seed_list", &self.seed_list)
+            .field("gossip_bind_address", &self.gossip_bind_address)
+            .field(
+                "shared_keys",
+                &format!("<{} redacted>", self.shared_keys.len()),
+            )
+            .field("encrypt", &self.encrypt)
+            .field("advertise_rpc_address", &self.advertise_rpc_address)
+            .finish()
+    }
+}
+
+fn parse_shared_key(s: &str) -> Result<String, String> {
+    if s.len() < MIN_SHARED_KEY_LEN {
+        return Err(format!(
+            "gossip shared keys must be at least {MIN_SHARED_KEY_LEN} bytes long"
+        ));
+    }
+    Ok(s.to_string())
 }
 
 impl GossipConfig {
@@ -44,6 +122,125 @@ impl GossipConfig {
         Self {
             seed_list: vec![],
             gossip_bind_address: None,
+            shared_keys: vec![],
+            encrypt: false,
+            advertise_rpc_address: None,
+        }
+    }
+
+    /// The configured gossip [`SharedKey`], in priority order.
+    ///
+    /// An empty list indicates gossip frames should not be authenticated.
+    pub fn shared_keys(&self) -> Vec<SharedKey> {
+        self.shared_keys.iter().map(SharedKey::new).collect()
+    }
+
+    /// The [`FrameProtection`] to apply when [`Self::shared_keys()`] is
+    /// non-empty.
+    pub fn frame_protection(&self) -> FrameProtection {
+        if self.encrypt {
+            FrameProtection::Encrypt
+        } else {
+            FrameProtection::Authenticate
         }
     }
+
+    /// The gRPC address to advertise in cluster membership announcements,
+    /// falling back to one derived from `grpc_bind_address`.
+    pub fn advertise_rpc_address(&self, grpc_bind_address: &SocketAddr) -> String {
+        self.advertise_rpc_address
+            .clone()
+            .unwrap_or_else(|| format!("http://{grpc_bind_address}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    #[test]
+    fn test_shared_keys() {
+        let config = GossipConfig::try_parse_from([
+            "server",
+            "--gossip-seed-list",
+            "10.0.0.1:4242",
+            "--gossip-bind-address",
+            "0.0.0.0:4242",
+            "--gossip-shared-keys",
+            "bananas-bananas-bananas,platanos-platanos-platanos",
+            "--gossip-encrypt",
+        ])
+        .unwrap();
+
+        assert_eq!(config.shared_keys().len(), 2);
+        assert_eq!(config.frame_protection(), FrameProtection::Encrypt);
+
+        let debug = format!("{config:?}");
+        assert!(!debug.contains("bananas"), "{debug}");
+    }
+
+    #[test]
+    fn test_shared_key_too_short() {
+        let err = GossipConfig::try_parse_from([
+            "server",
+            "--gossip-seed-list",
+            "10.0.0.1:4242",
+            "--gossip-bind-address",
+            "0.0.0.0:4242",
+            "--gossip-shared-keys",
+            "bananas",
+        ])
+        .unwrap_err()
+        .to_string();
+
+        assert!(err.contains("at least 16 bytes"), "{err}");
+    }
+
+    #[test]
+    fn test_advertise_rpc_address() {
+        let grpc_bind_address: SocketAddr = "127.0.0.1:8082".parse().unwrap();
+
+        let config = GossipConfig::try_parse_from([
+            "server",
+            "--gossip-seed-list",
+            "10.0.0.1:4242",
+            "--gossip-bind-address",
+            "0.0.0.0:4242",
+        ])
+        .unwrap();
+        assert_eq!(
+            config.advertise_rpc_address(&grpc_bind_address),
+            "http://127.0.0.1:8082"
+        );
+
+        let config = GossipConfig::try_parse_from([
+            "server",
+            "--gossip-seed-list",
+            "10.0.0.1:4242",
+            "--gossip-bind-address",
+            "0.0.0.0:4242",
+            "--gossip-advertise-rpc-address",
+            "http://ingester-0:8082",
+        ])
+        .unwrap();
+        assert_eq!(
+            config.advertise_rpc_address(&grpc_bind_address),
+            "http://ingester-0:8082"
+        );
+    }
+
+    #[test]
+    fn test_encrypt_requires_keys() {
+        GossipConfig::try_parse_from([
+            "server",
+            "--gossip-seed-list",
+            "10.0.0.1:4242",
+            "--gossip-bind-address",
+            "0.0.0.0:4242",
+            "--gossip-encrypt",
+        ])
+        .unwrap_err();
+    }
 }
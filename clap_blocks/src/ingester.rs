@@ -35,6 +35,20 @@ pub struct IngesterConfig {
     )]
     pub wal_rotation_period_seconds: u64,
 
+    /// Salvage corrupt WAL segments found at startup instead of failing to
+    /// start.
+    ///
+    /// The readable entries of a corrupt segment are replayed, the original
+    /// file is moved to the "quarantine" directory within the WAL directory,
+    /// and the sequence numbers of the lost writes are logged.
+    #[clap(
+        long = "wal-replay-salvage",
+        env = "INFLUXDB_IOX_WAL_REPLAY_SALVAGE",
+        default_value = "false",
+        action
+    )]
+    pub wal_replay_salvage: bool,
+
     /// Sets how many queries the ingester will handle simultaneously before
     /// rejecting further incoming requests.
     #[clap(
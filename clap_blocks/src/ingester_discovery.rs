@@ -0,0 +1,172 @@
+//! CLI config for runtime discovery of the ingester set.
+
+use std::{num::ParseIntError, path::PathBuf, time::Duration};
+
+/// A source of ingester addresses, discovered at runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IngesterDiscoverySource {
+    /// Resolve the DNS SRV records of the given service name.
+    DnsSrv(String),
+    /// Resolve the A / AAAA records of the given `host:port` pair.
+    Dns(String),
+    /// Read a newline-delimited list of addresses from the given file.
+    File(PathBuf),
+    /// Use the alive ingesters announced via gossip cluster membership.
+    Gossip,
+}
+
+/// Configuration parameters for discovering the set of ingesters at runtime,
+/// instead of relying on a static list of ingester addresses.
+///
+/// At most one discovery source may be configured. If discovery fails, or
+/// discovers no ingesters, the previously known set of ingesters is retained.
+#[derive(Debug, Clone, PartialEq, Eq, clap::Parser)]
+pub struct IngesterDiscoveryConfig {
+    /// Discover the ingesters by resolving the DNS SRV records of this service
+    /// name, connecting to "http://<target>:<port>" for each record.
+    ///
+    /// Example: "_grpc._tcp.ingester.iox.svc.cluster.local"
+    #[clap(
+        long = "ingester-discovery-dns-srv",
+        env = "INFLUXDB_IOX_INGESTER_DISCOVERY_DNS_SRV",
+        conflicts_with_all = [
+            "ingester_discovery_dns",
+            "ingester_discovery_file",
+            "ingester_discovery_gossip",
+        ],
+        action
+    )]
+    pub ingester_discovery_dns_srv: Option<String>,
+
+    /// Discover the ingesters by resolving the A / AAAA records of this
+    /// "host:port" pair, connecting to "http://<ip>:<port>" for each address.
+    ///
+    /// Example: "ingester.iox.svc.cluster.local:8082"
+    #[clap(
+        long = "ingester-discovery-dns",
+        env = "INFLUXDB_IOX_INGESTER_DISCOVERY_DNS",
+        conflicts_with_all = ["ingester_discovery_file", "ingester_discovery_gossip"],
+        action
+    )]
+    pub ingester_discovery_dns: Option<String>,
+
+    /// Discover the ingesters by reading a newline-delimited list of ingester
+    /// addresses from this file. Changes to the file are picked up at runtime.
+    #[clap(
+        long = "ingester-discovery-file",
+        env = "INFLUXDB_IOX_INGESTER_DISCOVERY_FILE",
+        conflicts_with = "ingester_discovery_gossip",
+        action
+    )]
+    pub ingester_discovery_file: Option<PathBuf>,
+
+    /// Discover the ingesters from the gossip cluster membership
+    /// announcements of alive ingesters.
+    ///
+    /// Requires gossip to be enabled.
+    #[clap(
+        long = "ingester-discovery-gossip",
+        env = "INFLUXDB_IOX_INGESTER_DISCOVERY_GOSSIP",
+        requires = "gossip_bind_address", // Field name, not flag
+        action
+    )]
+    pub ingester_discovery_gossip: bool,
+
+    /// How often the ingester set is re-discovered, in seconds.
+    #[clap(
+        long = "ingester-discovery-interval-seconds",
+        env = "INFLUXDB_IOX_INGESTER_DISCOVERY_INTERVAL_SECONDS",
+        default_value = "10",
+        value_parser = parse_duration
+    )]
+    pub ingester_discovery_interval: Duration,
+}
+
+/// Map a string containing an integer number of seconds into a [`Duration`].
+fn parse_duration(input: &str) -> Result<Duration, ParseIntError> {
+    input.parse().map(Duration::from_secs)
+}
+
+impl IngesterDiscoveryConfig {
+    /// Initialise the config with discovery disabled.
+    pub fn disabled() -> Self {
+        Self {
+            ingester_discovery_dns_srv: None,
+            ingester_discovery_dns: None,
+            ingester_discovery_file: None,
+            ingester_discovery_gossip: false,
+            ingester_discovery_interval: Duration::from_secs(10),
+        }
+    }
+
+    /// The configured [`IngesterDiscoverySource`], if any.
+    pub fn source(&self) -> Option<IngesterDiscoverySource> {
+        if let Some(name) = &self.ingester_discovery_dns_srv {
+            return Some(IngesterDiscoverySource::DnsSrv(name.clone()));
+        }
+        if let Some(host_port) = &self.ingester_discovery_dns {
+            return Some(IngesterDiscoverySource::Dns(host_port.clone()));
+        }
+        if let Some(path) = &self.ingester_discovery_file {
+            return Some(IngesterDiscoverySource::File(path.clone()));
+        }
+        self.ingester_discovery_gossip
+            .then_some(IngesterDiscoverySource::Gossip)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    #[test]
+    fn test_default_disabled() {
+        let config = IngesterDiscoveryConfig::try_parse_from(["server"]).unwrap();
+        assert_eq!(config, IngesterDiscoveryConfig::disabled());
+        assert_eq!(config.source(), None);
+    }
+
+    #[test]
+    fn test_source() {
+        let config = IngesterDiscoveryConfig::try_parse_from([
+            "server",
+            "--ingester-discovery-dns-srv",
+            "_grpc._tcp.ingester",
+            "--ingester-discovery-interval-seconds",
+            "42",
+        ])
+        .unwrap();
+        assert_eq!(
+            config.source(),
+            Some(IngesterDiscoverySource::DnsSrv(
+                "_grpc._tcp.ingester".to_string()
+            ))
+        );
+        assert_eq!(config.ingester_discovery_interval, Duration::from_secs(42));
+
+        let config = IngesterDiscoveryConfig::try_parse_from([
+            "server",
+            "--ingester-discovery-file",
+            "/etc/iox/ingesters",
+        ])
+        .unwrap();
+        assert_eq!(
+            config.source(),
+            Some(IngesterDiscoverySource::File("/etc/iox/ingesters".into()))
+        );
+    }
+
+    #[test]
+    fn test_multiple_sources_rejected() {
+        IngesterDiscoveryConfig::try_parse_from([
+            "server",
+            "--ingester-discovery-dns",
+            "ingester:8082",
+            "--ingester-discovery-file",
+            "/etc/iox/ingesters",
+        ])
+        .unwrap_err();
+    }
+}
@@ -18,6 +18,8 @@
 // Workaround for "unused crate" lint false positives.
 use workspace_hack as _;
 
+pub mod audit;
+pub mod authz;
 pub mod catalog_dsn;
 pub mod compactor;
 pub mod compactor_scheduler;
@@ -25,6 +27,7 @@ pub mod garbage_collector;
 pub mod gossip;
 pub mod ingester;
 pub mod ingester_address;
+pub mod ingester_discovery;
 pub mod memory_size;
 pub mod object_store;
 pub mod querier;
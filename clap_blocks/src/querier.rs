@@ -1,18 +1,36 @@
 //! Querier-related configs.
 
 use crate::{
-    ingester_address::IngesterAddress,
+    audit::AuditConfig, authz::AuthzConfig, gossip::GossipConfig,
+    ingester_address::IngesterAddress, ingester_discovery::IngesterDiscoveryConfig,
     memory_size::MemorySize,
-    single_tenant::{CONFIG_AUTHZ_ENV_NAME, CONFIG_AUTHZ_FLAG},
 };
-use std::{collections::HashMap, num::NonZeroUsize};
+use std::{collections::HashMap, num::NonZeroUsize, path::PathBuf, time::Duration};
 
 /// CLI config for querier configuration
 #[derive(Debug, Clone, PartialEq, Eq, clap::Parser)]
 pub struct QuerierConfig {
-    /// Addr for connection to authz
-    #[clap(long = CONFIG_AUTHZ_FLAG, env = CONFIG_AUTHZ_ENV_NAME)]
-    pub authz_address: Option<String>,
+    /// Gossip config.
+    ///
+    /// If enabled, the querier applies parquet file and compaction events announced by the
+    /// ingesters and compactors to its catalog cache.
+    #[clap(flatten)]
+    pub gossip_config: GossipConfig,
+
+    /// Request authorization config.
+    #[clap(flatten)]
+    pub authz_config: AuthzConfig,
+
+    /// Audit trail config.
+    #[clap(flatten)]
+    pub audit_config: AuditConfig,
+
+    /// Ingester discovery config.
+    ///
+    /// If enabled, the set of ingesters queried is updated at runtime,
+    /// replacing any static `--ingester-addresses` once discovery succeeds.
+    #[clap(flatten)]
+    pub ingester_discovery_config: IngesterDiscoveryConfig,
 
     /// The number of threads to use for queries.
     ///
@@ -79,6 +97,65 @@ pub struct QuerierConfig {
     )]
     pub ram_pool_data_bytes: MemorySize,
 
+    /// Directory for the local-disk tier of the object store cache.
+    ///
+    /// Parquet data evicted from the RAM cache is kept here (ideally on fast local NVMe storage)
+    /// and survives querier restarts. If not specified, only the RAM cache is used.
+    #[clap(long = "disk-cache-dir", env = "INFLUXDB_IOX_DISK_CACHE_DIR", action)]
+    pub disk_cache_dir: Option<PathBuf>,
+
+    /// Size of the local-disk tier of the object store cache, in bytes.
+    ///
+    /// Only used if `--disk-cache-dir` is set.
+    #[clap(
+        long = "disk-cache-bytes",
+        env = "INFLUXDB_IOX_DISK_CACHE_BYTES",
+        default_value = "10737418240",  // 10GB
+        action
+    )]
+    pub disk_cache_bytes: u64,
+
+    /// Warm up the caches on startup with the N most recently created partitions.
+    ///
+    /// This preloads the namespace schemas, partitions and Parquet file metadata, so that the
+    /// first queries after a deploy do not all fan out to the catalog. Disabled if 0.
+    #[clap(
+        long = "cache-warmup-partitions",
+        env = "INFLUXDB_IOX_CACHE_WARMUP_PARTITIONS",
+        default_value = "0",
+        conflicts_with = "cache_warmup_window",
+        action
+    )]
+    pub cache_warmup_partitions: usize,
+
+    /// Warm up the caches on startup with all partitions that received new Parquet files within
+    /// this time window (e.g. "2h").
+    #[clap(
+        long = "cache-warmup-window",
+        env = "INFLUXDB_IOX_CACHE_WARMUP_WINDOW",
+        value_parser = humantime::parse_duration,
+        action
+    )]
+    pub cache_warmup_window: Option<Duration>,
+
+    /// Also load the Parquet data of the warm-up partitions into the object store cache.
+    #[clap(
+        long = "cache-warmup-parquet-data",
+        env = "INFLUXDB_IOX_CACHE_WARMUP_PARQUET_DATA",
+        action
+    )]
+    pub cache_warmup_parquet_data: bool,
+
+    /// Finish the cache warm-up before serving requests.
+    ///
+    /// If not set, the warm-up runs in the background.
+    #[clap(
+        long = "cache-warmup-wait",
+        env = "INFLUXDB_IOX_CACHE_WARMUP_WAIT",
+        action
+    )]
+    pub cache_warmup_wait: bool,
+
     /// Limit the number of concurrent queries.
     #[clap(
         long = "max-concurrent-queries",
@@ -88,6 +165,27 @@ pub struct QuerierConfig {
     )]
     pub max_concurrent_queries: usize,
 
+    /// Limit the number of concurrent queries issued with the same authorization token.
+    ///
+    /// Per-namespace limits are configured in the catalog.
+    #[clap(
+        long = "max-concurrent-queries-per-token",
+        env = "INFLUXDB_IOX_MAX_CONCURRENT_QUERIES_PER_TOKEN",
+        action
+    )]
+    pub max_concurrent_queries_per_token: Option<NonZeroUsize>,
+
+    /// How long a query waits for a free token or namespace slot before it is rejected with
+    /// "ResourceExhausted".
+    #[clap(
+        long = "query-queue-timeout",
+        env = "INFLUXDB_IOX_QUERY_QUEUE_TIMEOUT",
+        default_value = "30s",
+        value_parser = humantime::parse_duration,
+        action
+    )]
+    pub query_queue_timeout: Duration,
+
     /// After how many ingester query errors should the querier enter circuit breaker mode?
     ///
     /// The querier normally contacts the ingester for any unpersisted data during query planning.
@@ -187,6 +285,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cache_warmup() {
+        let actual = QuerierConfig::try_parse_from(["my_binary"]).unwrap();
+        assert_eq!(actual.cache_warmup_partitions, 0);
+        assert_eq!(actual.cache_warmup_window, None);
+        assert!(!actual.cache_warmup_wait);
+
+        let actual = QuerierConfig::try_parse_from([
+            "my_binary",
+            "--cache-warmup-window",
+            "2h",
+            "--cache-warmup-wait",
+        ])
+        .unwrap();
+        assert_eq!(
+            actual.cache_warmup_window,
+            Some(Duration::from_secs(2 * 60 * 60))
+        );
+        assert!(actual.cache_warmup_wait);
+
+        let actual = QuerierConfig::try_parse_from([
+            "my_binary",
+            "--cache-warmup-window",
+            "2h",
+            "--cache-warmup-partitions",
+            "10",
+        ])
+        .unwrap_err()
+        .to_string();
+        assert_contains!(actual, "cannot be used with");
+    }
+
     #[test]
     fn test_ingester_addresses_list() {
         let querier = QuerierConfig::try_parse_from([
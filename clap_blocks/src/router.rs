@@ -1,11 +1,12 @@
 //! CLI config for the router using the RPC write path
 
 use crate::{
+    audit::AuditConfig,
+    authz::AuthzConfig,
     gossip::GossipConfig,
     ingester_address::IngesterAddress,
-    single_tenant::{
-        CONFIG_AUTHZ_ENV_NAME, CONFIG_AUTHZ_FLAG, CONFIG_CST_ENV_NAME, CONFIG_CST_FLAG,
-    },
+    ingester_discovery::IngesterDiscoveryConfig,
+    single_tenant::{CONFIG_CST_ENV_NAME, CONFIG_CST_FLAG},
 };
 use std::{
     num::{NonZeroUsize, ParseIntError},
@@ -20,13 +21,22 @@ pub struct RouterConfig {
     #[clap(flatten)]
     pub gossip_config: GossipConfig,
 
-    /// Addr for connection to authz
-    #[clap(
-        long = CONFIG_AUTHZ_FLAG,
-        env = CONFIG_AUTHZ_ENV_NAME,
-        requires("single_tenant_deployment"),
-    )]
-    pub authz_address: Option<String>,
+    /// Request authorization config.
+    ///
+    /// Authorization is only supported for single tenant deployments.
+    #[clap(flatten)]
+    pub authz_config: AuthzConfig,
+
+    /// Audit trail config.
+    #[clap(flatten)]
+    pub audit_config: AuditConfig,
+
+    /// Ingester discovery config.
+    ///
+    /// If enabled, the set of ingesters written to is updated at runtime,
+    /// replacing any static `--ingester-addresses` once discovery succeeds.
+    #[clap(flatten)]
+    pub ingester_discovery_config: IngesterDiscoveryConfig,
 
     /// Differential handling based upon deployment to CST vs MT.
     ///
@@ -36,7 +46,7 @@ pub struct RouterConfig {
         long = CONFIG_CST_FLAG,
         env = CONFIG_CST_ENV_NAME,
         default_value = "false",
-        requires_if("true", "authz_address")
+        requires_if("true", "authz_source")
     )]
     pub single_tenant_deployment: bool,
 
@@ -67,10 +77,17 @@ pub struct RouterConfig {
     /// "http://10.10.10.1:8083,http://10.10.10.2:8083"
     ///
     /// for multiple addresses.
+    ///
+    /// May be omitted when an `--ingester-discovery-*` source is set.
     #[clap(
         long = "ingester-addresses",
         env = "INFLUXDB_IOX_INGESTER_ADDRESSES",
-        required = true,
+        required_unless_present_any = [ // Field names, not flags
+            "ingester_discovery_dns_srv",
+            "ingester_discovery_dns",
+            "ingester_discovery_file",
+            "ingester_discovery_gossip",
+        ],
         num_args=1..,
         value_delimiter = ','
     )]
@@ -141,6 +158,20 @@ pub struct RouterConfig {
         default_value = "10"
     )]
     pub rpc_write_health_num_probes: u64,
+
+    /// Serve the catalog gRPC RPCs that modify the catalog (flagging parquet
+    /// files for deletion, changing partition sort keys, etc), intended for
+    /// operators responding to incidents.
+    ///
+    /// If authorization is configured, callers must be granted the write (or
+    /// for deletions, the delete) permission for the affected namespace.
+    #[clap(
+        long = "catalog-write-access",
+        env = "INFLUXDB_IOX_CATALOG_WRITE_ACCESS",
+        default_value = "false",
+        action
+    )]
+    pub catalog_write_access: bool,
 }
 
 /// Map a string containing an integer number of seconds into a [`Duration`].
@@ -1,12 +1,17 @@
 //! Main compactor entry point.
 use std::sync::Arc;
 
+use async_trait::async_trait;
+use bytes::Bytes;
 use futures::{
     future::{BoxFuture, Shared},
     FutureExt, TryFutureExt,
 };
 use generated_types::influxdata::iox::gossip::{v1::CompactionEvent, Topic};
-use gossip::{NopDispatcher, TopicInterests};
+use gossip::{Dispatcher, Identity, TopicInterests};
+use gossip_membership::{tx::MembershipTx, MemberInfo, Role};
+use gossip_schema::dispatcher::SchemaRx;
+use iox_time::TimeProvider;
 use observability_deps::tracing::{info, warn};
 use tokio::task::{JoinError, JoinHandle};
 use tokio_util::sync::CancellationToken;
@@ -29,11 +34,29 @@ fn shared_handle(handle: JoinHandle<()>) -> SharedJoinHandle {
     handle.map_err(Arc::new).boxed().shared()
 }
 
+/// Dispatches the gossiped schema changes to the catalog cache, if any.
+#[derive(Debug)]
+struct CompactorDispatcher {
+    schema: Option<SchemaRx>,
+}
+
+#[async_trait]
+impl Dispatcher<Topic> for CompactorDispatcher {
+    async fn dispatch(&self, topic: Topic, payload: Bytes, sender: Identity) {
+        if let Some(schema) = &self.schema {
+            schema.dispatch(topic, payload, sender).await;
+        }
+    }
+}
+
 /// Main compactor driver.
 #[derive(Debug)]
 pub struct Compactor {
     shutdown: CancellationToken,
     worker: SharedJoinHandle,
+
+    /// The cluster membership announcer, if gossip is enabled.
+    _membership_tx: Option<MembershipTx>,
 }
 
 impl Compactor {
@@ -55,27 +78,52 @@ impl Compactor {
         let df_semaphore = Arc::new(semaphore_metrics.new_semaphore(config.df_concurrency.get()));
 
         // Initialise the gossip subsystem, if configured.
-        let gossip = match config.gossip_bind_address {
+        let (gossip, membership_tx) = match config.gossip_bind_address {
             Some(bind) => {
+                // Invalidate the cached catalog rows, if any, when peers
+                // gossip schema changes.
+                let schema = config
+                    .catalog_cache
+                    .as_ref()
+                    .map(|cache| SchemaRx::new(Arc::clone(cache), 100));
+                let mut topics = TopicInterests::default();
+                if schema.is_some() {
+                    topics = topics.with_topic(Topic::SchemaChanges);
+                }
+
                 // Initialise the gossip subsystem.
                 let handle = gossip::Builder::<_, Topic>::new(
                     config.gossip_seeds,
-                    NopDispatcher,
+                    CompactorDispatcher { schema },
                     Arc::clone(&config.metric_registry),
                 )
-                // Configure the compactor to subscribe to no topics - it
-                // currently only sends events.
-                .with_topic_filter(TopicInterests::default())
+                // Configure the compactor to subscribe to no topics other
+                // than the schema changes it caches - it otherwise only sends
+                // events.
+                .with_topic_filter(topics)
+                .with_shared_keys(config.gossip_shared_keys, config.gossip_protection)
                 .bind(bind)
                 .await
+                .map(Arc::new)
                 .expect("failed to start gossip reactor");
 
+                // Announce this compactor to the cluster.
+                let membership_tx = MembershipTx::new(
+                    Arc::clone(&handle),
+                    MemberInfo {
+                        role: Role::Compactor,
+                        rpc_address: config.gossip_rpc_address,
+                        version: env!("CARGO_PKG_VERSION").to_string(),
+                        started_at: config.time_provider.now(),
+                    },
+                );
+
                 let event_tx =
                     gossip_compaction::tx::CompactionEventTx::<CompactionEvent>::new(handle);
 
-                Some(Arc::new(event_tx))
+                (Some(Arc::new(event_tx)), Some(membership_tx))
             }
-            None => None,
+            None => (None, None),
         };
 
         let worker = tokio::spawn(async move {
@@ -97,7 +145,11 @@ impl Compactor {
         });
         let worker = shared_handle(worker);
 
-        Self { shutdown, worker }
+        Self {
+            shutdown,
+            worker,
+            _membership_tx: membership_tx,
+        }
     }
 
     /// Trigger shutdown. You should [join](Self::join) afterwards.
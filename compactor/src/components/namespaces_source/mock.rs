@@ -187,6 +187,8 @@ mod tests {
                         name: "ns".to_string(),
                         max_tables: MaxTables::try_from(10).unwrap(),
                         max_columns_per_table: MaxColumnsPerTable::try_from(10).unwrap(),
+                        max_concurrent_queries: None,
+                        max_query_memory_bytes: None,
                         retention_period_ns: None,
                         deleted_at: None,
                         partition_template: Default::default(),
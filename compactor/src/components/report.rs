@@ -15,6 +15,8 @@ pub fn log_config(config: &Config) {
         // no need to print the internal state of the trace collector
         trace_collector: _,
         catalog,
+        // the caching decorator is displayed as part of the catalog
+        catalog_cache: _,
         scheduler_config,
         parquet_store_real,
         parquet_store_scratchpad,
@@ -40,6 +42,9 @@ pub fn log_config(config: &Config) {
         max_partition_fetch_queries_per_second,
         gossip_bind_address,
         gossip_seeds,
+        gossip_shared_keys,
+        gossip_protection,
+        gossip_rpc_address,
     } = &config;
 
     let parquet_files_sink_override = parquet_files_sink_override
@@ -74,6 +79,9 @@ pub fn log_config(config: &Config) {
         max_partition_fetch_queries_per_second,
         ?gossip_bind_address,
         ?gossip_seeds,
+        ?gossip_shared_keys,
+        ?gossip_protection,
+        %gossip_rpc_address,
         "config",
     );
 }
@@ -3,7 +3,7 @@ use std::{net::SocketAddr, num::NonZeroUsize, sync::Arc, time::Duration};
 
 use backoff::BackoffConfig;
 use compactor_scheduler::SchedulerConfig;
-use iox_catalog::interface::Catalog;
+use iox_catalog::{cache::CachingCatalog, interface::Catalog};
 use iox_query::exec::Executor;
 use iox_time::TimeProvider;
 use parquet_file::storage::ParquetStorage;
@@ -28,6 +28,12 @@ pub struct Config {
     /// Central catalog.
     pub catalog: Arc<dyn Catalog>,
 
+    /// The [`CachingCatalog`] that `catalog` is, if catalog rows are cached.
+    ///
+    /// It is invalidated by the schema changes received through gossip, if
+    /// `gossip_bind_address` is `Some`.
+    pub catalog_cache: Option<Arc<CachingCatalog>>,
+
     /// Scheduler configuration.
     pub scheduler_config: SchedulerConfig,
 
@@ -143,6 +149,21 @@ pub struct Config {
     ///
     /// Only used if `gossip_bind_address` is `Some`.
     pub gossip_seeds: Vec<String>,
+
+    /// Shared keys used to authenticate gossip frames, in priority order.
+    ///
+    /// If empty, gossip frames are unauthenticated.
+    pub gossip_shared_keys: Vec<gossip::SharedKey>,
+
+    /// The protection applied to outgoing gossip frames when
+    /// `gossip_shared_keys` is non-empty.
+    pub gossip_protection: gossip::FrameProtection,
+
+    /// The gRPC address advertised to peers in gossip cluster membership
+    /// announcements.
+    ///
+    /// Only used if `gossip_bind_address` is `Some`.
+    pub gossip_rpc_address: String,
 }
 
 impl Config {
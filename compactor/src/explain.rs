@@ -0,0 +1,194 @@
+//! Explain the compaction decisions for a partition without compacting it.
+
+use std::sync::Arc;
+
+use data_types::{CompactionLevel, ParquetFile, ParquetFileId, PartitionId};
+
+use crate::{
+    components::Components, error::DynError, file_classification::FilesToSplitOrCompact,
+    partition_info::PartitionInfo,
+};
+
+/// The decisions the compactor makes in the first round of compacting a partition.
+///
+/// Only the first round can be explained without compacting, as the rounds after it depend on
+/// the files the first round writes.
+#[derive(Debug)]
+pub struct PartitionExplanation {
+    /// The partition
+    pub partition_info: Arc<PartitionInfo>,
+
+    /// The files in the partition that are not marked for deletion
+    pub files: Vec<ParquetFile>,
+
+    /// Whether the partition passes the filters that decide if it needs compacting
+    pub needs_compaction: bool,
+
+    /// The ranges of the first round. Empty if the partition does not need compacting.
+    pub ranges: Vec<RangeExplanation>,
+}
+
+/// The decisions for a range of files within a round, see
+/// [`RoundInfo`](crate::RoundInfo).
+#[derive(Debug)]
+pub struct RangeExplanation {
+    /// The operation of this range, as shown in the compactor logs
+    pub op: String,
+
+    /// The minimum time of any file in the range
+    pub min: i64,
+
+    /// The maximum time of any file in the range
+    pub max: i64,
+
+    /// The sum of the sizes of all files in the range
+    pub cap: usize,
+
+    /// The branches compacted concurrently in this round
+    pub branches: Vec<BranchExplanation>,
+
+    /// Files left for a later round
+    pub files_for_later: Vec<ParquetFileId>,
+}
+
+/// The classification of the files in a single compaction branch.
+#[derive(Debug)]
+pub struct BranchExplanation {
+    /// The target level of the files written
+    pub target_level: CompactionLevel,
+
+    /// Files upgraded to the target level without being rewritten
+    pub files_to_upgrade: Vec<ParquetFileId>,
+
+    /// Files compacted together
+    pub files_to_compact: Vec<ParquetFileId>,
+
+    /// Files split at the given times
+    pub files_to_split: Vec<(ParquetFileId, Vec<i64>)>,
+
+    /// Files left unmodified
+    pub files_to_keep: Vec<ParquetFileId>,
+
+    /// Why the files were chosen to be compacted or split, or why there is nothing to do
+    pub reason: String,
+
+    /// Whether the branch passes the filters that check it can make progress within the
+    /// resource limits. A branch that does not is skipped, keeping all of its files.
+    pub makes_progress: bool,
+}
+
+/// Runs the partition filters, [round info source](Components::round_info_source) and
+/// [file classifier](Components::file_classifier) of `components` against the partition, returning
+/// the decisions made for its first compaction round.
+///
+/// Nothing is compacted or committed.
+pub async fn explain_partition(
+    components: Arc<Components>,
+    partition_id: PartitionId,
+    concurrency_limit: usize,
+) -> Result<PartitionExplanation, DynError> {
+    let files = components.partition_files_source.fetch(partition_id).await;
+    let partition_info = components.partition_info_source.fetch(partition_id).await?;
+
+    let mut explanation = PartitionExplanation {
+        partition_info: Arc::clone(&partition_info),
+        files: files.clone(),
+        needs_compaction: false,
+        ranges: vec![],
+    };
+
+    if files.is_empty()
+        || !components
+            .partition_filter
+            .apply(&partition_info, &files)
+            .await?
+    {
+        return Ok(explanation);
+    }
+
+    let (round_info, done) = components
+        .round_info_source
+        .calculate(
+            Arc::clone(&components),
+            None,
+            &partition_info,
+            concurrency_limit,
+            files,
+        )
+        .await?;
+    if done {
+        return Ok(explanation);
+    }
+    explanation.needs_compaction = true;
+
+    for range in &round_info.ranges {
+        let mut range_explanation = RangeExplanation {
+            op: range
+                .op
+                .as_ref()
+                .map_or_else(|| "None".to_string(), ToString::to_string),
+            min: range.min,
+            max: range.max,
+            cap: range.cap,
+            branches: vec![],
+            files_for_later: range
+                .files_for_later
+                .lock()
+                .unwrap()
+                .iter()
+                .flatten()
+                .map(|f| f.id)
+                .collect(),
+        };
+
+        let branches = range.branches.lock().unwrap().clone().unwrap_or_default();
+        if let Some(op) = &range.op {
+            for branch in branches {
+                let classification =
+                    components
+                        .file_classifier
+                        .classify(&partition_info, op, branch);
+                let makes_progress = components
+                    .post_classification_partition_filter
+                    .apply(
+                        &partition_info,
+                        &classification.files_to_make_progress_on,
+                        &classification.files_to_keep,
+                    )
+                    .await?;
+
+                let progress = classification.files_to_make_progress_on;
+                let (files_to_compact, files_to_split, reason) = match progress.split_or_compact {
+                    FilesToSplitOrCompact::None(reason) => (vec![], vec![], format!("{reason:?}")),
+                    FilesToSplitOrCompact::Split(files, reason) => (
+                        vec![],
+                        files
+                            .into_iter()
+                            .map(|f| (f.file.id, f.split_times))
+                            .collect(),
+                        format!("{reason:?}"),
+                    ),
+                    FilesToSplitOrCompact::Compact(files, reason) => (
+                        files.iter().map(|f| f.id).collect(),
+                        vec![],
+                        format!("{reason:?}"),
+                    ),
+                };
+
+                range_explanation.branches.push(BranchExplanation {
+                    target_level: classification.target_level,
+                    files_to_upgrade: progress.upgrade.iter().map(|f| f.id).collect(),
+                    files_to_compact,
+                    files_to_split,
+                    files_to_keep: classification.files_to_keep.iter().map(|f| f.id).collect(),
+                    reason,
+                    makes_progress,
+                });
+            }
+        }
+
+        explanation.ranges.push(range_explanation);
+    }
+
+    Ok(explanation)
+}
@@ -213,6 +213,7 @@ mod components;
 pub mod config;
 mod driver;
 mod error;
+mod explain;
 mod file_classification;
 pub mod object_store;
 mod partition_info;
@@ -226,6 +227,7 @@ pub use components::{
 };
 pub use driver::compact;
 pub use error::DynError;
+pub use explain::{explain_partition, BranchExplanation, PartitionExplanation, RangeExplanation};
 pub use partition_info::PartitionInfo;
 pub use plan_ir::PlanIR;
 pub use round_info::RoundInfo;
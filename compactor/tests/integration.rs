@@ -1,4 +1,5 @@
 use arrow_util::assert_batches_sorted_eq;
+use compactor::{explain_partition, hardcoded_components};
 use compactor_test_utils::{format_files, list_object_store, TestSetup};
 use data_types::{CompactionLevel, ParquetFile, PartitionId};
 
@@ -15,7 +16,8 @@ async fn test_compact_no_file() {
     assert!(files.is_empty());
 
     // compact
-    setup.run_compact().await;
+    let result = setup.run_compact().await;
+    assert_eq!(result.rounds, 0);
 
     // verify catalog is still empty
     let files = setup.list_by_table_not_to_delete().await;
@@ -54,7 +56,9 @@ async fn test_num_files_over_limit() {
         ],
     );
 
-    setup.run_compact().await;
+    // compacting the new L0s happens in a later round than writing them
+    let result = setup.run_compact().await;
+    assert!(result.rounds >= 2);
     //
     // read files and verify 2 files
     let files = setup.list_by_table_not_to_delete().await;
@@ -537,6 +541,63 @@ async fn test_shadow_mode_partition_fail() {
     assert_skipped_compactions(&setup, []).await;
 }
 
+#[tokio::test]
+async fn test_explain_partition() {
+    test_helpers::maybe_start_logging();
+
+    // Create a test setup with 6 files
+    let setup = TestSetup::builder().await.with_files().await.build().await;
+
+    let catalog_files_pre = setup.list_by_table_not_to_delete().await;
+    let object_store_files_pre = list_object_store(&setup.catalog.object_store).await;
+
+    let explanation = explain_partition(
+        hardcoded_components(&setup.config),
+        setup.partition_info.partition_id,
+        10,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(explanation.files.len(), 6);
+    assert!(explanation.needs_compaction);
+    assert!(!explanation.ranges.is_empty());
+    let branches = explanation
+        .ranges
+        .iter()
+        .flat_map(|range| &range.branches)
+        .collect::<Vec<_>>();
+    assert!(branches.iter().any(|branch| branch.makes_progress
+        && !(branch.files_to_compact.is_empty() && branch.files_to_split.is_empty())));
+
+    // nothing was compacted
+    let catalog_files_post = setup.list_by_table_not_to_delete().await;
+    assert_eq!(catalog_files_pre, catalog_files_post);
+
+    let object_store_files_post = list_object_store(&setup.catalog.object_store).await;
+    assert_eq!(object_store_files_pre, object_store_files_post);
+}
+
+#[tokio::test]
+async fn test_explain_partition_no_file() {
+    test_helpers::maybe_start_logging();
+
+    // no files
+    let setup = TestSetup::builder().await.build().await;
+
+    let explanation = explain_partition(
+        hardcoded_components(&setup.config),
+        setup.partition_info.partition_id,
+        10,
+    )
+    .await
+    .unwrap();
+
+    assert!(explanation.files.is_empty());
+    assert!(!explanation.needs_compaction);
+    assert!(explanation.ranges.is_empty());
+}
+
 #[track_caller]
 fn assert_levels<'a>(
     files: impl IntoIterator<Item = &'a ParquetFile>,
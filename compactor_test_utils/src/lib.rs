@@ -59,7 +59,7 @@ use iox_time::{MockProvider, Time, TimeProvider};
 use object_store::{path::Path, DynObjectStore};
 use parquet_file::storage::{ParquetStorage, StorageId};
 use schema::sort::SortKey;
-use trace::{RingBufferTraceCollector, TraceCollector};
+use trace::{ctx::SpanId, span::Span, RingBufferTraceCollector, TraceCollector};
 use tracker::AsyncSemaphoreMetrics;
 
 // Default values for the test setup builder
@@ -143,6 +143,7 @@ impl TestSetupBuilder<false> {
             metric_registry: catalog.metric_registry(),
             trace_collector,
             catalog: catalog.catalog(),
+            catalog_cache: None,
             scheduler_config: SchedulerConfig::new_local_with_wrapper(Arc::new(commit_wrapper)),
             parquet_store_real: catalog.parquet_store.clone(),
             parquet_store_scratchpad: ParquetStorage::new(
@@ -171,6 +172,9 @@ impl TestSetupBuilder<false> {
             max_partition_fetch_queries_per_second: None,
             gossip_bind_address: None,
             gossip_seeds: vec![],
+            gossip_shared_keys: vec![],
+            gossip_protection: gossip::FrameProtection::Authenticate,
+            gossip_rpc_address: String::new(),
         };
 
         let bytes_written = Arc::new(AtomicUsize::new(0));
@@ -753,7 +757,9 @@ impl TestSetup {
         let df_semaphore = Arc::new(
             Arc::new(AsyncSemaphoreMetrics::new(&config.metric_registry, [])).new_semaphore(10),
         );
-        let trace_collector = config.trace_collector.clone();
+        let round_counter = Arc::new(RoundCounter::new(config.trace_collector.clone()));
+        let trace_collector: Option<Arc<dyn TraceCollector>> =
+            Some(Arc::clone(&round_counter) as _);
 
         // register scratchpad store
         let runtime_env = self
@@ -781,6 +787,7 @@ impl TestSetup {
         // get the results
         CompactResult {
             run_log: self.run_log.lock().unwrap().clone(),
+            rounds: round_counter.rounds(),
         }
     }
 
@@ -891,6 +898,47 @@ fn assert_no_inter_level_misordered_overlap(f1: &ParquetFile, f2: &ParquetFile)
 pub struct CompactResult {
     /// [`ParquetFileSimulator`] output, if enabled
     pub run_log: Vec<String>,
+    /// The number of compaction rounds that had work to do
+    pub rounds: u64,
+}
+
+/// A [`TraceCollector`] counting the compaction rounds that ran at least one
+/// branch, forwarding all spans to an optional inner collector.
+#[derive(Debug)]
+struct RoundCounter {
+    inner: Option<Arc<dyn TraceCollector>>,
+    /// The span IDs of the "round" spans that were parents of a "branch" span
+    rounds: Mutex<HashSet<SpanId>>,
+}
+
+impl RoundCounter {
+    fn new(inner: Option<Arc<dyn TraceCollector>>) -> Self {
+        Self {
+            inner,
+            rounds: Default::default(),
+        }
+    }
+
+    fn rounds(&self) -> u64 {
+        self.rounds.lock().unwrap().len() as u64
+    }
+}
+
+impl TraceCollector for RoundCounter {
+    fn export(&self, span: Span) {
+        if span.name == "branch" {
+            if let Some(round) = span.ctx.parent_span_id {
+                self.rounds.lock().unwrap().insert(round);
+            }
+        }
+        if let Some(inner) = &self.inner {
+            inner.export(span);
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
 }
 
 /// A collection of nanosecond timestamps relative to now
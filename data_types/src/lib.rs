@@ -27,6 +27,8 @@ pub mod partition_template;
 use partition_template::*;
 pub mod partition;
 pub use partition::*;
+mod partition_statistics;
+pub use partition_statistics::*;
 pub mod sequence_number_set;
 pub mod service_limits;
 pub use service_limits::*;
@@ -287,6 +289,12 @@ pub struct Namespace {
     pub max_tables: MaxTables,
     /// The maximum number of columns per table in this namespace
     pub max_columns_per_table: MaxColumnsPerTable,
+    /// The maximum number of queries against this namespace a querier executes concurrently.
+    /// None represents no namespace-specific limit.
+    pub max_concurrent_queries: Option<i32>,
+    /// The maximum memory in bytes that the queries against this namespace may reserve from a
+    /// querier's query memory pool. None represents no namespace-specific limit.
+    pub max_query_memory_bytes: Option<i64>,
     /// When this file was marked for deletion.
     pub deleted_at: Option<Timestamp>,
     /// The partition template to use for new tables in this namespace either created implicitly or
@@ -0,0 +1,570 @@
+//! Summary statistics of the persisted data in a partition.
+
+use std::cmp::Ordering;
+
+use crate::{ColumnId, ParquetFile, ParquetFileId, PartitionId, Timestamp};
+
+/// Summary statistics of the persisted (not soft-deleted) parquet files in a
+/// partition, maintained by the catalog as files are persisted and compacted.
+///
+/// The row count and time range are folded in incrementally as each file is
+/// persisted, and recomputed from the partition's parquet files on every
+/// compaction commit. They are exact as of `max_parquet_file_id`.
+///
+/// The per-column statistics are merged from the statistics supplied when a
+/// file is persisted, and retained across compactions (which never introduce
+/// new values). They are therefore:
+///
+/// * conservative bounds: the tag `min` / `max` values bound all values in the
+///   partition, but compaction or retention may have removed the extremes.
+/// * upper bounds: `null_count` may overestimate the number of NULLs after
+///   compaction deduplicated rows.
+///
+/// The distinct tag values are exact as long as no more than
+/// [`MAX_PARTITION_TAG_VALUES`] are recorded per column - compaction and
+/// deduplication never remove a tag value from a partition.
+///
+/// Per-column statistics are only usable if [`Self::complete`] is true - a
+/// file persisted without column statistics leaves them incomplete.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionStatistics {
+    /// The catalog partition these statistics summarise.
+    pub partition_id: PartitionId,
+
+    /// The total number of rows in the partition's parquet files.
+    pub row_count: i64,
+
+    /// The minimum timestamp in the partition's parquet files, if any.
+    pub min_time: Option<Timestamp>,
+
+    /// The maximum timestamp in the partition's parquet files, if any.
+    pub max_time: Option<Timestamp>,
+
+    /// The highest parquet file ID accounted for by these statistics, if any.
+    ///
+    /// Any file in this partition with a greater ID was committed after these
+    /// statistics were read.
+    pub max_parquet_file_id: Option<ParquetFileId>,
+
+    /// True if [`Self::columns`] accounts for the content of every parquet
+    /// file in the partition.
+    pub complete: bool,
+
+    /// Per-column statistics, ordered by column ID.
+    pub columns: Vec<PartitionColumnStatistics>,
+}
+
+/// The maximum number of distinct values recorded per tag column in
+/// [`PartitionColumnStatistics::values`].
+pub const MAX_PARTITION_TAG_VALUES: usize = 1_000;
+
+/// Summary statistics for a single column in a partition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionColumnStatistics {
+    /// The column these statistics describe.
+    pub column_id: ColumnId,
+
+    /// The number of NULL values in this column.
+    pub null_count: i64,
+
+    /// The minimum non-NULL value of a tag column, if any.
+    ///
+    /// Always [`None`] for non-tag columns.
+    pub min: Option<String>,
+
+    /// The maximum non-NULL value of a tag column, if any.
+    ///
+    /// Always [`None`] for non-tag columns.
+    pub max: Option<String>,
+
+    /// The sorted, distinct non-NULL values of a tag column, if known.
+    ///
+    /// [`None`] for non-tag columns, and for tag columns with more than
+    /// [`MAX_PARTITION_TAG_VALUES`] distinct values.
+    pub values: Option<Vec<String>>,
+}
+
+/// The totals of the live (not soft-deleted) parquet files in a partition,
+/// as read by the catalog when committing a change to the partition's files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PartitionFileTotals {
+    /// The number of live files.
+    pub file_count: i64,
+    /// The sum of the live files' row counts.
+    pub row_count: i64,
+    /// The minimum `min_time` of the live files.
+    pub min_time: Option<Timestamp>,
+    /// The maximum `max_time` of the live files.
+    pub max_time: Option<Timestamp>,
+    /// The highest ID of the live files.
+    pub max_parquet_file_id: Option<ParquetFileId>,
+}
+
+impl From<&ParquetFile> for PartitionFileTotals {
+    /// The totals of a partition holding only `file`.
+    fn from(file: &ParquetFile) -> Self {
+        Self {
+            file_count: 1,
+            row_count: file.row_count,
+            min_time: Some(file.min_time),
+            max_time: Some(file.max_time),
+            max_parquet_file_id: Some(file.id),
+        }
+    }
+}
+
+/// The effect of a catalog commit on the per-column statistics of a
+/// partition.
+#[derive(Debug, Clone, Copy)]
+pub enum PartitionColumnStatisticsUpdate<'a> {
+    /// A single file with `row_count` rows and the given per-column statistics
+    /// was added.
+    Merge {
+        /// The number of rows in the new file.
+        row_count: i64,
+        /// The statistics of the columns in the new file.
+        columns: &'a [PartitionColumnStatistics],
+    },
+    /// Files of unknown content were added.
+    Unknown,
+    /// Files were rewritten without introducing new values (compaction), or
+    /// removed.
+    Retain,
+}
+
+impl PartitionStatistics {
+    /// Compute the statistics for `partition_id` after a commit described by
+    /// `update`, given the `previous` statistics (if any) and the `totals` of
+    /// the partition's live files after the commit.
+    pub fn apply(
+        partition_id: PartitionId,
+        previous: Option<Self>,
+        totals: PartitionFileTotals,
+        update: PartitionColumnStatisticsUpdate<'_>,
+    ) -> Self {
+        let mut stats = previous.unwrap_or_else(|| {
+            // Statistics were never recorded for this partition - they are
+            // only complete if the partition had no files before this commit.
+            let files_before = match update {
+                PartitionColumnStatisticsUpdate::Merge { .. } => totals.file_count - 1,
+                PartitionColumnStatisticsUpdate::Unknown
+                | PartitionColumnStatisticsUpdate::Retain => totals.file_count,
+            };
+            Self {
+                partition_id,
+                row_count: 0,
+                min_time: None,
+                max_time: None,
+                max_parquet_file_id: None,
+                complete: files_before <= 0,
+                columns: vec![],
+            }
+        });
+
+        match update {
+            PartitionColumnStatisticsUpdate::Merge { row_count, columns } => {
+                stats.merge_columns(row_count, columns)
+            }
+            PartitionColumnStatisticsUpdate::Unknown => stats.complete = false,
+            PartitionColumnStatisticsUpdate::Retain => {}
+        }
+
+        // A partition without any files has nothing left to summarise.
+        if totals.file_count == 0 {
+            stats.complete = true;
+            stats.columns.clear();
+        }
+
+        stats.row_count = totals.row_count;
+        stats.min_time = totals.min_time;
+        stats.max_time = totals.max_time;
+        stats.max_parquet_file_id = totals.max_parquet_file_id;
+        stats
+    }
+
+    /// Fold a single newly persisted `file` into the `previous` statistics of
+    /// `partition_id` (if any), without reading the partition's other files.
+    ///
+    /// Unlike [`Self::apply()`], a partition without `previous` statistics is
+    /// assumed to have had no files before `file` was added.
+    pub fn add_file(
+        partition_id: PartitionId,
+        previous: Option<Self>,
+        file: PartitionFileTotals,
+        update: PartitionColumnStatisticsUpdate<'_>,
+    ) -> Self {
+        let mut stats = previous.unwrap_or(Self {
+            partition_id,
+            row_count: 0,
+            min_time: None,
+            max_time: None,
+            max_parquet_file_id: None,
+            complete: true,
+            columns: vec![],
+        });
+
+        match update {
+            // Merging the columns also accounts for the new file's rows.
+            PartitionColumnStatisticsUpdate::Merge { row_count, columns } => {
+                stats.merge_columns(row_count, columns)
+            }
+            PartitionColumnStatisticsUpdate::Unknown => {
+                stats.complete = false;
+                stats.row_count += file.row_count;
+            }
+            PartitionColumnStatisticsUpdate::Retain => stats.row_count += file.row_count,
+        }
+
+        stats.min_time = merge_opt(stats.min_time, file.min_time, std::cmp::min);
+        stats.max_time = merge_opt(stats.max_time, file.max_time, std::cmp::max);
+        stats.max_parquet_file_id = merge_opt(
+            stats.max_parquet_file_id,
+            file.max_parquet_file_id,
+            std::cmp::max,
+        );
+        stats
+    }
+
+    /// Return the statistics for `column_id`, if any.
+    pub fn column(&self, column_id: ColumnId) -> Option<&PartitionColumnStatistics> {
+        self.columns
+            .binary_search_by_key(&column_id, |c| c.column_id)
+            .ok()
+            .map(|idx| &self.columns[idx])
+    }
+
+    /// Merge the statistics of a new file containing `row_count` rows.
+    ///
+    /// Rows of a file that does not contain a column are NULL for that column.
+    fn merge_columns(&mut self, row_count: i64, columns: &[PartitionColumnStatistics]) {
+        let mut new = columns.to_vec();
+        new.sort_unstable_by_key(|c| c.column_id);
+
+        let existing = std::mem::take(&mut self.columns);
+        let mut existing = existing.into_iter().peekable();
+        let mut new = new.into_iter().peekable();
+
+        let mut merged = Vec::with_capacity(existing.len().max(new.len()));
+        loop {
+            let ord = match (existing.peek(), new.peek()) {
+                (Some(a), Some(b)) => a.column_id.cmp(&b.column_id),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => break,
+            };
+            match ord {
+                Ordering::Less => {
+                    // Column absent from the new file.
+                    let mut c = existing.next().unwrap();
+                    c.null_count += row_count;
+                    merged.push(c);
+                }
+                Ordering::Greater => {
+                    // Column absent from all previous files.
+                    let mut c = new.next().unwrap();
+                    c.null_count += self.row_count;
+                    merged.push(c);
+                }
+                Ordering::Equal => {
+                    let mut c = existing.next().unwrap();
+                    let n = new.next().unwrap();
+                    c.null_count += n.null_count;
+                    c.min = merge_opt(c.min, n.min, std::cmp::min);
+                    c.max = merge_opt(c.max, n.max, std::cmp::max);
+                    c.values = merge_values(c.values, n.values);
+                    merged.push(c);
+                }
+            }
+        }
+
+        self.columns = merged;
+        self.row_count += row_count;
+    }
+}
+
+/// Union two sorted sets of distinct tag values, returning [`None`] if either
+/// is unknown or the union exceeds [`MAX_PARTITION_TAG_VALUES`].
+fn merge_values(a: Option<Vec<String>>, b: Option<Vec<String>>) -> Option<Vec<String>> {
+    let (a, b) = (a?, b?);
+    let mut merged = Vec::with_capacity(a.len().max(b.len()));
+    let mut a = a.into_iter().peekable();
+    let mut b = b.into_iter().peekable();
+    loop {
+        let next = match (a.peek(), b.peek()) {
+            (Some(x), Some(y)) => match x.cmp(y) {
+                Ordering::Less => a.next(),
+                Ordering::Greater => b.next(),
+                Ordering::Equal => {
+                    b.next();
+                    a.next()
+                }
+            },
+            (Some(_), None) => a.next(),
+            (None, Some(_)) => b.next(),
+            (None, None) => break,
+        };
+        merged.extend(next);
+        if merged.len() > MAX_PARTITION_TAG_VALUES {
+            return None;
+        }
+    }
+    Some(merged)
+}
+
+fn merge_opt<T>(a: Option<T>, b: Option<T>, f: impl FnOnce(T, T) -> T) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(f(a, b)),
+        (a, b) => a.or(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(id: i64, null_count: i64, min: &str, max: &str) -> PartitionColumnStatistics {
+        PartitionColumnStatistics {
+            column_id: ColumnId::new(id),
+            null_count,
+            min: Some(min.to_string()),
+            max: Some(max.to_string()),
+            values: None,
+        }
+    }
+
+    fn tag_values(id: i64, values: &[&str]) -> PartitionColumnStatistics {
+        PartitionColumnStatistics {
+            values: Some(values.iter().map(ToString::to_string).collect()),
+            ..tag(id, 0, values[0], values[values.len() - 1])
+        }
+    }
+
+    fn totals(file_count: i64, row_count: i64, max_id: i64) -> PartitionFileTotals {
+        PartitionFileTotals {
+            file_count,
+            row_count,
+            min_time: Some(Timestamp::new(1)),
+            max_time: Some(Timestamp::new(42)),
+            max_parquet_file_id: Some(ParquetFileId::new(max_id)),
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let partition_id = PartitionId::new(1);
+
+        let stats = PartitionStatistics::apply(
+            partition_id,
+            None,
+            totals(1, 10, 1),
+            PartitionColumnStatisticsUpdate::Merge {
+                row_count: 10,
+                columns: &[tag(2, 1, "b", "c"), tag(1, 0, "x", "y")],
+            },
+        );
+        assert!(stats.complete);
+        assert_eq!(stats.row_count, 10);
+        assert_eq!(stats.columns, [tag(1, 0, "x", "y"), tag(2, 1, "b", "c")]);
+
+        // The second file lacks column 1, and introduces column 3.
+        let stats = PartitionStatistics::apply(
+            partition_id,
+            Some(stats),
+            totals(2, 15, 2),
+            PartitionColumnStatisticsUpdate::Merge {
+                row_count: 5,
+                columns: &[tag(2, 2, "a", "b"), tag(3, 0, "q", "r")],
+            },
+        );
+        assert!(stats.complete);
+        assert_eq!(stats.row_count, 15);
+        assert_eq!(stats.max_parquet_file_id, Some(ParquetFileId::new(2)));
+        assert_eq!(
+            stats.columns,
+            [
+                tag(1, 5, "x", "y"),
+                tag(2, 3, "a", "c"),
+                tag(3, 10, "q", "r")
+            ]
+        );
+        assert_eq!(stats.column(ColumnId::new(2)), Some(&tag(2, 3, "a", "c")));
+        assert_eq!(stats.column(ColumnId::new(4)), None);
+
+        // Compaction recomputes the totals but retains the column statistics.
+        let compacted = PartitionStatistics::apply(
+            partition_id,
+            Some(stats.clone()),
+            totals(1, 12, 3),
+            PartitionColumnStatisticsUpdate::Retain,
+        );
+        assert!(compacted.complete);
+        assert_eq!(compacted.row_count, 12);
+        assert_eq!(compacted.columns, stats.columns);
+    }
+
+    #[test]
+    fn test_merge_values() {
+        let partition_id = PartitionId::new(1);
+
+        let stats = PartitionStatistics::apply(
+            partition_id,
+            None,
+            totals(1, 2, 1),
+            PartitionColumnStatisticsUpdate::Merge {
+                row_count: 2,
+                columns: &[tag_values(1, &["a", "c"]), tag_values(2, &["x"])],
+            },
+        );
+        let stats = PartitionStatistics::apply(
+            partition_id,
+            Some(stats),
+            totals(2, 4, 2),
+            PartitionColumnStatisticsUpdate::Merge {
+                row_count: 2,
+                columns: &[tag_values(1, &["b", "c"]), tag(2, 0, "y", "z")],
+            },
+        );
+        assert_eq!(
+            stats.column(ColumnId::new(1)).unwrap().values,
+            Some(vec!["a".to_string(), "b".to_string(), "c".to_string()])
+        );
+        // The values of column 2 in the second file are unknown.
+        assert_eq!(stats.column(ColumnId::new(2)).unwrap().values, None);
+
+        // Too many distinct values are not recorded.
+        let many = |offset: usize| {
+            let values = (offset..offset + MAX_PARTITION_TAG_VALUES / 2 + 1)
+                .map(|i| format!("{i:05}"))
+                .collect::<Vec<_>>();
+            tag_values(1, &values.iter().map(String::as_str).collect::<Vec<_>>())
+        };
+        let stats = PartitionStatistics::apply(
+            partition_id,
+            None,
+            totals(1, 1, 1),
+            PartitionColumnStatisticsUpdate::Merge {
+                row_count: 1,
+                columns: &[many(0)],
+            },
+        );
+        assert!(stats.columns[0].values.is_some());
+        let stats = PartitionStatistics::apply(
+            partition_id,
+            Some(stats),
+            totals(2, 2, 2),
+            PartitionColumnStatisticsUpdate::Merge {
+                row_count: 1,
+                columns: &[many(MAX_PARTITION_TAG_VALUES)],
+            },
+        );
+        assert_eq!(stats.columns[0].values, None);
+    }
+
+    #[test]
+    fn test_add_file() {
+        let partition_id = PartitionId::new(1);
+        let file = |row_count, min_time, max_time, id| PartitionFileTotals {
+            file_count: 1,
+            row_count,
+            min_time: Some(Timestamp::new(min_time)),
+            max_time: Some(Timestamp::new(max_time)),
+            max_parquet_file_id: Some(ParquetFileId::new(id)),
+        };
+
+        let stats = PartitionStatistics::add_file(
+            partition_id,
+            None,
+            file(10, 5, 20, 1),
+            PartitionColumnStatisticsUpdate::Merge {
+                row_count: 10,
+                columns: &[tag(1, 0, "x", "y")],
+            },
+        );
+        assert!(stats.complete);
+        assert_eq!(stats.row_count, 10);
+
+        // Adding a file gives the same result as recomputing the totals.
+        let update = PartitionColumnStatisticsUpdate::Merge {
+            row_count: 5,
+            columns: &[tag(2, 1, "a", "b")],
+        };
+        let added = PartitionStatistics::add_file(
+            partition_id,
+            Some(stats.clone()),
+            file(5, 1, 7, 2),
+            update,
+        );
+        let applied = PartitionStatistics::apply(
+            partition_id,
+            Some(stats),
+            PartitionFileTotals {
+                file_count: 2,
+                row_count: 15,
+                min_time: Some(Timestamp::new(1)),
+                max_time: Some(Timestamp::new(20)),
+                max_parquet_file_id: Some(ParquetFileId::new(2)),
+            },
+            update,
+        );
+        assert_eq!(added, applied);
+        assert_eq!(added.columns, [tag(1, 5, "x", "y"), tag(2, 11, "a", "b")]);
+
+        // A file of unknown content leaves the column statistics incomplete.
+        let stats = PartitionStatistics::add_file(
+            partition_id,
+            Some(added),
+            file(3, 30, 40, 3),
+            PartitionColumnStatisticsUpdate::Unknown,
+        );
+        assert!(!stats.complete);
+        assert_eq!(stats.row_count, 18);
+        assert_eq!(stats.min_time, Some(Timestamp::new(1)));
+        assert_eq!(stats.max_time, Some(Timestamp::new(40)));
+        assert_eq!(stats.max_parquet_file_id, Some(ParquetFileId::new(3)));
+    }
+
+    #[test]
+    fn test_incomplete() {
+        let partition_id = PartitionId::new(1);
+
+        // Statistics first recorded for a partition that already has files
+        // are incomplete.
+        let stats = PartitionStatistics::apply(
+            partition_id,
+            None,
+            totals(2, 10, 2),
+            PartitionColumnStatisticsUpdate::Merge {
+                row_count: 5,
+                columns: &[tag(1, 0, "x", "y")],
+            },
+        );
+        assert!(!stats.complete);
+
+        // As are statistics after a file of unknown content is added.
+        let stats = PartitionStatistics::apply(
+            partition_id,
+            None,
+            totals(0, 0, 1),
+            PartitionColumnStatisticsUpdate::Retain,
+        );
+        assert!(stats.complete);
+        let stats = PartitionStatistics::apply(
+            partition_id,
+            Some(stats),
+            totals(1, 3, 2),
+            PartitionColumnStatisticsUpdate::Unknown,
+        );
+        assert!(!stats.complete);
+
+        // Until all files are removed.
+        let stats = PartitionStatistics::apply(
+            partition_id,
+            Some(stats),
+            PartitionFileTotals::default(),
+            PartitionColumnStatisticsUpdate::Retain,
+        );
+        assert!(stats.complete);
+        assert!(stats.columns.is_empty());
+        assert_eq!(stats.min_time, None);
+    }
+}
@@ -229,7 +229,8 @@ mod tests {
     use async_trait::async_trait;
     use data_types::{
         ColumnId, ColumnSet, CompactionLevel, NamespaceId, ParquetFile, ParquetFileId,
-        ParquetFileParams, PartitionId, TableId, Timestamp, TransitionPartitionId,
+        ParquetFileParams, PartitionColumnStatistics, PartitionId, TableId, Timestamp,
+        TransitionPartitionId,
     };
     use iox_catalog::{
         interface::Catalog,
@@ -513,6 +514,16 @@ mod tests {
             self.inner.create(parquet_file_params).await
         }
 
+        async fn create_with_statistics(
+            &mut self,
+            parquet_file_params: ParquetFileParams,
+            column_statistics: &[PartitionColumnStatistics],
+        ) -> iox_catalog::interface::Result<ParquetFile> {
+            self.inner
+                .create_with_statistics(parquet_file_params, column_statistics)
+                .await
+        }
+
         async fn list_all(&mut self) -> iox_catalog::interface::Result<Vec<ParquetFile>> {
             self.inner.list_all().await
         }
@@ -20,6 +20,7 @@ fn main() -> Result<()> {
 ///
 /// - `influxdata.iox.authz.v1.rs`
 /// - `influxdata.iox.catalog.v1.rs`
+/// - `influxdata.iox.cluster.v1.rs`
 /// - `influxdata.iox.compactor.v1.rs`
 /// - `influxdata.iox.delete.v1.rs`
 /// - `influxdata.iox.ingester.v1.rs`
@@ -32,9 +33,15 @@ fn main() -> Result<()> {
 /// - `influxdata.iox.wal.v1.rs`
 /// - `influxdata.iox.write.v1.rs`
 /// - `influxdata.platform.storage.rs`
+/// - `opentelemetry.proto.collector.metrics.v1.rs`
+/// - `opentelemetry.proto.common.v1.rs`
+/// - `opentelemetry.proto.metrics.v1.rs`
+/// - `opentelemetry.proto.resource.v1.rs`
+/// - `prometheus.rs`
 fn generate_grpc_types(root: &Path) -> Result<()> {
     let authz_path = root.join("influxdata/iox/authz/v1");
     let catalog_path = root.join("influxdata/iox/catalog/v1");
+    let cluster_path = root.join("influxdata/iox/cluster/v1");
     let compactor_path = root.join("influxdata/iox/compactor/v1");
     let delete_path = root.join("influxdata/iox/delete/v1");
     let gossip_path = root.join("influxdata/iox/gossip/v1");
@@ -49,15 +56,19 @@ fn generate_grpc_types(root: &Path) -> Result<()> {
     let storage_path = root.join("influxdata/platform/storage");
     let table_path = root.join("influxdata/iox/table/v1");
     let wal_path = root.join("influxdata/iox/wal/v1");
+    let otel_path = root.join("opentelemetry/proto");
+    let prometheus_path = root.join("prometheus");
 
     let proto_files = vec![
         authz_path.join("authz.proto"),
         catalog_path.join("parquet_file.proto"),
         catalog_path.join("partition_identifier.proto"),
         catalog_path.join("service.proto"),
+        cluster_path.join("service.proto"),
         compactor_path.join("service.proto"),
         delete_path.join("service.proto"),
         gossip_path.join("compaction.proto"),
+        gossip_path.join("membership.proto"),
         gossip_path.join("parquet_file.proto"),
         gossip_path.join("schema.proto"),
         gossip_path.join("schema_sync.proto"),
@@ -68,6 +79,7 @@ fn generate_grpc_types(root: &Path) -> Result<()> {
         object_store_path.join("service.proto"),
         partition_template_path.join("template.proto"),
         predicate_path.join("predicate.proto"),
+        querier_path.join("cache.proto"),
         querier_path.join("flight.proto"),
         root.join("google/longrunning/operations.proto"),
         root.join("google/rpc/error_details.proto"),
@@ -83,6 +95,12 @@ fn generate_grpc_types(root: &Path) -> Result<()> {
         storage_path.join("test.proto"),
         table_path.join("service.proto"),
         wal_path.join("wal.proto"),
+        otel_path.join("collector/metrics/v1/metrics_service.proto"),
+        otel_path.join("common/v1/common.proto"),
+        otel_path.join("metrics/v1/metrics.proto"),
+        otel_path.join("resource/v1/resource.proto"),
+        prometheus_path.join("remote.proto"),
+        prometheus_path.join("types.proto"),
     ];
 
     // Tell cargo to recompile if any of these proto files are changed
@@ -94,7 +112,7 @@ fn generate_grpc_types(root: &Path) -> Result<()> {
 
     config
         .compile_well_known_types()
-        .disable_comments([".google"])
+        .disable_comments([".google", ".opentelemetry", ".prometheus"])
         .extern_path(".google.protobuf", "::pbjson_types")
         .btree_map([
             ".influxdata.iox.ingester.v1.IngesterQueryResponseMetadata.unpersisted_partitions",
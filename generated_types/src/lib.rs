@@ -72,6 +72,16 @@ pub mod influxdata {
             }
         }
 
+        pub mod cluster {
+            pub mod v1 {
+                include!(concat!(env!("OUT_DIR"), "/influxdata.iox.cluster.v1.rs"));
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/influxdata.iox.cluster.v1.serde.rs"
+                ));
+            }
+        }
+
         pub mod compactor {
             pub mod v1 {
                 include!(concat!(env!("OUT_DIR"), "/influxdata.iox.compactor.v1.rs"));
@@ -116,6 +126,9 @@ pub mod influxdata {
                 /// Schema cache consistency check / sync / convergence
                 /// messages.
                 SchemaCacheConsistency = 4,
+
+                /// Periodic cluster membership announcements.
+                ClusterMembership = 5,
             }
 
             impl TryFrom<u64> for Topic {
@@ -129,6 +142,7 @@ pub mod influxdata {
                         v if v == Self::SchemaCacheConsistency as u64 => {
                             Self::SchemaCacheConsistency
                         }
+                        v if v == Self::ClusterMembership as u64 => Self::ClusterMembership,
                         _ => return Err(format!("unknown topic id {}", v).into()),
                     })
                 }
@@ -243,6 +257,55 @@ pub mod influxdata {
     }
 }
 
+/// Types of the OpenTelemetry protocol (OTLP), used to accept metrics pushed
+/// by OpenTelemetry SDKs and collectors.
+pub mod opentelemetry {
+    pub mod proto {
+        pub mod collector {
+            pub mod metrics {
+                pub mod v1 {
+                    include!(concat!(
+                        env!("OUT_DIR"),
+                        "/opentelemetry.proto.collector.metrics.v1.rs"
+                    ));
+                }
+            }
+        }
+
+        pub mod common {
+            pub mod v1 {
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/opentelemetry.proto.common.v1.rs"
+                ));
+            }
+        }
+
+        pub mod metrics {
+            pub mod v1 {
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/opentelemetry.proto.metrics.v1.rs"
+                ));
+            }
+        }
+
+        pub mod resource {
+            pub mod v1 {
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/opentelemetry.proto.resource.v1.rs"
+                ));
+            }
+        }
+    }
+}
+
+/// Types of the Prometheus remote write protocol.
+pub mod prometheus {
+    include!(concat!(env!("OUT_DIR"), "/prometheus.rs"));
+}
+
 // Needed because of https://github.com/hyperium/tonic/issues/471
 pub mod grpc {
     pub mod health {
@@ -330,6 +393,7 @@ mod tests {
             Topic::NewParquetFiles,
             Topic::CompactionEvents,
             Topic::SchemaCacheConsistency,
+            Topic::ClusterMembership,
         ];
 
         for topic in topics {
@@ -346,6 +410,7 @@ mod tests {
             Topic::NewParquetFiles => {}
             Topic::CompactionEvents => {}
             Topic::SchemaCacheConsistency => {}
+            Topic::ClusterMembership => {}
         }
     }
 }
@@ -8,6 +8,7 @@ use tokio::{
 use crate::{
     handle::GossipHandle,
     reactor::Reactor,
+    security::{FrameCodec, FrameProtection, SharedKey},
     topic_set::{Topic, TopicSet},
     Dispatcher,
 };
@@ -19,6 +20,7 @@ pub struct Builder<T, S = u64> {
     dispatcher: T,
     metric: Arc<metric::Registry>,
     topic_set: TopicSet,
+    codec: FrameCodec,
     _topic_type: PhantomData<S>,
 }
 
@@ -34,6 +36,7 @@ impl<T, S> Builder<T, S> {
             dispatcher,
             metric,
             topic_set: TopicSet::default(),
+            codec: FrameCodec::default(),
             _topic_type: PhantomData,
         }
     }
@@ -56,9 +59,29 @@ impl<T, S> Builder<T, S> {
             dispatcher: self.dispatcher,
             metric: self.metric,
             topic_set: topics.0,
+            codec: self.codec,
             _topic_type: PhantomData,
         }
     }
+
+    /// Authenticate all frames using the provided shared `keys`, optionally
+    /// encrypting them as specified by `protection`.
+    ///
+    /// Outgoing frames are sealed with the first key in `keys`, and incoming
+    /// frames sealed with any key in `keys` are accepted - frames without a
+    /// valid MAC from a known key are dropped. All peers must be configured
+    /// with at least one common key to communicate.
+    ///
+    /// To rotate keys without downtime, first add the new key to the end of
+    /// `keys` on all nodes, then move it to the front, and finally remove
+    /// the old key.
+    ///
+    /// If `keys` is empty, frames are sent and accepted unauthenticated (the
+    /// default).
+    pub fn with_shared_keys(mut self, keys: Vec<SharedKey>, protection: FrameProtection) -> Self {
+        self.codec = FrameCodec::new(keys, protection);
+        self
+    }
 }
 
 impl<T, S, E> Builder<T, S>
@@ -85,6 +108,7 @@ where
             self.dispatcher,
             &self.metric,
             self.topic_set,
+            self.codec,
         );
         let identity = reactor.identity().clone();
 
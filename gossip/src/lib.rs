@@ -21,6 +21,7 @@ mod metric;
 mod peers;
 mod proto;
 mod reactor;
+mod security;
 pub(crate) mod seed;
 mod topic_set;
 
@@ -35,6 +36,7 @@ pub use builder::*;
 pub use dispatcher::*;
 pub use handle::*;
 pub use peers::Identity;
+pub use security::{FrameProtection, SharedKey, MAX_FRAME_AGE};
 
 /// The maximum duration of time allotted to performing a DNS resolution against
 /// a seed/peer address.
@@ -5,6 +5,8 @@
 
 use metric::U64Counter;
 
+use crate::security::Rejection;
+
 #[derive(Debug, Clone)]
 pub(crate) struct SentFrames(metric::U64Counter);
 
@@ -58,3 +60,44 @@ pub(crate) fn new_metrics(
         ReceivedBytes(metric_bytes.recorder(&[("direction", "received")])),
     )
 }
+
+/// The number of received frames rejected by the frame codec, broken down by
+/// the rejection reason.
+#[derive(Debug)]
+pub(crate) struct RejectedFrames {
+    unauthenticated: metric::U64Counter,
+    unknown_key: metric::U64Counter,
+    invalid: metric::U64Counter,
+    expired: metric::U64Counter,
+    replayed: metric::U64Counter,
+}
+
+impl RejectedFrames {
+    pub(crate) fn new(metrics: &metric::Registry) -> Self {
+        let metric = metrics.register_metric::<U64Counter>(
+            "gossip_frames_rejected",
+            "number of received frames rejected due to failed authentication or replay protection",
+        );
+
+        let recorder = |r: Rejection| metric.recorder(&[("reason", r.as_str())]);
+
+        Self {
+            unauthenticated: recorder(Rejection::Unauthenticated),
+            unknown_key: recorder(Rejection::UnknownKey),
+            invalid: recorder(Rejection::Invalid),
+            expired: recorder(Rejection::Expired),
+            replayed: recorder(Rejection::Replayed),
+        }
+    }
+
+    pub(crate) fn inc(&self, reason: Rejection) {
+        match reason {
+            Rejection::Unauthenticated => &self.unauthenticated,
+            Rejection::UnknownKey => &self.unknown_key,
+            Rejection::Invalid => &self.invalid,
+            Rejection::Expired => &self.expired,
+            Rejection::Replayed => &self.replayed,
+        }
+        .inc(1)
+    }
+}
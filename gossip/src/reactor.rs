@@ -12,6 +12,7 @@ use crate::{
     metric::*,
     peers::{Identity, PeerList},
     proto::{self, frame_message::Payload, FrameMessage, Ping},
+    security::{FrameCodec, Rejection, MAX_SEAL_OVERHEAD},
     seed::{seed_ping_task, Seed},
     topic_set::{Topic, TopicSet},
     Dispatcher, Request, MAX_FRAME_BYTES, PEER_PING_INTERVAL,
@@ -33,6 +34,11 @@ enum Error {
         addr: SocketAddr,
     },
 
+    Rejected {
+        addr: SocketAddr,
+        reason: Rejection,
+    },
+
     Io(std::io::Error),
 
     MaxSize(usize),
@@ -74,6 +80,8 @@ pub(crate) struct Reactor<T, S> {
     cached_ping_frame: Arc<[u8]>,
     /// A re-used buffer for serialising outgoing messages into.
     serialisation_buf: Vec<u8>,
+    /// The codec used to seal outgoing, and open incoming frames.
+    codec: FrameCodec,
 
     /// The immutable list of seed addresses provided by the user, periodically
     /// pinged.
@@ -100,6 +108,9 @@ pub(crate) struct Reactor<T, S> {
     metric_bytes_sent: SentBytes,
     metric_bytes_received: ReceivedBytes,
 
+    /// The count of frames that failed authentication.
+    metric_frames_rejected: RejectedFrames,
+
     _topic_type: PhantomData<S>,
 }
 
@@ -115,6 +126,7 @@ where
         dispatch: T,
         metrics: &metric::Registry,
         topics: TopicSet,
+        codec: FrameCodec,
     ) -> Self {
         // Generate a unique UUID for this Reactor instance, and cache the wire
         // representation.
@@ -132,12 +144,16 @@ where
 
         // A ping frame is static over the lifetime of a Reactor instance, so it
         // can be pre-serialised, cached, and reused for every ping.
+        //
+        // When encrypting, this reuses the same nonce for every ping, which is
+        // safe only because the plaintext is also identical for every ping.
         let cached_ping_frame = {
             populate_frame(
                 &mut cached_frame,
                 vec![new_payload(Payload::Ping(proto::Ping {
                     interests: u64::from(topics),
                 }))],
+                &codec,
                 &mut serialisation_buf,
             )
             .unwrap();
@@ -148,6 +164,7 @@ where
         // between the (very similar) counters.
         let (metric_frames_sent, metric_frames_received, metric_bytes_sent, metric_bytes_received) =
             new_metrics(metrics);
+        let metric_frames_rejected = RejectedFrames::new(metrics);
 
         // Spawn a task that periodically pings all known seeds.
         //
@@ -168,6 +185,7 @@ where
             identity,
             cached_frame,
             cached_ping_frame,
+            codec,
             serialisation_buf,
             peer_list: PeerList::with_capacity(seed_list.len(), metrics),
             seed_list,
@@ -177,6 +195,7 @@ where
             metric_frames_received,
             metric_bytes_sent,
             metric_bytes_received,
+            metric_frames_rejected,
             _topic_type: PhantomData,
         }
     }
@@ -215,6 +234,10 @@ where
                             warn!(%addr, "invalid identity value in frame");
                             continue;
                         }
+                        Err(Error::Rejected { addr, reason }) => {
+                            warn!(%addr, %reason, "rejected frame");
+                            continue;
+                        }
                         Err(Error::Io(error)) => {
                             error!(%error, "i/o error");
                             continue;
@@ -249,6 +272,7 @@ where
                                     payload,
                                     topic: topic.into(),
                                 }))],
+                                &self.codec,
                                 &mut self.serialisation_buf
                             ).expect("size validated in handle at enqueue time");
 
@@ -286,7 +310,13 @@ where
     /// Returns the bytes read and bytes sent during execution of this method.
     async fn read(&mut self) -> Result<(), Error> {
         // Read a frame into buf.
-        let (bytes_read, frame, peer_addr) = read_frame(&self.socket).await?;
+        let (bytes_read, frame, peer_addr) = match read_frame(&self.socket, &self.codec).await {
+            Err(e @ Error::Rejected { reason, .. }) => {
+                self.metric_frames_rejected.inc(reason);
+                return Err(e);
+            }
+            v => v?,
+        };
         self.metric_frames_received.inc(1);
         self.metric_bytes_received.inc(bytes_read as _);
 
@@ -405,6 +435,7 @@ where
         populate_frame(
             &mut self.cached_frame,
             out_messages,
+            &self.codec,
             &mut self.serialisation_buf,
         )?;
 
@@ -492,21 +523,29 @@ async fn recv(socket: &UdpSocket, buf: &mut BytesMut) -> (usize, SocketAddr) {
     (n_bytes, addr)
 }
 
-/// Wait for a UDP datagram to arrive, and decode it into a gossip Frame.
-///
-/// Clears the contents of `buf` before reading the frame.
-async fn read_frame(socket: &UdpSocket) -> Result<(usize, proto::Frame, SocketAddr), Error> {
-    // Pre-allocate a buffer large enough to hold the maximum message size.
+/// Wait for a UDP datagram to arrive, validate it using `codec` and decode it
+/// into a gossip Frame.
+async fn read_frame(
+    socket: &UdpSocket,
+    codec: &FrameCodec,
+) -> Result<(usize, proto::Frame, SocketAddr), Error> {
+    // Pre-allocate a buffer large enough to hold the maximum message size,
+    // including any authentication / encryption envelope.
     //
     // Reading data from a UDP socket silently truncates if there's not enough
     // buffer space to write the full packet payload (tokio doesn't support
     // MSG_TRUNC-like flags on reads).
-    let mut buf = BytesMut::with_capacity(MAX_FRAME_BYTES);
+    let mut buf = BytesMut::with_capacity(MAX_FRAME_BYTES + MAX_SEAL_OVERHEAD);
 
     let (n_bytes, addr) = recv(socket, &mut buf).await;
 
+    // Strip the envelope, if any, rejecting frames that fail validation.
+    let buf = codec
+        .decode(buf)
+        .map_err(|reason| Error::Rejected { addr, reason })?;
+
     // Decode the frame, re-using byte arrays from the underlying buffer.
-    match proto::Frame::decode(buf.freeze()) {
+    match proto::Frame::decode(buf) {
         Ok(frame) => {
             debug!(?frame, %addr, n_bytes, "read frame");
             Ok((n_bytes, frame, addr))
@@ -516,18 +555,21 @@ async fn read_frame(socket: &UdpSocket) -> Result<(usize, proto::Frame, SocketAd
 }
 
 /// Given a pre-allocated `frame`, clear and populate it with the provided
-/// `payload` containing a set of [`FrameMessage`], serialising it to `buf`.
+/// `payload` containing a set of [`FrameMessage`], serialising it to `buf`
+/// using `codec`.
 fn populate_frame(
     frame: &mut proto::Frame,
     payload: Vec<FrameMessage>,
+    codec: &FrameCodec,
     buf: &mut Vec<u8>,
 ) -> Result<(), Error> {
     frame.messages = payload;
 
     // Reading data from a UDP socket silently truncates if there's not enough
     // buffer space to write the full packet payload. This library will
-    // pre-allocate a buffer of this size to read packets into, therefore all
-    // messages must be shorter than this value.
+    // pre-allocate a buffer of this size (plus the fixed envelope overhead) to
+    // read packets into, therefore all messages must be shorter than this
+    // value.
     if frame.encoded_len() > MAX_FRAME_BYTES {
         error!(
             n_bytes=frame.encoded_len(),
@@ -537,10 +579,13 @@ fn populate_frame(
         return Err(Error::MaxSize(frame.encoded_len()));
     }
 
-    buf.clear();
-    frame.encode(buf).expect("buffer should grow");
+    codec.encode(frame, buf);
 
-    debug_assert!(proto::Frame::decode(crate::Bytes::from(buf.clone())).is_ok());
+    debug_assert!(codec
+        .verify(BytesMut::from(&buf[..]))
+        .ok()
+        .and_then(|v| proto::Frame::decode(v).ok())
+        .is_some());
 
     Ok(())
 }
@@ -595,6 +640,7 @@ mod tests {
                 payload: crate::Bytes::new(), // Empty/0-sized
                 topic: 1 << 63,
             }))],
+            &FrameCodec::Plaintext,
             &mut buf,
         )
         .unwrap();
@@ -0,0 +1,647 @@
+//! Shared-key authentication & encryption of gossip frames.
+//!
+//! When configured with one or more [`SharedKey`], every serialised frame is
+//! wrapped in an envelope before being sent:
+//!
+//! ```text
+//!   Authenticate: [ 0xA1 | key ID (4) | timestamp (8) | nonce (12) | frame | HMAC-SHA256 (32) ]
+//!   Encrypt:      [ 0xA2 | key ID (4) | timestamp (8) | nonce (12) | ChaCha20-Poly1305(frame) + tag (16) ]
+//! ```
+//!
+//! The header bytes are covered by the HMAC / used as the AEAD associated data.
+//!
+//! The timestamp is the sender's wall clock time in milliseconds since the
+//! UNIX epoch, and the nonce is random for every frame. Frames with a
+//! timestamp more than [`MAX_FRAME_AGE`] away from the local clock are
+//! rejected, and the nonces of accepted frames are remembered until they fall
+//! outside of this window, rejecting any replay of a previously accepted
+//! frame.
+//!
+//! The first key is used to seal outgoing frames, and all keys are accepted
+//! when opening incoming frames, allowing keys to be rotated without downtime.
+//! Frames sealed in either mode are accepted regardless of the local
+//! [`FrameProtection`] - both require knowledge of a shared key.
+
+use std::{
+    fmt::Display,
+    ops::Range,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use hashbrown::HashMap;
+use prost::{bytes::BytesMut, Message};
+use rand::RngCore;
+use ring::{aead, hmac};
+
+use crate::{proto, Bytes};
+
+/// Envelope marker for HMAC authenticated frames.
+const MODE_AUTHENTICATE: u8 = 0xA1;
+/// Envelope marker for AEAD encrypted frames.
+const MODE_ENCRYPT: u8 = 0xA2;
+
+/// The length of the truncated key identifier in the envelope header.
+const KEY_ID_LEN: usize = 4;
+/// The length of the envelope timestamp.
+const TIMESTAMP_LEN: usize = 8;
+/// The length of the per-frame nonce.
+const NONCE_LEN: usize = aead::NONCE_LEN;
+/// The offset of the timestamp in the envelope header.
+const TIMESTAMP_OFFSET: usize = 1 + KEY_ID_LEN;
+/// The offset of the nonce in the envelope header.
+const NONCE_OFFSET: usize = TIMESTAMP_OFFSET + TIMESTAMP_LEN;
+/// The length of the common envelope header (mode marker, key ID, timestamp &
+/// nonce).
+const HEADER_LEN: usize = NONCE_OFFSET + NONCE_LEN;
+/// The length of a HMAC-SHA256 tag.
+const MAC_LEN: usize = 32;
+/// The length of the AEAD authentication tag.
+const AEAD_TAG_LEN: usize = 16;
+
+/// The maximum difference between the timestamp of a received frame and the
+/// local clock for the frame to be accepted.
+///
+/// This bounds both the tolerated clock skew between peers, and how long the
+/// nonces of accepted frames must be remembered to detect replays.
+pub const MAX_FRAME_AGE: Duration = Duration::from_secs(30);
+
+/// The maximum number of bytes an envelope adds to a serialised frame.
+pub(crate) const MAX_SEAL_OVERHEAD: usize = {
+    let mac = HEADER_LEN + MAC_LEN;
+    let aead = HEADER_LEN + AEAD_TAG_LEN;
+    if mac > aead {
+        mac
+    } else {
+        aead
+    }
+};
+
+/// The protection applied to frames sent by the local node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameProtection {
+    /// Append a HMAC to every frame, rejecting frames that were not sent by a
+    /// peer holding a shared key.
+    Authenticate,
+
+    /// Encrypt and authenticate every frame, additionally preventing
+    /// observers from reading the frame content.
+    Encrypt,
+}
+
+/// A secret shared between all gossip peers.
+///
+/// The secret is never used directly - independent MAC and encryption keys are
+/// derived from it, alongside a short, non-secret key ID that allows the
+/// receiver to select the right key during rotation.
+///
+/// The [`Debug`] output of a key only contains the key ID.
+#[derive(Clone)]
+pub struct SharedKey(Arc<KeyMaterial>);
+
+struct KeyMaterial {
+    id: [u8; KEY_ID_LEN],
+    mac: hmac::Key,
+    aead: aead::LessSafeKey,
+}
+
+impl SharedKey {
+    /// Derive a new key from the provided `secret`.
+    ///
+    /// The secret should contain at least 128 bits of entropy.
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        let prk = hmac::Key::new(hmac::HMAC_SHA256, secret.as_ref());
+        let derive = |label: &[u8]| hmac::sign(&prk, label);
+
+        let mut id = [0; KEY_ID_LEN];
+        id.copy_from_slice(&derive(b"iox-gossip-v1-key-id").as_ref()[..KEY_ID_LEN]);
+
+        let mac = hmac::Key::new(hmac::HMAC_SHA256, derive(b"iox-gossip-v1-mac").as_ref());
+
+        let aead = aead::LessSafeKey::new(
+            aead::UnboundKey::new(
+                &aead::CHACHA20_POLY1305,
+                derive(b"iox-gossip-v1-aead").as_ref(),
+            )
+            .expect("HMAC-SHA256 output is a valid ChaCha20-Poly1305 key"),
+        );
+
+        Self(Arc::new(KeyMaterial { id, mac, aead }))
+    }
+
+    fn id(&self) -> &[u8; KEY_ID_LEN] {
+        &self.0.id
+    }
+}
+
+impl std::fmt::Debug for SharedKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SharedKey").field(&KeyId(self.id())).finish()
+    }
+}
+
+struct KeyId<'a>(&'a [u8; KEY_ID_LEN]);
+
+impl std::fmt::Debug for KeyId<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for b in self.0 {
+            write!(f, "{b:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The reason an incoming frame was rejected by a [`FrameCodec`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Rejection {
+    /// The frame is not wrapped in an envelope.
+    Unauthenticated,
+    /// The frame was sealed with a key unknown to the local node.
+    UnknownKey,
+    /// The envelope is truncated, or the MAC / AEAD tag is invalid.
+    Invalid,
+    /// The envelope timestamp is outside of the [`MAX_FRAME_AGE`] window.
+    Expired,
+    /// The frame was already accepted once.
+    Replayed,
+}
+
+impl Rejection {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Unauthenticated => "unauthenticated",
+            Self::UnknownKey => "unknown_key",
+            Self::Invalid => "invalid",
+            Self::Expired => "expired",
+            Self::Replayed => "replayed",
+        }
+    }
+}
+
+impl Display for Rejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Serialises frames to, and extracts frames from, the bytes on the wire.
+#[derive(Debug, Clone, Default)]
+pub(crate) enum FrameCodec {
+    /// Frames are sent and accepted as-is.
+    #[default]
+    Plaintext,
+
+    /// Frames are wrapped in an authenticated (and optionally encrypted)
+    /// envelope, and frames without a valid envelope are rejected.
+    Sealed {
+        /// The accepted keys - the first key seals outgoing frames.
+        keys: Arc<[SharedKey]>,
+        protection: FrameProtection,
+        /// The nonces of accepted frames, shared between clones of the codec.
+        seen: Arc<SeenNonces>,
+    },
+}
+
+impl FrameCodec {
+    /// Construct a codec using `keys`, or a plaintext codec if `keys` is empty.
+    pub(crate) fn new(keys: Vec<SharedKey>, protection: FrameProtection) -> Self {
+        if keys.is_empty() {
+            return Self::Plaintext;
+        }
+        Self::Sealed {
+            keys: keys.into(),
+            protection,
+            seen: Default::default(),
+        }
+    }
+
+    /// Clear `buf` and serialise `frame` into it.
+    pub(crate) fn encode(&self, frame: &proto::Frame, buf: &mut Vec<u8>) {
+        self.encode_at(frame, buf, unix_millis())
+    }
+
+    fn encode_at(&self, frame: &proto::Frame, buf: &mut Vec<u8>, now: u64) {
+        buf.clear();
+
+        let (keys, protection) = match self {
+            Self::Plaintext => {
+                frame.encode(buf).expect("buffer should grow");
+                return;
+            }
+            Self::Sealed {
+                keys, protection, ..
+            } => (keys, protection),
+        };
+        let key = &keys[0];
+
+        // Random nonces are safe for the number of frames a single key is
+        // expected to seal over its lifetime.
+        let mut nonce = [0; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        buf.push(match protection {
+            FrameProtection::Authenticate => MODE_AUTHENTICATE,
+            FrameProtection::Encrypt => MODE_ENCRYPT,
+        });
+        buf.extend_from_slice(key.id());
+        buf.extend_from_slice(&now.to_be_bytes());
+        buf.extend_from_slice(&nonce);
+        debug_assert_eq!(buf.len(), HEADER_LEN);
+
+        frame.encode(buf).expect("buffer should grow");
+
+        match protection {
+            FrameProtection::Authenticate => {
+                let tag = hmac::sign(&key.0.mac, buf);
+                buf.extend_from_slice(tag.as_ref());
+            }
+            FrameProtection::Encrypt => {
+                let (header, payload) = buf.split_at_mut(HEADER_LEN);
+                let tag = key
+                    .0
+                    .aead
+                    .seal_in_place_separate_tag(
+                        aead::Nonce::assume_unique_for_key(nonce),
+                        aead::Aad::from(&*header),
+                        payload,
+                    )
+                    .expect("frame within AEAD size limits");
+                buf.extend_from_slice(tag.as_ref());
+            }
+        }
+    }
+
+    /// Validate and strip the envelope (if any) of the datagram in `buf`,
+    /// returning the serialised frame.
+    ///
+    /// A sealed frame is accepted at most once.
+    pub(crate) fn decode(&self, buf: BytesMut) -> Result<Bytes, Rejection> {
+        self.decode_at(buf, unix_millis(), true)
+    }
+
+    /// Validate and strip the envelope (if any) of the datagram in `buf`
+    /// like [`Self::decode()`], without recording the frame as accepted.
+    pub(crate) fn verify(&self, buf: BytesMut) -> Result<Bytes, Rejection> {
+        self.decode_at(buf, unix_millis(), false)
+    }
+
+    fn decode_at(&self, mut buf: BytesMut, now: u64, record: bool) -> Result<Bytes, Rejection> {
+        let range = match self {
+            Self::Plaintext => 0..buf.len(),
+            Self::Sealed { keys, seen, .. } => {
+                let opened = open(keys, &mut buf)?;
+
+                if now.abs_diff(opened.timestamp) > MAX_FRAME_AGE.as_millis() as u64 {
+                    return Err(Rejection::Expired);
+                }
+                if record && !seen.insert(opened.nonce, opened.timestamp, now) {
+                    return Err(Rejection::Replayed);
+                }
+
+                opened.frame
+            }
+        };
+
+        Ok(buf.freeze().slice(range))
+    }
+}
+
+/// The nonces of accepted frames that are still within the [`MAX_FRAME_AGE`]
+/// window, mapped to the time after which they can be forgotten.
+#[derive(Debug, Default)]
+pub(crate) struct SeenNonces(Mutex<SeenState>);
+
+#[derive(Debug, Default)]
+struct SeenState {
+    nonces: HashMap<[u8; NONCE_LEN], u64>,
+    next_prune: u64,
+}
+
+impl SeenNonces {
+    /// Record `nonce` of a frame sealed at `timestamp`, returning false if it
+    /// was already recorded.
+    fn insert(&self, nonce: [u8; NONCE_LEN], timestamp: u64, now: u64) -> bool {
+        let window = MAX_FRAME_AGE.as_millis() as u64;
+        let mut state = self.0.lock().expect("seen nonces lock poisoned");
+
+        // Frames sealed more than a window ago are rejected as expired, so
+        // their nonces no longer need to be remembered.
+        if now >= state.next_prune {
+            state.nonces.retain(|_, expires| *expires >= now);
+            state.next_prune = now.saturating_add(window);
+        }
+
+        state
+            .nonces
+            .insert(nonce, timestamp.saturating_add(window))
+            .is_none()
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.0.lock().unwrap().nonces.len()
+    }
+}
+
+/// The wall clock time in milliseconds since the UNIX epoch.
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|v| v.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// A validated envelope.
+#[derive(Debug)]
+struct Opened {
+    /// The range of the buffer containing the serialised frame.
+    frame: Range<usize>,
+    /// The sender's timestamp in milliseconds since the UNIX epoch.
+    timestamp: u64,
+    nonce: [u8; NONCE_LEN],
+}
+
+/// Validate the envelope in `buf`, decrypting the frame in place if necessary,
+/// and return the range of `buf` containing the serialised frame alongside
+/// the authenticated header fields.
+fn open(keys: &[SharedKey], buf: &mut [u8]) -> Result<Opened, Rejection> {
+    let mode = match buf.first() {
+        Some(&v @ (MODE_AUTHENTICATE | MODE_ENCRYPT)) => v,
+        _ => return Err(Rejection::Unauthenticated),
+    };
+    if buf.len() < HEADER_LEN {
+        return Err(Rejection::Invalid);
+    }
+
+    let key = keys
+        .iter()
+        .find(|k| k.id()[..] == buf[1..TIMESTAMP_OFFSET])
+        .ok_or(Rejection::UnknownKey)?;
+
+    let timestamp = u64::from_be_bytes(
+        buf[TIMESTAMP_OFFSET..NONCE_OFFSET]
+            .try_into()
+            .expect("timestamp slice has correct length"),
+    );
+    let nonce: [u8; NONCE_LEN] = buf[NONCE_OFFSET..HEADER_LEN]
+        .try_into()
+        .expect("nonce slice has correct length");
+
+    let frame = match mode {
+        MODE_AUTHENTICATE => {
+            let tag_start = buf
+                .len()
+                .checked_sub(MAC_LEN)
+                .filter(|&v| v >= HEADER_LEN)
+                .ok_or(Rejection::Invalid)?;
+
+            let (data, tag) = buf.split_at(tag_start);
+            hmac::verify(&key.0.mac, data, tag).map_err(|_| Rejection::Invalid)?;
+
+            HEADER_LEN..tag_start
+        }
+        MODE_ENCRYPT => {
+            if buf.len() < HEADER_LEN + AEAD_TAG_LEN {
+                return Err(Rejection::Invalid);
+            }
+
+            let (header, payload) = buf.split_at_mut(HEADER_LEN);
+            let plaintext = key
+                .0
+                .aead
+                .open_in_place(
+                    aead::Nonce::assume_unique_for_key(nonce),
+                    aead::Aad::from(&*header),
+                    payload,
+                )
+                .map_err(|_| Rejection::Invalid)?;
+
+            HEADER_LEN..HEADER_LEN + plaintext.len()
+        }
+        _ => unreachable!(),
+    };
+
+    Ok(Opened {
+        frame,
+        timestamp,
+        nonce,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame() -> proto::Frame {
+        proto::Frame {
+            identity: Bytes::from_static(b"bananas"),
+            messages: vec![proto::FrameMessage {
+                payload: Some(proto::frame_message::Payload::Ping(proto::Ping {
+                    interests: 42,
+                })),
+            }],
+        }
+    }
+
+    fn round_trip(tx: &FrameCodec, rx: &FrameCodec) -> Result<proto::Frame, Rejection> {
+        let mut buf = Vec::new();
+        tx.encode(&frame(), &mut buf);
+        rx.decode(BytesMut::from(&buf[..]))
+            .map(|v| proto::Frame::decode(v).expect("valid frame"))
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let keys = vec![SharedKey::new("bananas-are-great")];
+
+        for codec in [
+            FrameCodec::default(),
+            FrameCodec::new(keys.clone(), FrameProtection::Authenticate),
+            FrameCodec::new(keys, FrameProtection::Encrypt),
+        ] {
+            assert_eq!(round_trip(&codec, &codec), Ok(frame()), "{codec:?}");
+        }
+    }
+
+    #[test]
+    fn test_overhead() {
+        let plain_len = frame().encoded_len();
+
+        for (protection, overhead) in [
+            (FrameProtection::Authenticate, HEADER_LEN + MAC_LEN),
+            (FrameProtection::Encrypt, HEADER_LEN + AEAD_TAG_LEN),
+        ] {
+            let codec = FrameCodec::new(vec![SharedKey::new("platanos")], protection);
+            let mut buf = Vec::new();
+            codec.encode(&frame(), &mut buf);
+            assert_eq!(buf.len(), plain_len + overhead);
+            assert!(overhead <= MAX_SEAL_OVERHEAD);
+        }
+    }
+
+    #[test]
+    fn test_encrypted_frame_is_opaque() {
+        let codec = FrameCodec::new(vec![SharedKey::new("platanos")], FrameProtection::Encrypt);
+
+        let mut buf = Vec::new();
+        codec.encode(&frame(), &mut buf);
+
+        assert!(!buf.windows(7).any(|w| w == b"bananas"));
+    }
+
+    #[test]
+    fn test_key_rotation() {
+        let old = SharedKey::new("old-secret");
+        let new = SharedKey::new("new-secret");
+
+        // A node still sealing with the old key, but accepting the new key.
+        let a = FrameCodec::new(vec![old.clone(), new.clone()], FrameProtection::Encrypt);
+        // A node sealing with the new key, still accepting the old key.
+        let b = FrameCodec::new(vec![new.clone(), old], FrameProtection::Authenticate);
+        // A node that only knows the new key.
+        let c = FrameCodec::new(vec![new], FrameProtection::Encrypt);
+
+        assert_eq!(round_trip(&a, &b), Ok(frame()));
+        assert_eq!(round_trip(&b, &a), Ok(frame()));
+        assert_eq!(round_trip(&b, &c), Ok(frame()));
+        assert_eq!(round_trip(&c, &a), Ok(frame()));
+        assert_eq!(round_trip(&a, &c), Err(Rejection::UnknownKey));
+    }
+
+    #[test]
+    fn test_rejections() {
+        let key = SharedKey::new("bananas");
+        let auth = FrameCodec::new(vec![key.clone()], FrameProtection::Authenticate);
+        let enc = FrameCodec::new(vec![key], FrameProtection::Encrypt);
+        let other = FrameCodec::new(vec![SharedKey::new("platanos")], FrameProtection::Encrypt);
+
+        // Plaintext frames are rejected by sealed codecs.
+        assert_eq!(
+            round_trip(&FrameCodec::Plaintext, &auth),
+            Err(Rejection::Unauthenticated)
+        );
+        assert_eq!(round_trip(&other, &enc), Err(Rejection::UnknownKey));
+
+        // Truncated envelopes.
+        for len in [0, 1, HEADER_LEN, HEADER_LEN + MAC_LEN - 1] {
+            let mut buf = Vec::new();
+            auth.encode(&frame(), &mut buf);
+            buf.truncate(len);
+            let want = if len == 0 {
+                Rejection::Unauthenticated
+            } else {
+                Rejection::Invalid
+            };
+            assert_eq!(auth.decode(BytesMut::from(&buf[..])), Err(want), "{len}");
+        }
+
+        // Tampering with any byte after the key ID is detected.
+        for codec in [&auth, &enc] {
+            let mut buf = Vec::new();
+            codec.encode(&frame(), &mut buf);
+            for i in TIMESTAMP_OFFSET..buf.len() {
+                let mut tampered = buf.clone();
+                tampered[i] ^= 0x01;
+                assert_eq!(
+                    codec.decode(BytesMut::from(&tampered[..])),
+                    Err(Rejection::Invalid),
+                    "{codec:?} byte {i}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_replay_rejected() {
+        let key = SharedKey::new("bananas");
+
+        for protection in [FrameProtection::Authenticate, FrameProtection::Encrypt] {
+            let codec = FrameCodec::new(vec![key.clone()], protection);
+
+            let mut buf = Vec::new();
+            codec.encode(&frame(), &mut buf);
+
+            // Verifying a frame does not record it.
+            assert!(codec.verify(BytesMut::from(&buf[..])).is_ok());
+
+            assert!(codec.decode(BytesMut::from(&buf[..])).is_ok());
+            assert_eq!(
+                codec.decode(BytesMut::from(&buf[..])),
+                Err(Rejection::Replayed),
+                "{protection:?}"
+            );
+
+            // The replay is detected by clones of the codec too.
+            assert_eq!(
+                codec.clone().decode(BytesMut::from(&buf[..])),
+                Err(Rejection::Replayed),
+                "{protection:?}"
+            );
+
+            // Sealing the same frame again produces a distinct envelope.
+            assert_eq!(round_trip(&codec, &codec), Ok(frame()));
+        }
+    }
+
+    #[test]
+    fn test_expired() {
+        let codec = FrameCodec::new(vec![SharedKey::new("bananas")], FrameProtection::Encrypt);
+        let window = MAX_FRAME_AGE.as_millis() as u64;
+        let now = 1_000 * window;
+
+        for (sealed_at, want) in [
+            (now - window - 1, Err(Rejection::Expired)),
+            (now + window + 1, Err(Rejection::Expired)),
+            (now - window, Ok(frame())),
+            (now + window, Ok(frame())),
+        ] {
+            let mut buf = Vec::new();
+            codec.encode_at(&frame(), &mut buf, sealed_at);
+            let got = codec
+                .decode_at(BytesMut::from(&buf[..]), now, true)
+                .map(|v| proto::Frame::decode(v).expect("valid frame"));
+            assert_eq!(got, want, "{sealed_at}");
+        }
+    }
+
+    #[test]
+    fn test_seen_nonces_pruned() {
+        let codec = FrameCodec::new(vec![SharedKey::new("bananas")], FrameProtection::Encrypt);
+        let seen = match &codec {
+            FrameCodec::Sealed { seen, .. } => Arc::clone(seen),
+            FrameCodec::Plaintext => unreachable!(),
+        };
+        let window = MAX_FRAME_AGE.as_millis() as u64;
+        let now = 1_000 * window;
+
+        let mut replay = Vec::new();
+        codec.encode_at(&frame(), &mut replay, now);
+        assert!(codec
+            .decode_at(BytesMut::from(&replay[..]), now, true)
+            .is_ok());
+        assert_eq!(seen.len(), 1);
+
+        // Once the first frame expires, its nonce is forgotten and a replay
+        // is still rejected.
+        let later = now + window + 1;
+        let mut buf = Vec::new();
+        codec.encode_at(&frame(), &mut buf, later);
+        assert!(codec
+            .decode_at(BytesMut::from(&buf[..]), later, true)
+            .is_ok());
+        assert_eq!(seen.len(), 1);
+
+        assert_eq!(
+            codec.decode_at(BytesMut::from(&replay[..]), later, true),
+            Err(Rejection::Expired)
+        );
+    }
+
+    #[test]
+    fn test_debug_redacts_secret() {
+        let key = SharedKey::new("super-secret");
+        let got = format!("{key:?}");
+        assert!(!got.contains("super-secret"));
+        assert_eq!(got.len(), "SharedKey()".len() + KEY_ID_LEN * 2);
+    }
+}
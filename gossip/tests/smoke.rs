@@ -773,3 +773,131 @@ async fn test_sender_ident() {
 
     assert_eq!(expect_addr, a_addr);
 }
+
+/// Assert peers configured with a common shared key exchange payloads,
+/// regardless of the protection they each apply to outgoing frames.
+#[tokio::test]
+async fn test_shared_key_payload_exchange() {
+    maybe_start_logging();
+
+    let metrics = Arc::new(metric::Registry::default());
+
+    let (a_socket, a_addr) = random_udp().await;
+    let (b_socket, b_addr) = random_udp().await;
+
+    let (a_tx, mut a_rx) = mpsc::channel(5);
+    let (b_tx, mut b_rx) = mpsc::channel(5);
+
+    // Peer B is mid-rotation, still sealing with the old key.
+    let old_key = SharedKey::new("bananas-bananas-bananas");
+    let new_key = SharedKey::new("platanos-platanos-platanos");
+
+    let addrs = vec![a_addr.to_string(), b_addr.to_string()];
+    let a = Builder::new(addrs.clone(), a_tx, Arc::clone(&metrics))
+        .with_shared_keys(vec![old_key.clone()], FrameProtection::Encrypt)
+        .build(a_socket);
+    let b = Builder::new(addrs, b_tx, Arc::clone(&metrics))
+        .with_shared_keys(vec![old_key, new_key], FrameProtection::Authenticate)
+        .build(b_socket);
+
+    // Wait for peer discovery to occur
+    async {
+        loop {
+            if a.get_peers().await.len() == 1 && b.get_peers().await.len() == 1 {
+                break;
+            }
+        }
+    }
+    .with_timeout_panic(TIMEOUT)
+    .await;
+
+    let a_payload = Bytes::from_static(b"bananas");
+    a.broadcast(a_payload.clone(), Topic::Bananas)
+        .await
+        .unwrap();
+    let (topic, got) = b_rx
+        .recv()
+        .with_timeout_panic(TIMEOUT)
+        .await
+        .expect("reactor stopped");
+    assert_eq!(got, a_payload);
+    assert_eq!(topic, Topic::Bananas);
+
+    let b_payload = Bytes::from_static(b"platanos");
+    b.broadcast(b_payload.clone(), Topic::Bananas)
+        .await
+        .unwrap();
+    let (topic, got) = a_rx
+        .recv()
+        .with_timeout_panic(TIMEOUT)
+        .await
+        .expect("reactor stopped");
+    assert_eq!(got, b_payload);
+    assert_eq!(topic, Topic::Bananas);
+}
+
+/// Assert a peer without a common shared key (or without any key) is unable
+/// to join the cluster, and the rejected frames are recorded.
+#[tokio::test]
+async fn test_shared_key_mismatch() {
+    maybe_start_logging();
+
+    let metrics = Arc::new(metric::Registry::default());
+
+    let (a_socket, a_addr) = random_udp().await;
+    let (b_socket, b_addr) = random_udp().await;
+    let (c_socket, c_addr) = random_udp().await;
+
+    let seeds = vec![a_addr.to_string(), b_addr.to_string(), c_addr.to_string()];
+    let a = Builder::<_, Topic>::new(
+        seeds.clone(),
+        NopDispatcher::default(),
+        Arc::clone(&metrics),
+    )
+    .with_shared_keys(
+        vec![SharedKey::new("bananas-bananas-bananas")],
+        FrameProtection::Authenticate,
+    )
+    .build(a_socket);
+    let b = Builder::<_, Topic>::new(
+        seeds.clone(),
+        NopDispatcher::default(),
+        Arc::clone(&metrics),
+    )
+    .with_shared_keys(
+        vec![SharedKey::new("platanos-platanos-platanos")],
+        FrameProtection::Authenticate,
+    )
+    .build(b_socket);
+    let c = Builder::<_, Topic>::new(seeds, NopDispatcher::default(), Arc::clone(&metrics))
+        .build(c_socket);
+
+    let rejected = |reason: &'static str| {
+        metrics
+            .get_instrument::<metric::Metric<metric::U64Counter>>("gossip_frames_rejected")
+            .expect("metric should be registered")
+            .get_observer(&metric::Attributes::from(&[("reason", reason)]))
+            .expect("observer should exist")
+            .fetch()
+    };
+
+    // Wait for the seed pings to be rejected by all nodes.
+    async {
+        loop {
+            if rejected("unknown_key") >= 2 && rejected("unauthenticated") >= 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+    .with_timeout_panic(TIMEOUT)
+    .await;
+
+    // The keyed nodes never learn of any peer.
+    assert!(a.get_peers().await.is_empty());
+    assert!(b.get_peers().await.is_empty());
+
+    // The unauthenticated node never receives a response from either keyed
+    // node either.
+    assert!(c.get_peers().await.is_empty());
+}
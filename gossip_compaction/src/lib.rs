@@ -188,12 +188,12 @@ mod tests {
         .await;
 
         let a = Peer {
-            tx: CompactionEventTx::new(a),
+            tx: CompactionEventTx::new(Arc::new(a)),
             rx: a_rx,
         };
 
         let b = Peer {
-            tx: CompactionEventTx::new(b),
+            tx: CompactionEventTx::new(Arc::new(b)),
             rx: b_rx,
         };
 
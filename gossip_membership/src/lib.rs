@@ -0,0 +1,174 @@
+//! Cluster membership announcements over [gossip].
+//!
+//! Every IOx node with gossip enabled periodically broadcasts a
+//! [`MemberAnnouncement`] describing its [`Role`], its advertised gRPC address
+//! and version. Interested peers collect these announcements into a
+//! [`ClusterMembers`] view, providing a best-effort picture of which nodes are
+//! currently alive in the cluster.
+//!
+//! This sub-system is composed of the following primary components:
+//!
+//! * [`gossip`] crate: provides the gossip transport, the [`GossipHandle`], and
+//!   the [`Dispatcher`]. This crate operates on raw bytes.
+//!
+//! * The outgoing [`MembershipTx`]: periodically serialises the local
+//!   [`MemberInfo`] into a [`MemberAnnouncement`] and broadcasts it over the
+//!   underlying [`gossip`] impl.
+//!
+//! * The incoming [`MembershipRx`]: deserialises the incoming bytes from the
+//!   gossip [`Dispatcher`] into [`MemberAnnouncement`] and passes them off to
+//!   the [`MemberAnnouncementHandler`] implementation for processing.
+//!
+//! * The [`ClusterMembers`] view: a [`MemberAnnouncementHandler`] that tracks
+//!   the most recent announcement of each peer, and when it was received.
+//!
+//! # Liveness
+//!
+//! A member is considered alive if an announcement from it was received
+//! within the last [`MEMBER_TIMEOUT`], and is removed from the view entirely
+//! once no announcement has been received for [`MEMBER_EXPIRY`]. Because the
+//! underlying gossip transport provides best-effort delivery only, a member
+//! may briefly be reported as dead while still running - consumers of the
+//! view SHOULD tolerate this.
+//!
+//! [`MembershipTx`]: tx::MembershipTx
+//! [`MembershipRx`]: rx::MembershipRx
+//! [`MemberAnnouncementHandler`]: rx::MemberAnnouncementHandler
+//! [`GossipHandle`]: gossip::GossipHandle
+//! [`Dispatcher`]: gossip::Dispatcher
+//! [`MemberAnnouncement`]:
+//!     generated_types::influxdata::iox::gossip::v1::MemberAnnouncement
+
+#![deny(rustdoc::broken_intra_doc_links, rust_2018_idioms)]
+#![warn(
+    clippy::clone_on_ref_ptr,
+    clippy::dbg_macro,
+    clippy::explicit_iter_loop,
+    // See https://github.com/influxdata/influxdb_iox/pull/1671
+    clippy::future_not_send,
+    clippy::todo,
+    clippy::use_self,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    unused_crate_dependencies,
+    missing_docs
+)]
+#![allow(clippy::default_constructed_unit_structs)]
+
+// Workaround for "unused crate" lint false positives.
+use workspace_hack as _;
+
+use std::time::Duration;
+
+mod members;
+pub mod rx;
+pub mod tx;
+
+pub use members::*;
+
+/// How often a node broadcasts its [`MemberAnnouncement`].
+///
+/// [`MemberAnnouncement`]:
+///     generated_types::influxdata::iox::gossip::v1::MemberAnnouncement
+pub const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The duration of time since the last received announcement after which a
+/// member is considered dead.
+pub const MEMBER_TIMEOUT: Duration = Duration::from_secs(3 * ANNOUNCE_INTERVAL.as_secs());
+
+/// The duration of time since the last received announcement after which a
+/// member is removed from the [`ClusterMembers`] view.
+pub const MEMBER_EXPIRY: Duration = Duration::from_secs(10 * 60);
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+    use generated_types::influxdata::iox::gossip::{v1::MemberAnnouncement, Topic};
+    use gossip::{Builder, TopicInterests};
+    use iox_time::{SystemProvider, Time};
+    use test_helpers::{maybe_start_logging, timeout::FutureTimeout};
+    use tokio::net::UdpSocket;
+
+    use crate::{rx::MembershipRx, tx::MembershipTx, ClusterMembers, MemberInfo, Role};
+
+    /// Bind a UDP socket on a random port and return it alongside the socket
+    /// address.
+    async fn random_udp() -> (UdpSocket, SocketAddr) {
+        // Bind a UDP socket to a random port
+        let socket = UdpSocket::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind UDP socket");
+        let addr = socket.local_addr().expect("failed to read local addr");
+
+        (socket, addr)
+    }
+
+    /// Ensure a member announcement broadcast by one node is observed in the
+    /// [`ClusterMembers`] view of another.
+    #[tokio::test]
+    async fn test_round_trip() {
+        maybe_start_logging();
+
+        let metrics = Arc::new(metric::Registry::default());
+
+        let (a_socket, a_addr) = random_udp().await;
+        let (b_socket, b_addr) = random_udp().await;
+
+        // Node B maintains a view of the cluster.
+        let b_members = Arc::new(ClusterMembers::new(Arc::new(SystemProvider::new())));
+        let b_dispatcher = MembershipRx::new(Arc::clone(&b_members), 100);
+
+        // Initialise both gossip reactors
+        let addrs = vec![a_addr.to_string(), b_addr.to_string()];
+        let a =
+            Builder::<_, Topic>::new(addrs.clone(), gossip::NopDispatcher, Arc::clone(&metrics))
+                .with_topic_filter(TopicInterests::default())
+                .build(a_socket);
+        let b = Builder::<_, Topic>::new(addrs, b_dispatcher, Arc::clone(&metrics))
+            .with_topic_filter(TopicInterests::default().with_topic(Topic::ClusterMembership))
+            .build(b_socket);
+
+        // Wait for peer discovery to occur
+        async {
+            loop {
+                if a.get_peers().await.len() == 1 && b.get_peers().await.len() == 1 {
+                    break;
+                }
+            }
+        }
+        .with_timeout_panic(Duration::from_secs(5))
+        .await;
+
+        // Start announcing node A.
+        let info = MemberInfo {
+            role: Role::Ingester,
+            rpc_address: "http://ingester-0:8082".to_string(),
+            version: "bananas".to_string(),
+            started_at: Time::from_timestamp_nanos(42),
+        };
+        let a_identity = a.identity();
+        let _tx = MembershipTx::new(Arc::new(a), info.clone());
+
+        // And wait for node B to observe it.
+        let got = async {
+            loop {
+                if let Some(v) = b_members.members().into_iter().next() {
+                    return v;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }
+        .with_timeout_panic(Duration::from_secs(5))
+        .await;
+
+        assert_eq!(got.identity, a_identity);
+        assert_eq!(got.info, info);
+        assert!(got.alive);
+        assert!(!got.local);
+
+        // Sanity check the wire encoding.
+        let proto = MemberAnnouncement::from(info.clone());
+        assert_eq!(MemberInfo::try_from(proto).unwrap(), info);
+    }
+}
@@ -0,0 +1,364 @@
+use std::{collections::HashMap, fmt::Display, sync::Arc};
+
+use async_trait::async_trait;
+use generated_types::influxdata::iox::gossip::v1::{
+    member_announcement::Role as ProtoRole, MemberAnnouncement,
+};
+use gossip::Identity;
+use iox_time::{Time, TimeProvider};
+use observability_deps::tracing::{debug, info, warn};
+use parking_lot::Mutex;
+
+use crate::{rx::MemberAnnouncementHandler, MEMBER_EXPIRY, MEMBER_TIMEOUT};
+
+/// The function an IOx node performs within the cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Role {
+    /// A router, accepting writes and forwarding them to ingesters.
+    Router,
+    /// An ingester, buffering writes and persisting them to object storage.
+    Ingester,
+    /// A querier, answering queries over persisted & buffered data.
+    Querier,
+    /// A compactor, compacting persisted data.
+    Compactor,
+}
+
+impl Role {
+    /// Return the name of this role.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Router => "router",
+            Self::Ingester => "ingester",
+            Self::Querier => "querier",
+            Self::Compactor => "compactor",
+        }
+    }
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<Role> for ProtoRole {
+    fn from(value: Role) -> Self {
+        match value {
+            Role::Router => Self::Router,
+            Role::Ingester => Self::Ingester,
+            Role::Querier => Self::Querier,
+            Role::Compactor => Self::Compactor,
+        }
+    }
+}
+
+/// An error converting a [`MemberAnnouncement`] into a [`MemberInfo`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidRole(i32);
+
+impl Display for InvalidRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid member role {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidRole {}
+
+impl TryFrom<i32> for Role {
+    type Error = InvalidRole;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match ProtoRole::try_from(value) {
+            Ok(ProtoRole::Router) => Ok(Self::Router),
+            Ok(ProtoRole::Ingester) => Ok(Self::Ingester),
+            Ok(ProtoRole::Querier) => Ok(Self::Querier),
+            Ok(ProtoRole::Compactor) => Ok(Self::Compactor),
+            Ok(ProtoRole::Unspecified) | Err(_) => Err(InvalidRole(value)),
+        }
+    }
+}
+
+/// The description of a node advertised to its peers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemberInfo {
+    /// The role of the node.
+    pub role: Role,
+    /// The gRPC address peers can use to reach the node.
+    pub rpc_address: String,
+    /// The IOx version running on the node.
+    pub version: String,
+    /// The time the node started.
+    pub started_at: Time,
+}
+
+impl From<MemberInfo> for MemberAnnouncement {
+    fn from(value: MemberInfo) -> Self {
+        Self {
+            role: ProtoRole::from(value.role).into(),
+            rpc_address: value.rpc_address,
+            version: value.version,
+            started_at_ns: value.started_at.timestamp_nanos(),
+        }
+    }
+}
+
+impl TryFrom<MemberAnnouncement> for MemberInfo {
+    type Error = InvalidRole;
+
+    fn try_from(value: MemberAnnouncement) -> Result<Self, Self::Error> {
+        Ok(Self {
+            role: Role::try_from(value.role)?,
+            rpc_address: value.rpc_address,
+            version: value.version,
+            started_at: Time::from_timestamp_nanos(value.started_at_ns),
+        })
+    }
+}
+
+/// A point-in-time view of a single member of the cluster.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClusterMember {
+    /// The gossip identity of the member.
+    pub identity: Identity,
+    /// The most recent description advertised by the member.
+    pub info: MemberInfo,
+    /// The time the most recent announcement was received.
+    pub last_seen: Time,
+    /// True if the member announced itself within the last
+    /// [`MEMBER_TIMEOUT`].
+    pub alive: bool,
+    /// True if this member is the local node.
+    pub local: bool,
+}
+
+#[derive(Debug)]
+struct Observed {
+    info: MemberInfo,
+    last_seen: Time,
+}
+
+/// A best-effort view of the members of the cluster, built from the
+/// [`MemberAnnouncement`] messages received via gossip.
+///
+/// Members that have not been heard from for [`MEMBER_EXPIRY`] are removed
+/// from the view.
+#[derive(Debug)]
+pub struct ClusterMembers {
+    time_provider: Arc<dyn TimeProvider>,
+
+    /// The local node, if it is advertising itself.
+    local: Mutex<Option<(Identity, MemberInfo)>>,
+
+    /// The remote members, keyed by their gossip identity.
+    members: Mutex<HashMap<Identity, Observed>>,
+}
+
+impl ClusterMembers {
+    /// Initialise an empty view, using `time_provider` to determine member
+    /// liveness.
+    pub fn new(time_provider: Arc<dyn TimeProvider>) -> Self {
+        Self {
+            time_provider,
+            local: Default::default(),
+            members: Default::default(),
+        }
+    }
+
+    /// Include the local node, identified by `identity`, in this view.
+    ///
+    /// The local node is always reported as alive.
+    pub fn set_local(&self, identity: Identity, info: MemberInfo) {
+        *self.local.lock() = Some((identity, info));
+    }
+
+    /// Record an announcement of `info` from the peer identified by
+    /// `identity`.
+    pub fn observe(&self, identity: Identity, info: MemberInfo) {
+        let last_seen = self.time_provider.now();
+
+        let mut members = self.members.lock();
+        if members.contains_key(&identity) {
+            debug!(%identity, "refreshed cluster member");
+        } else {
+            info!(
+                %identity,
+                role = %info.role,
+                rpc_address = %info.rpc_address,
+                version = %info.version,
+                "discovered cluster member"
+            );
+        }
+        members.insert(identity, Observed { info, last_seen });
+    }
+
+    /// Return a snapshot of all members in this view, ordered by role and
+    /// address.
+    pub fn members(&self) -> Vec<ClusterMember> {
+        let now = self.time_provider.now();
+        let age = |t: Time| now.checked_duration_since(t).unwrap_or_default();
+
+        let mut out = {
+            let mut members = self.members.lock();
+
+            // Remove any members that have not been seen for a long time.
+            members.retain(|identity, m| {
+                let keep = age(m.last_seen) < MEMBER_EXPIRY;
+                if !keep {
+                    info!(%identity, role = %m.info.role, "removing expired cluster member");
+                }
+                keep
+            });
+
+            members
+                .iter()
+                .map(|(identity, m)| ClusterMember {
+                    identity: identity.clone(),
+                    info: m.info.clone(),
+                    last_seen: m.last_seen,
+                    alive: age(m.last_seen) <= MEMBER_TIMEOUT,
+                    local: false,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        if let Some((identity, info)) = &*self.local.lock() {
+            out.push(ClusterMember {
+                identity: identity.clone(),
+                info: info.clone(),
+                last_seen: now,
+                alive: true,
+                local: true,
+            });
+        }
+
+        out.sort_unstable_by(|a, b| {
+            (a.info.role, &a.info.rpc_address).cmp(&(b.info.role, &b.info.rpc_address))
+        });
+        out
+    }
+
+    /// Return the advertised gRPC addresses of all alive members with the
+    /// specified `role`.
+    pub fn alive_addresses(&self, role: Role) -> Vec<String> {
+        self.members()
+            .into_iter()
+            .filter(|m| m.alive && m.info.role == role)
+            .map(|m| m.info.rpc_address)
+            .collect()
+    }
+}
+
+#[async_trait]
+impl MemberAnnouncementHandler for ClusterMembers {
+    async fn handle(&self, sender: Identity, announcement: MemberAnnouncement) {
+        match MemberInfo::try_from(announcement) {
+            Ok(info) => self.observe(sender, info),
+            Err(e) => warn!(error=%e, %sender, "ignoring invalid member announcement"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use iox_time::MockProvider;
+
+    use super::*;
+
+    fn identity(v: u8) -> Identity {
+        Identity::try_from(vec![v; 16]).unwrap()
+    }
+
+    fn info(role: Role, addr: &str) -> MemberInfo {
+        MemberInfo {
+            role,
+            rpc_address: addr.to_string(),
+            version: "v42".to_string(),
+            started_at: Time::from_timestamp_nanos(4242),
+        }
+    }
+
+    #[test]
+    fn test_member_liveness() {
+        let time = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let members = ClusterMembers::new(Arc::clone(&time) as _);
+
+        members.set_local(identity(1), info(Role::Querier, "http://q:8082"));
+        members.observe(identity(2), info(Role::Ingester, "http://i-1:8082"));
+        members.observe(identity(3), info(Role::Ingester, "http://i-0:8082"));
+
+        let got = members.members();
+        assert_eq!(
+            got.iter()
+                .map(|m| (m.info.rpc_address.as_str(), m.alive, m.local))
+                .collect::<Vec<_>>(),
+            [
+                ("http://i-0:8082", true, false),
+                ("http://i-1:8082", true, false),
+                ("http://q:8082", true, true),
+            ]
+        );
+        assert_eq!(
+            members.alive_addresses(Role::Ingester),
+            ["http://i-0:8082", "http://i-1:8082"]
+        );
+
+        // Only one ingester continues to announce itself.
+        time.inc(MEMBER_TIMEOUT);
+        members.observe(identity(2), info(Role::Ingester, "http://i-1:8082"));
+        time.inc(Duration::from_secs(1));
+
+        let got = members.members();
+        assert_eq!(got.len(), 3);
+        assert!(!got[0].alive);
+        assert!(got[1].alive);
+        assert_eq!(members.alive_addresses(Role::Ingester), ["http://i-1:8082"]);
+
+        // Eventually the dead members are removed, while the local node
+        // remains.
+        time.inc(MEMBER_EXPIRY);
+        let got = members.members();
+        assert_eq!(got.len(), 1);
+        assert!(got[0].local);
+        assert!(got[0].alive);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_role_ignored() {
+        let members =
+            ClusterMembers::new(Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))));
+
+        members
+            .handle(
+                identity(1),
+                MemberAnnouncement {
+                    role: ProtoRole::Unspecified.into(),
+                    rpc_address: "http://bananas:8082".to_string(),
+                    version: "v42".to_string(),
+                    started_at_ns: 42,
+                },
+            )
+            .await;
+        members
+            .handle(
+                identity(2),
+                MemberAnnouncement {
+                    role: 4242,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        assert!(members.members().is_empty());
+    }
+
+    #[test]
+    fn test_role_round_trip() {
+        for role in [Role::Router, Role::Ingester, Role::Querier, Role::Compactor] {
+            let v: i32 = ProtoRole::from(role).into();
+            assert_eq!(Role::try_from(v), Ok(role));
+        }
+    }
+}
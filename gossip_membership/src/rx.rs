@@ -0,0 +1,105 @@
+//! A deserialiser and dispatcher of [gossip] messages for the
+//! [`Topic::ClusterMembership`] topic.
+
+use std::{fmt::Debug, sync::Arc};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use generated_types::influxdata::iox::gossip::{v1::MemberAnnouncement, Topic};
+use generated_types::prost::Message;
+use gossip::Identity;
+use observability_deps::tracing::{info, warn};
+use tokio::{sync::mpsc, task::JoinHandle};
+
+/// A [`MemberAnnouncement`] handler received via gossip.
+#[async_trait]
+pub trait MemberAnnouncementHandler: Send + Sync + Debug {
+    /// Process `announcement` sent by the peer identified by `sender`.
+    async fn handle(&self, sender: Identity, announcement: MemberAnnouncement);
+}
+
+#[async_trait]
+impl<T> MemberAnnouncementHandler for Arc<T>
+where
+    T: MemberAnnouncementHandler,
+{
+    async fn handle(&self, sender: Identity, announcement: MemberAnnouncement) {
+        T::handle(self, sender, announcement).await
+    }
+}
+
+/// An async gossip message dispatcher.
+///
+/// This type is responsible for deserialising incoming gossip
+/// [`Topic::ClusterMembership`] payloads and passing them off to the provided
+/// [`MemberAnnouncementHandler`] implementation.
+///
+/// This type also provides a buffer between incoming announcements, and
+/// processing, preventing processing time from blocking the gossip reactor.
+/// Once the buffer is full, incoming announcements are dropped until space is
+/// made through processing of outstanding announcements. Dropping the
+/// [`MembershipRx`] stops the background event loop.
+#[derive(Debug)]
+pub struct MembershipRx {
+    tx: mpsc::Sender<(Identity, Bytes)>,
+    task: JoinHandle<()>,
+}
+
+impl MembershipRx {
+    /// Initialise a new dispatcher, buffering up to `buffer` number of
+    /// announcements.
+    ///
+    /// The provided `handler` does not block the gossip reactor during
+    /// execution.
+    pub fn new<T>(handler: T, buffer: usize) -> Self
+    where
+        T: MemberAnnouncementHandler + 'static,
+    {
+        // Initialise a buffered channel to decouple the two halves.
+        let (tx, rx) = mpsc::channel(buffer);
+
+        // And run a receiver loop to pull the events from the channel.
+        let task = tokio::spawn(dispatch_loop(rx, handler));
+
+        Self { tx, task }
+    }
+}
+
+#[async_trait]
+impl gossip::Dispatcher<Topic> for MembershipRx {
+    async fn dispatch(&self, topic: Topic, payload: Bytes, sender: Identity) {
+        if topic != Topic::ClusterMembership {
+            return;
+        }
+        if let Err(e) = self.tx.try_send((sender, payload)) {
+            warn!(error=%e, "failed to buffer gossip event");
+        }
+    }
+}
+
+impl Drop for MembershipRx {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn dispatch_loop<T>(mut rx: mpsc::Receiver<(Identity, Bytes)>, handler: T)
+where
+    T: MemberAnnouncementHandler,
+{
+    while let Some((sender, payload)) = rx.recv().await {
+        // Deserialise the payload into the appropriate proto type.
+        let announcement = match MemberAnnouncement::decode(payload) {
+            Ok(v) => v,
+            Err(e) => {
+                warn!(error=%e, "failed to deserialise gossip message");
+                continue;
+            }
+        };
+
+        // Pass this message off to the handler to process.
+        handler.handle(sender, announcement).await;
+    }
+
+    info!("stopping gossip dispatcher");
+}
@@ -0,0 +1,67 @@
+//! A periodic broadcaster of [`gossip`] messages for the
+//! [`Topic::ClusterMembership`] topic.
+
+use std::sync::Arc;
+
+use generated_types::{
+    influxdata::iox::gossip::{v1::MemberAnnouncement, Topic},
+    prost::Message,
+};
+use observability_deps::tracing::{debug, error, info};
+use tokio::task::JoinHandle;
+
+use crate::{MemberInfo, ANNOUNCE_INTERVAL};
+
+/// A gossip broadcast primitive that advertises the local node to all
+/// interested peers.
+///
+/// The provided [`MemberInfo`] is serialised once, and broadcast every
+/// [`ANNOUNCE_INTERVAL`] by a background task, starting immediately. Dropping
+/// the [`MembershipTx`] stops this background task, causing peers to consider
+/// the local node dead after
+/// [`MEMBER_TIMEOUT`](crate::MEMBER_TIMEOUT).
+#[derive(Debug)]
+pub struct MembershipTx {
+    task: JoinHandle<()>,
+}
+
+impl Drop for MembershipTx {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl MembershipTx {
+    /// Construct a new [`MembershipTx`] that periodically announces `info`
+    /// over `gossip`.
+    pub fn new(gossip: Arc<gossip::GossipHandle<Topic>>, info: MemberInfo) -> Self {
+        info!(
+            role = %info.role,
+            rpc_address = %info.rpc_address,
+            version = %info.version,
+            "announcing cluster membership"
+        );
+
+        let payload = MemberAnnouncement::from(info).encode_to_vec();
+        let task = tokio::spawn(actor_loop(payload, gossip));
+
+        Self { task }
+    }
+}
+
+async fn actor_loop(payload: Vec<u8>, gossip: Arc<gossip::GossipHandle<Topic>>) {
+    let mut interval = tokio::time::interval(ANNOUNCE_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+
+        debug!("sending cluster membership announcement");
+        if let Err(e) = gossip
+            .broadcast(payload.clone(), Topic::ClusterMembership)
+            .await
+        {
+            error!(error=%e, "failed to broadcast payload");
+        }
+    }
+}
@@ -166,12 +166,12 @@ mod tests {
         .await;
 
         let a = Peer {
-            tx: ParquetFileTx::new(a),
+            tx: ParquetFileTx::new(Arc::new(a)),
             rx: a_rx,
         };
 
         let b = Peer {
-            tx: ParquetFileTx::new(b),
+            tx: ParquetFileTx::new(Arc::new(b)),
             rx: b_rx,
         };
 
@@ -1,31 +1,47 @@
 //! Utilities for importing catalog and data from files
 //! MORE COMING SOON: <https://github.com/influxdata/influxdb_iox/issues/7744>
 
+use arrow::{
+    array::{Array, AsArray},
+    compute::cast,
+    datatypes::{DataType, Float64Type, Int64Type, TimeUnit, TimestampNanosecondType, UInt64Type},
+};
 use bytes::Bytes;
 use data_types::{
     partition_template::{
-        NamespacePartitionTemplateOverride, TablePartitionTemplateOverride, PARTITION_BY_DAY_PROTO,
+        NamespacePartitionTemplateOverride, TablePartitionTemplateOverride, TemplatePart,
+        PARTITION_BY_DAY_PROTO,
     },
-    ColumnSet, ColumnType, ColumnsByName, CompactionLevel, Namespace, NamespaceName,
+    ColumnSet, ColumnSummary, ColumnType, ColumnsByName, CompactionLevel, Namespace, NamespaceName,
     NamespaceNameError, ParquetFileParams, Partition, PartitionKey, SortedColumnSet, Statistics,
     Table, TableId, Timestamp,
 };
+use datafusion::{
+    error::DataFusionError, execution::memory_pool::UnboundedMemoryPool,
+    physical_plan::stream::RecordBatchStreamAdapter,
+};
 use generated_types::influxdata::iox::catalog::v1 as proto;
 //    ParquetFile as ProtoParquetFile, Partition as ProtoPartition,
 use iox_catalog::interface::{CasFailure, Catalog, RepoCollection, SoftDeletedRows};
+use mutable_batch::{writer::Writer, MutableBatch, PartitionWrite, WritePayload};
 use object_store::ObjectStore;
 use observability_deps::tracing::{debug, info, warn};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 use parquet_file::{
     metadata::{DecodedIoxParquetMetaData, IoxMetadata, IoxParquetMetaData},
+    storage::{ParquetStorage, StorageId},
     ParquetFilePath,
 };
+use schema::{InfluxColumnType, InfluxFieldType, Projection, Schema};
 use std::{
     borrow::Cow,
+    collections::{BTreeSet, HashSet},
     io::Read,
     path::{Path, PathBuf},
     sync::Arc,
 };
 use thiserror::Error;
+use uuid::Uuid;
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -78,6 +94,38 @@ pub enum Error {
 
     #[error("Object store error: {0}")]
     ObjectStore(#[from] object_store::Error),
+
+    #[error("Error reading parquet data: {0}")]
+    ReadParquet(#[from] parquet::errors::ParquetError),
+
+    #[error("Error converting parquet data: {0}")]
+    Arrow(#[from] arrow::error::ArrowError),
+
+    #[error("Column {0:?} described by the parquet metadata not found in the data")]
+    MissingColumn(String),
+
+    #[error("Error buffering parquet data: {0}")]
+    Writer(#[from] mutable_batch::writer::Error),
+
+    #[error("Error re-partitioning parquet data: {0}")]
+    MutableBatch(#[from] mutable_batch::Error),
+
+    #[error("Error generating partition key: {0}")]
+    PartitionKey(#[from] mutable_batch::PartitionKeyError),
+
+    #[error("Error uploading re-partitioned parquet file: {0}")]
+    Upload(#[from] parquet_file::storage::UploadError),
+
+    #[error(
+        "Cannot import into table {table_name:?}: the export holds {count} tables, expected one"
+    )]
+    MultipleTables { table_name: String, count: usize },
+
+    #[error("Cannot import into table {table_name:?}: found a parquet file from table {table_id}, which is not the exported table")]
+    UnexpectedTable {
+        table_name: String,
+        table_id: TableId,
+    },
 }
 
 impl Error {
@@ -244,6 +292,24 @@ impl ExportedContents {
             .cloned()
     }
 
+    /// Returns the IDs of the source tables described by the exported
+    /// catalog metadata
+    pub fn table_ids(&self) -> BTreeSet<i64> {
+        let from_names = self.table_json_files.iter().filter_map(|path| {
+            // names like "table.<id>.json"
+            file_name(path)
+                .strip_prefix("table.")?
+                .strip_suffix(".json")?
+                .parse()
+                .ok()
+        });
+
+        from_names
+            .chain(self.partition_metadata.iter().map(|p| p.table_id))
+            .chain(self.parquet_metadata.iter().map(|p| p.table_id))
+            .collect()
+    }
+
     /// Returns parquet file metadata, for the given object_store id, if any
     pub fn parquet_metadata(&self, object_store_id: &str) -> Option<proto::ParquetFile> {
         self.parquet_metadata
@@ -260,13 +326,45 @@ fn file_name(p: &Path) -> Cow<'_, str> {
         .unwrap_or_else(|| Cow::Borrowed(""))
 }
 
+/// What happened to a single exported parquet file during import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImportOutcome {
+    /// The file was copied as is
+    Copied,
+    /// The rows of the file were written to one or more new files
+    Rewritten,
+    /// The file was outside the time range, or already imported
+    Skipped,
+}
+
 /// Imports the contents of a [`ExportedContents`] into a catalog and
 /// object_store instance
+///
+/// By default the data is imported into the namespace and table it was
+/// exported from. Files are copied as is, unless the partition template of
+/// the target table partitions their rows differently, or a time range only
+/// covers some of their rows, in which case the rows are rewritten into new
+/// files.
 #[derive(Debug)]
 pub struct RemoteImporter {
     exported_contents: ExportedContents,
     catalog: Arc<dyn Catalog>,
     object_store: Arc<dyn ObjectStore>,
+
+    /// Import into this namespace, rather than the exported one
+    namespace_name: Option<String>,
+
+    /// Import into this table, rather than the exported one
+    table_name: Option<String>,
+
+    /// Only import rows with a timestamp at or after this one
+    start: Option<i64>,
+
+    /// Only import rows with a timestamp before this one
+    end: Option<i64>,
+
+    /// Skip files already present in the target catalog
+    skip_existing: bool,
 }
 
 impl RemoteImporter {
@@ -279,18 +377,71 @@ impl RemoteImporter {
             exported_contents,
             catalog,
             object_store,
+            namespace_name: None,
+            table_name: None,
+            start: None,
+            end: None,
+            skip_existing: false,
         }
     }
 
+    /// Import into the namespace `namespace_name`, creating it if needed,
+    /// rather than the namespace the data was exported from.
+    pub fn with_namespace_name(mut self, namespace_name: impl Into<String>) -> Self {
+        self.namespace_name = Some(namespace_name.into());
+        self
+    }
+
+    /// Import into the table `table_name`, creating it if needed, rather
+    /// than the table the data was exported from.
+    ///
+    /// The export must hold a single table, as all of its files are imported
+    /// into `table_name`.
+    pub fn with_table_name(mut self, table_name: impl Into<String>) -> Self {
+        self.table_name = Some(table_name.into());
+        self
+    }
+
+    /// Only import rows with a timestamp in `start..end` (nanoseconds since
+    /// the epoch). Either bound may be unbounded.
+    pub fn with_time_range(mut self, start: Option<i64>, end: Option<i64>) -> Self {
+        self.start = start;
+        self.end = end;
+        self
+    }
+
+    /// Skip, rather than re-import, files already recorded in the target
+    /// catalog, so an interrupted import can be resumed.
+    pub fn with_skip_existing(mut self, skip_existing: bool) -> Self {
+        self.skip_existing = skip_existing;
+        self
+    }
+
     /// Performs the import, reporting status to observer and erroring
     /// if a failure occurs
     pub async fn import(&self) -> Result<()> {
+        if let Some(table_name) = &self.table_name {
+            let count = self.exported_contents.table_ids().len();
+            if count > 1 {
+                return Err(Error::MultipleTables {
+                    table_name: table_name.clone(),
+                    count,
+                });
+            }
+        }
+
         let parquet_files = self.exported_contents.parquet_files();
 
         let total_files = parquet_files.len();
+        let mut rewritten = 0;
+        let mut skipped = 0;
         info!(%total_files, "Begin importing files");
         for (files_done, file) in parquet_files.iter().enumerate() {
-            self.import_parquet(file).await?;
+            match self.import_parquet(file).await? {
+                ImportOutcome::Copied => {}
+                ImportOutcome::Rewritten => rewritten += 1,
+                ImportOutcome::Skipped => skipped += 1,
+            }
 
             // print a log message every 50 files
             if files_done % 50 == 0 {
@@ -299,12 +450,17 @@ impl RemoteImporter {
             }
         }
 
-        info!(%total_files, "Completed importing files");
+        info!(%total_files, %rewritten, %skipped, "Completed importing files");
         Ok(())
     }
 
+    /// Returns true if `time` is within the configured time range.
+    fn in_time_range(&self, time: i64) -> bool {
+        self.start.map_or(true, |start| time >= start) && self.end.map_or(true, |end| time < end)
+    }
+
     // tries to import the specified parquet file into the catalog
-    async fn import_parquet(&self, file_path: &Path) -> Result<()> {
+    async fn import_parquet(&self, file_path: &Path) -> Result<ImportOutcome> {
         info!(?file_path, "Beginning Import");
 
         // step 1: figure out the location to write the parquet file in object store and do so
@@ -330,8 +486,44 @@ impl RemoteImporter {
 
         debug!(?iox_metadata, "read metadata");
 
+        // Note that for some reason, the object_store_id that is
+        // actually used in object_storage from the source system is
+        // different than what is stored in the metadata embedded in
+        // the parquet file itself. Thus use the object_store_id
+        // encoded into the parquet file name
+        let object_store_id =
+            object_store_id_from_parquet_filename(file_path).ok_or_else(|| {
+                Error::UnexpectedFileName {
+                    path: file_path.into(),
+                }
+            })?;
+        let parquet_metadata = self.exported_contents.parquet_metadata(&object_store_id);
+
+        // Skip files entirely outside the time range up front
+        let (min_time, max_time) = match &parquet_metadata {
+            Some(parquet_metadata) => (
+                Timestamp::new(parquet_metadata.min_time),
+                Timestamp::new(parquet_metadata.max_time),
+            ),
+            None => get_min_max_times(&decoded_iox_parquet_metadata)?,
+        };
+        if self.start.map_or(false, |start| max_time.get() < start)
+            || self.end.map_or(false, |end| min_time.get() >= end)
+        {
+            info!(
+                ?file_path,
+                ?min_time,
+                ?max_time,
+                "File outside time range, skipping"
+            );
+            return Ok(ImportOutcome::Skipped);
+        }
+
         // step 2: Add the appropriate entry to the catalog
-        let namespace_name = iox_metadata.namespace_name.as_ref();
+        let namespace_name = self
+            .namespace_name
+            .as_deref()
+            .unwrap_or(iox_metadata.namespace_name.as_ref());
         let mut repos = self.catalog.repositories().await;
 
         let namespace = repos
@@ -370,27 +562,74 @@ impl RemoteImporter {
         let table_id = table.id;
         debug!(%table_id, "Inserting catalog records into table");
 
+        if self.skip_existing
+            && repos
+                .parquet_files()
+                .get_by_object_store_id(iox_metadata.object_store_id)
+                .await?
+                .is_some()
+        {
+            info!(?file_path, "File already imported, skipping");
+            return Ok(ImportOutcome::Skipped);
+        }
+
+        // Only decode the file if some of its rows may have to be dropped or
+        // moved to another partition, otherwise copy it as is.
+        let schema = decoded_iox_parquet_metadata.read_schema()?;
+        if self.may_need_rewrite(
+            &table,
+            &iox_metadata,
+            &decoded_iox_parquet_metadata,
+            &schema,
+            (min_time, max_time),
+        )? {
+            // Split the rows of the file by the partition template of the
+            // target table, dropping any outside the time range.
+            let batch = read_mutable_batch(bytes.clone(), &schema)?;
+            let writes = PartitionWrite::partition(&batch, &table.partition_template)?
+                .into_iter()
+                .filter_map(|(partition_key, write)| match (self.start, self.end) {
+                    (None, None) => Some((partition_key, write)),
+                    _ => write
+                        .filter(|time| self.in_time_range(time))
+                        .map(|write| (partition_key, write)),
+                })
+                .collect::<Vec<_>>();
+
+            let unchanged = matches!(
+                writes.as_slice(),
+                [(partition_key, write)]
+                    if *partition_key == iox_metadata.partition_key
+                        && write.rows().get() == batch.rows()
+            );
+            if !unchanged {
+                if writes.is_empty() {
+                    info!(?file_path, "No rows in time range, skipping");
+                    return Ok(ImportOutcome::Skipped);
+                }
+                for (partition_key, write) in writes {
+                    self.rewrite_partition(
+                        repos.as_mut(),
+                        &namespace,
+                        &table,
+                        &iox_metadata,
+                        partition_key,
+                        &write,
+                    )
+                    .await?;
+                }
+                info!(?file_path, %namespace_name, %table_id, "Successfully imported rewritten file");
+                return Ok(ImportOutcome::Rewritten);
+            }
+        }
+
         // Create a new partition
         let partition_key = iox_metadata.partition_key.clone();
         let mut partition = self
             .create_partition(repos.as_mut(), &table, partition_key)
             .await?;
-
-        // Note that for some reason, the object_store_id that is
-        // actually used in object_storage from the source system is
-        // different than what is stored in the metadata embedded in
-        // the parquet file itself. Thus use the object_store_id
-        // encoded into the parquet file name
-        let object_store_id =
-            object_store_id_from_parquet_filename(file_path).ok_or_else(|| {
-                Error::UnexpectedFileName {
-                    path: file_path.into(),
-                }
-            })?;
         debug!(partition_id=%partition.id, %object_store_id, "Inserting into partition");
 
-        let parquet_metadata = self.exported_contents.parquet_metadata(&object_store_id);
-
         let parquet_params = self
             .parquet_file_params(
                 repos.as_mut(),
@@ -446,6 +685,123 @@ impl RemoteImporter {
             "Successfully imported file"
         );
 
+        Ok(ImportOutcome::Copied)
+    }
+
+    /// Returns false if the file can be copied as is, judging by its metadata
+    /// alone: the time range covers all of its rows, and the partition
+    /// template of `table` puts all of them in the exported partition.
+    ///
+    /// Returns true if the file has to be decoded to find out.
+    fn may_need_rewrite(
+        &self,
+        table: &Table,
+        iox_metadata: &IoxMetadata,
+        decoded_iox_parquet_metadata: &DecodedIoxParquetMetaData,
+        schema: &Schema,
+        (min_time, max_time): (Timestamp, Timestamp),
+    ) -> Result<bool> {
+        if !self.in_time_range(min_time.get()) || !self.in_time_range(max_time.get()) {
+            return Ok(true);
+        }
+
+        let stats = decoded_iox_parquet_metadata.read_statistics(schema)?;
+        let partition_key = file_partition_key(
+            &table.partition_template,
+            schema,
+            &stats,
+            (min_time, max_time),
+        )?;
+        Ok(partition_key.as_ref() != Some(&iox_metadata.partition_key))
+    }
+
+    /// Writes the rows of `write` to a new parquet file in the partition
+    /// `partition_key` of `table`, and records it in the catalog.
+    async fn rewrite_partition(
+        &self,
+        repos: &mut dyn RepoCollection,
+        namespace: &Namespace,
+        table: &Table,
+        iox_metadata: &IoxMetadata,
+        partition_key: PartitionKey,
+        write: &PartitionWrite<'_>,
+    ) -> Result<()> {
+        let object_store_id =
+            rewritten_object_store_id(iox_metadata.object_store_id, &partition_key);
+        if self.skip_existing
+            && repos
+                .parquet_files()
+                .get_by_object_store_id(object_store_id)
+                .await?
+                .is_some()
+        {
+            info!(%object_store_id, %partition_key, "Rewritten file already imported, skipping");
+            return Ok(());
+        }
+
+        let mut batch = MutableBatch::new();
+        write.write_to_batch(&mut batch)?;
+        let record_batch = batch.to_arrow(Projection::All)?;
+        let columns =
+            insert_schema_columns(table.id, &batch.schema(Projection::All)?, repos).await?;
+
+        let mut partition = self
+            .create_partition(repos, table, partition_key.clone())
+            .await?;
+        let partition_id = partition.transition_partition_id();
+
+        let meta = IoxMetadata {
+            object_store_id,
+            creation_timestamp: iox_metadata.creation_timestamp,
+            namespace_id: namespace.id,
+            namespace_name: Arc::from(namespace.name.as_str()),
+            table_id: table.id,
+            table_name: Arc::from(table.name.as_str()),
+            partition_key,
+            // The rows may now overlap with those of any other file in the
+            // partition, so leave it to the compactor to sort out
+            compaction_level: CompactionLevel::Initial,
+            // The rows retain the order of the source file
+            sort_key: iox_metadata.sort_key.clone(),
+            max_l0_created_at: iox_metadata.max_l0_created_at,
+        };
+
+        let stream = Box::pin(RecordBatchStreamAdapter::new(
+            record_batch.schema(),
+            futures_util::stream::iter([Ok::<_, DataFusionError>(record_batch)]),
+        ));
+        let (parquet_meta, file_size) =
+            ParquetStorage::new(Arc::clone(&self.object_store), StorageId::from("iox"))
+                .upload(
+                    stream,
+                    &partition_id,
+                    &meta,
+                    Arc::new(UnboundedMemoryPool::default()),
+                )
+                .await?;
+
+        let params = meta.to_parquet_file(partition_id.clone(), file_size, &parquet_meta, |name| {
+            columns.get(name).expect("column was inserted").id
+        });
+        match repos.parquet_files().create(params).await {
+            Ok(parquet_file) => {
+                debug!(parquet_file_id=?parquet_file.id, "Created rewritten parquet file entry");
+            }
+            Err(iox_catalog::interface::Error::FileExists { .. }) => {
+                warn!(%object_store_id, "parquet file already exists, skipping");
+            }
+            Err(e) => return Err(Error::Catalog(e)),
+        }
+
+        // Look up the sort key of the exported partition with the source table id
+        let source_meta = IoxMetadata {
+            table_id: iox_metadata.table_id,
+            ..meta.clone()
+        };
+        self.update_partition(&mut partition, repos, table, &source_meta)
+            .await?;
+
+        debug!(%object_store_id, %partition_id, rows = batch.rows(), "Rewrote parquet file");
         Ok(())
     }
 
@@ -464,7 +820,20 @@ impl RemoteImporter {
         let tables = repos.tables();
 
         // Note the export format doesn't currently have any table level information
-        let table_name = iox_metadata.table_name.as_ref();
+        let table_name = match &self.table_name {
+            Some(table_name) => {
+                // all files must come from the single exported table
+                let table_ids = self.exported_contents.table_ids();
+                if !table_ids.is_empty() && !table_ids.contains(&iox_metadata.table_id.get()) {
+                    return Err(Error::UnexpectedTable {
+                        table_name: table_name.clone(),
+                        table_id: iox_metadata.table_id,
+                    });
+                }
+                table_name.as_str()
+            }
+            None => iox_metadata.table_name.as_ref(),
+        };
 
         if let Some(table) = tables
             .get_by_namespace_and_name(namespace.id, table_name)
@@ -704,6 +1073,186 @@ async fn insert_columns(
     Ok(ColumnSet::new(column_ids))
 }
 
+/// Returns the [`ColumnsByName`] of the columns of `schema`, inserting the
+/// appropriate column entries in the catalog if they are not already
+/// present.
+async fn insert_schema_columns(
+    table_id: TableId,
+    schema: &Schema,
+    repos: &mut dyn RepoCollection,
+) -> Result<ColumnsByName> {
+    let mut columns = vec![];
+
+    for (iox_column_type, field) in schema.iter() {
+        let column = repos
+            .columns()
+            .create_or_get(field.name(), table_id, ColumnType::from(iox_column_type))
+            .await?;
+        columns.push(column);
+    }
+
+    Ok(ColumnsByName::new(columns))
+}
+
+/// Reads the rows of the parquet file in `bytes`, which has the IOx
+/// `schema`, into a [`MutableBatch`]
+fn read_mutable_batch(bytes: Bytes, schema: &Schema) -> Result<MutableBatch> {
+    let reader = ParquetRecordBatchReaderBuilder::try_new(bytes)?.build()?;
+
+    let mut batch = MutableBatch::new();
+    for record_batch in reader {
+        let record_batch = record_batch?;
+        if record_batch.num_rows() == 0 {
+            continue;
+        }
+
+        let mut writer = Writer::new(&mut batch, record_batch.num_rows());
+        for (column_type, field) in schema.iter() {
+            let name = field.name();
+            let array = record_batch
+                .column_by_name(name)
+                .ok_or_else(|| Error::MissingColumn(name.clone()))?;
+            let mask = valid_mask(array.as_ref());
+            let mask = mask.as_deref();
+
+            // The values of valid rows only, as the writer expects
+            match column_type {
+                InfluxColumnType::Tag => {
+                    let values = cast(array, &DataType::Utf8)?;
+                    writer.write_tag(name, mask, values.as_string::<i32>().iter().flatten())?
+                }
+                InfluxColumnType::Field(InfluxFieldType::Float) => writer.write_f64(
+                    name,
+                    mask,
+                    array.as_primitive::<Float64Type>().iter().flatten(),
+                )?,
+                InfluxColumnType::Field(InfluxFieldType::Integer) => writer.write_i64(
+                    name,
+                    mask,
+                    array.as_primitive::<Int64Type>().iter().flatten(),
+                )?,
+                InfluxColumnType::Field(InfluxFieldType::UInteger) => writer.write_u64(
+                    name,
+                    mask,
+                    array.as_primitive::<UInt64Type>().iter().flatten(),
+                )?,
+                InfluxColumnType::Field(InfluxFieldType::String) => {
+                    let values = cast(array, &DataType::Utf8)?;
+                    writer.write_string(name, mask, values.as_string::<i32>().iter().flatten())?
+                }
+                InfluxColumnType::Field(InfluxFieldType::Boolean) => {
+                    writer.write_bool(name, mask, array.as_boolean().iter().flatten())?
+                }
+                InfluxColumnType::Timestamp => {
+                    let values = cast(array, &DataType::Timestamp(TimeUnit::Nanosecond, None))?;
+                    writer.write_time(
+                        name,
+                        values
+                            .as_primitive::<TimestampNanosecondType>()
+                            .values()
+                            .iter()
+                            .copied(),
+                    )?
+                }
+            }
+        }
+        writer.commit();
+    }
+
+    Ok(batch)
+}
+
+/// Returns the partition key `template` assigns to every row of a file with
+/// the column statistics `stats` and time range `min_time..=max_time`, or
+/// `None` if the statistics do not show all rows to be in the same partition.
+///
+/// A partition template assigns the rows of a file to a single partition if
+/// each of its tag columns holds a single value (or only NULLs), and its time
+/// formats render the earliest and latest timestamp alike - time formats are
+/// expected to order their fields from most to least significant, as the
+/// default `%Y-%m-%d` does.
+fn file_partition_key(
+    template: &TablePartitionTemplateOverride,
+    schema: &Schema,
+    stats: &[ColumnSummary],
+    (min_time, max_time): (Timestamp, Timestamp),
+) -> Result<Option<PartitionKey>> {
+    // Build a batch of the earliest and latest row, with the single value of
+    // each partitioning tag column, and partition it instead of the file.
+    let mut batch = MutableBatch::new();
+    let mut writer = Writer::new(&mut batch, 2);
+    writer.write_time(
+        schema::TIME_COLUMN_NAME,
+        [min_time.get(), max_time.get()].into_iter(),
+    )?;
+
+    let mut tags = HashSet::new();
+    for part in template.parts() {
+        let TemplatePart::TagValue(name) = part else {
+            continue;
+        };
+        if !tags.insert(name) {
+            continue;
+        }
+        let Some(summary) = stats.iter().find(|s| s.name == name) else {
+            // Absent from the file, so NULL in all rows.
+            continue;
+        };
+        match (
+            schema.find_index_of(name).map(|i| schema.field(i).0),
+            &summary.stats,
+        ) {
+            (Some(InfluxColumnType::Tag), Statistics::String(values))
+                if values.null_count == Some(values.total_count) => {}
+            (Some(InfluxColumnType::Tag), Statistics::String(values))
+                if values.null_count == Some(0)
+                    && values.min.is_some()
+                    && values.min == values.max =>
+            {
+                let value = values.min.as_deref().expect("checked above");
+                writer.write_tag(name, None, [value, value].into_iter())?;
+            }
+            _ => return Ok(None),
+        }
+    }
+    writer.commit();
+
+    let mut keys = PartitionWrite::partition(&batch, template)?.into_keys();
+    Ok(match (keys.next(), keys.next()) {
+        (Some(partition_key), None) => Some(partition_key),
+        _ => None,
+    })
+}
+
+/// Returns the validity of the rows of `array` as the bitmask expected by
+/// [`Writer`], or `None` if all rows are valid.
+fn valid_mask(array: &dyn Array) -> Option<Vec<u8>> {
+    if array.null_count() == 0 {
+        return None;
+    }
+    let mut mask = vec![0_u8; (array.len() + 7) / 8];
+    for row in (0..array.len()).filter(|row| array.is_valid(*row)) {
+        mask[row / 8] |= 1 << (row % 8);
+    }
+    Some(mask)
+}
+
+/// Derives the object store id of the file holding the rows of the file
+/// `object_store_id` rewritten into `partition_key`.
+///
+/// The id is stable, so that files rewritten by a previous run of an import
+/// can be recognised.
+fn rewritten_object_store_id(object_store_id: Uuid, partition_key: &PartitionKey) -> Uuid {
+    // 64-bit FNV-1a, which unlike the std hasher is stable across releases
+    let hash = partition_key
+        .inner()
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, b| {
+            (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+        }) as u128;
+    Uuid::from_u128(object_store_id.as_u128() ^ (hash << 64 | hash))
+}
+
 /// Reads out the min and max value for the decoded_iox_parquet_metadata column
 fn get_min_max_times(
     decoded_iox_parquet_metadata: &DecodedIoxParquetMetaData,
@@ -751,3 +1300,508 @@ fn object_store_id_from_parquet_filename(path: &Path) -> Option<String> {
 
     Some(object_store_id.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::{Float64Array, StringArray},
+        compute::concat_batches,
+        record_batch::RecordBatch,
+    };
+    use data_types::{
+        partition_template::test_table_partition_override, InfluxDbType, NamespaceId, ParquetFile,
+        StatValues,
+    };
+    use iox_catalog::mem::MemCatalog;
+    use iox_time::Time;
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+    use object_store::memory::InMemory;
+    use parquet_file::serialize::to_parquet_bytes;
+    use schema::{sort::SortKey, SchemaBuilder};
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// 2023-01-01T00:00:00Z
+    const T0: i64 = 1_672_531_200_000_000_000;
+    const HOUR: i64 = 3_600_000_000_000;
+
+    /// Line protocol for rows of the table "cpu" at the given hours after
+    /// [`T0`], alternating between hosts "a", "b" and no host, and between
+    /// the fields "usage" and "idle".
+    fn lp(hours: impl IntoIterator<Item = i64>) -> String {
+        hours
+            .into_iter()
+            .map(|hour| {
+                let time = T0 + hour * HOUR;
+                match hour % 3 {
+                    0 => format!("cpu,host=a usage={hour} {time}"),
+                    1 => format!("cpu,host=b idle={hour} {time}"),
+                    _ => format!("cpu usage={hour},idle={hour} {time}"),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Writes the rows of `lp` to a parquet file of the source table
+    /// `table_id`, in the partition `partition_key`, named in `dir` like an
+    /// exported file. Returns the object store id of the file.
+    async fn write_file(dir: &Path, table_id: i64, partition_key: &str, lp: &str) -> Uuid {
+        let (table_name, batch) = lp_to_mutable_batch(lp);
+        let record_batch = batch.to_arrow(Projection::All).unwrap();
+
+        let object_store_id = Uuid::new_v4();
+        let meta = IoxMetadata {
+            object_store_id,
+            creation_timestamp: Time::from_timestamp_nanos(T0),
+            namespace_id: NamespaceId::new(1),
+            namespace_name: Arc::from("source"),
+            table_id: TableId::new(table_id),
+            table_name: Arc::from(table_name.as_str()),
+            partition_key: PartitionKey::from(partition_key),
+            compaction_level: CompactionLevel::FileNonOverlapped,
+            sort_key: Some(SortKey::from_columns(["host", "time"])),
+            max_l0_created_at: Time::from_timestamp_nanos(T0),
+        };
+        let stream = Box::pin(RecordBatchStreamAdapter::new(
+            record_batch.schema(),
+            futures_util::stream::iter([Ok::<_, DataFusionError>(record_batch)]),
+        ));
+        let (bytes, _) = to_parquet_bytes(stream, &meta, Arc::new(UnboundedMemoryPool::default()))
+            .await
+            .unwrap();
+
+        std::fs::write(dir.join(format!("{object_store_id}.1.parquet")), bytes).unwrap();
+        object_store_id
+    }
+
+    fn importer(dir: &Path) -> RemoteImporter {
+        RemoteImporter::new(
+            ExportedContents::try_new(dir).unwrap(),
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::default()))),
+            Arc::new(InMemory::new()),
+        )
+    }
+
+    /// Returns the files of the table `table_name` in the namespace
+    /// `namespace_name`, sorted by their time range
+    async fn imported_files(
+        importer: &RemoteImporter,
+        namespace_name: &str,
+        table_name: &str,
+    ) -> Vec<ParquetFile> {
+        let mut repos = importer.catalog.repositories().await;
+        let namespace = repos
+            .namespaces()
+            .get_by_name(namespace_name, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .unwrap()
+            .unwrap();
+        let table = repos
+            .tables()
+            .get_by_namespace_and_name(namespace.id, table_name)
+            .await
+            .unwrap()
+            .unwrap();
+        let mut files = repos
+            .parquet_files()
+            .list_by_table_not_to_delete(table.id)
+            .await
+            .unwrap();
+        files.sort_by_key(|f| (f.min_time, f.max_time));
+        files
+    }
+
+    async fn partition_key(importer: &RemoteImporter, file: &ParquetFile) -> String {
+        let mut repos = importer.catalog.repositories().await;
+        let partitions = repos
+            .partitions()
+            .list_by_table_id(file.table_id)
+            .await
+            .unwrap();
+        let partition = partitions
+            .iter()
+            .find(|p| p.transition_partition_id() == file.partition_id)
+            .unwrap();
+        partition.partition_key.inner().to_string()
+    }
+
+    async fn read_file(importer: &RemoteImporter, file: &ParquetFile) -> Bytes {
+        let path = ParquetFilePath::from(file).object_store_path();
+        importer
+            .object_store
+            .get(&path)
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap()
+    }
+
+    async fn read_rows(importer: &RemoteImporter, file: &ParquetFile) -> RecordBatch {
+        let reader = ParquetRecordBatchReaderBuilder::try_new(read_file(importer, file).await)
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        concat_batches(&batches[0].schema(), &batches).unwrap()
+    }
+
+    fn tags(batch: &RecordBatch, name: &str) -> Vec<Option<String>> {
+        let values = cast(batch.column_by_name(name).unwrap(), &DataType::Utf8).unwrap();
+        values
+            .as_string::<i32>()
+            .iter()
+            .map(|v| v.map(ToString::to_string))
+            .collect()
+    }
+
+    fn floats(batch: &RecordBatch, name: &str) -> Vec<Option<f64>> {
+        batch
+            .column_by_name(name)
+            .unwrap()
+            .as_primitive::<Float64Type>()
+            .iter()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_import_copies_files() {
+        let dir = TempDir::new().unwrap();
+        let object_store_id = write_file(dir.path(), 1, "2023-01-01", &lp(0..3)).await;
+
+        let importer = importer(dir.path());
+        importer.import().await.unwrap();
+
+        let files = imported_files(&importer, "source", "cpu").await;
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].object_store_id, object_store_id);
+        assert_eq!(files[0].row_count, 3);
+        assert_eq!(files[0].min_time, Timestamp::new(T0));
+        assert_eq!(files[0].max_time, Timestamp::new(T0 + 2 * HOUR));
+        assert_eq!(partition_key(&importer, &files[0]).await, "2023-01-01");
+
+        let exported = std::fs::read(dir.path().join(format!("{object_store_id}.1.parquet")));
+        assert_eq!(read_file(&importer, &files[0]).await, exported.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_import_retargets_namespace_and_table() {
+        let dir = TempDir::new().unwrap();
+        let object_store_id = write_file(dir.path(), 1, "2023-01-01", &lp(0..3)).await;
+
+        let importer = importer(dir.path())
+            .with_namespace_name("target")
+            .with_table_name("mem");
+        importer.import().await.unwrap();
+
+        let files = imported_files(&importer, "target", "mem").await;
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].object_store_id, object_store_id);
+
+        let rows = read_rows(&importer, &files[0]).await;
+        assert_eq!(
+            tags(&rows, "host"),
+            [Some("a".to_string()), Some("b".to_string()), None]
+        );
+        assert_eq!(floats(&rows, "usage"), [Some(0.0), None, Some(2.0)]);
+        assert_eq!(floats(&rows, "idle"), [None, Some(1.0), Some(2.0)]);
+
+        let mut repos = importer.catalog.repositories().await;
+        let source = repos
+            .namespaces()
+            .get_by_name("source", SoftDeletedRows::AllRows)
+            .await
+            .unwrap();
+        assert!(source.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_import_table_requires_single_exported_table() {
+        let dir = TempDir::new().unwrap();
+        write_file(dir.path(), 1, "2023-01-01", &lp(0..3)).await;
+        write_file(dir.path(), 2, "2023-01-01", &lp(0..3)).await;
+        std::fs::write(dir.path().join("table.1.json"), "{}").unwrap();
+        std::fs::write(dir.path().join("table.2.json"), "{}").unwrap();
+
+        let err = importer(dir.path())
+            .with_table_name("mem")
+            .import()
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::MultipleTables { count: 2, .. }),
+            "{err}"
+        );
+
+        // Files of another table than the exported one are rejected too
+        std::fs::remove_file(dir.path().join("table.2.json")).unwrap();
+        let err = importer(dir.path())
+            .with_table_name("mem")
+            .import()
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::UnexpectedTable { table_id, .. } if table_id.get() == 2),
+            "{err}"
+        );
+
+        // Without --table, files are imported into the table they name
+        importer(dir.path()).import().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_import_time_range() {
+        let dir = TempDir::new().unwrap();
+        let inside = write_file(dir.path(), 1, "2023-01-01", &lp(0..3)).await;
+        let straddling = write_file(dir.path(), 1, "2023-01-01", &lp(3..9)).await;
+        let outside = write_file(dir.path(), 1, "2023-01-01", &lp(10..12)).await;
+
+        let importer = importer(dir.path()).with_time_range(Some(T0), Some(T0 + 6 * HOUR));
+        let path = |id: Uuid| dir.path().join(format!("{id}.1.parquet"));
+        assert_eq!(
+            importer.import_parquet(&path(inside)).await.unwrap(),
+            ImportOutcome::Copied
+        );
+        assert_eq!(
+            importer.import_parquet(&path(straddling)).await.unwrap(),
+            ImportOutcome::Rewritten
+        );
+        assert_eq!(
+            importer.import_parquet(&path(outside)).await.unwrap(),
+            ImportOutcome::Skipped
+        );
+
+        let files = imported_files(&importer, "source", "cpu").await;
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].object_store_id, inside);
+
+        // Only the rows before the end of the range are kept
+        let key = PartitionKey::from("2023-01-01");
+        assert_eq!(
+            files[1].object_store_id,
+            rewritten_object_store_id(straddling, &key)
+        );
+        assert_eq!(files[1].row_count, 3);
+        assert_eq!(files[1].min_time, Timestamp::new(T0 + 3 * HOUR));
+        assert_eq!(files[1].max_time, Timestamp::new(T0 + 5 * HOUR));
+        assert_eq!(files[1].compaction_level, CompactionLevel::Initial);
+        assert_eq!(files[1].partition_id, files[0].partition_id);
+
+        let rows = read_rows(&importer, &files[1]).await;
+        assert_eq!(
+            tags(&rows, "host"),
+            [Some("a".to_string()), Some("b".to_string()), None]
+        );
+        assert_eq!(floats(&rows, "usage"), [Some(3.0), None, Some(5.0)]);
+        assert_eq!(floats(&rows, "idle"), [None, Some(4.0), Some(5.0)]);
+    }
+
+    #[tokio::test]
+    async fn test_import_repartitions_and_resumes() {
+        let dir = TempDir::new().unwrap();
+        let copied = write_file(
+            dir.path(),
+            1,
+            "a|2023-01-01",
+            &format!("cpu,host=a usage=1 {T0}"),
+        )
+        .await;
+        let copied_path = dir.path().join(format!("{copied}.1.parquet"));
+        // Moved from the default daily partitioning
+        let rewritten = write_file(dir.path(), 1, "2023-01-01", &lp(0..6)).await;
+        let rewritten_path = dir.path().join(format!("{rewritten}.1.parquet"));
+
+        let importer = importer(dir.path()).with_skip_existing(true);
+        {
+            let mut repos = importer.catalog.repositories().await;
+            let namespace_name = NamespaceName::try_from("source").unwrap();
+            let namespace = repos
+                .namespaces()
+                .create(&namespace_name, None, None, None)
+                .await
+                .unwrap();
+            let template = test_table_partition_override(vec![
+                TemplatePart::TagValue("host"),
+                TemplatePart::TimeFormat("%Y-%m-%d"),
+            ]);
+            repos
+                .tables()
+                .create("cpu", template, namespace.id)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            importer.import_parquet(&rewritten_path).await.unwrap(),
+            ImportOutcome::Rewritten
+        );
+        let files = imported_files(&importer, "source", "cpu").await;
+        assert_eq!(files.len(), 3);
+
+        let mut keys = vec![];
+        for file in &files {
+            let key = partition_key(&importer, file).await;
+            assert_eq!(
+                file.object_store_id,
+                rewritten_object_store_id(rewritten, &PartitionKey::from(key.as_str()))
+            );
+            assert_eq!(file.row_count, 2);
+
+            let rows = read_rows(&importer, file).await;
+            keys.push((key, tags(&rows, "host")));
+        }
+        keys.sort();
+        assert_eq!(
+            keys,
+            [
+                ("!|2023-01-01".to_string(), vec![None, None]),
+                (
+                    "a|2023-01-01".to_string(),
+                    vec![Some("a".to_string()), Some("a".to_string())]
+                ),
+                (
+                    "b|2023-01-01".to_string(),
+                    vec![Some("b".to_string()), Some("b".to_string())]
+                ),
+            ]
+        );
+
+        // A file already in the right partition is copied
+        assert_eq!(
+            importer.import_parquet(&copied_path).await.unwrap(),
+            ImportOutcome::Copied
+        );
+
+        // Resuming skips files already imported, whether copied or rewritten
+        assert_eq!(
+            importer.import_parquet(&copied_path).await.unwrap(),
+            ImportOutcome::Skipped
+        );
+        importer.import().await.unwrap();
+        let resumed = imported_files(&importer, "source", "cpu").await;
+        assert_eq!(resumed.len(), 4);
+        assert!(files.iter().all(|file| resumed.contains(file)));
+    }
+
+    #[test]
+    fn test_file_partition_key() {
+        let schema = SchemaBuilder::new()
+            .tag("host")
+            .tag("region")
+            .influx_field("usage", InfluxFieldType::Float)
+            .timestamp()
+            .build()
+            .unwrap();
+        let tag = |name: &str, stats| ColumnSummary {
+            name: name.to_string(),
+            influxdb_type: InfluxDbType::Tag,
+            stats: Statistics::String(stats),
+        };
+        let one_host = tag(
+            "host",
+            StatValues::new(Some("a".to_string()), Some("a".to_string()), 3, Some(0)),
+        );
+        let two_hosts = tag(
+            "host",
+            StatValues::new(Some("a".to_string()), Some("b".to_string()), 3, Some(0)),
+        );
+        let some_null = tag(
+            "host",
+            StatValues::new(Some("a".to_string()), Some("a".to_string()), 3, Some(1)),
+        );
+        let all_null = tag("host", StatValues::new(None, None, 3, Some(3)));
+
+        let day = (Timestamp::new(T0), Timestamp::new(T0 + 23 * HOUR));
+        let two_days = (Timestamp::new(T0), Timestamp::new(T0 + 24 * HOUR));
+        let key = |template: &TablePartitionTemplateOverride, stats: &[ColumnSummary], times| {
+            file_partition_key(template, &schema, stats, times)
+                .unwrap()
+                .map(|key| key.inner().to_string())
+        };
+
+        let by_day = test_table_partition_override(vec![TemplatePart::TimeFormat("%Y-%m-%d")]);
+        assert_eq!(key(&by_day, &[], day).as_deref(), Some("2023-01-01"));
+        assert_eq!(
+            key(&by_day, &[two_hosts.clone()], day).as_deref(),
+            Some("2023-01-01")
+        );
+        assert_eq!(key(&by_day, &[], two_days), None);
+
+        let by_host = test_table_partition_override(vec![
+            TemplatePart::TagValue("host"),
+            TemplatePart::TagValue("region"),
+            TemplatePart::TimeFormat("%Y-%m-%d"),
+        ]);
+        assert_eq!(
+            key(&by_host, &[one_host.clone()], day).as_deref(),
+            Some("a|!|2023-01-01")
+        );
+        assert_eq!(
+            key(&by_host, &[all_null], day).as_deref(),
+            Some("!|!|2023-01-01")
+        );
+        assert_eq!(key(&by_host, &[two_hosts], day), None);
+        assert_eq!(key(&by_host, &[some_null], day), None);
+        assert_eq!(key(&by_host, &[one_host], two_days), None);
+
+        // Only tag columns can be partitioned on
+        let by_field = test_table_partition_override(vec![TemplatePart::TagValue("usage")]);
+        let usage = ColumnSummary {
+            name: "usage".to_string(),
+            influxdb_type: InfluxDbType::Field,
+            stats: Statistics::F64(StatValues::new(Some(1.0), Some(1.0), 3, Some(0))),
+        };
+        assert_eq!(key(&by_field, &[usage], day), None);
+    }
+
+    #[test]
+    fn test_valid_mask() {
+        assert_eq!(valid_mask(&Float64Array::from(vec![1.0, 2.0])), None);
+        assert_eq!(
+            valid_mask(&Float64Array::from(vec![Some(1.0), None, Some(3.0)])),
+            Some(vec![0b101])
+        );
+
+        // Rows beyond the first byte
+        let mut values = vec![None; 10];
+        values[0] = Some("a");
+        values[9] = Some("b");
+        assert_eq!(
+            valid_mask(&StringArray::from(values)),
+            Some(vec![0b1, 0b10])
+        );
+
+        assert_eq!(
+            valid_mask(&StringArray::from(vec![None::<&str>; 3])),
+            Some(vec![0])
+        );
+    }
+
+    #[test]
+    fn test_rewritten_object_store_id() {
+        let object_store_id = Uuid::from_u128(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef);
+        let a = PartitionKey::from("a|2023-01-01");
+        let b = PartitionKey::from("b|2023-01-01");
+
+        // Stable across runs and releases
+        assert_eq!(
+            rewritten_object_store_id(object_store_id, &a),
+            rewritten_object_store_id(object_store_id, &a)
+        );
+        assert_eq!(
+            rewritten_object_store_id(object_store_id, &PartitionKey::from("")),
+            Uuid::from_u128(object_store_id.as_u128() ^ 0xcbf2_9ce4_8422_2325_cbf2_9ce4_8422_2325)
+        );
+
+        // Distinct per partition and source file
+        let ids = [
+            object_store_id,
+            rewritten_object_store_id(object_store_id, &a),
+            rewritten_object_store_id(object_store_id, &b),
+            rewritten_object_store_id(Uuid::from_u128(1), &a),
+        ];
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), ids.len());
+    }
+}
@@ -1,6 +1,13 @@
 //! This module implements the `catalog` CLI command
 
-use clap_blocks::catalog_dsn::CatalogDsnConfig;
+use std::sync::Arc;
+
+use clap_blocks::{
+    catalog_dsn::CatalogDsnConfig,
+    object_store::{make_object_store, ObjectStoreConfig},
+};
+use iox_catalog::transfer::{transfer, verify, TransferError};
+use parquet_file::ParquetFilePath;
 use thiserror::Error;
 
 use crate::process_info::setup_metric_registry;
@@ -13,6 +20,25 @@ pub enum Error {
 
     #[error("Catalog DSN error: {0}")]
     CatalogDsn(#[from] clap_blocks::catalog_dsn::Error),
+
+    #[error("Catalog migration error: {0}")]
+    Transfer(#[from] TransferError),
+
+    #[error("Cannot parse object store config: {0}")]
+    ObjectStoreParsing(#[from] clap_blocks::object_store::ParseError),
+
+    #[error("Cannot copy parquet file {from} to {to}: {source}")]
+    ObjectStore {
+        from: object_store::path::Path,
+        to: object_store::path::Path,
+        source: object_store::Error,
+    },
+
+    #[error(
+        "{0} parquet file(s) changed their object store location, copy them or re-run with \
+         --object-store to have them copied"
+    )]
+    Relocated(usize),
 }
 
 /// Various commands for catalog manipulation
@@ -29,11 +55,47 @@ struct Setup {
     catalog_dsn: CatalogDsnConfig,
 }
 
+/// Copy the content of one catalog into another, empty one
+///
+/// The target catalog is set up (see `catalog setup`) first. After the copy, the content of both
+/// catalogs is compared.
+///
+/// IDs are remapped where the target assigns different ones. If this changes the object store
+/// location of parquet files, they are copied within the object store given by `--object-store`
+/// (and its related options), or listed if no object store is configured.
+///
+/// The copy is not transactional. An interrupted copy is continued by running the command again
+/// with `--resume`, which keeps the rows already copied into the target.
+#[derive(Debug, clap::Parser)]
+struct Migrate {
+    /// Connection string of the catalog to copy from
+    #[clap(long, action)]
+    from: String,
+
+    /// Connection string of the catalog to copy into
+    #[clap(long, action)]
+    to: String,
+
+    /// Continue an interrupted copy into a target catalog that is not empty
+    #[clap(long, action)]
+    resume: bool,
+
+    /// Connection settings for both catalogs (the DSN is ignored)
+    #[clap(flatten)]
+    catalog_dsn: CatalogDsnConfig,
+
+    #[clap(flatten)]
+    object_store_config: ObjectStoreConfig,
+}
+
 /// All possible subcommands for catalog
 #[derive(Debug, clap::Parser)]
 enum Command {
     /// Run database migrations
     Setup(Setup),
+
+    /// Copy the content of one catalog into another
+    Migrate(Migrate),
 }
 
 pub async fn command(config: Config) -> Result<(), Error> {
@@ -44,7 +106,82 @@ pub async fn command(config: Config) -> Result<(), Error> {
             catalog.setup().await?;
             println!("OK");
         }
+        Command::Migrate(command) => migrate(command).await?,
+    }
+
+    Ok(())
+}
+
+async fn migrate(command: Migrate) -> Result<(), Error> {
+    let metrics = setup_metric_registry();
+    let from = CatalogDsnConfig {
+        dsn: Some(command.from),
+        ..command.catalog_dsn.clone()
+    }
+    .get_catalog("cli", Arc::clone(&metrics))
+    .await?;
+    let to = CatalogDsnConfig {
+        dsn: Some(command.to),
+        ..command.catalog_dsn
+    }
+    .get_catalog("cli", metrics)
+    .await?;
+
+    to.setup().await?;
+
+    let report = transfer(from.as_ref(), to.as_ref(), command.resume).await?;
+    println!(
+        "copied {} namespace(s), {} table(s), {} column(s), {} partition(s), {} parquet file(s) \
+         ({} already present) and {} skipped compaction(s), {} ID(s) remapped",
+        report.namespaces,
+        report.tables,
+        report.columns,
+        report.partitions,
+        report.parquet_files,
+        report.existing_parquet_files,
+        report.skipped_compactions,
+        report.ids.n_remapped(),
+    );
+
+    verify(from.as_ref(), to.as_ref(), &report.ids).await?;
+    println!("verified");
+
+    if report.relocated_files.is_empty() {
+        println!("OK");
+        return Ok(());
+    }
+
+    let relocations = report.relocated_files.iter().map(|f| {
+        (
+            ParquetFilePath::from(&f.source).object_store_path(),
+            ParquetFilePath::from(&f.target).object_store_path(),
+        )
+    });
+
+    // without an explicit object store, `make_object_store` would hand out an empty in-memory one
+    if command.object_store_config.object_store.is_none() {
+        for (from, to) in relocations {
+            println!("{from} -> {to}");
+        }
+        return Err(Error::Relocated(report.relocated_files.len()));
+    }
+
+    let object_store = make_object_store(&command.object_store_config)?;
+    for (from, to) in relocations {
+        object_store
+            .copy(&from, &to)
+            .await
+            .map_err(|source| Error::ObjectStore {
+                from: from.clone(),
+                to: to.clone(),
+                source,
+            })?;
     }
+    println!(
+        "copied {} parquet file(s) to their new location",
+        report.relocated_files.len()
+    );
+    println!("OK");
 
     Ok(())
 }
@@ -0,0 +1,88 @@
+//! This module implements the `compactor explain` CLI command
+
+use compactor::{explain_partition, hardcoded_components, PartitionExplanation};
+use data_types::{ParquetFileId, PartitionId};
+use serde_json::{json, Value};
+
+use super::{file_to_json, CompactorRunConfig, Error};
+
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The ID of the partition to explain
+    #[clap(long, action)]
+    partition_id: i64,
+
+    #[clap(flatten)]
+    compactor: CompactorRunConfig,
+}
+
+/// Runs the compactor's partition filters, round planning and file
+/// classification against the partition, printing the decisions as JSON.
+///
+/// Nothing is compacted or committed to the catalog.
+pub async fn command(config: Config) -> Result<(), Error> {
+    let partition_id = PartitionId::new(config.partition_id);
+    let compactor_config = config
+        .compactor
+        .into_partition_compactor_config(partition_id)
+        .await?;
+
+    let explanation = explain_partition(
+        hardcoded_components(&compactor_config),
+        partition_id,
+        compactor_config.df_concurrency.get(),
+    )
+    .await
+    .map_err(Error::Explain)?;
+
+    serde_json::to_writer_pretty(std::io::stdout(), &explanation_to_json(&explanation))?;
+    println!();
+    Ok(())
+}
+
+fn explanation_to_json(explanation: &PartitionExplanation) -> Value {
+    let partition_info = &explanation.partition_info;
+    json!({
+        "partition_id": partition_info.partition_id.get(),
+        "namespace": partition_info.namespace_name,
+        "table": partition_info.table.name,
+        "partition_key": partition_info.partition_key.inner(),
+        "files": explanation.files.iter().map(file_to_json).collect::<Vec<_>>(),
+        "needs_compaction": explanation.needs_compaction,
+        "ranges": explanation
+            .ranges
+            .iter()
+            .map(|range| json!({
+                "op": range.op,
+                "min": range.min,
+                "max": range.max,
+                "cap": range.cap,
+                "branches": range
+                    .branches
+                    .iter()
+                    .map(|branch| json!({
+                        "target_level": branch.target_level as i16,
+                        "reason": branch.reason,
+                        "makes_progress": branch.makes_progress,
+                        "files_to_compact": ids(&branch.files_to_compact),
+                        "files_to_split": branch
+                            .files_to_split
+                            .iter()
+                            .map(|(id, split_times)| json!({
+                                "id": id.get(),
+                                "split_times": split_times,
+                            }))
+                            .collect::<Vec<_>>(),
+                        "files_to_upgrade": ids(&branch.files_to_upgrade),
+                        "files_to_keep": ids(&branch.files_to_keep),
+                    }))
+                    .collect::<Vec<_>>(),
+                "files_for_later": ids(&range.files_for_later),
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn ids(ids: &[ParquetFileId]) -> Vec<i64> {
+    ids.iter().map(|id| id.get()).collect()
+}
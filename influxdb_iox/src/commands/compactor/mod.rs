@@ -0,0 +1,192 @@
+//! This module implements the `compactor` CLI command, running the compactor
+//! against a single partition of the live catalog, or simulating it against a
+//! copy of its files.
+
+use std::sync::Arc;
+
+use clap_blocks::{
+    catalog_dsn::CatalogDsnConfig, compactor::CompactorConfig,
+    compactor_scheduler::CompactorSchedulerType, gossip::GossipConfig, run_config::RunConfig,
+};
+use data_types::{ParquetFile, PartitionId};
+use iox_catalog::interface::Catalog;
+use iox_time::SystemProvider;
+use ioxd_common::server_type::{CommonServerState, CommonServerStateError};
+use ioxd_compactor::create_compactor_config;
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use super::run::compactor::{create_compactor_dependencies, CompactorDependencies};
+use crate::process_info::setup_metric_registry;
+
+mod explain;
+mod run_once;
+#[cfg(feature = "compactor_simulate")]
+mod simulate;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Invalid config: {0}")]
+    InvalidConfig(#[from] CommonServerStateError),
+
+    #[error("Cannot set up compactor: {0}")]
+    Setup(#[from] super::run::compactor::Error),
+
+    #[error("Catalog error: {0}")]
+    Catalog(#[from] iox_catalog::interface::Error),
+
+    #[cfg(feature = "compactor_simulate")]
+    #[error("Catalog DSN error: {0}")]
+    CatalogDsn(#[from] clap_blocks::catalog_dsn::Error),
+
+    #[error("Partition {0} not found")]
+    PartitionNotFound(PartitionId),
+
+    #[error("Cannot explain partition: {0}")]
+    Explain(compactor::DynError),
+
+    #[error("Compactor task failed: {0}")]
+    Compactor(Arc<tokio::task::JoinError>),
+
+    #[error("JSON Serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[cfg(feature = "compactor_simulate")]
+    #[error("Cannot read file list: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[cfg(feature = "compactor_simulate")]
+    #[error("Invalid file in file list: {0}")]
+    InvalidFile(String),
+}
+
+/// Various commands running the compactor against a single partition
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// All possible subcommands for compactor
+#[derive(Debug, clap::Parser)]
+enum Command {
+    /// Print the decisions the compactor makes for the next round of
+    /// compacting a partition, without compacting it
+    Explain(explain::Config),
+
+    /// Compact a single partition until the compactor considers it done,
+    /// then exit
+    RunOnce(run_once::Config),
+
+    /// Simulate compacting a partition's files with the given compactor
+    /// settings, reporting the resulting file layout, the bytes rewritten
+    /// and the number of rounds, without reading or writing any data
+    #[cfg(feature = "compactor_simulate")]
+    Simulate(simulate::Config),
+}
+
+pub async fn command(config: Config) -> Result<(), Error> {
+    match config.command {
+        Command::Explain(config) => explain::command(config).await,
+        Command::RunOnce(config) => run_once::command(config).await,
+        #[cfg(feature = "compactor_simulate")]
+        Command::Simulate(config) => simulate::command(config).await,
+    }
+}
+
+/// The compactor configuration, taking the same options as `influxdb_iox run
+/// compactor`.
+#[derive(Debug, clap::Parser)]
+struct CompactorRunConfig {
+    #[clap(flatten)]
+    run_config: RunConfig,
+
+    #[clap(flatten)]
+    catalog_dsn: CatalogDsnConfig,
+
+    #[clap(flatten)]
+    compactor_config: CompactorConfig,
+}
+
+impl CompactorRunConfig {
+    /// Build the configuration of a compactor that processes `partition_id`
+    /// once and exits.
+    ///
+    /// Sharding is ignored, so the partition is processed whichever shard it
+    /// belongs to, and gossip is disabled so the command does not announce
+    /// itself to the cluster.
+    async fn into_partition_compactor_config(
+        mut self,
+        partition_id: PartitionId,
+    ) -> Result<compactor::config::Config, Error> {
+        let common_state = CommonServerState::from_config(self.run_config.clone())?;
+        let metric_registry = setup_metric_registry();
+        let CompactorDependencies {
+            catalog,
+            parquet_store_real,
+            parquet_store_scratchpad,
+            exec,
+        } = create_compactor_dependencies(
+            &self.run_config,
+            &self.catalog_dsn,
+            &self.compactor_config,
+            &metric_registry,
+        )
+        .await?;
+
+        let compactor_config = &mut self.compactor_config;
+        compactor_config.process_once = true;
+        compactor_config.gossip_config = GossipConfig::disabled();
+
+        let scheduler_config = &mut compactor_config.compactor_scheduler_config;
+        scheduler_config.compactor_scheduler_type = CompactorSchedulerType::Local;
+        scheduler_config.partition_source_config.partition_filter = Some(vec![partition_id.get()]);
+        scheduler_config
+            .partition_source_config
+            .process_all_partitions = false;
+        scheduler_config.shard_config = Default::default();
+
+        Ok(create_compactor_config(
+            &common_state,
+            metric_registry,
+            catalog,
+            parquet_store_real,
+            parquet_store_scratchpad,
+            exec,
+            Arc::new(SystemProvider::new()),
+            self.compactor_config,
+        ))
+    }
+}
+
+/// Summarise a Parquet file the way the compactor sees it.
+fn file_to_json(file: &ParquetFile) -> Value {
+    json!({
+        "id": file.id.get(),
+        "compaction_level": file.compaction_level as i16,
+        "min_time": file.min_time.get(),
+        "max_time": file.max_time.get(),
+        "file_size_bytes": file.file_size_bytes,
+        "row_count": file.row_count,
+        "created_at": file.created_at.get(),
+        "max_l0_created_at": file.max_l0_created_at.get(),
+    })
+}
+
+/// The files of the partition that are not marked for deletion.
+async fn partition_files(
+    catalog: &dyn Catalog,
+    partition_id: PartitionId,
+) -> Result<Vec<ParquetFile>, Error> {
+    let mut repos = catalog.repositories().await;
+    let partition = repos
+        .partitions()
+        .get_by_id(partition_id)
+        .await?
+        .ok_or(Error::PartitionNotFound(partition_id))?;
+
+    Ok(repos
+        .parquet_files()
+        .list_by_partition_not_to_delete(&partition.transition_partition_id())
+        .await?)
+}
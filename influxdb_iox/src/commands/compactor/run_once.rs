@@ -0,0 +1,58 @@
+//! This module implements the `compactor run-once` CLI command
+
+use std::sync::Arc;
+
+use compactor::compactor::Compactor;
+use data_types::PartitionId;
+use serde_json::json;
+
+use super::{file_to_json, partition_files, CompactorRunConfig, Error};
+
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The ID of the partition to compact
+    #[clap(long, action)]
+    partition_id: i64,
+
+    #[clap(flatten)]
+    compactor: CompactorRunConfig,
+}
+
+/// Compacts the partition with the compactor's process-once mode restricted
+/// to it, then prints its files before and after as JSON, along with the
+/// reason it was skipped, if it was.
+///
+/// Partitions marked as skipped are not compacted unless
+/// `--compaction-ignore-partition-skip-marker` is set.
+pub async fn command(config: Config) -> Result<(), Error> {
+    let partition_id = PartitionId::new(config.partition_id);
+    let compactor_config = config
+        .compactor
+        .into_partition_compactor_config(partition_id)
+        .await?;
+    let catalog = Arc::clone(&compactor_config.catalog);
+
+    let files_before = partition_files(catalog.as_ref(), partition_id).await?;
+
+    let compactor = Compactor::start(compactor_config).await;
+    compactor.join().await.map_err(Error::Compactor)?;
+
+    let files_after = partition_files(catalog.as_ref(), partition_id).await?;
+    let skipped_compaction = catalog
+        .repositories()
+        .await
+        .partitions()
+        .get_in_skipped_compactions(&[partition_id])
+        .await?
+        .pop();
+
+    let summary = json!({
+        "partition_id": partition_id.get(),
+        "files_before": files_before.iter().map(file_to_json).collect::<Vec<_>>(),
+        "files_after": files_after.iter().map(file_to_json).collect::<Vec<_>>(),
+        "skipped_reason": skipped_compaction.map(|skipped| skipped.reason),
+    });
+    serde_json::to_writer_pretty(std::io::stdout(), &summary)?;
+    println!();
+    Ok(())
+}
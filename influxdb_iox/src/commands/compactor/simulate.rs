@@ -0,0 +1,250 @@
+//! This module implements the `compactor simulate` CLI command
+
+use std::{fs::File, io::BufReader, path::PathBuf, sync::atomic::Ordering};
+
+use clap_blocks::{catalog_dsn::CatalogDsnConfig, compactor::CompactorConfig};
+use compactor_test_utils::{display_size, format_files, TestSetup};
+use data_types::{CompactionLevel, ParquetFile, PartitionId};
+use iox_tests::TestParquetFileBuilder;
+use iox_time::Time;
+use serde_json::Value;
+
+use super::{partition_files, Error};
+use crate::process_info::setup_metric_registry;
+
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The ID of the partition to read the files of from the catalog
+    #[clap(
+        long,
+        action,
+        conflicts_with = "input",
+        required_unless_present = "input"
+    )]
+    partition_id: Option<i64>,
+
+    /// A JSON file listing the files of the partition, either as an array or
+    /// as the output of `influxdb_iox compactor explain`
+    #[clap(long, short, action)]
+    input: Option<PathBuf>,
+
+    /// Print every compaction, split and commit of the simulation
+    #[clap(long, action)]
+    show_runs: bool,
+
+    #[clap(flatten)]
+    catalog_dsn: CatalogDsnConfig,
+
+    #[clap(flatten)]
+    compactor_config: CompactorConfig,
+}
+
+/// Compacts a copy of the partition's files in an in-memory catalog, using
+/// the compactor's layout simulator in place of reading and writing Parquet
+/// data, then prints the input and output file layouts.
+///
+/// The copies are numbered in the order of the original file IDs.
+pub async fn command(config: Config) -> Result<(), Error> {
+    let mut files = match (config.partition_id, &config.input) {
+        (Some(partition_id), _) => {
+            let catalog = config
+                .catalog_dsn
+                .get_catalog("compactor_simulate", setup_metric_registry())
+                .await?;
+            partition_files(catalog.as_ref(), PartitionId::new(partition_id))
+                .await?
+                .iter()
+                .map(SimulatedFile::from)
+                .collect()
+        }
+        (None, Some(input)) => {
+            json_files(serde_json::from_reader(BufReader::new(File::open(input)?))?)?
+        }
+        (None, None) => unreachable!("clap requires a partition ID or input file"),
+    };
+
+    let compactor_config = &config.compactor_config;
+    let setup = TestSetup::builder()
+        .await
+        .with_max_desired_file_size_bytes(compactor_config.max_desired_file_size_bytes)
+        .with_percentage_max_file_size(compactor_config.percentage_max_file_size)
+        .with_split_percentage(compactor_config.split_percentage)
+        .with_max_num_files_per_plan(compactor_config.max_num_files_per_plan)
+        .with_min_num_l1_files_to_compact(compactor_config.min_num_l1_files_to_compact)
+        .simulate_without_object_store()
+        .build()
+        .await;
+
+    files.sort_by_key(|file| file.id);
+    for file in files {
+        setup
+            .partition
+            .create_parquet_file_catalog_record(file.into_builder())
+            .await;
+    }
+
+    let input_files = setup.list_by_table_not_to_delete().await;
+    let input_bytes: i64 = input_files.iter().map(|f| f.file_size_bytes).sum();
+    let mut output = format_files("**** Input Files", &input_files);
+
+    let result = setup.run_compact().await;
+    if config.show_runs {
+        output.extend(result.run_log);
+    }
+
+    let skipped = setup
+        .catalog
+        .catalog
+        .repositories()
+        .await
+        .partitions()
+        .list_skipped_compactions()
+        .await?;
+    output.extend(
+        skipped
+            .iter()
+            .map(|skipped| format!("SKIPPED COMPACTION: {}", skipped.reason)),
+    );
+
+    let bytes_written = setup.bytes_written.load(Ordering::Relaxed) as i64;
+    let mut output_files = setup.list_by_table_not_to_delete().await;
+    output_files.sort_by_key(|f| f.id);
+    output.extend(format_files(
+        format!(
+            "**** Final Output Files ({} written)",
+            display_size(bytes_written)
+        ),
+        &output_files,
+    ));
+
+    output.push(format!("Rounds: {}", result.rounds));
+    output.push(format!(
+        "Bytes rewritten: {} ({:.1}x the input size of {})",
+        display_size(bytes_written),
+        bytes_written as f64 / input_bytes.max(1) as f64,
+        display_size(input_bytes),
+    ));
+
+    for line in output {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+/// The attributes of a Parquet file the compactor makes its decisions on.
+#[derive(Debug)]
+struct SimulatedFile {
+    id: i64,
+    compaction_level: CompactionLevel,
+    min_time: i64,
+    max_time: i64,
+    file_size_bytes: i64,
+    row_count: i64,
+    created_at: i64,
+    max_l0_created_at: i64,
+}
+
+impl From<&ParquetFile> for SimulatedFile {
+    fn from(file: &ParquetFile) -> Self {
+        Self {
+            id: file.id.get(),
+            compaction_level: file.compaction_level,
+            min_time: file.min_time.get(),
+            max_time: file.max_time.get(),
+            file_size_bytes: file.file_size_bytes,
+            row_count: file.row_count,
+            created_at: file.created_at.get(),
+            max_l0_created_at: file.max_l0_created_at.get(),
+        }
+    }
+}
+
+impl SimulatedFile {
+    fn into_builder(self) -> TestParquetFileBuilder {
+        TestParquetFileBuilder::default()
+            .with_compaction_level(self.compaction_level)
+            .with_min_time(self.min_time)
+            .with_max_time(self.max_time)
+            .with_file_size_bytes(self.file_size_bytes as u64)
+            .with_row_count(self.row_count as usize)
+            .with_creation_time(Time::from_timestamp_nanos(self.created_at))
+            .with_max_l0_created_at(Time::from_timestamp_nanos(self.max_l0_created_at))
+    }
+}
+
+/// Reads the files from a JSON array of files, or from the "files" of an
+/// object such as the output of `compactor explain`.
+fn json_files(value: Value) -> Result<Vec<SimulatedFile>, Error> {
+    let files = match &value {
+        Value::Array(files) => files,
+        Value::Object(object) => object
+            .get("files")
+            .and_then(Value::as_array)
+            .ok_or_else(|| Error::InvalidFile("expected a \"files\" array".to_string()))?,
+        _ => {
+            return Err(Error::InvalidFile(
+                "expected an array of files or an object with a \"files\" array".to_string(),
+            ))
+        }
+    };
+
+    files
+        .iter()
+        .enumerate()
+        .map(|(i, file)| {
+            let field = |name: &str| {
+                file.get(name).and_then(Value::as_i64).ok_or_else(|| {
+                    Error::InvalidFile(format!("file {i} has no integer \"{name}\""))
+                })
+            };
+            let max_l0_created_at = field("max_l0_created_at")?;
+
+            Ok(SimulatedFile {
+                id: field("id").unwrap_or(i as i64),
+                compaction_level: CompactionLevel::try_from(field("compaction_level")? as i32)
+                    .map_err(|e| Error::InvalidFile(format!("file {i}: {e}")))?,
+                min_time: field("min_time")?,
+                max_time: field("max_time")?,
+                file_size_bytes: field("file_size_bytes")?,
+                row_count: field("row_count")?,
+                // optional, as it does not affect the compactor's decisions
+                created_at: field("created_at").unwrap_or(max_l0_created_at),
+                max_l0_created_at,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_json_files() {
+        let file = json!({
+            "id": 7,
+            "compaction_level": 1,
+            "min_time": 10,
+            "max_time": 20,
+            "file_size_bytes": 1024,
+            "row_count": 3,
+            "max_l0_created_at": 5,
+        });
+
+        let files = json_files(json!({ "files": [file.clone()] })).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].id, 7);
+        assert_eq!(
+            files[0].compaction_level,
+            CompactionLevel::FileNonOverlapped
+        );
+        assert_eq!(files[0].created_at, 5);
+
+        assert_eq!(json_files(json!([file])).unwrap().len(), 1);
+
+        let err = json_files(json!([{ "id": 1 }])).unwrap_err();
+        assert!(err.to_string().contains("compaction_level"), "{err}");
+    }
+}
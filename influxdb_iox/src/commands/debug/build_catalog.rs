@@ -1,6 +1,7 @@
 //! This module implements the `build_catalog` CLI command
 use import_export::file::{ExportedContents, RemoteImporter};
 use iox_catalog::interface::Catalog;
+use iox_time::Time;
 use metric::Registry;
 use object_store::ObjectStore;
 use observability_deps::tracing::info;
@@ -52,12 +53,54 @@ pub struct Config {
     /// `influxdb_iox --data-dir <dir>`.
     #[clap(value_parser)]
     pub data_dir: PathBuf,
+
+    /// Import into this namespace rather than the one the data was
+    /// exported from
+    #[clap(long = "namespace", action)]
+    namespace_name: Option<String>,
+
+    /// Import into this table rather than the one the data was exported
+    /// from. The export must contain a single table. Files are
+    /// re-partitioned if the table's partition template differs from the
+    /// exported one.
+    #[clap(long = "table", action)]
+    table_name: Option<String>,
+
+    /// Only import rows at or after this time, as an RFC3339 timestamp or
+    /// nanoseconds since the epoch
+    #[clap(long, value_parser = parse_timestamp)]
+    start: Option<i64>,
+
+    /// Only import rows before this time, as an RFC3339 timestamp or
+    /// nanoseconds since the epoch
+    #[clap(long, value_parser = parse_timestamp)]
+    end: Option<i64>,
+
+    /// Skip files already present in the target catalog, to resume an
+    /// interrupted import
+    #[clap(long, action)]
+    skip_existing: bool,
+}
+
+/// Accepts either an RFC3339 timestamp or nanoseconds since the epoch.
+fn parse_timestamp(value: &str) -> Result<i64, String> {
+    if let Ok(nanos) = value.parse::<i64>() {
+        return Ok(nanos);
+    }
+    Time::from_rfc3339(value)
+        .map(|t| t.timestamp_nanos())
+        .map_err(|e| format!("expected an RFC3339 timestamp or nanoseconds: {e}"))
 }
 
 pub async fn command(config: Config) -> Result<(), Error> {
     let Config {
         input_dir,
         data_dir,
+        namespace_name,
+        table_name,
+        start,
+        end,
+        skip_existing,
     } = config;
 
     let exported_contents = ExportedContents::try_new(&input_dir)?;
@@ -70,7 +113,15 @@ pub async fn command(config: Config) -> Result<(), Error> {
 
     info!("Initialized catalog, object store, and input path ...");
 
-    let importer = RemoteImporter::new(exported_contents, catalog, object_store);
+    let mut importer = RemoteImporter::new(exported_contents, catalog, object_store)
+        .with_time_range(start, end)
+        .with_skip_existing(skip_existing);
+    if let Some(namespace_name) = namespace_name {
+        importer = importer.with_namespace_name(namespace_name);
+    }
+    if let Some(table_name) = table_name {
+        importer = importer.with_table_name(table_name);
+    }
 
     info!(
         ?input_dir,
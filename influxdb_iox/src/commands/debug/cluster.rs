@@ -0,0 +1,82 @@
+//! This module implements the `debug cluster` CLI command
+
+use comfy_table::{Cell, Table};
+use influxdb_iox_client::{
+    cluster::{
+        self,
+        generated_types::{ClusterMember, Role},
+    },
+    connection::Connection,
+};
+use iox_time::Time;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("JSON Serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error("Client error: {0}")]
+    Client(#[from] influxdb_iox_client::error::Error),
+}
+
+/// Show the cluster members observed by the connected node via gossip
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// Output the members as JSON instead of a table
+    #[clap(long, action)]
+    json: bool,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
+    let mut client = cluster::Client::new(connection);
+    let members = client.get_cluster_members().await?;
+
+    if config.json {
+        println!("{}", serde_json::to_string_pretty(&members)?);
+    } else {
+        println!("{}", create_table(&members));
+    }
+
+    Ok(())
+}
+
+/// Turn cluster member records into a table
+fn create_table(members: &[ClusterMember]) -> Table {
+    let mut table = Table::new();
+    table.load_preset("||--+-++|    ++++++");
+
+    let headers: Vec<_> = [
+        "identity",
+        "role",
+        "rpc_address",
+        "version",
+        "started_at",
+        "last_seen",
+        "alive",
+        "local",
+    ]
+    .into_iter()
+    .map(Cell::new)
+    .collect();
+    table.set_header(headers);
+
+    for member in members {
+        let role = Role::try_from(member.role)
+            .map(|r| r.as_str_name())
+            .unwrap_or("UNKNOWN");
+
+        table.add_row(vec![
+            Cell::new(&member.identity),
+            Cell::new(role),
+            Cell::new(&member.rpc_address),
+            Cell::new(&member.version),
+            Cell::new(Time::from_timestamp_nanos(member.started_at_ns).to_rfc3339()),
+            Cell::new(Time::from_timestamp_nanos(member.last_seen_ns).to_rfc3339()),
+            Cell::new(member.alive.to_string()),
+            Cell::new(member.local.to_string()),
+        ]);
+    }
+
+    table
+}
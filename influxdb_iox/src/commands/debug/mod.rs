@@ -3,6 +3,7 @@ use influxdb_iox_client::connection::Connection;
 use snafu::prelude::*;
 
 mod build_catalog;
+mod cluster;
 mod parquet_to_lp;
 mod print_cpu;
 mod schema;
@@ -19,6 +20,10 @@ pub enum Error {
     #[snafu(display("Error in build_catalog subcommand: {}", source))]
     BuildCatalog { source: build_catalog::Error },
 
+    #[snafu(context(false))]
+    #[snafu(display("Error in cluster subcommand: {}", source))]
+    Cluster { source: cluster::Error },
+
     #[snafu(context(false))]
     #[snafu(display("Error in parquet_to_lp subcommand: {}", source))]
     ParquetToLp { source: parquet_to_lp::Error },
@@ -66,6 +71,10 @@ enum Command {
     #[clap(verbatim_doc_comment)]
     BuildCatalog(build_catalog::Config),
 
+    /// Show the cluster members (routers, ingesters, queriers and
+    /// compactors) observed by the connected node via gossip
+    Cluster(cluster::Config),
+
     /// Convert IOx Parquet files back into line protocol format
     ParquetToLp(parquet_to_lp::Config),
 
@@ -88,6 +97,10 @@ where
             schema::command(connection, config).await?
         }
         Command::BuildCatalog(config) => build_catalog::command(config).await?,
+        Command::Cluster(config) => {
+            let connection = connection().await;
+            cluster::command(connection, config).await?
+        }
         Command::ParquetToLp(config) => parquet_to_lp::command(config).await?,
         Command::SkippedCompactions(config) => {
             let connection = connection().await;
@@ -0,0 +1,219 @@
+//! A module providing a CLI command to dump the writes in a WAL file as Arrow
+//! or Parquet files, so they can be queried with DataFusion.
+use std::{collections::BTreeMap, fs::File, path::PathBuf, sync::Arc};
+
+use arrow::{
+    array::{new_null_array, ArrayRef, UInt64Array},
+    datatypes::{DataType, Field, Schema},
+    record_batch::RecordBatch,
+};
+use data_types::TableId;
+use generated_types::influxdata::iox::wal::v1::sequenced_wal_op::Op as WalOp;
+use mutable_batch_pb::decode::decode_database_batch;
+use observability_deps::tracing::{info, warn};
+use parquet::arrow::ArrowWriter;
+use schema::Projection;
+
+use super::Error;
+
+/// The file format of the dumped writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Apache Parquet
+    Parquet,
+    /// Arrow IPC file
+    Arrow,
+}
+
+impl Format {
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Parquet => "parquet",
+            Self::Arrow => "arrow",
+        }
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The path to the input WAL file
+    #[clap(value_parser)]
+    input: PathBuf,
+
+    /// The directory to write the files to, one per table named
+    /// "namespace_id_<id>_table_id_<id>". Creates the directory if it does
+    /// not exist.
+    #[clap(long, short, value_parser)]
+    output_directory: PathBuf,
+
+    /// The file format to write
+    #[clap(long, short, value_enum, default_value_t = Format::Parquet)]
+    format: Format,
+}
+
+/// Executes the `dump` command, writing the rows of every write in the WAL
+/// file along with the "_segment_id", "_entry" and "_sequence_number" they
+/// were written with.
+///
+/// Reading stops at the first corrupt or truncated entry, dumping the writes
+/// before it.
+pub fn command(config: Config) -> Result<(), Error> {
+    let reader = wal::ClosedSegmentFileReader::from_path(&config.input)
+        .map_err(Error::UnableToReadWalFile)?;
+    let segment_id = reader.id().get();
+
+    let mut tables = BTreeMap::<(i64, i64), Vec<RecordBatch>>::new();
+    for (entry, ops) in reader.enumerate() {
+        let ops = match ops {
+            Ok(ops) => ops,
+            Err(e) => {
+                warn!(%entry, %e, "failed to read WAL entry, dumping writes read so far");
+                break;
+            }
+        };
+
+        for op in ops {
+            let WalOp::Write(write) = op.op else {
+                continue;
+            };
+
+            for (table_id, batch) in decode_database_batch(&write)? {
+                let sequence_number = op
+                    .table_write_sequence_numbers
+                    .get(&TableId::new(table_id))
+                    .copied();
+                let batch = with_op_columns(
+                    batch.to_arrow(Projection::All)?,
+                    segment_id,
+                    entry as u64,
+                    sequence_number,
+                )?;
+                tables
+                    .entry((write.database_id, table_id))
+                    .or_default()
+                    .push(batch);
+            }
+        }
+    }
+
+    std::fs::create_dir_all(&config.output_directory)?;
+    for ((namespace_id, table_id), batches) in tables {
+        let file_path = config.output_directory.join(format!(
+            "namespace_id_{namespace_id}_table_id_{table_id}.{}",
+            config.format.extension()
+        ));
+        info!(?file_path, %namespace_id, %table_id, "writing table rows");
+
+        let (schema, batches) = unify_batches(batches)?;
+        let file = File::create(&file_path)?;
+        match config.format {
+            Format::Parquet => {
+                let mut writer = ArrowWriter::try_new(file, schema, None)?;
+                for batch in &batches {
+                    writer.write(batch)?;
+                }
+                writer.close()?;
+            }
+            Format::Arrow => {
+                let mut writer = arrow::ipc::writer::FileWriter::try_new(file, &schema)?;
+                for batch in &batches {
+                    writer.write(batch)?;
+                }
+                writer.finish()?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Appends the columns identifying the WAL op a batch was read from.
+fn with_op_columns(
+    batch: RecordBatch,
+    segment_id: u64,
+    entry: u64,
+    sequence_number: Option<u64>,
+) -> Result<RecordBatch, Error> {
+    let rows = batch.num_rows();
+    let mut fields = batch.schema().fields().iter().cloned().collect::<Vec<_>>();
+    fields.extend([
+        Arc::new(Field::new("_segment_id", DataType::UInt64, false)),
+        Arc::new(Field::new("_entry", DataType::UInt64, false)),
+        Arc::new(Field::new("_sequence_number", DataType::UInt64, true)),
+    ]);
+
+    let mut columns = batch.columns().to_vec();
+    columns.extend([
+        Arc::new(UInt64Array::from(vec![segment_id; rows])) as ArrayRef,
+        Arc::new(UInt64Array::from(vec![entry; rows])),
+        Arc::new(UInt64Array::from(vec![sequence_number; rows])),
+    ]);
+
+    let schema = Schema::new_with_metadata(fields, batch.schema().metadata().clone());
+    Ok(RecordBatch::try_new(Arc::new(schema), columns)?)
+}
+
+/// Columns are added to a table over time, so conform `batches` to the union
+/// of their schemas, filling in missing columns with nulls.
+fn unify_batches(batches: Vec<RecordBatch>) -> Result<(Arc<Schema>, Vec<RecordBatch>), Error> {
+    let merged = Schema::try_merge(batches.iter().map(|batch| batch.schema().as_ref().clone()))?;
+    let fields = merged
+        .fields()
+        .iter()
+        .map(|field| {
+            if batches
+                .iter()
+                .all(|batch| batch.column_by_name(field.name()).is_some())
+            {
+                Arc::clone(field)
+            } else {
+                Arc::new(field.as_ref().clone().with_nullable(true))
+            }
+        })
+        .collect::<Vec<_>>();
+    let schema = Arc::new(Schema::new_with_metadata(fields, merged.metadata().clone()));
+
+    let batches = batches
+        .into_iter()
+        .map(|batch| {
+            let columns = schema
+                .fields()
+                .iter()
+                .map(|field| match batch.column_by_name(field.name()) {
+                    Some(column) => Arc::clone(column),
+                    None => new_null_array(field.data_type(), batch.num_rows()),
+                })
+                .collect();
+            RecordBatch::try_new(Arc::clone(&schema), columns)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((schema, batches))
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Int64Array, StringArray};
+
+    use super::*;
+
+    #[test]
+    fn test_unify_batches() {
+        let a = RecordBatch::try_from_iter([(
+            "time",
+            Arc::new(Int64Array::from(vec![1, 2])) as ArrayRef,
+        )])
+        .unwrap();
+        let b = RecordBatch::try_from_iter([
+            ("time", Arc::new(Int64Array::from(vec![3])) as ArrayRef),
+            ("tag", Arc::new(StringArray::from(vec!["x"])) as ArrayRef),
+        ])
+        .unwrap();
+
+        let (schema, batches) = unify_batches(vec![a, b]).unwrap();
+        assert_eq!(schema.fields().len(), 2);
+        assert_eq!(batches[0].num_columns(), 2);
+        assert_eq!(batches[0].column(1).null_count(), 2);
+        assert_eq!(batches[1].column(1).null_count(), 0);
+    }
+}
@@ -5,8 +5,11 @@ use futures::Future;
 use influxdb_iox_client::connection::Connection;
 use thiserror::Error;
 
+mod dump;
 mod inspect;
 mod regenerate_lp;
+mod repair;
+mod stats;
 
 /// A command level error type to decorate WAL errors with some extra
 /// "human" context for the user
@@ -31,6 +34,21 @@ pub enum Error {
 
     #[error("errors occurred during inspection of the WAL file: {sources:?}")]
     IncompleteInspection { sources: Vec<wal::Error> },
+
+    #[error("failed to decode table batch from the WAL file: {0}")]
+    FailedToDecodeTableBatch(#[from] mutable_batch_pb::decode::Error),
+
+    #[error("failed to convert table batch to arrow: {0}")]
+    FailedToConvertTableBatch(#[from] mutable_batch::Error),
+
+    #[error("arrow failure: {0}")]
+    ArrowFailure(#[from] arrow::error::ArrowError),
+
+    #[error("parquet failure: {0}")]
+    ParquetFailure(#[from] parquet::errors::ParquetError),
+
+    #[error("failed to serialise WAL statistics: {0}")]
+    JsonFailure(#[from] serde_json::Error),
 }
 
 /// A set of non-fatal errors which can occur during the regeneration of write
@@ -58,6 +76,17 @@ enum Command {
     /// looking up measurement names from IOx, the target host must implement
     /// the namespace and schema APIs
     RegenerateLp(regenerate_lp::Config),
+    /// Summarise the contents of WAL files as JSON: the sequence number range,
+    /// per namespace and table row counts, byte counts and time ranges, and
+    /// any corrupt or truncated entry of each segment
+    Stats(stats::Config),
+    /// Dump the writes in a WAL file as one Arrow or Parquet file per table,
+    /// annotated with the segment, entry and sequence number of each write
+    Dump(dump::Config),
+    /// Repair a corrupt or truncated WAL file, replacing it with a file
+    /// containing only its readable entries. The original file is kept, and
+    /// the sequence numbers lost are reported as JSON
+    Repair(repair::Config),
 }
 
 /// Executes a WAL debugging subcommand as directed by the config
@@ -69,5 +98,8 @@ where
     match config.command {
         Command::Inspect(config) => inspect::command(config),
         Command::RegenerateLp(config) => regenerate_lp::command(connection, config).await,
+        Command::Stats(config) => stats::command(config),
+        Command::Dump(config) => dump::command(config),
+        Command::Repair(config) => repair::command(config),
     }
 }
@@ -0,0 +1,71 @@
+//! A module providing a CLI command to repair a corrupt or truncated WAL file.
+use std::path::PathBuf;
+
+use serde_json::json;
+use wal::{RepairReport, SegmentRepair};
+
+use super::Error;
+
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The path to the input WAL file
+    #[clap(value_parser)]
+    input: PathBuf,
+
+    /// The directory to move the original WAL file to. Defaults to the
+    /// "quarantine" directory alongside the input file, which the ingester
+    /// ignores.
+    #[clap(long, short, value_parser)]
+    quarantine_directory: Option<PathBuf>,
+
+    /// Report the corrupt regions of the WAL file without modifying it
+    #[clap(long, action)]
+    dry_run: bool,
+}
+
+/// Executes the `repair` command, replacing the WAL file with one containing
+/// only its readable entries and printing a JSON report of the corrupt
+/// regions dropped and the sequence numbers lost with them.
+pub fn command(config: Config) -> Result<(), Error> {
+    let repair = SegmentRepair::new(&config.input).map_err(Error::UnableToReadWalFile)?;
+
+    let report = if config.dry_run {
+        repair.report().clone()
+    } else {
+        let quarantine_directory = config.quarantine_directory.unwrap_or_else(|| {
+            config
+                .input
+                .parent()
+                .map(|dir| dir.join("quarantine"))
+                .unwrap_or_else(|| PathBuf::from("quarantine"))
+        });
+        repair
+            .apply(&quarantine_directory)
+            .map_err(Error::UnableToReadWalFile)?
+    };
+
+    serde_json::to_writer_pretty(std::io::stdout(), &report_to_json(&report))?;
+    println!();
+    Ok(())
+}
+
+fn report_to_json(report: &RepairReport) -> serde_json::Value {
+    json!({
+        "segment_id": report.segment_id.get(),
+        "salvaged_entries": report.salvaged_entries,
+        "corrupt_regions": report
+            .corrupt_regions
+            .iter()
+            .map(|region| json!({
+                "offset": region.offset,
+                "len": region.len,
+                "lost_sequence_numbers": {
+                    "after": region.lost_sequence_numbers.after,
+                    "before": region.lost_sequence_numbers.before,
+                    "range": region.lost_sequence_numbers.to_string(),
+                },
+            }))
+            .collect::<Vec<_>>(),
+        "quarantined_path": report.quarantined_path,
+    })
+}
@@ -0,0 +1,332 @@
+//! A module providing a CLI command to summarise the contents of WAL files as
+//! JSON.
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use data_types::TableId;
+use generated_types::influxdata::{
+    iox::wal::v1::sequenced_wal_op::Op as WalOp, pbdata::v1::TableBatch,
+};
+use prost::Message;
+use serde_json::{json, Value};
+use wal::SequencedWalOp;
+
+use super::Error;
+
+/// The file extension of WAL segment files.
+const SEGMENT_FILE_EXTENSION: &str = "dat";
+
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The paths to the input WAL files, or to directories containing them
+    #[clap(value_parser, required = true)]
+    inputs: Vec<PathBuf>,
+}
+
+pub fn command(config: Config) -> Result<(), Error> {
+    let segments = segment_files(&config.inputs)?
+        .iter()
+        .map(|path| segment_stats(path))
+        .collect::<Vec<_>>();
+
+    serde_json::to_writer_pretty(std::io::stdout(), &segments)?;
+    println!();
+    Ok(())
+}
+
+/// Expands any directories in `inputs` into the segment files they contain.
+fn segment_files(inputs: &[PathBuf]) -> Result<Vec<PathBuf>, Error> {
+    let mut files = vec![];
+    for input in inputs {
+        if !input.is_dir() {
+            files.push(input.clone());
+            continue;
+        }
+
+        let mut dir_files = std::fs::read_dir(input)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        dir_files.retain(|path| {
+            path.extension()
+                .map_or(false, |ext| ext == SEGMENT_FILE_EXTENSION)
+        });
+        dir_files.sort();
+        files.extend(dir_files);
+    }
+    Ok(files)
+}
+
+/// Reads the segment file at `path`, returning its summary. Failing to open
+/// the file is reported in the summary rather than aborting, so that one bad
+/// file doesn't hide the contents of the rest.
+fn segment_stats(path: &Path) -> Value {
+    let reader = match wal::ClosedSegmentFileReader::from_path(path) {
+        Ok(reader) => reader,
+        Err(e) => return json!({ "path": path, "error": e.to_string() }),
+    };
+
+    let segment_id = reader.id().get();
+    let mut stats = SegmentStats::default();
+    stats.add_entries(reader);
+
+    let mut summary = stats.to_json();
+    summary["path"] = json!(path);
+    summary["segment_id"] = json!(segment_id);
+    summary["file_size_bytes"] = json!(std::fs::metadata(path).ok().map(|m| m.len()));
+    summary
+}
+
+/// A summary of the ops in a single WAL segment.
+#[derive(Debug, Default)]
+struct SegmentStats {
+    /// The number of entries (batches of ops) read successfully
+    entries: u64,
+    writes: u64,
+    deletes: u64,
+    persists: u64,
+    sequence_numbers: SequenceNumberRange,
+    namespaces: BTreeMap<i64, NamespaceStats>,
+    /// The entry that could not be read, and why
+    corrupt_entry: Option<(usize, String)>,
+}
+
+impl SegmentStats {
+    fn add_entries<R>(&mut self, reader: R)
+    where
+        R: Iterator<Item = Result<Vec<SequencedWalOp>, wal::Error>>,
+    {
+        for (entry, ops) in reader.enumerate() {
+            match ops {
+                Ok(ops) => {
+                    self.entries += 1;
+                    ops.into_iter().for_each(|op| self.add_op(op));
+                }
+                Err(e) => {
+                    // The reader cannot find the start of the next entry
+                    // after a corrupt or truncated one.
+                    self.corrupt_entry = Some((entry, e.to_string()));
+                    break;
+                }
+            }
+        }
+    }
+
+    fn add_op(&mut self, op: SequencedWalOp) {
+        op.table_write_sequence_numbers
+            .values()
+            .for_each(|seq| self.sequence_numbers.add(*seq));
+
+        match op.op {
+            WalOp::Write(write) => {
+                self.writes += 1;
+                let namespace = self.namespaces.entry(write.database_id).or_default();
+                for table_batch in &write.table_batches {
+                    let sequence_number = op
+                        .table_write_sequence_numbers
+                        .get(&TableId::new(table_batch.table_id))
+                        .copied();
+                    namespace.totals.add(table_batch, sequence_number);
+                    namespace
+                        .tables
+                        .entry(table_batch.table_id)
+                        .or_default()
+                        .add(table_batch, sequence_number);
+                }
+            }
+            WalOp::Delete(_) => self.deletes += 1,
+            WalOp::Persist(_) => self.persists += 1,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "entries": self.entries,
+            "ops": {
+                "writes": self.writes,
+                "deletes": self.deletes,
+                "persists": self.persists,
+            },
+            "sequence_numbers": self.sequence_numbers.to_json(),
+            "namespaces": self
+                .namespaces
+                .iter()
+                .map(|(id, namespace)| {
+                    let mut summary = namespace.totals.to_json();
+                    summary["namespace_id"] = json!(id);
+                    summary["tables"] = namespace
+                        .tables
+                        .iter()
+                        .map(|(id, table)| {
+                            let mut summary = table.to_json();
+                            summary["table_id"] = json!(id);
+                            summary
+                        })
+                        .collect();
+                    summary
+                })
+                .collect::<Vec<_>>(),
+            "corrupt_entry": self
+                .corrupt_entry
+                .as_ref()
+                .map(|(entry, error)| json!({ "entry": entry, "error": error })),
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct NamespaceStats {
+    totals: WriteStats,
+    tables: BTreeMap<i64, WriteStats>,
+}
+
+/// Running totals of the table batches written to a table or namespace.
+#[derive(Debug, Default)]
+struct WriteStats {
+    table_batches: u64,
+    rows: u64,
+    /// The encoded (uncompressed) size of the table batches
+    bytes: u64,
+    min_time: Option<i64>,
+    max_time: Option<i64>,
+    sequence_numbers: SequenceNumberRange,
+}
+
+impl WriteStats {
+    fn add(&mut self, table_batch: &TableBatch, sequence_number: Option<u64>) {
+        self.table_batches += 1;
+        self.rows += u64::from(table_batch.row_count);
+        self.bytes += table_batch.encoded_len() as u64;
+        if let Some(seq) = sequence_number {
+            self.sequence_numbers.add(seq);
+        }
+
+        let times = table_batch
+            .columns
+            .iter()
+            .find(|c| c.column_name == schema::TIME_COLUMN_NAME)
+            .and_then(|c| c.values.as_ref())
+            .map(|v| v.i64_values.as_slice())
+            .unwrap_or_default();
+        if let Some(min) = times.iter().min() {
+            self.min_time = Some(self.min_time.map_or(*min, |t| t.min(*min)));
+        }
+        if let Some(max) = times.iter().max() {
+            self.max_time = Some(self.max_time.map_or(*max, |t| t.max(*max)));
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "table_batches": self.table_batches,
+            "rows": self.rows,
+            "bytes": self.bytes,
+            "min_time": self.min_time,
+            "max_time": self.max_time,
+            "sequence_numbers": self.sequence_numbers.to_json(),
+        })
+    }
+}
+
+/// The inclusive range of the sequence numbers seen, if any.
+#[derive(Debug, Default, Clone, Copy)]
+struct SequenceNumberRange(Option<(u64, u64)>);
+
+impl SequenceNumberRange {
+    fn add(&mut self, seq: u64) {
+        self.0 = Some(match self.0 {
+            Some((min, max)) => (min.min(seq), max.max(seq)),
+            None => (seq, seq),
+        });
+    }
+
+    fn to_json(self) -> Value {
+        match self.0 {
+            Some((min, max)) => json!({ "min": min, "max": max }),
+            None => Value::Null,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use generated_types::influxdata::{
+        iox::wal::v1::PersistOp,
+        pbdata::v1::{
+            column::{SemanticType, Values},
+            Column, DatabaseBatch,
+        },
+    };
+
+    use super::*;
+
+    fn write_op(namespace_id: i64, table_id: i64, seq: u64, times: Vec<i64>) -> SequencedWalOp {
+        SequencedWalOp {
+            table_write_sequence_numbers: [(TableId::new(table_id), seq)].into(),
+            op: WalOp::Write(DatabaseBatch {
+                database_id: namespace_id,
+                partition_key: Default::default(),
+                table_batches: vec![TableBatch {
+                    table_id,
+                    row_count: times.len() as u32,
+                    columns: vec![Column {
+                        column_name: schema::TIME_COLUMN_NAME.to_string(),
+                        semantic_type: SemanticType::Time as i32,
+                        values: Some(Values {
+                            i64_values: times,
+                            ..Default::default()
+                        }),
+                        null_mask: vec![],
+                    }],
+                }],
+            }),
+        }
+    }
+
+    #[test]
+    fn test_segment_stats() {
+        let mut stats = SegmentStats::default();
+        stats.add_entries(
+            [
+                Ok(vec![
+                    write_op(1, 10, 1, vec![100, 50]),
+                    write_op(1, 11, 2, vec![70]),
+                ]),
+                Ok(vec![
+                    write_op(1, 10, 3, vec![200]),
+                    SequencedWalOp {
+                        table_write_sequence_numbers: Default::default(),
+                        op: WalOp::Persist(PersistOp::default()),
+                    },
+                ]),
+                Err(wal::Error::UnableToSendRequestToReaderTask),
+                Ok(vec![write_op(2, 20, 4, vec![1])]),
+            ]
+            .into_iter(),
+        );
+
+        let summary = stats.to_json();
+        assert_eq!(summary["entries"], 2);
+        assert_eq!(summary["ops"]["writes"], 3);
+        assert_eq!(summary["ops"]["persists"], 1);
+        assert_eq!(summary["sequence_numbers"], json!({ "min": 1, "max": 3 }));
+        assert_eq!(summary["corrupt_entry"]["entry"], 2);
+
+        // Reading stops at the corrupt entry
+        let namespaces = summary["namespaces"].as_array().unwrap();
+        assert_eq!(namespaces.len(), 1);
+
+        let namespace = &namespaces[0];
+        assert_eq!(namespace["namespace_id"], 1);
+        assert_eq!(namespace["rows"], 4);
+        assert_eq!(namespace["min_time"], 50);
+        assert_eq!(namespace["max_time"], 200);
+
+        let table = &namespace["tables"][0];
+        assert_eq!(table["table_id"], 10);
+        assert_eq!(table["table_batches"], 2);
+        assert_eq!(table["rows"], 3);
+        assert_eq!(table["sequence_numbers"], json!({ "min": 1, "max": 3 }));
+    }
+}
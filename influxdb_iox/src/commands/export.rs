@@ -0,0 +1,798 @@
+//! This module implements the `export` CLI command
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use arrow::{compute::cast, error::ArrowError, record_batch::RecordBatch};
+use clap::ValueEnum;
+use data_types::{ColumnType, CompactionLevel, NamespaceId, PartitionKey, TableId};
+use futures::{Stream, TryStreamExt};
+use influxdb_iox_client::{connection::Connection, flight};
+use iox_time::{SystemProvider, Time, TimeProvider};
+use parquet::{
+    arrow::ArrowWriter,
+    basic::Compression,
+    file::{metadata::KeyValue, properties::WriterProperties},
+};
+use parquet_file::metadata::{IoxMetadata, METADATA_KEY};
+use schema::{builder::SchemaBuilder, InfluxColumnType, Schema, TIME_COLUMN_NAME};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Error querying: {0}")]
+    Query(#[from] influxdb_iox_client::flight::Error),
+
+    #[error("Client error: {0}")]
+    ClientError(#[from] influxdb_iox_client::error::Error),
+
+    #[error("Table {0:?} not found")]
+    TableNotFound(String),
+
+    #[error("Invalid type for column {name:?}: {reason}")]
+    ColumnType { name: String, reason: String },
+
+    #[error("Error building IOx schema: {0}")]
+    Schema(#[from] schema::Error),
+
+    #[error("Invalid timestamp {value:?}, expected RFC3339 or nanoseconds since the epoch")]
+    InvalidTime { value: String },
+
+    #[error("Error converting record batch: {0}")]
+    Arrow(#[from] ArrowError),
+
+    #[error("Error writing parquet: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+
+    #[error("Error encoding IOx metadata: {0}")]
+    Metadata(String),
+
+    #[error("Error converting to line protocol: {0}")]
+    LineProtocol(String),
+
+    #[error("Error writing {path:?}: {source}")]
+    Writing {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Export a time range of a table's data to files
+///
+/// The data is streamed from a querier, and written to one or more files in
+/// the output directory, starting a new file once `--max-file-size-bytes` is
+/// exceeded.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The IOx namespace to export from
+    #[clap(long, action)]
+    namespace: String,
+
+    /// The table to export
+    #[clap(long, action)]
+    table: String,
+
+    /// Inclusive start of the time range, as an RFC3339 timestamp or
+    /// nanoseconds since the epoch
+    #[clap(long, action)]
+    start: String,
+
+    /// Exclusive end of the time range, as an RFC3339 timestamp or
+    /// nanoseconds since the epoch
+    #[clap(long, action)]
+    end: String,
+
+    /// An additional SQL predicate rows must match, for example
+    /// `host = 'a' AND usage > 90`
+    #[clap(long = "where", action)]
+    predicate: Option<String>,
+
+    /// Format of the exported files
+    #[clap(short, long, action)]
+    #[clap(value_enum, default_value_t = ExportFormat::Parquet)]
+    format: ExportFormat,
+
+    /// Directory to write the exported files to. The files are named after
+    /// the table, with any character other than ASCII letters, digits, `-`
+    /// and `_` percent-escaped
+    #[clap(short, long, action, default_value = ".")]
+    output_dir: PathBuf,
+
+    /// Start a new file once the current one exceeds this many bytes
+    #[clap(long, action, default_value = "1073741824")]
+    max_file_size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    /// Parquet files with embedded IOx metadata, which can be loaded with
+    /// `influxdb_iox write`
+    Parquet,
+
+    /// CSV files with a header row
+    Csv,
+
+    /// Arrow IPC files
+    Arrow,
+
+    /// Line protocol
+    Lp,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Parquet => "parquet",
+            Self::Csv => "csv",
+            Self::Arrow => "arrow",
+            Self::Lp => "lp",
+        }
+    }
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let Config {
+        namespace,
+        table,
+        start,
+        end,
+        predicate,
+        format,
+        output_dir,
+        max_file_size_bytes,
+    } = config;
+
+    let start = parse_time(&start)?;
+    let end = parse_time(&end)?;
+
+    // The IOx column types are not carried by the query results, so derive
+    // the schema of the export from the catalog schema of the table.
+    let namespace_schema = influxdb_iox_client::schema::Client::new(connection.clone())
+        .get_schema(&namespace, Some(&table))
+        .await?;
+    let table_schema = namespace_schema
+        .tables
+        .get(&table)
+        .ok_or_else(|| Error::TableNotFound(table.clone()))?;
+
+    let mut builder = SchemaBuilder::new();
+    builder.measurement(&table);
+    let mut columns = table_schema.columns.iter().collect::<Vec<_>>();
+    columns.sort_by(|a, b| a.0.cmp(b.0));
+    for (name, column) in columns {
+        let column_type =
+            ColumnType::try_from(column.column_type()).map_err(|e| Error::ColumnType {
+                name: name.clone(),
+                reason: e.to_string(),
+            })?;
+        builder.influx_column(name, InfluxColumnType::from(column_type));
+    }
+    let iox_schema = builder.build()?;
+
+    let exporter = Exporter {
+        format,
+        output_dir,
+        table: table.clone(),
+        iox_schema: iox_schema.clone(),
+        iox_metadata: IoxMetadata {
+            object_store_id: Uuid::nil(),
+            creation_timestamp: SystemProvider::new().now(),
+            namespace_id: NamespaceId::new(namespace_schema.id),
+            namespace_name: namespace.as_str().into(),
+            table_id: TableId::new(table_schema.id),
+            table_name: table.as_str().into(),
+            partition_key: PartitionKey::from("export"),
+            compaction_level: CompactionLevel::Initial,
+            sort_key: None,
+            max_l0_created_at: SystemProvider::new().now(),
+        },
+    };
+
+    let query = build_query(&table, &iox_schema, start, end, predicate.as_deref());
+    let mut client = flight::Client::new(connection);
+    let batches = client.sql(namespace, query).await?;
+
+    let (files, rows) = exporter.export(batches, max_file_size_bytes).await?;
+
+    eprintln!("Exported {rows} rows to {files} file(s)");
+    Ok(())
+}
+
+/// Accepts either an RFC3339 timestamp or nanoseconds since the epoch.
+fn parse_time(value: &str) -> Result<Time> {
+    if let Ok(nanos) = value.parse::<i64>() {
+        return Ok(Time::from_timestamp_nanos(nanos));
+    }
+    Time::from_rfc3339(value).map_err(|_| Error::InvalidTime {
+        value: value.to_string(),
+    })
+}
+
+/// Quote an SQL identifier, so that column and table names need not be valid
+/// unquoted identifiers.
+fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Build the query selecting the columns of `schema`, in schema order, so
+/// the resulting batches line up with it.
+fn build_query(
+    table: &str,
+    schema: &Schema,
+    start: Time,
+    end: Time,
+    predicate: Option<&str>,
+) -> String {
+    let columns = schema
+        .iter()
+        .map(|(_, field)| quote_ident(field.name()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let time = quote_ident(TIME_COLUMN_NAME);
+
+    let mut query = format!(
+        "SELECT {columns} FROM {} WHERE {time} >= '{}' AND {time} < '{}'",
+        quote_ident(table),
+        start.to_rfc3339(),
+        end.to_rfc3339(),
+    );
+    if let Some(predicate) = predicate {
+        query.push_str(&format!(" AND ({predicate})"));
+    }
+    query
+}
+
+/// Creates the files of an export, and conforms query results to the IOx
+/// schema of the table.
+#[derive(Debug, Clone)]
+struct Exporter {
+    format: ExportFormat,
+    output_dir: PathBuf,
+    table: String,
+    iox_schema: Schema,
+    iox_metadata: IoxMetadata,
+}
+
+impl Exporter {
+    /// Cast the columns of `batch` to the arrow types of the IOx schema (for
+    /// example, tags may be returned as plain strings rather than
+    /// dictionaries) and attach the IOx column metadata.
+    fn conform(&self, batch: RecordBatch) -> Result<RecordBatch> {
+        let schema = self.iox_schema.as_arrow();
+        let columns = batch
+            .columns()
+            .iter()
+            .zip(schema.fields())
+            .map(
+                |(column, field)| match column.data_type() == field.data_type() {
+                    true => Ok(Arc::clone(column)),
+                    false => cast(column, field.data_type()),
+                },
+            )
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(RecordBatch::try_new(schema, columns)?)
+    }
+
+    /// Write the non-empty `batches` to as many files as needed, starting a
+    /// new file once the current one reaches `max_file_size_bytes`, and
+    /// return the number of files and rows written.
+    ///
+    /// The files are written on the blocking thread pool, as the writers of
+    /// all formats perform synchronous I/O.
+    async fn export<S, E>(&self, mut batches: S, max_file_size_bytes: u64) -> Result<(usize, usize)>
+    where
+        S: Stream<Item = Result<RecordBatch, E>> + Unpin,
+        Error: From<E>,
+    {
+        let (tx, rx) = tokio::sync::mpsc::channel(1);
+        let writer = tokio::task::spawn_blocking({
+            let this = self.clone();
+            move || this.write_all(rx, max_file_size_bytes)
+        });
+
+        while let Some(batch) = batches.try_next().await? {
+            if tx.send(batch).await.is_err() {
+                // The writer failed, and its error is returned below.
+                break;
+            }
+        }
+        drop(tx);
+
+        writer.await.expect("export writer panicked")
+    }
+
+    /// Write the batches received from `rx`, as described in
+    /// [`Self::export()`].
+    fn write_all(
+        &self,
+        mut rx: tokio::sync::mpsc::Receiver<RecordBatch>,
+        max_file_size_bytes: u64,
+    ) -> Result<(usize, usize)> {
+        let mut writer = None;
+        let mut files = 0;
+        let mut rows = 0;
+        while let Some(batch) = rx.blocking_recv() {
+            if batch.num_rows() == 0 {
+                continue;
+            }
+            let batch = self.conform(batch)?;
+            rows += batch.num_rows();
+
+            if writer.is_none() {
+                files += 1;
+                writer = Some(self.create(files)?);
+            }
+            let current = writer.as_mut().expect("writer was just created");
+            current.write(&batch)?;
+
+            if current.size() >= max_file_size_bytes {
+                writer.take().expect("writer was just used").finish()?;
+            }
+        }
+        if let Some(writer) = writer {
+            writer.finish()?;
+        }
+
+        Ok((files, rows))
+    }
+
+    fn create(&self, index: usize) -> Result<ExportWriter> {
+        let path = self.output_dir.join(format!(
+            "{}-{index:04}.{}",
+            escape_file_name(&self.table),
+            self.format.extension()
+        ));
+        let file = File::create(&path).map_err(|source| Error::Writing {
+            path: path.clone(),
+            source,
+        })?;
+        let bytes = Arc::new(AtomicU64::new(0));
+        let out = CountingWriter {
+            inner: BufWriter::new(file),
+            bytes: Arc::clone(&bytes),
+        };
+        let schema = self.iox_schema.as_arrow();
+
+        let format = match self.format {
+            ExportFormat::Parquet => {
+                // Give each file its own identity, so that it may be written
+                // back into IOx.
+                let meta = IoxMetadata {
+                    object_store_id: Uuid::new_v4(),
+                    ..self.iox_metadata.clone()
+                };
+                let props = WriterProperties::builder()
+                    .set_key_value_metadata(Some(vec![KeyValue {
+                        key: METADATA_KEY.to_string(),
+                        value: Some(
+                            meta.to_base64()
+                                .map_err(|e| Error::Metadata(e.to_string()))?,
+                        ),
+                    }]))
+                    .set_compression(Compression::ZSTD(Default::default()))
+                    .build();
+                FormatWriter::Parquet(ArrowWriter::try_new(out, schema, Some(props))?)
+            }
+            ExportFormat::Csv => FormatWriter::Csv(arrow::csv::Writer::new(out)),
+            ExportFormat::Arrow => {
+                FormatWriter::Arrow(arrow::ipc::writer::FileWriter::try_new(out, &schema)?)
+            }
+            ExportFormat::Lp => FormatWriter::Lp {
+                out,
+                schema: self.iox_schema.clone(),
+                measurement: self.table.clone(),
+            },
+        };
+
+        Ok(ExportWriter {
+            path,
+            format,
+            bytes,
+        })
+    }
+}
+
+/// Escape `name` for use as a file name, so that a table name can neither
+/// refer to another directory (such as `../cpu` or `a/b`) nor create a hidden
+/// file.
+///
+/// Every byte other than an ASCII letter, digit, `-` or `_` is replaced by
+/// `%` followed by its value in hex.
+fn escape_file_name(name: &str) -> String {
+    name.bytes()
+        .map(|b| match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => (b as char).to_string(),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+type Out = CountingWriter<BufWriter<File>>;
+
+/// An open export file.
+#[derive(Debug)]
+struct ExportWriter {
+    path: PathBuf,
+    format: FormatWriter,
+    /// Bytes written to the file so far.
+    bytes: Arc<AtomicU64>,
+}
+
+enum FormatWriter {
+    Parquet(ArrowWriter<Out>),
+    Csv(arrow::csv::Writer<Out>),
+    Arrow(arrow::ipc::writer::FileWriter<Out>),
+    Lp {
+        out: Out,
+        schema: Schema,
+        measurement: String,
+    },
+}
+
+impl std::fmt::Debug for FormatWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Parquet(_) => "Parquet",
+            Self::Csv(_) => "Csv",
+            Self::Arrow(_) => "Arrow",
+            Self::Lp { .. } => "Lp",
+        };
+        f.debug_tuple("FormatWriter").field(&name).finish()
+    }
+}
+
+impl ExportWriter {
+    fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        match &mut self.format {
+            FormatWriter::Parquet(w) => w.write(batch)?,
+            FormatWriter::Csv(w) => w.write(batch)?,
+            FormatWriter::Arrow(w) => w.write(batch)?,
+            FormatWriter::Lp {
+                out,
+                schema,
+                measurement,
+            } => {
+                let lines = parquet_to_line_protocol::convert_to_lines(measurement, schema, batch)
+                    .map_err(Error::LineProtocol)?;
+                out.write_all(&lines).map_err(|source| Error::Writing {
+                    path: self.path.clone(),
+                    source,
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The approximate size of the file, including any data buffered but not
+    /// yet written.
+    fn size(&self) -> u64 {
+        let written = self.bytes.load(Ordering::Relaxed);
+        match &self.format {
+            // Row groups are buffered in memory until complete
+            FormatWriter::Parquet(w) => written + w.in_progress_size() as u64,
+            _ => written,
+        }
+    }
+
+    fn finish(self) -> Result<()> {
+        let Self { path, format, .. } = self;
+        let mut out = match format {
+            FormatWriter::Parquet(w) => w.into_inner()?,
+            FormatWriter::Csv(w) => w.into_inner(),
+            FormatWriter::Arrow(mut w) => {
+                w.finish()?;
+                w.into_inner()?
+            }
+            FormatWriter::Lp { out, .. } => out,
+        };
+        out.flush().map_err(|source| Error::Writing {
+            path: path.clone(),
+            source,
+        })?;
+        println!("{}", path.display());
+        Ok(())
+    }
+}
+
+/// A [`Write`] implementation counting the bytes written through it.
+#[derive(Debug)]
+struct CountingWriter<W> {
+    inner: W,
+    bytes: Arc<AtomicU64>,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.bytes.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use arrow::{
+        array::{ArrayRef, Float64Array, StringArray, TimestampNanosecondArray},
+        datatypes::DataType,
+    };
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use super::*;
+
+    #[test]
+    fn test_build_query() {
+        let schema = SchemaBuilder::new()
+            .tag("host")
+            .influx_field("usage \"pct\"", schema::InfluxFieldType::Float)
+            .timestamp()
+            .build()
+            .unwrap();
+
+        let query = build_query(
+            "cpu",
+            &schema,
+            parse_time("2023-01-01T00:00:00Z").unwrap(),
+            parse_time("1672617600000000000").unwrap(),
+            Some("host = 'a'"),
+        );
+        assert_eq!(
+            query,
+            "SELECT \"host\", \"usage \"\"pct\"\"\", \"time\" FROM \"cpu\" \
+             WHERE \"time\" >= '2023-01-01T00:00:00+00:00' AND \"time\" < '2023-01-02T00:00:00+00:00' \
+             AND (host = 'a')"
+        );
+    }
+
+    #[test]
+    fn test_escape_file_name() {
+        assert_eq!(escape_file_name("cpu_load-1"), "cpu_load-1");
+        assert_eq!(escape_file_name("../cpu"), "%2E%2E%2Fcpu");
+        assert_eq!(escape_file_name("a/b\\c"), "a%2Fb%5Cc");
+        assert_eq!(escape_file_name(".hidden"), "%2Ehidden");
+        assert_eq!(escape_file_name("a b"), "a%20b");
+        assert_eq!(escape_file_name("ü"), "%C3%BC");
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert!(parse_time("yesterday").is_err());
+        assert_eq!(parse_time("42").unwrap().timestamp_nanos(), 42);
+    }
+
+    fn test_schema() -> Schema {
+        SchemaBuilder::new()
+            .measurement("cpu")
+            .tag("host")
+            .influx_field("usage", schema::InfluxFieldType::Float)
+            .influx_field("idle", schema::InfluxFieldType::Float)
+            .timestamp()
+            .build()
+            .unwrap()
+    }
+
+    fn test_exporter(format: ExportFormat, output_dir: &Path) -> Exporter {
+        Exporter {
+            format,
+            output_dir: output_dir.to_path_buf(),
+            table: "cpu".to_string(),
+            iox_schema: test_schema(),
+            iox_metadata: IoxMetadata {
+                object_store_id: Uuid::nil(),
+                creation_timestamp: Time::from_timestamp_nanos(0),
+                namespace_id: NamespaceId::new(1),
+                namespace_name: "ns".into(),
+                table_id: TableId::new(2),
+                table_name: "cpu".into(),
+                partition_key: PartitionKey::from("export"),
+                compaction_level: CompactionLevel::Initial,
+                sort_key: None,
+                max_l0_created_at: Time::from_timestamp_nanos(0),
+            },
+        }
+    }
+
+    /// A query result as returned by the querier, with the tag as a plain
+    /// string column and a NULL field.
+    fn query_batch(i: i64) -> RecordBatch {
+        RecordBatch::try_from_iter([
+            (
+                "host",
+                Arc::new(StringArray::from(vec!["a", "b"])) as ArrayRef,
+            ),
+            (
+                "usage",
+                Arc::new(Float64Array::from(vec![Some(1.0), None])) as ArrayRef,
+            ),
+            (
+                "idle",
+                Arc::new(Float64Array::from(vec![2.0, 3.0])) as ArrayRef,
+            ),
+            (
+                "time",
+                Arc::new(TimestampNanosecondArray::from(vec![2 * i, 2 * i + 1])) as ArrayRef,
+            ),
+        ])
+        .unwrap()
+    }
+
+    /// Export three batches of two rows, starting a new file after every
+    /// batch, and return the conformed batches and the paths of the files.
+    async fn export(format: ExportFormat, dir: &Path) -> (Vec<RecordBatch>, Vec<PathBuf>) {
+        let exporter = test_exporter(format, dir);
+        let batches = (0..3).map(query_batch).collect::<Vec<_>>();
+        let empty = RecordBatch::new_empty(batches[0].schema());
+
+        let input = batches
+            .iter()
+            .cloned()
+            .chain([empty])
+            .map(Ok::<_, Error>)
+            .collect::<Vec<_>>();
+        let (files, rows) = exporter
+            .export(futures::stream::iter(input), 1)
+            .await
+            .unwrap();
+        assert_eq!((files, rows), (3, 6));
+
+        let mut paths = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<_>>();
+        paths.sort();
+        let names = paths
+            .iter()
+            .map(|p| p.file_name().unwrap().to_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+        let ext = format.extension();
+        assert_eq!(
+            names,
+            [
+                format!("cpu-0001.{ext}"),
+                format!("cpu-0002.{ext}"),
+                format!("cpu-0003.{ext}"),
+            ]
+        );
+
+        let expected = batches
+            .into_iter()
+            .map(|b| exporter.conform(b).unwrap())
+            .collect();
+        (expected, paths)
+    }
+
+    #[test]
+    fn test_conform() {
+        let exporter = test_exporter(ExportFormat::Parquet, Path::new("."));
+        let batch = exporter.conform(query_batch(0)).unwrap();
+
+        assert_eq!(batch.schema(), test_schema().as_arrow());
+        assert_eq!(
+            batch.column(0).data_type(),
+            &DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8))
+        );
+        // The IOx column types survive in the arrow schema.
+        let schema = Schema::try_from(batch.schema()).unwrap();
+        assert_eq!(schema.field(0).0, InfluxColumnType::Tag);
+
+        // A result that can't be cast to the table schema is rejected.
+        let batch = RecordBatch::try_from_iter([
+            ("host", Arc::new(StringArray::from(vec!["a"])) as ArrayRef),
+            (
+                "usage",
+                Arc::new(StringArray::from(vec!["high"])) as ArrayRef,
+            ),
+            ("idle", Arc::new(Float64Array::from(vec![2.0])) as ArrayRef),
+            (
+                "time",
+                Arc::new(TimestampNanosecondArray::from(vec![1])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        assert!(exporter.conform(batch).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_export_parquet() {
+        let dir = tempfile::tempdir().unwrap();
+        let (expected, paths) = export(ExportFormat::Parquet, dir.path()).await;
+
+        let mut ids = vec![];
+        for (expected, path) in expected.iter().zip(&paths) {
+            let builder =
+                ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap()).unwrap();
+
+            let value = builder
+                .metadata()
+                .file_metadata()
+                .key_value_metadata()
+                .and_then(|kv| kv.iter().find(|kv| kv.key == METADATA_KEY))
+                .and_then(|kv| kv.value.clone())
+                .expect("IOx metadata must be embedded");
+            let meta = IoxMetadata::from_base64(value.as_bytes()).unwrap();
+            assert_eq!(meta.table_name.as_ref(), "cpu");
+            assert_eq!(meta.namespace_id, NamespaceId::new(1));
+            ids.push(meta.object_store_id);
+
+            let batches = builder
+                .build()
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(batches.len(), 1);
+            assert_eq!(batches[0].columns(), expected.columns());
+        }
+
+        // Each file has its own object store ID.
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 3);
+        assert!(!ids.contains(&Uuid::nil()));
+    }
+
+    #[tokio::test]
+    async fn test_export_arrow() {
+        let dir = tempfile::tempdir().unwrap();
+        let (expected, paths) = export(ExportFormat::Arrow, dir.path()).await;
+
+        for (expected, path) in expected.iter().zip(&paths) {
+            let reader =
+                arrow::ipc::reader::FileReader::try_new(File::open(path).unwrap(), None).unwrap();
+            assert_eq!(reader.schema(), test_schema().as_arrow());
+
+            let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+            assert_eq!(batches, [expected.clone()]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_, paths) = export(ExportFormat::Csv, dir.path()).await;
+
+        for path in paths {
+            let csv = std::fs::read_to_string(path).unwrap();
+            let lines = csv.lines().collect::<Vec<_>>();
+            assert_eq!(lines.len(), 3, "{csv}");
+            assert_eq!(lines[0], "host,usage,idle,time");
+            assert!(lines[1].starts_with("a,1"), "{csv}");
+            // The NULL usage is written as an empty value.
+            assert!(lines[2].starts_with("b,,3"), "{csv}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_lp() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_, paths) = export(ExportFormat::Lp, dir.path()).await;
+
+        let lp = paths
+            .into_iter()
+            .map(|path| std::fs::read_to_string(path).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lp,
+            [
+                "cpu,host=a usage=1,idle=2 0\ncpu,host=b idle=3 1\n",
+                "cpu,host=a usage=1,idle=2 2\ncpu,host=b idle=3 3\n",
+                "cpu,host=a usage=1,idle=2 4\ncpu,host=b idle=3 5\n",
+            ]
+        );
+    }
+}
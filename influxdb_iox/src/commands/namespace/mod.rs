@@ -7,6 +7,7 @@ mod create;
 mod delete;
 mod retention;
 mod update_limit;
+mod update_query_quota;
 
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug, Error)]
@@ -42,6 +43,10 @@ enum Command {
     /// Update one of the service protection limits for an existing namespace
     UpdateLimit(update_limit::Config),
 
+    /// Update the query quota of an existing namespace, replacing any existing
+    /// limits
+    UpdateQueryQuota(update_query_quota::Config),
+
     /// Delete a namespace
     Delete(delete::Config),
 }
@@ -62,6 +67,9 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
         Command::UpdateLimit(config) => {
             update_limit::command(connection, config).await?;
         }
+        Command::UpdateQueryQuota(config) => {
+            update_query_quota::command(connection, config).await?;
+        }
         Command::Delete(config) => {
             delete::command(connection, config).await?;
         } // Deliberately not adding _ => so the compiler will direct people here to impl new
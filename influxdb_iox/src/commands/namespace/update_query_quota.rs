@@ -0,0 +1,41 @@
+use influxdb_iox_client::connection::Connection;
+
+use crate::commands::namespace::Result;
+
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The namespace to update the query quota for
+    #[clap(action)]
+    namespace: String,
+
+    /// The maximum number of queries against this namespace each querier
+    /// executes concurrently.
+    ///
+    /// If not specified, the number of concurrent queries is only limited
+    /// by the querier's global limit.
+    #[clap(action, long = "max-concurrent-queries", short = 'q')]
+    max_concurrent_queries: Option<i32>,
+
+    /// The maximum memory in bytes the queries against this namespace may
+    /// reserve from each querier's query memory pool.
+    ///
+    /// If not specified, the memory use is only limited by the size of the
+    /// querier's pool.
+    #[clap(action, long = "max-query-memory-bytes", short = 'm')]
+    max_query_memory_bytes: Option<i64>,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let mut client = influxdb_iox_client::namespace::Client::new(connection);
+
+    let namespace = client
+        .update_namespace_query_quota(
+            &config.namespace,
+            config.max_concurrent_queries,
+            config.max_query_memory_bytes,
+        )
+        .await?;
+    println!("{}", serde_json::to_string_pretty(&namespace)?);
+
+    Ok(())
+}
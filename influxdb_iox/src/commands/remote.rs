@@ -3,12 +3,20 @@
 use influxdb_iox_client::connection::Connection;
 use thiserror::Error;
 
+mod cache;
+mod catalog;
 mod partition;
 mod store;
 
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug, Error)]
 pub enum Error {
+    #[error("{0}")]
+    Cache(#[from] cache::Error),
+
+    #[error("{0}")]
+    RemoteCatalog(#[from] catalog::Error),
+
     #[error("{0}")]
     Partition(#[from] partition::Error),
 
@@ -32,6 +40,10 @@ pub struct Config {
 /// All possible subcommands for remote
 #[derive(Debug, clap::Parser)]
 enum Command {
+    /// Manage the caches of a querier
+    Cache(cache::Config),
+    /// Modify the catalog (for incident response)
+    Catalog(catalog::Config),
     /// Get partition data
     Partition(partition::Config),
     /// Get Parquet files from the object store
@@ -40,6 +52,12 @@ enum Command {
 
 pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
     match config.command {
+        Command::Cache(config) => {
+            cache::command(connection, config).await?;
+        }
+        Command::Catalog(config) => {
+            catalog::command(connection, config).await?;
+        }
         Command::Partition(config) => {
             partition::command(connection, config).await?;
         }
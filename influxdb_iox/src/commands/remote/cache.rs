@@ -0,0 +1,94 @@
+//! This module implements the `remote cache` CLI subcommand
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use influxdb_iox_client::{cache, connection::Connection};
+use thiserror::Error;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("JSON Serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error("Client error: {0}")]
+    ClientError(#[from] influxdb_iox_client::error::Error),
+
+    #[error("Invalid time range: {0}")]
+    TimeRange(String),
+}
+
+/// Manage the caches of a querier
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// Preload the querier caches with the partitions of a namespace that received new Parquet
+/// files within the given time range
+#[derive(Debug, clap::Parser)]
+struct Prefetch {
+    /// The namespace to prefetch
+    #[clap(action)]
+    namespace: String,
+
+    /// Prefetch partitions that received new files within this duration before now (e.g. "2h")
+    #[clap(
+        long,
+        default_value = "1h",
+        value_parser = humantime::parse_duration,
+        conflicts_with = "start",
+        action
+    )]
+    since: Duration,
+
+    /// Prefetch partitions that received new files after this time (RFC3339, e.g.
+    /// "2023-10-01T00:00:00Z")
+    #[clap(long, value_parser = humantime::parse_rfc3339_weak, action)]
+    start: Option<SystemTime>,
+
+    /// Prefetch partitions that received new files before this time (RFC3339)
+    #[clap(long, value_parser = humantime::parse_rfc3339_weak, action)]
+    end: Option<SystemTime>,
+
+    /// Also load the Parquet data into the object store cache of the querier
+    #[clap(long, action)]
+    parquet_data: bool,
+}
+
+/// All possible subcommands for cache
+#[derive(Debug, clap::Parser)]
+enum Command {
+    Prefetch(Prefetch),
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
+    match config.command {
+        Command::Prefetch(prefetch) => {
+            let start = match prefetch.start {
+                Some(start) => start,
+                None => SystemTime::now()
+                    .checked_sub(prefetch.since)
+                    .unwrap_or(UNIX_EPOCH),
+            };
+            let start = nanos_since_epoch(start)?;
+            let end = prefetch.end.map(nanos_since_epoch).transpose()?;
+
+            let mut client = cache::Client::new(connection);
+            let response = client
+                .prefetch(prefetch.namespace, start, end, prefetch.parquet_data)
+                .await?;
+            println!("{}", serde_json::to_string_pretty(&response)?);
+
+            Ok(())
+        }
+    }
+}
+
+fn nanos_since_epoch(t: SystemTime) -> Result<i64, Error> {
+    t.duration_since(UNIX_EPOCH)
+        .ok()
+        .and_then(|d| i64::try_from(d.as_nanos()).ok())
+        .ok_or_else(|| Error::TimeRange(format!("{t:?} is out of range")))
+}
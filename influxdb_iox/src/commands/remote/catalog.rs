@@ -0,0 +1,303 @@
+//! This module implements the `remote catalog` CLI subcommand
+
+use std::path::PathBuf;
+
+use bytes::Bytes;
+use data_types::Statistics;
+use influxdb_iox_client::{
+    catalog::{
+        self,
+        generated_types::{
+            partition_identifier, PartitionIdentifier, RegisterParquetFileRequest,
+            SetPartitionSortKeyRequest, SortKey,
+        },
+    },
+    connection::Connection,
+};
+use parquet_file::metadata::IoxParquetMetaData;
+use thiserror::Error;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("JSON Serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error("Client error: {0}")]
+    ClientError(#[from] influxdb_iox_client::error::Error),
+
+    #[error("Error reading {path:?}: {source}")]
+    Reading {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("{0:?} contains no IOx metadata")]
+    NoMetadata(PathBuf),
+
+    #[error("Invalid parquet file metadata: {0}")]
+    Metadata(#[from] parquet_file::metadata::Error),
+
+    #[error("Parquet file contains no time range statistics")]
+    NoTimeRange,
+
+    #[error("Invalid partition id {0:?}, expected a catalog id or a hex encoded hash id")]
+    PartitionId(String),
+}
+
+/// Modify the catalog through a remote IOx server, for use during incident
+/// response.
+///
+/// The server (a router) must be started with `--catalog-write-access`. Each
+/// command accepts `--dry-run` to print the change without applying it.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// Flag Parquet files for deletion
+#[derive(Debug, clap::Parser)]
+struct FlagForDelete {
+    /// The object store uuids of the Parquet files, which must all belong to
+    /// the same namespace
+    #[clap(action, required = true)]
+    object_store_ids: Vec<String>,
+
+    /// Print the affected files without modifying the catalog
+    #[clap(long, action)]
+    dry_run: bool,
+}
+
+/// Change the compaction level of Parquet files
+#[derive(Debug, clap::Parser)]
+struct SetCompactionLevel {
+    /// The new compaction level (0 = initial, 1 = non-overlapping, 2 = final)
+    #[clap(long, action)]
+    level: i32,
+
+    /// The object store uuids of the Parquet files, which must all belong to
+    /// the same namespace
+    #[clap(action, required = true)]
+    object_store_ids: Vec<String>,
+
+    /// Print the updated files without modifying the catalog
+    #[clap(long, action)]
+    dry_run: bool,
+}
+
+/// Reset or replace the sort key of a partition
+#[derive(Debug, clap::Parser)]
+struct SetSortKey {
+    /// The catalog id or the hex encoded hash id of the partition
+    #[clap(action)]
+    partition_id: String,
+
+    /// Comma separated column names of the new sort key. If not specified,
+    /// the sort key is reset.
+    #[clap(long, action, value_delimiter = ',')]
+    sort_key: Vec<String>,
+
+    /// Comma separated column names of the sort key the partition is
+    /// expected to have, specify an empty value for no sort key
+    #[clap(
+        long,
+        action,
+        value_delimiter = ',',
+        required_unless_present = "force",
+        conflicts_with = "force"
+    )]
+    expected: Option<Vec<String>>,
+
+    /// Replace the sort key regardless of its current value
+    #[clap(long, action)]
+    force: bool,
+
+    /// Print the updated partition without modifying the catalog
+    #[clap(long, action)]
+    dry_run: bool,
+}
+
+/// Register a Parquet file found in object storage, but missing from the
+/// catalog
+///
+/// The catalog record is derived from the IOx metadata embedded in a local
+/// copy of the file. The namespace, table, partition and columns of the file
+/// must exist.
+#[derive(Debug, clap::Parser)]
+struct Register {
+    /// The local copy of the Parquet file
+    #[clap(action)]
+    file: PathBuf,
+
+    /// The object store uuid of the file, if it differs from the one in its
+    /// embedded metadata
+    #[clap(long, action)]
+    object_store_id: Option<String>,
+
+    /// Print the record without modifying the catalog
+    #[clap(long, action)]
+    dry_run: bool,
+}
+
+/// All possible subcommands for catalog
+#[derive(Debug, clap::Parser)]
+enum Command {
+    FlagForDelete(FlagForDelete),
+    SetCompactionLevel(SetCompactionLevel),
+    SetSortKey(SetSortKey),
+    Register(Register),
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
+    let mut client = catalog::Client::new(connection);
+
+    match config.command {
+        Command::FlagForDelete(FlagForDelete {
+            object_store_ids,
+            dry_run,
+        }) => {
+            let files = client
+                .flag_parquet_files_for_deletion(object_store_ids, dry_run)
+                .await?;
+            println!("{}", serde_json::to_string_pretty(&files)?);
+        }
+        Command::SetCompactionLevel(SetCompactionLevel {
+            level,
+            object_store_ids,
+            dry_run,
+        }) => {
+            let files = client
+                .update_parquet_file_compaction_level(object_store_ids, level, dry_run)
+                .await?;
+            println!("{}", serde_json::to_string_pretty(&files)?);
+        }
+        Command::SetSortKey(SetSortKey {
+            partition_id,
+            sort_key,
+            expected,
+            force,
+            dry_run,
+        }) => {
+            let partition_identifier = parse_partition_id(&partition_id)?;
+            let partition = client
+                .set_partition_sort_key(SetPartitionSortKeyRequest {
+                    partition_identifier: Some(partition_identifier),
+                    sort_key: non_empty(sort_key),
+                    expected_sort_key: expected.map(|v| SortKey {
+                        array_sort_key: non_empty(v),
+                    }),
+                    force,
+                    dry_run,
+                })
+                .await?;
+            println!("{}", serde_json::to_string_pretty(&partition)?);
+        }
+        Command::Register(register) => {
+            let request = register_request(register)?;
+            let file = client.register_parquet_file(request).await?;
+            println!("{}", serde_json::to_string_pretty(&file)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Drop the empty names resulting from an empty (or trailing comma)
+/// argument.
+fn non_empty(names: Vec<String>) -> Vec<String> {
+    names.into_iter().filter(|v| !v.is_empty()).collect()
+}
+
+fn parse_partition_id(s: &str) -> Result<PartitionIdentifier, Error> {
+    let id = match s.parse::<i64>() {
+        Ok(id) => partition_identifier::Id::CatalogId(id),
+        Err(_) => partition_identifier::Id::HashId(decode_hex(s)?),
+    };
+    Ok(PartitionIdentifier { id: Some(id) })
+}
+
+fn decode_hex(s: &str) -> Result<Vec<u8>, Error> {
+    let invalid = || Error::PartitionId(s.to_string());
+    if s.len() % 2 != 0 {
+        return Err(invalid());
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| {
+            s.get(i..i + 2)
+                .and_then(|b| u8::from_str_radix(b, 16).ok())
+                .ok_or_else(invalid)
+        })
+        .collect()
+}
+
+fn register_request(
+    Register {
+        file,
+        object_store_id,
+        dry_run,
+    }: Register,
+) -> Result<RegisterParquetFileRequest, Error> {
+    let data = std::fs::read(&file).map_err(|source| Error::Reading {
+        path: file.clone(),
+        source,
+    })?;
+    let file_size_bytes = data.len() as i64;
+    let metadata = IoxParquetMetaData::from_file_bytes(Bytes::from(data))?
+        .ok_or_else(|| Error::NoMetadata(file.clone()))?
+        .decode()?;
+    let iox_metadata = metadata.read_iox_metadata_new()?;
+    let schema = metadata.read_schema()?;
+
+    let time = metadata
+        .read_statistics(&schema)?
+        .into_iter()
+        .find(|s| s.name == schema::TIME_COLUMN_NAME)
+        .ok_or(Error::NoTimeRange)?;
+    let Statistics::I64(time) = time.stats else {
+        return Err(Error::NoTimeRange);
+    };
+    let (Some(min_time), Some(max_time)) = (time.min, time.max) else {
+        return Err(Error::NoTimeRange);
+    };
+
+    Ok(RegisterParquetFileRequest {
+        namespace_name: iox_metadata.namespace_name.to_string(),
+        table_name: iox_metadata.table_name.to_string(),
+        partition_key: iox_metadata.partition_key.to_string(),
+        object_store_id: object_store_id
+            .unwrap_or_else(|| iox_metadata.object_store_id.to_string()),
+        min_time,
+        max_time,
+        file_size_bytes,
+        row_count: metadata.row_count() as i64,
+        compaction_level: iox_metadata.compaction_level as i32,
+        created_at: iox_metadata.creation_timestamp.timestamp_nanos(),
+        max_l0_created_at: iox_metadata.max_l0_created_at.timestamp_nanos(),
+        column_names: schema
+            .iter()
+            .map(|(_, field)| field.name().to_string())
+            .collect(),
+        dry_run,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_partition_id() {
+        assert_eq!(
+            parse_partition_id("42").unwrap().id,
+            Some(partition_identifier::Id::CatalogId(42))
+        );
+        assert_eq!(
+            parse_partition_id("00ff10").unwrap().id,
+            Some(partition_identifier::Id::HashId(vec![0x00, 0xff, 0x10]))
+        );
+        assert!(parse_partition_id("0ff").is_err());
+        assert!(parse_partition_id("zz").is_err());
+    }
+}
@@ -4,20 +4,21 @@ use crate::process_info::setup_metric_registry;
 
 use super::main;
 use clap_blocks::{
+    audit::AuditConfig,
+    authz::AuthzConfig,
     catalog_dsn::CatalogDsnConfig,
     compactor::CompactorConfig,
     compactor_scheduler::CompactorSchedulerConfig,
     gossip::GossipConfig,
     ingester::IngesterConfig,
     ingester_address::IngesterAddress,
+    ingester_discovery::IngesterDiscoveryConfig,
     memory_size::MemorySize,
     object_store::{make_object_store, ObjectStoreConfig},
     querier::QuerierConfig,
     router::RouterConfig,
     run_config::RunConfig,
-    single_tenant::{
-        CONFIG_AUTHZ_ENV_NAME, CONFIG_AUTHZ_FLAG, CONFIG_CST_ENV_NAME, CONFIG_CST_FLAG,
-    },
+    single_tenant::{CONFIG_CST_ENV_NAME, CONFIG_CST_FLAG},
     socket_addr::SocketAddr,
 };
 use compactor::object_store::metrics::MetricsStore;
@@ -170,18 +171,17 @@ pub type Result<T, E = Error> = std::result::Result<T, E>;
 )]
 #[group(skip)]
 pub struct Config {
-    #[clap(
-        long = CONFIG_AUTHZ_FLAG,
-        env = CONFIG_AUTHZ_ENV_NAME,
-        requires("single_tenant_deployment"),
-    )]
-    pub(crate) authz_address: Option<String>,
+    #[clap(flatten)]
+    pub(crate) authz_config: AuthzConfig,
+
+    #[clap(flatten)]
+    pub(crate) audit_config: AuditConfig,
 
     #[clap(
         long = CONFIG_CST_FLAG,
         env = CONFIG_CST_ENV_NAME,
         default_value = "false",
-        requires_if("true", "authz_address")
+        requires_if("true", "authz_source")
     )]
     pub(crate) single_tenant_deployment: bool,
 
@@ -367,7 +367,8 @@ impl Config {
     /// configuration for each individual IOx service
     fn specialize(self) -> SpecializedConfig {
         let Self {
-            authz_address,
+            authz_config,
+            audit_config,
             logging_config,
             tracing_config,
             max_http_request_size,
@@ -479,6 +480,7 @@ impl Config {
         let ingester_config = IngesterConfig {
             wal_directory,
             wal_rotation_period_seconds,
+            wal_replay_salvage: false,
             concurrent_query_limit,
             persist_max_parallelism,
             persist_queue_depth,
@@ -489,7 +491,8 @@ impl Config {
         };
 
         let router_config = RouterConfig {
-            authz_address: authz_address.clone(),
+            authz_config: authz_config.clone(),
+            audit_config: audit_config.clone(),
             single_tenant_deployment,
             http_request_limit: 1_000,
             ingester_addresses: ingester_addresses.clone(),
@@ -499,7 +502,9 @@ impl Config {
             rpc_write_replicas: 1.try_into().unwrap(),
             rpc_write_max_outgoing_bytes: ingester_config.rpc_write_max_incoming_bytes,
             rpc_write_health_num_probes: 10,
+            catalog_write_access: false,
             gossip_config: GossipConfig::disabled(),
+            ingester_discovery_config: IngesterDiscoveryConfig::disabled(),
         };
 
         // create a CompactorConfig for the all in one server based on
@@ -524,16 +529,29 @@ impl Config {
             max_num_columns_per_table: 200,
             max_num_files_per_plan: 200,
             max_partition_fetch_queries_per_second: Some(500),
+            catalog_cache_bytes: None,
+            catalog_cache_ttl: iox_catalog::cache::DEFAULT_TTL,
             gossip_config: GossipConfig::disabled(),
         };
 
         let querier_config = QuerierConfig {
-            authz_address,
+            gossip_config: GossipConfig::disabled(),
+            ingester_discovery_config: IngesterDiscoveryConfig::disabled(),
+            authz_config,
+            audit_config,
             num_query_threads: None, // will be ignored
             ingester_addresses,
             ram_pool_metadata_bytes: querier_ram_pool_metadata_bytes,
             ram_pool_data_bytes: querier_ram_pool_data_bytes,
+            disk_cache_dir: None,
+            disk_cache_bytes: 0,
+            cache_warmup_partitions: 0,
+            cache_warmup_window: None,
+            cache_warmup_parquet_data: false,
+            cache_warmup_wait: false,
             max_concurrent_queries: querier_max_concurrent_queries,
+            max_concurrent_queries_per_token: None,
+            query_queue_timeout: Duration::from_secs(30),
             exec_mem_pool_bytes,
             ingester_circuit_breaker_threshold: u64::MAX, // never for all-in-one-mode
             datafusion_config: Default::default(),
@@ -7,6 +7,7 @@ use clap_blocks::{
     run_config::RunConfig,
 };
 use compactor::object_store::metrics::MetricsStore;
+use iox_catalog::interface::Catalog;
 use iox_query::exec::{Executor, ExecutorConfig};
 use iox_time::{SystemProvider, TimeProvider};
 use ioxd_common::{
@@ -69,45 +70,100 @@ pub struct Config {
 pub async fn command(config: Config) -> Result<(), Error> {
     let common_state = CommonServerState::from_config(config.run_config.clone())?;
 
-    let time_provider = Arc::new(SystemProvider::new()) as Arc<dyn TimeProvider>;
     let metric_registry = setup_metric_registry();
-    let catalog = config
-        .catalog_dsn
-        .get_catalog("compactor", Arc::clone(&metric_registry))
+    let CompactorDependencies {
+        catalog,
+        parquet_store_real,
+        parquet_store_scratchpad,
+        exec,
+    } = create_compactor_dependencies(
+        &config.run_config,
+        &config.catalog_dsn,
+        &config.compactor_config,
+        &metric_registry,
+    )
+    .await?;
+    let time_provider = Arc::new(SystemProvider::new());
+
+    let process_once = config.compactor_config.process_once;
+    let server_type = create_compactor_server_type(
+        &common_state,
+        Arc::clone(&metric_registry),
+        catalog,
+        parquet_store_real,
+        parquet_store_scratchpad,
+        exec,
+        time_provider,
+        config.compactor_config,
+    )
+    .await;
+
+    info!("starting compactor");
+
+    let services = vec![Service::create(server_type, common_state.run_config())];
+
+    let res = main::main(common_state, services, metric_registry).await;
+    match res {
+        Ok(()) => Ok(()),
+        // compactor is allowed to shut itself down
+        Err(main::Error::Wrapper {
+            source: _source @ ioxd_common::Error::LostServer,
+        }) if process_once => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The catalog, object stores and executor a compactor runs against.
+#[derive(Debug)]
+pub(crate) struct CompactorDependencies {
+    pub(crate) catalog: Arc<dyn Catalog>,
+    pub(crate) parquet_store_real: ParquetStorage,
+    pub(crate) parquet_store_scratchpad: ParquetStorage,
+    pub(crate) exec: Arc<Executor>,
+}
+
+/// Connects to the catalog and object store, and creates the executor with
+/// the scratchpad store registered.
+pub(crate) async fn create_compactor_dependencies(
+    run_config: &RunConfig,
+    catalog_dsn: &CatalogDsnConfig,
+    compactor_config: &CompactorConfig,
+    metric_registry: &Arc<metric::Registry>,
+) -> Result<CompactorDependencies, Error> {
+    let time_provider = Arc::new(SystemProvider::new()) as Arc<dyn TimeProvider>;
+    let catalog = catalog_dsn
+        .get_catalog("compactor", Arc::clone(metric_registry))
         .await?;
 
-    let object_store = make_object_store(config.run_config.object_store_config())
-        .map_err(Error::ObjectStoreParsing)?;
+    let object_store =
+        make_object_store(run_config.object_store_config()).map_err(Error::ObjectStoreParsing)?;
 
     // Decorate the object store with a metric recorder.
     let object_store: Arc<DynObjectStore> = Arc::new(ObjectStoreMetrics::new(
         object_store,
-        Arc::clone(&time_provider),
-        &metric_registry,
+        time_provider,
+        metric_registry,
     ));
 
     let parquet_store_real = ParquetStorage::new(object_store, StorageId::from("iox"));
     let parquet_store_scratchpad = ParquetStorage::new(
         Arc::new(MetricsStore::new(
             Arc::new(object_store::memory::InMemory::new()),
-            &metric_registry,
+            metric_registry,
             "scratchpad",
         )),
         StorageId::from("iox_scratchpad"),
     );
 
-    let num_threads = config
-        .compactor_config
-        .query_exec_thread_count
-        .unwrap_or_else(|| {
-            NonZeroUsize::new(num_cpus::get().saturating_sub(1))
-                .unwrap_or_else(|| NonZeroUsize::new(1).unwrap())
-        });
+    let num_threads = compactor_config.query_exec_thread_count.unwrap_or_else(|| {
+        NonZeroUsize::new(num_cpus::get().saturating_sub(1))
+            .unwrap_or_else(|| NonZeroUsize::new(1).unwrap())
+    });
     info!(%num_threads, "using specified number of threads");
 
     let mem_pool_size = derive_mem_pool_size(
-        config.compactor_config.exec_mem_pool_bytes.bytes(),
-        config.compactor_config.exec_mem_pool_percent,
+        compactor_config.exec_mem_pool_bytes.bytes(),
+        compactor_config.exec_mem_pool_percent,
     );
 
     let exec = Arc::new(Executor::new_with_config(ExecutorConfig {
@@ -117,35 +173,14 @@ pub async fn command(config: Config) -> Result<(), Error> {
             .into_iter()
             .map(|store| (store.id(), Arc::clone(store.object_store())))
             .collect(),
-        metric_registry: Arc::clone(&metric_registry),
+        metric_registry: Arc::clone(metric_registry),
         mem_pool_size,
     }));
-    let time_provider = Arc::new(SystemProvider::new());
 
-    let process_once = config.compactor_config.process_once;
-    let server_type = create_compactor_server_type(
-        &common_state,
-        Arc::clone(&metric_registry),
+    Ok(CompactorDependencies {
         catalog,
         parquet_store_real,
         parquet_store_scratchpad,
         exec,
-        time_provider,
-        config.compactor_config,
-    )
-    .await;
-
-    info!("starting compactor");
-
-    let services = vec![Service::create(server_type, common_state.run_config())];
-
-    let res = main::main(common_state, services, metric_registry).await;
-    match res {
-        Ok(()) => Ok(()),
-        // compactor is allowed to shut itself down
-        Err(main::Error::Wrapper {
-            source: _source @ ioxd_common::Error::LostServer,
-        }) if process_once => Ok(()),
-        Err(e) => Err(e.into()),
-    }
+    })
 }
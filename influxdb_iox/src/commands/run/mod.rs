@@ -2,7 +2,7 @@ use snafu::{ResultExt, Snafu};
 use trogging::cli::LoggingConfig;
 
 pub(crate) mod all_in_one;
-mod compactor;
+pub(crate) mod compactor;
 mod garbage_collector;
 mod ingester;
 mod main;
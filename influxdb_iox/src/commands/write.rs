@@ -7,9 +7,14 @@ use std::{
     io::{BufReader, Read},
     num::NonZeroUsize,
     path::{Path, PathBuf},
+    sync::Arc,
     time::Instant,
 };
 
+use self::mapping::{Mapping, MappingConfig, TabularFormat};
+
+mod mapping;
+
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug, Snafu)]
 pub enum Error {
@@ -43,6 +48,15 @@ pub enum Error {
 
     #[snafu(display("Max concurrent uploads must be greater than zero"))]
     MaxConcurrentUploadsVerfication,
+
+    #[snafu(display("Error mapping {:?} to line protocol: {}", file_name, source))]
+    Mapping {
+        file_name: PathBuf,
+        source: mapping::Error,
+    },
+
+    #[snafu(display("Invalid column mapping: {}", source))]
+    InvalidMapping { source: mapping::Error },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -67,6 +81,46 @@ pub struct Config {
     /// .parquet (IOx created parquet files), and .gz (gzipped line protocol)
     #[clap(action)]
     file_names: Vec<PathBuf>,
+
+    /// The format of the files. `auto` determines the format from the file
+    /// extension, as described above. The `csv`, `parquet` and `arrow`
+    /// formats convert arbitrary tabular data to line protocol, as described
+    /// by the column mapping options.
+    #[clap(long, short = 'f', action)]
+    #[clap(value_enum, default_value_t = InputFormat::Auto)]
+    format: InputFormat,
+
+    #[clap(flatten)]
+    mapping: MappingConfig,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum InputFormat {
+    /// Determine the format from the file extension
+    Auto,
+
+    /// Line protocol, which may be gzipped
+    Lp,
+
+    /// CSV with a header row
+    Csv,
+
+    /// Parquet, not necessarily created by IOx
+    Parquet,
+
+    /// Arrow IPC file
+    Arrow,
+}
+
+impl InputFormat {
+    fn tabular(&self) -> Option<TabularFormat> {
+        match self {
+            Self::Auto | Self::Lp => None,
+            Self::Csv => Some(TabularFormat::Csv),
+            Self::Parquet => Some(TabularFormat::Parquet),
+            Self::Arrow => Some(TabularFormat::Arrow),
+        }
+    }
 }
 
 pub async fn command(connection: Connection, config: Config) -> Result<()> {
@@ -77,11 +131,22 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
         file_names,
         max_request_payload_size_bytes,
         max_concurrent_uploads,
+        format,
+        mapping,
     } = config;
 
     let max_concurrent_uploads =
         NonZeroUsize::new(max_concurrent_uploads).context(MaxConcurrentUploadsVerficationSnafu)?;
 
+    // validate the column mapping up front, rather than once per file
+    let tabular = match format.tabular() {
+        Some(tabular) => Some((
+            tabular,
+            Arc::new(mapping.mapping().context(InvalidMappingSnafu)?),
+        )),
+        None => None,
+    };
+
     info!(
         num_files = file_names.len(),
         max_request_payload_size_bytes, max_concurrent_uploads, "Beginning upload"
@@ -108,7 +173,17 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
     // if everything looked good, go through and read the files out
     // them in parallel.
     let lp_stream = futures_util::stream::iter(file_names)
-        .map(|file_name| tokio::task::spawn(slurp_file(file_name)))
+        .map(|file_name| match &tabular {
+            Some((format, mapping)) => {
+                // The conversion reads and converts the whole file
+                // synchronously, so keep it off the async worker threads.
+                let (format, mapping) = (*format, Arc::clone(mapping));
+                tokio::task::spawn_blocking(move || {
+                    convert_tabular_file(file_name, format, mapping)
+                })
+            }
+            None => tokio::task::spawn(slurp_file(file_name)),
+        })
         // Since the contents of each file are buffered into a string,
         // limit the number that are open at once to the maximum
         // possible uploads
@@ -237,6 +312,28 @@ async fn slurp_file(file_name: PathBuf) -> Result<BoxStream<'static, Result<Stri
     }
 }
 
+/// Converts the CSV, Parquet or Arrow file `file_name` to line protocol
+/// using `mapping`, returning one String per record batch read.
+fn convert_tabular_file(
+    file_name: PathBuf,
+    format: TabularFormat,
+    mapping: Arc<Mapping>,
+) -> Result<BoxStream<'static, Result<String>>> {
+    info!(
+        ?file_name,
+        file_size_bytes = file_size(&file_name),
+        ?format,
+        "Converting tabular file to line protocol"
+    );
+    let lp_data = mapping
+        .convert_file(&file_name, format)
+        .context(MappingSnafu {
+            file_name: &file_name,
+        })?;
+
+    Ok(futures::stream::iter(lp_data.into_iter().map(Ok)).boxed())
+}
+
 fn file_size(path: &Path) -> u64 {
     std::fs::metadata(path)
         .map(|meta| meta.len())
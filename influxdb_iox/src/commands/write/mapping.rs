@@ -0,0 +1,592 @@
+//! Conversion of tabular (CSV, Parquet and Arrow IPC) files to line protocol
+
+use std::{
+    fs::File,
+    io::{Seek, SeekFrom},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+
+use arrow::{
+    array::{
+        Array, ArrayRef, AsArray, BooleanArray, Float64Array, Int64Array, StringArray, UInt64Array,
+    },
+    compute::cast,
+    datatypes::{DataType, Float64Type, Int64Type, TimeUnit, TimestampNanosecondType, UInt64Type},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use chrono::{DateTime, NaiveDateTime};
+use influxdb_line_protocol::{
+    builder::{AfterField, AfterMeasurement},
+    LineProtocolBuilder,
+};
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Either --measurement or --measurement-column must be specified"))]
+    NoMeasurement,
+
+    #[snafu(display(
+        "Invalid timestamp format {:?}, expected one of auto, rfc3339, ns, us, ms, s \
+         or a strftime pattern",
+        format
+    ))]
+    InvalidTimestampFormat { format: String },
+
+    #[snafu(display("Error opening {:?}: {}", file_name, source))]
+    Opening {
+        file_name: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[snafu(display("Error reading {:?}: {}", file_name, source))]
+    Reading {
+        file_name: PathBuf,
+        source: ArrowError,
+    },
+
+    #[snafu(display("Error reading parquet file {:?}: {}", file_name, source))]
+    ReadingParquet {
+        file_name: PathBuf,
+        source: parquet::errors::ParquetError,
+    },
+
+    #[snafu(display("Column {:?} not found", name))]
+    MissingColumn { name: String },
+
+    #[snafu(display("Column {:?} has unsupported type {}", name, data_type))]
+    UnsupportedType { name: String, data_type: DataType },
+
+    #[snafu(display("Error converting column {:?}: {}", name, source))]
+    Cast { name: String, source: ArrowError },
+
+    #[snafu(display("Row {} has no timestamp", row))]
+    NullTimestamp { row: usize },
+
+    #[snafu(display("Row {} has no measurement", row))]
+    NullMeasurement { row: usize },
+
+    #[snafu(display("Invalid timestamp {:?} in row {}", value, row))]
+    InvalidTimestamp { value: String, row: usize },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The tabular file formats that can be mapped to line protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TabularFormat {
+    Csv,
+    Parquet,
+    Arrow,
+}
+
+/// How the columns of CSV, Parquet or Arrow files map to measurements, tags,
+/// fields and timestamps
+#[derive(Debug, clap::Args)]
+pub struct MappingConfig {
+    /// The measurement name to write all rows to
+    #[clap(long, action, conflicts_with = "measurement_column")]
+    measurement: Option<String>,
+
+    /// The column holding the measurement name of each row
+    #[clap(long, action)]
+    measurement_column: Option<String>,
+
+    /// Comma separated names of the columns to write as tags
+    #[clap(long, action, value_delimiter = ',')]
+    tag_columns: Vec<String>,
+
+    /// Comma separated names of the columns to write as fields. Defaults to
+    /// all columns that are not tags, the timestamp or the measurement column
+    #[clap(long, action, value_delimiter = ',')]
+    field_columns: Vec<String>,
+
+    /// The column holding the timestamp of each row
+    #[clap(long, action, default_value = "time")]
+    timestamp_column: String,
+
+    /// How to interpret the timestamp column: `auto` (integers are
+    /// nanoseconds, strings are RFC3339), the precision of integer timestamps
+    /// (`ns`, `us`, `ms` or `s`), `rfc3339` or a strftime pattern such as
+    /// `%Y-%m-%d %H:%M:%S`
+    #[clap(long, action, default_value = "auto")]
+    timestamp_format: String,
+}
+
+impl MappingConfig {
+    /// Validate the configuration, returning the [`Mapping`] it describes.
+    pub fn mapping(&self) -> Result<Mapping> {
+        let measurement = match (&self.measurement, &self.measurement_column) {
+            (Some(name), _) => Measurement::Constant(name.clone()),
+            (None, Some(column)) => Measurement::Column(column.clone()),
+            (None, None) => return NoMeasurementSnafu.fail(),
+        };
+
+        Ok(Mapping {
+            measurement,
+            tag_columns: self.tag_columns.clone(),
+            field_columns: self.field_columns.clone(),
+            timestamp_column: self.timestamp_column.clone(),
+            timestamp_format: self.timestamp_format.parse()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Measurement {
+    Constant(String),
+    Column(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TimestampFormat {
+    /// Integers are nanoseconds, strings RFC3339
+    Auto,
+    /// Integers (or integer strings) in units of this many nanoseconds
+    Precision(i64),
+    Rfc3339,
+    Strftime(String),
+}
+
+impl FromStr for TimestampFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "auto" => Self::Auto,
+            "rfc3339" => Self::Rfc3339,
+            "ns" => Self::Precision(1),
+            "us" => Self::Precision(1_000),
+            "ms" => Self::Precision(1_000_000),
+            "s" => Self::Precision(1_000_000_000),
+            _ if s.contains('%') => Self::Strftime(s.to_string()),
+            _ => return InvalidTimestampFormatSnafu { format: s }.fail(),
+        })
+    }
+}
+
+impl TimestampFormat {
+    fn parse(&self, value: &str, row: usize) -> Result<i64> {
+        let invalid = || Error::InvalidTimestamp {
+            value: value.to_string(),
+            row,
+        };
+        match self {
+            Self::Precision(multiplier) => value
+                .parse::<i64>()
+                .ok()
+                .and_then(|v| v.checked_mul(*multiplier))
+                .ok_or_else(invalid),
+            Self::Auto | Self::Rfc3339 => DateTime::parse_from_rfc3339(value)
+                .ok()
+                .and_then(|t| t.timestamp_nanos_opt())
+                .ok_or_else(invalid),
+            Self::Strftime(format) => DateTime::parse_from_str(value, format)
+                .ok()
+                .and_then(|t| t.timestamp_nanos_opt())
+                .or_else(|| {
+                    // Patterns without a timezone are taken to be UTC
+                    NaiveDateTime::parse_from_str(value, format)
+                        .ok()
+                        .and_then(|t| t.timestamp_nanos_opt())
+                })
+                .ok_or_else(invalid),
+        }
+    }
+}
+
+/// A validated [`MappingConfig`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapping {
+    measurement: Measurement,
+    tag_columns: Vec<String>,
+    field_columns: Vec<String>,
+    timestamp_column: String,
+    timestamp_format: TimestampFormat,
+}
+
+/// The values of a field column, typed as one of the line protocol field
+/// types.
+#[derive(Debug)]
+enum FieldArray {
+    F64(Float64Array),
+    I64(Int64Array),
+    U64(UInt64Array),
+    Bool(BooleanArray),
+    String(StringArray),
+}
+
+impl Mapping {
+    /// Read `file_name` in the given format, returning line protocol with
+    /// one `String` per record batch.
+    pub fn convert_file(&self, file_name: &Path, format: TabularFormat) -> Result<Vec<String>> {
+        let mut file = File::open(file_name).context(OpeningSnafu { file_name })?;
+
+        let batches: Box<dyn Iterator<Item = Result<RecordBatch, ArrowError>>> = match format {
+            TabularFormat::Csv => {
+                let (schema, _) = arrow::csv::reader::Format::default()
+                    .with_header(true)
+                    .infer_schema(&mut file, Some(1000))
+                    .context(ReadingSnafu { file_name })?;
+                file.seek(SeekFrom::Start(0))
+                    .context(OpeningSnafu { file_name })?;
+                Box::new(
+                    arrow::csv::ReaderBuilder::new(Arc::new(schema))
+                        .with_header(true)
+                        .build(file)
+                        .context(ReadingSnafu { file_name })?,
+                )
+            }
+            TabularFormat::Parquet => Box::new(
+                parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(file)
+                    .and_then(|builder| builder.build())
+                    .context(ReadingParquetSnafu { file_name })?,
+            ),
+            TabularFormat::Arrow => Box::new(
+                arrow::ipc::reader::FileReader::try_new(file, None)
+                    .context(ReadingSnafu { file_name })?,
+            ),
+        };
+
+        batches
+            .map(|batch| {
+                let batch = batch.context(ReadingSnafu { file_name })?;
+                self.convert_batch(&batch)
+            })
+            .collect()
+    }
+
+    /// Convert `batch` to line protocol. Rows without any non-null field are
+    /// skipped, as they can not be represented in line protocol.
+    pub fn convert_batch(&self, batch: &RecordBatch) -> Result<String> {
+        let measurements = match &self.measurement {
+            Measurement::Constant(_) => None,
+            Measurement::Column(name) => Some(string_column(batch, name)?),
+        };
+
+        let tags = self
+            .tag_columns
+            .iter()
+            .map(|name| Ok((name.as_str(), string_column(batch, name)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let field_names = match self.field_columns.is_empty() {
+            false => self.field_columns.clone(),
+            true => batch
+                .schema()
+                .fields()
+                .iter()
+                .map(|f| f.name().clone())
+                .filter(|name| {
+                    *name != self.timestamp_column
+                        && !self.tag_columns.contains(name)
+                        && self.measurement != Measurement::Column(name.clone())
+                })
+                .collect(),
+        };
+        let fields = field_names
+            .iter()
+            .map(|name| Ok((name.as_str(), field_column(batch, name)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let timestamps = self.timestamps(batch)?;
+
+        let mut lp = LineProtocolBuilder::new();
+        for row in 0..batch.num_rows() {
+            let measurement = match (&self.measurement, &measurements) {
+                (Measurement::Constant(name), _) => name.as_str(),
+                (_, Some(values)) => {
+                    ensure!(values.is_valid(row), NullMeasurementSnafu { row });
+                    values.value(row)
+                }
+                (Measurement::Column(_), None) => unreachable!("measurement column was read"),
+            };
+
+            let mut row_fields = fields.iter().filter(|(_, values)| values.is_valid(row));
+            let Some((name, values)) = row_fields.next() else {
+                continue;
+            };
+
+            let line = tags
+                .iter()
+                .filter(|(_, values)| values.is_valid(row) && !values.value(row).is_empty())
+                .fold(lp.measurement(measurement), |line, (name, values)| {
+                    line.tag(name, values.value(row))
+                });
+            let line = values.first_field(line, name, row);
+            let line = row_fields.fold(line, |line, (name, values)| {
+                values.next_field(line, name, row)
+            });
+            lp = line.timestamp(timestamps[row]).close_line();
+        }
+
+        Ok(String::from_utf8(lp.build()).expect("line protocol is valid utf8"))
+    }
+
+    /// Return the timestamp of each row of `batch`, in nanoseconds.
+    fn timestamps(&self, batch: &RecordBatch) -> Result<Vec<i64>> {
+        let name = &self.timestamp_column;
+        let column = batch
+            .column_by_name(name)
+            .context(MissingColumnSnafu { name })?;
+
+        match column.data_type() {
+            DataType::Timestamp(_, _) | DataType::Date32 | DataType::Date64 => {
+                let values = cast_column(
+                    name,
+                    column,
+                    &DataType::Timestamp(TimeUnit::Nanosecond, None),
+                )?;
+                let values = values.as_primitive::<TimestampNanosecondType>();
+                (0..values.len())
+                    .map(|row| {
+                        ensure!(values.is_valid(row), NullTimestampSnafu { row });
+                        Ok(values.value(row))
+                    })
+                    .collect()
+            }
+            t if t.is_integer() => {
+                let multiplier = match self.timestamp_format {
+                    TimestampFormat::Precision(multiplier) => multiplier,
+                    _ => 1,
+                };
+                let values = cast_column(name, column, &DataType::Int64)?;
+                let values = values.as_primitive::<Int64Type>();
+                (0..values.len())
+                    .map(|row| {
+                        ensure!(values.is_valid(row), NullTimestampSnafu { row });
+                        values
+                            .value(row)
+                            .checked_mul(multiplier)
+                            .context(InvalidTimestampSnafu {
+                                value: values.value(row).to_string(),
+                                row,
+                            })
+                    })
+                    .collect()
+            }
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Dictionary(_, _) => {
+                let values = string_column(batch, name)?;
+                (0..values.len())
+                    .map(|row| {
+                        ensure!(values.is_valid(row), NullTimestampSnafu { row });
+                        self.timestamp_format.parse(values.value(row), row)
+                    })
+                    .collect()
+            }
+            data_type => UnsupportedTypeSnafu {
+                name,
+                data_type: data_type.clone(),
+            }
+            .fail(),
+        }
+    }
+}
+
+impl FieldArray {
+    fn is_valid(&self, row: usize) -> bool {
+        match self {
+            Self::F64(a) => a.is_valid(row),
+            Self::I64(a) => a.is_valid(row),
+            Self::U64(a) => a.is_valid(row),
+            Self::Bool(a) => a.is_valid(row),
+            Self::String(a) => a.is_valid(row),
+        }
+    }
+
+    /// Add the value of `row` as the first field of a line.
+    fn first_field(
+        &self,
+        line: LineProtocolBuilder<Vec<u8>, AfterMeasurement>,
+        name: &str,
+        row: usize,
+    ) -> LineProtocolBuilder<Vec<u8>, AfterField> {
+        match self {
+            Self::F64(a) => line.field(name, a.value(row)),
+            Self::I64(a) => line.field(name, a.value(row)),
+            Self::U64(a) => line.field(name, a.value(row)),
+            Self::Bool(a) => line.field(name, a.value(row)),
+            Self::String(a) => line.field(name, a.value(row)),
+        }
+    }
+
+    /// Add the value of `row` as a subsequent field of a line.
+    fn next_field(
+        &self,
+        line: LineProtocolBuilder<Vec<u8>, AfterField>,
+        name: &str,
+        row: usize,
+    ) -> LineProtocolBuilder<Vec<u8>, AfterField> {
+        match self {
+            Self::F64(a) => line.field(name, a.value(row)),
+            Self::I64(a) => line.field(name, a.value(row)),
+            Self::U64(a) => line.field(name, a.value(row)),
+            Self::Bool(a) => line.field(name, a.value(row)),
+            Self::String(a) => line.field(name, a.value(row)),
+        }
+    }
+}
+
+fn cast_column(name: &str, column: &ArrayRef, data_type: &DataType) -> Result<ArrayRef> {
+    cast(column, data_type).context(CastSnafu { name })
+}
+
+/// Read the column `name` of `batch` as strings.
+fn string_column(batch: &RecordBatch, name: &str) -> Result<StringArray> {
+    let column = batch
+        .column_by_name(name)
+        .context(MissingColumnSnafu { name })?;
+    Ok(cast_column(name, column, &DataType::Utf8)?
+        .as_string::<i32>()
+        .clone())
+}
+
+/// Read the column `name` of `batch` as the line protocol field type
+/// closest to its arrow type.
+fn field_column(batch: &RecordBatch, name: &str) -> Result<FieldArray> {
+    let column = batch
+        .column_by_name(name)
+        .context(MissingColumnSnafu { name })?;
+
+    Ok(match column.data_type() {
+        DataType::Float16 | DataType::Float32 | DataType::Float64 => FieldArray::F64(
+            cast_column(name, column, &DataType::Float64)?
+                .as_primitive::<Float64Type>()
+                .clone(),
+        ),
+        t if t.is_signed_integer() => FieldArray::I64(
+            cast_column(name, column, &DataType::Int64)?
+                .as_primitive::<Int64Type>()
+                .clone(),
+        ),
+        t if t.is_unsigned_integer() => FieldArray::U64(
+            cast_column(name, column, &DataType::UInt64)?
+                .as_primitive::<UInt64Type>()
+                .clone(),
+        ),
+        DataType::Boolean => FieldArray::Bool(column.as_boolean().clone()),
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Dictionary(_, _) => {
+            FieldArray::String(string_column(batch, name)?)
+        }
+        data_type => {
+            return UnsupportedTypeSnafu {
+                name,
+                data_type: data_type.clone(),
+            }
+            .fail()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::{Int64Array, TimestampMillisecondArray},
+        datatypes::{Field, Schema},
+    };
+
+    use super::*;
+
+    fn mapping(measurement: Measurement, timestamp_format: &str) -> Mapping {
+        Mapping {
+            measurement,
+            tag_columns: vec!["host".to_string()],
+            field_columns: vec![],
+            timestamp_column: "time".to_string(),
+            timestamp_format: timestamp_format.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_convert_batch() {
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "host",
+                Arc::new(StringArray::from(vec![Some("a"), None, Some("c")])) as ArrayRef,
+            ),
+            (
+                "usage",
+                Arc::new(Float64Array::from(vec![Some(1.5), Some(2.0), None])) as ArrayRef,
+            ),
+            (
+                "count",
+                Arc::new(Int64Array::from(vec![Some(1), None, None])) as ArrayRef,
+            ),
+            (
+                "time",
+                Arc::new(TimestampMillisecondArray::from(vec![1, 2, 3])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+
+        let lp = mapping(Measurement::Constant("cpu".to_string()), "auto")
+            .convert_batch(&batch)
+            .unwrap();
+
+        // The last row has no fields, so is skipped
+        assert_eq!(
+            lp,
+            "cpu,host=a usage=1.5,count=1i 1000000\n\
+             cpu usage=2 2000000\n"
+        );
+    }
+
+    #[test]
+    fn test_measurement_column_and_string_timestamps() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("table", DataType::Utf8, false),
+            Field::new("host", DataType::Utf8, false),
+            Field::new("value", DataType::Utf8, false),
+            Field::new("time", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec!["cpu", "mem"])),
+                Arc::new(StringArray::from(vec!["a", "b"])),
+                Arc::new(StringArray::from(vec!["x", "y"])),
+                Arc::new(StringArray::from(vec![
+                    "2023-01-01 00:00:00",
+                    "2023-01-01 00:00:01",
+                ])),
+            ],
+        )
+        .unwrap();
+
+        let lp = mapping(
+            Measurement::Column("table".to_string()),
+            "%Y-%m-%d %H:%M:%S",
+        )
+        .convert_batch(&batch)
+        .unwrap();
+
+        assert_eq!(
+            lp,
+            "cpu,host=a value=\"x\" 1672531200000000000\n\
+             mem,host=b value=\"y\" 1672531201000000000\n"
+        );
+    }
+
+    #[test]
+    fn test_integer_timestamp_precision() {
+        let batch = RecordBatch::try_from_iter(vec![
+            ("value", Arc::new(Int64Array::from(vec![1])) as ArrayRef),
+            ("time", Arc::new(Int64Array::from(vec![42])) as ArrayRef),
+        ])
+        .unwrap();
+
+        let lp = mapping(Measurement::Constant("m".to_string()), "s")
+            .convert_batch(&batch)
+            .unwrap();
+        assert_eq!(lp, "m value=1i 42000000000\n");
+    }
+
+    #[test]
+    fn test_invalid_timestamp_format() {
+        assert!("minutes".parse::<TimestampFormat>().is_err());
+    }
+}
@@ -38,7 +38,9 @@ use trace_exporters::{
 
 mod commands {
     pub mod catalog;
+    pub mod compactor;
     pub mod debug;
+    pub mod export;
     pub mod namespace;
     pub mod partition_template;
     pub mod query;
@@ -207,6 +209,9 @@ enum Command {
     /// Various commands for catalog manipulation
     Catalog(commands::catalog::Config),
 
+    /// Explain or run the compactor's decisions for a single partition
+    Compactor(commands::compactor::Config),
+
     /// Interrogate internal data
     Debug(commands::debug::Config),
 
@@ -219,6 +224,9 @@ enum Command {
     /// Query the data with SQL
     Query(commands::query::Config),
 
+    /// Export a time range of a table to Parquet, CSV, Arrow or line protocol files
+    Export(commands::export::Config),
+
     /// Query the ingester only
     QueryIngester(commands::query_ingester::Config),
 
@@ -344,6 +352,13 @@ fn main() -> Result<(), std::io::Error> {
                     std::process::exit(ReturnCode::Failure as _)
                 }
             }
+            Some(Command::Compactor(config)) => {
+                let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
+                if let Err(e) = commands::compactor::command(config).await {
+                    eprintln!("{e}");
+                    std::process::exit(ReturnCode::Failure as _)
+                }
+            }
             Some(Command::Debug(config)) => {
                 let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
                 if let Err(e) = commands::debug::command(|| connection(grpc_host), config).await {
@@ -367,6 +382,14 @@ fn main() -> Result<(), std::io::Error> {
                     std::process::exit(ReturnCode::Failure as _)
                 }
             }
+            Some(Command::Export(config)) => {
+                let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
+                let connection = connection(grpc_host).await;
+                if let Err(e) = commands::export::command(connection, config).await {
+                    eprintln!("{e}");
+                    std::process::exit(ReturnCode::Failure as _)
+                }
+            }
             Some(Command::QueryIngester(config)) => {
                 let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
                 let connection = connection(grpc_host).await;
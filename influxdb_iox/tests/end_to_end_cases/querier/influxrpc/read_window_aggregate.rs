@@ -189,6 +189,90 @@ async fn nanoseconds_measurement_count() {
     .await;
 }
 
+#[tokio::test]
+async fn nanoseconds_first() {
+    Arc::new(ReadWindowAggregateTest {
+        setup_name: "MeasurementForWindowAggregate",
+        aggregate_type: AggregateType::First,
+        every: 200,
+        offset: 0,
+        request: GrpcRequestBuilder::new().timestamp_range(100, 450),
+        expected_results: vec![
+            // selectors return the timestamp of the selected point
+            "SeriesFrame, tags: _field=temp,_measurement=h2o,city=Boston,state=MA, type: 0",
+            "FloatPointsFrame, timestamps: [100, 200, 400], values: \"70,71,73\"",
+            "SeriesFrame, tags: _field=temp,_measurement=h2o,city=Cambridge,state=MA, type: 0",
+            "FloatPointsFrame, timestamps: [100, 200, 400], values: \"80,81,83\"",
+            "SeriesFrame, tags: _field=temp,_measurement=h2o,city=LA,state=CA, type: 0",
+            "FloatPointsFrame, timestamps: [100, 200, 400], values: \"90,91,93\"",
+        ],
+    })
+    .run()
+    .await;
+}
+
+#[tokio::test]
+async fn nanoseconds_last() {
+    Arc::new(ReadWindowAggregateTest {
+        setup_name: "MeasurementForWindowAggregate",
+        aggregate_type: AggregateType::Last,
+        every: 200,
+        offset: 0,
+        request: GrpcRequestBuilder::new().timestamp_range(100, 450),
+        expected_results: vec![
+            "SeriesFrame, tags: _field=temp,_measurement=h2o,city=Boston,state=MA, type: 0",
+            "FloatPointsFrame, timestamps: [100, 300, 400], values: \"70,72,73\"",
+            "SeriesFrame, tags: _field=temp,_measurement=h2o,city=Cambridge,state=MA, type: 0",
+            "FloatPointsFrame, timestamps: [100, 300, 400], values: \"80,82,83\"",
+            "SeriesFrame, tags: _field=temp,_measurement=h2o,city=LA,state=CA, type: 0",
+            "FloatPointsFrame, timestamps: [100, 300, 400], values: \"90,92,93\"",
+        ],
+    })
+    .run()
+    .await;
+}
+
+#[tokio::test]
+async fn nanoseconds_offset() {
+    Arc::new(ReadWindowAggregateTest {
+        setup_name: "MeasurementForWindowAggregate",
+        aggregate_type: AggregateType::Sum,
+        every: 200,
+        offset: 100,
+        request: GrpcRequestBuilder::new().timestamp_range(100, 450),
+        expected_results: vec![
+            // windows are [100, 300) and [300, 500)
+            "SeriesFrame, tags: _field=temp,_measurement=h2o,city=Boston,state=MA, type: 0",
+            "FloatPointsFrame, timestamps: [300, 500], values: \"141,145\"",
+            "SeriesFrame, tags: _field=temp,_measurement=h2o,city=Cambridge,state=MA, type: 0",
+            "FloatPointsFrame, timestamps: [300, 500], values: \"161,165\"",
+            "SeriesFrame, tags: _field=temp,_measurement=h2o,city=LA,state=CA, type: 0",
+            "FloatPointsFrame, timestamps: [300, 500], values: \"181,185\"",
+        ],
+    })
+    .run()
+    .await;
+}
+
+#[tokio::test]
+async fn nanoseconds_offset_last() {
+    Arc::new(ReadWindowAggregateTest {
+        setup_name: "MeasurementForWindowAggregate",
+        aggregate_type: AggregateType::Last,
+        every: 200,
+        offset: 100,
+        request: GrpcRequestBuilder::new()
+            .tag_predicate("city", "Boston")
+            .timestamp_range(100, 450),
+        expected_results: vec![
+            "SeriesFrame, tags: _field=temp,_measurement=h2o,city=Boston,state=MA, type: 0",
+            "FloatPointsFrame, timestamps: [200, 400], values: \"71,73\"",
+        ],
+    })
+    .run()
+    .await;
+}
+
 // See <https://github.com/influxdata/influxdb_iox/issues/2697>
 #[tokio::test]
 async fn min_defect_2697() {
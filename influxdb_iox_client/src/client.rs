@@ -1,6 +1,12 @@
+/// Client for the querier cache API
+pub mod cache;
+
 /// Client for interacting with a remote catalog
 pub mod catalog;
 
+/// Client for the cluster membership API
+pub mod cluster;
+
 /// Client for the compactor API
 pub mod compactor;
 
@@ -0,0 +1,47 @@
+use self::generated_types::{cache_service_client::CacheServiceClient, *};
+use crate::{connection::Connection, error::Error};
+use client_util::connection::GrpcConnection;
+
+/// Re-export generated_types
+pub mod generated_types {
+    pub use generated_types::influxdata::iox::querier::v1::{
+        cache_service_client, PrefetchRequest, PrefetchResponse,
+    };
+}
+
+/// A basic client for interacting with the querier cache service.
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: CacheServiceClient<GrpcConnection>,
+}
+
+impl Client {
+    /// Creates a new client with the provided connection
+    pub fn new(connection: Connection) -> Self {
+        Self {
+            inner: CacheServiceClient::new(connection.into_grpc_connection()),
+        }
+    }
+
+    /// Preload the querier caches with the partitions of `namespace` that received new files
+    /// within the given time range (nanoseconds since the epoch, both ends exclusive).
+    pub async fn prefetch(
+        &mut self,
+        namespace: impl Into<String> + Send,
+        start_time_ns: i64,
+        end_time_ns: Option<i64>,
+        parquet_data: bool,
+    ) -> Result<PrefetchResponse, Error> {
+        let response = self
+            .inner
+            .prefetch(PrefetchRequest {
+                namespace: namespace.into(),
+                start_time_ns,
+                end_time_ns,
+                parquet_data,
+            })
+            .await?;
+
+        Ok(response.into_inner())
+    }
+}
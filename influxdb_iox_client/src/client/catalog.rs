@@ -4,6 +4,7 @@ use self::generated_types::{catalog_service_client::CatalogServiceClient, *};
 
 use crate::connection::Connection;
 use crate::error::Error;
+use ::generated_types::google::OptionalField;
 
 /// Re-export generated_types
 pub mod generated_types {
@@ -88,4 +89,71 @@ impl Client {
 
         Ok(response.into_inner().parquet_files)
     }
+
+    /// Flag the Parquet files with the given object store ids for deletion,
+    /// returning the affected records.
+    ///
+    /// If `dry_run` is set, the catalog is left unchanged.
+    pub async fn flag_parquet_files_for_deletion(
+        &mut self,
+        object_store_ids: Vec<String>,
+        dry_run: bool,
+    ) -> Result<Vec<ParquetFile>, Error> {
+        let response = self
+            .inner
+            .flag_parquet_files_for_deletion(FlagParquetFilesForDeletionRequest {
+                object_store_ids,
+                dry_run,
+            })
+            .await?;
+
+        Ok(response.into_inner().parquet_files)
+    }
+
+    /// Change the compaction level of the Parquet files with the given
+    /// object store ids, returning the updated records.
+    ///
+    /// If `dry_run` is set, the catalog is left unchanged.
+    pub async fn update_parquet_file_compaction_level(
+        &mut self,
+        object_store_ids: Vec<String>,
+        compaction_level: i32,
+        dry_run: bool,
+    ) -> Result<Vec<ParquetFile>, Error> {
+        let response = self
+            .inner
+            .update_parquet_file_compaction_level(UpdateParquetFileCompactionLevelRequest {
+                object_store_ids,
+                compaction_level,
+                dry_run,
+            })
+            .await?;
+
+        Ok(response.into_inner().parquet_files)
+    }
+
+    /// Reset or replace the sort key of a partition, returning the updated
+    /// record.
+    pub async fn set_partition_sort_key(
+        &mut self,
+        request: SetPartitionSortKeyRequest,
+    ) -> Result<Partition, Error> {
+        let response = self.inner.set_partition_sort_key(request).await?;
+
+        Ok(response.into_inner().partition.unwrap_field("partition")?)
+    }
+
+    /// Register a Parquet file present in object storage with the catalog,
+    /// returning the new record.
+    pub async fn register_parquet_file(
+        &mut self,
+        request: RegisterParquetFileRequest,
+    ) -> Result<ParquetFile, Error> {
+        let response = self.inner.register_parquet_file(request).await?;
+
+        Ok(response
+            .into_inner()
+            .parquet_file
+            .unwrap_field("parquet_file")?)
+    }
 }
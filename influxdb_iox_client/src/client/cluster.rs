@@ -0,0 +1,37 @@
+use self::generated_types::{cluster_service_client::ClusterServiceClient, *};
+use crate::{connection::Connection, error::Error};
+use client_util::connection::GrpcConnection;
+
+/// Re-export generated_types
+pub mod generated_types {
+    pub use generated_types::influxdata::iox::cluster::v1::{
+        cluster_service_client, ClusterMember, GetClusterMembersRequest, GetClusterMembersResponse,
+        Role,
+    };
+}
+
+/// A basic client for inspecting the cluster membership observed by a remote
+/// node.
+#[derive(Debug, Clone)]
+pub struct Client {
+    inner: ClusterServiceClient<GrpcConnection>,
+}
+
+impl Client {
+    /// Creates a new client with the provided connection
+    pub fn new(connection: Connection) -> Self {
+        Self {
+            inner: ClusterServiceClient::new(connection.into_grpc_connection()),
+        }
+    }
+
+    /// Return the cluster members known to the remote node.
+    pub async fn get_cluster_members(&mut self) -> Result<Vec<ClusterMember>, Error> {
+        let response = self
+            .inner
+            .get_cluster_members(GetClusterMembersRequest {})
+            .await?;
+
+        Ok(response.into_inner().members)
+    }
+}
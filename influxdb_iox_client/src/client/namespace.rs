@@ -108,6 +108,28 @@ impl Client {
         Ok(response.into_inner().namespace.unwrap_field("namespace")?)
     }
 
+    /// Update the query quota of a namespace
+    ///
+    /// [`None`] removes the corresponding namespace-specific limit. Zero-valued
+    /// limits are rejected, returning an error.
+    pub async fn update_namespace_query_quota(
+        &mut self,
+        namespace: &str,
+        max_concurrent_queries: Option<i32>,
+        max_query_memory_bytes: Option<i64>,
+    ) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .update_namespace_query_quota(UpdateNamespaceQueryQuotaRequest {
+                name: namespace.to_string(),
+                max_concurrent_queries,
+                max_query_memory_bytes,
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
+
     /// Delete a namespace
     pub async fn delete_namespace(&mut self, namespace: &str) -> Result<(), Error> {
         self.inner
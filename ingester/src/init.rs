@@ -1,6 +1,7 @@
 use data_types::ParquetFile;
 use gossip::{NopDispatcher, TopicInterests};
 
+use gossip_membership::{tx::MembershipTx, MemberInfo, Role};
 use gossip_parquet_file::tx::ParquetFileTx;
 /// This needs to be pub for the benchmarks but should not be used outside the crate.
 #[cfg(feature = "benches")]
@@ -22,6 +23,7 @@ use generated_types::influxdata::iox::{
 };
 use iox_catalog::interface::Catalog;
 use iox_query::exec::Executor;
+use iox_time::{SystemProvider, TimeProvider};
 use observability_deps::tracing::*;
 use parquet_file::storage::ParquetStorage;
 use thiserror::Error;
@@ -121,6 +123,11 @@ pub struct IngesterGuard<T> {
     /// The task handle executing the graceful shutdown once triggered.
     graceful_shutdown_handler: tokio::task::JoinHandle<()>,
     shutdown_complete: Shared<oneshot::Receiver<()>>,
+
+    /// The cluster membership announcer, if gossip is enabled.
+    ///
+    /// Stops announcing this ingester on drop.
+    _membership_tx: Option<MembershipTx>,
 }
 
 impl<T> IngesterGuard<T>
@@ -167,6 +174,16 @@ pub enum GossipConfig {
         ///   - "10.0.0.1:port"
         ///
         peers: Vec<String>,
+        /// Shared keys used to authenticate gossip frames, in priority order.
+        ///
+        /// If empty, frames are unauthenticated.
+        shared_keys: Vec<gossip::SharedKey>,
+        /// The protection applied to outgoing frames when `shared_keys` is
+        /// non-empty.
+        protection: gossip::FrameProtection,
+        /// The gRPC address advertised to peers in cluster membership
+        /// announcements.
+        rpc_address: String,
     },
 }
 
@@ -266,6 +283,14 @@ pub enum InitError {
 /// Decreasing this value increases the frequency of persist operations, and
 /// usually decreases the size of the resulting parquet files.
 ///
+/// ## WAL Salvage
+///
+/// By default a corrupt WAL segment fails the WAL replay at startup, leaving
+/// the segment for investigation. If `wal_replay_salvage` is true, the
+/// readable entries of a corrupt segment are replayed instead, the original
+/// file moved to the "quarantine" directory of the WAL directory and the
+/// sequence numbers lost logged.
+///
 /// [`MutableBatch::size_data()`]: mutable_batch::MutableBatch::size_data
 #[allow(clippy::too_many_arguments)]
 pub async fn new<F>(
@@ -274,6 +299,7 @@ pub async fn new<F>(
     persist_background_fetch_time: Duration,
     wal_directory: PathBuf,
     wal_rotation_period: Duration,
+    wal_replay_salvage: bool,
     persist_executor: Arc<Executor>,
     persist_workers: usize,
     persist_queue_depth: usize,
@@ -372,12 +398,18 @@ where
 
     // Optionally start the gossip subsystem and layer on the parquet file
     // gossip handler.
-    let persist_observer = match gossip {
+    let (persist_observer, membership_tx) = match gossip {
         GossipConfig::Disabled => {
             info!("gossip disabled");
-            MaybeLayer::Without(persist_observer)
+            (MaybeLayer::Without(persist_observer), None)
         }
-        GossipConfig::Enabled { bind_addr, peers } => {
+        GossipConfig::Enabled {
+            bind_addr,
+            peers,
+            shared_keys,
+            protection,
+            rpc_address,
+        } => {
             // Start the gossip sub-system, which logs during init.
             let handle = gossip::Builder::<_, Topic>::new(
                 peers,
@@ -387,16 +419,30 @@ where
             // Configure the ingester to ignore all user payloads, only acting
             // as a gossip peer exchange seed and sender of messages.
             .with_topic_filter(TopicInterests::default())
+            .with_shared_keys(shared_keys, protection)
             .bind(bind_addr)
             .await
+            .map(Arc::new)
             .map_err(InitError::GossipBind)?;
 
+            // Announce this ingester to the cluster, allowing routers to
+            // discover it.
+            let membership_tx = MembershipTx::new(
+                Arc::clone(&handle),
+                MemberInfo {
+                    role: Role::Ingester,
+                    rpc_address,
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                    started_at: SystemProvider::new().now(),
+                },
+            );
+
             let persist_observer = ParquetFileNotification::new(
                 persist_observer,
                 ParquetFileTx::<ParquetFile>::new(handle),
             );
 
-            MaybeLayer::With(persist_observer)
+            (MaybeLayer::With(persist_observer), Some(membership_tx))
         }
     };
 
@@ -471,6 +517,7 @@ where
         Arc::clone(&persist_handle),
         Arc::clone(&ingest_state),
         &metrics,
+        wal_replay_salvage,
     )
     .await
     .map_err(|e| InitError::WalReplay(e.into()))?;
@@ -550,5 +597,6 @@ where
         disk_metric_task,
         graceful_shutdown_handler: shutdown_task,
         shutdown_complete: shutdown_rx.shared(),
+        _membership_tx: membership_tx,
     })
 }
@@ -36,6 +36,10 @@ pub enum WalReplayError {
     #[error("failed to read wal entry: {0}")]
     ReadEntry(wal::Error, Option<SequenceNumber>),
 
+    /// An error salvaging the readable entries of a corrupt segment file.
+    #[error("failed to repair corrupt wal segment: {0}")]
+    RepairSegment(wal::Error),
+
     /// An error converting the WAL entry into a [`IngestOp`].
     #[error("failed converting wal entry to ingest operation: {0}")]
     MapToDml(#[from] mutable_batch_pb::decode::Error),
@@ -61,6 +65,10 @@ pub trait WalReader: Debug + Send + Sync + 'static {
     /// Lists the closed segments available for reading from the WAL as (id, size) tuples.
     fn closed_segments(&self) -> Vec<(SegmentId, u64)>;
 
+    /// Replaces the closed segment specified with one containing only its
+    /// readable entries.
+    fn repair_closed_segment(&self, id: SegmentId) -> Result<wal::RepairReport, wal::Error>;
+
     /// Deletes the closed segment specified.
     async fn delete(&self, id: SegmentId) -> Result<(), wal::Error>;
 }
@@ -80,6 +88,10 @@ impl WalReader for Arc<wal::Wal> {
             .collect()
     }
 
+    fn repair_closed_segment(&self, id: SegmentId) -> Result<wal::RepairReport, wal::Error> {
+        wal::Wal::repair_segment(self, id)
+    }
+
     async fn delete(&self, id: SegmentId) -> Result<(), wal::Error> {
         wal::Wal::delete(self, id).await
     }
@@ -106,12 +118,18 @@ impl SegmentedWalOpBatchReader for wal::ClosedSegmentFileReader {
 
 /// Replay all the entries in `wal` to `sink`, returning the maximum observed
 /// [`SequenceNumber`].
+///
+/// If `salvage_corrupt_segments` is true, a segment with a corrupt entry is
+/// replaced with one containing only its readable entries (quarantining the
+/// original file) and the readable entries replayed, rather than failing the
+/// replay. The sequence numbers lost are logged.
 pub async fn replay<W, T, P>(
     wal: &W,
     sink: &T,
     persist: P,
     ingest_state: Arc<IngestState>,
     metrics: &metric::Registry,
+    salvage_corrupt_segments: bool,
 ) -> Result<Option<SequenceNumber>, WalReplayError>
 where
     W: WalReader,
@@ -149,6 +167,8 @@ where
     let file_count_success_metric = replayed_file_count_metric.recorder(&[("result", "success")]);
     let file_count_error_truncated_metric =
         replayed_file_count_metric.recorder(&[("result", "error"), ("reason", "truncated")]);
+    let file_count_salvaged_metric =
+        replayed_file_count_metric.recorder(&[("result", "error"), ("reason", "salvaged")]);
 
     let op_count_metric = metrics.register_metric::<U64Counter>(
         "ingester_wal_replay_ops",
@@ -194,6 +214,7 @@ where
             &ok_op_count_metric,
             &empty_op_count_metric,
             &ingest_state,
+            None,
         )
         .await;
         if replay_result.is_ok() {
@@ -245,6 +266,41 @@ where
                 file_count_error_truncated_metric.inc(1);
                 warn!(%e, %file_id, "detected truncated WAL write, ending replay for file early");
             }
+            // Otherwise the segment is corrupt. When salvaging, replace it
+            // with its readable entries and replay those after the ones
+            // already applied.
+            Err(WalReplayError::ReadEntry(e, seq)) if salvage_corrupt_segments => {
+                warn!(%e, %file_id, "corrupt wal segment, salvaging readable entries");
+
+                let report = wal
+                    .repair_closed_segment(file_id)
+                    .map_err(WalReplayError::RepairSegment)?;
+                for region in &report.corrupt_regions {
+                    error!(
+                        %file_id,
+                        offset = region.offset,
+                        len = region.len,
+                        lost_sequence_numbers = %region.lost_sequence_numbers,
+                        quarantined_path = ?report.quarantined_path,
+                        "wal ops lost to corrupt segment region"
+                    );
+                }
+
+                let reader = wal
+                    .reader_for_closed_segment(file_id)
+                    .map_err(WalReplayError::OpenSegment)?;
+                let salvaged_seq = replay_file(
+                    reader,
+                    sink,
+                    &ok_op_count_metric,
+                    &empty_op_count_metric,
+                    &ingest_state,
+                    seq,
+                )
+                .await?;
+                max_sequence = max_sequence.max(seq).max(salvaged_seq);
+                file_count_salvaged_metric.inc(1);
+            }
             Err(e) => return Err(e),
         };
 
@@ -285,6 +341,9 @@ where
 /// highest sequence number observed across the batches read from the file, or
 /// [`None`] if there were no entries read.
 ///
+/// Ops with sequence numbers no greater than `applied` are skipped, as they
+/// were applied by a previous replay of the file.
+///
 /// # Warnings
 ///
 /// This function relies on the [`wal::blocking::ReaderError::UnableToReadData`]
@@ -297,6 +356,7 @@ async fn replay_file<T, F>(
     ok_op_count_metric: &U64Counter,
     empty_op_count_metric: &U64Counter,
     ingest_state: &Arc<IngestState>,
+    applied: Option<SequenceNumber>,
 ) -> Result<Option<SequenceNumber>, WalReplayError>
 where
     T: DmlSink,
@@ -315,6 +375,14 @@ where
                 op,
             } = op;
 
+            if applied.is_some()
+                && table_write_sequence_numbers
+                    .values()
+                    .all(|seq| Some(SequenceNumber::new(*seq)) <= applied)
+            {
+                continue;
+            }
+
             let op = match op {
                 Op::Write(w) => w,
                 Op::Delete(_) => unreachable!(),
@@ -609,6 +677,7 @@ mod tests {
             Arc::clone(&persist),
             Arc::clone(&ingest_state),
             &metrics,
+            false,
         )
         .with_timeout_panic(Duration::from_secs(2))
         .await
@@ -729,6 +798,16 @@ mod tests {
                 .collect()
         }
 
+        fn repair_closed_segment(&self, id: SegmentId) -> Result<wal::RepairReport, wal::Error> {
+            assert!(self.closed_segment_ids.lock().contains(&id));
+            Ok(wal::RepairReport {
+                segment_id: id,
+                salvaged_entries: 0,
+                corrupt_regions: vec![],
+                quarantined_path: None,
+            })
+        }
+
         async fn delete(&self, id: SegmentId) -> Result<(), wal::Error> {
             assert!(self.closed_segment_ids.lock().remove(&id));
             Ok(())
@@ -837,6 +916,7 @@ mod tests {
             Arc::clone(&persist),
             Arc::new(IngestState::default()),
             &metrics,
+            false,
         )
         .await
         .expect("failed to replay WAL")
@@ -903,6 +983,7 @@ mod tests {
             Arc::clone(&persist),
             Arc::new(IngestState::default()),
             &metrics,
+            false,
         )
         .await;
         assert_matches!(
@@ -920,6 +1001,69 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_replay_salvages_corrupt_segment() {
+        let wal = MockWalReader::new(
+            [
+                MockSegmentedWalOpBatchReader::new(SegmentId::new(1)).with_entry_results([Ok(
+                    vec![arbitrary_sequenced_wal_op(SequenceNumber::new(1))],
+                )]),
+                MockSegmentedWalOpBatchReader::new(SegmentId::new(2)).with_entry_results([
+                    Ok(vec![arbitrary_sequenced_wal_op(SequenceNumber::new(2))]),
+                    Err(wal::Error::UnableToReadNextOps {
+                        source: wal::blocking::ReaderError::ChecksumMismatch {
+                            expected: 1,
+                            actual: 2,
+                        },
+                    }),
+                ]),
+                // The repaired segment, with the entry for 3 dropped
+                MockSegmentedWalOpBatchReader::new(SegmentId::new(2)).with_entry_results([
+                    Ok(vec![arbitrary_sequenced_wal_op(SequenceNumber::new(2))]),
+                    Ok(vec![arbitrary_sequenced_wal_op(SequenceNumber::new(4))]),
+                ]),
+                MockSegmentedWalOpBatchReader::new(SegmentId::new(3)).with_entry_results([Ok(
+                    vec![arbitrary_sequenced_wal_op(SequenceNumber::new(5))],
+                )]),
+            ],
+            [1, 2, 3],
+        );
+
+        let persist = Arc::new(MockPersistQueue::default());
+        let mock_sink =
+            MockDmlSink::default().with_apply_return(vec![Ok(()), Ok(()), Ok(()), Ok(())]);
+        let mock_iter = MockIter {
+            sink: mock_sink,
+            partitions: vec![],
+        };
+        let metrics = metric::Registry::default();
+
+        let max_sequence_number = replay(
+            &wal,
+            &mock_iter,
+            Arc::clone(&persist),
+            Arc::new(IngestState::default()),
+            &metrics,
+            true,
+        )
+        .await
+        .expect("failed to replay WAL")
+        .expect("should receive max sequence number");
+        assert_eq!(max_sequence_number, SequenceNumber::new(5));
+        assert!(wal.closed_segment_ids.lock().is_empty());
+
+        // The op applied before the corrupt entry is not applied again
+        assert_eq!(mock_iter.sink.get_calls().len(), 4);
+
+        assert_counter!(
+            metrics,
+            U64Counter,
+            "ingester_wal_replay_files_finished",
+            labels = Attributes::from(&[("result", "error"), ("reason", "salvaged")]),
+            value = 1,
+        );
+    }
+
     #[tokio::test]
     async fn test_replay_respects_ingest_state() {
         let metrics = metric::Registry::default();
@@ -946,6 +1090,7 @@ mod tests {
                     &metric.recorder(&[]),
                     &metric.recorder(&[]),
                     &ingest_state,
+                    None,
                 )
                 .await
             })
@@ -993,6 +1138,7 @@ mod tests {
                 &metric.recorder(&[]),
                 &metric.recorder(&[]),
                 &Arc::clone(&ingest_state),
+                None,
             )
             .with_timeout_panic(Duration::from_secs(2))
             .await,
@@ -10,6 +10,7 @@ pub(crate) mod file_metrics;
 pub(crate) mod handle;
 pub(crate) mod hot_partitions;
 pub mod queue;
+mod tag_values;
 mod worker;
 
 #[cfg(test)]
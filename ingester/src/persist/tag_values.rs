@@ -0,0 +1,137 @@
+//! Distinct tag value collection for the partition statistics recorded in the
+//! catalog when a file is persisted.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use arrow::{
+    array::{as_dictionary_array, StringArray},
+    datatypes::{DataType, Int32Type},
+    record_batch::RecordBatch,
+};
+use data_types::{ColumnId, PartitionColumnStatistics, MAX_PARTITION_TAG_VALUES};
+use schema::Schema;
+
+/// Compute the distinct, non-NULL values of each tag column in `batches`.
+///
+/// Tags with more than [`MAX_PARTITION_TAG_VALUES`] distinct values map to
+/// [`None`].
+pub(super) fn distinct_tag_values(
+    schema: &Schema,
+    batches: &[RecordBatch],
+) -> BTreeMap<String, Option<BTreeSet<String>>> {
+    schema
+        .tags_iter()
+        .map(|field| {
+            let name = field.name();
+            let mut values = Some(BTreeSet::new());
+
+            for batch in batches {
+                let (Some(set), Some(col)) = (values.as_mut(), batch.column_by_name(name)) else {
+                    continue;
+                };
+
+                // Tags are always dictionary encoded by the buffer.
+                if !matches!(col.data_type(), DataType::Dictionary(k, v)
+                    if **k == DataType::Int32 && **v == DataType::Utf8)
+                {
+                    values = None;
+                    continue;
+                }
+
+                let dict = as_dictionary_array::<Int32Type>(col)
+                    .downcast_dict::<StringArray>()
+                    .expect("dictionary of strings");
+                for v in dict.into_iter().flatten() {
+                    if !set.contains(v) {
+                        set.insert(v.to_string());
+                    }
+                }
+                if set.len() > MAX_PARTITION_TAG_VALUES {
+                    values = None;
+                }
+            }
+
+            (name.clone(), values)
+        })
+        .collect()
+}
+
+/// Set [`PartitionColumnStatistics::values`] for each tag in `tag_values`,
+/// resolving column names to IDs with `column_id`.
+pub(super) fn apply_tag_values<F>(
+    column_statistics: &mut [PartitionColumnStatistics],
+    tag_values: BTreeMap<String, Option<BTreeSet<String>>>,
+    column_id: F,
+) where
+    F: Fn(&str) -> ColumnId,
+{
+    for (name, values) in tag_values {
+        let id = column_id(&name);
+        if let Some(stats) = column_statistics.iter_mut().find(|c| c.column_id == id) {
+            stats.values = values.map(|v| v.into_iter().collect());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+
+    use super::*;
+
+    #[test]
+    fn test_distinct_tag_values() {
+        let (_, mb) = lp_to_mutable_batch(
+            r#"
+                m,region=west,host=a v=1 1
+                m,region=east,host=a v=2 2
+                m,region=west v=3 3
+            "#,
+        );
+        let batch = mb.to_arrow(schema::Projection::All).unwrap();
+        let schema = Schema::try_from(batch.schema()).unwrap();
+
+        let got = distinct_tag_values(&schema, &[batch.clone(), batch]);
+        assert_eq!(
+            got,
+            BTreeMap::from([
+                ("host".to_string(), Some(BTreeSet::from(["a".to_string()]))),
+                (
+                    "region".to_string(),
+                    Some(BTreeSet::from(["east".to_string(), "west".to_string()]))
+                ),
+            ])
+        );
+
+        let mut stats = vec![PartitionColumnStatistics {
+            column_id: ColumnId::new(2),
+            null_count: 0,
+            min: Some("east".to_string()),
+            max: Some("west".to_string()),
+            values: None,
+        }];
+        apply_tag_values(&mut stats, got, |name| match name {
+            "host" => ColumnId::new(1),
+            "region" => ColumnId::new(2),
+            _ => unreachable!(),
+        });
+        assert_eq!(
+            stats[0].values,
+            Some(vec!["east".to_string(), "west".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_distinct_tag_values_too_many() {
+        let lp = (0..=MAX_PARTITION_TAG_VALUES)
+            .map(|i| format!("m,t=v{i} v=1 {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (_, mb) = lp_to_mutable_batch(&lp);
+        let batch = mb.to_arrow(schema::Projection::All).unwrap();
+        let schema = Schema::try_from(batch.schema()).unwrap();
+
+        let got = distinct_tag_values(&schema, &[batch]);
+        assert_eq!(got, BTreeMap::from([("t".to_string(), None)]));
+    }
+}
@@ -2,9 +2,12 @@ use std::{ops::ControlFlow, sync::Arc};
 
 use async_channel::RecvError;
 use backoff::Backoff;
-use data_types::{ColumnsByName, CompactionLevel, ParquetFile, ParquetFileParams, SortedColumnSet};
+use data_types::{
+    ColumnsByName, CompactionLevel, ParquetFile, ParquetFileParams, PartitionColumnStatistics,
+    SortedColumnSet,
+};
 use iox_catalog::interface::{CasFailure, Catalog};
-use iox_query::exec::Executor;
+use iox_query::{exec::Executor, QueryChunk};
 use iox_time::{SystemProvider, TimeProvider};
 use metric::DurationHistogram;
 use observability_deps::tracing::{debug, info, warn};
@@ -20,6 +23,7 @@ use super::{
     compact::CompactedStream,
     completion_observer::PersistCompletionObserver,
     context::{Context, PersistError, PersistRequest},
+    tag_values::{apply_tag_values, distinct_tag_values},
 };
 
 /// State shared across workers.
@@ -130,7 +134,7 @@ pub(super) async fn run_task<O, C>(
         // operation; if this update fails due to a concurrent sort key update,
         // the compaction must be redone with the new sort key and uploaded
         // before continuing.
-        let parquet_table_data = loop {
+        let (parquet_table_data, column_statistics) = loop {
             match compact_and_upload(&mut ctx, &worker_state).await {
                 Ok(v) => break v,
                 Err(PersistError::ConcurrentSortKeyUpdate(_sort_key, _sort_key_ids)) => continue,
@@ -138,7 +142,13 @@ pub(super) async fn run_task<O, C>(
         };
 
         // Make the newly uploaded parquet file visible to other nodes.
-        let parquet_file = update_catalog_parquet(&ctx, &worker_state, &parquet_table_data).await;
+        let parquet_file = update_catalog_parquet(
+            &ctx,
+            &worker_state,
+            &parquet_table_data,
+            column_statistics.as_deref(),
+        )
+        .await;
 
         // And finally mark the persist job as complete and notify any
         // observers.
@@ -152,7 +162,8 @@ pub(super) async fn run_task<O, C>(
 }
 
 /// Run a compaction on the [`PersistingData`], generate a parquet file and
-/// upload it to object storage.
+/// upload it to object storage, returning the catalog metadata and (if
+/// available) the per-column statistics of the file.
 ///
 /// This function composes functionality from the smaller [`compact()`],
 /// [`upload()`], and [`update_catalog_sort_key()`] functions.
@@ -168,7 +179,7 @@ pub(super) async fn run_task<O, C>(
 async fn compact_and_upload<O, C>(
     ctx: &mut Context,
     worker_state: &SharedWorkerState<O, C>,
-) -> Result<ParquetFileParams, PersistError>
+) -> Result<(ParquetFileParams, Option<Vec<PartitionColumnStatistics>>), PersistError>
 where
     O: Send + Sync,
     C: ColumnMapResolver,
@@ -191,7 +202,7 @@ where
         .await;
 
     let compacted = compact(ctx, worker_state, sort_key.as_ref()).await;
-    let (sort_key_update, parquet_table_data) =
+    let (sort_key_update, parquet_table_data, column_statistics) =
         upload(ctx, worker_state, compacted, &column_map).await;
 
     if let Some(sort_key_update) = sort_key_update {
@@ -207,7 +218,7 @@ where
         .await?
     }
 
-    Ok(parquet_table_data)
+    Ok((parquet_table_data, column_statistics))
 }
 
 /// Compact the data in `ctx` using sorted by the sort key returned from
@@ -247,14 +258,19 @@ where
     .await
 }
 
-/// Upload the compacted data in `compacted`, returning the new sort key value
-/// and parquet metadata to be upserted into the catalog.
+/// Upload the compacted data in `compacted`, returning the new sort key value,
+/// parquet metadata to be upserted into the catalog, and the per-column
+/// statistics of the file (if they could be read from the metadata).
 async fn upload<O, C>(
     ctx: &Context,
     worker_state: &SharedWorkerState<O, C>,
     compacted: CompactedStream,
     columns: &ColumnsByName,
-) -> (Option<SortKey>, ParquetFileParams)
+) -> (
+    Option<SortKey>,
+    ParquetFileParams,
+    Option<Vec<PartitionColumnStatistics>>,
+)
 where
     O: Send + Sync,
     C: Send + Sync,
@@ -320,20 +336,50 @@ where
 
     // Build the data that must be inserted into the parquet_files catalog
     // table in order to make the file visible to queriers.
+    let column_id = |name: &str| {
+        columns
+            .get(name)
+            .unwrap_or_else(|| {
+                panic!(
+                    "unknown column {name} in table ID {table_id}",
+                    table_id = ctx.table_id().get()
+                )
+            })
+            .id
+    };
     let parquet_table_data =
-        iox_metadata.to_parquet_file(ctx.partition_id().clone(), file_size, &md, |name| {
-            columns
-                .get(name)
-                .unwrap_or_else(|| {
-                    panic!(
-                        "unknown column {name} in table ID {table_id}",
-                        table_id = ctx.table_id().get()
-                    )
-                })
-                .id
+        iox_metadata.to_parquet_file(ctx.partition_id().clone(), file_size, &md, column_id);
+
+    // Extract the per-column statistics of the file, allowing the catalog to
+    // maintain the statistics of the partition as a whole.
+    let mut column_statistics = md
+        .decode()
+        .and_then(|decoded| {
+            let schema = decoded.read_schema()?;
+            decoded.read_partition_column_statistics(&schema, column_id)
+        })
+        .unwrap_or_else(|e| {
+            warn!(
+                error = %e,
+                partition_id = %ctx.partition_id(),
+                %object_store_id,
+                "failed to read parquet column statistics"
+            );
+            None
         });
 
-    (catalog_sort_key_update, parquet_table_data)
+    // Record the distinct values of each tag, allowing metadata queries to be
+    // answered from the catalog without reading the file.
+    if let Some(column_statistics) = column_statistics.as_mut() {
+        let tag_values = distinct_tag_values(ctx.data().schema(), ctx.data().record_batches());
+        apply_tag_values(column_statistics, tag_values, column_id);
+    }
+
+    (
+        catalog_sort_key_update,
+        parquet_table_data,
+        column_statistics,
+    )
 }
 
 /// Update the sort key value stored in the catalog for this [`Context`].
@@ -525,6 +571,7 @@ async fn update_catalog_parquet<O, C>(
     ctx: &Context,
     worker_state: &SharedWorkerState<O, C>,
     parquet_table_data: &ParquetFileParams,
+    column_statistics: Option<&[PartitionColumnStatistics]>,
 ) -> ParquetFile
 where
     O: Send + Sync,
@@ -554,10 +601,20 @@ where
     let file = Backoff::new(&Default::default())
         .retry_all_errors("add parquet file to catalog", || async {
             let mut repos = worker_state.catalog.repositories().await;
-            let parquet_file = repos
-                .parquet_files()
-                .create(parquet_table_data.clone())
-                .await?;
+            let parquet_file = match column_statistics {
+                Some(stats) => {
+                    repos
+                        .parquet_files()
+                        .create_with_statistics(parquet_table_data.clone(), stats)
+                        .await?
+                }
+                None => {
+                    repos
+                        .parquet_files()
+                        .create(parquet_table_data.clone())
+                        .await?
+                }
+            };
 
             debug!(
                 namespace_id = %ctx.namespace_id(),
@@ -0,0 +1,141 @@
+//! DNS-based ingester discovery.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use trust_dns_resolver::TokioAsyncResolver;
+
+use crate::{DiscoveryError, IngesterDiscovery};
+
+/// The URI scheme used for discovered ingester addresses.
+const SCHEME: &str = "http";
+
+/// Discover ingesters by resolving the DNS SRV records of a service name,
+/// such as `_grpc._tcp.ingester.iox.svc.cluster.local`.
+///
+/// Each SRV record yields an address of `http://<target>:<port>`.
+pub struct DnsSrvDiscovery {
+    name: String,
+    resolver: TokioAsyncResolver,
+}
+
+impl std::fmt::Debug for DnsSrvDiscovery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DnsSrvDiscovery")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl DnsSrvDiscovery {
+    /// Resolve the SRV records of `name`, using the system DNS configuration.
+    pub fn new(name: impl Into<String>) -> Result<Self, DiscoveryError> {
+        let name = name.into();
+        let resolver =
+            TokioAsyncResolver::tokio_from_system_conf().map_err(|e| DiscoveryError::Dns {
+                name: name.clone(),
+                source: Box::new(e),
+            })?;
+
+        Ok(Self { name, resolver })
+    }
+}
+
+#[async_trait]
+impl IngesterDiscovery for DnsSrvDiscovery {
+    fn name(&self) -> &'static str {
+        "dns_srv"
+    }
+
+    async fn discover(&self) -> Result<Vec<Arc<str>>, DiscoveryError> {
+        let records = self
+            .resolver
+            .srv_lookup(self.name.as_str())
+            .await
+            .map_err(|e| DiscoveryError::Dns {
+                name: self.name.clone(),
+                source: Box::new(e),
+            })?;
+
+        Ok(records
+            .iter()
+            .map(|srv| {
+                let target = srv.target().to_utf8();
+                srv_address(&target, srv.port())
+            })
+            .collect())
+    }
+}
+
+/// Build the address of an SRV record `target` (which may be fully
+/// qualified, ending with a dot) and `port`.
+fn srv_address(target: &str, port: u16) -> Arc<str> {
+    let target = target.trim_end_matches('.');
+    format!("{SCHEME}://{target}:{port}").into()
+}
+
+/// Discover ingesters by resolving the A / AAAA records of a `host:port`
+/// pair, such as `ingester.iox.svc.cluster.local:8082`.
+///
+/// Each resolved IP address yields an address of `http://<ip>:<port>`.
+#[derive(Debug)]
+pub struct DnsDiscovery {
+    host_port: String,
+}
+
+impl DnsDiscovery {
+    /// Resolve the addresses of `host_port`, using the system resolver.
+    pub fn new(host_port: impl Into<String>) -> Self {
+        Self {
+            host_port: host_port.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl IngesterDiscovery for DnsDiscovery {
+    fn name(&self) -> &'static str {
+        "dns"
+    }
+
+    async fn discover(&self) -> Result<Vec<Arc<str>>, DiscoveryError> {
+        let addrs = tokio::net::lookup_host(self.host_port.as_str())
+            .await
+            .map_err(|e| DiscoveryError::Dns {
+                name: self.host_port.clone(),
+                source: Box::new(e),
+            })?;
+
+        Ok(addrs
+            .map(|addr| format!("{SCHEME}://{addr}").into())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_srv_address() {
+        assert_eq!(
+            &*srv_address("ingester-0.ingester.iox.svc.cluster.local.", 8082),
+            "http://ingester-0.ingester.iox.svc.cluster.local:8082"
+        );
+        assert_eq!(&*srv_address("ingester-1", 4242), "http://ingester-1:4242");
+    }
+
+    #[tokio::test]
+    async fn test_dns_discovery() {
+        let got = DnsDiscovery::new("127.0.0.1:8082")
+            .discover()
+            .await
+            .unwrap();
+        assert_eq!(got, [Arc::from("http://127.0.0.1:8082")]);
+
+        let got = DnsDiscovery::new("[::1]:8082").discover().await.unwrap();
+        assert_eq!(got, [Arc::from("http://[::1]:8082")]);
+
+        DnsDiscovery::new("bananas").discover().await.unwrap_err();
+    }
+}
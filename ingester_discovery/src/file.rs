@@ -0,0 +1,91 @@
+//! File-based ingester discovery.
+
+use std::{path::PathBuf, sync::Arc};
+
+use async_trait::async_trait;
+
+use crate::{DiscoveryError, IngesterDiscovery};
+
+/// Discover ingesters by reading a newline-delimited list of addresses from a
+/// file.
+///
+/// Blank lines, and lines starting with `#` are ignored. The file is re-read
+/// on every discovery attempt, so changes made to it (for example by a
+/// mounted Kubernetes ConfigMap) are picked up without a restart.
+#[derive(Debug)]
+pub struct FileDiscovery {
+    path: PathBuf,
+}
+
+impl FileDiscovery {
+    /// Read the ingester addresses from the file at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl IngesterDiscovery for FileDiscovery {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    async fn discover(&self) -> Result<Vec<Arc<str>>, DiscoveryError> {
+        let content = tokio::fs::read_to_string(&self.path)
+            .await
+            .map_err(|source| DiscoveryError::ReadFile {
+                path: self.path.clone(),
+                source,
+            })?;
+
+        Ok(parse(&content))
+    }
+}
+
+fn parse(content: &str) -> Vec<Arc<str>> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(Arc::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let got = parse("# The ingesters\nhttp://ingester-0:8082\n\n  http://ingester-1:8082  \n");
+        assert_eq!(
+            got,
+            [
+                Arc::from("http://ingester-0:8082"),
+                Arc::from("http://ingester-1:8082")
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_discovery() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        let discovery = FileDiscovery::new(file.path());
+
+        assert!(discovery.discover().await.unwrap().is_empty());
+
+        writeln!(file, "http://ingester-0:8082").unwrap();
+        file.flush().unwrap();
+        assert_eq!(
+            discovery.discover().await.unwrap(),
+            [Arc::from("http://ingester-0:8082")]
+        );
+
+        let path = file.path().to_owned();
+        drop(file);
+        let err = FileDiscovery::new(path).discover().await.unwrap_err();
+        assert!(matches!(err, DiscoveryError::ReadFile { .. }));
+    }
+}
@@ -0,0 +1,307 @@
+//! Runtime discovery of the set of ingesters routers write to and queriers
+//! read from.
+//!
+//! Routers and queriers are configured with a static list of ingester
+//! addresses at startup. An [`IngesterDiscovery`] implementation provides an
+//! alternative source of ingester addresses that may change while the process
+//! runs, allowing the ingester fleet to be scaled without restarting every
+//! router and querier.
+//!
+//! The following implementations are provided:
+//!
+//! * [`DnsSrvDiscovery`]: resolves the DNS SRV records of a service name.
+//! * [`DnsDiscovery`]: resolves the A / AAAA records of a `host:port` pair.
+//! * [`FileDiscovery`]: reads a newline-delimited list of addresses from a
+//!   file, picking up any changes made to it.
+//! * [`MembershipDiscovery`]: uses the alive ingesters announced over the
+//!   gossip cluster membership topic.
+//!
+//! A [`DiscoveryTask`] periodically polls an [`IngesterDiscovery`]
+//! implementation and passes any change in the discovered set to the
+//! consumer, which is responsible for adding connections for new ingesters
+//! and draining connections to removed ingesters.
+//!
+//! # Failures
+//!
+//! A failed discovery attempt, or one that discovers no ingesters at all, is
+//! logged and otherwise ignored - the consumer continues to use the last
+//! successfully discovered set. This prevents a transient DNS or file system
+//! issue from removing every ingester at once.
+
+#![deny(rustdoc::broken_intra_doc_links, rust_2018_idioms)]
+#![warn(
+    clippy::clone_on_ref_ptr,
+    clippy::dbg_macro,
+    clippy::explicit_iter_loop,
+    // See https://github.com/influxdata/influxdb_iox/pull/1671
+    clippy::future_not_send,
+    clippy::todo,
+    clippy::use_self,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    unused_crate_dependencies,
+    missing_docs
+)]
+#![allow(clippy::default_constructed_unit_structs)]
+
+// Workaround for "unused crate" lint false positives.
+use workspace_hack as _;
+
+use std::{fmt::Debug, path::PathBuf, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use metric::{U64Counter, U64Gauge};
+use observability_deps::tracing::{info, warn};
+use thiserror::Error;
+use tokio::task::JoinHandle;
+
+mod dns;
+mod file;
+mod membership;
+
+pub use dns::*;
+pub use file::*;
+pub use membership::*;
+
+/// The default interval between discovery attempts.
+pub const DEFAULT_DISCOVERY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Errors returned by an [`IngesterDiscovery`] implementation.
+#[derive(Debug, Error)]
+pub enum DiscoveryError {
+    /// A DNS lookup failed.
+    #[error("dns lookup of {name} failed: {source}")]
+    Dns {
+        /// The name being resolved.
+        name: String,
+        /// The underlying error.
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    /// The address file could not be read.
+    #[error("failed to read ingester address file {}: {source}", path.display())]
+    ReadFile {
+        /// The path of the file.
+        path: PathBuf,
+        /// The underlying error.
+        source: std::io::Error,
+    },
+}
+
+/// A source of ingester gRPC addresses, such as `http://ingester-0:8082`.
+#[async_trait]
+pub trait IngesterDiscovery: Debug + Send + Sync {
+    /// A short, human readable name for this discovery mechanism, used in
+    /// logs and metrics.
+    fn name(&self) -> &'static str;
+
+    /// Return the current set of ingester addresses.
+    ///
+    /// The returned addresses need not be ordered or de-duplicated.
+    async fn discover(&self) -> Result<Vec<Arc<str>>, DiscoveryError>;
+}
+
+#[async_trait]
+impl<T> IngesterDiscovery for Arc<T>
+where
+    T: IngesterDiscovery + ?Sized,
+{
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    async fn discover(&self) -> Result<Vec<Arc<str>>, DiscoveryError> {
+        (**self).discover().await
+    }
+}
+
+/// A background task periodically polling an [`IngesterDiscovery`]
+/// implementation, and notifying the consumer of any change to the discovered
+/// set of ingesters.
+///
+/// The task is stopped when the [`DiscoveryTask`] is dropped.
+#[derive(Debug)]
+pub struct DiscoveryTask {
+    task: JoinHandle<()>,
+}
+
+impl Drop for DiscoveryTask {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl DiscoveryTask {
+    /// Poll `discovery` every `interval`, calling `on_change` with the sorted
+    /// and de-duplicated set of ingester addresses each time it changes.
+    ///
+    /// The first non-empty discovered set is always passed to `on_change`.
+    pub fn spawn<D, F>(
+        discovery: D,
+        interval: Duration,
+        metrics: &metric::Registry,
+        on_change: F,
+    ) -> Self
+    where
+        D: IngesterDiscovery + 'static,
+        F: FnMut(&[Arc<str>]) + Send + 'static,
+    {
+        let attributes = [("discovery", discovery.name())];
+        let endpoints = metrics
+            .register_metric::<U64Gauge>(
+                "ingester_discovery_endpoints",
+                "number of ingesters in the most recently applied discovered set",
+            )
+            .recorder(attributes);
+        let errors = metrics
+            .register_metric::<U64Counter>(
+                "ingester_discovery_errors",
+                "number of failed ingester discovery attempts",
+            )
+            .recorder(attributes);
+
+        let task = tokio::spawn(discovery_loop(
+            discovery, interval, endpoints, errors, on_change,
+        ));
+
+        Self { task }
+    }
+}
+
+async fn discovery_loop<D, F>(
+    discovery: D,
+    interval: Duration,
+    endpoints: U64Gauge,
+    errors: U64Counter,
+    mut on_change: F,
+) where
+    D: IngesterDiscovery,
+    F: FnMut(&[Arc<str>]) + Send,
+{
+    let mut interval = tokio::time::interval(interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    let mut last: Vec<Arc<str>> = vec![];
+    loop {
+        interval.tick().await;
+
+        let mut addrs = match discovery.discover().await {
+            Ok(v) => v,
+            Err(e) => {
+                warn!(discovery = discovery.name(), error=%e, "ingester discovery failed");
+                errors.inc(1);
+                continue;
+            }
+        };
+
+        addrs.sort_unstable();
+        addrs.dedup();
+
+        if addrs.is_empty() {
+            warn!(
+                discovery = discovery.name(),
+                "no ingesters discovered, retaining previous set"
+            );
+            continue;
+        }
+
+        if addrs == last {
+            continue;
+        }
+
+        info!(
+            discovery = discovery.name(),
+            ingesters = ?addrs,
+            "discovered ingester set changed"
+        );
+        on_change(&addrs);
+        endpoints.set(addrs.len() as u64);
+        last = addrs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use metric::{Attributes, Metric};
+    use parking_lot::Mutex;
+    use test_helpers::timeout::FutureTimeout;
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    /// A discovery mock returning a scripted sequence of results, repeating
+    /// the last one once exhausted.
+    #[derive(Debug)]
+    struct MockDiscovery {
+        results: Mutex<VecDeque<Option<Vec<&'static str>>>>,
+    }
+
+    #[async_trait]
+    impl IngesterDiscovery for MockDiscovery {
+        fn name(&self) -> &'static str {
+            "mock"
+        }
+
+        async fn discover(&self) -> Result<Vec<Arc<str>>, DiscoveryError> {
+            let mut results = self.results.lock();
+            let v = if results.len() > 1 {
+                results.pop_front().unwrap()
+            } else {
+                results.front().cloned().unwrap()
+            };
+
+            v.map(|v| v.into_iter().map(Arc::from).collect())
+                .ok_or_else(|| DiscoveryError::Dns {
+                    name: "bananas".to_string(),
+                    source: "platanos".into(),
+                })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_discovery_task() {
+        let metrics = metric::Registry::default();
+        let discovery = MockDiscovery {
+            results: Mutex::new(VecDeque::from([
+                Some(vec!["http://b:1", "http://a:1", "http://a:1"]),
+                // No change
+                Some(vec!["http://a:1", "http://b:1"]),
+                // Errors and empty sets are ignored
+                None,
+                Some(vec![]),
+                Some(vec!["http://c:1"]),
+            ])),
+        };
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let _task = DiscoveryTask::spawn(
+            discovery,
+            Duration::from_millis(1),
+            &metrics,
+            move |addrs| tx.send(addrs.to_vec()).unwrap(),
+        );
+
+        let got = rx.recv().with_timeout_panic(Duration::from_secs(5)).await;
+        assert_eq!(
+            got.unwrap(),
+            [Arc::from("http://a:1"), Arc::from("http://b:1")]
+        );
+
+        let got = rx.recv().with_timeout_panic(Duration::from_secs(5)).await;
+        assert_eq!(got.unwrap(), [Arc::from("http://c:1")]);
+
+        let errors = metrics
+            .get_instrument::<Metric<U64Counter>>("ingester_discovery_errors")
+            .unwrap()
+            .get_observer(&Attributes::from(&[("discovery", "mock")]))
+            .unwrap()
+            .fetch();
+        assert_eq!(errors, 1);
+
+        // The final set is repeated, and not passed to the consumer again.
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(rx.try_recv().is_err());
+    }
+}
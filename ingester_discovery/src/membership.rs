@@ -0,0 +1,77 @@
+//! Gossip cluster membership based ingester discovery.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use gossip_membership::{ClusterMembers, Role};
+
+use crate::{DiscoveryError, IngesterDiscovery};
+
+/// Discover ingesters from the alive [`Role::Ingester`] members of a gossip
+/// [`ClusterMembers`] view, using the RPC address each ingester advertises.
+#[derive(Debug)]
+pub struct MembershipDiscovery {
+    members: Arc<ClusterMembers>,
+}
+
+impl MembershipDiscovery {
+    /// Discover the ingesters in `members`.
+    pub fn new(members: Arc<ClusterMembers>) -> Self {
+        Self { members }
+    }
+}
+
+#[async_trait]
+impl IngesterDiscovery for MembershipDiscovery {
+    fn name(&self) -> &'static str {
+        "gossip"
+    }
+
+    async fn discover(&self) -> Result<Vec<Arc<str>>, DiscoveryError> {
+        Ok(self
+            .members
+            .alive_addresses(Role::Ingester)
+            .into_iter()
+            .map(Arc::from)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gossip::Identity;
+    use gossip_membership::MemberInfo;
+    use iox_time::{MockProvider, Time};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_membership_discovery() {
+        let members = Arc::new(ClusterMembers::new(Arc::new(MockProvider::new(
+            Time::from_timestamp_nanos(0),
+        ))));
+        let discovery = MembershipDiscovery::new(Arc::clone(&members));
+
+        assert!(discovery.discover().await.unwrap().is_empty());
+
+        for (id, role, addr) in [
+            (1, Role::Ingester, "http://ingester-0:8082"),
+            (2, Role::Querier, "http://querier-0:8082"),
+        ] {
+            members.observe(
+                Identity::try_from(vec![id; 16]).unwrap(),
+                MemberInfo {
+                    role,
+                    rpc_address: addr.to_string(),
+                    version: "v42".to_string(),
+                    started_at: Time::from_timestamp_nanos(0),
+                },
+            );
+        }
+
+        assert_eq!(
+            discovery.discover().await.unwrap(),
+            [Arc::from("http://ingester-0:8082")]
+        );
+    }
+}
@@ -163,6 +163,7 @@ impl TestContextBuilder {
             persist_background_fetch_time,
             dir.path().to_owned(),
             wal_rotation_period,
+            false,
             persist_executor,
             persist_workers,
             max_persist_queue_depth,
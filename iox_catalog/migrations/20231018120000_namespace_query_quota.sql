@@ -0,0 +1,11 @@
+-- Per-namespace query concurrency and memory limits, enforced by the querier.
+-- NULL represents no namespace-specific limit.
+ALTER TABLE
+  IF EXISTS namespace
+ADD
+  COLUMN max_concurrent_queries INT;
+
+ALTER TABLE
+  IF EXISTS namespace
+ADD
+  COLUMN max_query_memory_bytes BIGINT;
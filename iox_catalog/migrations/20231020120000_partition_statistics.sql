@@ -0,0 +1,16 @@
+-- Summary statistics of the persisted data in each partition, maintained when
+-- parquet files are persisted and compacted, and used by the querier to prune
+-- whole partitions.
+--
+-- column_statistics holds a JSON array of per-column null counts and tag
+-- min/max values, only valid if complete is true.
+CREATE TABLE IF NOT EXISTS partition_statistics (
+    partition_id BIGINT REFERENCES partition (id) ON DELETE CASCADE,
+    row_count BIGINT NOT NULL,
+    min_time BIGINT,
+    max_time BIGINT,
+    max_parquet_file_id BIGINT,
+    complete BOOLEAN NOT NULL,
+    column_statistics JSONB NOT NULL,
+    PRIMARY KEY (partition_id)
+);
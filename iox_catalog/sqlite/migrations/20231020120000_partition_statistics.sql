@@ -0,0 +1,20 @@
+-- Summary statistics of the persisted data in each partition, maintained when
+-- parquet files are persisted and compacted, and used by the querier to prune
+-- whole partitions.
+--
+-- column_statistics holds a JSON array of per-column null counts and tag
+-- min/max values, only valid if complete is true.
+CREATE TABLE IF NOT EXISTS partition_statistics
+(
+    partition_id        INTEGER not null
+        constraint partition_statistics_pkey
+            primary key
+        references partition
+            on delete cascade,
+    row_count           numeric not null,
+    min_time            numeric,
+    max_time            numeric,
+    max_parquet_file_id numeric,
+    complete            boolean not null,
+    column_statistics   text    not null
+);
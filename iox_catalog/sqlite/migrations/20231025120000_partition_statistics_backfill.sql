@@ -0,0 +1,27 @@
+-- Record incomplete statistics for every partition that already has parquet
+-- files, as the statistics of a partition are folded in incrementally as its
+-- files are persisted, assuming a partition without statistics has no files.
+--
+-- Files are matched to their partition by ID, or by hash ID if they have no
+-- partition ID, in two separate joins so that each can use its index.
+INSERT INTO partition_statistics (
+    partition_id, row_count, min_time, max_time, max_parquet_file_id, complete,
+    column_statistics )
+SELECT files.partition_id, SUM(files.row_count), MIN(files.min_time),
+       MAX(files.max_time), MAX(files.id), FALSE, '[]'
+FROM (
+    SELECT partition.id AS partition_id, parquet_file.row_count,
+           parquet_file.min_time, parquet_file.max_time, parquet_file.id
+    FROM partition
+    INNER JOIN parquet_file ON partition.id = parquet_file.partition_id
+    WHERE parquet_file.to_delete IS NULL
+    UNION ALL
+    SELECT partition.id AS partition_id, parquet_file.row_count,
+           parquet_file.min_time, parquet_file.max_time, parquet_file.id
+    FROM partition
+    INNER JOIN parquet_file ON partition.hash_id = parquet_file.partition_hash_id
+    WHERE parquet_file.to_delete IS NULL
+      AND parquet_file.partition_id IS NULL
+) AS files
+GROUP BY files.partition_id
+ON CONFLICT (partition_id) DO NOTHING;
@@ -0,0 +1,1378 @@
+//! A read-through caching decorator for [`Catalog`] implementations.
+//!
+//! Services such as the compactor issue the same namespace, table, column and
+//! partition lookups over and over again. The [`CachingCatalog`] answers these
+//! from a memory-bounded cache, falling back to the wrapped catalog on a miss.
+//! The compactor uses it when started with `--compaction-catalog-cache-bytes`.
+//!
+//! # Invalidation
+//!
+//! Cached entries are invalidated:
+//!
+//! * When the row is changed through this [`CachingCatalog`] (write-through).
+//! * When a schema change is received from a gossip peer, by registering the
+//!   [`CachingCatalog`] as a [`SchemaEventHandler`].
+//! * When the configured TTL elapses.
+//!
+//! Changes made to the catalog by other processes that are not gossiped (such
+//! as namespace limit updates or partition sort key changes) are only observed
+//! once the TTL has expired, and a read racing with a concurrent write may
+//! cache the old row until then. The TTL therefore bounds the staleness of any
+//! cached row.
+
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Display},
+    mem::size_of_val,
+    ops::{Add, Sub},
+    sync::Arc,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use cache_system::{
+    backend::{
+        policy::{
+            lru::{LruPolicy, ResourcePool},
+            ttl::{ConstantValueTtlProvider, TtlPolicy},
+            PolicyBackend,
+        },
+        CacheBackend,
+    },
+    resource_consumption::{FunctionEstimator, Resource},
+};
+use data_types::{
+    partition_template::{NamespacePartitionTemplateOverride, TablePartitionTemplateOverride},
+    Column, ColumnType, CompactionLevel, MaxColumnsPerTable, MaxTables, Namespace, NamespaceId,
+    NamespaceName, NamespaceServiceProtectionLimitsOverride, ParquetFile, ParquetFileId,
+    ParquetFileParams, Partition, PartitionColumnStatistics, PartitionHashId, PartitionId,
+    PartitionKey, PartitionStatistics, SkippedCompaction, SortedColumnSet, Table, TableId,
+    Timestamp, TransitionPartitionId,
+};
+use generated_types::influxdata::iox::gossip::v1::{schema_message::Event, TableUpdated};
+use gossip_schema::dispatcher::SchemaEventHandler;
+use iox_time::TimeProvider;
+use metric::{Metric, U64Counter};
+use observability_deps::tracing::{trace, warn};
+use parking_lot::Mutex;
+use tokio::runtime::Handle;
+use uuid::Uuid;
+
+use crate::interface::{
+    CasFailure, Catalog, ColumnRepo, Error, NamespaceRepo, ParquetFileRepo, PartitionRepo,
+    RepoCollection, Result, SoftDeletedRows, TableRepo,
+};
+
+/// The default duration after which a cached row is refreshed from the
+/// wrapped catalog.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+/// The name of the [`ResourcePool`] backing the cache.
+const POOL_NAME: &str = "catalog";
+
+/// The ID of the cache within the [`ResourcePool`].
+const CACHE_ID: &str = "catalog_rows";
+
+/// Memory used by the cached rows, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CacheBytes(pub usize);
+
+impl Resource for CacheBytes {
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    fn unit() -> &'static str {
+        "bytes"
+    }
+}
+
+impl From<CacheBytes> for u64 {
+    fn from(s: CacheBytes) -> Self {
+        s.0 as Self
+    }
+}
+
+impl Add for CacheBytes {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0.checked_add(rhs.0).expect("overflow"))
+    }
+}
+
+impl Sub for CacheBytes {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0.checked_sub(rhs.0).expect("underflow"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum CacheKey {
+    NamespaceById(NamespaceId),
+    NamespaceByName(String),
+    TableById(TableId),
+    TableByName(NamespaceId, String),
+    TablesByNamespace(NamespaceId),
+    ColumnsByTable(TableId),
+    ColumnsByNamespace(NamespaceId),
+    /// Holds the row of a partition without a [`PartitionHashId`], or the
+    /// (immutable) [`PartitionHashId`] of a partition that has one.
+    PartitionById(PartitionId),
+    /// Holds the row of the partition with the [`PartitionHashId`] of these
+    /// bytes.
+    PartitionByHashId(Vec<u8>),
+}
+
+impl CacheKey {
+    fn size(&self) -> usize {
+        size_of_val(self)
+            + match self {
+                Self::NamespaceByName(name) | Self::TableByName(_, name) => name.capacity(),
+                Self::PartitionByHashId(bytes) => bytes.capacity(),
+                _ => 0,
+            }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum CacheValue {
+    Namespace(Arc<Namespace>),
+    Table(Arc<Table>),
+    Tables(Arc<Vec<Table>>),
+    Columns(Arc<Vec<Column>>),
+    Partition(Arc<Partition>),
+    PartitionHashId(PartitionHashId),
+}
+
+impl CacheValue {
+    fn size(&self) -> usize {
+        size_of_val(self)
+            + match self {
+                Self::Namespace(ns) => size_of_val(ns.as_ref()) + ns.name.capacity(),
+                Self::Table(t) => table_size(t),
+                Self::Tables(tables) => tables.iter().map(table_size).sum::<usize>(),
+                Self::Columns(columns) => columns
+                    .iter()
+                    .map(|c| size_of_val(c) + c.name.capacity())
+                    .sum::<usize>(),
+                Self::Partition(p) => {
+                    size_of_val(p.as_ref())
+                        + p.partition_key.inner().len()
+                        + p.sort_key
+                            .iter()
+                            .flatten()
+                            .map(|s| size_of_val(s) + s.capacity())
+                            .sum::<usize>()
+                }
+                Self::PartitionHashId(hash_id) => hash_id.as_bytes().len(),
+            }
+    }
+}
+
+fn table_size(t: &Table) -> usize {
+    size_of_val(t) + t.name.capacity()
+}
+
+/// The cache shared by all repositories handed out by a [`CachingCatalog`].
+#[derive(Debug)]
+struct CatalogCache {
+    backend: Mutex<PolicyBackend<CacheKey, CacheValue>>,
+    requests: Metric<U64Counter>,
+}
+
+impl CatalogCache {
+    /// Read `k` from the cache, recording a hit or miss for `op`.
+    fn get(&self, op: &'static str, k: &CacheKey) -> Option<CacheValue> {
+        let v = self.backend.lock().get(k);
+        let result = match v {
+            Some(_) => "hit",
+            None => "miss",
+        };
+        self.requests
+            .recorder(&[("op", op), ("result", result)])
+            .inc(1);
+        v
+    }
+
+    fn set(&self, k: CacheKey, v: CacheValue) {
+        self.backend.lock().set(k, v);
+    }
+
+    fn remove(&self, k: &CacheKey) {
+        self.backend.lock().remove(k);
+    }
+
+    fn get_namespace(&self, op: &'static str, k: &CacheKey) -> Option<Arc<Namespace>> {
+        match self.get(op, k)? {
+            CacheValue::Namespace(v) => Some(v),
+            v => unreachable!("unexpected cache value {v:?} for {k:?}"),
+        }
+    }
+
+    fn get_table(&self, op: &'static str, k: &CacheKey) -> Option<Arc<Table>> {
+        match self.get(op, k)? {
+            CacheValue::Table(v) => Some(v),
+            v => unreachable!("unexpected cache value {v:?} for {k:?}"),
+        }
+    }
+
+    fn get_tables(&self, op: &'static str, k: &CacheKey) -> Option<Arc<Vec<Table>>> {
+        match self.get(op, k)? {
+            CacheValue::Tables(v) => Some(v),
+            v => unreachable!("unexpected cache value {v:?} for {k:?}"),
+        }
+    }
+
+    fn get_columns(&self, op: &'static str, k: &CacheKey) -> Option<Arc<Vec<Column>>> {
+        match self.get(op, k)? {
+            CacheValue::Columns(v) => Some(v),
+            v => unreachable!("unexpected cache value {v:?} for {k:?}"),
+        }
+    }
+
+    /// Read the partition `id`, following the [`PartitionHashId`] it maps to
+    /// if it has one, and record a single hit or miss for `op`.
+    fn get_partition_by_id(&self, op: &'static str, id: PartitionId) -> Option<Arc<Partition>> {
+        let k = CacheKey::PartitionById(id);
+        let v = {
+            let mut backend = self.backend.lock();
+            match backend.get(&k) {
+                Some(CacheValue::Partition(v)) => Some(v),
+                Some(CacheValue::PartitionHashId(hash_id)) => {
+                    match backend.get(&CacheKey::PartitionByHashId(hash_id.as_bytes().to_vec())) {
+                        Some(CacheValue::Partition(v)) => Some(v),
+                        Some(v) => unreachable!("unexpected cache value {v:?} for {hash_id}"),
+                        None => None,
+                    }
+                }
+                Some(v) => unreachable!("unexpected cache value {v:?} for {k:?}"),
+                None => None,
+            }
+        };
+        let result = match v {
+            Some(_) => "hit",
+            None => "miss",
+        };
+        self.requests
+            .recorder(&[("op", op), ("result", result)])
+            .inc(1);
+        v
+    }
+
+    fn get_partition_by_hash_id(
+        &self,
+        op: &'static str,
+        hash_id: &PartitionHashId,
+    ) -> Option<Arc<Partition>> {
+        let k = CacheKey::PartitionByHashId(hash_id.as_bytes().to_vec());
+        match self.get(op, &k)? {
+            CacheValue::Partition(v) => Some(v),
+            v => unreachable!("unexpected cache value {v:?} for {k:?}"),
+        }
+    }
+
+    fn set_namespace(&self, ns: &Arc<Namespace>) {
+        self.set(
+            CacheKey::NamespaceById(ns.id),
+            CacheValue::Namespace(Arc::clone(ns)),
+        );
+        self.set(
+            CacheKey::NamespaceByName(ns.name.clone()),
+            CacheValue::Namespace(Arc::clone(ns)),
+        );
+    }
+
+    /// Cache the row of `p` under its [`PartitionHashId`] if it has one, so
+    /// that the row can always be invalidated by either of its IDs.
+    fn set_partition(&self, p: &Arc<Partition>) {
+        match p.hash_id() {
+            Some(hash_id) => {
+                self.set(
+                    CacheKey::PartitionByHashId(hash_id.as_bytes().to_vec()),
+                    CacheValue::Partition(Arc::clone(p)),
+                );
+                self.set(
+                    CacheKey::PartitionById(p.id),
+                    CacheValue::PartitionHashId(hash_id.clone()),
+                );
+            }
+            None => self.set(
+                CacheKey::PartitionById(p.id),
+                CacheValue::Partition(Arc::clone(p)),
+            ),
+        }
+    }
+
+    fn invalidate_namespace(&self, id: Option<NamespaceId>, name: &str) {
+        self.remove(&CacheKey::NamespaceByName(name.to_string()));
+        if let Some(id) = id {
+            self.remove(&CacheKey::NamespaceById(id));
+        }
+    }
+
+    fn invalidate_partition(&self, partition_id: &TransitionPartitionId) {
+        match partition_id {
+            TransitionPartitionId::Deprecated(id) => {
+                let k = CacheKey::PartitionById(*id);
+                let mut backend = self.backend.lock();
+                if let Some(CacheValue::PartitionHashId(hash_id)) = backend.get(&k) {
+                    backend.remove(&CacheKey::PartitionByHashId(hash_id.as_bytes().to_vec()));
+                }
+                backend.remove(&k);
+            }
+            TransitionPartitionId::Deterministic(hash_id) => {
+                self.remove(&CacheKey::PartitionByHashId(hash_id.as_bytes().to_vec()));
+            }
+        }
+    }
+}
+
+/// Returns true if `namespace` is selected by `deleted`.
+fn is_selected(namespace: &Namespace, deleted: SoftDeletedRows) -> bool {
+    match deleted {
+        SoftDeletedRows::AllRows => true,
+        SoftDeletedRows::ExcludeDeleted => namespace.deleted_at.is_none(),
+        SoftDeletedRows::OnlyDeleted => namespace.deleted_at.is_some(),
+    }
+}
+
+/// A [`Catalog`] decorator caching namespace, table, column and partition rows
+/// read from the wrapped catalog.
+///
+/// Requests are counted under the `catalog_cache_requests` metric, labelled by
+/// operation name and result (hit/miss). Memory use is bounded by an LRU
+/// [`ResourcePool`] and reported through the usual `cache_lru_*` metrics.
+///
+/// Parquet file operations, listings of all rows and compaction bookkeeping
+/// are never cached.
+///
+/// See the [module documentation](self) for the invalidation rules.
+#[derive(Debug)]
+pub struct CachingCatalog {
+    inner: Arc<dyn Catalog>,
+    cache: Arc<CatalogCache>,
+}
+
+impl CachingCatalog {
+    /// Wrap `inner`, caching up to `ram_limit` bytes of rows for at most `ttl`
+    /// each.
+    pub fn new(
+        inner: Arc<dyn Catalog>,
+        metric_registry: Arc<metric::Registry>,
+        ram_limit: usize,
+        ttl: Duration,
+        runtime_handle: &Handle,
+    ) -> Self {
+        let ram_pool = Arc::new(ResourcePool::new(
+            POOL_NAME,
+            CacheBytes(ram_limit),
+            Arc::clone(&metric_registry),
+            runtime_handle,
+        ));
+
+        let mut backend = PolicyBackend::hashmap_backed(inner.time_provider());
+        backend.add_policy(TtlPolicy::new(
+            Arc::new(ConstantValueTtlProvider::new(Some(ttl))),
+            CACHE_ID,
+            &metric_registry,
+        ));
+        backend.add_policy(LruPolicy::new(
+            ram_pool,
+            CACHE_ID,
+            Arc::new(FunctionEstimator::new(|k: &CacheKey, v: &CacheValue| {
+                CacheBytes(k.size() + v.size())
+            })),
+        ));
+
+        let requests = metric_registry.register_metric(
+            "catalog_cache_requests",
+            "number of catalog requests answered by the caching catalog",
+        );
+
+        Self {
+            inner,
+            cache: Arc::new(CatalogCache {
+                backend: Mutex::new(backend),
+                requests,
+            }),
+        }
+    }
+
+    async fn invalidate_table(&self, update: TableUpdated) {
+        let table_id = TableId::new(update.table_id);
+        self.cache.remove(&CacheKey::TableById(table_id));
+        self.cache.remove(&CacheKey::ColumnsByTable(table_id));
+
+        // Gossip identifies the namespace by name only.
+        let mut repos = self.repositories().await;
+        let namespace = match repos
+            .namespaces()
+            .get_by_name(&update.namespace_name, SoftDeletedRows::AllRows)
+            .await
+        {
+            Ok(Some(v)) => v,
+            Ok(None) => return,
+            Err(error) => {
+                warn!(%error, namespace_name=%update.namespace_name, "failed to resolve gossip namespace");
+                return;
+            }
+        };
+
+        self.cache.remove(&CacheKey::TableByName(
+            namespace.id,
+            update.table_name.clone(),
+        ));
+        self.cache
+            .remove(&CacheKey::TablesByNamespace(namespace.id));
+        self.cache
+            .remove(&CacheKey::ColumnsByNamespace(namespace.id));
+    }
+}
+
+impl Display for CachingCatalog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "caching({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl Catalog for CachingCatalog {
+    async fn setup(&self) -> Result<(), Error> {
+        self.inner.setup().await
+    }
+
+    async fn repositories(&self) -> Box<dyn RepoCollection> {
+        Box::new(CachingRepos {
+            inner: self.inner.repositories().await,
+            cache: Arc::clone(&self.cache),
+        })
+    }
+
+    #[cfg(test)]
+    fn metrics(&self) -> Arc<metric::Registry> {
+        self.inner.metrics()
+    }
+
+    fn time_provider(&self) -> Arc<dyn TimeProvider> {
+        self.inner.time_provider()
+    }
+}
+
+/// Invalidates the cached rows affected by schema changes gossiped by peers.
+#[async_trait]
+impl SchemaEventHandler for CachingCatalog {
+    async fn handle(&self, event: Event) {
+        trace!(?event, "invalidating cached catalog rows");
+
+        match event {
+            Event::NamespaceCreated(v) => {
+                let id = NamespaceId::new(v.namespace_id);
+                self.cache.invalidate_namespace(Some(id), &v.namespace_name);
+                self.cache.remove(&CacheKey::TablesByNamespace(id));
+                self.cache.remove(&CacheKey::ColumnsByNamespace(id));
+            }
+            Event::TableCreated(v) => match v.table {
+                Some(table) => self.invalidate_table(table).await,
+                None => warn!("table create contains no table information"),
+            },
+            Event::TableUpdated(v) => self.invalidate_table(v).await,
+        }
+    }
+}
+
+/// The [`RepoCollection`] handed out by [`CachingCatalog::repositories()`].
+#[derive(Debug)]
+struct CachingRepos {
+    inner: Box<dyn RepoCollection>,
+    cache: Arc<CatalogCache>,
+}
+
+impl CachingRepos {
+    /// Resolve the namespace of `table_id`, if the table exists.
+    async fn namespace_of_table(&mut self, table_id: TableId) -> Result<Option<NamespaceId>> {
+        Ok(TableRepo::get_by_id(self, table_id)
+            .await?
+            .map(|t| t.namespace_id))
+    }
+
+    /// Drop the cached column lists that contain the columns of `table_id`.
+    async fn invalidate_columns(&mut self, table_id: TableId) -> Result<()> {
+        self.cache.remove(&CacheKey::ColumnsByTable(table_id));
+        if let Some(namespace_id) = self.namespace_of_table(table_id).await? {
+            self.cache
+                .remove(&CacheKey::ColumnsByNamespace(namespace_id));
+        }
+        Ok(())
+    }
+
+    async fn get_namespace(
+        &mut self,
+        op: &'static str,
+        key: CacheKey,
+        deleted: SoftDeletedRows,
+    ) -> Result<Option<Namespace>> {
+        let namespace = match self.cache.get_namespace(op, &key) {
+            Some(v) => Some(v),
+            None => {
+                // Cache the row regardless of its deletion state, and filter
+                // it below.
+                let v = match &key {
+                    CacheKey::NamespaceById(id) => {
+                        self.inner
+                            .namespaces()
+                            .get_by_id(*id, SoftDeletedRows::AllRows)
+                            .await?
+                    }
+                    CacheKey::NamespaceByName(name) => {
+                        self.inner
+                            .namespaces()
+                            .get_by_name(name, SoftDeletedRows::AllRows)
+                            .await?
+                    }
+                    _ => unreachable!("not a namespace key: {key:?}"),
+                }
+                .map(Arc::new);
+                if let Some(v) = &v {
+                    self.cache.set_namespace(v);
+                }
+                v
+            }
+        };
+
+        Ok(namespace
+            .filter(|v| is_selected(v, deleted))
+            .map(|v| Namespace::clone(&v)))
+    }
+
+    fn updated_namespace(&self, name: &str, res: Result<Namespace>) -> Result<Namespace> {
+        self.cache
+            .invalidate_namespace(res.as_ref().ok().map(|v| v.id), name);
+        res
+    }
+}
+
+impl RepoCollection for CachingRepos {
+    fn namespaces(&mut self) -> &mut dyn NamespaceRepo {
+        self
+    }
+
+    fn tables(&mut self) -> &mut dyn TableRepo {
+        self
+    }
+
+    fn columns(&mut self) -> &mut dyn ColumnRepo {
+        self
+    }
+
+    fn partitions(&mut self) -> &mut dyn PartitionRepo {
+        self
+    }
+
+    fn parquet_files(&mut self) -> &mut dyn ParquetFileRepo {
+        self
+    }
+}
+
+#[async_trait]
+impl NamespaceRepo for CachingRepos {
+    async fn create(
+        &mut self,
+        name: &NamespaceName<'_>,
+        partition_template: Option<NamespacePartitionTemplateOverride>,
+        retention_period_ns: Option<i64>,
+        service_protection_limits: Option<NamespaceServiceProtectionLimitsOverride>,
+    ) -> Result<Namespace> {
+        let res = self
+            .inner
+            .namespaces()
+            .create(
+                name,
+                partition_template,
+                retention_period_ns,
+                service_protection_limits,
+            )
+            .await;
+        self.updated_namespace(name.as_str(), res)
+    }
+
+    async fn update_retention_period(
+        &mut self,
+        name: &str,
+        retention_period_ns: Option<i64>,
+    ) -> Result<Namespace> {
+        let res = self
+            .inner
+            .namespaces()
+            .update_retention_period(name, retention_period_ns)
+            .await;
+        self.updated_namespace(name, res)
+    }
+
+    async fn list(&mut self, deleted: SoftDeletedRows) -> Result<Vec<Namespace>> {
+        self.inner.namespaces().list(deleted).await
+    }
+
+    async fn get_by_id(
+        &mut self,
+        id: NamespaceId,
+        deleted: SoftDeletedRows,
+    ) -> Result<Option<Namespace>> {
+        self.get_namespace("namespace_get_by_id", CacheKey::NamespaceById(id), deleted)
+            .await
+    }
+
+    async fn get_by_name(
+        &mut self,
+        name: &str,
+        deleted: SoftDeletedRows,
+    ) -> Result<Option<Namespace>> {
+        self.get_namespace(
+            "namespace_get_by_name",
+            CacheKey::NamespaceByName(name.to_string()),
+            deleted,
+        )
+        .await
+    }
+
+    async fn soft_delete(&mut self, name: &str) -> Result<()> {
+        // Resolve the ID before the row disappears from name lookups.
+        let id = NamespaceRepo::get_by_name(self, name, SoftDeletedRows::AllRows)
+            .await?
+            .map(|v| v.id);
+        let res = self.inner.namespaces().soft_delete(name).await;
+        self.cache.invalidate_namespace(id, name);
+        res
+    }
+
+    async fn update_table_limit(&mut self, name: &str, new_max: MaxTables) -> Result<Namespace> {
+        let res = self
+            .inner
+            .namespaces()
+            .update_table_limit(name, new_max)
+            .await;
+        self.updated_namespace(name, res)
+    }
+
+    async fn update_column_limit(
+        &mut self,
+        name: &str,
+        new_max: MaxColumnsPerTable,
+    ) -> Result<Namespace> {
+        let res = self
+            .inner
+            .namespaces()
+            .update_column_limit(name, new_max)
+            .await;
+        self.updated_namespace(name, res)
+    }
+
+    async fn update_query_quota(
+        &mut self,
+        name: &str,
+        max_concurrent_queries: Option<i32>,
+        max_query_memory_bytes: Option<i64>,
+    ) -> Result<Namespace> {
+        let res = self
+            .inner
+            .namespaces()
+            .update_query_quota(name, max_concurrent_queries, max_query_memory_bytes)
+            .await;
+        self.updated_namespace(name, res)
+    }
+}
+
+#[async_trait]
+impl TableRepo for CachingRepos {
+    async fn create(
+        &mut self,
+        name: &str,
+        partition_template: TablePartitionTemplateOverride,
+        namespace_id: NamespaceId,
+    ) -> Result<Table> {
+        let res = self
+            .inner
+            .tables()
+            .create(name, partition_template, namespace_id)
+            .await;
+        self.cache
+            .remove(&CacheKey::TablesByNamespace(namespace_id));
+        self.cache
+            .remove(&CacheKey::TableByName(namespace_id, name.to_string()));
+        res
+    }
+
+    async fn get_by_id(&mut self, table_id: TableId) -> Result<Option<Table>> {
+        let key = CacheKey::TableById(table_id);
+        if let Some(v) = self.cache.get_table("table_get_by_id", &key) {
+            return Ok(Some(Table::clone(&v)));
+        }
+
+        let v = self.inner.tables().get_by_id(table_id).await?;
+        if let Some(v) = &v {
+            self.cache.set(key, CacheValue::Table(Arc::new(v.clone())));
+        }
+        Ok(v)
+    }
+
+    async fn get_by_namespace_and_name(
+        &mut self,
+        namespace_id: NamespaceId,
+        name: &str,
+    ) -> Result<Option<Table>> {
+        let key = CacheKey::TableByName(namespace_id, name.to_string());
+        if let Some(v) = self
+            .cache
+            .get_table("table_get_by_namespace_and_name", &key)
+        {
+            return Ok(Some(Table::clone(&v)));
+        }
+
+        let v = self
+            .inner
+            .tables()
+            .get_by_namespace_and_name(namespace_id, name)
+            .await?;
+        if let Some(v) = &v {
+            self.cache.set(key, CacheValue::Table(Arc::new(v.clone())));
+        }
+        Ok(v)
+    }
+
+    async fn list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<Table>> {
+        let key = CacheKey::TablesByNamespace(namespace_id);
+        if let Some(v) = self.cache.get_tables("table_list_by_namespace_id", &key) {
+            return Ok(Vec::clone(&v));
+        }
+
+        let v = self
+            .inner
+            .tables()
+            .list_by_namespace_id(namespace_id)
+            .await?;
+        self.cache.set(key, CacheValue::Tables(Arc::new(v.clone())));
+        Ok(v)
+    }
+
+    async fn list(&mut self) -> Result<Vec<Table>> {
+        self.inner.tables().list().await
+    }
+}
+
+#[async_trait]
+impl ColumnRepo for CachingRepos {
+    async fn create_or_get(
+        &mut self,
+        name: &str,
+        table_id: TableId,
+        column_type: ColumnType,
+    ) -> Result<Column> {
+        let res = self
+            .inner
+            .columns()
+            .create_or_get(name, table_id, column_type)
+            .await;
+        self.invalidate_columns(table_id).await?;
+        res
+    }
+
+    async fn create_or_get_many_unchecked(
+        &mut self,
+        table_id: TableId,
+        columns: HashMap<&str, ColumnType>,
+    ) -> Result<Vec<Column>> {
+        // The upsert may partially commit, so invalidate even if it fails.
+        let res = self
+            .inner
+            .columns()
+            .create_or_get_many_unchecked(table_id, columns)
+            .await;
+        self.invalidate_columns(table_id).await?;
+        res
+    }
+
+    async fn list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<Column>> {
+        let key = CacheKey::ColumnsByNamespace(namespace_id);
+        if let Some(v) = self.cache.get_columns("column_list_by_namespace_id", &key) {
+            return Ok(Vec::clone(&v));
+        }
+
+        let v = self
+            .inner
+            .columns()
+            .list_by_namespace_id(namespace_id)
+            .await?;
+        self.cache
+            .set(key, CacheValue::Columns(Arc::new(v.clone())));
+        Ok(v)
+    }
+
+    async fn list_by_table_id(&mut self, table_id: TableId) -> Result<Vec<Column>> {
+        let key = CacheKey::ColumnsByTable(table_id);
+        if let Some(v) = self.cache.get_columns("column_list_by_table_id", &key) {
+            return Ok(Vec::clone(&v));
+        }
+
+        let v = self.inner.columns().list_by_table_id(table_id).await?;
+        self.cache
+            .set(key, CacheValue::Columns(Arc::new(v.clone())));
+        Ok(v)
+    }
+
+    async fn list(&mut self) -> Result<Vec<Column>> {
+        self.inner.columns().list().await
+    }
+}
+
+#[async_trait]
+impl PartitionRepo for CachingRepos {
+    async fn create_or_get(&mut self, key: PartitionKey, table_id: TableId) -> Result<Partition> {
+        self.inner.partitions().create_or_get(key, table_id).await
+    }
+
+    async fn get_by_id(&mut self, partition_id: PartitionId) -> Result<Option<Partition>> {
+        if let Some(v) = self
+            .cache
+            .get_partition_by_id("partition_get_by_id", partition_id)
+        {
+            return Ok(Some(Partition::clone(&v)));
+        }
+
+        let v = self.inner.partitions().get_by_id(partition_id).await?;
+        if let Some(v) = &v {
+            self.cache.set_partition(&Arc::new(v.clone()));
+        }
+        Ok(v)
+    }
+
+    async fn get_by_id_batch(&mut self, partition_ids: Vec<PartitionId>) -> Result<Vec<Partition>> {
+        let mut out = Vec::with_capacity(partition_ids.len());
+        let mut missing = vec![];
+        for id in partition_ids {
+            match self
+                .cache
+                .get_partition_by_id("partition_get_by_id_batch", id)
+            {
+                Some(v) => out.push(Partition::clone(&v)),
+                None => missing.push(id),
+            }
+        }
+
+        if !missing.is_empty() {
+            let fetched = self.inner.partitions().get_by_id_batch(missing).await?;
+            for v in &fetched {
+                self.cache.set_partition(&Arc::new(v.clone()));
+            }
+            out.extend(fetched);
+        }
+
+        Ok(out)
+    }
+
+    async fn get_by_hash_id(
+        &mut self,
+        partition_hash_id: &PartitionHashId,
+    ) -> Result<Option<Partition>> {
+        if let Some(v) = self
+            .cache
+            .get_partition_by_hash_id("partition_get_by_hash_id", partition_hash_id)
+        {
+            return Ok(Some(Partition::clone(&v)));
+        }
+
+        let v = self
+            .inner
+            .partitions()
+            .get_by_hash_id(partition_hash_id)
+            .await?;
+        if let Some(v) = &v {
+            self.cache.set_partition(&Arc::new(v.clone()));
+        }
+        Ok(v)
+    }
+
+    async fn get_by_hash_id_batch(
+        &mut self,
+        partition_hash_ids: &[&PartitionHashId],
+    ) -> Result<Vec<Partition>> {
+        self.inner
+            .partitions()
+            .get_by_hash_id_batch(partition_hash_ids)
+            .await
+    }
+
+    async fn list_by_table_id(&mut self, table_id: TableId) -> Result<Vec<Partition>> {
+        self.inner.partitions().list_by_table_id(table_id).await
+    }
+
+    async fn list_ids(&mut self) -> Result<Vec<PartitionId>> {
+        self.inner.partitions().list_ids().await
+    }
+
+    async fn cas_sort_key(
+        &mut self,
+        partition_id: &TransitionPartitionId,
+        old_sort_key: Option<Vec<String>>,
+        old_sort_key_ids: Option<SortedColumnSet>,
+        new_sort_key: &[&str],
+        new_sort_key_ids: &SortedColumnSet,
+    ) -> Result<Partition, CasFailure<(Option<Vec<String>>, SortedColumnSet)>> {
+        let res = self
+            .inner
+            .partitions()
+            .cas_sort_key(
+                partition_id,
+                old_sort_key,
+                old_sort_key_ids,
+                new_sort_key,
+                new_sort_key_ids,
+            )
+            .await;
+        self.cache.invalidate_partition(partition_id);
+        if let Ok(v) = &res {
+            self.cache
+                .invalidate_partition(&v.transition_partition_id());
+        }
+        res
+    }
+
+    async fn record_skipped_compaction(
+        &mut self,
+        partition_id: PartitionId,
+        reason: &str,
+        num_files: usize,
+        limit_num_files: usize,
+        limit_num_files_first_in_partition: usize,
+        estimated_bytes: u64,
+        limit_bytes: u64,
+    ) -> Result<()> {
+        self.inner
+            .partitions()
+            .record_skipped_compaction(
+                partition_id,
+                reason,
+                num_files,
+                limit_num_files,
+                limit_num_files_first_in_partition,
+                estimated_bytes,
+                limit_bytes,
+            )
+            .await
+    }
+
+    async fn get_in_skipped_compactions(
+        &mut self,
+        partition_id: &[PartitionId],
+    ) -> Result<Vec<SkippedCompaction>> {
+        self.inner
+            .partitions()
+            .get_in_skipped_compactions(partition_id)
+            .await
+    }
+
+    async fn list_skipped_compactions(&mut self) -> Result<Vec<SkippedCompaction>> {
+        self.inner.partitions().list_skipped_compactions().await
+    }
+
+    async fn delete_skipped_compactions(
+        &mut self,
+        partition_id: PartitionId,
+    ) -> Result<Option<SkippedCompaction>> {
+        self.inner
+            .partitions()
+            .delete_skipped_compactions(partition_id)
+            .await
+    }
+
+    async fn most_recent_n(&mut self, n: usize) -> Result<Vec<Partition>> {
+        self.inner.partitions().most_recent_n(n).await
+    }
+
+    async fn partitions_new_file_between(
+        &mut self,
+        minimum_time: Timestamp,
+        maximum_time: Option<Timestamp>,
+    ) -> Result<Vec<PartitionId>> {
+        self.inner
+            .partitions()
+            .partitions_new_file_between(minimum_time, maximum_time)
+            .await
+    }
+
+    async fn list_old_style(&mut self) -> Result<Vec<Partition>> {
+        self.inner.partitions().list_old_style().await
+    }
+
+    async fn get_statistics_batch(
+        &mut self,
+        partition_ids: &[PartitionId],
+    ) -> Result<Vec<PartitionStatistics>> {
+        self.inner
+            .partitions()
+            .get_statistics_batch(partition_ids)
+            .await
+    }
+}
+
+/// Creating parquet files changes the `new_file_at` timestamp of their
+/// partition, so the cached partition rows are invalidated.
+#[async_trait]
+impl ParquetFileRepo for CachingRepos {
+    async fn create(&mut self, parquet_file_params: ParquetFileParams) -> Result<ParquetFile> {
+        let partition_id = parquet_file_params.partition_id.clone();
+        let res = self.inner.parquet_files().create(parquet_file_params).await;
+        self.cache.invalidate_partition(&partition_id);
+        res
+    }
+
+    async fn create_with_statistics(
+        &mut self,
+        parquet_file_params: ParquetFileParams,
+        column_statistics: &[PartitionColumnStatistics],
+    ) -> Result<ParquetFile> {
+        let partition_id = parquet_file_params.partition_id.clone();
+        let res = self
+            .inner
+            .parquet_files()
+            .create_with_statistics(parquet_file_params, column_statistics)
+            .await;
+        self.cache.invalidate_partition(&partition_id);
+        res
+    }
+
+    async fn list_all(&mut self) -> Result<Vec<ParquetFile>> {
+        self.inner.parquet_files().list_all().await
+    }
+
+    async fn flag_for_delete_by_retention(&mut self) -> Result<Vec<ParquetFileId>> {
+        self.inner
+            .parquet_files()
+            .flag_for_delete_by_retention()
+            .await
+    }
+
+    async fn list_by_namespace_not_to_delete(
+        &mut self,
+        namespace_id: NamespaceId,
+    ) -> Result<Vec<ParquetFile>> {
+        self.inner
+            .parquet_files()
+            .list_by_namespace_not_to_delete(namespace_id)
+            .await
+    }
+
+    async fn list_by_table_not_to_delete(&mut self, table_id: TableId) -> Result<Vec<ParquetFile>> {
+        self.inner
+            .parquet_files()
+            .list_by_table_not_to_delete(table_id)
+            .await
+    }
+
+    async fn delete_old_ids_only(&mut self, older_than: Timestamp) -> Result<Vec<ParquetFileId>> {
+        self.inner
+            .parquet_files()
+            .delete_old_ids_only(older_than)
+            .await
+    }
+
+    async fn list_by_partition_not_to_delete(
+        &mut self,
+        partition_id: &TransitionPartitionId,
+    ) -> Result<Vec<ParquetFile>> {
+        self.inner
+            .parquet_files()
+            .list_by_partition_not_to_delete(partition_id)
+            .await
+    }
+
+    async fn get_by_object_store_id(
+        &mut self,
+        object_store_id: Uuid,
+    ) -> Result<Option<ParquetFile>> {
+        self.inner
+            .parquet_files()
+            .get_by_object_store_id(object_store_id)
+            .await
+    }
+
+    async fn exists_by_object_store_id_batch(
+        &mut self,
+        object_store_ids: Vec<Uuid>,
+    ) -> Result<Vec<Uuid>> {
+        self.inner
+            .parquet_files()
+            .exists_by_object_store_id_batch(object_store_ids)
+            .await
+    }
+
+    async fn create_upgrade_delete(
+        &mut self,
+        delete: &[ParquetFileId],
+        upgrade: &[ParquetFileId],
+        create: &[ParquetFileParams],
+        target_level: CompactionLevel,
+    ) -> Result<Vec<ParquetFileId>> {
+        let res = self
+            .inner
+            .parquet_files()
+            .create_upgrade_delete(delete, upgrade, create, target_level)
+            .await;
+        for params in create {
+            self.cache.invalidate_partition(&params.partition_id);
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use generated_types::influxdata::iox::gossip::v1::TableCreated;
+    use metric::{Attributes, Metric};
+
+    use super::*;
+    use crate::{
+        mem::MemCatalog,
+        test_helpers::{arbitrary_namespace, arbitrary_table},
+    };
+
+    fn cache_requests(metrics: &metric::Registry, op: &'static str, result: &'static str) -> u64 {
+        metrics
+            .get_instrument::<Metric<U64Counter>>("catalog_cache_requests")
+            .expect("failed to read metric")
+            .get_observer(&Attributes::from(&[("op", op), ("result", result)]))
+            .map(|v| v.fetch())
+            .unwrap_or_default()
+    }
+
+    fn new_catalog(metrics: &Arc<metric::Registry>) -> (Arc<dyn Catalog>, CachingCatalog) {
+        let inner: Arc<dyn Catalog> = Arc::new(MemCatalog::new(Arc::clone(metrics)));
+        let catalog = CachingCatalog::new(
+            Arc::clone(&inner),
+            Arc::clone(metrics),
+            usize::MAX,
+            DEFAULT_TTL,
+            &Handle::current(),
+        );
+        (inner, catalog)
+    }
+
+    #[tokio::test]
+    async fn test_read_through_and_write_through() {
+        let metrics = Arc::new(metric::Registry::default());
+        let (_inner, catalog) = new_catalog(&metrics);
+        let mut repos = catalog.repositories().await;
+
+        let namespace = arbitrary_namespace(&mut *repos, "ns").await;
+        let table = arbitrary_table(&mut *repos, "t", &namespace).await;
+
+        for _ in 0..3 {
+            let got = repos
+                .namespaces()
+                .get_by_name("ns", SoftDeletedRows::ExcludeDeleted)
+                .await
+                .unwrap();
+            assert_eq!(got.as_ref(), Some(&namespace));
+        }
+        assert_eq!(cache_requests(&metrics, "namespace_get_by_name", "miss"), 1);
+        assert_eq!(cache_requests(&metrics, "namespace_get_by_name", "hit"), 2);
+
+        // The row is shared with lookups by ID.
+        let got = repos
+            .namespaces()
+            .get_by_id(namespace.id, SoftDeletedRows::AllRows)
+            .await
+            .unwrap();
+        assert_eq!(got.as_ref(), Some(&namespace));
+        assert_eq!(cache_requests(&metrics, "namespace_get_by_id", "hit"), 1);
+
+        // Populate the column cache, then add a column through the cache.
+        assert!(repos
+            .columns()
+            .list_by_table_id(table.id)
+            .await
+            .unwrap()
+            .is_empty());
+        repos
+            .columns()
+            .create_or_get("c", table.id, ColumnType::Tag)
+            .await
+            .unwrap();
+        let got = repos.columns().list_by_table_id(table.id).await.unwrap();
+        assert_eq!(got.len(), 1);
+        let got = repos
+            .columns()
+            .list_by_namespace_id(namespace.id)
+            .await
+            .unwrap();
+        assert_eq!(got.len(), 1);
+
+        // Soft deleting the namespace hides it from cached lookups.
+        repos.namespaces().soft_delete("ns").await.unwrap();
+        let got = repos
+            .namespaces()
+            .get_by_id(namespace.id, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .unwrap();
+        assert_eq!(got, None);
+        let got = repos
+            .namespaces()
+            .get_by_name("ns", SoftDeletedRows::OnlyDeleted)
+            .await
+            .unwrap();
+        assert!(got.unwrap().deleted_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_gossip_invalidation() {
+        let metrics = Arc::new(metric::Registry::default());
+        let (inner, catalog) = new_catalog(&metrics);
+
+        // Note the in-memory catalog is locked while repositories are held.
+        let (namespace, table) = {
+            let mut repos = catalog.repositories().await;
+            let namespace = arbitrary_namespace(&mut *repos, "ns").await;
+            let table = arbitrary_table(&mut *repos, "t", &namespace).await;
+            assert!(repos
+                .columns()
+                .list_by_table_id(table.id)
+                .await
+                .unwrap()
+                .is_empty());
+            let tables = repos
+                .tables()
+                .list_by_namespace_id(namespace.id)
+                .await
+                .unwrap();
+            assert_eq!(tables.len(), 1);
+            (namespace, table)
+        };
+
+        // Changes made by another process are not observed...
+        let table2 = {
+            let mut other = inner.repositories().await;
+            other
+                .columns()
+                .create_or_get("c", table.id, ColumnType::Tag)
+                .await
+                .unwrap();
+            arbitrary_table(&mut *other, "t2", &namespace).await
+        };
+        let got = catalog
+            .repositories()
+            .await
+            .columns()
+            .list_by_table_id(table.id)
+            .await
+            .unwrap();
+        assert!(got.is_empty());
+
+        // ...until they are gossiped.
+        catalog
+            .handle(Event::TableUpdated(TableUpdated {
+                table_name: "t".to_string(),
+                namespace_name: "ns".to_string(),
+                table_id: table.id.get(),
+                columns: vec![],
+            }))
+            .await;
+        let got = catalog
+            .repositories()
+            .await
+            .columns()
+            .list_by_table_id(table.id)
+            .await
+            .unwrap();
+        assert_eq!(got.len(), 1);
+
+        catalog
+            .handle(Event::TableCreated(TableCreated {
+                table: Some(TableUpdated {
+                    table_name: "t2".to_string(),
+                    namespace_name: "ns".to_string(),
+                    table_id: table2.id.get(),
+                    columns: vec![],
+                }),
+                partition_template: None,
+            }))
+            .await;
+        let got = catalog
+            .repositories()
+            .await
+            .tables()
+            .list_by_namespace_id(namespace.id)
+            .await
+            .unwrap();
+        assert_eq!(got.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_partition_invalidation_after_id_eviction() {
+        let metrics = Arc::new(metric::Registry::default());
+        let (inner, catalog) = new_catalog(&metrics);
+
+        let partition = {
+            let mut repos = catalog.repositories().await;
+            let namespace = arbitrary_namespace(&mut *repos, "ns").await;
+            let table = arbitrary_table(&mut *repos, "t", &namespace).await;
+            let partition = repos
+                .partitions()
+                .create_or_get("p".into(), table.id)
+                .await
+                .unwrap();
+            // Cache the row.
+            repos
+                .partitions()
+                .get_by_id(partition.id)
+                .await
+                .unwrap()
+                .unwrap();
+            partition
+        };
+        let hash_id = partition.hash_id().cloned().unwrap();
+
+        // The LRU evicts the entry of the partition ID before the row.
+        catalog.cache.remove(&CacheKey::PartitionById(partition.id));
+
+        // Another process changes the row, which is then invalidated by its
+        // hash ID alone.
+        let updated = inner
+            .repositories()
+            .await
+            .partitions()
+            .cas_sort_key(
+                &partition.transition_partition_id(),
+                None,
+                None,
+                &["tag1", "time"],
+                &SortedColumnSet::from([1, 2]),
+            )
+            .await
+            .unwrap();
+        catalog
+            .cache
+            .invalidate_partition(&TransitionPartitionId::Deterministic(hash_id.clone()));
+
+        let mut repos = catalog.repositories().await;
+        let got = repos
+            .partitions()
+            .get_by_id(partition.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(got, updated);
+        let got = repos
+            .partitions()
+            .get_by_hash_id(&hash_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(got, updated);
+        assert_eq!(cache_requests(&metrics, "partition_get_by_id", "miss"), 2);
+        assert_eq!(
+            cache_requests(&metrics, "partition_get_by_hash_id", "hit"),
+            1
+        );
+    }
+}
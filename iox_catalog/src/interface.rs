@@ -5,9 +5,9 @@ use data_types::{
     partition_template::{NamespacePartitionTemplateOverride, TablePartitionTemplateOverride},
     Column, ColumnType, ColumnsByName, CompactionLevel, MaxColumnsPerTable, MaxTables, Namespace,
     NamespaceId, NamespaceName, NamespaceSchema, NamespaceServiceProtectionLimitsOverride,
-    ParquetFile, ParquetFileId, ParquetFileParams, Partition, PartitionHashId, PartitionId,
-    PartitionKey, SkippedCompaction, SortedColumnSet, Table, TableId, TableSchema, Timestamp,
-    TransitionPartitionId,
+    ParquetFile, ParquetFileId, ParquetFileParams, Partition, PartitionColumnStatistics,
+    PartitionHashId, PartitionId, PartitionKey, PartitionStatistics, SkippedCompaction,
+    SortedColumnSet, Table, TableId, TableSchema, Timestamp, TransitionPartitionId,
 };
 use iox_time::TimeProvider;
 use snafu::{OptionExt, Snafu};
@@ -301,6 +301,15 @@ pub trait NamespaceRepo: Send + Sync {
         name: &str,
         new_max: MaxColumnsPerTable,
     ) -> Result<Namespace>;
+
+    /// Update the limits on the concurrency and memory use of queries against a namespace,
+    /// enforced by the querier. [`None`] removes the corresponding limit.
+    async fn update_query_quota(
+        &mut self,
+        name: &str,
+        max_concurrent_queries: Option<i32>,
+        max_query_memory_bytes: Option<i64>,
+    ) -> Result<Namespace>;
 }
 
 /// Functions for working with tables in the catalog
@@ -475,14 +484,40 @@ pub trait PartitionRepo: Send + Sync {
     /// Can be removed when all partitions have hash IDs and support for old-style partitions is no
     /// longer needed.
     async fn list_old_style(&mut self) -> Result<Vec<Partition>>;
+
+    /// Return the [`PartitionStatistics`] of the specified partitions.
+    ///
+    /// The statistics are maintained when parquet files are created through
+    /// [`ParquetFileRepo::create`], [`ParquetFileRepo::create_with_statistics`]
+    /// and [`ParquetFileRepo::create_upgrade_delete`].
+    ///
+    /// The output order is undefined, partitions without any recorded
+    /// statistics are not part of the output.
+    async fn get_statistics_batch(
+        &mut self,
+        partition_ids: &[PartitionId],
+    ) -> Result<Vec<PartitionStatistics>>;
 }
 
 /// Functions for working with parquet file pointers in the catalog
 #[async_trait]
 pub trait ParquetFileRepo: Send + Sync {
     /// create the parquet file
+    ///
+    /// The content of the file is unknown to the catalog, so the per-column
+    /// [`PartitionStatistics`] of its partition become incomplete. Prefer
+    /// [`Self::create_with_statistics`] where the column statistics are known.
     async fn create(&mut self, parquet_file_params: ParquetFileParams) -> Result<ParquetFile>;
 
+    /// create the parquet file, merging the per-column statistics of its
+    /// content into the [`PartitionStatistics`] of its partition in the same
+    /// transaction.
+    async fn create_with_statistics(
+        &mut self,
+        parquet_file_params: ParquetFileParams,
+        column_statistics: &[PartitionColumnStatistics],
+    ) -> Result<ParquetFile>;
+
     /// List all parquet files in implementation-defined, non-deterministic order.
     ///
     /// This includes files that were marked for deletion.
@@ -534,6 +569,11 @@ pub trait ParquetFileRepo: Send + Sync {
 
     /// Commit deletions, upgrades and creations in a single transaction.
     ///
+    /// The created files must not contain any values not present in the
+    /// deleted / upgraded files (as is the case for compaction): the
+    /// per-column [`PartitionStatistics`] of the affected partitions are
+    /// retained, while their row counts and time ranges are refreshed.
+    ///
     /// Returns IDs of created files.
     async fn create_upgrade_delete(
         &mut self,
@@ -798,6 +838,7 @@ pub(crate) mod test_helpers {
         test_list_schemas(clean_state().await).await;
         test_list_schemas_soft_deleted_rows(clean_state().await).await;
         test_delete_namespace(clean_state().await).await;
+        test_partition_statistics(clean_state().await).await;
 
         let catalog = clean_state().await;
         test_namespace(Arc::clone(&catalog)).await;
@@ -936,6 +977,30 @@ pub(crate) mod test_helpers {
             .expect("namespace should be updateable");
         assert!(modified.retention_period_ns.is_none());
 
+        // query quotas are unset by default
+        assert_eq!(namespace.max_concurrent_queries, None);
+        assert_eq!(namespace.max_query_memory_bytes, None);
+        let modified = repos
+            .namespaces()
+            .update_query_quota(namespace_name.as_str(), Some(4), Some(1024 * 1024))
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.max_concurrent_queries, Some(4));
+        assert_eq!(modified.max_query_memory_bytes, Some(1024 * 1024));
+        let modified = repos
+            .namespaces()
+            .update_query_quota(namespace_name.as_str(), None, None)
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.max_concurrent_queries, None);
+        assert_eq!(modified.max_query_memory_bytes, None);
+        let err = repos
+            .namespaces()
+            .update_query_quota("does_not_exist", Some(1), None)
+            .await
+            .expect_err("should fail to update unknown namespace");
+        assert!(matches!(err, Error::NamespaceNotFoundByName { .. }));
+
         // create namespace with retention period NULL (the default)
         let namespace3 = arbitrary_namespace(&mut *repos, "test_namespace3").await;
         assert!(namespace3.retention_period_ns.is_none());
@@ -2979,6 +3044,188 @@ pub(crate) mod test_helpers {
         assert!(partitions.is_empty());
     }
 
+    async fn test_partition_statistics(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+        let namespace = arbitrary_namespace(&mut *repos, "test_partition_statistics").await;
+        let table = arbitrary_table(&mut *repos, "test_table", &namespace).await;
+        let partition = repos
+            .partitions()
+            .create_or_get("one".into(), table.id)
+            .await
+            .unwrap();
+        let other_partition = repos
+            .partitions()
+            .create_or_get("two".into(), table.id)
+            .await
+            .unwrap();
+
+        let tag = |id, null_count, min: &str, max: &str| {
+            let mut values = vec![min.to_string(), max.to_string()];
+            values.dedup();
+            PartitionColumnStatistics {
+                column_id: ColumnId::new(id),
+                null_count,
+                min: Some(min.to_string()),
+                max: Some(max.to_string()),
+                values: Some(values),
+            }
+        };
+
+        // No statistics are recorded for a partition without files.
+        let stats = repos
+            .partitions()
+            .get_statistics_batch(&[partition.id, other_partition.id])
+            .await
+            .unwrap();
+        assert!(stats.is_empty());
+
+        // Persist a file with known column statistics.
+        let params = ParquetFileParams {
+            min_time: Timestamp::new(10),
+            max_time: Timestamp::new(20),
+            row_count: 5,
+            ..arbitrary_parquet_file_params(&namespace, &table, &partition)
+        };
+        let file_1 = repos
+            .parquet_files()
+            .create_with_statistics(params.clone(), &[tag(1, 0, "b", "c"), tag(2, 1, "x", "x")])
+            .await
+            .unwrap();
+
+        // And a second file.
+        let params_2 = ParquetFileParams {
+            object_store_id: Uuid::new_v4(),
+            min_time: Timestamp::new(5),
+            max_time: Timestamp::new(15),
+            row_count: 3,
+            ..params.clone()
+        };
+        let file_2 = repos
+            .parquet_files()
+            .create_with_statistics(params_2, &[tag(1, 1, "a", "b")])
+            .await
+            .unwrap();
+
+        let mut stats = repos
+            .partitions()
+            .get_statistics_batch(&[partition.id, other_partition.id])
+            .await
+            .unwrap();
+        assert_eq!(stats.len(), 1);
+        let stats = stats.pop().unwrap();
+        assert_eq!(stats.partition_id, partition.id);
+        assert_eq!(stats.row_count, 8);
+        assert_eq!(stats.min_time, Some(Timestamp::new(5)));
+        assert_eq!(stats.max_time, Some(Timestamp::new(20)));
+        assert_eq!(stats.max_parquet_file_id, Some(file_2.id));
+        assert!(stats.complete);
+        assert_eq!(
+            stats.columns,
+            [
+                PartitionColumnStatistics {
+                    values: Some(vec!["a".to_string(), "b".to_string(), "c".to_string()]),
+                    ..tag(1, 1, "a", "c")
+                },
+                tag(2, 4, "x", "x")
+            ]
+        );
+
+        // Compact both files into one, deduplicating a row.
+        let compacted = ParquetFileParams {
+            object_store_id: Uuid::new_v4(),
+            min_time: Timestamp::new(5),
+            max_time: Timestamp::new(20),
+            row_count: 7,
+            compaction_level: CompactionLevel::FileNonOverlapped,
+            ..params.clone()
+        };
+        let created = repos
+            .parquet_files()
+            .create_upgrade_delete(
+                &[file_1.id, file_2.id],
+                &[],
+                &[compacted],
+                CompactionLevel::FileNonOverlapped,
+            )
+            .await
+            .unwrap();
+
+        let compacted_stats = repos
+            .partitions()
+            .get_statistics_batch(&[partition.id])
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(compacted_stats.row_count, 7);
+        assert_eq!(compacted_stats.max_parquet_file_id, Some(created[0]));
+        assert!(compacted_stats.complete);
+        assert_eq!(compacted_stats.columns, stats.columns);
+
+        // A file introducing a new column is folded into the compacted
+        // statistics - the new column is NULL for all previous rows.
+        let params_3 = ParquetFileParams {
+            object_store_id: Uuid::new_v4(),
+            min_time: Timestamp::new(30),
+            max_time: Timestamp::new(40),
+            row_count: 2,
+            ..params.clone()
+        };
+        let file_3 = repos
+            .parquet_files()
+            .create_with_statistics(params_3, &[tag(1, 0, "d", "d"), tag(3, 0, "q", "r")])
+            .await
+            .unwrap();
+
+        let stats = repos
+            .partitions()
+            .get_statistics_batch(&[partition.id])
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(stats.row_count, 9);
+        assert_eq!(stats.min_time, Some(Timestamp::new(5)));
+        assert_eq!(stats.max_time, Some(Timestamp::new(40)));
+        assert_eq!(stats.max_parquet_file_id, Some(file_3.id));
+        assert!(stats.complete);
+        assert_eq!(
+            stats.columns,
+            [
+                PartitionColumnStatistics {
+                    values: Some(
+                        ["a", "b", "c", "d"]
+                            .into_iter()
+                            .map(ToString::to_string)
+                            .collect()
+                    ),
+                    ..tag(1, 1, "a", "d")
+                },
+                tag(2, 6, "x", "x"),
+                tag(3, 7, "q", "r"),
+            ]
+        );
+
+        // A file of unknown content makes the column statistics incomplete.
+        let unknown = ParquetFileParams {
+            object_store_id: Uuid::new_v4(),
+            row_count: 1,
+            ..params
+        };
+        repos.parquet_files().create(unknown).await.unwrap();
+
+        let unknown_stats = repos
+            .partitions()
+            .get_statistics_batch(&[partition.id])
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(unknown_stats.row_count, 10);
+        assert!(!unknown_stats.complete);
+        assert_eq!(unknown_stats.columns, stats.columns);
+    }
+
     async fn test_list_by_partiton_not_to_delete(catalog: Arc<dyn Catalog>) {
         let mut repos = catalog.repositories().await;
         let namespace = arbitrary_namespace(
@@ -33,13 +33,16 @@ const TIME_COLUMN: &str = "time";
 /// Default retention period for data in the catalog.
 pub const DEFAULT_RETENTION_PERIOD: Option<i64> = None;
 
+pub mod cache;
 pub mod interface;
 pub(crate) mod kafkaless_transition;
 pub mod mem;
 pub mod metrics;
 pub mod migrate;
+pub(crate) mod partition_statistics;
 pub mod postgres;
 pub mod sqlite;
+pub mod transfer;
 
 /// An [`crate::interface::Error`] scoped to a single table for schema validation errors.
 #[derive(Debug, Error)]
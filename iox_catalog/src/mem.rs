@@ -16,8 +16,10 @@ use data_types::{
     },
     Column, ColumnId, ColumnType, CompactionLevel, MaxColumnsPerTable, MaxTables, Namespace,
     NamespaceId, NamespaceName, NamespaceServiceProtectionLimitsOverride, ParquetFile,
-    ParquetFileId, ParquetFileParams, Partition, PartitionHashId, PartitionId, PartitionKey,
-    SkippedCompaction, SortedColumnSet, Table, TableId, Timestamp, TransitionPartitionId,
+    ParquetFileId, ParquetFileParams, Partition, PartitionColumnStatistics,
+    PartitionColumnStatisticsUpdate, PartitionFileTotals, PartitionHashId, PartitionId,
+    PartitionKey, PartitionStatistics, SkippedCompaction, SortedColumnSet, Table, TableId,
+    Timestamp, TransitionPartitionId,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use snafu::ensure;
@@ -69,6 +71,7 @@ struct MemCollections {
     partitions: Vec<Partition>,
     skipped_compactions: Vec<SkippedCompaction>,
     parquet_files: Vec<ParquetFile>,
+    partition_statistics: HashMap<PartitionId, PartitionStatistics>,
 }
 
 /// transaction bound to an in-memory catalog.
@@ -169,6 +172,8 @@ impl NamespaceRepo for MemTxn {
             name: name.to_string(),
             max_tables,
             max_columns_per_table,
+            max_concurrent_queries: None,
+            max_query_memory_bytes: None,
             retention_period_ns,
             deleted_at: None,
             partition_template: partition_template.unwrap_or_default(),
@@ -272,6 +277,25 @@ impl NamespaceRepo for MemTxn {
             }),
         }
     }
+
+    async fn update_query_quota(
+        &mut self,
+        name: &str,
+        max_concurrent_queries: Option<i32>,
+        max_query_memory_bytes: Option<i64>,
+    ) -> Result<Namespace> {
+        let stage = self.stage();
+        match stage.namespaces.iter_mut().find(|n| n.name == name) {
+            Some(n) => {
+                n.max_concurrent_queries = max_concurrent_queries;
+                n.max_query_memory_bytes = max_query_memory_bytes;
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
 }
 
 #[async_trait]
@@ -828,12 +852,46 @@ impl PartitionRepo for MemTxn {
 
         Ok(old_style)
     }
+
+    async fn get_statistics_batch(
+        &mut self,
+        partition_ids: &[PartitionId],
+    ) -> Result<Vec<PartitionStatistics>> {
+        let stage = self.stage();
+
+        Ok(partition_ids
+            .iter()
+            .filter_map(|id| stage.partition_statistics.get(id))
+            .cloned()
+            .collect())
+    }
 }
 
 #[async_trait]
 impl ParquetFileRepo for MemTxn {
     async fn create(&mut self, parquet_file_params: ParquetFileParams) -> Result<ParquetFile> {
-        create_parquet_file(self.stage(), parquet_file_params).await
+        let stage = self.stage();
+        let file = create_parquet_file(stage, parquet_file_params).await?;
+        add_partition_statistics(stage, &file, PartitionColumnStatisticsUpdate::Unknown)?;
+        Ok(file)
+    }
+
+    async fn create_with_statistics(
+        &mut self,
+        parquet_file_params: ParquetFileParams,
+        column_statistics: &[PartitionColumnStatistics],
+    ) -> Result<ParquetFile> {
+        let stage = self.stage();
+        let file = create_parquet_file(stage, parquet_file_params).await?;
+        add_partition_statistics(
+            stage,
+            &file,
+            PartitionColumnStatisticsUpdate::Merge {
+                row_count: file.row_count,
+                columns: column_statistics,
+            },
+        )?;
+        Ok(file)
     }
 
     async fn list_all(&mut self) -> Result<Vec<ParquetFile>> {
@@ -1013,6 +1071,26 @@ impl ParquetFileRepo for MemTxn {
             ids.push(res.id);
         }
 
+        // Refresh the statistics of all partitions with changed files.
+        let changed = delete
+            .iter()
+            .chain(upgrade)
+            .chain(&ids)
+            .collect::<HashSet<_>>();
+        let partitions = stage
+            .parquet_files
+            .iter()
+            .filter(|f| changed.contains(&f.id))
+            .map(|f| f.partition_id.clone())
+            .collect::<HashSet<_>>();
+        for partition_id in partitions {
+            refresh_partition_statistics(
+                &mut stage,
+                &partition_id,
+                PartitionColumnStatisticsUpdate::Retain,
+            )?;
+        }
+
         *self.inner = stage;
 
         Ok(ids)
@@ -1065,6 +1143,67 @@ async fn create_parquet_file(
     Ok(stage.parquet_files.last().unwrap().clone())
 }
 
+/// Fold the newly created `file`, with content described by `update`, into
+/// the [`PartitionStatistics`] of its partition.
+fn add_partition_statistics(
+    stage: &mut MemCollections,
+    file: &ParquetFile,
+    update: PartitionColumnStatisticsUpdate<'_>,
+) -> Result<()> {
+    let id = stage
+        .partitions
+        .iter()
+        .find(|p| p.transition_partition_id() == file.partition_id)
+        .ok_or_else(|| Error::PartitionNotFound {
+            id: file.partition_id.clone(),
+        })?
+        .id;
+
+    let previous = stage.partition_statistics.remove(&id);
+    let stats =
+        PartitionStatistics::add_file(id, previous, PartitionFileTotals::from(file), update);
+    stage.partition_statistics.insert(id, stats);
+
+    Ok(())
+}
+
+/// Recompute the [`PartitionStatistics`] of `partition_id` after a change
+/// described by `update` was made to its parquet files.
+fn refresh_partition_statistics(
+    stage: &mut MemCollections,
+    partition_id: &TransitionPartitionId,
+    update: PartitionColumnStatisticsUpdate<'_>,
+) -> Result<()> {
+    let id = stage
+        .partitions
+        .iter()
+        .find(|p| p.transition_partition_id() == *partition_id)
+        .ok_or_else(|| Error::PartitionNotFound {
+            id: partition_id.clone(),
+        })?
+        .id;
+
+    let totals = stage
+        .parquet_files
+        .iter()
+        .filter(|f| f.partition_id == *partition_id && f.to_delete.is_none())
+        .fold(PartitionFileTotals::default(), |acc, f| {
+            PartitionFileTotals {
+                file_count: acc.file_count + 1,
+                row_count: acc.row_count + f.row_count,
+                min_time: Some(acc.min_time.map_or(f.min_time, |t| t.min(f.min_time))),
+                max_time: Some(acc.max_time.map_or(f.max_time, |t| t.max(f.max_time))),
+                max_parquet_file_id: acc.max_parquet_file_id.max(Some(f.id)),
+            }
+        });
+
+    let previous = stage.partition_statistics.remove(&id);
+    let stats = PartitionStatistics::apply(id, previous, totals, update);
+    stage.partition_statistics.insert(id, stats);
+
+    Ok(())
+}
+
 async fn flag_for_delete(
     stage: &mut MemCollections,
     id: ParquetFileId,
@@ -9,8 +9,9 @@ use data_types::{
     partition_template::{NamespacePartitionTemplateOverride, TablePartitionTemplateOverride},
     Column, ColumnType, CompactionLevel, MaxColumnsPerTable, MaxTables, Namespace, NamespaceId,
     NamespaceName, NamespaceServiceProtectionLimitsOverride, ParquetFile, ParquetFileId,
-    ParquetFileParams, Partition, PartitionHashId, PartitionId, PartitionKey, SkippedCompaction,
-    SortedColumnSet, Table, TableId, Timestamp, TransitionPartitionId,
+    ParquetFileParams, Partition, PartitionColumnStatistics, PartitionHashId, PartitionId,
+    PartitionKey, PartitionStatistics, SkippedCompaction, SortedColumnSet, Table, TableId,
+    Timestamp, TransitionPartitionId,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use metric::{DurationHistogram, Metric};
@@ -141,6 +142,7 @@ decorate!(
         "namespace_soft_delete" = soft_delete(&mut self, name: &str) -> Result<()>;
         "namespace_update_table_limit" = update_table_limit(&mut self, name: &str, new_max: MaxTables) -> Result<Namespace>;
         "namespace_update_column_limit" = update_column_limit(&mut self, name: &str, new_max: MaxColumnsPerTable) -> Result<Namespace>;
+        "namespace_update_query_quota" = update_query_quota(&mut self, name: &str, max_concurrent_queries: Option<i32>, max_query_memory_bytes: Option<i64>) -> Result<Namespace>;
     ]
 );
 
@@ -184,6 +186,7 @@ decorate!(
         "partition_partitions_new_file_between" = partitions_new_file_between(&mut self, minimum_time: Timestamp, maximum_time: Option<Timestamp>) -> Result<Vec<PartitionId>>;
         "partition_get_in_skipped_compactions" = get_in_skipped_compactions(&mut self, partition_ids: &[PartitionId]) -> Result<Vec<SkippedCompaction>>;
         "partition_list_old_style" = list_old_style(&mut self) -> Result<Vec<Partition>>;
+        "partition_get_statistics_batch" = get_statistics_batch(&mut self, partition_ids: &[PartitionId]) -> Result<Vec<PartitionStatistics>>;
     ]
 );
 
@@ -191,6 +194,7 @@ decorate!(
     impl_trait = ParquetFileRepo,
     methods = [
         "parquet_create" = create(&mut self, parquet_file_params: ParquetFileParams) -> Result<ParquetFile>;
+        "parquet_create_with_statistics" = create_with_statistics(&mut self, parquet_file_params: ParquetFileParams, column_statistics: &[PartitionColumnStatistics]) -> Result<ParquetFile>;
         "parquet_list_all" = list_all(&mut self) -> Result<Vec<ParquetFile>>;
         "parquet_flag_for_delete_by_retention" = flag_for_delete_by_retention(&mut self) -> Result<Vec<ParquetFileId>>;
         "parquet_list_by_namespace_not_to_delete" = list_by_namespace_not_to_delete(&mut self, namespace_id: NamespaceId) -> Result<Vec<ParquetFile>>;
@@ -0,0 +1,98 @@
+//! Row types for the `partition_statistics` table, shared by the SQL catalog
+//! implementations.
+
+use data_types::{
+    ColumnId, ParquetFileId, PartitionColumnStatistics, PartitionFileTotals, PartitionId,
+    PartitionStatistics, Timestamp,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+
+/// A row of the `partition_statistics` table.
+#[derive(Debug, sqlx::FromRow)]
+pub(crate) struct PartitionStatisticsPod {
+    partition_id: PartitionId,
+    row_count: i64,
+    min_time: Option<Timestamp>,
+    max_time: Option<Timestamp>,
+    max_parquet_file_id: Option<ParquetFileId>,
+    complete: bool,
+    column_statistics: Json<Vec<ColumnStatisticsPod>>,
+}
+
+/// The JSON encoding of a [`PartitionColumnStatistics`].
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ColumnStatisticsPod {
+    column_id: i64,
+    null_count: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    min: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    values: Option<Vec<String>>,
+}
+
+impl From<PartitionStatisticsPod> for PartitionStatistics {
+    fn from(value: PartitionStatisticsPod) -> Self {
+        Self {
+            partition_id: value.partition_id,
+            row_count: value.row_count,
+            min_time: value.min_time,
+            max_time: value.max_time,
+            max_parquet_file_id: value.max_parquet_file_id,
+            complete: value.complete,
+            columns: value
+                .column_statistics
+                .0
+                .into_iter()
+                .map(|c| PartitionColumnStatistics {
+                    column_id: ColumnId::new(c.column_id),
+                    null_count: c.null_count,
+                    min: c.min,
+                    max: c.max,
+                    values: c.values,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Encode the column statistics of `stats` for storage.
+pub(crate) fn encode_columns(stats: &PartitionStatistics) -> Json<Vec<ColumnStatisticsPod>> {
+    Json(
+        stats
+            .columns
+            .iter()
+            .map(|c| ColumnStatisticsPod {
+                column_id: c.column_id.get(),
+                null_count: c.null_count,
+                min: c.min.clone(),
+                max: c.max.clone(),
+                values: c.values.clone(),
+            })
+            .collect(),
+    )
+}
+
+/// The aggregate of the live parquet files in a partition.
+#[derive(Debug, sqlx::FromRow)]
+pub(crate) struct PartitionFileTotalsPod {
+    file_count: i64,
+    row_count: i64,
+    min_time: Option<Timestamp>,
+    max_time: Option<Timestamp>,
+    max_parquet_file_id: Option<ParquetFileId>,
+}
+
+impl From<PartitionFileTotalsPod> for PartitionFileTotals {
+    fn from(value: PartitionFileTotalsPod) -> Self {
+        Self {
+            file_count: value.file_count,
+            row_count: value.row_count,
+            min_time: value.min_time,
+            max_time: value.max_time,
+            max_parquet_file_id: value.max_parquet_file_id,
+        }
+    }
+}
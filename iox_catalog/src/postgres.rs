@@ -12,6 +12,7 @@ use crate::{
     },
     metrics::MetricDecorator,
     migrate::IOxMigrator,
+    partition_statistics::{encode_columns, PartitionFileTotalsPod, PartitionStatisticsPod},
 };
 use async_trait::async_trait;
 use data_types::{
@@ -20,8 +21,10 @@ use data_types::{
     },
     Column, ColumnType, CompactionLevel, MaxColumnsPerTable, MaxTables, Namespace, NamespaceId,
     NamespaceName, NamespaceServiceProtectionLimitsOverride, ParquetFile, ParquetFileId,
-    ParquetFileParams, Partition, PartitionHashId, PartitionId, PartitionKey, SkippedCompaction,
-    SortedColumnSet, Table, TableId, Timestamp, TransitionPartitionId,
+    ParquetFileParams, Partition, PartitionColumnStatistics, PartitionColumnStatisticsUpdate,
+    PartitionFileTotals, PartitionHashId, PartitionId, PartitionKey, PartitionStatistics,
+    SkippedCompaction, SortedColumnSet, Table, TableId, Timestamp, TransitionPartitionId,
+    MAX_PARTITION_TAG_VALUES,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use metric::{Attributes, Instrument, MetricKind};
@@ -31,8 +34,8 @@ use parking_lot::{RwLock, RwLockWriteGuard};
 use snafu::prelude::*;
 use sqlx::{
     postgres::{PgConnectOptions, PgPoolOptions},
-    types::Uuid,
-    Acquire, ConnectOptions, Executor, Postgres, Row,
+    types::{Json, Uuid},
+    Acquire, ConnectOptions, Executor, PgConnection, Postgres, Row,
 };
 use sqlx_hotswap_pool::HotSwapPool;
 use std::{
@@ -715,7 +718,7 @@ INSERT INTO namespace (
 )
 VALUES ( $1, $2, $3, $4, $5, $6, $7 )
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          max_concurrent_queries, max_query_memory_bytes, partition_template;
             "#,
         )
         .bind(name.as_str()) // $1
@@ -746,7 +749,7 @@ RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, dele
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       max_concurrent_queries, max_query_memory_bytes, partition_template
 FROM namespace
 WHERE {v};
                 "#,
@@ -770,7 +773,7 @@ WHERE {v};
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       max_concurrent_queries, max_query_memory_bytes, partition_template
 FROM namespace
 WHERE id=$1 AND {v};
                 "#,
@@ -800,7 +803,7 @@ WHERE id=$1 AND {v};
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       max_concurrent_queries, max_query_memory_bytes, partition_template
 FROM namespace
 WHERE name=$1 AND {v};
                 "#,
@@ -841,7 +844,7 @@ UPDATE namespace
 SET max_tables = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          max_concurrent_queries, max_query_memory_bytes, partition_template;
         "#,
         )
         .bind(new_max)
@@ -870,7 +873,7 @@ UPDATE namespace
 SET max_columns_per_table = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          max_concurrent_queries, max_query_memory_bytes, partition_template;
         "#,
         )
         .bind(new_max)
@@ -899,7 +902,7 @@ UPDATE namespace
 SET retention_period_ns = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          max_concurrent_queries, max_query_memory_bytes, partition_template;
         "#,
         )
         .bind(retention_period_ns) // $1
@@ -916,6 +919,37 @@ RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, dele
 
         Ok(namespace)
     }
+
+    async fn update_query_quota(
+        &mut self,
+        name: &str,
+        max_concurrent_queries: Option<i32>,
+        max_query_memory_bytes: Option<i64>,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET max_concurrent_queries = $1, max_query_memory_bytes = $2
+WHERE name = $3
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          max_concurrent_queries, max_query_memory_bytes, partition_template;
+        "#,
+        )
+        .bind(max_concurrent_queries) // $1
+        .bind(max_query_memory_bytes) // $2
+        .bind(name) // $3
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
 }
 
 #[async_trait]
@@ -1564,14 +1598,55 @@ ORDER BY id DESC;"#,
         .await
         .map_err(|e| Error::SqlxError { source: e })
     }
+
+    async fn get_statistics_batch(
+        &mut self,
+        partition_ids: &[PartitionId],
+    ) -> Result<Vec<PartitionStatistics>> {
+        let ids: Vec<_> = partition_ids.iter().map(|p| p.get()).collect();
+
+        Ok(sqlx::query_as::<_, PartitionStatisticsPod>(
+            r#"
+SELECT partition_id, row_count, min_time, max_time, max_parquet_file_id, complete,
+       column_statistics
+FROM partition_statistics
+WHERE partition_id = ANY($1);
+        "#,
+        )
+        .bind(&ids[..]) // $1
+        .fetch_all(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?
+        .into_iter()
+        .map(Into::into)
+        .collect())
+    }
 }
 
 #[async_trait]
 impl ParquetFileRepo for PostgresTxn {
     async fn create(&mut self, parquet_file_params: ParquetFileParams) -> Result<ParquetFile> {
-        let executor = &mut self.inner;
-        let id = create_parquet_file(executor, &parquet_file_params).await?;
-        Ok(ParquetFile::from_params(parquet_file_params, id))
+        self.create_and_add_statistics(
+            parquet_file_params,
+            PartitionColumnStatisticsUpdate::Unknown,
+        )
+        .await
+    }
+
+    async fn create_with_statistics(
+        &mut self,
+        parquet_file_params: ParquetFileParams,
+        column_statistics: &[PartitionColumnStatistics],
+    ) -> Result<ParquetFile> {
+        let row_count = parquet_file_params.row_count;
+        self.create_and_add_statistics(
+            parquet_file_params,
+            PartitionColumnStatisticsUpdate::Merge {
+                row_count,
+                columns: column_statistics,
+            },
+        )
+        .await
     }
 
     async fn list_all(&mut self) -> Result<Vec<ParquetFile>> {
@@ -1803,6 +1878,43 @@ WHERE object_store_id = ANY($1);
             ids.push(id);
         }
 
+        // Refresh the statistics of all partitions with changed files, locking
+        // their statistics rows (in a consistent order) against concurrent
+        // commits. Every partition with files has a statistics row, as it is
+        // created along with the partition's first file.
+        let changed: Vec<_> = delete.iter().chain(upgrade).chain(&ids).copied().collect();
+        let partitions = sqlx::query(
+            r#"
+SELECT partition.id, partition.hash_id
+FROM partition
+INNER JOIN partition_statistics
+ON partition_statistics.partition_id = partition.id
+WHERE partition.id IN (
+    SELECT partition.id
+    FROM parquet_file
+    INNER JOIN partition
+    ON partition.id = parquet_file.partition_id
+        OR partition.hash_id = parquet_file.partition_hash_id
+    WHERE parquet_file.id = ANY($1)
+)
+ORDER BY partition.id
+FOR UPDATE OF partition_statistics;
+        "#,
+        )
+        .bind(&changed[..]) // $1
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+        for row in partitions {
+            refresh_partition_statistics(
+                &mut tx,
+                row.get("id"),
+                row.get::<Option<PartitionHashId>, _>("hash_id").as_ref(),
+                PartitionColumnStatisticsUpdate::Retain,
+            )
+            .await?;
+        }
+
         tx.commit()
             .await
             .map_err(|source| Error::FailedToCommit { source })?;
@@ -1810,6 +1922,213 @@ WHERE object_store_id = ANY($1);
     }
 }
 
+impl PostgresTxn {
+    /// Create a parquet file and fold it into the statistics of its partition
+    /// in a single transaction.
+    async fn create_and_add_statistics(
+        &mut self,
+        parquet_file_params: ParquetFileParams,
+        update: PartitionColumnStatisticsUpdate<'_>,
+    ) -> Result<ParquetFile> {
+        let mut tx = self
+            .inner
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::StartTransaction { source: e })?;
+
+        let id = create_parquet_file(&mut *tx, &parquet_file_params).await?;
+        let file = ParquetFile::from_params(parquet_file_params, id);
+        add_partition_statistics(&mut tx, &file, update).await?;
+
+        tx.commit()
+            .await
+            .map_err(|source| Error::FailedToCommit { source })?;
+
+        Ok(file)
+    }
+}
+
+/// Fold the newly created `file`, with content described by `update`, into
+/// the [`PartitionStatistics`] of its partition.
+///
+/// This is the single-statement equivalent of [`PartitionStatistics::add_file()`]:
+/// the upsert merges the file into the existing statistics row without
+/// reading the partition's other files, and without locking the partition -
+/// concurrent commits to the same partition are serialised by the row lock
+/// the upsert takes on the statistics row.
+async fn add_partition_statistics(
+    conn: &mut PgConnection,
+    file: &ParquetFile,
+    update: PartitionColumnStatisticsUpdate<'_>,
+) -> Result<()> {
+    let (id, hash_id) = match &file.partition_id {
+        TransitionPartitionId::Deterministic(hash_id) => (None, Some(hash_id)),
+        TransitionPartitionId::Deprecated(id) => (Some(*id), None),
+    };
+
+    // The statistics of a partition holding only this file. The partition ID
+    // is resolved by the query below.
+    let stats = PartitionStatistics::add_file(
+        id.unwrap_or(PartitionId::new(0)),
+        None,
+        PartitionFileTotals::from(file),
+        update,
+    );
+
+    // Column statistics are merged as in PartitionStatistics::merge_columns():
+    // a column missing from one side is NULL for all of that side's rows, tag
+    // bounds and values compare bytewise, and values are only retained while
+    // known on both sides and within MAX_PARTITION_TAG_VALUES. A file of
+    // unknown content (excluded.complete is false) retains the existing
+    // column statistics.
+    let res = sqlx::query(
+        r#"
+INSERT INTO partition_statistics (
+    partition_id, row_count, min_time, max_time, max_parquet_file_id, complete,
+    column_statistics )
+SELECT id, $3, $4, $5, $6, $7, $8
+FROM partition
+WHERE id = $1 OR hash_id = $2
+ON CONFLICT (partition_id) DO UPDATE
+SET row_count = partition_statistics.row_count + excluded.row_count,
+    min_time = LEAST(partition_statistics.min_time, excluded.min_time),
+    max_time = GREATEST(partition_statistics.max_time, excluded.max_time),
+    max_parquet_file_id = GREATEST(
+        partition_statistics.max_parquet_file_id, excluded.max_parquet_file_id),
+    complete = partition_statistics.complete AND excluded.complete,
+    column_statistics = CASE
+        WHEN NOT excluded.complete THEN partition_statistics.column_statistics
+        ELSE (
+            SELECT COALESCE(jsonb_agg(jsonb_strip_nulls(jsonb_build_object(
+                'column_id', COALESCE(o.column_id, n.column_id),
+                'null_count', COALESCE(o.null_count, partition_statistics.row_count)
+                    + COALESCE(n.null_count, excluded.row_count),
+                'min', LEAST(o.min, n.min),
+                'max', GREATEST(o.max, n.max),
+                'values', CASE
+                    WHEN o.column_id IS NULL THEN n.values
+                    WHEN n.column_id IS NULL THEN o.values
+                    WHEN o.values IS NULL OR n.values IS NULL THEN NULL
+                    ELSE (
+                        SELECT CASE
+                            WHEN COUNT(*) <= $9 THEN COALESCE(jsonb_agg(v ORDER BY v), '[]')
+                        END
+                        FROM (
+                            SELECT DISTINCT value COLLATE "C" AS v
+                            FROM jsonb_array_elements_text(o.values || n.values)
+                        ) AS d
+                    )
+                END
+            )) ORDER BY COALESCE(o.column_id, n.column_id)), '[]')
+            FROM (
+                SELECT (c->>'column_id')::BIGINT AS column_id,
+                       (c->>'null_count')::BIGINT AS null_count,
+                       (c->>'min') COLLATE "C" AS min, (c->>'max') COLLATE "C" AS max,
+                       c->'values' AS values
+                FROM jsonb_array_elements(partition_statistics.column_statistics) AS c
+            ) AS o
+            FULL OUTER JOIN (
+                SELECT (c->>'column_id')::BIGINT AS column_id,
+                       (c->>'null_count')::BIGINT AS null_count,
+                       (c->>'min') COLLATE "C" AS min, (c->>'max') COLLATE "C" AS max,
+                       c->'values' AS values
+                FROM jsonb_array_elements(excluded.column_statistics) AS c
+            ) AS n
+            ON o.column_id = n.column_id
+        )
+    END;
+        "#,
+    )
+    .bind(id) // $1
+    .bind(hash_id) // $2
+    .bind(stats.row_count) // $3
+    .bind(stats.min_time) // $4
+    .bind(stats.max_time) // $5
+    .bind(stats.max_parquet_file_id) // $6
+    .bind(stats.complete) // $7
+    .bind(encode_columns(&stats)) // $8
+    .bind(MAX_PARTITION_TAG_VALUES as i64) // $9
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| Error::SqlxError { source: e })?;
+
+    // A missing partition fails the file insert with a foreign key violation
+    // for old-style partition IDs, but not for hash IDs.
+    if res.rows_affected() == 0 {
+        return Err(Error::PartitionNotFound {
+            id: file.partition_id.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Recompute the [`PartitionStatistics`] of the partition identified by `id`
+/// and `hash_id` after a change described by `update` was made to its parquet
+/// files.
+async fn refresh_partition_statistics(
+    conn: &mut PgConnection,
+    id: PartitionId,
+    hash_id: Option<&PartitionHashId>,
+    update: PartitionColumnStatisticsUpdate<'_>,
+) -> Result<()> {
+    let totals = sqlx::query_as::<_, PartitionFileTotalsPod>(
+        r#"
+SELECT COUNT(*) AS file_count, COALESCE(SUM(row_count), 0)::BIGINT AS row_count,
+       MIN(min_time) AS min_time, MAX(max_time) AS max_time, MAX(id) AS max_parquet_file_id
+FROM parquet_file
+WHERE (partition_id = $1 OR partition_hash_id = $2)
+  AND to_delete IS NULL;
+        "#,
+    )
+    .bind(id) // $1
+    .bind(hash_id) // $2
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| Error::SqlxError { source: e })?;
+
+    let previous = sqlx::query_as::<_, PartitionStatisticsPod>(
+        r#"
+SELECT partition_id, row_count, min_time, max_time, max_parquet_file_id, complete,
+       column_statistics
+FROM partition_statistics
+WHERE partition_id = $1;
+        "#,
+    )
+    .bind(id) // $1
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| Error::SqlxError { source: e })?;
+
+    let stats = PartitionStatistics::apply(id, previous.map(Into::into), totals.into(), update);
+
+    sqlx::query(
+        r#"
+INSERT INTO partition_statistics (
+    partition_id, row_count, min_time, max_time, max_parquet_file_id, complete,
+    column_statistics )
+VALUES ( $1, $2, $3, $4, $5, $6, $7 )
+ON CONFLICT (partition_id) DO UPDATE
+SET row_count = excluded.row_count, min_time = excluded.min_time,
+    max_time = excluded.max_time, max_parquet_file_id = excluded.max_parquet_file_id,
+    complete = excluded.complete, column_statistics = excluded.column_statistics;
+        "#,
+    )
+    .bind(id) // $1
+    .bind(stats.row_count) // $2
+    .bind(stats.min_time) // $3
+    .bind(stats.max_time) // $4
+    .bind(stats.max_parquet_file_id) // $5
+    .bind(stats.complete) // $6
+    .bind(encode_columns(&stats)) // $7
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| Error::SqlxError { source: e })?;
+
+    Ok(())
+}
+
 // The following three functions are helpers to the create_upgrade_delete method.
 // They are also used by the respective create/flag_for_delete/update_compaction_level methods.
 async fn create_parquet_file<'q, E>(
@@ -2656,7 +2975,7 @@ INSERT INTO namespace (
 )
 VALUES ( $1, $2, $3, $4, NULL )
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          max_concurrent_queries, max_query_memory_bytes, partition_template;
             "#,
         )
         .bind(namespace_name) // $1
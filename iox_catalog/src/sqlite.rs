@@ -11,6 +11,7 @@ use crate::{
         TRANSITION_SHARD_ID, TRANSITION_SHARD_INDEX,
     },
     metrics::MetricDecorator,
+    partition_statistics::{encode_columns, PartitionFileTotalsPod, PartitionStatisticsPod},
 };
 use async_trait::async_trait;
 use data_types::{
@@ -19,8 +20,10 @@ use data_types::{
     },
     Column, ColumnId, ColumnSet, ColumnType, CompactionLevel, MaxColumnsPerTable, MaxTables,
     Namespace, NamespaceId, NamespaceName, NamespaceServiceProtectionLimitsOverride, ParquetFile,
-    ParquetFileId, ParquetFileParams, Partition, PartitionHashId, PartitionId, PartitionKey,
-    SkippedCompaction, SortedColumnSet, Table, TableId, Timestamp, TransitionPartitionId,
+    ParquetFileId, ParquetFileParams, Partition, PartitionColumnStatistics,
+    PartitionColumnStatisticsUpdate, PartitionFileTotals, PartitionHashId, PartitionId,
+    PartitionKey, PartitionStatistics, SkippedCompaction, SortedColumnSet, Table, TableId,
+    Timestamp, TransitionPartitionId,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use metric::Registry;
@@ -32,7 +35,7 @@ use sqlx::{
     migrate::Migrator,
     sqlite::{SqliteConnectOptions, SqliteRow},
     types::{Json, Uuid},
-    Executor, Pool, Row, Sqlite, SqlitePool,
+    Executor, Pool, Row, Sqlite, SqliteConnection, SqlitePool,
 };
 use std::{
     collections::{HashMap, HashSet},
@@ -282,7 +285,7 @@ impl NamespaceRepo for SqliteTxn {
 INSERT INTO namespace ( name, topic_id, query_pool_id, retention_period_ns, max_tables, max_columns_per_table, partition_template )
 VALUES ( $1, $2, $3, $4, $5, $6, $7 )
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          max_concurrent_queries, max_query_memory_bytes, partition_template;
             "#,
         )
         .bind(name.as_str()) // $1
@@ -313,7 +316,7 @@ RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, dele
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       max_concurrent_queries, max_query_memory_bytes, partition_template
 FROM namespace
 WHERE {v};
                 "#,
@@ -337,7 +340,7 @@ WHERE {v};
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       max_concurrent_queries, max_query_memory_bytes, partition_template
 FROM namespace
 WHERE id=$1 AND {v};
                 "#,
@@ -367,7 +370,7 @@ WHERE id=$1 AND {v};
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       max_concurrent_queries, max_query_memory_bytes, partition_template
 FROM namespace
 WHERE name=$1 AND {v};
                 "#,
@@ -408,7 +411,7 @@ UPDATE namespace
 SET max_tables = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          max_concurrent_queries, max_query_memory_bytes, partition_template;
         "#,
         )
         .bind(new_max)
@@ -437,7 +440,7 @@ UPDATE namespace
 SET max_columns_per_table = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          max_concurrent_queries, max_query_memory_bytes, partition_template;
         "#,
         )
         .bind(new_max)
@@ -466,7 +469,7 @@ UPDATE namespace
 SET retention_period_ns = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          max_concurrent_queries, max_query_memory_bytes, partition_template;
             "#,
         )
         .bind(retention_period_ns) // $1
@@ -483,6 +486,37 @@ RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, dele
 
         Ok(namespace)
     }
+
+    async fn update_query_quota(
+        &mut self,
+        name: &str,
+        max_concurrent_queries: Option<i32>,
+        max_query_memory_bytes: Option<i64>,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET max_concurrent_queries = $1, max_query_memory_bytes = $2
+WHERE name = $3
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          max_concurrent_queries, max_query_memory_bytes, partition_template;
+            "#,
+        )
+        .bind(max_concurrent_queries) // $1
+        .bind(max_query_memory_bytes) // $2
+        .bind(name) // $3
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
 }
 
 /// [`TableRepo::create`] needs the ability to create some columns within the same transaction as
@@ -1253,6 +1287,29 @@ ORDER BY id DESC;
         .map(Into::into)
         .collect())
     }
+
+    async fn get_statistics_batch(
+        &mut self,
+        partition_ids: &[PartitionId],
+    ) -> Result<Vec<PartitionStatistics>> {
+        let ids: Vec<_> = partition_ids.iter().map(|p| p.get()).collect();
+
+        Ok(sqlx::query_as::<_, PartitionStatisticsPod>(
+            r#"
+SELECT partition_id, row_count, min_time, max_time, max_parquet_file_id, complete,
+       column_statistics
+FROM partition_statistics
+WHERE partition_id IN (SELECT value FROM json_each($1));
+        "#,
+        )
+        .bind(Json(&ids[..])) // $1
+        .fetch_all(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?
+        .into_iter()
+        .map(Into::into)
+        .collect())
+    }
 }
 
 fn from_column_set(v: &ColumnSet) -> Json<Vec<i64>> {
@@ -1306,8 +1363,27 @@ impl From<ParquetFilePod> for ParquetFile {
 #[async_trait]
 impl ParquetFileRepo for SqliteTxn {
     async fn create(&mut self, parquet_file_params: ParquetFileParams) -> Result<ParquetFile> {
-        let executor = self.inner.get_mut();
-        create_parquet_file(executor, parquet_file_params).await
+        self.create_and_add_statistics(
+            parquet_file_params,
+            PartitionColumnStatisticsUpdate::Unknown,
+        )
+        .await
+    }
+
+    async fn create_with_statistics(
+        &mut self,
+        parquet_file_params: ParquetFileParams,
+        column_statistics: &[PartitionColumnStatistics],
+    ) -> Result<ParquetFile> {
+        let row_count = parquet_file_params.row_count;
+        self.create_and_add_statistics(
+            parquet_file_params,
+            PartitionColumnStatisticsUpdate::Merge {
+                row_count,
+                columns: column_statistics,
+            },
+        )
+        .await
     }
 
     async fn list_all(&mut self) -> Result<Vec<ParquetFile>> {
@@ -1562,6 +1638,37 @@ WHERE object_store_id IN ({v});",
             let res = create_parquet_file(&mut *tx, file.clone()).await?;
             ids.push(res.id);
         }
+
+        // Refresh the statistics of all partitions with changed files.
+        let changed: Vec<_> = delete
+            .iter()
+            .chain(upgrade)
+            .chain(&ids)
+            .map(|id| id.get())
+            .collect();
+        let partitions = sqlx::query(
+            r#"
+SELECT DISTINCT partition.id, partition.hash_id
+FROM parquet_file
+INNER JOIN partition
+ON partition.id = parquet_file.partition_id OR partition.hash_id = parquet_file.partition_hash_id
+WHERE parquet_file.id IN (SELECT value FROM json_each($1));
+        "#,
+        )
+        .bind(Json(&changed[..])) // $1
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+        for row in partitions {
+            refresh_partition_statistics(
+                &mut tx,
+                row.get("id"),
+                row.get::<Option<PartitionHashId>, _>("hash_id").as_ref(),
+                PartitionColumnStatisticsUpdate::Retain,
+            )
+            .await?;
+        }
+
         tx.commit()
             .await
             .map_err(|e| Error::FailedToCommit { source: e })?;
@@ -1570,6 +1677,135 @@ WHERE object_store_id IN ({v});",
     }
 }
 
+impl SqliteTxn {
+    /// Create a parquet file and fold it into the statistics of its partition
+    /// in a single transaction.
+    async fn create_and_add_statistics(
+        &mut self,
+        parquet_file_params: ParquetFileParams,
+        update: PartitionColumnStatisticsUpdate<'_>,
+    ) -> Result<ParquetFile> {
+        let mut tx = self
+            .inner
+            .get_mut()
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::StartTransaction { source: e })?;
+
+        let file = create_parquet_file(&mut *tx, parquet_file_params).await?;
+
+        let id = match &file.partition_id {
+            TransitionPartitionId::Deprecated(id) => *id,
+            TransitionPartitionId::Deterministic(hash_id) => sqlx::query_scalar::<_, PartitionId>(
+                r#"SELECT id FROM partition WHERE hash_id = $1;"#,
+            )
+            .bind(hash_id) // $1
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| Error::SqlxError { source: e })?
+            .ok_or_else(|| Error::PartitionNotFound {
+                id: file.partition_id.clone(),
+            })?,
+        };
+
+        // SQLite serialises writers, so the statistics can't change between
+        // reading and writing them.
+        let previous = read_partition_statistics(&mut tx, id).await?;
+        let stats =
+            PartitionStatistics::add_file(id, previous, PartitionFileTotals::from(&file), update);
+        write_partition_statistics(&mut tx, &stats).await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| Error::FailedToCommit { source: e })?;
+
+        Ok(file)
+    }
+}
+
+/// Recompute the [`PartitionStatistics`] of the partition identified by `id`
+/// and `hash_id` after a change described by `update` was made to its parquet
+/// files.
+async fn refresh_partition_statistics(
+    conn: &mut SqliteConnection,
+    id: PartitionId,
+    hash_id: Option<&PartitionHashId>,
+    update: PartitionColumnStatisticsUpdate<'_>,
+) -> Result<()> {
+    let totals = sqlx::query_as::<_, PartitionFileTotalsPod>(
+        r#"
+SELECT COUNT(*) AS file_count, COALESCE(SUM(row_count), 0) AS row_count,
+       MIN(min_time) AS min_time, MAX(max_time) AS max_time, MAX(id) AS max_parquet_file_id
+FROM parquet_file
+WHERE (partition_id = $1 OR partition_hash_id = $2)
+  AND to_delete IS NULL;
+        "#,
+    )
+    .bind(id) // $1
+    .bind(hash_id) // $2
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| Error::SqlxError { source: e })?;
+
+    let previous = read_partition_statistics(&mut *conn, id).await?;
+    let stats = PartitionStatistics::apply(id, previous, totals.into(), update);
+    write_partition_statistics(&mut *conn, &stats).await?;
+
+    Ok(())
+}
+
+/// Read the [`PartitionStatistics`] of the partition with `id`, if any.
+async fn read_partition_statistics(
+    conn: &mut SqliteConnection,
+    id: PartitionId,
+) -> Result<Option<PartitionStatistics>> {
+    Ok(sqlx::query_as::<_, PartitionStatisticsPod>(
+        r#"
+SELECT partition_id, row_count, min_time, max_time, max_parquet_file_id, complete,
+       column_statistics
+FROM partition_statistics
+WHERE partition_id = $1;
+        "#,
+    )
+    .bind(id) // $1
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| Error::SqlxError { source: e })?
+    .map(Into::into))
+}
+
+/// Insert or replace the statistics row of `stats.partition_id`.
+async fn write_partition_statistics(
+    conn: &mut SqliteConnection,
+    stats: &PartitionStatistics,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+INSERT INTO partition_statistics (
+    partition_id, row_count, min_time, max_time, max_parquet_file_id, complete,
+    column_statistics )
+VALUES ( $1, $2, $3, $4, $5, $6, $7 )
+ON CONFLICT (partition_id) DO UPDATE
+SET row_count = excluded.row_count, min_time = excluded.min_time,
+    max_time = excluded.max_time, max_parquet_file_id = excluded.max_parquet_file_id,
+    complete = excluded.complete, column_statistics = excluded.column_statistics;
+        "#,
+    )
+    .bind(stats.partition_id) // $1
+    .bind(stats.row_count) // $2
+    .bind(stats.min_time) // $3
+    .bind(stats.max_time) // $4
+    .bind(stats.max_parquet_file_id) // $5
+    .bind(stats.complete) // $6
+    .bind(encode_columns(stats)) // $7
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| Error::SqlxError { source: e })?;
+
+    Ok(())
+}
+
 // The following three functions are helpers to the create_upgrade_delete method.
 // They are also used by the respective create/flag_for_delete/update_compaction_level methods.
 async fn create_parquet_file<'q, E>(
@@ -2131,7 +2367,7 @@ INSERT INTO namespace (
 )
 VALUES ( $1, $2, $3, $4, NULL )
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          max_concurrent_queries, max_query_memory_bytes, partition_template;
             "#,
         )
         .bind(namespace_name) // $1
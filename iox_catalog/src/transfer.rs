@@ -0,0 +1,978 @@
+//! Copy the content of one catalog into another, e.g. to move an all-in-one deployment from SQLite
+//! to Postgres.
+//!
+//! The target catalog assigns its own IDs. Entities are created in the order of their source IDs,
+//! so a freshly set up target usually ends up with the same IDs, but gaps in the source sequences
+//! (or a target that was used before) lead to remapped IDs. The [`IdMap`] returned by [`transfer`]
+//! records all of them.
+//!
+//! Namespace, table and partition IDs are part of the object store location of a parquet file. Files
+//! whose location changed are reported as [`RelocatedFile`]s, and their objects MUST be copied to the
+//! new location before the target catalog is used.
+//!
+//! Only live parquet files are copied; files already marked for deletion are left to the garbage
+//! collector of the source deployment.
+//!
+//! The copy is not transactional. If it is interrupted, it can be resumed by running it again with
+//! `resume` set: rows that already exist in the target (matched by namespace, table, column and
+//! partition key, and parquet file object store ID) are reused instead of being created again.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use data_types::{
+    Column, ColumnId, ColumnSet, MaxTables, Namespace, NamespaceId, NamespaceName,
+    NamespaceServiceProtectionLimitsOverride, ParquetFile, ParquetFileParams, Partition,
+    PartitionColumnStatistics, PartitionHashId, PartitionId, PartitionStatistics,
+    SkippedCompaction, SortedColumnSet, Table, TableId, TransitionPartitionId,
+};
+use observability_deps::tracing::info;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::interface::{Catalog, Error, RepoCollection, SoftDeletedRows};
+
+/// Errors of [`transfer`] and [`verify`].
+#[derive(Debug, Error)]
+#[allow(missing_docs)]
+pub enum TransferError {
+    #[error("catalog error: {0}")]
+    Catalog(#[from] Error),
+
+    #[error(
+        "target catalog is not empty, it contains {0} namespace(s), resume the transfer to \
+         continue an interrupted one"
+    )]
+    TargetNotEmpty(usize),
+
+    #[error("invalid namespace name {name}: {reason}")]
+    InvalidNamespaceName { name: String, reason: String },
+
+    #[error("cannot set sort key of partition {0} in target catalog")]
+    SortKey(TransitionPartitionId),
+
+    #[error("{what} {id} references unknown {reference}")]
+    Dangling {
+        what: &'static str,
+        id: String,
+        reference: &'static str,
+    },
+
+    #[error("verification failed:\n{}", .0.join("\n"))]
+    Verification(Vec<String>),
+}
+
+/// Mapping of source catalog IDs to the target catalog.
+#[derive(Debug, Default)]
+pub struct IdMap {
+    /// Namespace IDs.
+    pub namespaces: HashMap<NamespaceId, NamespaceId>,
+
+    /// Table IDs.
+    pub tables: HashMap<TableId, TableId>,
+
+    /// Column IDs.
+    pub columns: HashMap<ColumnId, ColumnId>,
+
+    /// Partition IDs.
+    pub partitions: HashMap<PartitionId, PartitionId>,
+}
+
+impl IdMap {
+    /// Number of IDs that differ between source and target.
+    pub fn n_remapped(&self) -> usize {
+        fn count<K: PartialEq>(m: &HashMap<K, K>) -> usize {
+            m.iter().filter(|(a, b)| a != b).count()
+        }
+
+        count(&self.namespaces)
+            + count(&self.tables)
+            + count(&self.columns)
+            + count(&self.partitions)
+    }
+
+    fn column_set(&self, columns: &[ColumnId]) -> Option<ColumnSet> {
+        columns
+            .iter()
+            .map(|id| self.columns.get(id).copied())
+            .collect::<Option<Vec<_>>>()
+            .map(ColumnSet::new)
+    }
+
+    fn sorted_column_set(&self, columns: &[ColumnId]) -> Option<SortedColumnSet> {
+        columns
+            .iter()
+            .map(|id| self.columns.get(id).copied())
+            .collect::<Option<Vec<_>>>()
+            .map(SortedColumnSet::new)
+    }
+}
+
+/// A parquet file whose object store location differs between source and target.
+#[derive(Debug, Clone)]
+pub struct RelocatedFile {
+    /// The file in the source catalog.
+    pub source: ParquetFile,
+
+    /// The file in the target catalog.
+    pub target: ParquetFile,
+}
+
+/// Summary of a [`transfer`].
+#[derive(Debug, Default)]
+pub struct TransferReport {
+    /// Number of copied namespaces (including soft-deleted ones).
+    pub namespaces: usize,
+
+    /// Number of copied tables.
+    pub tables: usize,
+
+    /// Number of copied columns.
+    pub columns: usize,
+
+    /// Number of copied partitions.
+    pub partitions: usize,
+
+    /// Number of copied (live) parquet files.
+    pub parquet_files: usize,
+
+    /// Number of parquet files that already existed in the target when resuming.
+    pub existing_parquet_files: usize,
+
+    /// Number of copied skipped compaction records.
+    pub skipped_compactions: usize,
+
+    /// Source to target ID mapping.
+    pub ids: IdMap,
+
+    /// Files that must be copied to a new object store location.
+    pub relocated_files: Vec<RelocatedFile>,
+}
+
+/// Copy all namespaces, tables, columns, partitions (with their sort keys and statistics), live
+/// parquet files and skipped compactions from `source` into the empty `target` catalog.
+///
+/// If `resume` is set, the target may contain the rows copied by an earlier, interrupted call,
+/// which are kept.
+pub async fn transfer(
+    source: &dyn Catalog,
+    target: &dyn Catalog,
+    resume: bool,
+) -> Result<TransferReport, TransferError> {
+    let mut src = source.repositories().await;
+    let mut dst = target.repositories().await;
+
+    let existing = dst.namespaces().list(SoftDeletedRows::AllRows).await?;
+    if !existing.is_empty() && !resume {
+        return Err(TransferError::TargetNotEmpty(existing.len()));
+    }
+
+    let mut report = TransferReport::default();
+
+    let mut namespaces = src.namespaces().list(SoftDeletedRows::AllRows).await?;
+    namespaces.sort_by_key(|ns| ns.id);
+    for ns in namespaces {
+        transfer_namespace(src.as_mut(), dst.as_mut(), &ns, &mut report).await?;
+    }
+
+    let mut skipped = src.partitions().list_skipped_compactions().await?;
+    skipped.sort_by_key(|s| s.partition_id);
+    for s in skipped {
+        let partition_id =
+            *report
+                .ids
+                .partitions
+                .get(&s.partition_id)
+                .ok_or_else(|| TransferError::Dangling {
+                    what: "skipped compaction of partition",
+                    id: s.partition_id.to_string(),
+                    reference: "partition",
+                })?;
+        dst.partitions()
+            .record_skipped_compaction(
+                partition_id,
+                &s.reason,
+                s.num_files as usize,
+                s.limit_num_files as usize,
+                s.limit_num_files_first_in_partition as usize,
+                s.estimated_bytes as u64,
+                s.limit_bytes as u64,
+            )
+            .await?;
+        report.skipped_compactions += 1;
+    }
+
+    Ok(report)
+}
+
+async fn transfer_namespace(
+    src: &mut dyn RepoCollection,
+    dst: &mut dyn RepoCollection,
+    ns: &Namespace,
+    report: &mut TransferReport,
+) -> Result<(), TransferError> {
+    let name =
+        NamespaceName::new(ns.name.as_str()).map_err(|e| TransferError::InvalidNamespaceName {
+            name: ns.name.clone(),
+            reason: e.to_string(),
+        })?;
+
+    let mut tables = src.tables().list_by_namespace_id(ns.id).await?;
+    tables.sort_by_key(|t| t.id);
+
+    // the table limit may have been lowered below the number of existing tables, so it is raised
+    // until all tables are copied
+    let max_tables =
+        MaxTables::try_from(tables.len()).map_or(ns.max_tables, |n| n.max(ns.max_tables));
+    let new_ns = match dst
+        .namespaces()
+        .get_by_name(&ns.name, SoftDeletedRows::AllRows)
+        .await?
+    {
+        Some(existing) => {
+            if existing.max_tables != max_tables {
+                dst.namespaces()
+                    .update_table_limit(&ns.name, max_tables)
+                    .await?
+            } else {
+                existing
+            }
+        }
+        None => {
+            dst.namespaces()
+                .create(
+                    &name,
+                    Some(ns.partition_template.clone()),
+                    ns.retention_period_ns,
+                    Some(NamespaceServiceProtectionLimitsOverride {
+                        max_tables: Some(max_tables),
+                        max_columns_per_table: Some(ns.max_columns_per_table),
+                    }),
+                )
+                .await?
+        }
+    };
+    report.ids.namespaces.insert(ns.id, new_ns.id);
+    report.namespaces += 1;
+
+    if ns.max_concurrent_queries.is_some() || ns.max_query_memory_bytes.is_some() {
+        dst.namespaces()
+            .update_query_quota(
+                &ns.name,
+                ns.max_concurrent_queries,
+                ns.max_query_memory_bytes,
+            )
+            .await?;
+    }
+
+    for table in tables {
+        let new_table = match dst
+            .tables()
+            .get_by_namespace_and_name(new_ns.id, &table.name)
+            .await?
+        {
+            Some(existing) => existing,
+            None => {
+                dst.tables()
+                    .create(&table.name, table.partition_template.clone(), new_ns.id)
+                    .await?
+            }
+        };
+        report.ids.tables.insert(table.id, new_table.id);
+        report.tables += 1;
+
+        let mut columns = src.columns().list_by_table_id(table.id).await?;
+        columns.sort_by_key(|c| c.id);
+        let mut column_names = HashMap::with_capacity(columns.len());
+        for col in columns {
+            let new_col = dst
+                .columns()
+                .create_or_get_many_unchecked(
+                    new_table.id,
+                    HashMap::from([(col.name.as_str(), col.column_type)]),
+                )
+                .await?
+                .pop()
+                .expect("created one column");
+            report.ids.columns.insert(col.id, new_col.id);
+            column_names.insert(new_col.id, new_col.name);
+            report.columns += 1;
+        }
+
+        let mut partitions = src.partitions().list_by_table_id(table.id).await?;
+        partitions.sort_by_key(|p| p.id);
+
+        let statistics = src
+            .partitions()
+            .get_statistics_batch(&partitions.iter().map(|p| p.id).collect::<Vec<_>>())
+            .await?
+            .into_iter()
+            .filter(|s| s.complete)
+            .map(|s| (s.partition_id, s))
+            .collect::<HashMap<_, _>>();
+
+        let mut new_partitions = HashMap::with_capacity(partitions.len());
+        for p in partitions {
+            let mut new_p = dst
+                .partitions()
+                .create_or_get(p.partition_key.clone(), new_table.id)
+                .await?;
+
+            // a sort key set by an interrupted transfer is kept
+            if !p.sort_key_ids.is_empty() && new_p.sort_key_ids.is_empty() {
+                let dangling = || TransferError::Dangling {
+                    what: "sort key of partition",
+                    id: p.id.to_string(),
+                    reference: "column",
+                };
+                let sort_key_ids = report
+                    .ids
+                    .sorted_column_set(&p.sort_key_ids)
+                    .ok_or_else(dangling)?;
+                let sort_key = sort_key_ids
+                    .iter()
+                    .map(|id| column_names.get(id).map(String::as_str))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(dangling)?;
+                new_p = dst
+                    .partitions()
+                    .cas_sort_key(
+                        &new_p.transition_partition_id(),
+                        None,
+                        None,
+                        &sort_key,
+                        &sort_key_ids,
+                    )
+                    .await
+                    .map_err(|_| TransferError::SortKey(p.transition_partition_id()))?;
+            }
+
+            report.ids.partitions.insert(p.id, new_p.id);
+            report.partitions += 1;
+            new_partitions.insert(p.transition_partition_id(), (p.id, new_p));
+        }
+
+        let mut files = src
+            .parquet_files()
+            .list_by_table_not_to_delete(table.id)
+            .await?;
+        files.sort_by_key(|f| f.id);
+
+        // files copied by an interrupted transfer
+        let mut existing_files = dst
+            .parquet_files()
+            .list_by_table_not_to_delete(new_table.id)
+            .await?
+            .into_iter()
+            .map(|f| (f.object_store_id, f))
+            .collect::<HashMap<_, _>>();
+
+        // the per-column statistics of a partition are passed along with its first file only;
+        // later files contribute the same ranges and tag values (which is a no-op) and no NULLs
+        let existing_partitions = existing_files
+            .values()
+            .map(|f| &f.partition_id)
+            .collect::<HashSet<_>>();
+        let mut statistics_sent = new_partitions
+            .values()
+            .filter(|(_, new_p)| existing_partitions.contains(&new_p.transition_partition_id()))
+            .map(|(id, _)| *id)
+            .collect::<HashSet<_>>();
+
+        for file in files {
+            let (partition_id, new_p) =
+                new_partitions
+                    .get(&file.partition_id)
+                    .ok_or_else(|| TransferError::Dangling {
+                        what: "parquet file",
+                        id: file.id.to_string(),
+                        reference: "partition",
+                    })?;
+            let column_set =
+                report
+                    .ids
+                    .column_set(&file.column_set)
+                    .ok_or_else(|| TransferError::Dangling {
+                        what: "parquet file",
+                        id: file.id.to_string(),
+                        reference: "column",
+                    })?;
+
+            if let Some(new_file) = existing_files.remove(&file.object_store_id) {
+                report.existing_parquet_files += 1;
+                relocate(report, file, new_file);
+                continue;
+            }
+
+            let params = ParquetFileParams {
+                namespace_id: new_ns.id,
+                table_id: new_table.id,
+                partition_id: new_p.transition_partition_id(),
+                column_set,
+                ..file.clone().into()
+            };
+
+            let column_statistics = statistics.get(partition_id).map(|s| {
+                let first = statistics_sent.insert(*partition_id);
+                map_column_statistics(s, &report.ids, first)
+            });
+            let new_file = match column_statistics {
+                Some(column_statistics) => {
+                    dst.parquet_files()
+                        .create_with_statistics(params, &column_statistics)
+                        .await?
+                }
+                None => dst.parquet_files().create(params).await?,
+            };
+            report.parquet_files += 1;
+            relocate(report, file, new_file);
+        }
+    }
+
+    if max_tables != ns.max_tables {
+        dst.namespaces()
+            .update_table_limit(&ns.name, ns.max_tables)
+            .await?;
+    }
+    if ns.deleted_at.is_some() && new_ns.deleted_at.is_none() {
+        dst.namespaces().soft_delete(&ns.name).await?;
+    }
+
+    info!(
+        namespace = %ns.name,
+        source_id = %ns.id,
+        target_id = %new_ns.id,
+        "namespace copied"
+    );
+
+    Ok(())
+}
+
+/// Record `target` as a [`RelocatedFile`] if its object store location differs from `source`.
+fn relocate(report: &mut TransferReport, source: ParquetFile, target: ParquetFile) {
+    if (source.namespace_id, source.table_id, &source.partition_id)
+        != (target.namespace_id, target.table_id, &target.partition_id)
+    {
+        report
+            .relocated_files
+            .push(RelocatedFile { source, target });
+    }
+}
+
+fn map_column_statistics(
+    stats: &PartitionStatistics,
+    ids: &IdMap,
+    with_null_count: bool,
+) -> Vec<PartitionColumnStatistics> {
+    stats
+        .columns
+        .iter()
+        .filter_map(|c| {
+            Some(PartitionColumnStatistics {
+                column_id: *ids.columns.get(&c.column_id)?,
+                null_count: if with_null_count { c.null_count } else { 0 },
+                ..c.clone()
+            })
+        })
+        .collect()
+}
+
+/// Compare the content of `source` and `target` after a [`transfer`] with the given ID mapping.
+///
+/// Returns [`TransferError::Verification`] listing all differences.
+pub async fn verify(
+    source: &dyn Catalog,
+    target: &dyn Catalog,
+    ids: &IdMap,
+) -> Result<(), TransferError> {
+    let src = Snapshot::load(source).await?;
+    let dst = Snapshot::load(target).await?;
+
+    let mut mismatches = vec![];
+    let mut check = |what: String, equal: bool| {
+        if !equal {
+            mismatches.push(what);
+        }
+    };
+
+    check(
+        format!(
+            "namespace count: {} vs {}",
+            src.namespaces.len(),
+            dst.namespaces.len()
+        ),
+        src.namespaces.len() == dst.namespaces.len(),
+    );
+    for (id, ns) in &src.namespaces {
+        let Some(other) = ids.namespaces.get(id).and_then(|id| dst.namespaces.get(id)) else {
+            check(format!("namespace {} missing", ns.name), false);
+            continue;
+        };
+        check(
+            format!("namespace {} differs", ns.name),
+            ns.name == other.name
+                && ns.retention_period_ns == other.retention_period_ns
+                && ns.max_tables == other.max_tables
+                && ns.max_columns_per_table == other.max_columns_per_table
+                && ns.max_concurrent_queries == other.max_concurrent_queries
+                && ns.max_query_memory_bytes == other.max_query_memory_bytes
+                && ns.deleted_at.is_some() == other.deleted_at.is_some()
+                && ns.partition_template == other.partition_template,
+        );
+    }
+
+    check(
+        format!("table count: {} vs {}", src.tables.len(), dst.tables.len()),
+        src.tables.len() == dst.tables.len(),
+    );
+    for (id, t) in &src.tables {
+        let Some(other) = ids.tables.get(id).and_then(|id| dst.tables.get(id)) else {
+            check(format!("table {} ({id}) missing", t.name), false);
+            continue;
+        };
+        check(
+            format!("table {} ({id}) differs", t.name),
+            t.name == other.name
+                && ids.namespaces.get(&t.namespace_id) == Some(&other.namespace_id)
+                && t.partition_template == other.partition_template,
+        );
+    }
+
+    check(
+        format!(
+            "column count: {} vs {}",
+            src.columns.len(),
+            dst.columns.len()
+        ),
+        src.columns.len() == dst.columns.len(),
+    );
+    for (id, c) in &src.columns {
+        let Some(other) = ids.columns.get(id).and_then(|id| dst.columns.get(id)) else {
+            check(format!("column {} ({id}) missing", c.name), false);
+            continue;
+        };
+        check(
+            format!("column {} ({id}) differs", c.name),
+            c.name == other.name
+                && c.column_type == other.column_type
+                && ids.tables.get(&c.table_id) == Some(&other.table_id),
+        );
+    }
+
+    check(
+        format!(
+            "partition count: {} vs {}",
+            src.partitions.len(),
+            dst.partitions.len()
+        ),
+        src.partitions.len() == dst.partitions.len(),
+    );
+    for (id, p) in &src.partitions {
+        let Some(other) = ids.partitions.get(id).and_then(|id| dst.partitions.get(id)) else {
+            check(format!("partition {id} missing"), false);
+            continue;
+        };
+        check(
+            format!("partition {id} differs"),
+            p.partition_key == other.partition_key
+                && ids.tables.get(&p.table_id) == Some(&other.table_id)
+                && p.sort_key() == other.sort_key()
+                && ids.sorted_column_set(&p.sort_key_ids).as_ref() == Some(&other.sort_key_ids)
+                && other.hash_id()
+                    == Some(&PartitionHashId::new(other.table_id, &other.partition_key)),
+        );
+    }
+
+    check(
+        format!(
+            "parquet file count: {} vs {}",
+            src.parquet_files.len(),
+            dst.parquet_files.len()
+        ),
+        src.parquet_files.len() == dst.parquet_files.len(),
+    );
+    for (object_store_id, f) in &src.parquet_files {
+        let Some(other) = dst.parquet_files.get(object_store_id) else {
+            check(format!("parquet file {object_store_id} missing"), false);
+            continue;
+        };
+        let partition_matches = src
+            .partition_ids
+            .get(&f.partition_id)
+            .and_then(|id| ids.partitions.get(id))
+            .and_then(|id| dst.partitions.get(id))
+            .map(|p| p.transition_partition_id() == other.partition_id)
+            .unwrap_or_default();
+        check(
+            format!("parquet file {object_store_id} differs"),
+            partition_matches
+                && ids.namespaces.get(&f.namespace_id) == Some(&other.namespace_id)
+                && ids.tables.get(&f.table_id) == Some(&other.table_id)
+                && ids.column_set(&f.column_set).as_ref() == Some(&other.column_set)
+                && f.min_time == other.min_time
+                && f.max_time == other.max_time
+                && f.file_size_bytes == other.file_size_bytes
+                && f.row_count == other.row_count
+                && f.compaction_level == other.compaction_level
+                && f.created_at == other.created_at
+                && f.max_l0_created_at == other.max_l0_created_at,
+        );
+    }
+
+    check(
+        format!(
+            "skipped compaction count: {} vs {}",
+            src.skipped_compactions.len(),
+            dst.skipped_compactions.len()
+        ),
+        src.skipped_compactions.len() == dst.skipped_compactions.len(),
+    );
+    for s in &src.skipped_compactions {
+        let found = ids.partitions.get(&s.partition_id).map_or(false, |id| {
+            dst.skipped_compactions.iter().any(|other| {
+                other.partition_id == *id
+                    && other.reason == s.reason
+                    && other.num_files == s.num_files
+                    && other.estimated_bytes == s.estimated_bytes
+            })
+        });
+        check(
+            format!("skipped compaction of partition {} missing", s.partition_id),
+            found,
+        );
+    }
+
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(TransferError::Verification(mismatches))
+    }
+}
+
+/// The content of a catalog that [`transfer`] copies.
+#[derive(Debug)]
+struct Snapshot {
+    namespaces: BTreeMap<NamespaceId, Namespace>,
+    tables: BTreeMap<TableId, Table>,
+    columns: BTreeMap<ColumnId, Column>,
+    partitions: BTreeMap<PartitionId, Partition>,
+    partition_ids: HashMap<TransitionPartitionId, PartitionId>,
+    parquet_files: BTreeMap<Uuid, ParquetFile>,
+    skipped_compactions: Vec<SkippedCompaction>,
+}
+
+impl Snapshot {
+    async fn load(catalog: &dyn Catalog) -> Result<Self, Error> {
+        let mut repos = catalog.repositories().await;
+
+        let namespaces = repos
+            .namespaces()
+            .list(SoftDeletedRows::AllRows)
+            .await?
+            .into_iter()
+            .map(|ns| (ns.id, ns))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut tables = BTreeMap::new();
+        let mut columns = BTreeMap::new();
+        let mut partitions = BTreeMap::new();
+        let mut partition_ids = HashMap::new();
+        let mut parquet_files = BTreeMap::new();
+        for ns_id in namespaces.keys() {
+            for t in repos.tables().list_by_namespace_id(*ns_id).await? {
+                for c in repos.columns().list_by_table_id(t.id).await? {
+                    columns.insert(c.id, c);
+                }
+                for p in repos.partitions().list_by_table_id(t.id).await? {
+                    partition_ids.insert(p.transition_partition_id(), p.id);
+                    partitions.insert(p.id, p);
+                }
+                for f in repos
+                    .parquet_files()
+                    .list_by_table_not_to_delete(t.id)
+                    .await?
+                {
+                    parquet_files.insert(f.object_store_id, f);
+                }
+                tables.insert(t.id, t);
+            }
+        }
+
+        let skipped_compactions = repos.partitions().list_skipped_compactions().await?;
+
+        Ok(Self {
+            namespaces,
+            tables,
+            columns,
+            partitions,
+            partition_ids,
+            parquet_files,
+            skipped_compactions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use data_types::{ColumnType, CompactionLevel, PartitionKey, Timestamp};
+
+    use super::*;
+    use crate::{mem::MemCatalog, test_helpers::arbitrary_parquet_file_params};
+
+    #[tokio::test]
+    async fn test_transfer() {
+        let metrics = Arc::new(metric::Registry::default());
+        let source = MemCatalog::new(Arc::clone(&metrics));
+        let target = MemCatalog::new(Arc::clone(&metrics));
+
+        let mut repos = source.repositories().await;
+
+        // soft-deleted namespaces are copied as well
+        let gap = NamespaceName::new("gap").unwrap();
+        repos
+            .namespaces()
+            .create(&gap, None, None, None)
+            .await
+            .unwrap();
+        repos.namespaces().soft_delete("gap").await.unwrap();
+
+        let name = NamespaceName::new("ns").unwrap();
+        let ns = repos
+            .namespaces()
+            .create(&name, None, Some(42), None)
+            .await
+            .unwrap();
+        let ns = repos
+            .namespaces()
+            .update_query_quota("ns", Some(3), None)
+            .await
+            .unwrap();
+        let table = repos
+            .tables()
+            .create("t", Default::default(), ns.id)
+            .await
+            .unwrap();
+        let tag = repos
+            .columns()
+            .create_or_get("tag", table.id, ColumnType::Tag)
+            .await
+            .unwrap();
+        let time = repos
+            .columns()
+            .create_or_get("time", table.id, ColumnType::Time)
+            .await
+            .unwrap();
+        let partition = repos
+            .partitions()
+            .create_or_get(PartitionKey::from("p"), table.id)
+            .await
+            .unwrap();
+        let partition = repos
+            .partitions()
+            .cas_sort_key(
+                &partition.transition_partition_id(),
+                None,
+                None,
+                &["tag", "time"],
+                &SortedColumnSet::new([tag.id, time.id]),
+            )
+            .await
+            .unwrap();
+        let params = ParquetFileParams {
+            column_set: ColumnSet::new([tag.id, time.id]),
+            compaction_level: CompactionLevel::FileNonOverlapped,
+            created_at: Timestamp::new(7),
+            ..arbitrary_parquet_file_params(&ns, &table, &partition)
+        };
+        repos
+            .parquet_files()
+            .create_with_statistics(
+                params,
+                &[PartitionColumnStatistics {
+                    column_id: tag.id,
+                    null_count: 1,
+                    min: Some("a".into()),
+                    max: Some("b".into()),
+                    values: Some(vec!["a".into(), "b".into()]),
+                }],
+            )
+            .await
+            .unwrap();
+        repos
+            .partitions()
+            .record_skipped_compaction(partition.id, "too big", 1, 2, 3, 4, 5)
+            .await
+            .unwrap();
+        drop(repos);
+
+        let report = transfer(&source, &target, false).await.unwrap();
+        assert_eq!(report.namespaces, 2);
+        assert_eq!(report.tables, 1);
+        assert_eq!(report.columns, 2);
+        assert_eq!(report.partitions, 1);
+        assert_eq!(report.parquet_files, 1);
+        assert_eq!(report.skipped_compactions, 1);
+
+        verify(&source, &target, &report.ids).await.unwrap();
+
+        // the statistics are carried over
+        let new_partition_id = report.ids.partitions[&partition.id];
+        let stats = target
+            .repositories()
+            .await
+            .partitions()
+            .get_statistics_batch(&[new_partition_id])
+            .await
+            .unwrap();
+        assert!(stats[0].complete);
+        assert_eq!(stats[0].columns[0].null_count, 1);
+
+        // a non-empty target is refused
+        assert!(matches!(
+            transfer(&source, &target, false).await,
+            Err(TransferError::TargetNotEmpty(2))
+        ));
+
+        // resuming a complete transfer changes nothing
+        let report = transfer(&source, &target, true).await.unwrap();
+        assert_eq!(report.namespaces, 2);
+        assert_eq!(report.parquet_files, 0);
+        assert_eq!(report.existing_parquet_files, 1);
+        assert_eq!(report.skipped_compactions, 1);
+        verify(&source, &target, &report.ids).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_resume_interrupted_transfer() {
+        let metrics = Arc::new(metric::Registry::default());
+        let source = MemCatalog::new(Arc::clone(&metrics));
+        let target = MemCatalog::new(Arc::clone(&metrics));
+
+        let mut repos = source.repositories().await;
+        let name = NamespaceName::new("ns").unwrap();
+        let ns = repos
+            .namespaces()
+            .create(&name, None, None, None)
+            .await
+            .unwrap();
+        let mut files = vec![];
+        for table_name in ["a", "b"] {
+            let table = repos
+                .tables()
+                .create(table_name, Default::default(), ns.id)
+                .await
+                .unwrap();
+            let time = repos
+                .columns()
+                .create_or_get("time", table.id, ColumnType::Time)
+                .await
+                .unwrap();
+            let partition = repos
+                .partitions()
+                .create_or_get(PartitionKey::from("p"), table.id)
+                .await
+                .unwrap();
+            let params = ParquetFileParams {
+                column_set: ColumnSet::new([time.id]),
+                ..arbitrary_parquet_file_params(&ns, &table, &partition)
+            };
+            files.push(repos.parquet_files().create(params).await.unwrap());
+        }
+        // lowered below the number of tables
+        repos
+            .namespaces()
+            .update_table_limit("ns", MaxTables::try_from(1_usize).unwrap())
+            .await
+            .unwrap();
+        drop(repos);
+
+        // an interrupted transfer left the first table and its file, and the raised table limit,
+        // behind
+        let mut repos = target.repositories().await;
+        let new_ns = repos
+            .namespaces()
+            .create(
+                &name,
+                None,
+                None,
+                Some(NamespaceServiceProtectionLimitsOverride {
+                    max_tables: Some(MaxTables::try_from(2_usize).unwrap()),
+                    max_columns_per_table: None,
+                }),
+            )
+            .await
+            .unwrap();
+        let table = repos
+            .tables()
+            .create("a", Default::default(), new_ns.id)
+            .await
+            .unwrap();
+        let time = repos
+            .columns()
+            .create_or_get("time", table.id, ColumnType::Time)
+            .await
+            .unwrap();
+        let partition = repos
+            .partitions()
+            .create_or_get(PartitionKey::from("p"), table.id)
+            .await
+            .unwrap();
+        repos
+            .parquet_files()
+            .create(ParquetFileParams {
+                namespace_id: new_ns.id,
+                table_id: table.id,
+                partition_id: partition.transition_partition_id(),
+                column_set: ColumnSet::new([time.id]),
+                ..ParquetFileParams::from(files[0].clone())
+            })
+            .await
+            .unwrap();
+        drop(repos);
+
+        assert!(matches!(
+            transfer(&source, &target, false).await,
+            Err(TransferError::TargetNotEmpty(1))
+        ));
+
+        let report = transfer(&source, &target, true).await.unwrap();
+        assert_eq!(report.namespaces, 1);
+        assert_eq!(report.tables, 2);
+        assert_eq!(report.parquet_files, 1);
+        assert_eq!(report.existing_parquet_files, 1);
+        verify(&source, &target, &report.ids).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_verify_detects_differences() {
+        let metrics = Arc::new(metric::Registry::default());
+        let source = MemCatalog::new(Arc::clone(&metrics));
+        let target = MemCatalog::new(Arc::clone(&metrics));
+
+        let name = NamespaceName::new("ns").unwrap();
+        source
+            .repositories()
+            .await
+            .namespaces()
+            .create(&name, None, None, None)
+            .await
+            .unwrap();
+
+        let report = transfer(&source, &target, false).await.unwrap();
+        verify(&source, &target, &report.ids).await.unwrap();
+
+        target
+            .repositories()
+            .await
+            .namespaces()
+            .update_retention_period("ns", Some(1))
+            .await
+            .unwrap();
+        let err = verify(&source, &target, &report.ids).await.unwrap_err();
+        assert!(
+            matches!(&err, TransferError::Verification(m) if m == &["namespace ns differs"]),
+            "{err}"
+        );
+    }
+}
@@ -217,6 +217,20 @@ impl IOxSessionConfig {
         }
     }
 
+    /// Use the given memory pool instead of the executor-wide one.
+    ///
+    /// All other parts of the runtime (disk manager, caches, object stores) are shared with the
+    /// executor.
+    pub fn with_memory_pool(self, memory_pool: Arc<dyn MemoryPool>) -> Self {
+        let runtime = Arc::new(RuntimeEnv {
+            memory_pool,
+            disk_manager: Arc::clone(&self.runtime.disk_manager),
+            cache_manager: Arc::clone(&self.runtime.cache_manager),
+            object_store_registry: Arc::clone(&self.runtime.object_store_registry),
+        });
+        Self { runtime, ..self }
+    }
+
     /// Set the span context from which to create  distributed tracing spans for this query
     pub fn with_span_context(self, span_ctx: Option<SpanContext>) -> Self {
         Self { span_ctx, ..self }
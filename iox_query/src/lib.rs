@@ -24,17 +24,17 @@ use arrow::{
     record_batch::RecordBatch,
 };
 use async_trait::async_trait;
-use data_types::{ChunkId, ChunkOrder, TransitionPartitionId};
+use data_types::{ChunkId, ChunkOrder, TimestampMinMax, TransitionPartitionId};
 use datafusion::{
     error::DataFusionError,
     physical_plan::{SendableRecordBatchStream, Statistics},
     prelude::{Expr, SessionContext},
 };
-use exec::IOxSessionContext;
+use exec::{stringset::StringSet, IOxSessionContext};
 use once_cell::sync::Lazy;
 use parquet_file::storage::ParquetExecInput;
 use schema::{sort::SortKey, Projection, Schema};
-use std::{any::Any, fmt::Debug, sync::Arc};
+use std::{any::Any, collections::HashMap, fmt::Debug, sync::Arc};
 
 pub mod chunk_statistics;
 pub mod config;
@@ -96,6 +96,45 @@ pub trait QueryChunk: Debug + Send + Sync + 'static {
 
     /// Return backend as [`Any`] which can be used to downcast to a specific implementation.
     fn as_any(&self) -> &dyn Any;
+
+    /// The distinct tag values of the partition this chunk belongs to, if known.
+    ///
+    /// If present, these are exact for ALL chunks of the partition that report them, i.e. a planner may answer a
+    /// tag value request for these chunks without scanning any of them.
+    fn partition_tag_values(&self) -> Option<Arc<PartitionTagValues>> {
+        None
+    }
+}
+
+/// The distinct tag values of the persisted data of a partition, see [`QueryChunk::partition_tag_values`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionTagValues {
+    /// The (inclusive) time range of the data.
+    pub time_range: TimestampMinMax,
+
+    /// The distinct non-NULL values per tag column.
+    ///
+    /// Tags without an entry have unknown values.
+    pub values: HashMap<Arc<str>, Arc<StringSet>>,
+}
+
+impl PartitionTagValues {
+    /// RAM-bytes INCLUDING `self`.
+    pub fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self.values.capacity() * std::mem::size_of::<(Arc<str>, Arc<StringSet>)>()
+            + self
+                .values
+                .iter()
+                .map(|(k, v)| {
+                    k.len()
+                        + std::mem::size_of::<StringSet>()
+                        + v.iter()
+                            .map(|s| std::mem::size_of::<String>() + s.capacity())
+                            .sum::<usize>()
+                })
+                .sum::<usize>()
+    }
 }
 
 /// A `QueryCompletedToken` is returned by `record_query` implementations of
@@ -280,6 +319,10 @@ where
         // present the underlying implementation, not the wrapper
         self.as_ref().as_any()
     }
+
+    fn partition_tag_values(&self) -> Option<Arc<PartitionTagValues>> {
+        self.as_ref().partition_tag_values()
+    }
 }
 
 impl QueryChunk for Arc<dyn QueryChunk> {
@@ -323,6 +366,10 @@ impl QueryChunk for Arc<dyn QueryChunk> {
         // present the underlying implementation, not the wrapper
         self.as_ref().as_any()
     }
+
+    fn partition_tag_values(&self) -> Option<Arc<PartitionTagValues>> {
+        self.as_ref().partition_tag_values()
+    }
 }
 
 /// return true if all the chunks include distinct counts for all columns.
@@ -8,7 +8,7 @@ use crate::{
         Executor, ExecutorType, IOxSessionContext,
     },
     pruning::prune_chunks,
-    QueryChunk, QueryChunkData, QueryCompletedToken, QueryNamespace, QueryText,
+    PartitionTagValues, QueryChunk, QueryChunkData, QueryCompletedToken, QueryNamespace, QueryText,
 };
 use arrow::array::{BooleanArray, Float64Array};
 use arrow::datatypes::SchemaRef;
@@ -322,6 +322,9 @@ pub struct TestChunk {
 
     /// Suppress output
     quiet: bool,
+
+    /// Distinct tag values of the partition
+    partition_tag_values: Option<Arc<PartitionTagValues>>,
 }
 
 /// Implements a method for adding a column with default stats
@@ -385,6 +388,7 @@ impl TestChunk {
             sort_key: None,
             partition_id: TransitionPartitionId::arbitrary_for_testing(),
             quiet: false,
+            partition_tag_values: None,
         }
     }
 
@@ -461,6 +465,12 @@ impl TestChunk {
         self
     }
 
+    /// Report the distinct tag values of this chunk's partition
+    pub fn with_partition_tag_values(mut self, values: PartitionTagValues) -> Self {
+        self.partition_tag_values = Some(Arc::new(values));
+        self
+    }
+
     /// specify that any call should result in an error with the message
     /// specified
     pub fn with_error(mut self, error_message: impl Into<String>) -> Self {
@@ -1111,6 +1121,10 @@ impl QueryChunk for TestChunk {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn partition_tag_values(&self) -> Option<Arc<PartitionTagValues>> {
+        self.partition_tag_values.clone()
+    }
 }
 
 /// Return the raw data from the list of chunks
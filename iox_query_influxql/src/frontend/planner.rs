@@ -5,13 +5,15 @@ use influxdb_influxql_parser::show_measurements::ShowMeasurementsStatement;
 use influxdb_influxql_parser::show_tag_keys::ShowTagKeysStatement;
 use influxdb_influxql_parser::show_tag_values::ShowTagValuesStatement;
 use std::any::Any;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt;
 use std::fmt::Debug;
 use std::ops::Deref;
 use std::sync::Arc;
 
-use crate::plan::{parse_regex, InfluxQLToLogicalPlan, SchemaProvider};
+use crate::plan::{
+    metadata_cutoff, metadata_cutoff_time, parse_regex, InfluxQLToLogicalPlan, SchemaProvider,
+};
 use datafusion::common::Statistics;
 use datafusion::datasource::provider_as_source;
 use datafusion::execution::context::{SessionState, TaskContext};
@@ -20,6 +22,8 @@ use datafusion::physical_expr::PhysicalSortExpr;
 use datafusion::physical_plan::{
     DisplayAs, DisplayFormatType, Partitioning, SendableRecordBatchStream,
 };
+use datafusion::prelude::{col, lit};
+use datafusion::scalar::ScalarValue;
 use datafusion::{
     error::{DataFusionError, Result},
     physical_plan::ExecutionPlan,
@@ -27,14 +31,19 @@ use datafusion::{
 use influxdb_influxql_parser::common::MeasurementName;
 use influxdb_influxql_parser::parse_statements;
 use influxdb_influxql_parser::statement::Statement;
+use influxdb_influxql_parser::timestamp::Timestamp;
 use influxdb_influxql_parser::visit::{Visitable, Visitor};
 use iox_query::exec::IOxSessionContext;
+use iox_query::QueryNamespace;
 use observability_deps::tracing::debug;
-use schema::Schema;
+use schema::{Schema, TIME_COLUMN_NAME};
 
 struct ContextSchemaProvider<'a> {
     state: &'a SessionState,
     tables: HashMap<String, (Arc<dyn TableSource>, Schema)>,
+    /// The distinct values of each tag of each table, where known
+    /// without scanning the table
+    tag_values: HashMap<String, HashMap<String, BTreeSet<String>>>,
 }
 
 impl<'a> SchemaProvider for ContextSchemaProvider<'a> {
@@ -65,6 +74,10 @@ impl<'a> SchemaProvider for ContextSchemaProvider<'a> {
         self.tables.get(name).map(|(_, s)| s.clone())
     }
 
+    fn table_tag_values(&self, table_name: &str, tag_name: &str) -> Option<&BTreeSet<String>> {
+        self.tag_values.get(table_name)?.get(tag_name)
+    }
+
     fn execution_props(&self) -> &ExecutionProps {
         self.state.execution_props()
     }
@@ -137,13 +150,24 @@ impl DisplayAs for SchemaExec {
 
 /// Create plans for running InfluxQL queries against databases
 #[derive(Debug, Default)]
-pub struct InfluxQLQueryPlanner {}
+pub struct InfluxQLQueryPlanner {
+    /// The namespace queried, to look up metadata not available through
+    /// the DataFusion catalog
+    namespace: Option<Arc<dyn QueryNamespace>>,
+}
 
 impl InfluxQLQueryPlanner {
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Answer metadata queries from the tag values the chunks of `namespace`
+    /// record for their partition, where possible.
+    pub fn with_namespace(mut self, namespace: Arc<dyn QueryNamespace>) -> Self {
+        self.namespace = Some(namespace);
+        self
+    }
+
     /// Plan an InfluxQL query against the catalogs registered with `ctx`, and return a
     /// DataFusion physical execution plan that runs on the query executor.
     pub async fn query(
@@ -203,6 +227,7 @@ impl InfluxQLQueryPlanner {
         let mut sp = ContextSchemaProvider {
             state: &ctx.inner().state(),
             tables: HashMap::with_capacity(query_tables.len()),
+            tag_values: HashMap::new(),
         };
 
         for table_name in &query_tables {
@@ -217,6 +242,23 @@ impl InfluxQLQueryPlanner {
             }
         }
 
+        if let (Some(namespace), Statement::ShowTagValues(show_tag_values)) =
+            (&self.namespace, &statement)
+        {
+            if show_tag_values.condition.is_none() {
+                let start_time =
+                    Timestamp::from(sp.state.execution_props().query_execution_start_time);
+                let min_time = metadata_cutoff_time(metadata_cutoff(ctx), start_time)?
+                    .max(namespace.retention_time_ns().unwrap_or(i64::MIN));
+                for (table_name, (_, schema)) in &sp.tables {
+                    let tag_values =
+                        known_tag_values(namespace.as_ref(), table_name, schema, min_time, ctx)
+                            .await?;
+                    sp.tag_values.insert(table_name.clone(), tag_values);
+                }
+            }
+        }
+
         let planner = InfluxQLToLogicalPlan::new(&sp, ctx);
         let logical_plan = planner.statement_to_plan(statement)?;
         debug!(plan=%logical_plan.display_graphviz(), "logical plan");
@@ -237,6 +279,59 @@ impl InfluxQLQueryPlanner {
     }
 }
 
+/// Returns the distinct values of the tags of `table_name` with rows at or after
+/// `min_time`, as recorded for the partitions of its chunks.
+///
+/// Only tags recorded for all chunks are returned, and none if any chunk
+/// lacks recorded tag values, such as unpersisted data, or may hold rows
+/// before `min_time`.
+async fn known_tag_values(
+    namespace: &dyn QueryNamespace,
+    table_name: &str,
+    schema: &Schema,
+    min_time: i64,
+    ctx: &IOxSessionContext,
+) -> Result<HashMap<String, BTreeSet<String>>> {
+    let filters = [
+        col(TIME_COLUMN_NAME).gt_eq(lit(ScalarValue::TimestampNanosecond(Some(min_time), None)))
+    ];
+    let chunks = namespace
+        .chunks(
+            table_name,
+            &filters,
+            None,
+            ctx.child_ctx("known_tag_values"),
+        )
+        .await?;
+
+    let mut partitions = HashMap::with_capacity(chunks.len());
+    for chunk in &chunks {
+        match chunk.partition_tag_values() {
+            Some(tag_values) if tag_values.time_range.min >= min_time => {
+                partitions.insert(chunk.partition_id().clone(), tag_values);
+            }
+            _ => return Ok(HashMap::new()),
+        }
+    }
+
+    Ok(schema
+        .tags_iter()
+        .filter_map(|field| {
+            let mut values = BTreeSet::new();
+            for tag_values in partitions.values() {
+                values.extend(
+                    tag_values
+                        .values
+                        .get(field.name().as_str())?
+                        .iter()
+                        .cloned(),
+                );
+            }
+            Some((field.name().clone(), values))
+        })
+        .collect())
+}
+
 fn find_all_measurements(stmt: &Statement, tables: &[String]) -> Result<HashSet<String>> {
     struct Matcher<'a>(&'a mut HashSet<String>, &'a [String]);
     impl<'a> Visitor for Matcher<'a> {
@@ -323,6 +418,10 @@ fn find_all_measurements(stmt: &Statement, tables: &[String]) -> Result<HashSet<
 #[cfg(test)]
 mod test {
     use super::*;
+    use data_types::TimestampMinMax;
+    use iox_query::exec::Executor;
+    use iox_query::test::{TestChunk, TestDatabase};
+    use iox_query::{PartitionTagValues, QueryChunk};
     use itertools::Itertools;
     use test_helpers::assert_error;
 
@@ -404,4 +503,53 @@ mod test {
         assert!(find("SELECT * FROM /^l/").is_empty());
         assert!(find("SELECT * FROM (SELECT * FROM /^l/)").is_empty());
     }
+
+    #[tokio::test]
+    async fn test_known_tag_values() {
+        let tag_values = |min_time: i64, values: &[&str]| PartitionTagValues {
+            time_range: TimestampMinMax::new(min_time, 100),
+            values: HashMap::from([(
+                Arc::from("tag"),
+                Arc::new(values.iter().map(ToString::to_string).collect()),
+            )]),
+        };
+        let chunk = |id: u128, partition: i64| {
+            TestChunk::new("table")
+                .with_id(id)
+                .with_partition(partition)
+                .with_tag_column("tag")
+                .with_tag_column("other")
+                .with_time_column()
+        };
+        let schema = chunk(0, 0).schema().clone();
+        let ctx = IOxSessionContext::with_testing();
+
+        let db = TestDatabase::new(Arc::new(Executor::new_testing()));
+        for id in [1, 2] {
+            let chunk = chunk(id, 1).with_partition_tag_values(tag_values(10, &["a", "b"]));
+            db.add_chunk("p1", Arc::new(chunk));
+        }
+        let chunk_3 = chunk(3, 2).with_partition_tag_values(tag_values(20, &["b", "c"]));
+        db.add_chunk("p2", Arc::new(chunk_3));
+
+        // Only tags recorded for all partitions are known
+        let got = known_tag_values(&db, "table", &schema, 0, &ctx)
+            .await
+            .unwrap();
+        let want = BTreeSet::from(["a".to_string(), "b".to_string(), "c".to_string()]);
+        assert_eq!(got, HashMap::from([("tag".to_string(), want)]));
+
+        // Partitions that may hold rows before the cutoff must be scanned
+        let got = known_tag_values(&db, "table", &schema, 15, &ctx)
+            .await
+            .unwrap();
+        assert!(got.is_empty());
+
+        // as must data without recorded tag values
+        db.add_chunk("p3", Arc::new(chunk(4, 3)));
+        let got = known_tag_values(&db, "table", &schema, 0, &ctx)
+            .await
+            .unwrap();
+        assert!(got.is_empty());
+    }
 }
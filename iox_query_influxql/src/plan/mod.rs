@@ -13,4 +13,5 @@ mod var_ref;
 
 pub use planner::InfluxQLToLogicalPlan;
 pub use planner::SchemaProvider;
+pub(crate) use planner::{metadata_cutoff, metadata_cutoff_time};
 pub(crate) use util::parse_regex;
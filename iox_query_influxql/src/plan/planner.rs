@@ -126,6 +126,13 @@ pub trait SchemaProvider {
     /// Get the schema for the specified `table`.
     fn table_schema(&self, name: &str) -> Option<Schema>;
 
+    /// The distinct values of the tag `tag_name` of the table `table_name`, if known
+    /// without scanning the table for a `SHOW TAG VALUES` statement without a `WHERE`
+    /// clause.
+    fn table_tag_values(&self, _table_name: &str, _tag_name: &str) -> Option<&BTreeSet<String>> {
+        None
+    }
+
     fn execution_props(&self) -> &ExecutionProps;
 }
 
@@ -2236,17 +2243,7 @@ impl<'a> InfluxQLToLogicalPlan<'a> {
         // Add time restriction to logical plan if there isn't any.
         let time_range = if time_range.is_unbounded() {
             TimeRange {
-                lower: Some(match cutoff {
-                    MetadataCutoff::Absolute(dt) => dt
-                        .timestamp_nanos_opt()
-                        .ok_or_else(|| error::map::query("timestamp out of range"))?,
-                    MetadataCutoff::Relative(delta) => {
-                        start_time
-                            .timestamp_nanos_opt()
-                            .ok_or_else(|| error::map::query("timestamp out of range"))?
-                            - delta.as_nanos() as i64
-                    }
-                }),
+                lower: Some(metadata_cutoff_time(cutoff, start_time)?),
                 upper: None,
             }
         } else {
@@ -2692,19 +2689,39 @@ impl<'a> InfluxQLToLogicalPlan<'a> {
                 self.plan_where_clause(plan, &show_tag_values.condition, metadata_cutoff, &schema)?;
 
             for key in keys {
-                let idx = plan
-                    .schema()
-                    .index_of_column_by_name(None, key)?
-                    .expect("where is the key?");
-
-                let plan = LogicalPlanBuilder::from(plan.clone())
-                    .select([idx])?
-                    .distinct()?
+                // Use the tag values recorded in the catalog, rather than
+                // scanning the table, if they cover all rows to consider.
+                let known_values = match show_tag_values.condition {
+                    Some(_) => None,
+                    None => self.s.table_tag_values(&table, key),
+                };
+
+                let plan = match known_values {
+                    Some(values) if values.is_empty() => continue,
+                    Some(values) => LogicalPlanBuilder::values(
+                        values.iter().map(|value| vec![lit_dict(value)]).collect(),
+                    )?
                     .project(measurement_expr.iter().cloned().chain([
                         lit_dict(key).alias(key_col),
-                        Expr::Column(Column::from_name(key)).alias(value_col),
+                        Expr::Column(Column::from_name("column1")).alias(value_col),
                     ]))?
-                    .build()?;
+                    .build()?,
+                    None => {
+                        let idx = plan
+                            .schema()
+                            .index_of_column_by_name(None, key)?
+                            .expect("where is the key?");
+
+                        LogicalPlanBuilder::from(plan.clone())
+                            .select([idx])?
+                            .distinct()?
+                            .project(measurement_expr.iter().cloned().chain([
+                                lit_dict(key).alias(key_col),
+                                Expr::Column(Column::from_name(key)).alias(value_col),
+                            ]))?
+                            .build()?
+                    }
+                };
 
                 union_plan = match union_plan {
                     Some(union_plan) => {
@@ -2944,19 +2961,41 @@ impl<'a> InfluxQLToLogicalPlan<'a> {
     }
 
     fn metadata_cutoff(&self) -> MetadataCutoff {
-        self.iox_ctx
-            .inner()
-            .state()
-            .config()
-            .options()
-            .extensions
-            .get::<IoxConfigExt>()
-            .cloned()
-            .unwrap_or_default()
-            .influxql_metadata_cutoff
+        metadata_cutoff(self.iox_ctx)
     }
 }
 
+/// Returns the configured cutoff of the data `SHOW` statements consider, if their
+/// `WHERE` clause does not restrict the time range.
+pub(crate) fn metadata_cutoff(iox_ctx: &IOxSessionContext) -> MetadataCutoff {
+    iox_ctx
+        .inner()
+        .state()
+        .config()
+        .options()
+        .extensions
+        .get::<IoxConfigExt>()
+        .cloned()
+        .unwrap_or_default()
+        .influxql_metadata_cutoff
+}
+
+/// Returns the earliest time of the data a `SHOW` statement started at `start_time`
+/// considers under the metadata `cutoff`.
+pub(crate) fn metadata_cutoff_time(cutoff: MetadataCutoff, start_time: Timestamp) -> Result<i64> {
+    Ok(match cutoff {
+        MetadataCutoff::Absolute(dt) => dt
+            .timestamp_nanos_opt()
+            .ok_or_else(|| error::map::query("timestamp out of range"))?,
+        MetadataCutoff::Relative(delta) => {
+            start_time
+                .timestamp_nanos_opt()
+                .ok_or_else(|| error::map::query("timestamp out of range"))?
+                - delta.as_nanos() as i64
+        }
+    })
+}
+
 /// Returns a [`LogicalPlan`] that performs gap-filling for the `input` plan.
 ///
 /// # Arguments
@@ -3351,7 +3390,18 @@ mod test {
     use schema::SchemaBuilder;
 
     fn logical_plan(sql: &str) -> Result<LogicalPlan> {
+        logical_plan_with_provider(sql, &schema_provider())
+    }
+
+    fn logical_plan_with_provider(sql: &str, sp: &MockSchemaProvider) -> Result<LogicalPlan> {
         let mut statements = parse_statements(sql).unwrap();
+        let iox_ctx = IOxSessionContext::with_testing();
+        let planner = InfluxQLToLogicalPlan::new(sp, &iox_ctx);
+
+        planner.statement_to_plan(statements.pop().unwrap())
+    }
+
+    fn schema_provider() -> MockSchemaProvider {
         let mut sp = MockSchemaProvider::default();
         sp.add_schemas(vec![
             SchemaBuilder::new()
@@ -3391,11 +3441,7 @@ mod test {
                 .build()
                 .unwrap(),
         ]);
-
-        let iox_ctx = IOxSessionContext::with_testing();
-        let planner = InfluxQLToLogicalPlan::new(&sp, &iox_ctx);
-
-        planner.statement_to_plan(statements.pop().unwrap())
+        sp
     }
 
     fn metadata(sql: &str) -> Option<InfluxQlMetadata> {
@@ -3850,6 +3896,42 @@ mod test {
             "###);
         }
 
+        #[test]
+        fn test_show_tag_values_known() {
+            let mut sp = schema_provider();
+            sp.add_tag_values("data", "bar", &["bar_1", "bar_2"]);
+            sp.add_tag_values("data", "foo", &[]);
+            let plan = |sql: &str| {
+                logical_plan_with_provider(sql, &sp)
+                    .unwrap()
+                    .display_indent()
+                    .to_string()
+            };
+
+            // The known values are used in place of scanning the table
+            let got = plan("SHOW TAG VALUES FROM data WITH KEY = bar");
+            assert!(got.contains("Values:"), "{got}");
+            assert!(got.contains("bar_1") && got.contains("bar_2"), "{got}");
+            assert!(!got.contains("TableScan"), "{got}");
+
+            // A tag without values has none to show
+            let got = plan("SHOW TAG VALUES FROM data WITH KEY = foo");
+            assert!(got.contains("EmptyRelation"), "{got}");
+
+            // Only tags with known values are answered from them
+            let got = plan("SHOW TAG VALUES FROM all_types WITH KEY = tag0");
+            assert!(got.contains("TableScan: all_types"), "{got}");
+            let got = plan("SHOW TAG VALUES FROM data, all_types WITH KEY IN (bar, tag0)");
+            assert!(got.contains("Values"), "{got}");
+            assert!(got.contains("TableScan: all_types"), "{got}");
+            assert!(!got.contains("TableScan: data"), "{got}");
+
+            // The known values cover all rows, so can't answer a WHERE clause
+            let got = plan("SHOW TAG VALUES FROM data WITH KEY = bar WHERE foo = 'some_foo'");
+            assert!(!got.contains("Values"), "{got}");
+            assert!(got.contains("TableScan: data"), "{got}");
+        }
+
         #[test]
         fn test_show_retention_policies() {
             assert_snapshot!(plan("SHOW RETENTION POLICIES"), @r###"
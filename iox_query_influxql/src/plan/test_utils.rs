@@ -14,7 +14,7 @@ use influxdb_influxql_parser::select::SelectStatement;
 use influxdb_influxql_parser::statement::Statement;
 use itertools::Itertools;
 use schema::{Schema, SchemaBuilder};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
 
 /// Returns the InfluxQL [`SelectStatement`] for the specified SQL, `s`.
@@ -135,6 +135,7 @@ pub(crate) mod database {
 pub(crate) struct MockSchemaProvider {
     execution_props: ExecutionProps,
     tables: HashMap<String, (Arc<dyn TableSource>, Schema)>,
+    tag_values: HashMap<(String, String), BTreeSet<String>>,
 }
 
 impl Default for MockSchemaProvider {
@@ -150,6 +151,7 @@ impl Default for MockSchemaProvider {
         let mut res = Self {
             execution_props,
             tables: HashMap::new(),
+            tag_values: HashMap::new(),
         };
         res.add_schemas(database::schemas());
         res
@@ -169,6 +171,14 @@ impl MockSchemaProvider {
     pub(crate) fn add_schemas(&mut self, schemas: impl IntoIterator<Item = Schema>) {
         schemas.into_iter().for_each(|s| self.add_schema(s));
     }
+
+    /// Report `values` as the known values of the tag `tag_name` of `table_name`.
+    pub(crate) fn add_tag_values(&mut self, table_name: &str, tag_name: &str, values: &[&str]) {
+        self.tag_values.insert(
+            (table_name.to_owned(), tag_name.to_owned()),
+            values.iter().map(|v| v.to_string()).collect(),
+        );
+    }
 }
 
 impl SchemaProvider for MockSchemaProvider {
@@ -199,6 +209,11 @@ impl SchemaProvider for MockSchemaProvider {
         self.tables.get(name).map(|(_, s)| s.clone())
     }
 
+    fn table_tag_values(&self, table_name: &str, tag_name: &str) -> Option<&BTreeSet<String>> {
+        self.tag_values
+            .get(&(table_name.to_owned(), tag_name.to_owned()))
+    }
+
     fn execution_props(&self) -> &ExecutionProps {
         &self.execution_props
     }
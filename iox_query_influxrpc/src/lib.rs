@@ -18,12 +18,16 @@
 use workspace_hack as _;
 
 use arrow::datatypes::DataType;
-use data_types::ChunkId;
+use data_types::{ChunkId, TimestampMinMax};
 use datafusion::{
     common::DFSchemaRef,
     error::DataFusionError,
-    logical_expr::{utils::exprlist_to_columns, ExprSchemable, LogicalPlan, LogicalPlanBuilder},
-    prelude::{when, Column, Expr},
+    logical_expr::{
+        expr::BinaryExpr, utils::exprlist_to_columns, ExprSchemable, LogicalPlan,
+        LogicalPlanBuilder, Operator,
+    },
+    prelude::{approx_distinct, cast, coalesce, concat_ws, count, lit, sum, when, Column, Expr},
+    scalar::ScalarValue,
 };
 use datafusion_util::{
     config::{DEFAULT_CATALOG, DEFAULT_SCHEMA},
@@ -492,6 +496,8 @@ impl InfluxRpcPlanner {
         )
         .and_then(|(table_name, table_schema, predicate, chunks)| async move {
             let mut chunks_full = vec![];
+            let mut known_values = StringSet::new();
+            let mut known_partitions = StdHashSet::new();
 
             let chunks = prune_chunks(&table_schema, chunks, &predicate);
             for chunk in cheap_chunk_first(chunks) {
@@ -524,6 +530,18 @@ impl InfluxRpcPlanner {
                     }
                 );
 
+                if let Some(values) = chunk_partition_tag_values(&chunk, tag_name, &predicate) {
+                    debug!(
+                        %table_name,
+                        chunk_id=%chunk.id().get(),
+                        "tag values found from partition metadata"
+                    );
+                    if known_partitions.insert(chunk.partition_id().clone()) {
+                        known_values.extend(values.iter().cloned());
+                    }
+                    continue;
+                }
+
                 debug!(
                     %table_name,
                     chunk_id=%chunk.id().get(),
@@ -532,7 +550,7 @@ impl InfluxRpcPlanner {
                 chunks_full.push(chunk);
             }
 
-            Ok((table_name, predicate, chunks_full))
+            Ok((table_name, predicate, chunks_full, known_values))
         })
         .try_collect()
         .await?;
@@ -544,7 +562,11 @@ impl InfluxRpcPlanner {
         // At this point, we have a set of tag_values we know at plan
         // time in `known_columns`, and some tables in chunks that we
         // need to run a plan to find what values pass the predicate.
-        for (table_name, predicate, chunks_full) in tables {
+        for (table_name, predicate, chunks_full, known_values) in tables {
+            if !known_values.is_empty() {
+                builder = builder.append_other(known_values.into());
+            }
+
             if !chunks_full.is_empty() {
                 let schema = self
                     .meta
@@ -836,6 +858,43 @@ impl InfluxRpcPlanner {
         Ok(SeriesSetPlans::new(plans))
     }
 
+    /// Creates one plan per table that counts the number of series
+    /// matching the predicate.
+    ///
+    /// A series is a distinct combination of measurement, tag set and field
+    /// key, i.e. what `read_filter` would return as a separate series frame.
+    ///
+    /// Each plan produces a single row with a single Int64 column named
+    /// [`SERIES_CARDINALITY_COLUMN_NAME`]. The column may be NULL if the
+    /// table contains no matching rows. The total cardinality is the sum
+    /// over all plans.
+    pub async fn series_cardinality(
+        &self,
+        namespace: Arc<dyn QueryNamespace>,
+        rpc_predicate: InfluxRpcPredicate,
+        mode: SeriesCardinalityMode,
+    ) -> Result<Vec<LogicalPlan>> {
+        let ctx = self.ctx.child_ctx("series_cardinality planning");
+        debug!(?rpc_predicate, ?mode, "planning series_cardinality");
+
+        let table_predicates = rpc_predicate
+            .table_predicates(self.meta.as_ref())
+            .context(CreatingPredicatesSnafu)?;
+
+        let plans = create_plans(
+            namespace,
+            &table_predicates,
+            ctx,
+            Arc::clone(&self.meta),
+            |table_name, predicate, chunks, schema| {
+                Self::series_cardinality_plan(table_name, schema, predicate, mode, chunks)
+            },
+        )
+        .await?;
+
+        Ok(plans.into_iter().flatten().collect())
+    }
+
     /// Creates a DataFusion LogicalPlan that returns column *names* as a
     /// single column of Strings for a specific table
     ///
@@ -1199,6 +1258,15 @@ impl InfluxRpcPlanner {
     ///   tag1, ... tagN,
     ///   window_bound(time, every, offset) as time
     ///
+    /// Windows are shifted by `offset`, i.e. a window covers
+    /// `[offset + n * every, offset + (n + 1) * every)` and is identified by its
+    /// stop time.
+    ///
+    /// For selector aggregates (first, last, min, max) the timestamp of the
+    /// selected point is returned in a separate time column per field, see
+    /// `AggExprs::selector_aggregates`, which matches the TSM engine's
+    /// window aggregate behaviour.
+    ///
     /// The created plan looks like:
     ///
     ///  OrderBy(gby: tag columns, window_function; agg: aggregate(field))
@@ -1265,6 +1333,149 @@ impl InfluxRpcPlanner {
             field_columns,
         ))
     }
+
+    /// Creates a DataFusion LogicalPlan that counts the series of a table,
+    /// returning `None` if no field of the table passes the predicate.
+    ///
+    /// For [`SeriesCardinalityMode::Exact`] the created plan looks like:
+    ///
+    /// ```text
+    ///  Aggregate(agg: sum(series))
+    ///    Projection(series: sum(CASE WHEN field_i > 0 THEN 1 ELSE 0 END))
+    ///      GroupBy(gby: tag columns; agg: count(field_i))
+    ///        Filter(predicate)
+    ///          Scan
+    /// ```
+    ///
+    /// For [`SeriesCardinalityMode::Estimate`] the tag set is encoded as a
+    /// single string key and the plan looks like:
+    ///
+    /// ```text
+    ///  Projection(series: sum(CAST(field_i AS Int64)))
+    ///    Aggregate(agg: approx_distinct(CASE WHEN field_i IS NOT NULL THEN key END))
+    ///      Filter(predicate)
+    ///        Scan
+    /// ```
+    fn series_cardinality_plan(
+        table_name: &str,
+        schema: &Schema,
+        predicate: &Predicate,
+        mode: SeriesCardinalityMode,
+        chunks: Vec<Arc<dyn QueryChunk>>,
+    ) -> Result<Option<LogicalPlan>> {
+        let scan_and_filter = ScanPlanBuilder::new(Arc::from(table_name), schema)
+            .with_predicate(predicate)
+            .with_chunks(chunks)
+            .build()?;
+
+        let schema = scan_and_filter.provider.iox_schema();
+
+        let fields: Vec<_> = filtered_fields_iter(schema, predicate).collect();
+        if fields.is_empty() {
+            return Ok(None);
+        }
+
+        let plan_builder = match mode {
+            SeriesCardinalityMode::Exact => {
+                let group_exprs = schema
+                    .tags_iter()
+                    .map(|field| field.name().as_expr())
+                    .collect::<Vec<_>>();
+
+                let agg_exprs = fields
+                    .iter()
+                    .map(|field| count(field.expr.clone()).alias(field.name))
+                    .collect::<Vec<_>>();
+
+                // every field with at least one value in a tag set is a series
+                let series = fields
+                    .iter()
+                    .map(|field| {
+                        when(field.name.as_expr().gt(lit(0_i64)), lit(1_i64)).otherwise(lit(0_i64))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
+                    .into_iter()
+                    .reduce(|a, b| a + b)
+                    .expect("at least one field");
+
+                scan_and_filter
+                    .plan_builder
+                    .aggregate(group_exprs, agg_exprs)?
+                    .project(vec![series.alias(SERIES_CARDINALITY_COLUMN_NAME)])?
+                    .aggregate(
+                        Vec::<Expr>::new(),
+                        vec![sum(SERIES_CARDINALITY_COLUMN_NAME.as_expr())
+                            .alias(SERIES_CARDINALITY_COLUMN_NAME)],
+                    )?
+            }
+            SeriesCardinalityMode::Estimate => {
+                // NULL tags are encoded with a control character marker (not a
+                // realistic tag value) so that `a=NULL,b=x` and `a=x,b=NULL` differ.
+                // Tags are usually dictionary encoded, so cast them first.
+                let tags = schema
+                    .tags_iter()
+                    .map(|field| {
+                        coalesce(vec![
+                            cast(field.name().as_expr(), DataType::Utf8),
+                            lit("\u{1}"),
+                        ])
+                    })
+                    .collect::<Vec<_>>();
+                let key = if tags.is_empty() {
+                    lit("")
+                } else {
+                    concat_ws(lit("\u{0}"), tags)
+                };
+
+                let agg_exprs = fields
+                    .iter()
+                    .map(|field| {
+                        when(field.expr.clone().is_not_null(), key.clone())
+                            .end()
+                            .map(|expr| approx_distinct(expr).alias(field.name))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let plan_builder = scan_and_filter
+                    .plan_builder
+                    .aggregate(Vec::<Expr>::new(), agg_exprs)?;
+
+                let series = fields
+                    .iter()
+                    .map(|field| {
+                        field
+                            .name
+                            .as_expr()
+                            .cast_to(&DataType::Int64, plan_builder.schema())
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
+                    .into_iter()
+                    .reduce(|a, b| a + b)
+                    .expect("at least one field");
+
+                plan_builder.project(vec![series.alias(SERIES_CARDINALITY_COLUMN_NAME)])?
+            }
+        };
+
+        Ok(Some(plan_builder.build()?))
+    }
+}
+
+/// Name of the column produced by [`InfluxRpcPlanner::series_cardinality`]
+/// plans.
+pub const SERIES_CARDINALITY_COLUMN_NAME: &str = "series";
+
+/// How [`InfluxRpcPlanner::series_cardinality`] counts series.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SeriesCardinalityMode {
+    /// Count every distinct series. Memory usage grows with the number of
+    /// series in the queried range.
+    #[default]
+    Exact,
+
+    /// Estimate the number of series using a HyperLogLog sketch per field,
+    /// which uses bounded memory regardless of the number of series.
+    Estimate,
 }
 
 /// Stream of chunks for table predicates.
@@ -1841,6 +2052,49 @@ fn chunk_column_names(
     })
 }
 
+/// Returns the distinct values of `tag_name` in the partition of `chunk`, if these can be used in place of scanning
+/// the chunk.
+///
+/// This is the case if `predicate` selects every row of the partition, i.e. it has no restrictions other than a time
+/// range and a retention cut-off that both cover the entire partition.
+fn chunk_partition_tag_values(
+    chunk: &dyn QueryChunk,
+    tag_name: &str,
+    predicate: &Predicate,
+) -> Option<Arc<StringSet>> {
+    if predicate.field_columns.is_some() || !predicate.value_expr.is_empty() {
+        return None;
+    }
+
+    let partition_tag_values = chunk.partition_tag_values()?;
+    let values = partition_tag_values.values.get(tag_name)?;
+    let TimestampMinMax { min, max } = partition_tag_values.time_range;
+
+    if let Some(range) = &predicate.range {
+        if !(range.contains(min) && range.contains(max)) {
+            return None;
+        }
+    }
+
+    // only the retention cut-off (`time > retention`) is understood here
+    let all_retained = predicate.exprs.iter().all(|expr| match expr {
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::Gt,
+            right,
+        }) => {
+            matches!(left.as_ref(), Expr::Column(c) if c.name == TIME_COLUMN_NAME)
+                && matches!(
+                    right.as_ref(),
+                    Expr::Literal(ScalarValue::TimestampNanosecond(Some(t), _)) if *t < min
+                )
+        }
+        _ => false,
+    });
+
+    all_retained.then(|| Arc::clone(values))
+}
+
 #[derive(Debug)]
 struct NamespaceMeta {
     tables: BTreeMap<String, Schema>,
@@ -1899,7 +2153,9 @@ mod tests {
     use iox_query::{
         exec::Executor,
         test::{TestChunk, TestDatabase},
+        PartitionTagValues,
     };
+    use std::collections::HashMap;
     use test_helpers::maybe_start_logging;
 
     use super::*;
@@ -2502,6 +2758,68 @@ mod tests {
         "###);
     }
 
+    #[tokio::test]
+    async fn test_tag_values_from_partition_metadata() {
+        maybe_start_logging();
+
+        let partition_tag_values = |values: &[&str]| PartitionTagValues {
+            time_range: TimestampMinMax::new(10, 20),
+            values: HashMap::from([(
+                Arc::from("tag"),
+                Arc::new(values.iter().map(ToString::to_string).collect()),
+            )]),
+        };
+        let chunk = |id: u128, partition: i64, values: &[&str]| {
+            Arc::new(
+                TestChunk::new("table")
+                    .with_id(id)
+                    .with_partition(partition)
+                    .with_tag_column("tag")
+                    .with_f64_field_column("field")
+                    .with_time_column()
+                    .with_partition_tag_values(partition_tag_values(values)),
+            )
+        };
+
+        let executor = Arc::new(Executor::new_testing());
+        let test_db =
+            Arc::new(TestDatabase::new(Arc::clone(&executor)).with_retention_time_ns(Some(1)));
+        test_db.add_chunk("p1", chunk(0, 1, &["a", "b"]));
+        test_db.add_chunk("p1", chunk(1, 1, &["a", "b"]));
+        test_db.add_chunk("p2", chunk(2, 2, &["c"]));
+
+        let plan = |predicate: Predicate| {
+            let test_db = Arc::clone(&test_db);
+            async move {
+                InfluxRpcPlanner::new(test_db.new_query_context(None))
+                    .await
+                    .tag_values(test_db, "tag", InfluxRpcPredicate::new(None, predicate))
+                    .await
+                    .expect("creating plan")
+            }
+        };
+        let known = |plan: StringSetPlan| match plan {
+            StringSetPlan::Known(values) => Some(values.iter().cloned().collect::<Vec<_>>()),
+            StringSetPlan::Plan(_) => None,
+        };
+
+        // no predicate (other than the retention period) selects all rows
+        let got = known(plan(Predicate::new()).await);
+        assert_eq!(got, Some(vec!["a".to_string(), "b".into(), "c".into()]));
+
+        // nor does a time range that covers the partitions
+        let got = known(plan(Predicate::new().with_range(0, 100)).await);
+        assert_eq!(got, Some(vec!["a".to_string(), "b".into(), "c".into()]));
+
+        // a time range within the partitions needs a scan
+        let got = known(plan(Predicate::new().with_range(15, 100)).await);
+        assert_eq!(got, None);
+
+        // as does any other predicate
+        let got = known(plan(Predicate::new().with_expr("tag".as_expr().eq(lit("a")))).await);
+        assert_eq!(got, None);
+    }
+
     /// Runs func() and checks that predicates are simplified prior to
     /// sending them down to the chunks for processing.
     async fn run_test<T>(func: T)
@@ -0,0 +1,273 @@
+//! Construction of the [`AuditSink`] from CLI config, and the sink writing
+//! audit events to an IOx table.
+
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use authz::audit::{AuditEvent, AuditSink, FileAuditSink};
+use clap_blocks::audit::AuditConfig;
+use influxdb_line_protocol::builder::LineProtocolBuilder;
+use observability_deps::tracing::warn;
+use snafu::{ResultExt, Snafu};
+use tokio::{sync::mpsc, time::MissedTickBehavior};
+
+/// The table audit events are written to by an [`IoxTableAuditSink`].
+pub const AUDIT_TABLE_NAME: &str = "audit";
+
+/// The maximum number of events buffered by an [`IoxTableAuditSink`] before
+/// further events are dropped.
+const MAX_BUFFERED_EVENTS: usize = 10_000;
+
+/// The maximum number of events written in a single request.
+const MAX_BATCH_SIZE: usize = 1_000;
+
+/// The interval at which buffered events are written.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display("failed to open audit log file {}: {source}", path.display()))]
+    LogFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// Initialise the [`AuditSink`] selected by `config`, returning [`None`] if
+/// auditing is disabled.
+pub async fn audit_sink_from_config(
+    config: &AuditConfig,
+) -> Result<Option<Arc<dyn AuditSink>>, Error> {
+    let mut sinks: Vec<Arc<dyn AuditSink>> = vec![];
+
+    if let Some(path) = &config.audit_log_file {
+        let sink = FileAuditSink::open(path.clone())
+            .await
+            .context(LogFileSnafu { path: path.clone() })?;
+        sinks.push(Arc::new(sink));
+    }
+
+    if let Some(addr) = &config.audit_router_addr {
+        sinks.push(Arc::new(IoxTableAuditSink::new(
+            addr,
+            &config.audit_org,
+            &config.audit_bucket,
+            config.audit_token.clone(),
+        )));
+    }
+
+    Ok(match sinks.len() {
+        0 => None,
+        1 => sinks.pop(),
+        _ => Some(Arc::new(MultiAuditSink(sinks))),
+    })
+}
+
+/// Records each event to all of the inner sinks.
+#[derive(Debug)]
+struct MultiAuditSink(Vec<Arc<dyn AuditSink>>);
+
+#[async_trait]
+impl AuditSink for MultiAuditSink {
+    async fn record(&self, event: AuditEvent) {
+        for sink in &self.0 {
+            sink.record(event.clone()).await;
+        }
+    }
+}
+
+/// An [`AuditSink`] writing events as rows of the [`AUDIT_TABLE_NAME`] table
+/// in an IOx namespace, using the HTTP write API of a router.
+///
+/// Events are buffered and written in batches by a background task, so that
+/// recording an event never blocks the audited operation. If the router
+/// cannot keep up, or rejects a write, the affected events are logged and
+/// discarded.
+///
+/// Each row is tagged with the event `operation` and `outcome`, and the
+/// targeted `namespace` and `table` if any. The `subject` is stored as a
+/// field, as are the `before` and `after` states serialised as JSON.
+#[derive(Debug)]
+pub struct IoxTableAuditSink {
+    tx: mpsc::Sender<AuditEvent>,
+}
+
+impl IoxTableAuditSink {
+    /// Write events to the `org` / `bucket` namespace through the router
+    /// at `router_addr`, authenticating with `token` if provided.
+    ///
+    /// # Panics
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn new(router_addr: &str, org: &str, bucket: &str, token: Option<String>) -> Self {
+        let url = format!(
+            "{}/api/v2/write?{}",
+            router_addr.trim_end_matches('/'),
+            serde_urlencoded::to_string([("org", org), ("bucket", bucket)])
+                .expect("encode query params"),
+        );
+
+        let (tx, rx) = mpsc::channel(MAX_BUFFERED_EVENTS);
+        tokio::spawn(write_loop(reqwest::Client::new(), url, token, rx));
+
+        Self { tx }
+    }
+}
+
+#[async_trait]
+impl AuditSink for IoxTableAuditSink {
+    async fn record(&self, event: AuditEvent) {
+        if let Err(e) = self.tx.try_send(event) {
+            warn!(error=%e, "dropping audit event");
+        }
+    }
+}
+
+/// Buffer events received from `rx`, writing them to `url` every
+/// [`FLUSH_INTERVAL`] or once [`MAX_BATCH_SIZE`] events are buffered.
+///
+/// Exits once all senders are dropped and the remaining events are written.
+async fn write_loop(
+    client: reqwest::Client,
+    url: String,
+    token: Option<String>,
+    mut rx: mpsc::Receiver<AuditEvent>,
+) {
+    let mut ticker = tokio::time::interval(FLUSH_INTERVAL);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let mut buf = Vec::with_capacity(MAX_BATCH_SIZE);
+    loop {
+        tokio::select! {
+            event = rx.recv() => match event {
+                Some(event) => {
+                    buf.push(event);
+                    if buf.len() < MAX_BATCH_SIZE {
+                        continue;
+                    }
+                }
+                None => {
+                    write(&client, &url, token.as_deref(), &mut buf).await;
+                    return;
+                }
+            },
+            _ = ticker.tick() => {}
+        }
+
+        write(&client, &url, token.as_deref(), &mut buf).await;
+    }
+}
+
+/// Write, and then clear, the events in `buf`.
+async fn write(
+    client: &reqwest::Client,
+    url: &str,
+    token: Option<&str>,
+    buf: &mut Vec<AuditEvent>,
+) {
+    if buf.is_empty() {
+        return;
+    }
+
+    let body = to_line_protocol(buf);
+    let mut req = client.post(url).body(body);
+    if let Some(token) = token {
+        req = req.header(http::header::AUTHORIZATION, format!("Token {token}"));
+    }
+
+    let res = match req.send().await {
+        Ok(resp) => resp.error_for_status().map(|_| ()),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = res {
+        warn!(error=%e, n_events=buf.len(), "failed to write audit events");
+    }
+
+    buf.clear();
+}
+
+/// Serialise `events` as rows of the [`AUDIT_TABLE_NAME`] table.
+fn to_line_protocol(events: &[AuditEvent]) -> Vec<u8> {
+    events
+        .iter()
+        .fold(LineProtocolBuilder::new(), |lp, event| {
+            let mut lp = lp
+                .measurement(AUDIT_TABLE_NAME)
+                .tag("operation", &event.operation)
+                .tag("outcome", event.outcome.as_str());
+            if let Some(namespace) = &event.namespace {
+                lp = lp.tag("namespace", namespace);
+            }
+            if let Some(table) = &event.table {
+                lp = lp.tag("table", table);
+            }
+
+            let mut lp = lp.field("subject", event.subject.as_deref().unwrap_or_default());
+            if let Some(before) = &event.before {
+                lp = lp.field("before", before.to_string().as_str());
+            }
+            if let Some(after) = &event.after {
+                lp = lp.field("after", after.to_string().as_str());
+            }
+
+            lp.timestamp(event.time.timestamp_nanos()).close_line()
+        })
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use authz::audit::AuditOutcome;
+    use iox_time::Time;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_to_line_protocol() {
+        let events = [
+            AuditEvent {
+                time: Time::from_timestamp_nanos(42),
+                ..AuditEvent::new("authz:write", AuditOutcome::Denied)
+            }
+            .with_namespace("bananas")
+            .with_table("cpu"),
+            AuditEvent {
+                time: Time::from_timestamp_nanos(43),
+                subject: Some("jwt:telegraf".to_string()),
+                ..AuditEvent::new("update_namespace_retention", AuditOutcome::Succeeded)
+            }
+            .with_namespace("bananas")
+            .with_change(
+                Some(json!({"retention_period_ns": null})),
+                Some(json!({"retention_period_ns": 1})),
+            ),
+        ];
+
+        let got = String::from_utf8(to_line_protocol(&events)).unwrap();
+        let want = [
+            r#"audit,operation=authz:write,outcome=denied,namespace=bananas,table=cpu subject="" 42"#,
+            r#"audit,operation=update_namespace_retention,outcome=succeeded,namespace=bananas subject="jwt:telegraf",before="{\"retention_period_ns\":null}",after="{\"retention_period_ns\":1}" 43"#,
+        ];
+        assert_eq!(got.lines().collect::<Vec<_>>(), want);
+
+        // The output must be accepted by the line protocol parser.
+        let lines = influxdb_line_protocol::parse_lines(&got)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(lines.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_disabled() {
+        let config = AuditConfig {
+            audit_log_file: None,
+            audit_router_addr: None,
+            audit_org: "iox".to_string(),
+            audit_bucket: "audit".to_string(),
+            audit_token: None,
+        };
+        assert!(audit_sink_from_config(&config).await.unwrap().is_none());
+    }
+}
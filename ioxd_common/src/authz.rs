@@ -0,0 +1,59 @@
+//! Construction of the request [`Authorizer`] from CLI config.
+
+use std::sync::Arc;
+
+use authz::{Authorizer, IoxAuthorizer, JwtAuthorizer, LoadError, TokenFileAuthorizer};
+use clap_blocks::authz::AuthzConfig;
+use snafu::Snafu;
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display("failed to create authz client for {addr}: {source}"))]
+    Address {
+        addr: String,
+        source: Box<dyn std::error::Error>,
+    },
+
+    #[snafu(display("failed to load authz token file: {source}"))]
+    TokenFile { source: LoadError },
+
+    #[snafu(display("failed to load authz JWKS file: {source}"))]
+    Jwks { source: LoadError },
+}
+
+/// Initialise the [`Authorizer`] selected by `config`, returning [`None`] if
+/// no authorizer is configured.
+///
+/// The authorizer is probed before being returned, waiting until an
+/// external authz service (if any) is reachable.
+pub async fn authorizer_from_config(
+    config: &AuthzConfig,
+) -> Result<Option<Arc<dyn Authorizer>>, Error> {
+    let authz: Arc<dyn Authorizer> = if let Some(addr) = &config.authz_address {
+        IoxAuthorizer::connect_lazy(addr.clone())
+            .map(|c| Arc::new(c) as _)
+            .map_err(|source| Error::Address {
+                addr: addr.clone(),
+                source,
+            })?
+    } else if let Some(path) = &config.authz_token_file {
+        TokenFileAuthorizer::new(path.clone())
+            .map(|c| Arc::new(c) as _)
+            .map_err(|source| Error::TokenFile { source })?
+    } else if let Some(path) = &config.authz_jwks_file {
+        JwtAuthorizer::new(
+            path.clone(),
+            config.authz_jwt_issuer.clone(),
+            config.authz_jwt_audience.clone(),
+        )
+        .map(|c| Arc::new(c) as _)
+        .map_err(|source| Error::Jwks { source })?
+    } else {
+        return Ok(None);
+    };
+
+    authz.probe().await.expect("Authz connection test failed.");
+
+    Ok(Some(authz))
+}
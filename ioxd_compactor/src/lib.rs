@@ -20,7 +20,7 @@ use backoff::BackoffConfig;
 use clap_blocks::compactor::CompactorConfig;
 use compactor::{compactor::Compactor, config::Config};
 use hyper::{Body, Request, Response};
-use iox_catalog::interface::Catalog;
+use iox_catalog::{cache::CachingCatalog, interface::Catalog};
 use iox_query::exec::Executor;
 use iox_time::TimeProvider;
 use ioxd_common::{
@@ -40,6 +40,7 @@ use std::{
     sync::Arc,
     time::Duration,
 };
+use tokio::runtime::Handle;
 use tokio_util::sync::CancellationToken;
 use trace::TraceCollector;
 
@@ -200,12 +201,58 @@ pub async fn create_compactor_server_type(
     time_provider: Arc<dyn TimeProvider>,
     compactor_config: CompactorConfig,
 ) -> Arc<dyn ServerType> {
+    let compactor = Compactor::start(create_compactor_config(
+        common_state,
+        Arc::clone(&metric_registry),
+        catalog,
+        parquet_store_real,
+        parquet_store_scratchpad,
+        exec,
+        time_provider,
+        compactor_config,
+    ))
+    .await;
+
+    Arc::new(CompactorServerType::new(
+        compactor,
+        metric_registry,
+        common_state,
+    ))
+}
+
+/// Build the compactor [`Config`] from the command line configuration.
+#[allow(clippy::too_many_arguments)]
+pub fn create_compactor_config(
+    common_state: &CommonServerState,
+    metric_registry: Arc<metric::Registry>,
+    catalog: Arc<dyn Catalog>,
+    parquet_store_real: ParquetStorage,
+    parquet_store_scratchpad: ParquetStorage,
+    exec: Arc<Executor>,
+    time_provider: Arc<dyn TimeProvider>,
+    compactor_config: CompactorConfig,
+) -> Config {
     let backoff_config = BackoffConfig::default();
 
-    let compactor = Compactor::start(Config {
-        metric_registry: Arc::clone(&metric_registry),
+    let catalog_cache = compactor_config.catalog_cache_bytes.map(|ram_limit| {
+        Arc::new(CachingCatalog::new(
+            Arc::clone(&catalog),
+            Arc::clone(&metric_registry),
+            ram_limit,
+            compactor_config.catalog_cache_ttl,
+            &Handle::current(),
+        ))
+    });
+    let catalog = match &catalog_cache {
+        Some(cache) => Arc::clone(cache) as Arc<dyn Catalog>,
+        None => catalog,
+    };
+
+    Config {
+        metric_registry,
         trace_collector: common_state.trace_collector(),
         catalog,
+        catalog_cache,
         scheduler_config: convert_scheduler_config(
             compactor_config.compactor_scheduler_config.clone(),
         ),
@@ -233,17 +280,15 @@ pub async fn create_compactor_server_type(
         max_num_files_per_plan: compactor_config.max_num_files_per_plan,
         max_partition_fetch_queries_per_second: compactor_config
             .max_partition_fetch_queries_per_second,
+        gossip_shared_keys: compactor_config.gossip_config.shared_keys(),
+        gossip_protection: compactor_config.gossip_config.frame_protection(),
+        gossip_rpc_address: compactor_config
+            .gossip_config
+            .advertise_rpc_address(&common_state.run_config().grpc_bind_address),
         gossip_seeds: compactor_config.gossip_config.seed_list,
         gossip_bind_address: compactor_config
             .gossip_config
             .gossip_bind_address
             .map(Into::into),
-    })
-    .await;
-
-    Arc::new(CompactorServerType::new(
-        compactor,
-        metric_registry,
-        common_state,
-    ))
+    }
 }
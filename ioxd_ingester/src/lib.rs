@@ -216,6 +216,11 @@ pub async fn create_ingester_server_type(
         Some(v) => GossipConfig::Enabled {
             bind_addr: v.into(),
             peers: ingester_config.gossip_config.seed_list.clone(),
+            shared_keys: ingester_config.gossip_config.shared_keys(),
+            protection: ingester_config.gossip_config.frame_protection(),
+            rpc_address: ingester_config
+                .gossip_config
+                .advertise_rpc_address(&common_state.run_config().grpc_bind_address),
         },
     };
 
@@ -225,6 +230,7 @@ pub async fn create_ingester_server_type(
         PERSIST_BACKGROUND_FETCH_TIME,
         ingester_config.wal_directory.clone(),
         Duration::from_secs(ingester_config.wal_rotation_period_seconds),
+        ingester_config.wal_replay_salvage,
         exec,
         ingester_config.persist_max_parallelism,
         ingester_config.persist_queue_depth,
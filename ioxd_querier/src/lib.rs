@@ -13,25 +13,41 @@
 
 use generated_types::influxdata::iox::{
     catalog::v1::catalog_service_server::CatalogServiceServer,
+    cluster::v1::cluster_service_server::ClusterServiceServer, gossip::Topic,
     object_store::v1::object_store_service_server::ObjectStoreServiceServer,
     schema::v1::schema_service_server::SchemaServiceServer,
 };
 use service_grpc_catalog::CatalogService;
+use service_grpc_cluster::ClusterService;
 use service_grpc_object_store::ObjectStoreService;
 use service_grpc_schema::SchemaService;
 // Workaround for "unused crate" lint false positives.
 use workspace_hack as _;
 
 use async_trait::async_trait;
-use authz::{Authorizer, IoxAuthorizer};
-use clap_blocks::querier::QuerierConfig;
+use authz::{audit::AuditingAuthorizer, Authorizer};
+use cache_system::disk::DiskCache;
+use clap_blocks::{
+    gossip::GossipConfig, ingester_discovery::IngesterDiscoverySource, querier::QuerierConfig,
+};
+use data_types::Timestamp;
 use datafusion_util::config::register_iox_object_store;
+use gossip::{Bytes, Dispatcher, GossipHandle, Identity, TopicInterests};
+use gossip_compaction::rx::CompactionEventRx;
+use gossip_membership::{rx::MembershipRx, tx::MembershipTx, ClusterMembers, MemberInfo, Role};
+use gossip_parquet_file::rx::ParquetFileRx;
 use hyper::{Body, Request, Response};
+use ingester_discovery::{
+    DiscoveryTask, DnsDiscovery, DnsSrvDiscovery, FileDiscovery, IngesterDiscovery,
+    MembershipDiscovery,
+};
 use iox_catalog::interface::Catalog;
 use iox_query::exec::{Executor, ExecutorType};
-use iox_time::TimeProvider;
+use iox_time::{Time, TimeProvider};
 use ioxd_common::{
     add_service,
+    audit::audit_sink_from_config,
+    authz::authorizer_from_config,
     http::error::{HttpApiError, HttpApiErrorCode, HttpApiErrorSource},
     rpc::RpcBuilderInput,
     serve_builder,
@@ -40,7 +56,11 @@ use ioxd_common::{
 };
 use metric::Registry;
 use object_store::{DynObjectStore, ObjectStore};
-use querier::{create_ingester_connections, QuerierCatalogCache, QuerierDatabase, QuerierServer};
+use observability_deps::tracing::{info, warn};
+use querier::{
+    create_ingester_connections, AdmissionConfig, CatalogCacheGossip, IngesterAddresses,
+    PartitionSelection, PrefetchRequest, QuerierCatalogCache, QuerierDatabase, QuerierServer,
+};
 use std::{
     fmt::{Debug, Display},
     sync::Arc,
@@ -60,6 +80,20 @@ pub struct QuerierServerType {
     object_store: Arc<dyn ObjectStore>,
     trace_collector: Option<Arc<dyn TraceCollector>>,
     authz: Option<Arc<dyn Authorizer>>,
+
+    /// The gossip cluster membership view, served over gRPC.
+    cluster_members: Arc<ClusterMembers>,
+
+    /// The gossip reactor stops once all handles are dropped, so keep it alive for as long as the
+    /// server runs.
+    _gossip_handle: Option<Arc<GossipHandle<Topic>>>,
+
+    /// The local cluster membership announcer, if gossip is enabled.
+    _membership_tx: Option<MembershipTx>,
+
+    /// The task refreshing the set of queried ingesters, if discovery is
+    /// enabled.
+    _ingester_discovery: Option<DiscoveryTask>,
 }
 
 impl std::fmt::Debug for QuerierServerType {
@@ -105,12 +139,19 @@ impl ServerType for QuerierServerType {
         );
         add_service!(
             builder,
-            rpc::query::make_storage_server(Arc::clone(&self.database))
+            rpc::query::make_storage_server(
+                Arc::clone(&self.database),
+                self.authz.as_ref().map(Arc::clone)
+            )
         );
         add_service!(
             builder,
             rpc::namespace::namespace_service(Arc::clone(&self.database))
         );
+        add_service!(
+            builder,
+            rpc::cache::cache_service(Arc::clone(&self.database))
+        );
         add_service!(
             builder,
             SchemaServiceServer::new(SchemaService::new(Arc::clone(&self.catalog)))
@@ -126,6 +167,10 @@ impl ServerType for QuerierServerType {
                 Arc::clone(&self.object_store),
             ))
         );
+        add_service!(
+            builder,
+            ClusterServiceServer::new(ClusterService::new(Arc::clone(&self.cluster_members)))
+        );
 
         serve_builder!(builder);
 
@@ -188,27 +233,79 @@ pub enum Error {
     #[error("querier error: {0}")]
     Querier(#[from] querier::QuerierDatabaseError),
 
-    #[error("authz configuration error for '{addr}': '{source}'")]
-    AuthzConfig {
-        source: Box<dyn std::error::Error>,
-        addr: String,
-    },
+    #[error("authz configuration error: {0}")]
+    AuthzConfig(#[from] ioxd_common::authz::Error),
+
+    #[error("audit configuration error: {0}")]
+    AuditConfig(#[from] ioxd_common::audit::Error),
+
+    #[error("cannot open disk cache: {0}")]
+    DiskCache(#[source] std::io::Error),
+
+    /// An error binding the UDP socket for gossip communication.
+    #[error("failed to bind udp gossip socket: {0}")]
+    GossipBind(#[source] std::io::Error),
+
+    /// An error initialising the configured ingester discovery source.
+    #[error("ingester discovery configuration error: {0}")]
+    IngesterDiscovery(#[from] ingester_discovery::DiscoveryError),
 }
 
 /// Instantiate a querier server
 pub async fn create_querier_server_type(
     args: QuerierServerTypeArgs<'_>,
 ) -> Result<Arc<dyn ServerType>, Error> {
+    let disk_cache = match &args.querier_config.disk_cache_dir {
+        Some(dir) => Some(
+            DiskCache::new(
+                "object_store",
+                dir,
+                args.querier_config.disk_cache_bytes,
+                &args.metric_registry,
+            )
+            .await
+            .map_err(Error::DiskCache)?,
+        ),
+        None => None,
+    };
+
+    let startup_time = args.time_provider.now();
+    let cluster_members = Arc::new(ClusterMembers::new(Arc::clone(&args.time_provider)));
     let catalog_cache = Arc::new(QuerierCatalogCache::new(
         Arc::clone(&args.catalog),
         args.time_provider,
         Arc::clone(&args.metric_registry),
         Arc::clone(&args.object_store),
+        disk_cache,
         args.querier_config.ram_pool_metadata_bytes.bytes(),
         args.querier_config.ram_pool_data_bytes.bytes(),
         &Handle::current(),
     ));
 
+    // Optionally subscribe to the parquet file / compaction events, keeping the
+    // cached file lists up to date without waiting for the cache refresh, and
+    // announce this querier to the cluster.
+    let gossip_config = &args.querier_config.gossip_config;
+    let local = MemberInfo {
+        role: Role::Querier,
+        rpc_address: gossip_config
+            .advertise_rpc_address(&args.common_state.run_config().grpc_bind_address),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        started_at: startup_time,
+    };
+    let (gossip_handle, membership_tx) = match init_gossip(
+        gossip_config,
+        Arc::clone(&catalog_cache),
+        Arc::clone(&cluster_members),
+        local,
+        &args.metric_registry,
+    )
+    .await?
+    {
+        Some((handle, tx)) => (Some(handle), Some(tx)),
+        None => (None, None),
+    };
+
     // register cached object store with the execution context
     let parquet_store = catalog_cache.parquet_store();
     let runtime_env = args
@@ -223,38 +320,57 @@ pub async fn create_querier_server_type(
     );
     assert!(existing.is_none());
 
-    let authz = match &args.querier_config.authz_address {
-        Some(addr) => {
-            let authz = IoxAuthorizer::connect_lazy(addr.clone())
-                .map(|c| Arc::new(c) as Arc<dyn Authorizer>)
-                .map_err(|source| Error::AuthzConfig {
-                    source,
-                    addr: addr.clone(),
-                })?;
-            authz.probe().await.expect("Authz connection test failed.");
-
-            Some(authz)
+    let authz = authorizer_from_config(&args.querier_config.authz_config).await?;
+    let authz = match audit_sink_from_config(&args.querier_config.audit_config).await? {
+        Some(sink) => {
+            authz.map(|authz| Arc::new(AuditingAuthorizer::new(authz, sink)) as Arc<dyn Authorizer>)
         }
-        None => None,
+        None => authz,
     };
 
-    let ingester_connections = if args.querier_config.ingester_addresses.is_empty() {
-        None
-    } else {
-        let ingester_addresses = args
-            .querier_config
-            .ingester_addresses
-            .iter()
-            .map(|addr| addr.to_string().into())
-            .collect();
-        Some(create_ingester_connections(
-            ingester_addresses,
-            Arc::clone(&catalog_cache),
-            args.querier_config.ingester_circuit_breaker_threshold,
-            &args.trace_context_header_name,
-            args.querier_config.v2_ingester_api,
-        ))
-    };
+    let discovery_source = args.querier_config.ingester_discovery_config.source();
+    let (ingester_connections, ingester_discovery) =
+        if args.querier_config.ingester_addresses.is_empty() && discovery_source.is_none() {
+            (None, None)
+        } else {
+            let ingester_addresses = IngesterAddresses::new(
+                args.querier_config
+                    .ingester_addresses
+                    .iter()
+                    .map(|addr| addr.to_string().into()),
+            );
+
+            // Optionally discover the queried ingesters at runtime.
+            let ingester_discovery = match discovery_source {
+                Some(source) => {
+                    let discovery = init_ingester_discovery(source, &cluster_members)?;
+                    info!(discovery = discovery.name(), "enabled ingester discovery");
+                    let addrs = ingester_addresses.clone();
+                    Some(DiscoveryTask::spawn(
+                        discovery,
+                        args.querier_config
+                            .ingester_discovery_config
+                            .ingester_discovery_interval,
+                        &args.metric_registry,
+                        move |discovered| {
+                            if addrs.set(discovered.iter().cloned()) {
+                                info!(ingesters = ?discovered, "updated discovered ingester set");
+                            }
+                        },
+                    ))
+                }
+                None => None,
+            };
+
+            let ingester_connections = create_ingester_connections(
+                ingester_addresses,
+                Arc::clone(&catalog_cache),
+                args.querier_config.ingester_circuit_breaker_threshold,
+                &args.trace_context_header_name,
+                args.querier_config.v2_ingester_api,
+            );
+            (Some(ingester_connections), ingester_discovery)
+        };
 
     let database = Arc::new(
         QuerierDatabase::new(
@@ -265,9 +381,31 @@ pub async fn create_querier_server_type(
             args.querier_config.max_concurrent_queries,
             Arc::new(args.querier_config.datafusion_config),
         )
-        .await?,
+        .await?
+        .with_admission_config(AdmissionConfig {
+            max_concurrent_queries_per_token: args.querier_config.max_concurrent_queries_per_token,
+            queue_timeout: args.querier_config.query_queue_timeout,
+        })
+        .with_cluster_members(Arc::clone(&cluster_members)),
     );
 
+    // Optionally warm up the caches, either in the background or before any request is served.
+    if let Some(partitions) = warmup_selection(&args.querier_config, startup_time) {
+        let warmup = warmup(
+            Arc::clone(&database),
+            PrefetchRequest {
+                namespace: None,
+                partitions,
+                parquet_data: args.querier_config.cache_warmup_parquet_data,
+            },
+        );
+        if args.querier_config.cache_warmup_wait {
+            warmup.await;
+        } else {
+            tokio::spawn(warmup);
+        }
+    }
+
     let server = QuerierServer::new(Arc::clone(&database));
     Ok(Arc::new(QuerierServerType {
         catalog: args.catalog,
@@ -277,5 +415,112 @@ pub async fn create_querier_server_type(
         object_store: args.object_store,
         trace_collector: args.common_state.trace_collector(),
         authz,
+        cluster_members,
+        _gossip_handle: gossip_handle,
+        _membership_tx: membership_tx,
+        _ingester_discovery: ingester_discovery,
     }))
 }
+
+/// Initialise the [`IngesterDiscovery`] implementation for `source`.
+fn init_ingester_discovery(
+    source: IngesterDiscoverySource,
+    cluster_members: &Arc<ClusterMembers>,
+) -> Result<Arc<dyn IngesterDiscovery>, Error> {
+    Ok(match source {
+        IngesterDiscoverySource::DnsSrv(name) => Arc::new(DnsSrvDiscovery::new(name)?),
+        IngesterDiscoverySource::Dns(host_port) => Arc::new(DnsDiscovery::new(host_port)),
+        IngesterDiscoverySource::File(path) => Arc::new(FileDiscovery::new(path)),
+        IngesterDiscoverySource::Gossip => {
+            Arc::new(MembershipDiscovery::new(Arc::clone(cluster_members)))
+        }
+    })
+}
+
+/// Partitions to warm up the caches with, if enabled.
+fn warmup_selection(config: &QuerierConfig, now: Time) -> Option<PartitionSelection> {
+    match (config.cache_warmup_window, config.cache_warmup_partitions) {
+        (Some(window), _) => Some(PartitionSelection::NewFileBetween {
+            start: now
+                .checked_sub(window)
+                .map(Timestamp::from)
+                .unwrap_or(Timestamp::new(0)),
+            end: None,
+        }),
+        (None, 0) => None,
+        (None, n) => Some(PartitionSelection::MostRecent(n)),
+    }
+}
+
+async fn warmup(database: Arc<QuerierDatabase>, request: PrefetchRequest) {
+    info!(?request, "starting cache warm-up");
+    match database.prefetch(request).await {
+        Ok(summary) => info!(?summary, "cache warm-up done"),
+        Err(e) => warn!(error=%e, "cache warm-up failed"),
+    }
+}
+
+/// Initialise the gossip subsystem, if enabled.
+///
+/// The querier listens for parquet file and compaction events and applies
+/// them to the catalog cache via [`CatalogCacheGossip`], and tracks the
+/// announcements of other cluster members in `cluster_members` while
+/// announcing itself as `local`.
+async fn init_gossip(
+    config: &GossipConfig,
+    catalog_cache: Arc<QuerierCatalogCache>,
+    cluster_members: Arc<ClusterMembers>,
+    local: MemberInfo,
+    metrics: &Arc<Registry>,
+) -> Result<Option<(Arc<GossipHandle<Topic>>, MembershipTx)>, Error> {
+    let Some(bind_addr) = config.gossip_bind_address else {
+        info!("gossip disabled");
+        return Ok(None);
+    };
+
+    let handler = Arc::new(CatalogCacheGossip::new(catalog_cache));
+    let dispatcher = GossipDemuxer {
+        parquet_file: ParquetFileRx::new(Arc::clone(&handler), 1_000),
+        compaction: CompactionEventRx::new(handler, 1_000),
+        membership: MembershipRx::new(Arc::clone(&cluster_members), 100),
+    };
+
+    let handle =
+        gossip::Builder::<_, Topic>::new(config.seed_list.clone(), dispatcher, Arc::clone(metrics))
+            .with_topic_filter(
+                TopicInterests::default()
+                    .with_topic(Topic::NewParquetFiles)
+                    .with_topic(Topic::CompactionEvents)
+                    .with_topic(Topic::ClusterMembership),
+            )
+            .with_shared_keys(config.shared_keys(), config.frame_protection())
+            .bind(*bind_addr)
+            .await
+            .map(Arc::new)
+            .map_err(Error::GossipBind)?;
+
+    cluster_members.set_local(handle.identity(), local.clone());
+    let membership_tx = MembershipTx::new(Arc::clone(&handle), local);
+
+    Ok(Some((handle, membership_tx)))
+}
+
+/// Routes the gossip topics the querier is interested in to their dispatchers.
+#[derive(Debug)]
+struct GossipDemuxer {
+    parquet_file: ParquetFileRx,
+    compaction: CompactionEventRx,
+    membership: MembershipRx,
+}
+
+#[async_trait]
+impl Dispatcher<Topic> for GossipDemuxer {
+    async fn dispatch(&self, topic: Topic, payload: Bytes, sender: Identity) {
+        match topic {
+            Topic::NewParquetFiles => self.parquet_file.dispatch(topic, payload, sender).await,
+            Topic::CompactionEvents => self.compaction.dispatch(topic, payload, sender).await,
+            Topic::ClusterMembership => self.membership.dispatch(topic, payload, sender).await,
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! CacheService gRPC implementation
+
+use data_types::Timestamp;
+use generated_types::influxdata::iox::querier::v1 as proto;
+use querier::{PartitionSelection, PrefetchError, PrefetchRequest, QuerierDatabase};
+use std::sync::Arc;
+
+/// Acquire a [`CacheService`](proto::cache_service_server::CacheService) gRPC service implementation.
+pub fn cache_service(
+    server: Arc<QuerierDatabase>,
+) -> proto::cache_service_server::CacheServiceServer<impl proto::cache_service_server::CacheService>
+{
+    proto::cache_service_server::CacheServiceServer::new(CacheServiceImpl::new(server))
+}
+
+#[derive(Debug)]
+struct CacheServiceImpl {
+    server: Arc<QuerierDatabase>,
+}
+
+impl CacheServiceImpl {
+    pub fn new(server: Arc<QuerierDatabase>) -> Self {
+        Self { server }
+    }
+}
+
+#[tonic::async_trait]
+impl proto::cache_service_server::CacheService for CacheServiceImpl {
+    async fn prefetch(
+        &self,
+        request: tonic::Request<proto::PrefetchRequest>,
+    ) -> Result<tonic::Response<proto::PrefetchResponse>, tonic::Status> {
+        let proto::PrefetchRequest {
+            namespace,
+            start_time_ns,
+            end_time_ns,
+            parquet_data,
+        } = request.into_inner();
+
+        if namespace.is_empty() {
+            return Err(tonic::Status::invalid_argument("namespace is required"));
+        }
+
+        let summary = self
+            .server
+            .prefetch(PrefetchRequest {
+                namespace: Some(namespace),
+                partitions: PartitionSelection::NewFileBetween {
+                    start: Timestamp::new(start_time_ns),
+                    end: end_time_ns.map(Timestamp::new),
+                },
+                parquet_data,
+            })
+            .await
+            .map_err(|e| match e {
+                PrefetchError::NamespaceNotFound { .. } => tonic::Status::not_found(e.to_string()),
+            })?;
+
+        Ok(tonic::Response::new(proto::PrefetchResponse {
+            tables: summary.tables as u64,
+            partitions: summary.partitions as u64,
+            parquet_files: summary.parquet_files as u64,
+            parquet_bytes: summary.parquet_bytes,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use data_types::ColumnType;
+    use generated_types::influxdata::iox::querier::v1::cache_service_server::CacheService;
+    use iox_tests::{TestCatalog, TestParquetFileBuilder};
+    use querier::{create_ingester_connection_for_testing, QuerierCatalogCache};
+    use tokio::runtime::Handle;
+
+    #[tokio::test]
+    async fn test_prefetch() {
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        let table = ns.create_table("table").await;
+        table.create_column("foo", ColumnType::F64).await;
+        table.create_column("time", ColumnType::Time).await;
+        let partition = table.create_partition("k").await;
+        partition
+            .create_parquet_file(
+                TestParquetFileBuilder::default().with_line_protocol("table foo=1 11"),
+            )
+            .await;
+
+        let catalog_cache = Arc::new(QuerierCatalogCache::new_testing(
+            catalog.catalog(),
+            catalog.time_provider(),
+            catalog.metric_registry(),
+            catalog.object_store(),
+            &Handle::current(),
+        ));
+        let db = Arc::new(
+            QuerierDatabase::new(
+                catalog_cache,
+                catalog.metric_registry(),
+                catalog.exec(),
+                Some(create_ingester_connection_for_testing()),
+                QuerierDatabase::MAX_CONCURRENT_QUERIES_MAX,
+                Arc::new(HashMap::default()),
+            )
+            .await
+            .unwrap(),
+        );
+        let service = CacheServiceImpl::new(db);
+
+        let response = service
+            .prefetch(tonic::Request::new(proto::PrefetchRequest {
+                namespace: "ns".to_owned(),
+                start_time_ns: 0,
+                end_time_ns: None,
+                parquet_data: true,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(response.tables, 1);
+        assert_eq!(response.partitions, 1);
+        assert_eq!(response.parquet_files, 1);
+        assert!(response.parquet_bytes > 0);
+
+        let status = service
+            .prefetch(tonic::Request::new(proto::PrefetchRequest {
+                namespace: "unknown".to_owned(),
+                start_time_ns: 0,
+                end_time_ns: None,
+                parquet_data: false,
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+
+        let status = service
+            .prefetch(tonic::Request::new(proto::PrefetchRequest::default()))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+}
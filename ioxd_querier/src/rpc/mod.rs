@@ -1,2 +1,3 @@
+pub(crate) mod cache;
 pub(crate) mod namespace;
 pub(crate) mod query;
@@ -39,6 +39,8 @@ fn namespace_to_proto(namespace: Namespace) -> proto::Namespace {
         max_tables: namespace.max_tables.get_i32(),
         max_columns_per_table: namespace.max_columns_per_table.get_i32(),
         partition_template: namespace.partition_template.as_proto().cloned(),
+        max_concurrent_queries: namespace.max_concurrent_queries,
+        max_query_memory_bytes: namespace.max_query_memory_bytes,
     }
 }
 
@@ -95,6 +97,15 @@ impl proto::namespace_service_server::NamespaceService for NamespaceServiceImpl
             "use router instances to manage namespaces",
         ))
     }
+
+    async fn update_namespace_query_quota(
+        &self,
+        _request: tonic::Request<proto::UpdateNamespaceQueryQuotaRequest>,
+    ) -> Result<tonic::Response<proto::UpdateNamespaceQueryQuotaResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "use router instances to manage namespaces",
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -184,6 +195,8 @@ mod tests {
                         max_tables: MaxTables::default().get_i32(),
                         max_columns_per_table: MaxColumnsPerTable::default().get_i32(),
                         partition_template: None,
+                        max_concurrent_queries: None,
+                        max_query_memory_bytes: None,
                     },
                     proto::Namespace {
                         id: 2,
@@ -192,6 +205,8 @@ mod tests {
                         max_tables: MaxTables::default().get_i32(),
                         max_columns_per_table: MaxColumnsPerTable::default().get_i32(),
                         partition_template: None,
+                        max_concurrent_queries: None,
+                        max_query_memory_bytes: None,
                     },
                 ]
             }
@@ -14,6 +14,9 @@ pub fn make_flight_server(
     service_grpc_flight::make_server(server, authz)
 }
 
-pub fn make_storage_server(server: Arc<QuerierDatabase>) -> StorageServer<impl Storage> {
-    service_grpc_influxrpc::make_server(server)
+pub fn make_storage_server(
+    server: Arc<QuerierDatabase>,
+    authz: Option<Arc<dyn Authorizer>>,
+) -> StorageServer<impl Storage> {
+    service_grpc_influxrpc::make_server(server, authz)
 }
@@ -13,8 +13,15 @@
 #![allow(clippy::default_constructed_unit_structs)]
 
 use gossip::{Bytes, Identity, TopicInterests};
+use gossip_membership::{rx::MembershipRx, tx::MembershipTx, ClusterMembers, MemberInfo, Role};
 use gossip_schema::{dispatcher::SchemaRx, handle::SchemaTx};
-use observability_deps::tracing::info;
+use ingester_discovery::{
+    DiscoveryTask, DnsDiscovery, DnsSrvDiscovery, FileDiscovery, IngesterDiscovery,
+    MembershipDiscovery,
+};
+use iox_time::{SystemProvider, TimeProvider};
+use observability_deps::tracing::{info, warn};
+use service_grpc_cluster::ClusterService;
 // Workaround for "unused crate" lint false positives.
 use workspace_hack as _;
 
@@ -25,18 +32,23 @@ use std::{
 };
 
 use async_trait::async_trait;
-use authz::{Authorizer, AuthorizerInstrumentation, IoxAuthorizer};
-use clap_blocks::{gossip::GossipConfig, router::RouterConfig};
+use authz::{audit::AuditingAuthorizer, Authorizer, AuthorizerInstrumentation};
+use clap_blocks::{
+    gossip::GossipConfig, ingester_discovery::IngesterDiscoverySource, router::RouterConfig,
+};
 use data_types::NamespaceName;
 use hashbrown::HashMap;
 use hyper::{Body, Request, Response};
 use iox_catalog::interface::Catalog;
 use ioxd_common::{
     add_service,
+    audit::audit_sink_from_config,
+    authz::authorizer_from_config,
     http::error::{HttpApiError, HttpApiErrorSource},
     reexport::{
         generated_types::influxdata::iox::{
             catalog::v1::catalog_service_server,
+            cluster::v1::cluster_service_server,
             gossip::{v1::anti_entropy_service_server, Topic},
             namespace::v1::namespace_service_server,
             object_store::v1::object_store_service_server,
@@ -56,7 +68,7 @@ use object_store::DynObjectStore;
 use router::{
     dml_handlers::{
         lazy_connector::LazyConnector, DmlHandler, DmlHandlerChainExt, FanOutAdaptor,
-        InstrumentationDecorator, Partitioner, RetentionValidator, RpcWrite,
+        InstrumentationDecorator, Partitioner, RetentionValidator, RpcWrite, UpstreamSet,
     },
     gossip::{
         anti_entropy::{
@@ -103,15 +115,32 @@ pub enum Error {
     #[error("Catalog DSN error: {0}")]
     CatalogDsn(#[from] clap_blocks::catalog_dsn::Error),
 
-    #[error("authz configuration error for '{addr}': '{source}'")]
-    AuthzConfig {
-        source: Box<dyn std::error::Error>,
-        addr: String,
-    },
+    #[error("authz configuration error: {0}")]
+    AuthzConfig(#[from] ioxd_common::authz::Error),
+
+    #[error("audit configuration error: {0}")]
+    AuditConfig(#[from] ioxd_common::audit::Error),
+
+    /// Audit events are written through a router that audits its writes,
+    /// without a token to exempt those writes from auditing.
+    #[error(
+        "an audit token must be set when writing audit events to a router with authz enabled, check INFLUXDB_IOX_AUDIT_TOKEN"
+    )]
+    AuditWithoutToken,
+
+    /// An authorizer was configured for a multi-tenant router.
+    #[error(
+        "authz is only supported for single tenant deployments, check INFLUXDB_IOX_SINGLE_TENANCY"
+    )]
+    AuthzWithoutSingleTenancy,
 
     /// An error binding the UDP socket for gossip communication.
     #[error("failed to bind udp gossip socket: {0}")]
     GossipBind(std::io::Error),
+
+    /// An error initialising the configured ingester discovery source.
+    #[error("ingester discovery configuration error: {0}")]
+    IngesterDiscovery(#[from] ingester_discovery::DiscoveryError),
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -120,6 +149,16 @@ pub struct RpcWriteRouterServerType<D, N, T> {
     server: RpcWriteRouterServer<D, N, T>,
     shutdown: CancellationToken,
     trace_collector: Option<Arc<dyn TraceCollector>>,
+
+    /// The gossip cluster membership view, served over gRPC.
+    cluster_members: Arc<ClusterMembers>,
+
+    /// The local cluster membership announcer, if gossip is enabled.
+    _membership_tx: Option<MembershipTx>,
+
+    /// The task refreshing the set of upstream ingesters, if discovery is
+    /// enabled.
+    _ingester_discovery: Option<DiscoveryTask>,
 }
 
 impl<D, N, T> RpcWriteRouterServerType<D, N, T> {
@@ -128,8 +167,25 @@ impl<D, N, T> RpcWriteRouterServerType<D, N, T> {
             server,
             shutdown: CancellationToken::new(),
             trace_collector: common_state.trace_collector(),
+            cluster_members: Arc::new(ClusterMembers::new(Arc::new(SystemProvider::new()))),
+            _membership_tx: None,
+            _ingester_discovery: None,
         }
     }
+
+    /// Serve the provided cluster membership view, keeping `membership_tx`
+    /// and `ingester_discovery` running for the lifetime of the server.
+    fn with_cluster_membership(
+        mut self,
+        cluster_members: Arc<ClusterMembers>,
+        membership_tx: Option<MembershipTx>,
+        ingester_discovery: Option<DiscoveryTask>,
+    ) -> Self {
+        self.cluster_members = cluster_members;
+        self._membership_tx = membership_tx;
+        self._ingester_discovery = ingester_discovery;
+        self
+    }
 }
 
 impl<D, N, T> std::fmt::Debug for RpcWriteRouterServerType<D, N, T> {
@@ -211,6 +267,12 @@ where
             .max_decoding_message_size(MAX_SYNC_MSG_SIZE)
             .max_encoding_message_size(MAX_SYNC_MSG_SIZE)
         );
+        add_service!(
+            builder,
+            cluster_service_server::ClusterServiceServer::new(ClusterService::new(Arc::clone(
+                &self.cluster_members
+            )))
+        );
         serve_builder!(builder);
 
         Ok(())
@@ -281,8 +343,14 @@ pub async fn create_router_server_type(
         &metrics,
         router_config.rpc_write_health_num_probes,
     );
+    let upstream_set = rpc_writer.upstream_set();
     let rpc_writer = InstrumentationDecorator::new("rpc_writer", &metrics, rpc_writer);
 
+    // Initialise the view of the cluster members, populated by gossip (if
+    // enabled).
+    let time_provider: Arc<dyn TimeProvider> = Arc::new(SystemProvider::new());
+    let cluster_members = Arc::new(ClusterMembers::new(Arc::clone(&time_provider)));
+
     // # Namespace cache
     //
     // Initialise an instrumented namespace cache to be shared with the schema
@@ -314,20 +382,51 @@ pub async fn create_router_server_type(
     // cache content, ensuring the MST remains in-sync.
     let ns_cache = MerkleTree::new(ns_cache, mst.clone());
 
-    // Optionally initialise the schema gossip subsystem.
-    let ns_cache = match gossip_config.gossip_bind_address {
-        Some(bind_addr) => MaybeLayer::With(
-            init_gossip(
+    // Optionally initialise the schema gossip & cluster membership subsystem.
+    let (ns_cache, membership_tx) = match gossip_config.gossip_bind_address {
+        Some(bind_addr) => {
+            let local = MemberInfo {
+                role: Role::Router,
+                rpc_address: gossip_config
+                    .advertise_rpc_address(&common_state.run_config().grpc_bind_address),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                started_at: time_provider.now(),
+            };
+            let (ns_cache, membership_tx) = init_gossip(
                 ns_cache,
                 *bind_addr,
-                gossip_config.seed_list.clone(),
+                gossip_config,
                 mst.clone(),
                 grpc_bind_port,
+                Arc::clone(&cluster_members),
+                local,
                 &metrics,
             )
-            .await?,
-        ),
-        None => MaybeLayer::Without(ns_cache),
+            .await?;
+            (MaybeLayer::With(ns_cache), Some(membership_tx))
+        }
+        None => (MaybeLayer::Without(ns_cache), None),
+    };
+
+    // Optionally discover the upstream ingesters at runtime.
+    let ingester_discovery = match router_config.ingester_discovery_config.source() {
+        Some(source) => {
+            let discovery = init_ingester_discovery(source, &cluster_members)?;
+            info!(discovery = discovery.name(), "enabled ingester discovery");
+            Some(DiscoveryTask::spawn(
+                discovery,
+                router_config
+                    .ingester_discovery_config
+                    .ingester_discovery_interval,
+                &metrics,
+                update_upstreams(
+                    upstream_set,
+                    router_config.clone(),
+                    trace_context_header_name.clone(),
+                ),
+            ))
+        }
+        None => None,
     };
 
     // Initialise the sync/anti-entropy RPC server, implementing the server-side
@@ -409,37 +508,47 @@ pub async fn create_router_server_type(
     // Record the overall request handling latency
     let handler_stack = InstrumentationDecorator::new("request", &metrics, handler_stack);
 
+    // Initialize the audit trail, if configured.
+    let audit_sink = audit_sink_from_config(&router_config.audit_config).await?;
+
     // Initialize the HTTP API delegate
+    let authz = authorizer_from_config(&router_config.authz_config)
+        .await?
+        .map(|authz| {
+            Arc::new(AuthorizerInstrumentation::new(&metrics, authz)) as Arc<dyn Authorizer>
+        })
+        .map(|authz| {
+            let Some(sink) = &audit_sink else {
+                return Ok(authz);
+            };
+            // Events written to the audit table through a router are
+            // themselves authorized, so the sink's writes must be exempt from
+            // auditing or each event would record another.
+            let audit_config = &router_config.audit_config;
+            let authz = AuditingAuthorizer::new(authz, Arc::clone(sink));
+            let authz = match (&audit_config.audit_router_addr, &audit_config.audit_token) {
+                (Some(_), Some(token)) => authz.with_exempt_token(token.as_bytes()),
+                (Some(_), None) => return Err(Error::AuditWithoutToken),
+                (None, _) => authz,
+            };
+            Ok(Arc::new(authz) as Arc<dyn Authorizer>)
+        })
+        .transpose()?;
+    let catalog_authz = authz.clone();
     let write_request_unifier: Result<Box<dyn WriteRequestUnifier>> = match (
         router_config.single_tenant_deployment,
-        &router_config.authz_address,
+        authz,
     ) {
-        (true, Some(addr)) => {
-            let authz = IoxAuthorizer::connect_lazy(addr.clone())
-                .map(|c| {
-                    Arc::new(AuthorizerInstrumentation::new(&metrics, c)) as Arc<dyn Authorizer>
-                })
-                .map_err(|source| Error::AuthzConfig {
-                    source,
-                    addr: addr.clone(),
-                })?;
-            authz.probe().await.expect("Authz connection test failed.");
-
-            Ok(Box::new(SingleTenantRequestUnifier::new(authz)))
-        }
+        (true, Some(authz)) => Ok(Box::new(SingleTenantRequestUnifier::new(authz))),
         (true, None) => {
             // Single tenancy was requested, but no auth was provided - the
             // router's clap flag parse configuration should not allow this
             // combination to be accepted and therefore execution should
             // never reach here.
-            unreachable!("INFLUXDB_IOX_SINGLE_TENANCY is set, but could not create an authz service. Check the INFLUXDB_IOX_AUTHZ_ADDR")
+            unreachable!("INFLUXDB_IOX_SINGLE_TENANCY is set, but could not create an authz service. Check the authz configuration")
         }
         (false, None) => Ok(Box::<MultiTenantRequestUnifier>::default()),
-        (false, Some(_)) => {
-            // As above, this combination should be prevented by the
-            // router's clap flag parse configuration.
-            unreachable!("INFLUXDB_IOX_AUTHZ_ADDR is set, but authz only exists for single_tenancy. Check the INFLUXDB_IOX_SINGLE_TENANCY")
-        }
+        (false, Some(_)) => Err(Error::AuthzWithoutSingleTenancy),
     };
     let http = HttpDelegate::new(
         common_state.run_config().max_http_request_size,
@@ -454,13 +563,93 @@ pub async fn create_router_server_type(
     // write router path and use it to create the relevant `RpcWriteRouterServer` and
     // `RpcWriteRouterServerType`.
     let grpc = RpcWriteGrpcDelegate::new(catalog, object_store, sync_rpc_server);
+    let grpc = match audit_sink {
+        Some(sink) => grpc.with_audit_sink(sink),
+        None => grpc,
+    };
+    let grpc = match router_config.catalog_write_access {
+        true => grpc.with_catalog_write_access(catalog_authz),
+        false => grpc,
+    };
 
     let router_server =
         RpcWriteRouterServer::new(http, grpc, metrics, common_state.trace_collector());
-    let server_type = Arc::new(RpcWriteRouterServerType::new(router_server, common_state));
+    let server_type = Arc::new(
+        RpcWriteRouterServerType::new(router_server, common_state).with_cluster_membership(
+            cluster_members,
+            membership_tx,
+            ingester_discovery,
+        ),
+    );
     Ok(server_type)
 }
 
+/// Initialise the [`IngesterDiscovery`] implementation for `source`.
+fn init_ingester_discovery(
+    source: IngesterDiscoverySource,
+    cluster_members: &Arc<ClusterMembers>,
+) -> Result<Arc<dyn IngesterDiscovery>> {
+    Ok(match source {
+        IngesterDiscoverySource::DnsSrv(name) => Arc::new(DnsSrvDiscovery::new(name)?),
+        IngesterDiscoverySource::Dns(host_port) => Arc::new(DnsDiscovery::new(host_port)),
+        IngesterDiscoverySource::File(path) => Arc::new(FileDiscovery::new(path)),
+        IngesterDiscoverySource::Gossip => {
+            Arc::new(MembershipDiscovery::new(Arc::clone(cluster_members)))
+        }
+    })
+}
+
+/// Return a discovery callback that replaces the set of upstream ingesters
+/// with the discovered addresses.
+///
+/// Connections to ingesters that remain in the set are retained, and removed
+/// ingesters are drained - writes already in flight to them run to
+/// completion, while new writes are only routed to the new set.
+fn update_upstreams(
+    upstreams: UpstreamSet<LazyConnector>,
+    router_config: RouterConfig,
+    trace_context_header_name: String,
+) -> impl FnMut(&[Arc<str>]) + Send + 'static {
+    move |addrs| {
+        let mut endpoints = HashMap::with_capacity(addrs.len());
+        for addr in addrs {
+            match Endpoint::from_shared(hyper::body::Bytes::from(addr.to_string())) {
+                Ok(endpoint) => {
+                    endpoints.insert(Arc::clone(addr), endpoint);
+                }
+                Err(e) => warn!(%addr, error=%e, "ignoring invalid discovered ingester address"),
+            }
+        }
+
+        if endpoints.is_empty() {
+            return;
+        }
+
+        let mut names = endpoints.keys().cloned().collect::<Vec<_>>();
+        names.sort_unstable();
+
+        let changed = upstreams.set(&names, |name| {
+            LazyConnector::new(
+                endpoints[name].clone(),
+                router_config.rpc_write_timeout_seconds,
+                router_config.rpc_write_max_outgoing_bytes,
+                trace_context_header_name.clone(),
+            )
+        });
+
+        if changed {
+            info!(ingesters = ?names, "updated discovered ingester set");
+            if names.len() < router_config.rpc_write_replicas.get() {
+                warn!(
+                    n_ingesters = names.len(),
+                    n_copies = router_config.rpc_write_replicas.get(),
+                    "fewer discovered ingesters than write replicas"
+                );
+            }
+        }
+    }
+}
+
 /// Pre-populate `cache` with the all existing schemas in `catalog`.
 async fn pre_warm_schema_cache<T>(
     cache: &T,
@@ -531,14 +720,17 @@ where
 // drive catalog queries themselves (defeating the point of the gossiping!).
 // If a local node has to perform a catalog lookup, it gossips the result to
 // other peers, helping converge them.
+#[allow(clippy::too_many_arguments)]
 async fn init_gossip<T>(
     ns_cache: MerkleTree<T>,
     bind_addr: SocketAddr,
-    seed_list: Vec<String>,
+    gossip_config: &GossipConfig,
     mst: AntiEntropyHandle,
     local_rpc_port: u16,
+    cluster_members: Arc<ClusterMembers>,
+    local: MemberInfo,
     metrics: &Arc<metric::Registry>,
-) -> Result<impl NamespaceCache<ReadError = CacheMissErr>, Error>
+) -> Result<(impl NamespaceCache<ReadError = CacheMissErr>, MembershipTx), Error>
 where
     T: NamespaceCache<ReadError = CacheMissErr> + 'static,
 {
@@ -561,21 +753,35 @@ where
     // Initialise the consistency probe dispatcher and layer it in a demuxer to
     // route the topics to the correct dispatcher implementations.
     let (probe_dispatcher, probe_rx) = ProbeDispatcher::new();
-    let dispatcher = GossipDemuxer::new(probe_dispatcher, schema_dispatcher);
+    let membership_dispatcher = MembershipRx::new(Arc::clone(&cluster_members), 100);
+    let dispatcher = GossipDemuxer::new(probe_dispatcher, schema_dispatcher, membership_dispatcher);
 
     // Initialise the gossip subsystem, delegating message processing to
     // the above dispatcher.
-    let handle = gossip::Builder::<_, Topic>::new(seed_list, dispatcher, Arc::clone(metrics))
-        // Configure the router to listen to SchemaChange messages.
-        .with_topic_filter(
-            TopicInterests::default()
-                .with_topic(Topic::SchemaChanges)
-                .with_topic(Topic::SchemaCacheConsistency),
-        )
-        .bind(bind_addr)
-        .await
-        .map(Arc::new)
-        .map_err(Error::GossipBind)?;
+    let handle = gossip::Builder::<_, Topic>::new(
+        gossip_config.seed_list.clone(),
+        dispatcher,
+        Arc::clone(metrics),
+    )
+    // Configure the router to listen to SchemaChange messages.
+    .with_topic_filter(
+        TopicInterests::default()
+            .with_topic(Topic::SchemaChanges)
+            .with_topic(Topic::SchemaCacheConsistency)
+            .with_topic(Topic::ClusterMembership),
+    )
+    .with_shared_keys(
+        gossip_config.shared_keys(),
+        gossip_config.frame_protection(),
+    )
+    .bind(bind_addr)
+    .await
+    .map(Arc::new)
+    .map_err(Error::GossipBind)?;
+
+    // Advertise the local router to the cluster.
+    cluster_members.set_local(handle.identity(), local.clone());
+    let membership_tx = MembershipTx::new(Arc::clone(&handle), local);
 
     // Initialise the local cache diff observer responsible for gossiping any
     // local changes made to the cache content.
@@ -612,19 +818,25 @@ where
     );
     tokio::spawn(convergence_actor.run());
 
-    Ok(ns_cache)
+    Ok((ns_cache, membership_tx))
 }
 
 struct GossipDemuxer {
     consistency_probe: ProbeDispatcher,
     schema_update: SchemaRx,
+    membership: MembershipRx,
 }
 
 impl GossipDemuxer {
-    fn new(consistency_probe: ProbeDispatcher, schema_update: SchemaRx) -> Self {
+    fn new(
+        consistency_probe: ProbeDispatcher,
+        schema_update: SchemaRx,
+        membership: MembershipRx,
+    ) -> Self {
         Self {
             consistency_probe,
             schema_update,
+            membership,
         }
     }
 }
@@ -639,6 +851,7 @@ impl gossip::Dispatcher<Topic> for GossipDemuxer {
                     .dispatch(topic, payload, sender)
                     .await
             }
+            Topic::ClusterMembership => self.membership.dispatch(topic, payload, sender).await,
             _ => {}
         }
     }
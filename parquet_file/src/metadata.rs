@@ -90,8 +90,8 @@ use base64::{prelude::BASE64_STANDARD, Engine};
 use bytes::Bytes;
 use data_types::{
     ColumnId, ColumnSet, ColumnSummary, CompactionLevel, InfluxDbType, NamespaceId,
-    ParquetFileParams, PartitionKey, StatValues, Statistics, TableId, Timestamp,
-    TransitionPartitionId,
+    ParquetFileParams, PartitionColumnStatistics, PartitionKey, StatValues, Statistics, TableId,
+    Timestamp, TransitionPartitionId,
 };
 use generated_types::influxdata::iox::ingester::v1 as proto;
 use iox_time::Time;
@@ -793,6 +793,44 @@ impl DecodedIoxParquetMetaData {
         Ok(column_summaries)
     }
 
+    /// Read the per-column [`PartitionColumnStatistics`] of this file from the
+    /// parquet metadata, resolving column names to IDs with `column_id_map`.
+    ///
+    /// The time column is omitted, as its range is recorded in the file's
+    /// `min_time` / `max_time`. Returns [`None`] if the NULL count of any
+    /// column is unknown.
+    pub fn read_partition_column_statistics<F>(
+        &self,
+        schema: &Schema,
+        column_id_map: F,
+    ) -> Result<Option<Vec<PartitionColumnStatistics>>>
+    where
+        F: for<'a> Fn(&'a str) -> ColumnId,
+    {
+        let mut out = vec![];
+        for summary in self.read_statistics(schema)? {
+            let (min, max) = match (summary.influxdb_type, &summary.stats) {
+                (InfluxDbType::Timestamp, _) => continue,
+                (InfluxDbType::Tag, Statistics::String(s)) => (s.min.clone(), s.max.clone()),
+                _ => (None, None),
+            };
+            let Some(null_count) = summary.stats.null_count() else {
+                return Ok(None);
+            };
+
+            out.push(PartitionColumnStatistics {
+                column_id: column_id_map(&summary.name),
+                null_count: null_count as i64,
+                min,
+                max,
+                values: None,
+            });
+        }
+
+        out.sort_unstable_by_key(|c| c.column_id);
+        Ok(Some(out))
+    }
+
     /// Estimate the memory consumption of this object and its contents
     pub fn size(&self) -> usize {
         // This is likely a wild under count as it doesn't include
@@ -0,0 +1,152 @@
+//! Per-namespace memory budget.
+use datafusion::{
+    error::{DataFusionError, Result},
+    execution::memory_pool::{MemoryConsumer, MemoryPool, MemoryReservation},
+};
+use metric::U64Counter;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+/// A [`MemoryPool`] that caps the memory reserved by all queries against a single namespace.
+///
+/// Every reservation within the budget is forwarded to the querier-wide pool, so the namespace
+/// budget is carved out of the global one and never extends it.
+#[derive(Debug)]
+pub(crate) struct NamespaceMemoryPool {
+    namespace: Arc<str>,
+    inner: Arc<dyn MemoryPool>,
+    limit: AtomicUsize,
+    reserved: AtomicUsize,
+    rejected: U64Counter,
+}
+
+impl NamespaceMemoryPool {
+    pub(crate) fn new(
+        namespace: Arc<str>,
+        inner: Arc<dyn MemoryPool>,
+        limit: usize,
+        rejected: U64Counter,
+    ) -> Self {
+        Self {
+            namespace,
+            inner,
+            limit: AtomicUsize::new(limit),
+            reserved: AtomicUsize::new(0),
+            rejected,
+        }
+    }
+
+    /// Change the budget.
+    ///
+    /// Existing reservations are kept even if they exceed the new limit.
+    pub(crate) fn set_limit(&self, limit: usize) {
+        self.limit.store(limit, Ordering::Relaxed);
+    }
+}
+
+impl MemoryPool for NamespaceMemoryPool {
+    fn register(&self, consumer: &MemoryConsumer) {
+        self.inner.register(consumer)
+    }
+
+    fn unregister(&self, consumer: &MemoryConsumer) {
+        self.inner.unregister(consumer)
+    }
+
+    fn grow(&self, reservation: &MemoryReservation, additional: usize) {
+        self.reserved.fetch_add(additional, Ordering::Relaxed);
+        self.inner.grow(reservation, additional);
+    }
+
+    fn shrink(&self, reservation: &MemoryReservation, shrink: usize) {
+        self.reserved.fetch_sub(shrink, Ordering::Relaxed);
+        self.inner.shrink(reservation, shrink);
+    }
+
+    fn try_grow(&self, reservation: &MemoryReservation, additional: usize) -> Result<()> {
+        let limit = self.limit.load(Ordering::Relaxed);
+        self.reserved
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |reserved| {
+                reserved
+                    .checked_add(additional)
+                    .filter(|&new_reserved| new_reserved <= limit)
+            })
+            .map_err(|reserved| {
+                self.rejected.inc(1);
+                DataFusionError::ResourcesExhausted(format!(
+                    "Failed to allocate additional {additional} bytes: query memory limit of \
+                     namespace {} is {limit} bytes and {reserved} bytes are already reserved",
+                    self.namespace,
+                ))
+            })?;
+
+        if let Err(e) = self.inner.try_grow(reservation, additional) {
+            self.reserved.fetch_sub(additional, Ordering::Relaxed);
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    fn reserved(&self) -> usize {
+        self.reserved.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use datafusion::execution::memory_pool::GreedyMemoryPool;
+
+    #[test]
+    fn test_budget() {
+        let inner: Arc<dyn MemoryPool> = Arc::new(GreedyMemoryPool::new(100));
+        let rejected = U64Counter::default();
+        let pool: Arc<dyn MemoryPool> = Arc::new(NamespaceMemoryPool::new(
+            Arc::from("ns"),
+            Arc::clone(&inner),
+            10,
+            rejected.clone(),
+        ));
+
+        let mut r1 = MemoryConsumer::new("r1").register(&pool);
+        r1.try_grow(8).unwrap();
+        assert_eq!(pool.reserved(), 8);
+        assert_eq!(inner.reserved(), 8);
+
+        let mut r2 = MemoryConsumer::new("r2").register(&pool);
+        let err = r2.try_grow(4).unwrap_err();
+        assert_matches!(err, DataFusionError::ResourcesExhausted(_));
+        assert_eq!(rejected.fetch(), 1);
+        assert_eq!(pool.reserved(), 8);
+        assert_eq!(inner.reserved(), 8);
+
+        r1.shrink(4);
+        r2.try_grow(4).unwrap();
+        assert_eq!(pool.reserved(), 8);
+
+        drop(r1);
+        drop(r2);
+        assert_eq!(pool.reserved(), 0);
+        assert_eq!(inner.reserved(), 0);
+    }
+
+    #[test]
+    fn test_global_pool_is_respected() {
+        let inner: Arc<dyn MemoryPool> = Arc::new(GreedyMemoryPool::new(5));
+        let pool: Arc<dyn MemoryPool> = Arc::new(NamespaceMemoryPool::new(
+            Arc::from("ns"),
+            Arc::clone(&inner),
+            10,
+            U64Counter::default(),
+        ));
+
+        let mut r = MemoryConsumer::new("r").register(&pool);
+        r.try_grow(8).unwrap_err();
+        assert_eq!(pool.reserved(), 0);
+        assert_eq!(inner.reserved(), 0);
+    }
+}
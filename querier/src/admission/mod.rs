@@ -0,0 +1,530 @@
+//! Per-namespace and per-token admission control for queries.
+use crate::QuerierDatabase;
+use data_types::Namespace;
+use datafusion::execution::memory_pool::MemoryPool;
+use futures::future::BoxFuture;
+use metric::U64Counter;
+use parking_lot::Mutex;
+use service_common::admission::{AdmissionError, QueryAdmission};
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use trace::span::{Span, SpanRecorder};
+use tracker::{
+    AsyncSemaphoreMetrics, InstrumentedAsyncOwnedSemaphorePermit, InstrumentedAsyncSemaphore,
+};
+
+mod memory_pool;
+
+pub(crate) use memory_pool::NamespaceMemoryPool;
+
+/// Default time a query may wait for a free slot before it is rejected.
+pub const DEFAULT_QUERY_QUEUE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Query limits of a single namespace, as configured in the catalog.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueryQuota {
+    /// Maximum number of queries that may run against the namespace at the same time.
+    pub max_concurrent_queries: Option<NonZeroUsize>,
+
+    /// Maximum number of bytes that all queries against the namespace may reserve from the
+    /// querier's memory pool.
+    pub max_memory_bytes: Option<NonZeroUsize>,
+}
+
+impl From<&Namespace> for QueryQuota {
+    fn from(namespace: &Namespace) -> Self {
+        Self {
+            max_concurrent_queries: namespace
+                .max_concurrent_queries
+                .and_then(|v| usize::try_from(v).ok())
+                .and_then(NonZeroUsize::new),
+            max_memory_bytes: namespace
+                .max_query_memory_bytes
+                .and_then(|v| usize::try_from(v).ok())
+                .and_then(NonZeroUsize::new),
+        }
+    }
+}
+
+/// Querier-wide admission settings that are not stored in the catalog.
+#[derive(Debug, Clone, Copy)]
+pub struct AdmissionConfig {
+    /// Maximum number of queries a single authorization token may run at the same time.
+    pub max_concurrent_queries_per_token: Option<NonZeroUsize>,
+
+    /// How long a query waits for free slots before it is rejected.
+    pub queue_timeout: Duration,
+}
+
+impl Default for AdmissionConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_queries_per_token: None,
+            queue_timeout: DEFAULT_QUERY_QUEUE_TIMEOUT,
+        }
+    }
+}
+
+/// A semaphore together with the limit it was created for.
+#[derive(Debug)]
+struct LimitedSemaphore {
+    limit: NonZeroUsize,
+    semaphore: Arc<InstrumentedAsyncSemaphore>,
+}
+
+impl LimitedSemaphore {
+    /// Returns true if nobody holds or waits for a permit.
+    fn is_idle(&self) -> bool {
+        Arc::strong_count(&self.semaphore) == 1
+            && self.semaphore.available_permits() == self.semaphore.total_permits()
+    }
+
+    /// Resize the semaphore to `limit`, keeping the permits held by running queries.
+    ///
+    /// Growing takes effect immediately. Shrinking has to wait for running queries to release
+    /// their permits, which the returned future does; it must be started before queueing for a
+    /// permit, see [`shrink`].
+    fn resize(&mut self, limit: NonZeroUsize) -> Option<Shrink> {
+        let (old, new) = (permits(self.limit), permits(limit));
+        self.limit = limit;
+
+        if new > old {
+            self.semaphore.add_permits(new - old);
+            None
+        } else if new < old {
+            let n = u32::try_from(old - new).expect("permits are capped");
+            Some(Box::pin(self.semaphore.forget_permits(n)))
+        } else {
+            None
+        }
+    }
+}
+
+/// Pending removal of permits from a [`LimitedSemaphore`].
+type Shrink = BoxFuture<'static, ()>;
+
+/// Start removing permits from a semaphore, completing in the background if they are in use.
+///
+/// The semaphore is fair, so once started, queries queue up behind the removal and are admitted
+/// within the new limit.
+async fn shrink(shrink: Option<Shrink>) {
+    if let Some(mut shrink) = shrink {
+        if futures::poll!(shrink.as_mut()).is_pending() {
+            tokio::spawn(shrink);
+        }
+    }
+}
+
+/// Number of permits of a semaphore for `limit`.
+fn permits(limit: NonZeroUsize) -> usize {
+    limit.get().min(QuerierDatabase::MAX_CONCURRENT_QUERIES_MAX)
+}
+
+/// Queues and admits queries based on per-namespace and per-token limits.
+///
+/// Semaphores are created lazily. If the limit of a namespace changes (i.e. the cached namespace
+/// was refreshed), its semaphore is resized: queries that hold permits keep counting against the
+/// new limit, and no further query is admitted until enough of them finished.
+#[derive(Debug)]
+pub(crate) struct QueryAdmissionController {
+    config: AdmissionConfig,
+    namespace_semaphore_metrics: Arc<AsyncSemaphoreMetrics>,
+    token_semaphore_metrics: Arc<AsyncSemaphoreMetrics>,
+    namespaces: Mutex<HashMap<Arc<str>, LimitedSemaphore>>,
+    tokens: Mutex<HashMap<Vec<u8>, LimitedSemaphore>>,
+    memory_pools: Mutex<HashMap<Arc<str>, Arc<NamespaceMemoryPool>>>,
+    rejected_namespace: U64Counter,
+    rejected_token: U64Counter,
+    rejected_memory: U64Counter,
+}
+
+impl QueryAdmissionController {
+    pub(crate) fn new(metric_registry: &metric::Registry, config: AdmissionConfig) -> Self {
+        let namespace_semaphore_metrics = Arc::new(AsyncSemaphoreMetrics::new(
+            metric_registry,
+            &[("semaphore", "query_namespace")],
+        ));
+        let token_semaphore_metrics = Arc::new(AsyncSemaphoreMetrics::new(
+            metric_registry,
+            &[("semaphore", "query_token")],
+        ));
+
+        let rejected = metric_registry.register_metric::<U64Counter>(
+            "query_admission_rejected",
+            "Number of queries rejected because a namespace or token limit was exceeded",
+        );
+
+        Self {
+            config,
+            namespace_semaphore_metrics,
+            token_semaphore_metrics,
+            namespaces: Default::default(),
+            tokens: Default::default(),
+            memory_pools: Default::default(),
+            rejected_namespace: rejected.recorder(&[("limit", "namespace")]),
+            rejected_token: rejected.recorder(&[("limit", "token")]),
+            rejected_memory: rejected.recorder(&[("limit", "memory")]),
+        }
+    }
+
+    pub(crate) fn set_config(&mut self, config: AdmissionConfig) {
+        self.config = config;
+    }
+
+    /// Wait for the token and namespace slots of a query.
+    ///
+    /// Both waits share a single deadline of [`AdmissionConfig::queue_timeout`].
+    pub(crate) async fn admit(
+        &self,
+        namespace: &str,
+        quota: QueryQuota,
+        token: Option<&[u8]>,
+        span: Option<Span>,
+    ) -> Result<QueryAdmission, AdmissionError> {
+        let span_recorder = SpanRecorder::new(span);
+        let timeout = self.config.queue_timeout;
+        let deadline = Instant::now() + timeout;
+        let mut admission = QueryAdmission::default();
+
+        if let (Some(limit), Some(token)) = (self.config.max_concurrent_queries_per_token, token) {
+            let (semaphore, resized) = self.token_semaphore(token, limit);
+            shrink(resized).await;
+            let permit = acquire(
+                &semaphore,
+                deadline,
+                span_recorder.child_span("acquire token slot"),
+            )
+            .await
+            .ok_or_else(|| {
+                self.rejected_token.inc(1);
+                AdmissionError::TokenConcurrency {
+                    limit: limit.get(),
+                    timeout,
+                }
+            })?;
+            admission = admission.with_permit(permit);
+        }
+
+        match quota.max_concurrent_queries {
+            Some(limit) => {
+                let (semaphore, resized) = self.namespace_semaphore(namespace, limit);
+                shrink(resized).await;
+                let permit = acquire(
+                    &semaphore,
+                    deadline,
+                    span_recorder.child_span("acquire namespace slot"),
+                )
+                .await
+                .ok_or_else(|| {
+                    self.rejected_namespace.inc(1);
+                    AdmissionError::NamespaceConcurrency {
+                        namespace: namespace.to_owned(),
+                        limit: limit.get(),
+                        timeout,
+                    }
+                })?;
+                admission = admission.with_permit(permit);
+            }
+            None => {
+                self.namespaces.lock().remove(namespace);
+            }
+        }
+
+        Ok(admission)
+    }
+
+    /// Get the memory pool that enforces the memory budget of the given namespace, if it has
+    /// any.
+    pub(crate) fn memory_pool(
+        &self,
+        namespace: &Arc<str>,
+        quota: QueryQuota,
+        inner: &Arc<dyn MemoryPool>,
+    ) -> Option<Arc<dyn MemoryPool>> {
+        let mut pools = self.memory_pools.lock();
+        let Some(limit) = quota.max_memory_bytes else {
+            pools.remove(namespace);
+            return None;
+        };
+
+        let pool = pools.entry(Arc::clone(namespace)).or_insert_with(|| {
+            Arc::new(NamespaceMemoryPool::new(
+                Arc::clone(namespace),
+                Arc::clone(inner),
+                limit.get(),
+                self.rejected_memory.clone(),
+            ))
+        });
+        pool.set_limit(limit.get());
+        Some(Arc::clone(pool) as _)
+    }
+
+    fn namespace_semaphore(
+        &self,
+        namespace: &str,
+        limit: NonZeroUsize,
+    ) -> (Arc<InstrumentedAsyncSemaphore>, Option<Shrink>) {
+        let mut namespaces = self.namespaces.lock();
+        match namespaces.get_mut(namespace) {
+            Some(s) => {
+                let resized = s.resize(limit);
+                (Arc::clone(&s.semaphore), resized)
+            }
+            None => {
+                let s = self.new_semaphore(&self.namespace_semaphore_metrics, limit);
+                let semaphore = Arc::clone(&s.semaphore);
+                namespaces.insert(Arc::from(namespace), s);
+                (semaphore, None)
+            }
+        }
+    }
+
+    fn token_semaphore(
+        &self,
+        token: &[u8],
+        limit: NonZeroUsize,
+    ) -> (Arc<InstrumentedAsyncSemaphore>, Option<Shrink>) {
+        let mut tokens = self.tokens.lock();
+        match tokens.get_mut(token) {
+            Some(s) => {
+                let resized = s.resize(limit);
+                (Arc::clone(&s.semaphore), resized)
+            }
+            None => {
+                // tokens are not catalog entities and can be created at any time, so drop the
+                // ones that are not in use anymore
+                tokens.retain(|_, s| !s.is_idle());
+
+                let s = self.new_semaphore(&self.token_semaphore_metrics, limit);
+                let semaphore = Arc::clone(&s.semaphore);
+                tokens.insert(token.to_vec(), s);
+                (semaphore, None)
+            }
+        }
+    }
+
+    fn new_semaphore(
+        &self,
+        metrics: &Arc<AsyncSemaphoreMetrics>,
+        limit: NonZeroUsize,
+    ) -> LimitedSemaphore {
+        LimitedSemaphore {
+            limit,
+            semaphore: Arc::new(metrics.new_semaphore(permits(limit))),
+        }
+    }
+}
+
+/// Acquire a permit, giving up at `deadline`.
+async fn acquire(
+    semaphore: &Arc<InstrumentedAsyncSemaphore>,
+    deadline: Instant,
+    span: Option<Span>,
+) -> Option<InstrumentedAsyncOwnedSemaphorePermit> {
+    tokio::time::timeout_at(deadline.into(), semaphore.acquire_owned(span))
+        .await
+        .ok()
+        .map(|res| res.expect("Semaphore should not be closed by anyone"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use metric::{Attributes, Metric};
+
+    const TIMEOUT: Duration = Duration::from_millis(10);
+
+    fn quota(max_concurrent_queries: usize) -> QueryQuota {
+        QueryQuota {
+            max_concurrent_queries: NonZeroUsize::new(max_concurrent_queries),
+            max_memory_bytes: None,
+        }
+    }
+
+    fn rejected(registry: &metric::Registry, limit: &'static str) -> u64 {
+        registry
+            .get_instrument::<Metric<U64Counter>>("query_admission_rejected")
+            .unwrap()
+            .get_observer(&Attributes::from(&[("limit", limit)]))
+            .unwrap()
+            .fetch()
+    }
+
+    #[test]
+    fn test_quota_from_namespace() {
+        let mut ns = Namespace {
+            id: data_types::NamespaceId::new(1),
+            name: "ns".to_string(),
+            retention_period_ns: None,
+            max_tables: Default::default(),
+            max_columns_per_table: Default::default(),
+            max_concurrent_queries: Some(3),
+            max_query_memory_bytes: Some(1024),
+            deleted_at: None,
+            partition_template: Default::default(),
+        };
+        assert_eq!(
+            QueryQuota::from(&ns),
+            QueryQuota {
+                max_concurrent_queries: NonZeroUsize::new(3),
+                max_memory_bytes: NonZeroUsize::new(1024),
+            }
+        );
+
+        ns.max_concurrent_queries = Some(0);
+        ns.max_query_memory_bytes = Some(-1);
+        assert_eq!(QueryQuota::from(&ns), QueryQuota::default());
+    }
+
+    #[tokio::test]
+    async fn test_namespace_limit() {
+        let registry = metric::Registry::new();
+        let controller = QueryAdmissionController::new(
+            &registry,
+            AdmissionConfig {
+                max_concurrent_queries_per_token: None,
+                queue_timeout: TIMEOUT,
+            },
+        );
+
+        let a1 = controller.admit("ns", quota(1), None, None).await.unwrap();
+        assert_eq!(a1.len(), 1);
+
+        // other namespaces are not affected
+        controller
+            .admit("other", quota(1), None, None)
+            .await
+            .unwrap();
+
+        let err = controller
+            .admit("ns", quota(1), None, None)
+            .await
+            .unwrap_err();
+        assert_matches!(err, AdmissionError::NamespaceConcurrency { limit: 1, .. });
+        assert_eq!(rejected(&registry, "namespace"), 1);
+
+        drop(a1);
+        controller.admit("ns", quota(1), None, None).await.unwrap();
+
+        // without a limit, no permit is taken
+        let a = controller
+            .admit("ns", QueryQuota::default(), None, None)
+            .await
+            .unwrap();
+        assert!(a.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_queued_query_is_admitted() {
+        let controller = Arc::new(QueryAdmissionController::new(
+            &metric::Registry::new(),
+            AdmissionConfig {
+                max_concurrent_queries_per_token: None,
+                queue_timeout: Duration::from_secs(10),
+            },
+        ));
+
+        let a1 = controller.admit("ns", quota(1), None, None).await.unwrap();
+
+        let controller_captured = Arc::clone(&controller);
+        let queued = tokio::spawn(async move {
+            controller_captured
+                .admit("ns", quota(1), None, None)
+                .await
+                .map(|a| a.len())
+        });
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!queued.is_finished());
+
+        drop(a1);
+        assert_eq!(queued.await.unwrap().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_token_limit() {
+        let registry = metric::Registry::new();
+        let controller = QueryAdmissionController::new(
+            &registry,
+            AdmissionConfig {
+                max_concurrent_queries_per_token: NonZeroUsize::new(1),
+                queue_timeout: TIMEOUT,
+            },
+        );
+
+        let a1 = controller
+            .admit("ns1", QueryQuota::default(), Some(b"t1"), None)
+            .await
+            .unwrap();
+        assert_eq!(a1.len(), 1);
+
+        // same token, different namespace
+        let err = controller
+            .admit("ns2", QueryQuota::default(), Some(b"t1"), None)
+            .await
+            .unwrap_err();
+        assert_matches!(err, AdmissionError::TokenConcurrency { limit: 1, .. });
+        assert_eq!(rejected(&registry, "token"), 1);
+
+        // other tokens and unauthenticated queries are not affected
+        controller
+            .admit("ns1", QueryQuota::default(), Some(b"t2"), None)
+            .await
+            .unwrap();
+        let a = controller
+            .admit("ns1", QueryQuota::default(), None, None)
+            .await
+            .unwrap();
+        assert!(a.is_empty());
+
+        // idle tokens are pruned
+        drop(a1);
+        controller
+            .admit("ns1", QueryQuota::default(), Some(b"t3"), None)
+            .await
+            .unwrap();
+        assert_eq!(controller.tokens.lock().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_limit_change() {
+        let controller = QueryAdmissionController::new(
+            &metric::Registry::new(),
+            AdmissionConfig {
+                max_concurrent_queries_per_token: None,
+                queue_timeout: TIMEOUT,
+            },
+        );
+
+        let a1 = controller.admit("ns", quota(1), None, None).await.unwrap();
+
+        // the running query counts against the raised limit
+        let a2 = controller.admit("ns", quota(2), None, None).await.unwrap();
+        controller
+            .admit("ns", quota(2), None, None)
+            .await
+            .unwrap_err();
+
+        // lowering the limit admits no query until the running ones are within the new limit
+        controller
+            .admit("ns", quota(1), None, None)
+            .await
+            .unwrap_err();
+        drop(a1);
+        controller
+            .admit("ns", quota(1), None, None)
+            .await
+            .unwrap_err();
+        drop(a2);
+        let _a3 = controller.admit("ns", quota(1), None, None).await.unwrap();
+        controller
+            .admit("ns", quota(1), None, None)
+            .await
+            .unwrap_err();
+    }
+}
@@ -0,0 +1,327 @@
+//! Apply catalog changes announced via gossip to the [`CatalogCache`].
+use std::{collections::HashSet, sync::Arc};
+
+use async_trait::async_trait;
+use data_types::{CompactionLevel, ParquetFile, ParquetFileId};
+use generated_types::influxdata::iox::{catalog::v1 as catalog_proto, gossip::v1::CompactionEvent};
+use gossip_compaction::rx::CompactionEventHandler;
+use gossip_parquet_file::rx::ParquetFileEventHandler;
+use metric::U64Counter;
+use observability_deps::tracing::{debug, warn};
+use tokio::sync::Mutex;
+
+use super::CatalogCache;
+
+/// Applies [`ParquetFile`] and [`CompactionEvent`] gossip messages to the
+/// parquet file cache of a [`CatalogCache`].
+///
+/// Only tables that are already cached are updated, everything else is loaded
+/// from the catalog on the next query as usual. Files that extend the primary
+/// key of a partition do not need special handling: the partition cache
+/// re-validates the sort key against the parquet files of every query and
+/// refreshes the partition if the sort key does not cover them.
+///
+/// Gossip is best-effort, hence the TTL / refresh policies of the caches stay
+/// in place and eventually repair missed or reordered messages.
+#[derive(Debug)]
+pub struct CatalogCacheGossip {
+    cache: Arc<CatalogCache>,
+
+    /// Serialises updates, since applying a change is a read-modify-write
+    /// operation on the cached file list.
+    lock: Mutex<()>,
+
+    metrics: Metrics,
+}
+
+impl CatalogCacheGossip {
+    /// Create a new handler for the given cache.
+    pub fn new(cache: Arc<CatalogCache>) -> Self {
+        let metrics = Metrics::new(&cache.metric_registry());
+
+        Self {
+            cache,
+            lock: Mutex::new(()),
+            metrics,
+        }
+    }
+}
+
+#[async_trait]
+impl ParquetFileEventHandler for CatalogCacheGossip {
+    async fn handle(&self, event: catalog_proto::ParquetFile) {
+        let file = match ParquetFile::try_from(event) {
+            Ok(v) => v,
+            Err(e) => {
+                warn!(error=%e, "invalid parquet file gossip event");
+                self.metrics.parquet_file_invalid.inc(1);
+                return;
+            }
+        };
+        let table_id = file.table_id;
+
+        let _guard = self.lock.lock().await;
+        let updated = self
+            .cache
+            .parquet_file()
+            .apply_changes(table_id, vec![file], &HashSet::new(), None)
+            .await;
+
+        debug!(%table_id, updated, "applied parquet file gossip event");
+        self.metrics.parquet_file(updated).inc(1);
+    }
+}
+
+#[async_trait]
+impl CompactionEventHandler for CatalogCacheGossip {
+    async fn handle(&self, event: CompactionEvent) {
+        let new_files = match event
+            .new_files
+            .into_iter()
+            .map(ParquetFile::try_from)
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(v) => v,
+            Err(e) => {
+                warn!(error=%e, "invalid compaction gossip event");
+                self.metrics.compaction_invalid.inc(1);
+                return;
+            }
+        };
+
+        let upgraded = if event.updated_file_ids.is_empty() {
+            None
+        } else {
+            match i32::try_from(event.upgraded_target_level)
+                .ok()
+                .and_then(|v| CompactionLevel::try_from(v).ok())
+            {
+                Some(level) => Some((level, to_ids(event.updated_file_ids))),
+                None => {
+                    warn!(
+                        level = event.upgraded_target_level,
+                        "invalid compaction level in compaction gossip event"
+                    );
+                    self.metrics.compaction_invalid.inc(1);
+                    return;
+                }
+            }
+        };
+
+        // The event does not carry the table ID, so it is derived from the
+        // compaction output. Events without output (e.g. pure level upgrades)
+        // cannot be attributed to a table and are left to the regular cache
+        // refresh. A stale compaction level does not affect query results.
+        let Some(table_id) = new_files.first().map(|f| f.table_id) else {
+            debug!("ignoring compaction gossip event without new files");
+            self.metrics.compaction_unattributed.inc(1);
+            return;
+        };
+
+        let _guard = self.lock.lock().await;
+        let updated = self
+            .cache
+            .parquet_file()
+            .apply_changes(
+                table_id,
+                new_files,
+                &to_ids(event.deleted_file_ids),
+                upgraded.as_ref().map(|(level, ids)| (*level, ids)),
+            )
+            .await;
+
+        debug!(%table_id, updated, "applied compaction gossip event");
+        self.metrics.compaction(updated).inc(1);
+    }
+}
+
+fn to_ids(ids: Vec<i64>) -> HashSet<ParquetFileId> {
+    ids.into_iter().map(ParquetFileId::new).collect()
+}
+
+#[derive(Debug)]
+struct Metrics {
+    parquet_file_applied: U64Counter,
+    parquet_file_uncached: U64Counter,
+    parquet_file_invalid: U64Counter,
+    compaction_applied: U64Counter,
+    compaction_uncached: U64Counter,
+    compaction_invalid: U64Counter,
+    compaction_unattributed: U64Counter,
+}
+
+impl Metrics {
+    fn new(registry: &metric::Registry) -> Self {
+        let metric = registry.register_metric::<U64Counter>(
+            "querier_gossip_cache_events",
+            "number of gossip events processed by the querier catalog cache",
+        );
+        let counter = |event: &'static str, result: &'static str| {
+            metric.recorder(&[("event", event), ("result", result)])
+        };
+
+        Self {
+            parquet_file_applied: counter("parquet_file", "applied"),
+            parquet_file_uncached: counter("parquet_file", "uncached"),
+            parquet_file_invalid: counter("parquet_file", "invalid"),
+            compaction_applied: counter("compaction", "applied"),
+            compaction_uncached: counter("compaction", "uncached"),
+            compaction_invalid: counter("compaction", "invalid"),
+            compaction_unattributed: counter("compaction", "unattributed"),
+        }
+    }
+
+    fn parquet_file(&self, updated: bool) -> &U64Counter {
+        if updated {
+            &self.parquet_file_applied
+        } else {
+            &self.parquet_file_uncached
+        }
+    }
+
+    fn compaction(&self, updated: bool) -> &U64Counter {
+        if updated {
+            &self.compaction_applied
+        } else {
+            &self.compaction_uncached
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_types::ColumnType;
+    use iox_tests::{TestCatalog, TestParquetFileBuilder};
+    use metric::{Attributes, Metric};
+    use tokio::runtime::Handle;
+
+    use crate::cache::test_util::assert_catalog_access_metric_count;
+
+    const TABLE1_LINE_PROTOCOL: &str = "table1 foo=1 11";
+    const TABLE1_LINE_PROTOCOL2: &str = "table1 foo=2 22";
+    const TABLE1_LINE_PROTOCOL3: &str = "table1 foo=3 33";
+
+    #[tokio::test]
+    async fn test_apply_events() {
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        let table = ns.create_table("table1").await;
+        table.create_column("foo", ColumnType::F64).await;
+        table.create_column("time", ColumnType::Time).await;
+        let partition = table.create_partition("k").await;
+        let table_id = table.table.id;
+
+        let builder = TestParquetFileBuilder::default().with_line_protocol(TABLE1_LINE_PROTOCOL);
+        let tfile1 = partition.create_parquet_file(builder).await;
+
+        let cache = Arc::new(CatalogCache::new_testing(
+            catalog.catalog(),
+            catalog.time_provider(),
+            catalog.metric_registry(),
+            catalog.object_store(),
+            &Handle::current(),
+        ));
+        let gossip = CatalogCacheGossip::new(Arc::clone(&cache));
+
+        // table not cached yet
+        let builder = TestParquetFileBuilder::default().with_line_protocol(TABLE1_LINE_PROTOCOL2);
+        let tfile2 = partition.create_parquet_file(builder).await;
+        ParquetFileEventHandler::handle(&gossip, tfile2.parquet_file.clone().into()).await;
+        assert_eq!(events(&catalog, "parquet_file", "uncached"), 1);
+
+        let cached = cache.parquet_file().get(table_id, None, None).await;
+        assert_eq!(cached.files.len(), 2);
+
+        // new file from an ingester
+        let builder = TestParquetFileBuilder::default().with_line_protocol(TABLE1_LINE_PROTOCOL3);
+        let tfile3 = partition.create_parquet_file(builder).await;
+        ParquetFileEventHandler::handle(&gossip, tfile3.parquet_file.clone().into()).await;
+        assert_eq!(events(&catalog, "parquet_file", "applied"), 1);
+
+        let cached = cache.parquet_file().get(table_id, None, None).await;
+        assert_eq!(
+            ids(&cached.files),
+            HashSet::from([
+                tfile1.parquet_file.id,
+                tfile2.parquet_file.id,
+                tfile3.parquet_file.id,
+            ])
+        );
+
+        // compaction of file 1 & 2 into a new file, upgrade of file 3
+        let compacted = ParquetFile {
+            id: ParquetFileId::new(42),
+            compaction_level: CompactionLevel::FileNonOverlapped,
+            ..tfile3.parquet_file.clone()
+        };
+        CompactionEventHandler::handle(
+            &gossip,
+            CompactionEvent {
+                deleted_file_ids: vec![tfile1.parquet_file.id.get(), tfile2.parquet_file.id.get()],
+                upgraded_target_level: CompactionLevel::FileNonOverlapped as i64,
+                updated_file_ids: vec![tfile3.parquet_file.id.get()],
+                new_files: vec![compacted.into()],
+            },
+        )
+        .await;
+        assert_eq!(events(&catalog, "compaction", "applied"), 1);
+
+        let cached = cache.parquet_file().get(table_id, None, None).await;
+        assert_eq!(
+            ids(&cached.files),
+            HashSet::from([tfile3.parquet_file.id, ParquetFileId::new(42)])
+        );
+        assert!(cached
+            .files
+            .iter()
+            .all(|f| f.compaction_level == CompactionLevel::FileNonOverlapped));
+
+        // upgrade-only events cannot be attributed to a table
+        CompactionEventHandler::handle(
+            &gossip,
+            CompactionEvent {
+                deleted_file_ids: vec![],
+                upgraded_target_level: CompactionLevel::Final as i64,
+                updated_file_ids: vec![42],
+                new_files: vec![],
+            },
+        )
+        .await;
+        assert_eq!(events(&catalog, "compaction", "unattributed"), 1);
+
+        // garbage
+        CompactionEventHandler::handle(
+            &gossip,
+            CompactionEvent {
+                deleted_file_ids: vec![],
+                upgraded_target_level: 42,
+                updated_file_ids: vec![42],
+                new_files: vec![],
+            },
+        )
+        .await;
+        assert_eq!(events(&catalog, "compaction", "invalid"), 1);
+
+        // the cache was only loaded once
+        assert_catalog_access_metric_count(
+            &catalog.metric_registry,
+            "parquet_list_by_table_not_to_delete",
+            1,
+        );
+    }
+
+    fn ids(files: &[Arc<ParquetFile>]) -> HashSet<ParquetFileId> {
+        files.iter().map(|f| f.id).collect()
+    }
+
+    fn events(catalog: &TestCatalog, event: &'static str, result: &'static str) -> u64 {
+        catalog
+            .metric_registry()
+            .get_instrument::<Metric<U64Counter>>("querier_gossip_cache_events")
+            .expect("failed to read metric")
+            .get_observer(&Attributes::from(&[("event", event), ("result", result)]))
+            .expect("failed to get observer")
+            .fetch()
+    }
+}
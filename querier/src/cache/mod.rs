@@ -2,7 +2,7 @@
 use ::object_store::ObjectStore;
 use ::parquet_file::storage::{ParquetStorage, StorageId};
 use backoff::BackoffConfig;
-use cache_system::backend::policy::lru::ResourcePool;
+use cache_system::{backend::policy::lru::ResourcePool, disk::DiskCache};
 use iox_catalog::interface::Catalog;
 use iox_time::TimeProvider;
 use std::sync::Arc;
@@ -13,6 +13,7 @@ use self::{
     partition::PartitionCache, projected_schema::ProjectedSchemaCache, ram::RamSize,
 };
 
+pub mod gossip;
 pub mod namespace;
 pub mod object_store;
 pub mod parquet_file;
@@ -53,11 +54,15 @@ pub struct CatalogCache {
 
 impl CatalogCache {
     /// Create empty cache.
+    ///
+    /// Object store data evicted from RAM is kept in the optional `disk_cache`.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         catalog: Arc<dyn Catalog>,
         time_provider: Arc<dyn TimeProvider>,
         metric_registry: Arc<metric::Registry>,
         object_store: Arc<dyn ObjectStore>,
+        disk_cache: Option<Arc<DiskCache>>,
         ram_pool_metadata_bytes: usize,
         ram_pool_data_bytes: usize,
         handle: &Handle,
@@ -67,6 +72,7 @@ impl CatalogCache {
             time_provider,
             metric_registry,
             object_store,
+            disk_cache,
             ram_pool_metadata_bytes,
             ram_pool_data_bytes,
             handle,
@@ -89,6 +95,7 @@ impl CatalogCache {
             time_provider,
             metric_registry,
             object_store,
+            None,
             usize::MAX,
             usize::MAX,
             handle,
@@ -102,6 +109,7 @@ impl CatalogCache {
         time_provider: Arc<dyn TimeProvider>,
         metric_registry: Arc<metric::Registry>,
         object_store: Arc<dyn ObjectStore>,
+        disk_cache: Option<Arc<DiskCache>>,
         ram_pool_metadata_bytes: usize,
         ram_pool_data_bytes: usize,
         handle: &Handle,
@@ -156,6 +164,7 @@ impl CatalogCache {
         let object_store_cache = ObjectStoreCache::new(
             backoff_config,
             object_store,
+            disk_cache,
             Arc::clone(&time_provider),
             &metric_registry,
             Arc::clone(&ram_pool_data),
@@ -1,5 +1,6 @@
 //! Namespace cache.
 
+use crate::admission::QueryQuota;
 use backoff::{Backoff, BackoffConfig};
 use cache_system::{
     backend::policy::{
@@ -307,6 +308,7 @@ impl CachedTable {
 pub struct CachedNamespace {
     pub id: NamespaceId,
     pub retention_period: Option<Duration>,
+    pub query_quota: QueryQuota,
     pub tables: HashMap<Arc<str>, Arc<CachedTable>>,
 }
 
@@ -339,6 +341,7 @@ impl CachedNamespace {
         Self {
             id: namespace.id,
             retention_period,
+            query_quota: QueryQuota::from(&namespace),
             tables,
         }
     }
@@ -418,6 +421,7 @@ mod tests {
         let expected_ns_1 = CachedNamespace {
             id: ns1.namespace.id,
             retention_period,
+            query_quota: QueryQuota::default(),
             tables: HashMap::from([
                 (
                     Arc::from("table1"),
@@ -482,6 +486,7 @@ mod tests {
         let expected_ns_2 = CachedNamespace {
             id: ns2.namespace.id,
             retention_period,
+            query_quota: QueryQuota::default(),
             tables: HashMap::from([(
                 Arc::from("table1"),
                 Arc::new(CachedTable {
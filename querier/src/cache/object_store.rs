@@ -10,9 +10,11 @@ use cache_system::{
         PolicyBackend,
     },
     cache::{driver::CacheDriver, metrics::CacheWithMetrics, Cache},
+    disk::{DiskCache, DiskCacheEntry},
     loader::{metrics::MetricsLoader, FunctionLoader},
     resource_consumption::FunctionEstimator,
 };
+use chrono::{TimeZone, Utc};
 use futures::{stream::BoxStream, StreamExt};
 use iox_time::TimeProvider;
 use object_store::{
@@ -46,6 +48,54 @@ impl CachedRead {
             self.meta.e_tag.as_ref().map(|v| v.capacity()).unwrap_or(0)
     }
 
+    /// Convert into an entry of the disk cache.
+    ///
+    /// The location is the cache key and the size is the data length, so only the modification time and the e-tag need
+    /// to be stored as metadata.
+    fn to_disk_entry(&self) -> DiskCacheEntry {
+        let mut metadata = Vec::with_capacity(12);
+        metadata.extend_from_slice(&self.meta.last_modified.timestamp().to_le_bytes());
+        metadata.extend_from_slice(
+            &self
+                .meta
+                .last_modified
+                .timestamp_subsec_nanos()
+                .to_le_bytes(),
+        );
+        if let Some(e_tag) = &self.meta.e_tag {
+            metadata.extend_from_slice(e_tag.as_bytes());
+        }
+
+        DiskCacheEntry {
+            metadata: metadata.into(),
+            data: self.bytes.clone(),
+        }
+    }
+
+    /// Restore from an entry of the disk cache.
+    fn from_disk_entry(location: &Path, entry: DiskCacheEntry) -> Option<Self> {
+        let DiskCacheEntry { metadata, data } = entry;
+        if metadata.len() < 12 {
+            return None;
+        }
+        let secs = i64::from_le_bytes(metadata[..8].try_into().ok()?);
+        let nanos = u32::from_le_bytes(metadata[8..12].try_into().ok()?);
+        let last_modified = Utc.timestamp_opt(secs, nanos).single()?;
+        let e_tag = (metadata.len() > 12)
+            .then(|| String::from_utf8(metadata[12..].to_vec()).ok())
+            .flatten();
+
+        Some(Self {
+            meta: ObjectMeta {
+                location: location.clone(),
+                last_modified,
+                size: data.len(),
+                e_tag,
+            },
+            bytes: data,
+        })
+    }
+
     /// Convert this CachedRead into a GetResult
     fn into_result(self) -> GetResult {
         let Self { bytes, meta } = self;
@@ -102,9 +152,14 @@ pub struct ObjectStoreCache {
 
 impl ObjectStoreCache {
     /// Create new empty cache.
+    ///
+    /// If a `disk_cache` is given, RAM misses are looked up there before hitting the object store, and objects fetched
+    /// from the object store are written back to disk.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         backoff_config: BackoffConfig,
         object_store: Arc<dyn ObjectStore>,
+        disk_cache: Option<Arc<DiskCache>>,
         time_provider: Arc<dyn TimeProvider>,
         metric_registry: &metric::Registry,
         ram_pool: Arc<ResourcePool<RamSize>>,
@@ -115,9 +170,20 @@ impl ObjectStoreCache {
         let loader = FunctionLoader::new(move |key: Path, _extra: ()| {
             let backoff_config = backoff_config.clone();
             let object_store = Arc::clone(&object_store_captured);
+            let disk_cache = disk_cache.clone();
 
             async move {
-                Backoff::new(&backoff_config)
+                if let Some(disk_cache) = &disk_cache {
+                    if let Some(read) = disk_cache
+                        .get(key.as_ref())
+                        .await
+                        .and_then(|entry| CachedRead::from_disk_entry(&key, entry))
+                    {
+                        return Some(read);
+                    }
+                }
+
+                let data = Backoff::new(&backoff_config)
                     .retry_all_errors::<_, _, _, ObjectStoreError>(
                         "get object from object store",
                         || async {
@@ -127,7 +193,16 @@ impl ObjectStoreCache {
                         },
                     )
                     .await
-                    .expect("retry forever")
+                    .expect("retry forever");
+
+                // "not found" results are not persisted, the disk cache is only for data
+                if let (Some(disk_cache), Some(data)) = (disk_cache, &data) {
+                    let entry = data.to_disk_entry();
+                    let key = key.to_string();
+                    tokio::spawn(async move { disk_cache.put(&key, entry).await });
+                }
+
+                data
             }
         });
         let loader = Arc::new(MetricsLoader::new(
@@ -397,6 +472,7 @@ mod tests {
         let cache = ObjectStoreCache::new(
             BackoffConfig::default(),
             Arc::new(instrumented_store),
+            None,
             time_provider,
             &metric_registry,
             test_ram_pool(),
@@ -464,6 +540,64 @@ mod tests {
         assert_eq!(get_count_miss(&metric_registry), 1);
     }
 
+    #[tokio::test]
+    async fn test_disk_tier() {
+        let inner = Arc::new(InMemory::new());
+        let path = Path::from("foo");
+        let bytes = Bytes::from(b"data_foo" as &'static [u8]);
+        inner.put(&path, bytes.clone()).await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let metric_registry = metric::Registry::new();
+        let time_provider = Arc::new(SystemProvider::new());
+        let instrumented_store: Arc<dyn ObjectStore> = Arc::new(ObjectStoreMetrics::new(
+            Arc::clone(&inner) as _,
+            Arc::clone(&time_provider) as _,
+            &metric_registry,
+        ));
+        let disk_cache = DiskCache::new("object_store", dir.path(), 1_000, &metric_registry)
+            .await
+            .unwrap();
+
+        let new_cache = || {
+            ObjectStoreCache::new(
+                BackoffConfig::default(),
+                Arc::clone(&instrumented_store),
+                Some(Arc::clone(&disk_cache)),
+                Arc::clone(&time_provider) as _,
+                &metric_registry,
+                test_ram_pool(),
+                &Handle::current(),
+                true,
+            )
+        };
+
+        let cache = new_cache();
+        let meta = cache.object_store().head(&path).await.unwrap();
+        assert_eq!(get_count_hit(&metric_registry), 1);
+
+        // write-back happens in the background
+        while disk_cache.used_bytes() == 0 {
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+
+        // a fresh RAM cache (e.g. after a restart) is served from disk
+        let cache = new_cache();
+        assert_eq!(cache.object_store().head(&path).await.unwrap(), meta);
+        assert_eq!(
+            cache
+                .object_store()
+                .get(&path)
+                .await
+                .unwrap()
+                .bytes()
+                .await
+                .unwrap(),
+            bytes,
+        );
+        assert_eq!(get_count_hit(&metric_registry), 1);
+    }
+
     fn get_count_hit(metric_registry: &metric::Registry) -> u64 {
         metric_registry
             .get_instrument::<Metric<DurationHistogram>>("object_store_op_duration")
@@ -12,11 +12,16 @@ use cache_system::{
     loader::{metrics::MetricsLoader, FunctionLoader},
     resource_consumption::FunctionEstimator,
 };
-use data_types::{ParquetFile, TableId};
+use data_types::{CompactionLevel, ParquetFile, ParquetFileId, TableId};
 use iox_catalog::interface::Catalog;
 use iox_time::TimeProvider;
 use snafu::{ResultExt, Snafu};
-use std::{collections::HashMap, mem, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    mem,
+    sync::Arc,
+    time::Duration,
+};
 use trace::span::Span;
 use uuid::Uuid;
 
@@ -257,6 +262,68 @@ impl ParquetFileCache {
             .await
     }
 
+    /// Apply changes to the parquet files of a table that other nodes announced (e.g. via
+    /// gossip), instead of reloading the files from the catalog.
+    ///
+    /// - `added` files are appended (unless they are already known)
+    /// - `removed` files are dropped
+    /// - `upgraded` files are moved to the given compaction level
+    ///
+    /// Tables that are not cached are left alone, they are loaded from the catalog on the next
+    /// request. Returns `true` if the cached entry was updated.
+    pub async fn apply_changes(
+        &self,
+        table_id: TableId,
+        added: Vec<ParquetFile>,
+        removed: &HashSet<ParquetFileId>,
+        upgraded: Option<(CompactionLevel, &HashSet<ParquetFileId>)>,
+    ) -> bool {
+        let Some(cached) = self.cache.peek(table_id, ((), None)).await else {
+            return false;
+        };
+
+        let mut files = Vec::with_capacity(cached.files.len() + added.len());
+        let mut known = HashSet::with_capacity(cached.files.len());
+        for file in cached.files.iter() {
+            if removed.contains(&file.id) {
+                continue;
+            }
+            known.insert(file.id);
+
+            match upgraded {
+                Some((level, ids)) if ids.contains(&file.id) => {
+                    files.push(Arc::new(ParquetFile {
+                        compaction_level: level,
+                        ..file.as_ref().clone()
+                    }));
+                }
+                _ => files.push(Arc::clone(file)),
+            }
+        }
+        files.extend(
+            added
+                .into_iter()
+                .filter(|f| {
+                    f.table_id == table_id && !removed.contains(&f.id) && known.insert(f.id)
+                })
+                .map(Arc::new),
+        );
+
+        self.cache
+            .set(
+                table_id,
+                Arc::new(CachedParquetFiles {
+                    files: files.into(),
+                    persisted_file_counts_from_ingesters: cached
+                        .persisted_file_counts_from_ingesters
+                        .clone(),
+                }),
+            )
+            .await;
+
+        true
+    }
+
     /// Mark the entry for table_id as expired (and needs a refresh)
     #[cfg(test)]
     pub fn expire(&self, table_id: TableId) {
@@ -477,6 +544,66 @@ mod tests {
         assert_eq!(cached_files[2].as_ref(), &tfile4.parquet_file);
     }
 
+    #[tokio::test]
+    async fn test_apply_changes() {
+        let (catalog, table, partition) = make_catalog().await;
+        let table_id = table.table.id;
+        let builder = TestParquetFileBuilder::default().with_line_protocol(TABLE1_LINE_PROTOCOL);
+        let tfile1 = partition.create_parquet_file(builder).await;
+        let builder = TestParquetFileBuilder::default().with_line_protocol(TABLE1_LINE_PROTOCOL2);
+        let tfile2 = partition.create_parquet_file(builder).await;
+
+        let cache = make_cache(&catalog);
+
+        // table not cached yet => nothing to do
+        assert!(
+            !cache
+                .apply_changes(table_id, vec![], &HashSet::new(), None)
+                .await
+        );
+
+        let cached_files = cache.get(table_id, None, None).await;
+        assert_eq!(
+            cached_files.ids(),
+            HashSet::from([tfile1.parquet_file.id, tfile2.parquet_file.id])
+        );
+        assert_catalog_access_metric_count(&catalog.metric_registry, METRIC_NAME, 1);
+
+        // new file, known again and one for another table
+        let builder = TestParquetFileBuilder::default().with_line_protocol(TABLE1_LINE_PROTOCOL3);
+        let tfile3 = partition.create_parquet_file(builder).await;
+        let other_table = ParquetFile {
+            id: ParquetFileId::new(i64::MAX),
+            table_id: TableId::new(i64::MAX),
+            ..tfile3.parquet_file.clone()
+        };
+        assert!(
+            cache
+                .apply_changes(
+                    table_id,
+                    vec![
+                        tfile3.parquet_file.clone(),
+                        tfile2.parquet_file.clone(),
+                        other_table,
+                    ],
+                    &HashSet::from([tfile1.parquet_file.id]),
+                    Some((
+                        CompactionLevel::Final,
+                        &HashSet::from([tfile2.parquet_file.id]),
+                    )),
+                )
+                .await
+        );
+
+        let mut cached_files = cache.get(table_id, None, None).await.vec();
+        cached_files.sort_by_key(|f| f.id);
+        assert_catalog_access_metric_count(&catalog.metric_registry, METRIC_NAME, 1);
+        assert_eq!(cached_files.len(), 2);
+        assert_eq!(cached_files[0].id, tfile2.parquet_file.id);
+        assert_eq!(cached_files[0].compaction_level, CompactionLevel::Final);
+        assert_eq!(cached_files[1].as_ref(), &tfile3.parquet_file);
+    }
+
     /// Extracts parquet ids from various objects
     trait ParquetIds {
         fn ids(&self) -> HashSet<ParquetFileId>;
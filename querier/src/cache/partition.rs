@@ -18,14 +18,19 @@ use cache_system::{
 use chrono::{DateTime, Duration, TimeZone, Utc};
 use data_types::{
     partition_template::{build_column_values, ColumnValue},
-    ColumnId, Partition, SortedColumnSet, TransitionPartitionId, MAX_NANO_TIME, MIN_NANO_TIME,
+    ColumnId, ParquetFileId, Partition, PartitionId, PartitionStatistics, SortedColumnSet,
+    TimestampMinMax, TransitionPartitionId, MAX_NANO_TIME, MIN_NANO_TIME,
 };
 use datafusion::scalar::ScalarValue;
 use iox_catalog::{interface::Catalog, partition_lookup_batch};
-use iox_query::chunk_statistics::{ColumnRange, ColumnRanges};
+use iox_query::{
+    chunk_statistics::{ColumnRange, ColumnRanges},
+    exec::stringset::StringSet,
+    PartitionTagValues,
+};
 use iox_time::TimeProvider;
 use observability_deps::tracing::debug;
-use schema::{sort::SortKey, TIME_DATA_TIMEZONE};
+use schema::{sort::SortKey, TIME_COLUMN_NAME, TIME_DATA_TIMEZONE};
 use std::{
     collections::{hash_map::Entry, HashMap, HashSet},
     mem::{size_of, size_of_val},
@@ -101,11 +106,25 @@ impl PartitionCache {
                         .await
                         .expect("retry forever");
 
+                    // fetch the statistics maintained by the catalog
+                    let catalog_ids: Vec<PartitionId> = partitions.iter().map(|p| p.id).collect();
+                    let mut statistics = Backoff::new(&backoff_config)
+                        .retry_all_errors("get partition statistics", || async {
+                            let mut repos = catalog.repositories().await;
+                            repos.partitions().get_statistics_batch(&catalog_ids).await
+                        })
+                        .await
+                        .expect("retry forever")
+                        .into_iter()
+                        .map(|s| (s.partition_id, s))
+                        .collect::<HashMap<_, _>>();
+
                     // build output
                     for p in partitions {
                         let idx = out_map[&p.transition_partition_id()];
                         let cached_table = &cached_tables[idx];
-                        let p = Arc::new(CachedPartition::new(p, cached_table));
+                        let statistics = statistics.remove(&p.id);
+                        let p = Arc::new(CachedPartition::new(p, cached_table, statistics));
                         out[idx] = Some(p);
                     }
 
@@ -160,7 +179,8 @@ impl PartitionCache {
     ///
     /// The result only contains existing partitions. The order is undefined.
     ///
-    /// Expire partition if the cached sort key does NOT cover the given set of columns.
+    /// Expire partition if the cached sort key does NOT cover the given set of columns, or if the cached statistics
+    /// do not cover the given parquet file ID.
     pub async fn get(
         &self,
         cached_table: Arc<CachedTable>,
@@ -175,6 +195,7 @@ impl PartitionCache {
                 |PartitionRequest {
                      partition_id,
                      sort_key_should_cover,
+                     max_parquet_file_id,
                  }| {
                     let cached_table = Arc::clone(&cached_table);
 
@@ -187,7 +208,7 @@ impl PartitionCache {
                         partition_id.clone(),
                         move |cached_partition| {
                             let invalidates = if let Some(sort_key) =
-                                &cached_partition.and_then(|p| p.sort_key.clone())
+                                &cached_partition.as_ref().and_then(|p| p.sort_key.clone())
                             {
                                 sort_key_should_cover
                                     .iter()
@@ -197,6 +218,18 @@ impl PartitionCache {
                                 !sort_key_should_cover.is_empty()
                             };
 
+                            // Statistics that were loaded before a newer file was committed are stale. Partitions
+                            // without any statistics (e.g. those without any commits since the statistics were
+                            // introduced) are NOT refreshed, otherwise they would be reloaded by every query.
+                            let invalidates = invalidates
+                                || cached_partition
+                                    .and_then(|p| {
+                                        p.statistics
+                                            .as_ref()
+                                            .map(|s| s.max_parquet_file_id < max_parquet_file_id)
+                                    })
+                                    .unwrap_or_default();
+
                             if invalidates {
                                 debug!(
                                     %partition_id,
@@ -243,17 +276,47 @@ impl PartitionCache {
 pub struct PartitionRequest {
     pub partition_id: TransitionPartitionId,
     pub sort_key_should_cover: Vec<ColumnId>,
+    /// The highest ID of the parquet files in this partition that the caller knows about, if any.
+    pub max_parquet_file_id: Option<ParquetFileId>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct CachedPartition {
     pub id: TransitionPartitionId,
     pub sort_key: Option<Arc<PartitionSortKey>>,
+
+    /// Column ranges derived from the partition key.
+    ///
+    /// These hold for ALL data in the partition, including data that has not been persisted yet.
     pub column_ranges: ColumnRanges,
+
+    /// Statistics of the persisted data, maintained by the catalog.
+    pub statistics: Option<CachedPartitionStatistics>,
+}
+
+/// Statistics of the parquet files in a partition, derived from the [`PartitionStatistics`] maintained by the catalog.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CachedPartitionStatistics {
+    /// The highest parquet file ID covered by these statistics.
+    pub max_parquet_file_id: Option<ParquetFileId>,
+
+    /// The total row count of the covered files.
+    pub row_count: usize,
+
+    /// The [`CachedPartition::column_ranges`] narrowed down by the time range and (if known) the tag value ranges of
+    /// the covered files.
+    pub column_ranges: ColumnRanges,
+
+    /// The distinct tag values of the covered files, if known.
+    pub tag_values: Option<Arc<PartitionTagValues>>,
 }
 
 impl CachedPartition {
-    fn new(partition: Partition, table: &CachedTable) -> Self {
+    fn new(
+        partition: Partition,
+        table: &CachedTable,
+        statistics: Option<PartitionStatistics>,
+    ) -> Self {
         // build sort_key from the partition's sort_key_ids and table columns
         let sort_key = partition.sort_key_ids_none_if_empty().map(|sort_key_ids| {
             let sort_key_ids = sort_key_ids.clone();
@@ -370,14 +433,125 @@ impl CachedPartition {
             }
         }
         column_ranges.shrink_to_fit();
+        let column_ranges = Arc::new(column_ranges);
+
+        let statistics = statistics.map(|stats| {
+            let mut ranges = column_ranges.as_ref().clone();
+            let mut narrow = |col: &str, range: ColumnRange| {
+                // resolve column name to already existing Arc for cheaper storage
+                let Some((col, _id)) = table.column_id_map_rev.get_key_value(col) else {
+                    return;
+                };
+                match ranges.entry(Arc::clone(col)) {
+                    Entry::Occupied(mut o) => {
+                        let narrowed = intersect(o.get(), range);
+                        o.insert(narrowed);
+                    }
+                    Entry::Vacant(v) => {
+                        v.insert(range);
+                    }
+                }
+            };
+
+            // The time range is recomputed from the files on every commit and therefore always exact.
+            if let (Some(min), Some(max)) = (stats.min_time, stats.max_time) {
+                narrow(
+                    TIME_COLUMN_NAME,
+                    ColumnRange {
+                        min_value: Arc::new(ScalarValue::TimestampNanosecond(
+                            Some(min.get()),
+                            TIME_DATA_TIMEZONE(),
+                        )),
+                        max_value: Arc::new(ScalarValue::TimestampNanosecond(
+                            Some(max.get()),
+                            TIME_DATA_TIMEZONE(),
+                        )),
+                    },
+                );
+            }
+
+            // Tag ranges (the catalog only records min/max values for tags) are only known if all files reported
+            // their statistics.
+            if stats.complete {
+                for col in &stats.columns {
+                    let (Some(min), Some(max)) = (&col.min, &col.max) else {
+                        continue;
+                    };
+                    let Some(name) = table.column_id_map.get(&col.column_id) else {
+                        continue;
+                    };
+                    narrow(
+                        name,
+                        ColumnRange {
+                            min_value: Arc::new(ScalarValue::from(min.as_str())),
+                            max_value: Arc::new(ScalarValue::from(max.as_str())),
+                        },
+                    );
+                }
+            }
+            ranges.shrink_to_fit();
+
+            // Like the tag ranges, the tag values are only usable if all files reported them.
+            let tag_values = match (stats.complete, stats.min_time, stats.max_time) {
+                (true, Some(min), Some(max)) => {
+                    let values = stats
+                        .columns
+                        .iter()
+                        .filter_map(|col| {
+                            let values = col.values.as_ref()?;
+                            let name = table.column_id_map.get(&col.column_id)?;
+                            Some((
+                                Arc::clone(name),
+                                Arc::new(values.iter().cloned().collect::<StringSet>()),
+                            ))
+                        })
+                        .collect::<HashMap<_, _>>();
+                    Some(Arc::new(PartitionTagValues {
+                        time_range: TimestampMinMax::new(min.get(), max.get()),
+                        values,
+                    }))
+                }
+                _ => None,
+            };
+
+            CachedPartitionStatistics {
+                max_parquet_file_id: stats.max_parquet_file_id,
+                row_count: stats.row_count as usize,
+                column_ranges: Arc::new(ranges),
+                tag_values,
+            }
+        });
 
         Self {
             id: partition.transition_partition_id(),
             sort_key,
-            column_ranges: Arc::new(column_ranges),
+            column_ranges,
+            statistics,
         }
     }
 
+    /// Statistics of this partition, if they cover all parquet files up to `max_parquet_file_id`.
+    pub fn statistics_covering(
+        &self,
+        max_parquet_file_id: Option<ParquetFileId>,
+    ) -> Option<&CachedPartitionStatistics> {
+        self.statistics
+            .as_ref()
+            .filter(|s| s.max_parquet_file_id >= max_parquet_file_id)
+    }
+
+    /// Column ranges that hold for all parquet files of this partition up to `max_parquet_file_id`.
+    ///
+    /// Falls back to [`Self::column_ranges`] if the statistics do not cover these files.
+    pub fn parquet_column_ranges(
+        &self,
+        max_parquet_file_id: Option<ParquetFileId>,
+    ) -> &ColumnRanges {
+        self.statistics_covering(max_parquet_file_id)
+            .map(|s| &s.column_ranges)
+            .unwrap_or(&self.column_ranges)
+    }
+
     /// RAM-bytes INCLUDING `self`.
     fn size(&self) -> usize {
         let id = self.id.size() - std::mem::size_of_val(&self.id);
@@ -390,15 +564,19 @@ impl CachedPartition {
             .unwrap_or_default();
 
         // Arc content
-        let column_ranges = std::mem::size_of::<HashMap<Arc<str>, ColumnRange>>()
-            + (self.column_ranges.capacity() * std::mem::size_of::<(Arc<str>, ColumnRange)>())
-            + self
-                .column_ranges
-                .iter()
-                .map(|(col, range)| col.len() + range.min_value.size() + range.max_value.size())
-                .sum::<usize>();
+        let column_ranges = column_ranges_size(&self.column_ranges);
 
-        std::mem::size_of_val(self) + id + sort_key + column_ranges
+        // Arc content
+        let statistics = self
+            .statistics
+            .as_ref()
+            .map(|s| {
+                column_ranges_size(&s.column_ranges)
+                    + s.tag_values.as_ref().map(|v| v.size()).unwrap_or_default()
+            })
+            .unwrap_or_default();
+
+        std::mem::size_of_val(self) + id + sort_key + column_ranges + statistics
     }
 }
 
@@ -443,6 +621,32 @@ impl PartitionSortKey {
     }
 }
 
+/// Size of the content of `column_ranges` in bytes, excluding the [`Arc`] itself.
+fn column_ranges_size(column_ranges: &ColumnRanges) -> usize {
+    std::mem::size_of::<HashMap<Arc<str>, ColumnRange>>()
+        + (column_ranges.capacity() * std::mem::size_of::<(Arc<str>, ColumnRange)>())
+        + column_ranges
+            .iter()
+            .map(|(col, range)| col.len() + range.min_value.size() + range.max_value.size())
+            .sum::<usize>()
+}
+
+/// Narrow `range` by `other`, both of which bound the values of the same column.
+fn intersect(range: &ColumnRange, other: ColumnRange) -> ColumnRange {
+    ColumnRange {
+        min_value: if other.min_value > range.min_value {
+            other.min_value
+        } else {
+            Arc::clone(&range.min_value)
+        },
+        max_value: if other.max_value < range.max_value {
+            other.max_value
+        } else {
+            Arc::clone(&range.max_value)
+        },
+    }
+}
+
 /// Minimum datatime that can exist in IOx.
 fn t_min() -> DateTime<Utc> {
     Utc.timestamp_nanos(MIN_NANO_TIME)
@@ -462,16 +666,18 @@ mod tests {
     use async_trait::async_trait;
     use chrono::Datelike;
     use data_types::{
-        partition_template::TablePartitionTemplateOverride, ColumnType, PartitionId, PartitionKey,
-        SortedColumnSet, TableId,
+        partition_template::TablePartitionTemplateOverride, ColumnType, ParquetFileParams,
+        PartitionColumnStatistics, PartitionId, PartitionKey, SortedColumnSet, TableId, Timestamp,
     };
     use futures::StreamExt;
     use generated_types::influxdata::iox::partition_template::v1::{
         template_part::Part, PartitionTemplate, TemplatePart,
     };
+    use iox_catalog::test_helpers::arbitrary_parquet_file_params;
     use iox_tests::{TestCatalog, TestNamespace};
     use schema::{Schema, SchemaBuilder, TIME_COLUMN_NAME};
     use tokio::sync::Barrier;
+    use uuid::Uuid;
 
     #[tokio::test]
     async fn test_sort_key() {
@@ -938,6 +1144,192 @@ mod tests {
         assert_eq!(ranges.as_ref(), &HashMap::new(),);
     }
 
+    #[tokio::test]
+    async fn test_statistics() {
+        let catalog = TestCatalog::new();
+
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        let t = ns.create_table("table").await;
+        let c1 = t.create_column("tag", ColumnType::Tag).await;
+        let c2 = t.create_column(TIME_COLUMN_NAME, ColumnType::Time).await;
+        let p = t.create_partition("k1").await.partition.clone();
+        let p_id = p.transition_partition_id();
+        let cached_table = Arc::new(CachedTable {
+            id: t.table.id,
+            schema: schema(),
+            column_id_map: HashMap::from([
+                (c1.column.id, Arc::from(c1.column.name.clone())),
+                (c2.column.id, Arc::from(c2.column.name.clone())),
+            ]),
+            column_id_map_rev: HashMap::from([
+                (Arc::from(c1.column.name.clone()), c1.column.id),
+                (Arc::from(c2.column.name.clone()), c2.column.id),
+            ]),
+            primary_key_column_ids: [c1.column.id, c2.column.id].into(),
+            partition_template: TablePartitionTemplateOverride::default(),
+        });
+
+        let cache = PartitionCache::new(
+            catalog.catalog(),
+            BackoffConfig::default(),
+            catalog.time_provider(),
+            &catalog.metric_registry(),
+            test_ram_pool(),
+            true,
+        );
+
+        // no files => no statistics
+        let cached = cache
+            .get_one(Arc::clone(&cached_table), &p_id, &[], None)
+            .await
+            .unwrap();
+        assert_eq!(cached.statistics, None);
+        assert!(Arc::ptr_eq(
+            cached.parquet_column_ranges(None),
+            &cached.column_ranges
+        ));
+
+        // persist a file with known column statistics
+        let params = ParquetFileParams {
+            min_time: Timestamp::new(10),
+            max_time: Timestamp::new(20),
+            row_count: 3,
+            ..arbitrary_parquet_file_params(&ns.namespace, &t.table, &p)
+        };
+        let f1 = catalog
+            .catalog()
+            .repositories()
+            .await
+            .parquet_files()
+            .create_with_statistics(
+                params.clone(),
+                &[PartitionColumnStatistics {
+                    column_id: c1.column.id,
+                    null_count: 0,
+                    min: Some(String::from("a")),
+                    max: Some(String::from("c")),
+                    values: Some(vec![String::from("a"), String::from("c")]),
+                }],
+            )
+            .await
+            .unwrap();
+
+        // the cached partition without statistics is NOT refreshed
+        let cached = get_covering(&cache, &cached_table, &p_id, f1.id).await;
+        assert_eq!(cached.statistics, None);
+
+        let cache = PartitionCache::new(
+            catalog.catalog(),
+            BackoffConfig::default(),
+            catalog.time_provider(),
+            &catalog.metric_registry(),
+            test_ram_pool(),
+            true,
+        );
+        let cached = get_covering(&cache, &cached_table, &p_id, f1.id).await;
+        let stats = cached.statistics.as_ref().unwrap();
+        assert_eq!(stats.max_parquet_file_id, Some(f1.id));
+        assert_eq!(stats.row_count, 3);
+        assert_eq!(
+            stats.column_ranges.as_ref(),
+            &HashMap::from([
+                (
+                    Arc::from("tag"),
+                    ColumnRange {
+                        min_value: Arc::new(ScalarValue::from("a")),
+                        max_value: Arc::new(ScalarValue::from("c")),
+                    }
+                ),
+                (
+                    Arc::from(TIME_COLUMN_NAME),
+                    ColumnRange {
+                        min_value: Arc::new(ScalarValue::TimestampNanosecond(
+                            Some(10),
+                            TIME_DATA_TIMEZONE()
+                        )),
+                        max_value: Arc::new(ScalarValue::TimestampNanosecond(
+                            Some(20),
+                            TIME_DATA_TIMEZONE()
+                        )),
+                    }
+                ),
+            ]),
+        );
+        assert!(Arc::ptr_eq(
+            cached.parquet_column_ranges(Some(f1.id)),
+            &stats.column_ranges
+        ));
+        assert_eq!(
+            stats.tag_values.as_deref(),
+            Some(&PartitionTagValues {
+                time_range: TimestampMinMax::new(10, 20),
+                values: HashMap::from([(
+                    Arc::from("tag"),
+                    Arc::new(StringSet::from([String::from("a"), String::from("c")])),
+                )]),
+            }),
+        );
+
+        // persist a file of unknown content
+        let f2 = catalog
+            .catalog()
+            .repositories()
+            .await
+            .parquet_files()
+            .create(ParquetFileParams {
+                object_store_id: Uuid::new_v4(),
+                row_count: 1,
+                ..params
+            })
+            .await
+            .unwrap();
+
+        // the cached statistics do not cover the new file
+        assert!(Arc::ptr_eq(
+            cached.parquet_column_ranges(Some(f2.id)),
+            &cached.column_ranges
+        ));
+
+        // so the partition is refreshed, the tag range is now unknown
+        let cached = get_covering(&cache, &cached_table, &p_id, f2.id).await;
+        let stats = cached.statistics.as_ref().unwrap();
+        assert_eq!(stats.max_parquet_file_id, Some(f2.id));
+        assert_eq!(stats.row_count, 4);
+        assert!(!stats.column_ranges.contains_key("tag"));
+        assert!(stats.column_ranges.contains_key(TIME_COLUMN_NAME));
+        assert_eq!(stats.tag_values, None);
+
+        // and only refreshed once
+        get_covering(&cache, &cached_table, &p_id, f2.id).await;
+        assert_catalog_access_metric_count(
+            &catalog.metric_registry,
+            "partition_get_statistics_batch",
+            3,
+        );
+    }
+
+    async fn get_covering(
+        cache: &PartitionCache,
+        cached_table: &Arc<CachedTable>,
+        partition_id: &TransitionPartitionId,
+        max_parquet_file_id: ParquetFileId,
+    ) -> Arc<CachedPartition> {
+        cache
+            .get(
+                Arc::clone(cached_table),
+                vec![PartitionRequest {
+                    partition_id: partition_id.clone(),
+                    sort_key_should_cover: vec![],
+                    max_parquet_file_id: Some(max_parquet_file_id),
+                }],
+                None,
+            )
+            .await
+            .into_iter()
+            .next()
+            .unwrap()
+    }
+
     #[tokio::test]
     async fn test_column_ranges_time_edges() {
         let catalog = TestCatalog::new();
@@ -1249,20 +1641,24 @@ mod tests {
                     PartitionRequest {
                         partition_id: p1_id.clone(),
                         sort_key_should_cover: vec![],
+                        max_parquet_file_id: None,
                     },
                     PartitionRequest {
                         partition_id: p2_id.clone(),
                         sort_key_should_cover: vec![],
+                        max_parquet_file_id: None,
                     },
                     PartitionRequest {
                         partition_id: p1_id.clone(),
                         sort_key_should_cover: vec![],
+                        max_parquet_file_id: None,
                     },
                     // requesting non-existing partitions is fine, they just don't appear in
                     // the output
                     PartitionRequest {
                         partition_id: TransitionPartitionId::Deprecated(PartitionId::new(i64::MAX)),
                         sort_key_should_cover: vec![],
+                        max_parquet_file_id: None,
                     },
                     PartitionRequest {
                         partition_id: TransitionPartitionId::new(
@@ -1270,6 +1666,7 @@ mod tests {
                             &PartitionKey::from("bananas_not_found"),
                         ),
                         sort_key_should_cover: vec![],
+                        max_parquet_file_id: None,
                     },
                 ],
                 None,
@@ -1500,6 +1897,7 @@ mod tests {
                         .map(|p| PartitionRequest {
                             partition_id: p.clone(),
                             sort_key_should_cover: vec![],
+                            max_parquet_file_id: None,
                         })
                         .collect(),
                     None,
@@ -1544,6 +1942,7 @@ mod tests {
                 vec![PartitionRequest {
                     partition_id: partition_id.clone(),
                     sort_key_should_cover: sort_key_should_cover.to_vec(),
+                    max_parquet_file_id: None,
                 }],
                 span,
             )
@@ -1,10 +1,12 @@
 //! Database for the querier that contains all namespaces.
 
 use crate::{
+    admission::{AdmissionConfig, QueryAdmissionController},
     cache::CatalogCache,
     ingester::IngesterConnection,
     namespace::{QuerierNamespace, QuerierNamespaceArgs},
     parquet::ChunkAdapter,
+    prefetch::{self, PrefetchRequest, PrefetchSummary},
     query_log::QueryLog,
     table::PruneMetrics,
     QueryLogEntry,
@@ -12,9 +14,13 @@ use crate::{
 use async_trait::async_trait;
 use backoff::{Backoff, BackoffConfig};
 use data_types::Namespace;
+use gossip_membership::ClusterMembers;
 use iox_catalog::interface::SoftDeletedRows;
 use iox_query::exec::Executor;
-use service_common::QueryNamespaceProvider;
+use service_common::{
+    admission::{AdmissionError, QueryAdmission},
+    QueryNamespaceProvider, TableFilter,
+};
 use snafu::Snafu;
 use std::{
     collections::{HashMap, VecDeque},
@@ -74,6 +80,12 @@ pub struct QuerierDatabase {
 
     /// DataFusion config.
     datafusion_config: Arc<HashMap<String, String>>,
+
+    /// Per-namespace and per-token query limits.
+    admission_controller: QueryAdmissionController,
+
+    /// Gossip cluster membership view, exposed as `system.cluster_members`.
+    cluster_members: Option<Arc<ClusterMembers>>,
 }
 
 #[async_trait]
@@ -85,8 +97,10 @@ impl QueryNamespaceProvider for QuerierDatabase {
         name: &str,
         span: Option<Span>,
         include_debug_info_tables: bool,
+        table_filter: &TableFilter,
     ) -> Option<Arc<Self::Db>> {
-        self.namespace(name, span, include_debug_info_tables).await
+        self.namespace(name, span, include_debug_info_tables, table_filter)
+            .await
     }
 
     async fn acquire_semaphore(&self, span: Option<Span>) -> InstrumentedAsyncOwnedSemaphorePermit {
@@ -95,6 +109,30 @@ impl QueryNamespaceProvider for QuerierDatabase {
             .await
             .expect("Semaphore should not be closed by anyone")
     }
+
+    async fn admit_query(
+        &self,
+        name: &str,
+        token: Option<&[u8]>,
+        span: Option<Span>,
+    ) -> Result<QueryAdmission, AdmissionError> {
+        let span_recorder = SpanRecorder::new(span);
+        let quota = self
+            .catalog_cache
+            .namespace()
+            .get(
+                Arc::from(name),
+                &[],
+                span_recorder.child_span("cache GET namespace schema"),
+            )
+            .await
+            .map(|ns| ns.query_quota)
+            .unwrap_or_default();
+
+        self.admission_controller
+            .admit(name, quota, token, span_recorder.child_span("admit query"))
+            .await
+    }
 }
 
 impl QuerierDatabase {
@@ -146,9 +184,27 @@ impl QuerierDatabase {
             query_execution_semaphore,
             prune_metrics,
             datafusion_config,
+            admission_controller: QueryAdmissionController::new(
+                &metric_registry,
+                AdmissionConfig::default(),
+            ),
+            cluster_members: None,
         })
     }
 
+    /// Set the querier-wide admission settings.
+    pub fn with_admission_config(mut self, config: AdmissionConfig) -> Self {
+        self.admission_controller.set_config(config);
+        self
+    }
+
+    /// Expose the provided gossip cluster membership view as the
+    /// `system.cluster_members` debug table.
+    pub fn with_cluster_members(mut self, members: Arc<ClusterMembers>) -> Self {
+        self.cluster_members = Some(members);
+        self
+    }
+
     /// Get namespace if it exists.
     ///
     /// This will await the internal namespace semaphore. Existence of namespaces is checked AFTER
@@ -158,6 +214,7 @@ impl QuerierDatabase {
         name: &str,
         span: Option<Span>,
         include_debug_info_tables: bool,
+        table_filter: &TableFilter,
     ) -> Option<Arc<QuerierNamespace>> {
         let span_recorder = SpanRecorder::new(span);
         let name = Arc::from(name.to_owned());
@@ -171,6 +228,9 @@ impl QuerierDatabase {
                 span_recorder.child_span("cache GET namespace schema"),
             )
             .await?;
+        let memory_pool =
+            self.admission_controller
+                .memory_pool(&name, ns.query_quota, &self.exec.pool());
         Some(Arc::new(QuerierNamespace::new(QuerierNamespaceArgs {
             chunk_adapter: Arc::clone(&self.chunk_adapter),
             ns,
@@ -181,6 +241,9 @@ impl QuerierDatabase {
             prune_metrics: Arc::clone(&self.prune_metrics),
             datafusion_config: Arc::clone(&self.datafusion_config),
             include_debug_info_tables,
+            table_filter: table_filter.clone(),
+            memory_pool,
+            cluster_members: self.cluster_members.clone(),
         })))
     }
 
@@ -200,6 +263,16 @@ impl QuerierDatabase {
             .expect("retry forever")
     }
 
+    /// Preload the caches with the partitions selected by `request`.
+    ///
+    /// See [`PrefetchRequest`] for the details.
+    pub async fn prefetch(
+        &self,
+        request: PrefetchRequest,
+    ) -> Result<PrefetchSummary, prefetch::Error> {
+        prefetch::prefetch(&self.catalog_cache, &self.backoff_config, request).await
+    }
+
     /// Executor
     pub(crate) fn exec(&self) -> &Executor {
         &self.exec
@@ -215,7 +288,10 @@ impl QuerierDatabase {
 mod tests {
     use super::*;
     use crate::create_ingester_connection_for_testing;
+    use assert_matches::assert_matches;
+    use iox_query::QueryNamespace;
     use iox_tests::TestCatalog;
+    use std::{collections::HashSet, time::Duration};
     use tokio::runtime::Handle;
 
     #[tokio::test]
@@ -251,8 +327,61 @@ mod tests {
 
         catalog.create_namespace_1hr_retention("ns1").await;
 
-        assert!(db.namespace("ns1", None, true).await.is_some());
-        assert!(db.namespace("ns2", None, true).await.is_none());
+        assert!(db
+            .namespace("ns1", None, true, &TableFilter::All)
+            .await
+            .is_some());
+        assert!(db
+            .namespace("ns2", None, true, &TableFilter::All)
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_namespace_table_filter() {
+        let catalog = TestCatalog::new();
+        let db = new_db(&catalog).await;
+
+        let ns = catalog.create_namespace_1hr_retention("ns1").await;
+        ns.create_table("cpu").await;
+        ns.create_table("mem").await;
+
+        let filter = TableFilter::Only(Arc::new(HashSet::from(["cpu".to_string()])));
+        let ns = db.namespace("ns1", None, true, &filter).await.unwrap();
+        let ctx = ns.new_query_context(None);
+        assert!(ctx.inner().table_exist("cpu").unwrap());
+        assert!(!ctx.inner().table_exist("mem").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_admit_query() {
+        let catalog = TestCatalog::new();
+        let db = new_db(&catalog)
+            .await
+            .with_admission_config(AdmissionConfig {
+                max_concurrent_queries_per_token: None,
+                queue_timeout: Duration::from_millis(10),
+            });
+
+        catalog.create_namespace_1hr_retention("ns1").await;
+        catalog
+            .catalog()
+            .repositories()
+            .await
+            .namespaces()
+            .update_query_quota("ns1", Some(1), Some(1024))
+            .await
+            .unwrap();
+
+        let admission = db.admit_query("ns1", None, None).await.unwrap();
+        assert_eq!(admission.len(), 1);
+        let err = db.admit_query("ns1", None, None).await.unwrap_err();
+        assert_matches!(err, AdmissionError::NamespaceConcurrency { limit: 1, .. });
+        drop(admission);
+        db.admit_query("ns1", None, None).await.unwrap();
+
+        // unknown namespaces are not limited
+        assert!(db.admit_query("ns2", None, None).await.unwrap().is_empty());
     }
 
     #[tokio::test]
@@ -9,6 +9,7 @@ use iox_query::{
     QueryChunk, QueryChunkData,
 };
 use observability_deps::tracing::trace;
+use parking_lot::RwLock;
 use schema::{sort::SortKey, Schema};
 use std::{any::Any, sync::Arc};
 use trace::span::Span;
@@ -17,9 +18,53 @@ use uuid::Uuid;
 pub(crate) mod test_util;
 mod v1;
 
+/// A shared, runtime-updatable set of ingester addresses queried by an
+/// [`IngesterConnection`].
+///
+/// Each query fans out to a snapshot of the set taken when the query starts.
+/// Removing an ingester therefore drains it: queries already in flight
+/// continue to use it, while new queries do not.
+#[derive(Debug, Clone, Default)]
+pub struct IngesterAddresses {
+    addrs: Arc<RwLock<Arc<[Arc<str>]>>>,
+}
+
+impl IngesterAddresses {
+    /// Initialise the set with `addrs`, removing any duplicates.
+    pub fn new(addrs: impl IntoIterator<Item = Arc<str>>) -> Self {
+        Self {
+            addrs: Arc::new(RwLock::new(dedup(addrs))),
+        }
+    }
+
+    /// Return the current set of ingester addresses.
+    pub fn snapshot(&self) -> Arc<[Arc<str>]> {
+        Arc::clone(&self.addrs.read())
+    }
+
+    /// Replace the set of ingester addresses with `addrs`, returning true if
+    /// the set changed.
+    pub fn set(&self, addrs: impl IntoIterator<Item = Arc<str>>) -> bool {
+        let addrs = dedup(addrs);
+        let mut guard = self.addrs.write();
+        if *guard == addrs {
+            return false;
+        }
+        *guard = addrs;
+        true
+    }
+}
+
+fn dedup(addrs: impl IntoIterator<Item = Arc<str>>) -> Arc<[Arc<str>]> {
+    let mut addrs = addrs.into_iter().collect::<Vec<_>>();
+    addrs.sort_unstable();
+    addrs.dedup();
+    addrs.into()
+}
+
 /// Create a new set of connections given ingester configurations
 pub fn create_ingester_connections(
-    ingester_addresses: Vec<Arc<str>>,
+    ingester_addresses: IngesterAddresses,
     catalog_cache: Arc<CatalogCache>,
     open_circuit_after_n_errors: u64,
     trace_context_header_name: &str,
@@ -256,3 +301,33 @@ impl QueryChunk for IngesterChunk {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ingester_addresses_set() {
+        let addrs =
+            IngesterAddresses::new(["http://b".into(), "http://a".into(), "http://b".into()]);
+        let before = addrs.snapshot();
+        assert_eq!(&*before, &[Arc::from("http://a"), Arc::from("http://b")]);
+
+        // Setting the same set (in any order) is a no-op.
+        assert!(!addrs.set(["http://b".into(), "http://a".into()]));
+
+        // Removing an ingester changes the set seen by new snapshots, but not
+        // by existing snapshots.
+        assert!(addrs.set(["http://a".into(), "http://c".into()]));
+        assert_eq!(
+            &*addrs.snapshot(),
+            &[Arc::from("http://a"), Arc::from("http://c")]
+        );
+        assert_eq!(&*before, &[Arc::from("http://a"), Arc::from("http://b")]);
+
+        // Clones share the same set.
+        let cloned = addrs.clone();
+        assert!(cloned.set(["http://d".into()]));
+        assert_eq!(&*addrs.snapshot(), &[Arc::from("http://d")]);
+    }
+}
@@ -32,16 +32,13 @@ use observability_deps::tracing::{debug, warn};
 use predicate::Predicate;
 use schema::Schema;
 use snafu::{ensure, OptionExt, ResultExt, Snafu};
-use std::{
-    any::Any,
-    collections::{HashMap, HashSet},
-    sync::Arc,
-    time::Duration,
-};
+use std::{any::Any, collections::HashMap, sync::Arc, time::Duration};
 use trace::span::{Span, SpanRecorder};
 use uuid::Uuid;
 
-use super::{DynError, IngesterChunkData, IngesterConnection, IngesterPartition};
+use super::{
+    DynError, IngesterAddresses, IngesterChunkData, IngesterConnection, IngesterPartition,
+};
 
 mod circuit_breaker;
 pub(crate) mod flight_client;
@@ -143,7 +140,7 @@ pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 /// Create a new set of connections given ingester configurations
 pub fn create_ingester_connections(
-    ingester_addresses: Vec<Arc<str>>,
+    ingester_addresses: IngesterAddresses,
     catalog_cache: Arc<CatalogCache>,
     open_circuit_after_n_errors: u64,
     trace_context_header_name: &str,
@@ -305,7 +302,7 @@ impl<'a> Drop for ObserveIngesterRequest<'a> {
 /// IngesterConnection that communicates with an ingester.
 #[derive(Debug)]
 struct IngesterConnectionImpl {
-    unique_ingester_addresses: HashSet<Arc<str>>,
+    ingester_addresses: IngesterAddresses,
     flight_client: Arc<dyn IngesterFlightClient>,
     time_provider: Arc<dyn TimeProvider>,
     metrics: Arc<IngesterConnectionMetrics>,
@@ -315,7 +312,7 @@ struct IngesterConnectionImpl {
 impl IngesterConnectionImpl {
     /// Create a new set of connections given a list of ingester addresses.
     fn by_addrs(
-        ingester_addresses: Vec<Arc<str>>,
+        ingester_addresses: IngesterAddresses,
         catalog_cache: Arc<CatalogCache>,
         backoff_config: BackoffConfig,
         circuit_breaker_backoff_config: BackoffConfig,
@@ -345,7 +342,7 @@ impl IngesterConnectionImpl {
     /// This is helpful for testing, i.e. when the flight client should not be backed by normal
     /// network communication.
     fn by_addrs_with_flight_client(
-        ingester_addresses: Vec<Arc<str>>,
+        ingester_addresses: IngesterAddresses,
         flight_client: Arc<dyn IngesterFlightClient>,
         catalog_cache: Arc<CatalogCache>,
         backoff_config: BackoffConfig,
@@ -354,7 +351,7 @@ impl IngesterConnectionImpl {
         let metrics = Arc::new(IngesterConnectionMetrics::new(&metric_registry));
 
         Self {
-            unique_ingester_addresses: ingester_addresses.into_iter().collect(),
+            ingester_addresses,
             flight_client,
             time_provider: catalog_cache.time_provider(),
             metrics,
@@ -727,8 +724,9 @@ impl IngesterConnection for IngesterConnectionImpl {
             }
         };
 
-        let mut ingester_partitions: Vec<IngesterPartition> = self
-            .unique_ingester_addresses
+        // Take a snapshot of the (possibly changing) set of ingesters.
+        let ingester_addresses = self.ingester_addresses.snapshot();
+        let mut ingester_partitions: Vec<IngesterPartition> = ingester_addresses
             .iter()
             .cloned()
             .map(move |ingester_address| measured_ingester_request(ingester_address))
@@ -1518,7 +1516,7 @@ mod tests {
             let ingester_addresses: BTreeSet<_> =
                 self.responses.lock().await.keys().cloned().collect();
             IngesterConnectionImpl::by_addrs_with_flight_client(
-                ingester_addresses.into_iter().map(Into::into).collect(),
+                IngesterAddresses::new(ingester_addresses.into_iter().map(Into::into)),
                 Arc::clone(self) as _,
                 Arc::new(CatalogCache::new_testing(
                     self.catalog.catalog(),
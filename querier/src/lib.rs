@@ -16,11 +16,13 @@
 // Workaround for "unused crate" lint false positives.
 use workspace_hack as _;
 
+mod admission;
 mod cache;
 mod database;
 mod ingester;
 mod namespace;
 mod parquet;
+mod prefetch;
 mod query_log;
 mod server;
 mod system_tables;
@@ -31,9 +33,13 @@ mod table;
 /// This is mostly to fetch per-partition data concurrently.
 const CONCURRENT_CHUNK_CREATION_JOBS: usize = 100;
 
-pub use cache::CatalogCache as QuerierCatalogCache;
+pub use admission::{AdmissionConfig, QueryQuota, DEFAULT_QUERY_QUEUE_TIMEOUT};
+pub use cache::{gossip::CatalogCacheGossip, CatalogCache as QuerierCatalogCache};
 pub use database::{Error as QuerierDatabaseError, QuerierDatabase};
-pub use ingester::{create_ingester_connection_for_testing, create_ingester_connections};
+pub use ingester::{
+    create_ingester_connection_for_testing, create_ingester_connections, IngesterAddresses,
+};
 pub use namespace::QuerierNamespace;
+pub use prefetch::{Error as PrefetchError, PartitionSelection, PrefetchRequest, PrefetchSummary};
 pub use query_log::QueryLogEntry;
 pub use server::QuerierServer;
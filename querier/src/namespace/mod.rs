@@ -8,7 +8,10 @@ use crate::{
     table::{PruneMetrics, QuerierTable, QuerierTableArgs},
 };
 use data_types::NamespaceId;
+use datafusion::execution::memory_pool::MemoryPool;
+use gossip_membership::ClusterMembers;
 use iox_query::exec::Executor;
+use service_common::TableFilter;
 use std::{collections::HashMap, sync::Arc, time::Duration};
 
 mod query_access;
@@ -28,6 +31,9 @@ pub struct QuerierNamespaceArgs {
     pub prune_metrics: Arc<PruneMetrics>,
     pub datafusion_config: Arc<HashMap<String, String>>,
     pub include_debug_info_tables: bool,
+    pub table_filter: TableFilter,
+    pub memory_pool: Option<Arc<dyn MemoryPool>>,
+    pub cluster_members: Option<Arc<ClusterMembers>>,
 }
 
 /// Maps a catalog namespace to all the in-memory resources and sync-state that the querier needs.
@@ -67,6 +73,12 @@ pub struct QuerierNamespace {
 
     /// Retention period.
     retention_period: Option<Duration>,
+
+    /// Memory pool enforcing the namespace query memory budget, if any.
+    memory_pool: Option<Arc<dyn MemoryPool>>,
+
+    /// Gossip cluster membership view, if any.
+    cluster_members: Option<Arc<ClusterMembers>>,
 }
 
 impl QuerierNamespace {
@@ -82,11 +94,15 @@ impl QuerierNamespace {
             prune_metrics,
             datafusion_config,
             include_debug_info_tables,
+            table_filter,
+            memory_pool,
+            cluster_members,
         } = args;
 
         let tables: HashMap<_, _> = ns
             .tables
             .iter()
+            .filter(|(table_name, _)| table_filter.allows(table_name))
             .map(|(table_name, cached_table)| {
                 let table = Arc::new(QuerierTable::new(QuerierTableArgs {
                     namespace_id: ns.id,
@@ -116,6 +132,8 @@ impl QuerierNamespace {
             datafusion_config,
             include_debug_info_tables,
             retention_period: ns.retention_period,
+            memory_pool,
+            cluster_members,
         }
     }
 
@@ -143,6 +161,9 @@ impl QuerierNamespace {
             prune_metrics,
             datafusion_config: Default::default(),
             include_debug_info_tables: true,
+            table_filter: TableFilter::All,
+            memory_pool: None,
+            cluster_members: None,
         })
     }
 
@@ -15,6 +15,7 @@ use datafusion::{
     prelude::Expr,
 };
 use datafusion_util::config::DEFAULT_SCHEMA;
+use gossip_membership::ClusterMembers;
 use iox_query::{
     exec::{ExecutorType, IOxSessionContext},
     QueryChunk, QueryCompletedToken, QueryNamespace, QueryText,
@@ -85,6 +86,10 @@ impl QueryNamespace for QuerierNamespace {
             cfg = cfg.with_config_option(k, v);
         }
 
+        if let Some(memory_pool) = &self.memory_pool {
+            cfg = cfg.with_memory_pool(Arc::clone(memory_pool));
+        }
+
         cfg.build()
     }
 }
@@ -101,6 +106,9 @@ pub struct QuerierCatalogProvider {
 
     /// Include debug info tables.
     include_debug_info_tables: bool,
+
+    /// Gossip cluster membership view, if any.
+    cluster_members: Option<Arc<ClusterMembers>>,
 }
 
 impl QuerierCatalogProvider {
@@ -110,6 +118,7 @@ impl QuerierCatalogProvider {
             tables: Arc::clone(&namespace.tables),
             query_log: Arc::clone(&namespace.query_log),
             include_debug_info_tables: namespace.include_debug_info_tables,
+            cluster_members: namespace.cluster_members.clone(),
         }
     }
 }
@@ -132,6 +141,7 @@ impl CatalogProvider for QuerierCatalogProvider {
                 Arc::clone(&self.query_log),
                 self.namespace_id,
                 self.include_debug_info_tables,
+                self.cluster_members.clone(),
             ))),
             _ => None,
         }
@@ -247,6 +247,9 @@ impl ChunkAdapter {
             order,
             sort_key: Some(sort_key),
             partition_id: file.partition_id.clone(),
+            partition_tag_values: cached_partition
+                .statistics_covering(Some(file.id))
+                .and_then(|s| s.tag_values.clone()),
         });
 
         let parquet_chunk = Arc::new(ParquetChunk::new(
@@ -342,7 +345,7 @@ impl PreparedParquetFileWithStats {
             Some(file.row_count as usize),
             &schema,
             Some(ts_min_max),
-            Some(&cached_partition.column_ranges),
+            Some(cached_partition.parquet_column_ranges(Some(file.id))),
         ));
 
         Self {
@@ -2,6 +2,7 @@
 
 use data_types::{ChunkId, ChunkOrder, TransitionPartitionId};
 use datafusion::physical_plan::Statistics;
+use iox_query::PartitionTagValues;
 use parquet_file::chunk::ParquetChunk;
 use schema::sort::SortKey;
 use std::sync::Arc;
@@ -25,6 +26,9 @@ pub struct QuerierParquetChunkMeta {
 
     /// Partition identifier.
     partition_id: TransitionPartitionId,
+
+    /// Distinct tag values of the partition, if they cover this chunk.
+    partition_tag_values: Option<Arc<PartitionTagValues>>,
 }
 
 impl QuerierParquetChunkMeta {
@@ -42,6 +46,11 @@ impl QuerierParquetChunkMeta {
     pub fn partition_id(&self) -> &TransitionPartitionId {
         &self.partition_id
     }
+
+    /// Distinct tag values of the partition.
+    pub fn partition_tag_values(&self) -> Option<&Arc<PartitionTagValues>> {
+        self.partition_tag_values.as_ref()
+    }
 }
 
 #[derive(Debug)]
@@ -233,6 +242,7 @@ pub mod tests {
                     vec![PartitionRequest {
                         partition_id: self.parquet_file.partition_id.clone(),
                         sort_key_should_cover: vec![],
+                        max_parquet_file_id: Some(self.parquet_file.id),
                     }],
                     None,
                 )
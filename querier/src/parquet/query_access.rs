@@ -1,7 +1,7 @@
 use crate::parquet::QuerierParquetChunk;
 use data_types::{ChunkId, ChunkOrder, TransitionPartitionId};
 use datafusion::physical_plan::Statistics;
-use iox_query::{QueryChunk, QueryChunkData};
+use iox_query::{PartitionTagValues, QueryChunk, QueryChunkData};
 use schema::{sort::SortKey, Schema};
 use std::{any::Any, sync::Arc};
 
@@ -45,4 +45,8 @@ impl QueryChunk for QuerierParquetChunk {
     fn as_any(&self) -> &dyn Any {
         self
     }
+
+    fn partition_tag_values(&self) -> Option<Arc<PartitionTagValues>> {
+        self.meta().partition_tag_values().cloned()
+    }
 }
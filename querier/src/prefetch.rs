@@ -0,0 +1,486 @@
+//! Cache warm-up and prefetching.
+//!
+//! A fresh querier starts with empty caches, so the first queries fan out to the catalog and the
+//! object store. Prefetching loads the namespace schemas, partitions and parquet file metadata
+//! (and optionally the parquet data) of recently written partitions ahead of time.
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use backoff::{Backoff, BackoffConfig};
+use data_types::{
+    ColumnId, NamespaceId, ParquetFileId, Partition, TableId, Timestamp, TransitionPartitionId,
+};
+use futures::{stream, StreamExt};
+use iox_catalog::interface::SoftDeletedRows;
+use observability_deps::tracing::{debug, warn};
+use parquet_file::ParquetFilePath;
+use snafu::Snafu;
+
+use crate::cache::{
+    namespace::{CachedNamespace, CachedTable},
+    partition::PartitionRequest,
+    CatalogCache,
+};
+
+/// Number of tables that are prefetched concurrently.
+const CONCURRENT_TABLE_JOBS: usize = 10;
+
+/// Number of parquet files whose data is prefetched concurrently (per table).
+const CONCURRENT_OBJECT_STORE_JOBS: usize = 10;
+
+#[allow(missing_docs)]
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("Namespace not found: {name}"))]
+    NamespaceNotFound { name: String },
+}
+
+/// Selects the partitions to prefetch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartitionSelection {
+    /// The N most recently created partitions.
+    MostRecent(usize),
+
+    /// Partitions that received new files within the given time range (both ends exclusive).
+    NewFileBetween {
+        /// Lower bound.
+        start: Timestamp,
+
+        /// Optional upper bound.
+        end: Option<Timestamp>,
+    },
+}
+
+/// What to prefetch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefetchRequest {
+    /// Only prefetch partitions of this namespace. If `None`, all namespaces are considered.
+    pub namespace: Option<String>,
+
+    /// Partitions to prefetch.
+    pub partitions: PartitionSelection,
+
+    /// Also load the parquet data into the object store cache.
+    pub parquet_data: bool,
+}
+
+/// What was prefetched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrefetchSummary {
+    /// Number of namespaces.
+    pub namespaces: usize,
+
+    /// Number of tables.
+    pub tables: usize,
+
+    /// Number of partitions.
+    pub partitions: usize,
+
+    /// Number of parquet files.
+    pub parquet_files: usize,
+
+    /// Number of parquet data bytes loaded into the object store cache.
+    pub parquet_bytes: u64,
+}
+
+impl std::ops::AddAssign for PrefetchSummary {
+    fn add_assign(&mut self, rhs: Self) {
+        self.namespaces += rhs.namespaces;
+        self.tables += rhs.tables;
+        self.partitions += rhs.partitions;
+        self.parquet_files += rhs.parquet_files;
+        self.parquet_bytes += rhs.parquet_bytes;
+    }
+}
+
+/// Prefetch the partitions selected by `request` into the `catalog_cache`.
+///
+/// Partitions of tables or namespaces that are unknown (e.g. because they were deleted
+/// concurrently) are skipped.
+pub(crate) async fn prefetch(
+    catalog_cache: &CatalogCache,
+    backoff_config: &BackoffConfig,
+    request: PrefetchRequest,
+) -> Result<PrefetchSummary, Error> {
+    let catalog = catalog_cache.catalog();
+
+    let partitions = match request.partitions {
+        PartitionSelection::MostRecent(n) => Backoff::new(backoff_config)
+            .retry_all_errors("get most recent partitions", || async {
+                catalog
+                    .repositories()
+                    .await
+                    .partitions()
+                    .most_recent_n(n)
+                    .await
+            })
+            .await
+            .expect("retry forever"),
+        PartitionSelection::NewFileBetween { start, end } => {
+            let ids = Backoff::new(backoff_config)
+                .retry_all_errors("get partitions with new files", || async {
+                    catalog
+                        .repositories()
+                        .await
+                        .partitions()
+                        .partitions_new_file_between(start, end)
+                        .await
+                })
+                .await
+                .expect("retry forever");
+
+            Backoff::new(backoff_config)
+                .retry_all_errors("get partitions by id", || {
+                    let ids = ids.clone();
+                    let catalog = Arc::clone(&catalog);
+                    async move {
+                        catalog
+                            .repositories()
+                            .await
+                            .partitions()
+                            .get_by_id_batch(ids)
+                            .await
+                    }
+                })
+                .await
+                .expect("retry forever")
+        }
+    };
+
+    let mut partitions_by_table: HashMap<TableId, Vec<Partition>> = HashMap::new();
+    for partition in partitions {
+        partitions_by_table
+            .entry(partition.table_id)
+            .or_default()
+            .push(partition);
+    }
+
+    let namespaces = match &request.namespace {
+        Some(name) => {
+            let ns = catalog_cache
+                .namespace()
+                .get(Arc::from(name.as_str()), &[], None)
+                .await
+                .ok_or_else(|| Error::NamespaceNotFound { name: name.clone() })?;
+            vec![ns]
+        }
+        None => {
+            resolve_namespaces(
+                catalog_cache,
+                backoff_config,
+                partitions_by_table.keys().copied(),
+            )
+            .await
+        }
+    };
+
+    let mut summary = PrefetchSummary {
+        namespaces: namespaces.len(),
+        ..Default::default()
+    };
+    let jobs = namespaces
+        .iter()
+        .flat_map(|ns| ns.tables.values())
+        .filter_map(|table| {
+            partitions_by_table
+                .remove(&table.id)
+                .map(|partitions| (Arc::clone(table), partitions))
+        })
+        .collect::<Vec<_>>();
+
+    let mut results = stream::iter(jobs)
+        .map(|(table, partitions)| {
+            prefetch_table(catalog_cache, table, partitions, request.parquet_data)
+        })
+        .buffer_unordered(CONCURRENT_TABLE_JOBS);
+    while let Some(table_summary) = results.next().await {
+        summary += table_summary;
+    }
+
+    Ok(summary)
+}
+
+/// Find the namespaces of the given tables.
+async fn resolve_namespaces(
+    catalog_cache: &CatalogCache,
+    backoff_config: &BackoffConfig,
+    table_ids: impl Iterator<Item = TableId>,
+) -> Vec<Arc<CachedNamespace>> {
+    let catalog = catalog_cache.catalog();
+
+    let mut namespace_ids = HashSet::<NamespaceId>::new();
+    for table_id in table_ids {
+        let table = Backoff::new(backoff_config)
+            .retry_all_errors("get table by id", || async {
+                catalog
+                    .repositories()
+                    .await
+                    .tables()
+                    .get_by_id(table_id)
+                    .await
+            })
+            .await
+            .expect("retry forever");
+
+        if let Some(table) = table {
+            namespace_ids.insert(table.namespace_id);
+        }
+    }
+
+    let mut namespaces = Vec::with_capacity(namespace_ids.len());
+    for namespace_id in namespace_ids {
+        let namespace = Backoff::new(backoff_config)
+            .retry_all_errors("get namespace by id", || async {
+                catalog
+                    .repositories()
+                    .await
+                    .namespaces()
+                    .get_by_id(namespace_id, SoftDeletedRows::ExcludeDeleted)
+                    .await
+            })
+            .await
+            .expect("retry forever");
+        let Some(namespace) = namespace else {
+            continue;
+        };
+
+        if let Some(ns) = catalog_cache
+            .namespace()
+            .get(Arc::from(namespace.name), &[], None)
+            .await
+        {
+            namespaces.push(ns);
+        }
+    }
+
+    namespaces
+}
+
+/// Prefetch the given partitions of a single table.
+async fn prefetch_table(
+    catalog_cache: &CatalogCache,
+    table: Arc<CachedTable>,
+    partitions: Vec<Partition>,
+    parquet_data: bool,
+) -> PrefetchSummary {
+    let partition_ids = partitions
+        .iter()
+        .map(|p| p.transition_partition_id())
+        .collect::<HashSet<_>>();
+
+    let files = catalog_cache
+        .parquet_file()
+        .get(table.id, None, None)
+        .await
+        .files
+        .iter()
+        .filter(|f| partition_ids.contains(&f.partition_id))
+        .map(Arc::clone)
+        .collect::<Vec<_>>();
+
+    // Same coverage as used for queries, so that the prefetched sort keys are not invalidated by
+    // the first query.
+    let pk = table
+        .primary_key_column_ids
+        .iter()
+        .copied()
+        .collect::<HashSet<_>>();
+    let mut should_cover: HashMap<
+        TransitionPartitionId,
+        (HashSet<ColumnId>, Option<ParquetFileId>),
+    > = partition_ids
+        .into_iter()
+        .map(|id| (id, Default::default()))
+        .collect();
+    for f in &files {
+        let (cover, max_file_id) = should_cover.entry(f.partition_id.clone()).or_default();
+        cover.extend(f.column_set.iter().copied().filter(|id| pk.contains(id)));
+        *max_file_id = (*max_file_id).max(Some(f.id));
+    }
+    let requests = should_cover
+        .into_iter()
+        .map(
+            |(partition_id, (cover, max_parquet_file_id))| PartitionRequest {
+                partition_id,
+                sort_key_should_cover: cover.into_iter().collect(),
+                max_parquet_file_id,
+            },
+        )
+        .collect();
+    let n_partitions = catalog_cache
+        .partition()
+        .get(Arc::clone(&table), requests, None)
+        .await
+        .len();
+
+    let mut parquet_bytes = 0;
+    if parquet_data {
+        let store = catalog_cache.parquet_store();
+        let mut results = stream::iter(&files)
+            .map(|f| {
+                let store = Arc::clone(store.object_store());
+                let path = ParquetFilePath::from(f.as_ref()).object_store_path();
+                async move {
+                    let res = match store.get(&path).await {
+                        Ok(res) => res.bytes().await,
+                        Err(e) => Err(e),
+                    };
+                    res.map(|data| data.len() as u64).map_err(|e| (path, e))
+                }
+            })
+            .buffer_unordered(CONCURRENT_OBJECT_STORE_JOBS);
+        while let Some(res) = results.next().await {
+            match res {
+                Ok(n) => parquet_bytes += n,
+                Err((path, e)) => {
+                    // files may be deleted concurrently, this is not fatal
+                    warn!(%path, error=%e, "cannot prefetch parquet data");
+                }
+            }
+        }
+    }
+
+    debug!(
+        table_id = %table.id,
+        n_partitions,
+        n_files = files.len(),
+        parquet_bytes,
+        "prefetched table",
+    );
+
+    PrefetchSummary {
+        namespaces: 0,
+        tables: 1,
+        partitions: n_partitions,
+        parquet_files: files.len(),
+        parquet_bytes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_types::ColumnType;
+    use iox_tests::{TestCatalog, TestParquetFileBuilder};
+    use tokio::runtime::Handle;
+
+    use crate::cache::test_util::assert_catalog_access_metric_count;
+
+    #[tokio::test]
+    async fn test_prefetch() {
+        let catalog = TestCatalog::new();
+
+        let ns1 = catalog.create_namespace_1hr_retention("ns1").await;
+        let table1 = ns1.create_table("table1").await;
+        table1.create_column("tag", ColumnType::Tag).await;
+        table1.create_column("foo", ColumnType::F64).await;
+        table1.create_column("time", ColumnType::Time).await;
+        let partition1 = table1.create_partition("k1").await;
+        partition1
+            .create_parquet_file(
+                TestParquetFileBuilder::default().with_line_protocol("table1,tag=a foo=1 11"),
+            )
+            .await;
+        partition1
+            .create_parquet_file(
+                TestParquetFileBuilder::default().with_line_protocol("table1,tag=b foo=2 22"),
+            )
+            .await;
+
+        let ns2 = catalog.create_namespace_1hr_retention("ns2").await;
+        let table2 = ns2.create_table("table2").await;
+        table2.create_column("foo", ColumnType::F64).await;
+        table2.create_column("time", ColumnType::Time).await;
+        let partition2 = table2.create_partition("k2").await;
+        partition2
+            .create_parquet_file(
+                TestParquetFileBuilder::default().with_line_protocol("table2 foo=1 11"),
+            )
+            .await;
+
+        let catalog_cache = CatalogCache::new_testing(
+            catalog.catalog(),
+            catalog.time_provider(),
+            catalog.metric_registry(),
+            catalog.object_store(),
+            &Handle::current(),
+        );
+        let backoff_config = BackoffConfig::default();
+
+        // all namespaces
+        let summary = prefetch(
+            &catalog_cache,
+            &backoff_config,
+            PrefetchRequest {
+                namespace: None,
+                partitions: PartitionSelection::MostRecent(10),
+                parquet_data: true,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(summary.namespaces, 2);
+        assert_eq!(summary.tables, 2);
+        assert_eq!(summary.partitions, 2);
+        assert_eq!(summary.parquet_files, 3);
+        assert!(summary.parquet_bytes > 0);
+
+        // the caches are warm now
+        assert_catalog_access_metric_count(
+            &catalog.metric_registry,
+            "parquet_list_by_table_not_to_delete",
+            2,
+        );
+        catalog_cache
+            .parquet_file()
+            .get(table1.table.id, None, None)
+            .await;
+        assert_catalog_access_metric_count(
+            &catalog.metric_registry,
+            "parquet_list_by_table_not_to_delete",
+            2,
+        );
+
+        // single namespace
+        let summary = prefetch(
+            &catalog_cache,
+            &backoff_config,
+            PrefetchRequest {
+                namespace: Some("ns2".to_owned()),
+                partitions: PartitionSelection::NewFileBetween {
+                    start: Timestamp::new(0),
+                    end: None,
+                },
+                parquet_data: false,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            summary,
+            PrefetchSummary {
+                namespaces: 1,
+                tables: 1,
+                partitions: 1,
+                parquet_files: 1,
+                parquet_bytes: 0,
+            }
+        );
+
+        // unknown namespace
+        let err = prefetch(
+            &catalog_cache,
+            &backoff_config,
+            PrefetchRequest {
+                namespace: Some("ns3".to_owned()),
+                partitions: PartitionSelection::MostRecent(10),
+                parquet_data: false,
+            },
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Namespace not found: ns3");
+    }
+}
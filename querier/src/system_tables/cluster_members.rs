@@ -0,0 +1,175 @@
+use crate::system_tables::{BatchIterator, IoxSystemTable};
+use arrow::{
+    array::{ArrayRef, BooleanArray, StringArray, TimestampNanosecondArray},
+    datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit},
+    error::Result,
+    record_batch::RecordBatch,
+};
+use gossip_membership::{ClusterMember, ClusterMembers};
+use std::sync::Arc;
+
+/// Implementation of system.cluster_members table
+#[derive(Debug)]
+pub(super) struct ClusterMembersTable {
+    schema: SchemaRef,
+    members: Arc<ClusterMembers>,
+}
+
+impl ClusterMembersTable {
+    pub(super) fn new(members: Arc<ClusterMembers>) -> Self {
+        Self {
+            schema: cluster_members_schema(),
+            members,
+        }
+    }
+}
+
+impl IoxSystemTable for ClusterMembersTable {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    fn scan(&self, batch_size: usize) -> Result<BatchIterator> {
+        let schema = self.schema();
+        let members = self.members.members();
+
+        let mut offset = 0;
+        Ok(Box::new(std::iter::from_fn(move || {
+            if offset >= members.len() {
+                return None;
+            }
+
+            let len = batch_size.min(members.len() - offset);
+            let batch = from_cluster_members(Arc::clone(&schema), &members[offset..offset + len]);
+            offset += len;
+            Some(batch)
+        })))
+    }
+}
+
+fn cluster_members_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("identity", DataType::Utf8, false),
+        Field::new("role", DataType::Utf8, false),
+        Field::new("rpc_address", DataType::Utf8, false),
+        Field::new("version", DataType::Utf8, false),
+        Field::new(
+            "started_at",
+            DataType::Timestamp(TimeUnit::Nanosecond, None),
+            false,
+        ),
+        Field::new(
+            "last_seen",
+            DataType::Timestamp(TimeUnit::Nanosecond, None),
+            false,
+        ),
+        Field::new("alive", DataType::Boolean, false),
+        Field::new("local", DataType::Boolean, false),
+    ]))
+}
+
+fn from_cluster_members(schema: SchemaRef, members: &[ClusterMember]) -> Result<RecordBatch> {
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(
+            members
+                .iter()
+                .map(|m| Some(m.identity.to_string()))
+                .collect::<StringArray>(),
+        ),
+        Arc::new(
+            members
+                .iter()
+                .map(|m| Some(m.info.role.as_str()))
+                .collect::<StringArray>(),
+        ),
+        Arc::new(
+            members
+                .iter()
+                .map(|m| Some(m.info.rpc_address.as_str()))
+                .collect::<StringArray>(),
+        ),
+        Arc::new(
+            members
+                .iter()
+                .map(|m| Some(m.info.version.as_str()))
+                .collect::<StringArray>(),
+        ),
+        Arc::new(
+            members
+                .iter()
+                .map(|m| Some(m.info.started_at.timestamp_nanos()))
+                .collect::<TimestampNanosecondArray>(),
+        ),
+        Arc::new(
+            members
+                .iter()
+                .map(|m| Some(m.last_seen.timestamp_nanos()))
+                .collect::<TimestampNanosecondArray>(),
+        ),
+        Arc::new(
+            members
+                .iter()
+                .map(|m| Some(m.alive))
+                .collect::<BooleanArray>(),
+        ),
+        Arc::new(
+            members
+                .iter()
+                .map(|m| Some(m.local))
+                .collect::<BooleanArray>(),
+        ),
+    ];
+
+    RecordBatch::try_new(schema, columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_util::assert_batches_eq;
+    use gossip::Identity;
+    use gossip_membership::{MemberInfo, Role};
+    use iox_time::{MockProvider, Time};
+
+    #[test]
+    fn test_cluster_members() {
+        let now = Time::from_rfc3339("1996-12-19T16:39:57+00:00").unwrap();
+        let time_provider = Arc::new(MockProvider::new(now));
+        let members = Arc::new(ClusterMembers::new(Arc::clone(&time_provider) as _));
+
+        members.set_local(
+            Identity::try_from(vec![1; 16]).unwrap(),
+            MemberInfo {
+                role: Role::Querier,
+                rpc_address: "http://querier:8082".to_string(),
+                version: "v42".to_string(),
+                started_at: now,
+            },
+        );
+        members.observe(
+            Identity::try_from(vec![2; 16]).unwrap(),
+            MemberInfo {
+                role: Role::Ingester,
+                rpc_address: "http://ingester:8082".to_string(),
+                version: "v41".to_string(),
+                started_at: Time::from_rfc3339("1996-12-18T16:39:57+00:00").unwrap(),
+            },
+        );
+        time_provider.inc(gossip_membership::MEMBER_TIMEOUT * 2);
+
+        let table = ClusterMembersTable::new(members);
+
+        let expected = vec![
+            "+--------------------------------------+----------+----------------------+---------+----------------------+----------------------+-------+-------+",
+            "| identity                             | role     | rpc_address          | version | started_at           | last_seen            | alive | local |",
+            "+--------------------------------------+----------+----------------------+---------+----------------------+----------------------+-------+-------+",
+            "| 02020202-0202-0202-0202-020202020202 | ingester | http://ingester:8082 | v41     | 1996-12-18T16:39:57Z | 1996-12-19T16:39:57Z | false | false |",
+            "| 01010101-0101-0101-0101-010101010101 | querier  | http://querier:8082  | v42     | 1996-12-19T16:39:57Z | 1996-12-19T16:40:57Z | true  | true  |",
+            "+--------------------------------------+----------+----------------------+---------+----------------------+----------------------+-------+-------+",
+        ];
+
+        let entries = table.scan(1).unwrap().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_batches_eq!(&expected, &entries);
+    }
+}
@@ -16,6 +16,7 @@ use datafusion::{
     },
     prelude::Expr,
 };
+use gossip_membership::ClusterMembers;
 use std::collections::HashMap;
 use std::{
     any::Any,
@@ -24,11 +25,13 @@ use std::{
     task::{Context, Poll},
 };
 
+mod cluster_members;
 mod queries;
 
 pub const SYSTEM_SCHEMA: &str = "system";
 
 const QUERIES_TABLE: &str = "queries";
+const CLUSTER_MEMBERS_TABLE: &str = "cluster_members";
 
 pub struct SystemSchemaProvider {
     tables: HashMap<&'static str, Arc<dyn TableProvider>>,
@@ -39,6 +42,7 @@ impl SystemSchemaProvider {
         query_log: Arc<QueryLog>,
         namespace_id: NamespaceId,
         include_debug_info: bool,
+        cluster_members: Option<Arc<ClusterMembers>>,
     ) -> Self {
         let mut tables: HashMap<&'static str, Arc<dyn TableProvider>> = HashMap::new();
 
@@ -47,6 +51,13 @@ impl SystemSchemaProvider {
                 table: Arc::new(queries::QueriesTable::new(query_log, Some(namespace_id))),
             });
             tables.insert(QUERIES_TABLE, queries);
+
+            if let Some(members) = cluster_members {
+                let cluster_members = Arc::new(SystemTableProvider {
+                    table: Arc::new(cluster_members::ClusterMembersTable::new(members)),
+                });
+                tables.insert(CLUSTER_MEMBERS_TABLE, cluster_members);
+            }
         }
 
         Self { tables }
@@ -8,8 +8,8 @@ use crate::{
     CONCURRENT_CHUNK_CREATION_JOBS,
 };
 use data_types::{
-    ColumnId, NamespaceId, ParquetFile, TableId, TimestampMinMax, TransitionPartitionId,
-    MAX_NANO_TIME, MIN_NANO_TIME,
+    ColumnId, NamespaceId, ParquetFile, ParquetFileId, TableId, TimestampMinMax,
+    TransitionPartitionId, MAX_NANO_TIME, MIN_NANO_TIME,
 };
 use datafusion::{error::DataFusionError, prelude::Expr};
 use futures::{join, StreamExt};
@@ -266,11 +266,23 @@ impl QuerierTable {
         else {
             return Ok(vec![]);
         };
+
+        // The highest parquet file ID per partition, used to determine if the catalog
+        // partition statistics cover all files.
+        let mut max_file_ids: HashMap<TransitionPartitionId, ParquetFileId> = HashMap::new();
+        for f in parquet_files.files.iter() {
+            max_file_ids
+                .entry(f.partition_id.clone())
+                .and_modify(|id| *id = (*id).max(f.id))
+                .or_insert(f.id);
+        }
+
         let cached_partitions = self
             .fetch_cached_partitions(
                 cached_table,
                 &partitions,
                 &parquet_files.files,
+                &max_file_ids,
                 span_recorder.child_span("fetch cached partitions"),
             )
             .await;
@@ -280,6 +292,7 @@ impl QuerierTable {
             .prune_partitions(
                 cached_partitions,
                 cached_table,
+                &max_file_ids,
                 filters,
                 span_recorder.child_span("prune partitions"),
             )
@@ -351,6 +364,7 @@ impl QuerierTable {
         cached_table: &Arc<CachedTable>,
         ingester_partitions: &[IngesterPartition],
         parquet_files: &[Arc<ParquetFile>],
+        max_file_ids: &HashMap<TransitionPartitionId, ParquetFileId>,
         span: Option<Span>,
     ) -> Vec<Arc<CachedPartition>> {
         let span_recorder = SpanRecorder::new(span);
@@ -388,6 +402,7 @@ impl QuerierTable {
         let requests = should_cover
             .into_iter()
             .map(|(id, cover)| PartitionRequest {
+                max_parquet_file_id: max_file_ids.get(&id).copied(),
                 partition_id: id,
                 sort_key_should_cover: cover.into_iter().collect(),
             })
@@ -407,22 +422,31 @@ impl QuerierTable {
         &self,
         partitions: Vec<Arc<CachedPartition>>,
         cached_table: &Arc<CachedTable>,
+        max_file_ids: &HashMap<TransitionPartitionId, ParquetFileId>,
         filters: &[Expr],
         span: Option<Span>,
     ) -> HashMap<TransitionPartitionId, Arc<CachedPartition>> {
         let span_recorder = SpanRecorder::new(span);
 
+        // Only the parquet files are pruned by partition, so the catalog statistics can
+        // be used if they cover all of them.
+        let statistics = partitions
+            .iter()
+            .map(|p| p.statistics_covering(max_file_ids.get(&p.id).copied()))
+            .collect::<Vec<_>>();
+
         let projections = partitions
             .iter()
-            .map(|p| {
-                let mut projection = p
-                    .column_ranges
+            .zip(statistics.iter().copied())
+            .map(|(p, stats)| {
+                let column_ranges = stats.map(|s| &s.column_ranges).unwrap_or(&p.column_ranges);
+                let mut projection = column_ranges
                     .keys()
                     .filter_map(|col| cached_table.column_id_map_rev.get(col).copied())
                     .collect::<Vec<_>>();
 
                 // "time" is always required, otherwise DataFusion will be confused since it is marked as "not NULL"
-                if !p.column_ranges.contains_key(TIME_COLUMN_NAME) {
+                if !column_ranges.contains_key(TIME_COLUMN_NAME) {
                     if let Some(col_id) = cached_table.column_id_map_rev.get(TIME_COLUMN_NAME) {
                         projection.push(*col_id);
                     }
@@ -463,18 +487,24 @@ impl QuerierTable {
 
         let summaries = partitions
             .iter()
+            .zip(statistics)
             .zip(projections)
-            .map(|(p, projection)| {
+            .map(|((p, stats), projection)| {
                 let schema = projection_to_schema
                     .get(&projection)
                     .expect("just gathered all projections");
+                let column_ranges = stats.map(|s| &s.column_ranges).unwrap_or(&p.column_ranges);
 
                 // provide "all time" fall back for time column because DataFusion doesn't like if we set this to NULL
-                let ts_min_max = (!p.column_ranges.contains_key(TIME_COLUMN_NAME))
+                let ts_min_max = (!column_ranges.contains_key(TIME_COLUMN_NAME))
                     .then(|| TimestampMinMax::new(MIN_NANO_TIME, MAX_NANO_TIME));
 
-                let stats =
-                    create_chunk_statistics(None, schema, ts_min_max, Some(&p.column_ranges));
+                let stats = create_chunk_statistics(
+                    stats.map(|s| s.row_count),
+                    schema,
+                    ts_min_max,
+                    Some(column_ranges),
+                );
                 (Arc::new(stats), Arc::clone(schema.inner()))
             })
             .collect::<Vec<_>>();
@@ -23,7 +23,7 @@ use thiserror::Error;
 use trace::ctx::SpanContext;
 
 use self::{
-    balancer::Balancer,
+    balancer::{Balancer, EndpointSet},
     circuit_breaker::CircuitBreaker,
     circuit_breaking_client::{CircuitBreakerState, CircuitBreakingClient},
     client::RpcWriteClientError,
@@ -112,6 +112,51 @@ pub struct RpcWrite<T, C = CircuitBreaker> {
     /// may NACK a write, having already buffered the data. When this request is
     /// retried, the data will be duplicated.
     n_copies: usize,
+
+    /// The number of health probes per second configured for each upstream,
+    /// used when adding upstreams at runtime.
+    num_probes: u64,
+}
+
+/// A handle to replace the set of upstream ingesters of a [`RpcWrite`] at
+/// runtime, obtained from [`RpcWrite::upstream_set()`].
+#[derive(Debug)]
+pub struct UpstreamSet<T> {
+    endpoints: EndpointSet<T>,
+    num_probes: u64,
+}
+
+impl<T> Clone for UpstreamSet<T> {
+    fn clone(&self) -> Self {
+        Self {
+            endpoints: self.endpoints.clone(),
+            num_probes: self.num_probes,
+        }
+    }
+}
+
+impl<T> UpstreamSet<T> {
+    /// Return the names of the current set of upstream ingesters.
+    pub fn names(&self) -> Vec<Arc<str>> {
+        self.endpoints.names()
+    }
+
+    /// Replace the set of upstream ingesters with those named in `names`,
+    /// calling `new_client` to initialise a client for any upstream not
+    /// already in the set.
+    ///
+    /// Upstreams that remain in the set retain their health state. Writes in
+    /// flight to a removed upstream are allowed to complete.
+    ///
+    /// Returns true if the set of upstreams changed.
+    pub fn set<F>(&self, names: &[Arc<str>], mut new_client: F) -> bool
+    where
+        F: FnMut(&str) -> T,
+    {
+        self.endpoints.set(names, |name| {
+            CircuitBreakingClient::new(new_client(name), Arc::clone(name), self.num_probes)
+        })
+    }
 }
 
 impl<T> RpcWrite<T> {
@@ -128,6 +173,9 @@ impl<T> RpcWrite<T> {
     ///
     /// It's invalid to configure `replica_copies` such that more ACKs are
     /// needed than the number of `endpoints`; doing so will cause a panic.
+    ///
+    /// An empty set of `endpoints` is allowed if they are to be populated at
+    /// runtime through an [`UpstreamSet`].
     pub fn new<N>(
         endpoints: impl IntoIterator<Item = (T, N)>,
         n_copies: NonZeroUsize,
@@ -153,7 +201,7 @@ impl<T> RpcWrite<T> {
 
         // Assert this configuration is not impossible to satisfy.
         assert!(
-            n_copies <= endpoints.len(),
+            endpoints.len() == 0 || n_copies <= endpoints.len(),
             "cannot configure more write copies ({n_copies}) than ingester \
             endpoints ({count})",
             count = endpoints.len(),
@@ -162,6 +210,15 @@ impl<T> RpcWrite<T> {
         Self {
             endpoints,
             n_copies,
+            num_probes,
+        }
+    }
+
+    /// Return a handle to change the set of upstream ingesters at runtime.
+    pub fn upstream_set(&self) -> UpstreamSet<T> {
+        UpstreamSet {
+            endpoints: self.endpoints.endpoint_set(),
+            num_probes: self.num_probes,
         }
     }
 }
@@ -418,6 +475,7 @@ mod tests {
         let handler = RpcWrite {
             endpoints: Balancer::new(endpoints, None),
             n_copies,
+            num_probes: ARBITRARY_TEST_NUM_PROBES,
         };
 
         assert!(
@@ -1,8 +1,11 @@
 use std::{borrow::Cow, cell::RefCell, cmp::max, fmt::Debug, sync::Arc, time::Duration};
 
+use hashbrown::HashMap;
+
 use futures::Future;
 use metric::U64Gauge;
-use observability_deps::tracing::warn;
+use observability_deps::tracing::{info, warn};
+use parking_lot::RwLock;
 use tokio::task::JoinHandle;
 
 use super::{
@@ -34,9 +37,16 @@ const METRIC_EVAL_INTERVAL: Duration = Duration::from_secs(3);
 /// Requests are distributed uniformly across all endpoints **per thread**. Given
 /// enough requests (where `N` is significantly larger than the number of
 /// threads) an approximately uniform distribution is achieved.
+///
+/// # Dynamic Endpoints
+///
+/// The set of endpoints can be changed at runtime through an
+/// [`EndpointSet`] handle obtained from [`Balancer::endpoint_set()`].
+/// Requests already in flight continue to use the snapshot of endpoints they
+/// were started with, draining naturally from any removed endpoint.
 #[derive(Debug)]
 pub(super) struct Balancer<T, C = CircuitBreaker> {
-    endpoints: Arc<[Arc<CircuitBreakingClient<T, C>>]>,
+    endpoints: EndpointSet<T, C>,
 
     /// An optional metric exporter task that evaluates the state of this
     /// [`Balancer`] every [`METRIC_EVAL_INTERVAL`].
@@ -54,16 +64,24 @@ where
         endpoints: impl IntoIterator<Item = CircuitBreakingClient<T, C>>,
         metrics: Option<&metric::Registry>,
     ) -> Self {
-        let endpoints = endpoints.into_iter().map(Arc::new).collect();
+        let endpoints = EndpointSet {
+            inner: Arc::new(RwLock::new(endpoints.into_iter().map(Arc::new).collect())),
+        };
         Self {
-            metric_task: metrics.map(|m| tokio::spawn(metric_task(m, Arc::clone(&endpoints)))),
+            metric_task: metrics.map(|m| tokio::spawn(metric_task(m, endpoints.clone()))),
             endpoints,
         }
     }
 
     /// Returns the number of configured upstream endpoints.
     pub(super) fn len(&self) -> usize {
-        self.endpoints.len()
+        self.endpoints.snapshot().len()
+    }
+
+    /// Return a handle to change the set of endpoints of this [`Balancer`] at
+    /// runtime.
+    pub(super) fn endpoint_set(&self) -> EndpointSet<T, C> {
+        self.endpoints.clone()
     }
 
     /// Return an (infinite) iterator of healthy [`CircuitBreakingClient`], and
@@ -92,9 +110,10 @@ where
         // request having to make multiple RPC calls that are likely to fail -
         // this smooths out the P99. The probe node is always requested first to
         // drive recovery.
+        let endpoints = self.endpoints.snapshot();
         let mut probe = None;
-        let mut healthy = Vec::with_capacity(self.endpoints.len());
-        for e in &*self.endpoints {
+        let mut healthy = Vec::with_capacity(endpoints.len());
+        for e in &*endpoints {
             if e.is_healthy() {
                 healthy.push(Arc::clone(e));
                 continue;
@@ -125,11 +144,79 @@ where
     }
 }
 
+/// A shared, replaceable set of [`CircuitBreakingClient`] used by a
+/// [`Balancer`].
+#[derive(Debug)]
+pub(super) struct EndpointSet<T, C = CircuitBreaker> {
+    inner: Arc<RwLock<Arc<[Arc<CircuitBreakingClient<T, C>>]>>>,
+}
+
+impl<T, C> Clone for EndpointSet<T, C> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T, C> EndpointSet<T, C> {
+    /// Return the current set of endpoints.
+    pub(super) fn snapshot(&self) -> Arc<[Arc<CircuitBreakingClient<T, C>>]> {
+        Arc::clone(&self.inner.read())
+    }
+
+    /// Return the names of the current set of endpoints.
+    pub(super) fn names(&self) -> Vec<Arc<str>> {
+        self.snapshot().iter().map(|c| c.endpoint_name()).collect()
+    }
+
+    /// Replace the set of endpoints with the endpoints named `names`.
+    ///
+    /// Endpoints already in the set are retained (preserving their health
+    /// state), while `new_client` is called to initialise any endpoint not
+    /// already in the set. Endpoints not named in `names` are removed.
+    ///
+    /// Returns true if the set of endpoints changed.
+    pub(super) fn set<F>(&self, names: &[Arc<str>], mut new_client: F) -> bool
+    where
+        F: FnMut(&Arc<str>) -> CircuitBreakingClient<T, C>,
+    {
+        let mut guard = self.inner.write();
+
+        let current = guard
+            .iter()
+            .map(|c| (c.endpoint_name(), c))
+            .collect::<HashMap<_, _>>();
+
+        if names.len() == current.len() && names.iter().all(|n| current.contains_key(n)) {
+            return false;
+        }
+
+        for name in current.keys().filter(|&n| !names.contains(n)) {
+            info!(endpoint = %name, "removing upstream rpc endpoint");
+        }
+
+        let next = names
+            .iter()
+            .map(|name| match current.get(name) {
+                Some(c) => Arc::clone(c),
+                None => {
+                    info!(endpoint = %name, "adding upstream rpc endpoint");
+                    Arc::new(new_client(name))
+                }
+            })
+            .collect::<Arc<[_]>>();
+
+        *guard = next;
+        true
+    }
+}
+
 /// Initialise the health metric exported by the RPC balancer, and return the
 /// health evaluation future that updates it.
 fn metric_task<T, C>(
     metrics: &metric::Registry,
-    endpoints: Arc<[Arc<CircuitBreakingClient<T, C>>]>,
+    endpoints: EndpointSet<T, C>,
 ) -> impl Future<Output = ()> + Send
 where
     T: Send + Sync + 'static,
@@ -143,23 +230,11 @@ where
     metric_loop(metric, endpoints)
 }
 
-async fn metric_loop<T, C>(
-    metric: metric::Metric<U64Gauge>,
-    endpoints: Arc<[Arc<CircuitBreakingClient<T, C>>]>,
-) where
+async fn metric_loop<T, C>(metric: metric::Metric<U64Gauge>, endpoints: EndpointSet<T, C>)
+where
     T: Send + Sync + 'static,
     C: CircuitBreakerState + 'static,
 {
-    // Map the endpoints into an endpoint and a metric.
-    let endpoints = endpoints
-        .iter()
-        .map(|c| {
-            let name = Cow::from(c.endpoint_name().to_string());
-            let metric = metric.recorder([("endpoint", name)]);
-            (c, metric)
-        })
-        .collect::<Vec<_>>();
-
     // Periodically re-evaluate the health state of the balancer's endpoints.
     let mut tick = tokio::time::interval(METRIC_EVAL_INTERVAL);
 
@@ -171,6 +246,17 @@ async fn metric_loop<T, C>(
         unhealthy.clear();
         tick.tick().await;
 
+        // Map the current endpoints into an endpoint and a metric.
+        let endpoints = endpoints
+            .snapshot()
+            .iter()
+            .map(|c| {
+                let name = Cow::from(c.endpoint_name().to_string());
+                let metric = metric.recorder([("endpoint", name)]);
+                (Arc::clone(c), metric)
+            })
+            .collect::<Vec<_>>();
+
         for (client, metric) in &endpoints {
             let value = match client.is_healthy() {
                 true => {
@@ -628,4 +714,45 @@ mod tests {
         circuit_err.set_healthy(true);
         assert!(balancer.endpoints().is_some());
     }
+
+    /// Replacing the endpoint set retains existing clients, initialises new
+    /// clients, and removes clients no longer named.
+    #[tokio::test]
+    async fn test_set_endpoints() {
+        let new_client = |name: &Arc<str>| {
+            CircuitBreakingClient::new(
+                Arc::new(MockWriteClient::default()),
+                Arc::clone(name),
+                ARBITRARY_TEST_NUM_PROBES,
+            )
+            .with_circuit_breaker(Arc::new(MockCircuitBreaker::default()))
+        };
+
+        let balancer = Balancer::new(
+            [new_client(&Arc::from("a")), new_client(&Arc::from("b"))],
+            None,
+        );
+        let set = balancer.endpoint_set();
+        let b = Arc::clone(&set.snapshot()[1]);
+
+        let mut added = vec![];
+        let changed = set.set(&[Arc::from("b"), Arc::from("c")], |name| {
+            added.push(Arc::clone(name));
+            new_client(name)
+        });
+        assert!(changed);
+        assert_eq!(added, [Arc::from("c")]);
+        assert_eq!(balancer.len(), 2);
+        assert_eq!(set.names(), [Arc::from("b"), Arc::from("c")]);
+
+        // The existing client was retained.
+        assert!(Arc::ptr_eq(&set.snapshot()[0], &b));
+
+        // Setting the same endpoints is a no-op.
+        let changed = set.set(&[Arc::from("c"), Arc::from("b")], |_| {
+            panic!("no new clients should be initialised")
+        });
+        assert!(!changed);
+        assert_eq!(set.names(), [Arc::from("b"), Arc::from("c")]);
+    }
 }
@@ -230,6 +230,8 @@ mod tests {
                 name: ns.to_string(),
                 max_tables: Default::default(),
                 max_columns_per_table: Default::default(),
+                max_concurrent_queries: None,
+                max_query_memory_bytes: None,
                 retention_period_ns: TEST_RETENTION_PERIOD_NS,
                 deleted_at: None,
                 partition_template: Default::default(),
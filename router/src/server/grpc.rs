@@ -1,5 +1,6 @@
 //! gRPC service implementations for `router`.
 
+use authz::{audit::AuditSink, Authorizer};
 use generated_types::influxdata::iox::{
     catalog::v1::*, gossip::v1::anti_entropy_service_server, namespace::v1::*, object_store::v1::*,
     table::v1::*,
@@ -24,6 +25,9 @@ pub struct RpcWriteGrpcDelegate<T> {
     catalog: Arc<dyn Catalog>,
     object_store: Arc<DynObjectStore>,
     anti_entropy: AntiEntropyService<T>,
+    audit_sink: Option<Arc<dyn AuditSink>>,
+    catalog_write_access: bool,
+    authz: Option<Arc<dyn Authorizer>>,
 }
 
 impl<T> RpcWriteGrpcDelegate<T> {
@@ -37,9 +41,32 @@ impl<T> RpcWriteGrpcDelegate<T> {
             catalog,
             object_store,
             anti_entropy,
+            audit_sink: None,
+            catalog_write_access: false,
+            authz: None,
         }
     }
 
+    /// Record the administrative operations performed through the
+    /// [`NamespaceService`], [`TableService`] and catalog service to `sink`.
+    ///
+    /// [`NamespaceService`]: generated_types::influxdata::iox::namespace::v1::namespace_service_server::NamespaceService.
+    /// [`TableService`]: generated_types::influxdata::iox::table::v1::table_service_server::TableService
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = Some(sink);
+        self
+    }
+
+    /// Serve the catalog modifying RPCs of the [`CatalogService`], authorized
+    /// by `authz` if provided.
+    ///
+    /// [`CatalogService`]: generated_types::influxdata::iox::catalog::v1::catalog_service_server::CatalogService.
+    pub fn with_catalog_write_access(mut self, authz: Option<Arc<dyn Authorizer>>) -> Self {
+        self.catalog_write_access = true;
+        self.authz = authz;
+        self
+    }
+
     /// Acquire a [`SchemaService`] gRPC service implementation.
     ///
     /// [`SchemaService`]: generated_types::influxdata::iox::schema::v1::schema_service_server::SchemaService.
@@ -51,7 +78,15 @@ impl<T> RpcWriteGrpcDelegate<T> {
     ///
     /// [`CatalogService`]: generated_types::influxdata::iox::catalog::v1::catalog_service_server::CatalogService.
     pub fn catalog_service(&self) -> impl catalog_service_server::CatalogService {
-        CatalogService::new(Arc::clone(&self.catalog))
+        let service = CatalogService::new(Arc::clone(&self.catalog));
+        let service = match self.catalog_write_access {
+            true => service.with_write_access(self.authz.clone()),
+            false => service,
+        };
+        match &self.audit_sink {
+            Some(sink) => service.with_audit_sink(Arc::clone(sink)),
+            None => service,
+        }
     }
 
     /// Acquire a [`ObjectStoreService`] gRPC service implementation.
@@ -65,14 +100,22 @@ impl<T> RpcWriteGrpcDelegate<T> {
     ///
     /// [`NamespaceService`]: generated_types::influxdata::iox::namespace::v1::namespace_service_server::NamespaceService.
     pub fn namespace_service(&self) -> impl namespace_service_server::NamespaceService {
-        NamespaceService::new(Arc::clone(&self.catalog))
+        let service = NamespaceService::new(Arc::clone(&self.catalog));
+        match &self.audit_sink {
+            Some(sink) => service.with_audit_sink(Arc::clone(sink)),
+            None => service,
+        }
     }
 
     /// Acquire a [`TableService`] gRPC service implementation.
     ///
     /// [`TableService`]: generated_types::influxdata::iox::table::v1::table_service_server::TableService
     pub fn table_service(&self) -> impl table_service_server::TableService {
-        TableService::new(Arc::clone(&self.catalog))
+        let service = TableService::new(Arc::clone(&self.catalog));
+        match &self.audit_sink {
+            Some(sink) => service.with_audit_sink(Arc::clone(sink)),
+            None => service,
+        }
     }
 
     /// Acquire a [`AntiEntropyService`] gRPC service implementation.
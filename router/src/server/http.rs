@@ -1,15 +1,26 @@
 //! HTTP service implementations for `router`.
 
+pub mod metric_write;
 pub mod write;
 
 use std::{str::Utf8Error, time::Instant};
 
 use bytes::{Bytes, BytesMut};
 use futures::StreamExt;
+use generated_types::{
+    opentelemetry::proto::collector::metrics::v1::{
+        ExportMetricsPartialSuccess, ExportMetricsServiceRequest, ExportMetricsServiceResponse,
+    },
+    prometheus::WriteRequest,
+    prost::Message,
+};
 use hashbrown::HashMap;
-use hyper::{header::CONTENT_ENCODING, Body, Method, Request, Response, StatusCode};
+use hyper::{
+    header::{CONTENT_ENCODING, CONTENT_TYPE},
+    Body, Method, Request, Response, StatusCode,
+};
 use iox_time::{SystemProvider, TimeProvider};
-use metric::{DurationHistogram, U64Counter};
+use metric::{DurationHistogram, Metric, U64Counter};
 use mutable_batch::MutableBatch;
 use mutable_batch_lp::{LineError, LinesConverter};
 use observability_deps::tracing::*;
@@ -17,9 +28,12 @@ use thiserror::Error;
 use tokio::sync::{Semaphore, TryAcquireError};
 use trace::ctx::SpanContext;
 
-use self::write::{
-    multi_tenant::MultiTenantExtractError, single_tenant::SingleTenantExtractError, WriteParams,
-    WriteRequestUnifier,
+use self::{
+    metric_write::{MetricWriteError, MetricWriteStats},
+    write::{
+        multi_tenant::MultiTenantExtractError, single_tenant::SingleTenantExtractError,
+        WriteParams, WriteRequestUnifier,
+    },
 };
 use crate::{
     dml_handlers::{
@@ -73,10 +87,22 @@ pub enum Error {
     #[error("error decoding gzip stream: {0}")]
     InvalidGzip(std::io::Error),
 
+    /// Decoding a snappy-compressed block of data failed.
+    #[error("error decoding snappy block: {0}")]
+    InvalidSnappy(snap::Error),
+
     /// Failure to decode the provided line protocol.
     #[error("failed to parse line protocol: {0}")]
     ParseLineProtocol(mutable_batch_lp::Error),
 
+    /// Failure to decode a protobuf encoded metrics payload.
+    #[error("failed to decode protobuf payload: {0}")]
+    InvalidProtobuf(generated_types::DecodeError),
+
+    /// Failure to convert the pushed metric samples into a write.
+    #[error("failed to convert metrics: {0}")]
+    MetricWrite(#[from] MetricWriteError),
+
     /// An error returned from the [`DmlHandler`].
     #[error("dml handler error: {0}")]
     DmlHandler(#[from] DmlError),
@@ -111,9 +137,12 @@ impl Error {
             Error::DeletesUnsupported => StatusCode::NOT_IMPLEMENTED,
             Error::ClientHangup(_) => StatusCode::BAD_REQUEST,
             Error::InvalidGzip(_) => StatusCode::BAD_REQUEST,
+            Error::InvalidSnappy(_) => StatusCode::BAD_REQUEST,
             Error::NonUtf8ContentHeader(_) => StatusCode::BAD_REQUEST,
             Error::NonUtf8Body(_) => StatusCode::BAD_REQUEST,
             Error::ParseLineProtocol(_) => StatusCode::BAD_REQUEST,
+            Error::InvalidProtobuf(_) => StatusCode::BAD_REQUEST,
+            Error::MetricWrite(_) => StatusCode::BAD_REQUEST,
             Error::RequestSizeExceeded(_) => StatusCode::PAYLOAD_TOO_LARGE,
             Error::InvalidContentEncoding(_) => {
                 // https://www.rfc-editor.org/rfc/rfc7231#section-6.5.13
@@ -226,6 +255,13 @@ pub struct HttpDelegate<D, N, T = SystemProvider> {
     write_metric_tables: U64Counter,
     write_metric_body_size: U64Counter,
     request_limit_rejected: U64Counter,
+
+    // Samples ingested through the metric push endpoints, labelled by
+    // protocol.
+    prometheus_write_samples: U64Counter,
+    prometheus_write_rejected_samples: U64Counter,
+    otlp_write_samples: U64Counter,
+    otlp_write_rejected_samples: U64Counter,
 }
 
 impl<D, N> HttpDelegate<D, N, SystemProvider> {
@@ -278,6 +314,16 @@ impl<D, N> HttpDelegate<D, N, SystemProvider> {
                 "write latency of line protocol parsing",
             )
             .recorder(&[]);
+        let metric_write_samples = metrics.register_metric::<U64Counter>(
+            "http_metric_write_samples",
+            "cumulative number of pushed metric samples successfully routed",
+        );
+        let metric_write_rejected_samples = metrics.register_metric::<U64Counter>(
+            "http_metric_write_rejected_samples",
+            "cumulative number of pushed metric samples discarded during conversion",
+        );
+        let protocol_counter =
+            |m: &Metric<U64Counter>, protocol: &'static str| m.recorder(&[("protocol", protocol)]);
 
         Self {
             max_request_bytes,
@@ -292,6 +338,13 @@ impl<D, N> HttpDelegate<D, N, SystemProvider> {
             write_metric_tables,
             write_metric_body_size,
             request_limit_rejected,
+            prometheus_write_samples: protocol_counter(&metric_write_samples, "prometheus"),
+            prometheus_write_rejected_samples: protocol_counter(
+                &metric_write_rejected_samples,
+                "prometheus",
+            ),
+            otlp_write_samples: protocol_counter(&metric_write_samples, "otlp"),
+            otlp_write_rejected_samples: protocol_counter(&metric_write_rejected_samples, "otlp"),
         }
     }
 }
@@ -323,24 +376,31 @@ where
         };
 
         // Route the request to a handler.
+        //
+        // The metric push endpoints derive the target namespace from the same
+        // org/bucket parameters (and authorization rules) as V2 writes.
         match (req.method(), req.uri().path()) {
             (&Method::POST, "/write") => {
                 let dml_info = self.write_request_mode_handler.parse_v1(&req).await?;
-                self.write_handler(req, dml_info).await
+                self.write_handler(req, dml_info).await.map(no_content)
             }
             (&Method::POST, "/api/v2/write") => {
                 let dml_info = self.write_request_mode_handler.parse_v2(&req).await?;
-                self.write_handler(req, dml_info).await
+                self.write_handler(req, dml_info).await.map(no_content)
             }
-            (&Method::POST, "/api/v2/delete") => return Err(Error::DeletesUnsupported),
-            _ => return Err(Error::NoHandler),
+            (&Method::POST, "/api/v2/prom/write") => {
+                let dml_info = self.write_request_mode_handler.parse_v2(&req).await?;
+                self.prometheus_write_handler(req, dml_info)
+                    .await
+                    .map(no_content)
+            }
+            (&Method::POST, "/api/v2/otlp/v1/metrics") => {
+                let dml_info = self.write_request_mode_handler.parse_v2(&req).await?;
+                self.otlp_write_handler(req, dml_info).await
+            }
+            (&Method::POST, "/api/v2/delete") => Err(Error::DeletesUnsupported),
+            _ => Err(Error::NoHandler),
         }
-        .map(|_summary| {
-            Response::builder()
-                .status(StatusCode::NO_CONTENT)
-                .body(Body::empty())
-                .unwrap()
-        })
     }
 
     async fn write_handler(
@@ -369,12 +429,17 @@ where
         let (batches, stats) = match converter.write_lp(body).and_then(|_| converter.finish()) {
             Ok(v) => v,
             Err(mutable_batch_lp::Error::EmptyPayload) => {
+                write_info.authorize_tables([]).await?;
                 debug!("nothing to write");
                 return Ok(());
             }
             Err(line_errors) => return Err(Error::ParseLineProtocol(line_errors)),
         };
 
+        write_info
+            .authorize_tables(batches.keys().map(String::as_str))
+            .await?;
+
         let num_tables = batches.len();
         let duration = start_instant.elapsed();
         self.http_line_protocol_parse_duration.record(duration);
@@ -408,6 +473,119 @@ where
         Ok(())
     }
 
+    /// Handle a [Prometheus remote write] request, writing the decoded
+    /// samples into `write_info.namespace`.
+    ///
+    /// [Prometheus remote write]:
+    ///     https://prometheus.io/docs/concepts/remote_write_spec/
+    async fn prometheus_write_handler(
+        &self,
+        req: Request<Body>,
+        write_info: WriteParams,
+    ) -> Result<(), Error> {
+        let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
+
+        let body = self.read_body(req).await?;
+        let body_len = body.len();
+        let write_req = WriteRequest::decode(body).map_err(Error::InvalidProtobuf)?;
+
+        let (batches, stats) = metric_write::prometheus::convert(&write_req)?;
+        self.prometheus_write_rejected_samples
+            .inc(stats.num_rejected as _);
+
+        self.write_metric_batches(&write_info, batches, stats, body_len, span_ctx)
+            .await?;
+        self.prometheus_write_samples.inc(stats.num_samples as _);
+
+        Ok(())
+    }
+
+    /// Handle an [OTLP/HTTP] metrics export request, writing the decoded
+    /// data points into `write_info.namespace`.
+    ///
+    /// Only the binary protobuf encoding is supported. On success, the
+    /// encoded [`ExportMetricsServiceResponse`] is returned, reporting any
+    /// data points that were discarded as a partial success.
+    ///
+    /// [OTLP/HTTP]: https://opentelemetry.io/docs/specs/otlp/#otlphttp
+    async fn otlp_write_handler(
+        &self,
+        req: Request<Body>,
+        write_info: WriteParams,
+    ) -> Result<Response<Body>, Error> {
+        let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
+
+        let body = self.read_body(req).await?;
+        let body_len = body.len();
+        let export_req =
+            ExportMetricsServiceRequest::decode(body).map_err(Error::InvalidProtobuf)?;
+
+        let default_time = self.time_provider.now().timestamp_nanos();
+        let (batches, stats) = metric_write::otlp::convert(&export_req, default_time)?;
+        self.otlp_write_rejected_samples
+            .inc(stats.num_rejected as _);
+
+        self.write_metric_batches(&write_info, batches, stats, body_len, span_ctx)
+            .await?;
+        self.otlp_write_samples.inc(stats.num_samples as _);
+
+        let partial_success = (stats.num_rejected > 0).then(|| ExportMetricsPartialSuccess {
+            rejected_data_points: stats.num_rejected as _,
+            error_message: "data points without a value were discarded".to_string(),
+        });
+        let resp = ExportMetricsServiceResponse { partial_success };
+
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, "application/x-protobuf")
+            .body(Body::from(resp.encode_to_vec()))
+            .unwrap())
+    }
+
+    /// Pass the converted metric `batches` to the DML handler.
+    async fn write_metric_batches(
+        &self,
+        write_info: &WriteParams,
+        batches: HashMap<String, MutableBatch>,
+        stats: MetricWriteStats,
+        body_len: usize,
+        span_ctx: Option<SpanContext>,
+    ) -> Result<(), Error> {
+        write_info
+            .authorize_tables(batches.keys().map(String::as_str))
+            .await?;
+
+        if batches.is_empty() {
+            debug!("nothing to write");
+            return Ok(());
+        }
+
+        let num_tables = batches.len();
+        debug!(
+            num_samples=stats.num_samples,
+            num_rejected=stats.num_rejected,
+            num_tables,
+            body_size=body_len,
+            namespace=%write_info.namespace,
+            "routing metric write",
+        );
+
+        let namespace_schema = self
+            .namespace_resolver
+            .get_namespace_schema(&write_info.namespace)
+            .await?;
+
+        self.dml_handler
+            .write(&write_info.namespace, namespace_schema, batches, span_ctx)
+            .await
+            .map_err(Into::into)?;
+
+        self.write_metric_tables.inc(num_tables as _);
+        self.write_metric_body_size.inc(body_len as _);
+
+        Ok(())
+    }
+
     /// Parse the request's body into raw bytes, applying the configured size
     /// limits and decoding any content encoding.
     async fn read_body(&self, req: hyper::Request<Body>) -> Result<Bytes, Error> {
@@ -416,9 +594,12 @@ where
             .get(&CONTENT_ENCODING)
             .map(|v| v.to_str().map_err(Error::NonUtf8ContentHeader))
             .transpose()?;
-        let ungzip = match encoding {
-            None | Some("identity") => false,
-            Some("gzip") => true,
+        let (ungzip, unsnappy) = match encoding {
+            None | Some("identity") => (false, false),
+            Some("gzip") => (true, false),
+            // Used by Prometheus remote write, which compresses the body as a
+            // single snappy block (not the framed format).
+            Some("snappy") => (false, true),
             Some(v) => return Err(Error::InvalidContentEncoding(v.to_string())),
         };
 
@@ -435,6 +616,19 @@ where
         }
         let body = body.freeze();
 
+        if unsnappy {
+            // The decompressed length is encoded in the block header, allowing
+            // oversized payloads to be rejected before decompressing them.
+            let len = snap::raw::decompress_len(&body).map_err(Error::InvalidSnappy)?;
+            if len > self.max_request_bytes {
+                return Err(Error::RequestSizeExceeded(self.max_request_bytes));
+            }
+            return snap::raw::Decoder::new()
+                .decompress_vec(&body)
+                .map(Into::into)
+                .map_err(Error::InvalidSnappy);
+        }
+
         // If the body is not compressed, return early.
         if !ungzip {
             return Ok(body);
@@ -466,6 +660,14 @@ where
     }
 }
 
+/// Construct an empty "204 No Content" response for a successful write.
+fn no_content(_: ()) -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .body(Body::empty())
+        .unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use std::{io::Write, iter, sync::Arc, time::Duration};
@@ -1106,6 +1308,7 @@ mod tests {
                     Ok(WriteParams {
                         namespace: NamespaceName::new(NAMESPACE_NAME).unwrap(),
                         precision: Precision::default(),
+                        table_authz: None,
                     })
                 })),
             ),
@@ -1246,6 +1449,198 @@ mod tests {
         assert_matches!(got, Err(Error::NoHandler));
     }
 
+    /// Assert a snappy-compressed Prometheus remote write request is decoded
+    /// and passed to the DML handler.
+    #[tokio::test]
+    async fn test_prometheus_remote_write() {
+        use generated_types::prometheus::{Label, Sample, TimeSeries};
+
+        let mock_namespace_resolver =
+            MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NAMESPACE_ID);
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(
+            MAX_BYTES,
+            1,
+            mock_namespace_resolver,
+            Arc::clone(&dml_handler),
+            &metrics,
+            Box::<MultiTenantRequestUnifier>::default(),
+        );
+
+        let body = WriteRequest {
+            timeseries: vec![TimeSeries {
+                labels: vec![
+                    Label {
+                        name: "__name__".to_string(),
+                        value: "up".to_string(),
+                    },
+                    Label {
+                        name: "job".to_string(),
+                        value: "router".to_string(),
+                    },
+                ],
+                samples: vec![Sample {
+                    value: 1.0,
+                    timestamp: 1647622847000,
+                }],
+            }],
+            metadata: vec![],
+        }
+        .encode_to_vec();
+        let body = snap::raw::Encoder::new()
+            .compress_vec(&body)
+            .expect("failed to compress test body");
+
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/prom/write?org=bananas&bucket=test")
+            .method("POST")
+            .header(CONTENT_ENCODING, "snappy")
+            .body(Body::from(body))
+            .unwrap();
+
+        let got = delegate.route(request).await.expect("write should succeed");
+        assert_eq!(got.status(), StatusCode::NO_CONTENT);
+
+        assert_matches!(
+            dml_handler.calls().as_slice(),
+            [MockDmlHandlerCall::Write { namespace, write_input, .. }] => {
+                assert_eq!(namespace, NAMESPACE_NAME);
+                let table = write_input.get("up").expect("table not found");
+                let ts = table.timestamp_summary().expect("no timestamp summary");
+                assert_eq!(Some(1647622847000000000), ts.stats.min);
+            }
+        );
+
+        let samples = metrics
+            .get_instrument::<Metric<U64Counter>>("http_metric_write_samples")
+            .expect("failed to read metric")
+            .get_observer(&Attributes::from(&[("protocol", "prometheus")]))
+            .expect("failed to get observer")
+            .fetch();
+        assert_eq!(samples, 1);
+    }
+
+    /// Assert the snappy decoder rejects payloads that would exceed the
+    /// configured maximum request size once decompressed.
+    #[tokio::test]
+    async fn test_snappy_max_request_size() {
+        let mock_namespace_resolver =
+            MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NAMESPACE_ID);
+        let dml_handler = Arc::new(MockDmlHandler::default());
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(
+            MAX_BYTES,
+            1,
+            mock_namespace_resolver,
+            Arc::clone(&dml_handler),
+            &metrics,
+            Box::<MultiTenantRequestUnifier>::default(),
+        );
+
+        // A highly compressible body that is well under the limit when
+        // compressed, but over it when decompressed.
+        let body = snap::raw::Encoder::new()
+            .compress_vec(&[42; MAX_BYTES + 1])
+            .expect("failed to compress test body");
+        assert!(body.len() < MAX_BYTES);
+
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/prom/write?org=bananas&bucket=test")
+            .method("POST")
+            .header(CONTENT_ENCODING, "snappy")
+            .body(Body::from(body))
+            .unwrap();
+
+        let got = delegate.route(request).await;
+        assert_matches!(got, Err(Error::RequestSizeExceeded(_)));
+        assert!(dml_handler.calls().is_empty());
+    }
+
+    /// Assert an OTLP metrics export request is decoded, passed to the DML
+    /// handler, and answered with an OTLP response reporting any discarded
+    /// data points.
+    #[tokio::test]
+    async fn test_otlp_metrics_write() {
+        use generated_types::opentelemetry::proto::metrics::v1::{
+            metric::Data, number_data_point, Gauge, Metric as OtlpMetric, NumberDataPoint,
+            ResourceMetrics, ScopeMetrics,
+        };
+
+        let mock_namespace_resolver =
+            MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NAMESPACE_ID);
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(
+            MAX_BYTES,
+            1,
+            mock_namespace_resolver,
+            Arc::clone(&dml_handler),
+            &metrics,
+            Box::<MultiTenantRequestUnifier>::default(),
+        );
+
+        let point = |value| NumberDataPoint {
+            attributes: vec![],
+            start_time_unix_nano: 0,
+            time_unix_nano: 1647622847000000000,
+            value,
+            flags: 0,
+        };
+        let body = ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: None,
+                scope_metrics: vec![ScopeMetrics {
+                    scope: None,
+                    metrics: vec![OtlpMetric {
+                        name: "platanos".to_string(),
+                        description: String::new(),
+                        unit: String::new(),
+                        data: Some(Data::Gauge(Gauge {
+                            data_points: vec![
+                                point(Some(number_data_point::Value::AsInt(42))),
+                                point(None),
+                            ],
+                        })),
+                    }],
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        }
+        .encode_to_vec();
+
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/otlp/v1/metrics?org=bananas&bucket=test")
+            .method("POST")
+            .body(Body::from(body))
+            .unwrap();
+
+        let got = delegate.route(request).await.expect("write should succeed");
+        assert_eq!(got.status(), StatusCode::OK);
+
+        let body = hyper::body::to_bytes(got.into_body())
+            .await
+            .expect("failed to read response body");
+        let resp =
+            ExportMetricsServiceResponse::decode(body).expect("failed to decode OTLP response");
+        assert_matches!(
+            resp.partial_success,
+            Some(ExportMetricsPartialSuccess {
+                rejected_data_points: 1,
+                ..
+            })
+        );
+
+        assert_matches!(
+            dml_handler.calls().as_slice(),
+            [MockDmlHandlerCall::Write { namespace, write_input, .. }] => {
+                assert_eq!(namespace, NAMESPACE_NAME);
+                assert_eq!(write_input.get("platanos").expect("table not found").rows(), 1);
+            }
+        );
+    }
+
     /// Assert the router delegates request parsing to the
     /// [`WriteRequestUnifier`] implementation.
     ///
@@ -1262,6 +1657,7 @@ mod tests {
                 Ok(WriteParams {
                     namespace: NamespaceName::new(NAMESPACE_NAME).unwrap(),
                     precision: Precision::default(),
+                    table_authz: None,
                 })
             }),
         ));
@@ -1414,6 +1810,22 @@ mod tests {
             "error decoding gzip stream: [io Error]",
         ),
 
+        (
+            InvalidSnappy(snap::Error::Empty),
+            "error decoding snappy block: snappy: corrupt input (empty)",
+        ),
+
+        (
+            InvalidProtobuf(WriteRequest::decode(&[0xFF][..]).unwrap_err()),
+            "failed to decode protobuf payload: failed to decode Protobuf message: \
+            invalid varint",
+        ),
+
+        (
+            MetricWrite(MetricWriteError::MissingMetricName),
+            "failed to convert metrics: series has no metric name",
+        ),
+
         (
             ParseLineProtocol(mutable_batch_lp::Error::PerLine {
                 lines: vec![mutable_batch_lp::LineError::LineProtocol {
@@ -0,0 +1,203 @@
+//! Conversion of metric samples pushed by external monitoring systems into
+//! IOx writes.
+//!
+//! Both the [Prometheus remote write] and [OTLP] metrics payloads are mapped
+//! onto the same data model, mirroring the Prometheus exposition format:
+//!
+//!   * The metric name is used as the table name.
+//!   * Each label (or attribute) becomes a tag column.
+//!   * The sample value is written to a single float field named
+//!     [`VALUE_FIELD_NAME`].
+//!   * The sample timestamp is written to the `time` column.
+//!
+//! Composite metric types (histograms and summaries) are flattened into the
+//! `_bucket`, `_count` and `_sum` tables a Prometheus scrape of the same
+//! metric would produce, so queries behave identically regardless of which
+//! protocol was used to push the data.
+//!
+//! [Prometheus remote write]:
+//!     https://prometheus.io/docs/concepts/remote_write_spec/
+//! [OTLP]: https://opentelemetry.io/docs/specs/otlp/
+
+pub mod otlp;
+pub mod prometheus;
+
+use hashbrown::{HashMap, HashSet};
+use mutable_batch::{writer::Writer, MutableBatch};
+use schema::TIME_COLUMN_NAME;
+use thiserror::Error;
+
+/// The name of the field column each sample value is written to.
+pub const VALUE_FIELD_NAME: &str = "value";
+
+/// Errors converting a metrics payload into [`MutableBatch`] instances.
+#[derive(Debug, Error)]
+pub enum MetricWriteError {
+    /// A series was provided without a metric name.
+    #[error("series has no metric name")]
+    MissingMetricName,
+
+    /// A label / attribute uses a column name reserved by the IOx metric data
+    /// model.
+    #[error("label name '{0}' conflicts with a reserved column name")]
+    ReservedLabelName(String),
+
+    /// A label / attribute name appears more than once in a single series.
+    #[error("label '{0}' is specified more than once")]
+    DuplicateLabelName(String),
+
+    /// A sample timestamp cannot be represented as nanoseconds since the
+    /// epoch in an i64.
+    #[error("sample timestamp overflows i64 nanoseconds")]
+    TimestampOverflow,
+
+    /// A sample could not be written to the table batch, typically due to a
+    /// column type conflict within the request.
+    #[error("error writing to table '{table}': {source}")]
+    Write {
+        /// The table the sample was being written to.
+        table: String,
+        /// The underlying writer error.
+        source: mutable_batch::writer::Error,
+    },
+}
+
+/// Statistics describing a converted metrics payload.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MetricWriteStats {
+    /// The number of rows (samples) written to the output batches.
+    pub num_samples: usize,
+    /// The number of input samples / data points that were discarded.
+    pub num_rejected: usize,
+}
+
+/// Accumulates converted metric samples into a set of per-table
+/// [`MutableBatch`] instances.
+#[derive(Debug, Default)]
+pub(crate) struct TableBatches {
+    batches: HashMap<String, MutableBatch>,
+    stats: MetricWriteStats,
+}
+
+impl TableBatches {
+    /// Append a single sample for the series described by `table` and
+    /// `tags`.
+    ///
+    /// Tags with empty values are omitted, matching the Prometheus semantics
+    /// of an empty label value being equivalent to the label being absent.
+    pub(crate) fn push<'a, I>(
+        &mut self,
+        table: &str,
+        tags: I,
+        value: f64,
+        timestamp_nanos: i64,
+    ) -> Result<(), MetricWriteError>
+    where
+        I: IntoIterator<Item = (&'a str, &'a str)>,
+    {
+        if table.is_empty() {
+            return Err(MetricWriteError::MissingMetricName);
+        }
+
+        let batch = self.batches.entry_ref(table).or_default();
+
+        let mut writer = Writer::new(batch, 1);
+        let mut seen = HashSet::new();
+        for (name, tag_value) in tags {
+            if name == TIME_COLUMN_NAME || name == VALUE_FIELD_NAME {
+                return Err(MetricWriteError::ReservedLabelName(name.to_string()));
+            }
+            if !seen.insert(name) {
+                return Err(MetricWriteError::DuplicateLabelName(name.to_string()));
+            }
+            if tag_value.is_empty() {
+                continue;
+            }
+            writer
+                .write_tag(name, None, std::iter::once(tag_value))
+                .map_err(|source| MetricWriteError::Write {
+                    table: table.to_string(),
+                    source,
+                })?;
+        }
+
+        writer
+            .write_f64(VALUE_FIELD_NAME, None, std::iter::once(value))
+            .and_then(|_| writer.write_time(TIME_COLUMN_NAME, std::iter::once(timestamp_nanos)))
+            .map_err(|source| MetricWriteError::Write {
+                table: table.to_string(),
+                source,
+            })?;
+
+        writer.commit();
+        self.stats.num_samples += 1;
+
+        Ok(())
+    }
+
+    /// Record `n` input samples as discarded.
+    pub(crate) fn reject(&mut self, n: usize) {
+        self.stats.num_rejected += n;
+    }
+
+    /// Consume this accumulator, returning the per-table batches and the
+    /// conversion statistics.
+    pub(crate) fn finish(self) -> (HashMap<String, MutableBatch>, MetricWriteStats) {
+        (self.batches, self.stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn test_reserved_label_names() {
+        for name in [TIME_COLUMN_NAME, VALUE_FIELD_NAME] {
+            let mut batches = TableBatches::default();
+            let got = batches.push("bananas", [(name, "platanos")], 4.2, 42);
+            assert_matches!(got, Err(MetricWriteError::ReservedLabelName(n)) => {
+                assert_eq!(n, name);
+            });
+
+            // The failed row must not have been committed.
+            let (batches, stats) = batches.finish();
+            assert_eq!(batches["bananas"].rows(), 0);
+            assert_eq!(stats.num_samples, 0);
+        }
+    }
+
+    #[test]
+    fn test_duplicate_label_names() {
+        let mut batches = TableBatches::default();
+        let got = batches.push("bananas", [("a", "b"), ("a", "c")], 4.2, 42);
+        assert_matches!(got, Err(MetricWriteError::DuplicateLabelName(n)) => {
+            assert_eq!(n, "a");
+        });
+    }
+
+    #[test]
+    fn test_missing_table_name() {
+        let mut batches = TableBatches::default();
+        let got = batches.push("", [("a", "b")], 4.2, 42);
+        assert_matches!(got, Err(MetricWriteError::MissingMetricName));
+    }
+
+    #[test]
+    fn test_empty_tag_values_omitted() {
+        let mut batches = TableBatches::default();
+        batches
+            .push("bananas", [("a", "b"), ("c", "")], 4.2, 42)
+            .expect("write should succeed");
+
+        let (batches, stats) = batches.finish();
+        assert_eq!(stats.num_samples, 1);
+        let names = batches["bananas"].column_names();
+        assert_eq!(
+            names.into_iter().collect::<Vec<_>>(),
+            ["a", "time", "value"]
+        );
+    }
+}
@@ -0,0 +1,487 @@
+//! Conversion of [OTLP] metrics export requests.
+//!
+//! Resource attributes and data point attributes are both written as tags,
+//! with data point attributes taking precedence when the same key is present
+//! in both. Attributes with non-scalar values (arrays, key/value lists and
+//! byte strings) are not representable as a tag and are skipped.
+//!
+//! [OTLP]: https://opentelemetry.io/docs/specs/otlp/
+
+use std::collections::BTreeMap;
+
+use generated_types::opentelemetry::proto::{
+    collector::metrics::v1::ExportMetricsServiceRequest,
+    common::v1::{any_value, KeyValue},
+    metrics::v1::{metric::Data, number_data_point, NumberDataPoint},
+};
+use hashbrown::HashMap;
+use mutable_batch::MutableBatch;
+
+use super::{MetricWriteError, MetricWriteStats, TableBatches};
+
+/// The tag containing the upper bound of a histogram bucket.
+const BUCKET_BOUND_TAG: &str = "le";
+
+/// The tag containing the quantile of a summary value.
+const QUANTILE_TAG: &str = "quantile";
+
+/// Convert a decoded OTLP [`ExportMetricsServiceRequest`] into a set of
+/// per-table [`MutableBatch`].
+///
+/// Gauge and sum data points are written to the table named after the
+/// metric. Histograms and summaries are flattened into the
+/// `<name>_bucket`/`<name>` (with an `le`/`quantile` tag), `<name>_count` and
+/// `<name>_sum` tables, matching their Prometheus representation. Only the
+/// count and sum of exponential histograms are retained.
+///
+/// Data points without a timestamp are assigned `default_time`. Number data
+/// points without a value are discarded and counted as rejected.
+pub fn convert(
+    req: &ExportMetricsServiceRequest,
+    default_time: i64,
+) -> Result<(HashMap<String, MutableBatch>, MetricWriteStats), MetricWriteError> {
+    let mut batches = TableBatches::default();
+
+    for resource_metrics in &req.resource_metrics {
+        let resource_attrs = resource_metrics
+            .resource
+            .as_ref()
+            .map(|r| r.attributes.as_slice())
+            .unwrap_or_default();
+
+        for metric in resource_metrics
+            .scope_metrics
+            .iter()
+            .flat_map(|s| s.metrics.iter())
+        {
+            let name = metric.name.as_str();
+            let data = match &metric.data {
+                Some(v) => v,
+                None => continue,
+            };
+
+            match data {
+                Data::Gauge(v) => write_number_points(
+                    &mut batches,
+                    name,
+                    resource_attrs,
+                    &v.data_points,
+                    default_time,
+                )?,
+                Data::Sum(v) => write_number_points(
+                    &mut batches,
+                    name,
+                    resource_attrs,
+                    &v.data_points,
+                    default_time,
+                )?,
+                Data::Histogram(v) => {
+                    for p in &v.data_points {
+                        let tags = merge_attributes(resource_attrs, &p.attributes);
+                        let ts = timestamp(p.time_unix_nano, default_time)?;
+
+                        // OTLP bucket counts are per-bucket, whereas the
+                        // Prometheus representation is cumulative.
+                        let mut cumulative = 0;
+                        for (i, count) in p.bucket_counts.iter().enumerate() {
+                            cumulative += count;
+                            let bound = p
+                                .explicit_bounds
+                                .get(i)
+                                .map(|b| b.to_string())
+                                .unwrap_or_else(|| "+Inf".to_string());
+
+                            batches.push(
+                                &format!("{name}_bucket"),
+                                with_tag(&tags, BUCKET_BOUND_TAG, &bound),
+                                cumulative as f64,
+                                ts,
+                            )?;
+                        }
+
+                        write_count_sum(&mut batches, name, &tags, p.count, p.sum, ts)?;
+                    }
+                }
+                Data::ExponentialHistogram(v) => {
+                    for p in &v.data_points {
+                        let tags = merge_attributes(resource_attrs, &p.attributes);
+                        let ts = timestamp(p.time_unix_nano, default_time)?;
+                        write_count_sum(&mut batches, name, &tags, p.count, p.sum, ts)?;
+                    }
+                }
+                Data::Summary(v) => {
+                    for p in &v.data_points {
+                        let tags = merge_attributes(resource_attrs, &p.attributes);
+                        let ts = timestamp(p.time_unix_nano, default_time)?;
+
+                        for q in &p.quantile_values {
+                            batches.push(
+                                name,
+                                with_tag(&tags, QUANTILE_TAG, &q.quantile.to_string()),
+                                q.value,
+                                ts,
+                            )?;
+                        }
+
+                        write_count_sum(&mut batches, name, &tags, p.count, Some(p.sum), ts)?;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(batches.finish())
+}
+
+/// Write the gauge / sum `points` of the metric `name` to `batches`.
+fn write_number_points(
+    batches: &mut TableBatches,
+    name: &str,
+    resource_attrs: &[KeyValue],
+    points: &[NumberDataPoint],
+    default_time: i64,
+) -> Result<(), MetricWriteError> {
+    for p in points {
+        let value = match p.value {
+            Some(number_data_point::Value::AsDouble(v)) => v,
+            Some(number_data_point::Value::AsInt(v)) => v as f64,
+            None => {
+                batches.reject(1);
+                continue;
+            }
+        };
+
+        let tags = merge_attributes(resource_attrs, &p.attributes);
+        let ts = timestamp(p.time_unix_nano, default_time)?;
+        batches.push(name, iter_tags(&tags), value, ts)?;
+    }
+
+    Ok(())
+}
+
+/// Write the `<name>_count` and (if present) `<name>_sum` series of a
+/// histogram or summary data point.
+fn write_count_sum(
+    batches: &mut TableBatches,
+    name: &str,
+    tags: &BTreeMap<&str, String>,
+    count: u64,
+    sum: Option<f64>,
+    ts: i64,
+) -> Result<(), MetricWriteError> {
+    batches.push(&format!("{name}_count"), iter_tags(tags), count as f64, ts)?;
+    if let Some(sum) = sum {
+        batches.push(&format!("{name}_sum"), iter_tags(tags), sum, ts)?;
+    }
+    Ok(())
+}
+
+/// Merge the `resource` and data `point` attributes into a single, ordered
+/// set of tags, with the data point attributes overriding any resource
+/// attribute of the same name.
+fn merge_attributes<'a>(
+    resource: &'a [KeyValue],
+    point: &'a [KeyValue],
+) -> BTreeMap<&'a str, String> {
+    resource
+        .iter()
+        .chain(point.iter())
+        .filter_map(|kv| {
+            let value = kv.value.as_ref()?.value.as_ref()?;
+            let value = match value {
+                any_value::Value::StringValue(v) => v.clone(),
+                any_value::Value::BoolValue(v) => v.to_string(),
+                any_value::Value::IntValue(v) => v.to_string(),
+                any_value::Value::DoubleValue(v) => v.to_string(),
+                any_value::Value::ArrayValue(_)
+                | any_value::Value::KvlistValue(_)
+                | any_value::Value::BytesValue(_) => return None,
+            };
+            Some((kv.key.as_str(), value))
+        })
+        .collect()
+}
+
+fn iter_tags<'a>(
+    tags: &'a BTreeMap<&'a str, String>,
+) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+    tags.iter().map(|(k, v)| (*k, v.as_str()))
+}
+
+/// Return the tags in `tags`, with `name` set to `value`.
+fn with_tag<'a>(
+    tags: &'a BTreeMap<&'a str, String>,
+    name: &'a str,
+    value: &'a str,
+) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+    iter_tags(tags)
+        .filter(move |(k, _)| *k != name)
+        .chain(std::iter::once((name, value)))
+}
+
+/// Convert an OTLP `time_unix_nano` timestamp into an IOx timestamp,
+/// substituting `default_time` if unset.
+fn timestamp(time_unix_nano: u64, default_time: i64) -> Result<i64, MetricWriteError> {
+    match time_unix_nano {
+        0 => Ok(default_time),
+        v => i64::try_from(v).map_err(|_| MetricWriteError::TimestampOverflow),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use generated_types::opentelemetry::proto::{
+        common::v1::AnyValue,
+        metrics::v1::{
+            summary_data_point::ValueAtQuantile, Gauge, Histogram, HistogramDataPoint, Metric,
+            ResourceMetrics, ScopeMetrics, Summary, SummaryDataPoint,
+        },
+        resource::v1::Resource,
+    };
+    use mutable_batch::column::ColumnData;
+
+    use super::*;
+
+    const DEFAULT_TIME: i64 = 4242;
+
+    fn kv(key: &str, value: any_value::Value) -> KeyValue {
+        KeyValue {
+            key: key.to_string(),
+            value: Some(AnyValue { value: Some(value) }),
+        }
+    }
+
+    fn request(resource: Vec<KeyValue>, metrics: Vec<Metric>) -> ExportMetricsServiceRequest {
+        ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: Some(Resource {
+                    attributes: resource,
+                    dropped_attributes_count: 0,
+                }),
+                scope_metrics: vec![ScopeMetrics {
+                    scope: None,
+                    metrics,
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        }
+    }
+
+    fn metric(name: &str, data: Data) -> Metric {
+        Metric {
+            name: name.to_string(),
+            description: String::new(),
+            unit: String::new(),
+            data: Some(data),
+        }
+    }
+
+    fn tag_values(batch: &MutableBatch, column: &str) -> Vec<Option<String>> {
+        match batch.column(column).unwrap().data() {
+            ColumnData::Tag(keys, dict, _) => keys
+                .iter()
+                .map(|k| dict.lookup_id(*k).map(ToString::to_string))
+                .collect(),
+            _ => panic!("column {column} is not a tag column"),
+        }
+    }
+
+    #[test]
+    fn test_gauge() {
+        let req = request(
+            vec![
+                kv(
+                    "service.name",
+                    any_value::Value::StringValue("router".into()),
+                ),
+                kv("region", any_value::Value::StringValue("us-east".into())),
+            ],
+            vec![metric(
+                "cpu.utilization",
+                Data::Gauge(Gauge {
+                    data_points: vec![
+                        NumberDataPoint {
+                            attributes: vec![
+                                // Overrides the resource attribute
+                                kv("region", any_value::Value::StringValue("eu-west".into())),
+                                kv("core", any_value::Value::IntValue(1)),
+                                // Not representable as a tag
+                                kv("raw", any_value::Value::BytesValue(vec![42])),
+                            ],
+                            start_time_unix_nano: 0,
+                            time_unix_nano: 1_000,
+                            value: Some(number_data_point::Value::AsDouble(0.5)),
+                            flags: 0,
+                        },
+                        NumberDataPoint {
+                            attributes: vec![],
+                            start_time_unix_nano: 0,
+                            time_unix_nano: 0,
+                            value: Some(number_data_point::Value::AsInt(2)),
+                            flags: 0,
+                        },
+                        NumberDataPoint {
+                            attributes: vec![],
+                            start_time_unix_nano: 0,
+                            time_unix_nano: 2_000,
+                            value: None,
+                            flags: 0,
+                        },
+                    ],
+                }),
+            )],
+        );
+
+        let (batches, stats) = convert(&req, DEFAULT_TIME).expect("conversion should succeed");
+        assert_eq!(
+            stats,
+            MetricWriteStats {
+                num_samples: 2,
+                num_rejected: 1,
+            }
+        );
+
+        let batch = &batches["cpu.utilization"];
+        assert_eq!(
+            batch.column_names().into_iter().collect::<Vec<_>>(),
+            ["core", "region", "service.name", "time", "value"]
+        );
+        assert_eq!(
+            tag_values(batch, "region"),
+            [Some("eu-west".to_string()), Some("us-east".to_string())]
+        );
+        assert_matches!(
+            batch.column("time").unwrap().data(),
+            ColumnData::I64(v, _) => {
+                assert_eq!(v, &[1_000, DEFAULT_TIME]);
+            }
+        );
+        assert_matches!(
+            batch.column("value").unwrap().data(),
+            ColumnData::F64(v, _) => {
+                assert_eq!(v, &[0.5, 2.0]);
+            }
+        );
+    }
+
+    #[test]
+    fn test_histogram() {
+        let req = request(
+            vec![],
+            vec![metric(
+                "latency",
+                Data::Histogram(Histogram {
+                    data_points: vec![HistogramDataPoint {
+                        attributes: vec![kv("path", any_value::Value::StringValue("/".into()))],
+                        start_time_unix_nano: 0,
+                        time_unix_nano: 1_000,
+                        count: 6,
+                        sum: Some(42.0),
+                        bucket_counts: vec![1, 2, 3],
+                        explicit_bounds: vec![0.5, 1.0],
+                        flags: 0,
+                        min: None,
+                        max: None,
+                    }],
+                    aggregation_temporality: 2,
+                }),
+            )],
+        );
+
+        let (batches, stats) = convert(&req, DEFAULT_TIME).expect("conversion should succeed");
+        assert_eq!(stats.num_samples, 5);
+
+        let buckets = &batches["latency_bucket"];
+        assert_eq!(
+            tag_values(buckets, "le"),
+            [
+                Some("0.5".to_string()),
+                Some("1".to_string()),
+                Some("+Inf".to_string())
+            ]
+        );
+        assert_matches!(
+            buckets.column("value").unwrap().data(),
+            ColumnData::F64(v, _) => {
+                assert_eq!(v, &[1.0, 3.0, 6.0]);
+            }
+        );
+        assert_matches!(
+            batches["latency_count"].column("value").unwrap().data(),
+            ColumnData::F64(v, _) => {
+                assert_eq!(v, &[6.0]);
+            }
+        );
+        assert_matches!(
+            batches["latency_sum"].column("value").unwrap().data(),
+            ColumnData::F64(v, _) => {
+                assert_eq!(v, &[42.0]);
+            }
+        );
+    }
+
+    #[test]
+    fn test_summary() {
+        let req = request(
+            vec![],
+            vec![metric(
+                "rpc_duration",
+                Data::Summary(Summary {
+                    data_points: vec![SummaryDataPoint {
+                        attributes: vec![],
+                        start_time_unix_nano: 0,
+                        time_unix_nano: 1_000,
+                        count: 10,
+                        sum: 20.0,
+                        quantile_values: vec![
+                            ValueAtQuantile {
+                                quantile: 0.5,
+                                value: 1.5,
+                            },
+                            ValueAtQuantile {
+                                quantile: 0.99,
+                                value: 3.5,
+                            },
+                        ],
+                        flags: 0,
+                    }],
+                }),
+            )],
+        );
+
+        let (batches, stats) = convert(&req, DEFAULT_TIME).expect("conversion should succeed");
+        assert_eq!(stats.num_samples, 4);
+        assert_eq!(
+            tag_values(&batches["rpc_duration"], "quantile"),
+            [Some("0.5".to_string()), Some("0.99".to_string())]
+        );
+        assert_eq!(batches["rpc_duration_count"].rows(), 1);
+        assert_eq!(batches["rpc_duration_sum"].rows(), 1);
+    }
+
+    #[test]
+    fn test_timestamp_overflow() {
+        let req = request(
+            vec![],
+            vec![metric(
+                "bananas",
+                Data::Gauge(Gauge {
+                    data_points: vec![NumberDataPoint {
+                        attributes: vec![],
+                        start_time_unix_nano: 0,
+                        time_unix_nano: u64::MAX,
+                        value: Some(number_data_point::Value::AsInt(2)),
+                        flags: 0,
+                    }],
+                }),
+            )],
+        );
+
+        assert_matches!(
+            convert(&req, DEFAULT_TIME),
+            Err(MetricWriteError::TimestampOverflow)
+        );
+    }
+}
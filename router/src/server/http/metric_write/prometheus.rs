@@ -0,0 +1,212 @@
+//! Conversion of [Prometheus remote write] requests.
+//!
+//! [Prometheus remote write]:
+//!     https://prometheus.io/docs/concepts/remote_write_spec/
+
+use generated_types::prometheus::WriteRequest;
+use hashbrown::HashMap;
+use mutable_batch::MutableBatch;
+
+use super::{MetricWriteError, MetricWriteStats, TableBatches};
+
+/// The label carrying the metric name of a series.
+const METRIC_NAME_LABEL: &str = "__name__";
+
+/// The bit pattern of the NaN value Prometheus uses to mark a series as
+/// stale.
+///
+/// See `model/value/value.go` in the Prometheus source.
+const STALE_NAN_BITS: u64 = 0x7ff0000000000002;
+
+/// Convert a decoded Prometheus [`WriteRequest`] into a set of per-table
+/// [`MutableBatch`].
+///
+/// Each series is written to the table named by its `__name__` label, with
+/// all remaining labels written as tags. Staleness markers carry no data and
+/// are discarded (counted as rejected samples).
+pub fn convert(
+    req: &WriteRequest,
+) -> Result<(HashMap<String, MutableBatch>, MetricWriteStats), MetricWriteError> {
+    let mut batches = TableBatches::default();
+
+    for series in &req.timeseries {
+        let table = series
+            .labels
+            .iter()
+            .find(|l| l.name == METRIC_NAME_LABEL)
+            .map(|l| l.value.as_str())
+            .ok_or(MetricWriteError::MissingMetricName)?;
+
+        let tags = || {
+            series
+                .labels
+                .iter()
+                .filter(|l| l.name != METRIC_NAME_LABEL)
+                .map(|l| (l.name.as_str(), l.value.as_str()))
+        };
+
+        for sample in &series.samples {
+            if sample.value.to_bits() == STALE_NAN_BITS {
+                batches.reject(1);
+                continue;
+            }
+
+            // Remote write timestamps are milliseconds since the epoch.
+            let ts = sample
+                .timestamp
+                .checked_mul(1_000_000)
+                .ok_or(MetricWriteError::TimestampOverflow)?;
+
+            batches.push(table, tags(), sample.value, ts)?;
+        }
+    }
+
+    Ok(batches.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use generated_types::prometheus::{Label, Sample, TimeSeries};
+    use mutable_batch::column::ColumnData;
+
+    use super::*;
+
+    fn label(name: &str, value: &str) -> Label {
+        Label {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_convert() {
+        let req = WriteRequest {
+            timeseries: vec![
+                TimeSeries {
+                    labels: vec![
+                        label("__name__", "http_requests_total"),
+                        label("job", "router"),
+                        label("code", "200"),
+                    ],
+                    samples: vec![
+                        Sample {
+                            value: 42.0,
+                            timestamp: 1_000,
+                        },
+                        Sample {
+                            value: 43.0,
+                            timestamp: 2_000,
+                        },
+                    ],
+                },
+                TimeSeries {
+                    labels: vec![label("job", "querier"), label("__name__", "up")],
+                    samples: vec![Sample {
+                        value: 1.0,
+                        timestamp: 3_000,
+                    }],
+                },
+            ],
+            metadata: vec![],
+        };
+
+        let (batches, stats) = convert(&req).expect("conversion should succeed");
+        assert_eq!(
+            stats,
+            MetricWriteStats {
+                num_samples: 3,
+                num_rejected: 0
+            }
+        );
+        assert_eq!(batches.len(), 2);
+
+        let requests = &batches["http_requests_total"];
+        assert_eq!(requests.rows(), 2);
+        assert_eq!(
+            requests.column_names().into_iter().collect::<Vec<_>>(),
+            ["code", "job", "time", "value"]
+        );
+        assert_matches!(
+            requests.column("time").unwrap().data(),
+            ColumnData::I64(v, _) => {
+                assert_eq!(v, &[1_000_000_000, 2_000_000_000]);
+            }
+        );
+        assert_matches!(
+            requests.column("value").unwrap().data(),
+            ColumnData::F64(v, _) => {
+                assert_eq!(v, &[42.0, 43.0]);
+            }
+        );
+
+        let up = &batches["up"];
+        assert_eq!(up.rows(), 1);
+        assert_eq!(
+            up.column_names().into_iter().collect::<Vec<_>>(),
+            ["job", "time", "value"]
+        );
+    }
+
+    #[test]
+    fn test_stale_markers_discarded() {
+        let req = WriteRequest {
+            timeseries: vec![TimeSeries {
+                labels: vec![label("__name__", "up")],
+                samples: vec![
+                    Sample {
+                        value: f64::from_bits(STALE_NAN_BITS),
+                        timestamp: 1_000,
+                    },
+                    Sample {
+                        value: 1.0,
+                        timestamp: 2_000,
+                    },
+                ],
+            }],
+            metadata: vec![],
+        };
+
+        let (batches, stats) = convert(&req).expect("conversion should succeed");
+        assert_eq!(
+            stats,
+            MetricWriteStats {
+                num_samples: 1,
+                num_rejected: 1
+            }
+        );
+        assert_eq!(batches["up"].rows(), 1);
+    }
+
+    #[test]
+    fn test_missing_metric_name() {
+        let req = WriteRequest {
+            timeseries: vec![TimeSeries {
+                labels: vec![label("job", "router")],
+                samples: vec![Sample {
+                    value: 1.0,
+                    timestamp: 2_000,
+                }],
+            }],
+            metadata: vec![],
+        };
+
+        assert_matches!(convert(&req), Err(MetricWriteError::MissingMetricName));
+    }
+
+    #[test]
+    fn test_timestamp_overflow() {
+        let req = WriteRequest {
+            timeseries: vec![TimeSeries {
+                labels: vec![label("__name__", "up")],
+                samples: vec![Sample {
+                    value: 1.0,
+                    timestamp: i64::MAX,
+                }],
+            }],
+            metadata: vec![],
+        };
+
+        assert_matches!(convert(&req), Err(MetricWriteError::TimestampOverflow));
+    }
+}
@@ -72,6 +72,7 @@ fn parse_v2(req: &Request<Body>) -> Result<WriteParams, MultiTenantExtractError>
     Ok(WriteParams {
         namespace,
         precision: write_params.precision,
+        table_authz: None,
     })
 }
 
@@ -225,7 +226,8 @@ mod tests {
         query_string = "?org=banana&bucket=cool&precision=ms",
         want = Ok(WriteParams {
             namespace,
-            precision
+            precision,
+            ..
         }) => {
             assert_eq!(namespace.as_str(), "banana_cool");
             assert_matches!(precision, Precision::Milliseconds);
@@ -5,6 +5,7 @@ use data_types::NamespaceName;
 use hyper::{Body, Request};
 use serde::Deserialize;
 
+use super::single_tenant::{auth::TableAuthorization, SingleTenantExtractError};
 use crate::server::http::Error;
 
 #[derive(Clone, Debug, Deserialize)]
@@ -42,6 +43,30 @@ impl Precision {
 pub struct WriteParams {
     pub(crate) namespace: NamespaceName<'static>,
     pub(crate) precision: Precision,
+
+    /// Set when the request is only authorized to write to individual tables
+    /// within `namespace`, requiring the tables in the write to be checked
+    /// once the request body has been parsed.
+    pub(crate) table_authz: Option<TableAuthorization>,
+}
+
+impl WriteParams {
+    /// Verify the request is permitted to write to all of `tables`.
+    ///
+    /// This is a no-op if the request is authorized to write to the whole
+    /// namespace.
+    pub(crate) async fn authorize_tables<'a, T>(&self, tables: T) -> Result<(), Error>
+    where
+        T: IntoIterator<Item = &'a str> + Send,
+    {
+        match &self.table_authz {
+            Some(table_authz) => table_authz
+                .authorize(tables)
+                .await
+                .map_err(|e| SingleTenantExtractError::Authorizer(e).into()),
+            None => Ok(()),
+        }
+    }
 }
 
 /// A [`WriteRequestUnifier`] abstraction returns a unified [`WriteParams`]
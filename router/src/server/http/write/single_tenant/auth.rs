@@ -9,12 +9,18 @@ use authz::{
 use data_types::NamespaceName;
 use hyper::{Body, Request};
 
+/// Authorize a write to `namespace`.
+///
+/// If the request token holds the write permission for the whole namespace,
+/// [`None`] is returned. If it does not, the token may still be permitted to
+/// write to individual tables, which cannot be known until the request body
+/// is parsed - a [`TableAuthorization`] is returned to perform this check.
 pub(crate) async fn authorize(
     authz: &Arc<dyn Authorizer>,
     req: &Request<Body>,
     namespace: &NamespaceName<'_>,
     query_param_token: Option<String>,
-) -> Result<(), Error> {
+) -> Result<Option<TableAuthorization>, Error> {
     let token = extract_token(
         req.extensions()
             .get::<AuthorizationHeaderExtension>()
@@ -27,8 +33,70 @@ pub(crate) async fn authorize(
         Action::Write,
     )];
 
-    authz.permissions(token, &perms).await?;
-    Ok(())
+    match authz.permissions(token.clone(), &perms).await {
+        Ok(_) => Ok(None),
+        Err(Error::Forbidden) => Ok(Some(TableAuthorization {
+            authz: Arc::clone(authz),
+            token,
+            namespace: namespace.to_string(),
+        })),
+        Err(e) => Err(e),
+    }
+}
+
+/// A deferred authorization check for a write request whose token is not
+/// permitted to write to the whole namespace.
+///
+/// The write is only accepted if the token is permitted to write to every
+/// table it contains.
+#[derive(Clone)]
+pub(crate) struct TableAuthorization {
+    authz: Arc<dyn Authorizer>,
+    token: Option<Vec<u8>>,
+    namespace: String,
+}
+
+impl std::fmt::Debug for TableAuthorization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never log the token.
+        f.debug_struct("TableAuthorization")
+            .field("authz", &self.authz)
+            .field("namespace", &self.namespace)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TableAuthorization {
+    /// Returns [`Error::Forbidden`] unless the token is permitted to write to
+    /// all of `tables`.
+    ///
+    /// A write containing no tables is rejected.
+    pub(crate) async fn authorize<'a, T>(&self, tables: T) -> Result<(), Error>
+    where
+        T: IntoIterator<Item = &'a str> + Send,
+    {
+        let perms = tables
+            .into_iter()
+            .map(|table| {
+                Permission::ResourceAction(
+                    Resource::Table {
+                        database: self.namespace.clone(),
+                        table: table.to_string(),
+                    },
+                    Action::Write,
+                )
+            })
+            .collect::<Vec<_>>();
+        if perms.is_empty() {
+            return Err(Error::Forbidden);
+        }
+
+        let granted = self.authz.permissions(self.token.clone(), &perms).await?;
+        if granted.len() != perms.len() {
+            return Err(Error::Forbidden);
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -40,6 +108,8 @@ pub mod mock {
     pub const MOCK_AUTH_VALID_TOKEN: &str = "GOOD";
     pub const MOCK_AUTH_INVALID_TOKEN: &str = "UGLY";
     pub const MOCK_AUTH_NO_PERMS_TOKEN: &str = "BAD";
+    /// Permitted to write only to the "platanos" table of any namespace.
+    pub const MOCK_AUTH_TABLE_TOKEN: &str = "TABLE";
 
     #[derive(Debug, Default)]
     pub struct MockAuthorizer {}
@@ -55,6 +125,23 @@ pub mod mock {
                 Some(token) => match (&token as &dyn AsRef<[u8]>).as_ref() {
                     b"GOOD" => Ok(perms.to_vec()),
                     b"BAD" => Err(authz::Error::Forbidden),
+                    b"TABLE" => {
+                        let got = perms
+                            .iter()
+                            .filter(|p| {
+                                matches!(
+                                    p,
+                                    Permission::ResourceAction(Resource::Table { table, .. }, _)
+                                        if table == "platanos"
+                                )
+                            })
+                            .cloned()
+                            .collect::<Vec<_>>();
+                        if got.is_empty() {
+                            return Err(authz::Error::Forbidden);
+                        }
+                        Ok(got)
+                    }
                     b"UGLY" => Err(authz::Error::verification("test", "test error")),
                     _ => panic!("unexpected token"),
                 },
@@ -171,6 +258,62 @@ mod tests {
         })
     }
 
+    #[tokio::test]
+    async fn test_table_authz() {
+        static NAMESPACE_NAME: &str = "test";
+        let mock_namespace_resolver =
+            MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NamespaceId::new(42));
+
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let metrics = Arc::new(metric::Registry::default());
+        let authz = Arc::new(MockAuthorizer::default());
+        let delegate = HttpDelegate::new(
+            MAX_BYTES,
+            1,
+            mock_namespace_resolver,
+            Arc::clone(&dml_handler),
+            &metrics,
+            Box::new(SingleTenantRequestUnifier::new(authz)),
+        );
+
+        let request = |body: &'static str| {
+            Request::builder()
+                .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test")
+                .method("POST")
+                .extension(AuthorizationHeaderExtension::new(Some(
+                    HeaderValue::from_str(format!("Token {MOCK_AUTH_TABLE_TOKEN}").as_str())
+                        .unwrap(),
+                )))
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        // A write to only the permitted table is accepted.
+        let got = delegate
+            .route(request("platanos,tag1=A val=42i 123456"))
+            .await;
+        assert_matches!(got, Ok(_));
+
+        // A write that also touches another table is rejected in its entirety.
+        let got = delegate
+            .route(request(
+                "platanos,tag1=A val=42i 123456\nbananas,tag1=A val=42i 123456",
+            ))
+            .await;
+        assert_matches!(
+            got,
+            Err(http::Error::SingleTenantError(
+                SingleTenantExtractError::Authorizer(authz::Error::Forbidden)
+            ))
+        );
+
+        let calls = dml_handler.calls();
+        assert_matches!(calls.as_slice(), [MockDmlHandlerCall::Write{namespace, write_input, ..}] => {
+            assert_eq!(namespace, NAMESPACE_NAME);
+            assert!(write_input.contains_key("platanos"));
+        })
+    }
+
     #[tokio::test]
     async fn test_authz_metric() {
         static NAMESPACE_NAME: &str = "test";
@@ -280,7 +423,7 @@ mod tests {
         token_header_ok,
         header_value = format!("Token {MOCK_AUTH_VALID_TOKEN}").as_str(),
         query_param_token = Some("ignore".to_string()),
-        want = Ok(())
+        want = Ok(None)
     );
 
     test_authorize!(
@@ -294,7 +437,7 @@ mod tests {
         token_header_forbidden,
         header_value = format!("Token {MOCK_AUTH_NO_PERMS_TOKEN}").as_str(),
         query_param_token = Some("ignore".to_string()),
-        want = Err(authz::Error::Forbidden)
+        want = Ok(Some(_))
     );
 
     test_authorize!(
@@ -315,14 +458,14 @@ mod tests {
         token_header_missing_whitespace_match_next,
         header_value = "Token",
         query_param_token = Some(MOCK_AUTH_VALID_TOKEN.to_string()),
-        want = Ok(())
+        want = Ok(None)
     );
 
     test_authorize!(
         bearer_header_ok,
         header_value = format!("Bearer {MOCK_AUTH_VALID_TOKEN}").as_str(),
         query_param_token = Some("ignore".to_string()),
-        want = Ok(())
+        want = Ok(None)
     );
 
     test_authorize!(
@@ -336,7 +479,7 @@ mod tests {
         basic_header_ok,
         header_value = encode_basic_header(format!("ignore:{MOCK_AUTH_VALID_TOKEN}")).as_str(),
         query_param_token = Some("ignore".to_string()),
-        want = Ok(())
+        want = Ok(None)
     );
 
     test_authorize!(
@@ -364,14 +507,14 @@ mod tests {
         basic_header_forbidden,
         header_value = encode_basic_header(format!("ignore:{MOCK_AUTH_NO_PERMS_TOKEN}")).as_str(),
         query_param_token = Some("ignore".to_string()),
-        want = Err(authz::Error::Forbidden)
+        want = Ok(Some(_))
     );
 
     test_authorize!(
         query_param_token_ok,
         header_value = "",
         query_param_token = Some(MOCK_AUTH_VALID_TOKEN.to_string()),
-        want = Ok(())
+        want = Ok(None)
     );
 
     test_authorize!(
@@ -385,7 +528,7 @@ mod tests {
         query_param_token_forbidden,
         header_value = "",
         query_param_token = Some(MOCK_AUTH_NO_PERMS_TOKEN.to_string()),
-        want = Err(authz::Error::Forbidden)
+        want = Ok(Some(_))
     );
 
     test_authorize!(
@@ -0,0 +1,64 @@
+//! Admission control for queries.
+use std::time::Duration;
+
+use snafu::Snafu;
+use tracker::InstrumentedAsyncOwnedSemaphorePermit;
+
+/// Errors returned when a query is not admitted.
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum AdmissionError {
+    #[snafu(display(
+        "namespace {namespace} is limited to {limit} concurrent queries, \
+         no slot became available within {timeout:?}"
+    ))]
+    NamespaceConcurrency {
+        namespace: String,
+        limit: usize,
+        timeout: Duration,
+    },
+
+    #[snafu(display(
+        "token is limited to {limit} concurrent queries, \
+         no slot became available within {timeout:?}"
+    ))]
+    TokenConcurrency { limit: usize, timeout: Duration },
+}
+
+impl From<AdmissionError> for tonic::Status {
+    fn from(e: AdmissionError) -> Self {
+        Self::resource_exhausted(e.to_string())
+    }
+}
+
+/// The permits that admitted a query.
+///
+/// The query holds onto this for as long as it runs (incl. streaming the results back to the
+/// client). Dropping it releases all the slots the query occupied.
+#[derive(Debug, Default)]
+pub struct QueryAdmission {
+    permits: Vec<InstrumentedAsyncOwnedSemaphorePermit>,
+}
+
+impl QueryAdmission {
+    /// Create admission from the given permits.
+    pub fn new(permits: Vec<InstrumentedAsyncOwnedSemaphorePermit>) -> Self {
+        Self { permits }
+    }
+
+    /// Add another permit that is released together with this admission.
+    pub fn with_permit(mut self, permit: InstrumentedAsyncOwnedSemaphorePermit) -> Self {
+        self.permits.push(permit);
+        self
+    }
+
+    /// Number of permits held.
+    pub fn len(&self) -> usize {
+        self.permits.len()
+    }
+
+    /// Returns true if no permits are held.
+    pub fn is_empty(&self) -> bool {
+        self.permits.is_empty()
+    }
+}
@@ -17,12 +17,15 @@
 // Workaround for "unused crate" lint false positives.
 use workspace_hack as _;
 
+pub mod admission;
 mod error;
 pub mod planner;
+mod table_filter;
 pub mod test_util;
 
 use std::sync::Arc;
 
+use admission::{AdmissionError, QueryAdmission};
 use async_trait::async_trait;
 use iox_query::QueryNamespace;
 use trace::span::Span;
@@ -40,15 +43,33 @@ pub trait QueryNamespaceProvider: std::fmt::Debug + Send + Sync + 'static {
     /// Get namespace if it exists.
     ///
     /// System tables may contain debug information depending on `include_debug_info_tables`.
+    ///
+    /// Only the user tables allowed by `table_filter` are visible in the returned namespace.
     async fn db(
         &self,
         name: &str,
         span: Option<Span>,
         include_debug_info_tables: bool,
+        table_filter: &TableFilter,
     ) -> Option<Arc<Self::Db>>;
 
     /// Acquire concurrency-limiting sempahore
     async fn acquire_semaphore(&self, span: Option<Span>) -> InstrumentedAsyncOwnedSemaphorePermit;
+
+    /// Admit a query against the given namespace, issued with the given authorization token.
+    ///
+    /// This waits for the per-namespace and per-token concurrency limits (if any) and fails with
+    /// [`AdmissionError`] if no slot becomes available in time. The returned [`QueryAdmission`]
+    /// must be held until the query completes. The default implementation admits all queries.
+    async fn admit_query(
+        &self,
+        _name: &str,
+        _token: Option<&[u8]>,
+        _span: Option<Span>,
+    ) -> Result<QueryAdmission, AdmissionError> {
+        Ok(QueryAdmission::default())
+    }
 }
 
 pub use error::datafusion_error_to_tonic_code;
+pub use table_filter::{authorize_tables, TableFilter};
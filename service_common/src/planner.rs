@@ -3,7 +3,8 @@ use std::sync::Arc;
 
 use bytes::Bytes;
 use datafusion::{
-    arrow::datatypes::SchemaRef, error::DataFusionError, physical_plan::ExecutionPlan,
+    arrow::datatypes::SchemaRef, error::DataFusionError, logical_expr::LogicalPlan,
+    physical_plan::ExecutionPlan,
 };
 use flightsql::{FlightSQLCommand, FlightSQLPlanner};
 use iox_query::{
@@ -16,6 +17,7 @@ use iox_query_influxrpc::InfluxRpcPlanner;
 
 pub use datafusion::error::{DataFusionError as Error, Result};
 use iox_query_influxql::frontend::planner::InfluxQLQueryPlanner;
+pub use iox_query_influxrpc::SeriesCardinalityMode;
 use predicate::rpc_predicate::InfluxRpcPredicate;
 
 /// Query planner that plans queries on a separate threadpool.
@@ -49,13 +51,17 @@ impl Planner {
             .await
     }
 
-    /// Plan an InfluxQL query against the data in `database`, and return a
+    /// Plan an InfluxQL query against the data in `namespace`, and return a
     /// DataFusion physical execution plan.
-    pub async fn influxql(
+    pub async fn influxql<N>(
         &self,
         query: impl Into<String> + Send,
-    ) -> Result<Arc<dyn ExecutionPlan>> {
-        let planner = InfluxQLQueryPlanner::new();
+        namespace: Arc<N>,
+    ) -> Result<Arc<dyn ExecutionPlan>>
+    where
+        N: QueryNamespace + 'static,
+    {
+        let planner = InfluxQLQueryPlanner::new().with_namespace(namespace);
         let query = query.into();
         let ctx = self.ctx.child_ctx("planner influxql");
 
@@ -291,4 +297,27 @@ impl Planner {
             })
             .await
     }
+
+    /// Creates plans as described on [`InfluxRpcPlanner::series_cardinality`],
+    /// on a separate threadpool
+    pub async fn series_cardinality<N>(
+        &self,
+        namespace: Arc<N>,
+        predicate: InfluxRpcPredicate,
+        mode: SeriesCardinalityMode,
+    ) -> Result<Vec<LogicalPlan>>
+    where
+        N: QueryNamespace + 'static,
+    {
+        let planner = InfluxRpcPlanner::new(self.ctx.child_ctx("planner series_cardinality")).await;
+
+        self.ctx
+            .run(async move {
+                planner
+                    .series_cardinality(namespace, predicate, mode)
+                    .await
+                    .map_err(|e| e.to_df_error("series_cardinality"))
+            })
+            .await
+    }
 }
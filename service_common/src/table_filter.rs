@@ -0,0 +1,272 @@
+//! Restriction of the tables visible to a query, derived from the caller's
+//! table-level permissions.
+
+use std::{collections::HashSet, sync::Arc};
+
+use authz::{Action, Authorizer, Permission, Resource};
+use iox_query::QueryNamespace;
+use trace::span::Span;
+
+use crate::QueryNamespaceProvider;
+
+/// The set of user tables within a namespace visible to a request.
+///
+/// Tables not passing the filter are omitted from the namespace returned by
+/// [`QueryNamespaceProvider::db()`], so they cannot be queried and do not
+/// appear in schema listings (such as `SHOW MEASUREMENTS`, FlightSQL
+/// `GetTables` or the storage `MeasurementNames` RPC).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TableFilter {
+    /// All tables are visible.
+    #[default]
+    All,
+
+    /// Only the named tables are visible.
+    Only(Arc<HashSet<String>>),
+}
+
+impl TableFilter {
+    /// Returns true if `table_name` is visible.
+    pub fn allows(&self, table_name: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Only(tables) => tables.contains(table_name),
+        }
+    }
+}
+
+/// Authorize `action` on the namespace `namespace_name`, returning the
+/// [`TableFilter`] the request must be restricted to.
+///
+/// If `token` holds the permission for the whole namespace, all tables are
+/// visible. Otherwise only those tables for which the permission is granted
+/// individually are visible. If no table is permitted,
+/// [`authz::Error::Forbidden`] is returned.
+///
+/// The namespace and table permissions are requested in a single
+/// [`Authorizer::permissions()`] call, so they are recorded as a single
+/// decision by an [`AuditingAuthorizer`](authz::audit::AuditingAuthorizer).
+pub async fn authorize_tables<P, A>(
+    provider: &P,
+    authz: &A,
+    token: Option<Vec<u8>>,
+    namespace_name: &str,
+    action: Action,
+    span: Option<Span>,
+) -> Result<TableFilter, authz::Error>
+where
+    P: QueryNamespaceProvider + ?Sized,
+    A: Authorizer + ?Sized,
+{
+    let namespace_perm =
+        Permission::ResourceAction(Resource::Database(namespace_name.to_string()), action);
+
+    // The token may hold permissions for individual tables. Unknown
+    // namespaces only request the namespace permission, so as to not reveal
+    // whether they exist.
+    let tables = match provider
+        .db(namespace_name, span, false, &TableFilter::All)
+        .await
+    {
+        Some(db) => table_names(db.as_ref()),
+        None => vec![],
+    };
+    let perms = std::iter::once(namespace_perm.clone())
+        .chain(tables.into_iter().map(|table| {
+            Permission::ResourceAction(
+                Resource::Table {
+                    database: namespace_name.to_string(),
+                    table,
+                },
+                action,
+            )
+        }))
+        .collect::<Vec<_>>();
+
+    let granted = authz.permissions(token, &perms).await?;
+    if granted.contains(&namespace_perm) {
+        return Ok(TableFilter::All);
+    }
+
+    let tables = granted
+        .into_iter()
+        .filter_map(|p| match p {
+            Permission::ResourceAction(Resource::Table { table, .. }, _) => Some(table),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+    if tables.is_empty() {
+        return Err(authz::Error::Forbidden);
+    }
+
+    Ok(TableFilter::Only(Arc::new(tables)))
+}
+
+/// Return the names of the user tables in `db`.
+fn table_names<D>(db: &D) -> Vec<String>
+where
+    D: QueryNamespace + ?Sized,
+{
+    let ctx = db.new_query_context(None);
+    let session_cfg = ctx.inner().copied_config();
+    let cfg = session_cfg.options();
+    ctx.inner()
+        .catalog(&cfg.catalog.default_catalog)
+        .and_then(|c| c.schema(&cfg.catalog.default_schema))
+        .map(|s| s.table_names())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use async_trait::async_trait;
+    use authz::audit::{mock::MockAuditSink, AuditOutcome, AuditingAuthorizer};
+    use iox_query::test::TestChunk;
+
+    use super::*;
+    use crate::test_util::TestDatabaseStore;
+
+    /// Grants read access to the "bananas" namespace for the token "all", and
+    /// to the "cpu" table of any namespace for the token "cpu".
+    #[derive(Debug)]
+    struct MockAuthorizer;
+
+    #[async_trait]
+    impl Authorizer for MockAuthorizer {
+        async fn permissions(
+            &self,
+            token: Option<Vec<u8>>,
+            perms: &[Permission],
+        ) -> Result<Vec<Permission>, authz::Error> {
+            let token = token.ok_or(authz::Error::NoToken)?;
+            let got = perms
+                .iter()
+                .filter(|p| match (token.as_slice(), p) {
+                    (b"all", Permission::ResourceAction(Resource::Database(db), _)) => {
+                        db == "bananas"
+                    }
+                    (b"cpu", Permission::ResourceAction(Resource::Table { table, .. }, _)) => {
+                        table == "cpu"
+                    }
+                    _ => false,
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            if got.is_empty() {
+                return Err(authz::Error::Forbidden);
+            }
+            Ok(got)
+        }
+    }
+
+    async fn store() -> TestDatabaseStore {
+        let store = TestDatabaseStore::default();
+        let db = store.db_or_create("bananas").await;
+        db.add_chunk("p1", Arc::new(TestChunk::new("cpu").with_id(1)));
+        db.add_chunk("p1", Arc::new(TestChunk::new("mem").with_id(2)));
+        store
+    }
+
+    #[tokio::test]
+    async fn test_authorize_tables() {
+        let store = store().await;
+
+        let got = authorize_tables(
+            &store,
+            &MockAuthorizer,
+            Some(b"all".to_vec()),
+            "bananas",
+            Action::Read,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(got, TableFilter::All);
+
+        let got = authorize_tables(
+            &store,
+            &MockAuthorizer,
+            Some(b"cpu".to_vec()),
+            "bananas",
+            Action::Read,
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(got.allows("cpu"));
+        assert!(!got.allows("mem"));
+
+        let got = authorize_tables(
+            &store,
+            &MockAuthorizer,
+            Some(b"none".to_vec()),
+            "bananas",
+            Action::Read,
+            None,
+        )
+        .await;
+        assert_matches!(got, Err(authz::Error::Forbidden));
+
+        // Unknown namespaces are indistinguishable from forbidden ones.
+        let got = authorize_tables(
+            &store,
+            &MockAuthorizer,
+            Some(b"cpu".to_vec()),
+            "platanos",
+            Action::Read,
+            None,
+        )
+        .await;
+        assert_matches!(got, Err(authz::Error::Forbidden));
+
+        let got =
+            authorize_tables(&store, &MockAuthorizer, None, "bananas", Action::Read, None).await;
+        assert_matches!(got, Err(authz::Error::NoToken));
+    }
+
+    #[tokio::test]
+    async fn test_audited_as_one_decision() {
+        let store = store().await;
+        let sink = Arc::new(MockAuditSink::default());
+        let authz = AuditingAuthorizer::new(MockAuthorizer, Arc::clone(&sink) as _);
+
+        let got = authorize_tables(
+            &store,
+            &authz,
+            Some(b"cpu".to_vec()),
+            "bananas",
+            Action::Read,
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(got.allows("cpu"));
+
+        // The refused namespace permission is not recorded as a denial.
+        let mut events = sink
+            .events()
+            .into_iter()
+            .map(|e| (e.table, e.outcome))
+            .collect::<Vec<_>>();
+        events.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            events,
+            [
+                (Some("cpu".to_string()), AuditOutcome::Allowed),
+                (Some("mem".to_string()), AuditOutcome::Denied),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_no_authorizer() {
+        let store = store().await;
+        let authz: Option<Arc<dyn Authorizer>> = None;
+
+        let got = authorize_tables(&store, &authz, None, "bananas", Action::Read, None)
+            .await
+            .unwrap();
+        assert_eq!(got, TableFilter::All);
+    }
+}
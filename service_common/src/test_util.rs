@@ -8,7 +8,7 @@ use tracker::{
     AsyncSemaphoreMetrics, InstrumentedAsyncOwnedSemaphorePermit, InstrumentedAsyncSemaphore,
 };
 
-use crate::QueryNamespaceProvider;
+use crate::{QueryNamespaceProvider, TableFilter};
 
 #[derive(Debug)]
 pub struct TestDatabaseStore {
@@ -61,11 +61,15 @@ impl QueryNamespaceProvider for TestDatabaseStore {
     type Db = TestDatabase;
 
     /// Retrieve the database specified name
+    ///
+    /// The [`TestDatabase`] does not support restricting the visible tables,
+    /// so `table_filter` is ignored.
     async fn db(
         &self,
         name: &str,
         _span: Option<Span>,
         _include_debug_info_tables: bool,
+        _table_filter: &TableFilter,
     ) -> Option<Arc<Self::Db>> {
         let databases = self.databases.lock();
 
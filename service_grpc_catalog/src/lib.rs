@@ -18,6 +18,7 @@
 // Workaround for "unused crate" lint false positives.
 use workspace_hack as _;
 
+use authz::{audit::AuditSink, extract_token, Authorizer};
 use data_types::{TableId, TransitionPartitionId};
 use generated_types::influxdata::iox::catalog::v1::*;
 use iox_catalog::interface::{Catalog, SoftDeletedRows};
@@ -25,17 +26,53 @@ use observability_deps::tracing::*;
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
 
+mod write;
+
 /// Implementation of the Catalog gRPC service
 #[derive(Debug)]
 pub struct CatalogService {
     /// Catalog.
     catalog: Arc<dyn Catalog>,
+
+    /// Whether the RPCs modifying the catalog are served.
+    write_access: bool,
+
+    /// Authorizer for the RPCs modifying the catalog, if authorization is
+    /// enabled.
+    authz: Option<Arc<dyn Authorizer>>,
+
+    /// Destination for the audit trail of catalog modifications, if auditing
+    /// is enabled.
+    audit_sink: Option<Arc<dyn AuditSink>>,
 }
 
 impl CatalogService {
     /// Create a new catalog service with the given catalog
     pub fn new(catalog: Arc<dyn Catalog>) -> Self {
-        Self { catalog }
+        Self {
+            catalog,
+            write_access: false,
+            authz: None,
+            audit_sink: None,
+        }
+    }
+
+    /// Serve the RPCs modifying the catalog, which are otherwise rejected.
+    ///
+    /// If `authz` is provided, the caller must hold the write (or for
+    /// deletions, the delete) permission for the affected namespace.
+    pub fn with_write_access(mut self, authz: Option<Arc<dyn Authorizer>>) -> Self {
+        self.write_access = true;
+        self.authz = authz;
+        self
+    }
+
+    /// Record an [`AuditEvent`] to `sink` for each catalog modification.
+    ///
+    /// [`AuditEvent`]: authz::audit::AuditEvent
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = Some(sink);
+        self
     }
 }
 
@@ -171,6 +208,56 @@ impl catalog_service_server::CatalogService for CatalogService {
 
         Ok(Response::new(response))
     }
+
+    async fn flag_parquet_files_for_deletion(
+        &self,
+        request: Request<FlagParquetFilesForDeletionRequest>,
+    ) -> Result<Response<FlagParquetFilesForDeletionResponse>, Status> {
+        let token = extract_token(request.metadata().get("authorization"));
+        let parquet_files = self.flag_for_deletion(token, request.into_inner()).await?;
+
+        Ok(Response::new(FlagParquetFilesForDeletionResponse {
+            parquet_files,
+        }))
+    }
+
+    async fn update_parquet_file_compaction_level(
+        &self,
+        request: Request<UpdateParquetFileCompactionLevelRequest>,
+    ) -> Result<Response<UpdateParquetFileCompactionLevelResponse>, Status> {
+        let token = extract_token(request.metadata().get("authorization"));
+        let parquet_files = self
+            .update_compaction_level(token, request.into_inner())
+            .await?;
+
+        Ok(Response::new(UpdateParquetFileCompactionLevelResponse {
+            parquet_files,
+        }))
+    }
+
+    async fn set_partition_sort_key(
+        &self,
+        request: Request<SetPartitionSortKeyRequest>,
+    ) -> Result<Response<SetPartitionSortKeyResponse>, Status> {
+        let token = extract_token(request.metadata().get("authorization"));
+        let partition = self.set_sort_key(token, request.into_inner()).await?;
+
+        Ok(Response::new(SetPartitionSortKeyResponse {
+            partition: Some(partition),
+        }))
+    }
+
+    async fn register_parquet_file(
+        &self,
+        request: Request<RegisterParquetFileRequest>,
+    ) -> Result<Response<RegisterParquetFileResponse>, Status> {
+        let token = extract_token(request.metadata().get("authorization"));
+        let parquet_file = self.register_file(token, request.into_inner()).await?;
+
+        Ok(Response::new(RegisterParquetFileResponse {
+            parquet_file: Some(parquet_file),
+        }))
+    }
 }
 
 // converts the catalog Partition to protobuf
@@ -196,12 +283,19 @@ fn to_partition(p: data_types::Partition) -> Partition {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use data_types::{ColumnId, ColumnSet, CompactionLevel, ParquetFileParams, Timestamp};
+    use authz::{
+        audit::{mock::MockAuditSink, AuditOutcome},
+        Permission,
+    };
+    use data_types::{
+        ColumnId, ColumnSet, ColumnType, CompactionLevel, ParquetFileParams, Timestamp,
+    };
     use generated_types::influxdata::iox::catalog::v1::catalog_service_server::CatalogService;
     use iox_catalog::{
         mem::MemCatalog,
         test_helpers::{arbitrary_namespace, arbitrary_table},
     };
+    use tonic::Code;
     use uuid::Uuid;
 
     #[tokio::test]
@@ -307,4 +401,320 @@ mod tests {
             .collect();
         assert_eq!(expect, response.partitions);
     }
+
+    /// An [`Authorizer`] refusing all permissions.
+    #[derive(Debug)]
+    struct DenyAll;
+
+    #[async_trait::async_trait]
+    impl Authorizer for DenyAll {
+        async fn permissions(
+            &self,
+            _token: Option<Vec<u8>>,
+            _perms: &[Permission],
+        ) -> Result<Vec<Permission>, authz::Error> {
+            Err(authz::Error::Forbidden)
+        }
+    }
+
+    /// Create a catalog containing a single parquet file, returning the
+    /// catalog, the partition and the file.
+    async fn catalog_with_file() -> (
+        Arc<dyn Catalog>,
+        data_types::Partition,
+        data_types::ParquetFile,
+    ) {
+        let metrics = Arc::new(metric::Registry::default());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metrics));
+        let mut repos = catalog.repositories().await;
+        let namespace = arbitrary_namespace(&mut *repos, "catalog_write_test").await;
+        let table = arbitrary_table(&mut *repos, "schema_test_table", &namespace).await;
+        let tag = repos
+            .columns()
+            .create_or_get("tag", table.id, ColumnType::Tag)
+            .await
+            .unwrap();
+        let time = repos
+            .columns()
+            .create_or_get("time", table.id, ColumnType::Time)
+            .await
+            .unwrap();
+        let partition = repos
+            .partitions()
+            .create_or_get("foo".into(), table.id)
+            .await
+            .unwrap();
+        let file = repos
+            .parquet_files()
+            .create(ParquetFileParams {
+                namespace_id: namespace.id,
+                table_id: table.id,
+                partition_id: partition.transition_partition_id(),
+                object_store_id: Uuid::new_v4(),
+                min_time: Timestamp::new(1),
+                max_time: Timestamp::new(5),
+                file_size_bytes: 2343,
+                row_count: 29,
+                compaction_level: CompactionLevel::Initial,
+                created_at: Timestamp::new(2343),
+                column_set: ColumnSet::new([tag.id, time.id]),
+                max_l0_created_at: Timestamp::new(2343),
+            })
+            .await
+            .unwrap();
+        drop(repos);
+
+        (catalog, partition, file)
+    }
+
+    #[tokio::test]
+    async fn write_access_required() {
+        let (catalog, _partition, file) = catalog_with_file().await;
+
+        let request = FlagParquetFilesForDeletionRequest {
+            object_store_ids: vec![file.object_store_id.to_string()],
+            dry_run: false,
+        };
+
+        let grpc = super::CatalogService::new(Arc::clone(&catalog));
+        let err = grpc
+            .flag_parquet_files_for_deletion(Request::new(request.clone()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::Unimplemented);
+
+        let grpc = super::CatalogService::new(Arc::clone(&catalog))
+            .with_write_access(Some(Arc::new(DenyAll) as _));
+        let err = grpc
+            .flag_parquet_files_for_deletion(Request::new(request))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::PermissionDenied);
+
+        let files = catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .list_all()
+            .await;
+        assert!(files.unwrap().iter().all(|f| f.to_delete.is_none()));
+    }
+
+    #[tokio::test]
+    async fn update_parquet_files() {
+        let (catalog, _partition, file) = catalog_with_file().await;
+        let sink = Arc::new(MockAuditSink::default());
+        let grpc = super::CatalogService::new(Arc::clone(&catalog))
+            .with_write_access(None)
+            .with_audit_sink(Arc::clone(&sink) as _);
+        let object_store_ids = vec![file.object_store_id.to_string()];
+
+        // A dry run returns the change without applying it.
+        let got = grpc
+            .update_parquet_file_compaction_level(Request::new(
+                UpdateParquetFileCompactionLevelRequest {
+                    object_store_ids: object_store_ids.clone(),
+                    compaction_level: CompactionLevel::Final as i32,
+                    dry_run: true,
+                },
+            ))
+            .await
+            .unwrap()
+            .into_inner()
+            .parquet_files;
+        assert_eq!(got[0].compaction_level, CompactionLevel::Final as i32);
+        let mut repos = catalog.repositories().await;
+        let stored = repos
+            .parquet_files()
+            .get_by_object_store_id(file.object_store_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.compaction_level, CompactionLevel::Initial);
+        drop(repos);
+
+        grpc.update_parquet_file_compaction_level(Request::new(
+            UpdateParquetFileCompactionLevelRequest {
+                object_store_ids: object_store_ids.clone(),
+                compaction_level: CompactionLevel::Final as i32,
+                dry_run: false,
+            },
+        ))
+        .await
+        .unwrap();
+
+        let got = grpc
+            .flag_parquet_files_for_deletion(Request::new(FlagParquetFilesForDeletionRequest {
+                object_store_ids: object_store_ids.clone(),
+                dry_run: false,
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .parquet_files;
+        assert!(got[0].to_delete.is_some());
+        assert_eq!(got[0].compaction_level, CompactionLevel::Final as i32);
+
+        // Flagging the file again fails.
+        let err = grpc
+            .flag_parquet_files_for_deletion(Request::new(FlagParquetFilesForDeletionRequest {
+                object_store_ids,
+                dry_run: false,
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::FailedPrecondition);
+
+        let events = sink.events();
+        let [upgraded, flagged] = events.as_slice() else {
+            panic!("unexpected events: {events:?}");
+        };
+        assert_eq!(upgraded.operation, "update_parquet_file_compaction_level");
+        assert_eq!(upgraded.outcome, AuditOutcome::Succeeded);
+        assert_eq!(upgraded.namespace.as_deref(), Some("catalog_write_test"));
+        assert_eq!(flagged.operation, "flag_parquet_files_for_deletion");
+        assert!(flagged.before.is_some());
+        assert!(flagged.after.is_some());
+    }
+
+    #[tokio::test]
+    async fn set_partition_sort_key() {
+        let (catalog, partition, _file) = catalog_with_file().await;
+        let grpc = super::CatalogService::new(Arc::clone(&catalog)).with_write_access(None);
+        let partition_identifier = Some(PartitionIdentifier::from(
+            partition.transition_partition_id(),
+        ));
+
+        // The expected sort key must match.
+        let err = grpc
+            .set_partition_sort_key(Request::new(SetPartitionSortKeyRequest {
+                partition_identifier: partition_identifier.clone(),
+                sort_key: vec!["tag".to_string(), "time".to_string()],
+                expected_sort_key: Some(SortKey {
+                    array_sort_key: vec!["time".to_string()],
+                }),
+                force: false,
+                dry_run: false,
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::FailedPrecondition);
+
+        // Unknown columns are rejected.
+        let err = grpc
+            .set_partition_sort_key(Request::new(SetPartitionSortKeyRequest {
+                partition_identifier: partition_identifier.clone(),
+                sort_key: vec!["bananas".to_string()],
+                expected_sort_key: None,
+                force: true,
+                dry_run: false,
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::InvalidArgument);
+
+        let got = grpc
+            .set_partition_sort_key(Request::new(SetPartitionSortKeyRequest {
+                partition_identifier: partition_identifier.clone(),
+                sort_key: vec!["tag".to_string(), "time".to_string()],
+                expected_sort_key: Some(SortKey {
+                    array_sort_key: vec![],
+                }),
+                force: false,
+                dry_run: false,
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .partition
+            .unwrap();
+        assert_eq!(
+            got.optional_sort_key.unwrap().array_sort_key,
+            ["tag", "time"]
+        );
+
+        // Reset the sort key regardless of its value.
+        grpc.set_partition_sort_key(Request::new(SetPartitionSortKeyRequest {
+            partition_identifier,
+            sort_key: vec![],
+            expected_sort_key: None,
+            force: true,
+            dry_run: false,
+        }))
+        .await
+        .unwrap();
+        let stored = catalog
+            .repositories()
+            .await
+            .partitions()
+            .get_by_id(partition.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!stored.sort_key_has_value());
+        assert!(stored.sort_key_ids().is_empty());
+    }
+
+    #[tokio::test]
+    async fn register_parquet_file() {
+        let (catalog, _partition, file) = catalog_with_file().await;
+        let grpc = super::CatalogService::new(Arc::clone(&catalog)).with_write_access(None);
+
+        let request = RegisterParquetFileRequest {
+            namespace_name: "catalog_write_test".to_string(),
+            table_name: "schema_test_table".to_string(),
+            partition_key: "foo".to_string(),
+            object_store_id: Uuid::new_v4().to_string(),
+            min_time: 1,
+            max_time: 5,
+            file_size_bytes: 42,
+            row_count: 2,
+            compaction_level: CompactionLevel::FileNonOverlapped as i32,
+            created_at: 2343,
+            max_l0_created_at: 2343,
+            column_names: vec!["tag".to_string(), "time".to_string()],
+            dry_run: false,
+        };
+
+        // Files already in the catalog cannot be registered again.
+        let err = grpc
+            .register_parquet_file(Request::new(RegisterParquetFileRequest {
+                object_store_id: file.object_store_id.to_string(),
+                ..request.clone()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::AlreadyExists);
+
+        let got = grpc
+            .register_parquet_file(Request::new(RegisterParquetFileRequest {
+                dry_run: true,
+                ..request.clone()
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .parquet_file
+            .unwrap();
+        assert_eq!(got.id, 0);
+        assert_eq!(
+            got.column_set,
+            file.column_set.iter().map(|c| c.get()).collect::<Vec<_>>()
+        );
+
+        let got = grpc
+            .register_parquet_file(Request::new(request.clone()))
+            .await
+            .unwrap()
+            .into_inner()
+            .parquet_file
+            .unwrap();
+        assert_ne!(got.id, 0);
+        assert_eq!(got.object_store_id, request.object_store_id);
+        assert_eq!(got.table_id, file.table_id.get());
+        assert_eq!(
+            got.partition_identifier,
+            Some(file.partition_id.clone().into())
+        );
+    }
 }
@@ -0,0 +1,545 @@
+//! Catalog modifying RPCs of the [`CatalogService`].
+//!
+//! These are intended for operators responding to incidents, are only served
+//! if enabled with [`CatalogService::with_write_access()`], and are recorded
+//! to the audit trail if one is configured.
+
+use std::collections::HashSet;
+
+use authz::{
+    audit::{AuditEvent, AuditOutcome},
+    Action, Authorizer, Permission, Resource,
+};
+use data_types::{
+    ColumnSet, ColumnsByName, CompactionLevel, NamespaceId, ParquetFileId, ParquetFileParams,
+    PartitionHashId, PartitionKey, SortedColumnSet, Timestamp, TransitionPartitionId,
+};
+use generated_types::influxdata::iox::catalog::v1::*;
+use iox_catalog::interface::{CasFailure, RepoCollection, SoftDeletedRows};
+use observability_deps::tracing::*;
+use tonic::Status;
+use uuid::Uuid;
+
+use crate::{to_partition, CatalogService};
+
+/// The number of attempts made to replace a sort key with `force` set, before
+/// giving up on concurrent modifications.
+const MAX_FORCED_CAS_ATTEMPTS: usize = 10;
+
+fn internal(e: iox_catalog::interface::Error) -> Status {
+    Status::internal(e.to_string())
+}
+
+fn files_json(files: &[ParquetFile]) -> serde_json::Value {
+    serde_json::to_value(files).expect("parquet files serialise to JSON")
+}
+
+fn partition_json(partition: &Partition) -> serde_json::Value {
+    serde_json::to_value(partition).expect("partition serialises to JSON")
+}
+
+impl CatalogService {
+    /// Verify the caller presenting `token` may perform `action` on the
+    /// namespace `namespace_name`.
+    async fn authorize(
+        &self,
+        token: Option<Vec<u8>>,
+        namespace_name: &str,
+        action: Action,
+    ) -> Result<(), Status> {
+        if !self.write_access {
+            return Err(Status::unimplemented(
+                "catalog modifications are not enabled on this server",
+            ));
+        }
+        let Some(authz) = &self.authz else {
+            return Ok(());
+        };
+
+        let perms = [Permission::ResourceAction(
+            Resource::Database(namespace_name.to_string()),
+            action,
+        )];
+        match authz.permissions(token, &perms).await {
+            Ok(_) => Ok(()),
+            Err(authz::Error::Forbidden) | Err(authz::Error::InvalidToken) => {
+                Err(Status::permission_denied("permission denied"))
+            }
+            Err(authz::Error::NoToken) => Err(Status::unauthenticated("no token")),
+            Err(e) => Err(Status::unavailable(e.to_string())),
+        }
+    }
+
+    /// Record the outcome of `operation` to the audit trail, if configured.
+    async fn audit(
+        &self,
+        operation: &str,
+        token: Option<&[u8]>,
+        namespace_name: &str,
+        table_name: Option<&str>,
+        change: Result<(Option<serde_json::Value>, serde_json::Value), &Status>,
+    ) {
+        let Some(sink) = &self.audit_sink else {
+            return;
+        };
+
+        let event = match change {
+            Ok((before, after)) => {
+                AuditEvent::new(operation, AuditOutcome::Succeeded).with_change(before, Some(after))
+            }
+            Err(_) => AuditEvent::new(operation, AuditOutcome::Failed),
+        };
+        let event = event.with_token(token).with_namespace(namespace_name);
+        let event = match table_name {
+            Some(table_name) => event.with_table(table_name),
+            None => event,
+        };
+        sink.record(event).await;
+    }
+
+    pub(crate) async fn flag_for_deletion(
+        &self,
+        token: Option<Vec<u8>>,
+        req: FlagParquetFilesForDeletionRequest,
+    ) -> Result<Vec<ParquetFile>, Status> {
+        let mut repos = self.catalog.repositories().await;
+        let (namespace_name, files) =
+            files_in_namespace(&mut *repos, &req.object_store_ids).await?;
+        self.authorize(token.clone(), &namespace_name, Action::Delete)
+            .await?;
+
+        if let Some(f) = files.iter().find(|f| f.to_delete.is_some()) {
+            return Err(Status::failed_precondition(format!(
+                "parquet file {} is already flagged for deletion",
+                f.object_store_id
+            )));
+        }
+
+        let before = files.iter().cloned().map(Into::into).collect::<Vec<_>>();
+        if req.dry_run {
+            return Ok(before);
+        }
+
+        let ids = files.iter().map(|f| f.id).collect::<Vec<_>>();
+        let res = async {
+            repos
+                .parquet_files()
+                // The target level is irrelevant, as no files are upgraded.
+                .create_upgrade_delete(&ids, &[], &[], CompactionLevel::Initial)
+                .await
+                .map_err(internal)?;
+            reload_files(&mut *repos, &files).await
+        }
+        .await;
+
+        if res.is_ok() {
+            info!(%namespace_name, ?ids, "flagged parquet files for deletion");
+        }
+        self.audit(
+            "flag_parquet_files_for_deletion",
+            token.as_deref(),
+            &namespace_name,
+            None,
+            res.as_ref()
+                .map(|after| (Some(files_json(&before)), files_json(after))),
+        )
+        .await;
+        res
+    }
+
+    pub(crate) async fn update_compaction_level(
+        &self,
+        token: Option<Vec<u8>>,
+        req: UpdateParquetFileCompactionLevelRequest,
+    ) -> Result<Vec<ParquetFile>, Status> {
+        let level = CompactionLevel::try_from(req.compaction_level)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        let mut repos = self.catalog.repositories().await;
+        let (namespace_name, files) =
+            files_in_namespace(&mut *repos, &req.object_store_ids).await?;
+        self.authorize(token.clone(), &namespace_name, Action::Write)
+            .await?;
+
+        if let Some(f) = files.iter().find(|f| f.to_delete.is_some()) {
+            return Err(Status::failed_precondition(format!(
+                "parquet file {} is flagged for deletion",
+                f.object_store_id
+            )));
+        }
+
+        let before = files.iter().cloned().map(Into::into).collect::<Vec<_>>();
+        if req.dry_run {
+            return Ok(files
+                .into_iter()
+                .map(|mut f| {
+                    f.compaction_level = level;
+                    f.into()
+                })
+                .collect());
+        }
+
+        let ids = files.iter().map(|f| f.id).collect::<Vec<_>>();
+        let res = async {
+            repos
+                .parquet_files()
+                .create_upgrade_delete(&[], &ids, &[], level)
+                .await
+                .map_err(internal)?;
+            reload_files(&mut *repos, &files).await
+        }
+        .await;
+
+        if res.is_ok() {
+            info!(%namespace_name, ?ids, ?level, "updated parquet file compaction level");
+        }
+        self.audit(
+            "update_parquet_file_compaction_level",
+            token.as_deref(),
+            &namespace_name,
+            None,
+            res.as_ref()
+                .map(|after| (Some(files_json(&before)), files_json(after))),
+        )
+        .await;
+        res
+    }
+
+    pub(crate) async fn set_sort_key(
+        &self,
+        token: Option<Vec<u8>>,
+        req: SetPartitionSortKeyRequest,
+    ) -> Result<Partition, Status> {
+        let partition_id = req
+            .partition_identifier
+            .map(TransitionPartitionId::try_from)
+            .ok_or_else(|| Status::invalid_argument("no partition id specified"))?
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        let mut repos = self.catalog.repositories().await;
+        let mut partition = get_partition(&mut *repos, &partition_id).await?;
+        let table = repos
+            .tables()
+            .get_by_id(partition.table_id)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| Status::not_found(format!("table {} not found", partition.table_id)))?;
+        let namespace_name = namespace_name(&mut *repos, table.namespace_id).await?;
+        self.authorize(token.clone(), &namespace_name, Action::Write)
+            .await?;
+
+        let columns = ColumnsByName::new(
+            repos
+                .columns()
+                .list_by_table_id(table.id)
+                .await
+                .map_err(internal)?,
+        );
+        let new_sort_key_ids = SortedColumnSet::new(column_ids(&columns, &req.sort_key)?);
+
+        let current = partition.sort_key.clone().unwrap_or_default();
+        if !req.force {
+            let expected = req
+                .expected_sort_key
+                .ok_or_else(|| {
+                    Status::invalid_argument("expected_sort_key is required unless force is set")
+                })?
+                .array_sort_key;
+            if expected != current {
+                return Err(Status::failed_precondition(format!(
+                    "partition sort key is [{}], expected [{}]",
+                    current.join(","),
+                    expected.join(",")
+                )));
+            }
+        }
+
+        let before = to_partition(partition.clone());
+        if req.dry_run {
+            partition.sort_key = Some(req.sort_key);
+            partition.sort_key_ids = new_sort_key_ids;
+            return Ok(to_partition(partition));
+        }
+
+        let new_sort_key = req.sort_key.iter().map(String::as_str).collect::<Vec<_>>();
+        let mut attempts = 0;
+        let res = loop {
+            attempts += 1;
+            match repos
+                .partitions()
+                .cas_sort_key(
+                    &partition.transition_partition_id(),
+                    partition.sort_key.clone(),
+                    Some(partition.sort_key_ids.clone()),
+                    &new_sort_key,
+                    &new_sort_key_ids,
+                )
+                .await
+            {
+                Ok(p) => break Ok(to_partition(p)),
+                Err(CasFailure::ValueMismatch((sort_key, sort_key_ids)))
+                    if req.force && attempts < MAX_FORCED_CAS_ATTEMPTS =>
+                {
+                    partition.sort_key = sort_key;
+                    partition.sort_key_ids = sort_key_ids;
+                }
+                Err(CasFailure::ValueMismatch(_)) => {
+                    break Err(Status::aborted(
+                        "partition sort key was concurrently modified",
+                    ))
+                }
+                Err(CasFailure::QueryError(e)) => break Err(internal(e)),
+            }
+        };
+
+        if res.is_ok() {
+            info!(
+                %namespace_name,
+                table_name=%table.name,
+                %partition_id,
+                sort_key=?req.sort_key,
+                "set partition sort key"
+            );
+        }
+        self.audit(
+            "set_partition_sort_key",
+            token.as_deref(),
+            &namespace_name,
+            Some(&table.name),
+            res.as_ref()
+                .map(|after| (Some(partition_json(&before)), partition_json(after))),
+        )
+        .await;
+        res
+    }
+
+    pub(crate) async fn register_file(
+        &self,
+        token: Option<Vec<u8>>,
+        req: RegisterParquetFileRequest,
+    ) -> Result<ParquetFile, Status> {
+        // Authorize before revealing whether the namespace exists.
+        self.authorize(token.clone(), &req.namespace_name, Action::Write)
+            .await?;
+
+        let object_store_id = parse_object_store_id(&req.object_store_id)?;
+        let compaction_level = CompactionLevel::try_from(req.compaction_level)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        let mut repos = self.catalog.repositories().await;
+        let namespace = repos
+            .namespaces()
+            .get_by_name(&req.namespace_name, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| {
+                Status::not_found(format!("namespace {} not found", req.namespace_name))
+            })?;
+        let table = repos
+            .tables()
+            .get_by_namespace_and_name(namespace.id, &req.table_name)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| Status::not_found(format!("table {} not found", req.table_name)))?;
+
+        let partition_key = PartitionKey::from(req.partition_key);
+        let partition = match repos
+            .partitions()
+            .get_by_hash_id(&PartitionHashId::new(table.id, &partition_key))
+            .await
+            .map_err(internal)?
+        {
+            Some(p) => Some(p),
+            // Partitions created before the introduction of hash IDs.
+            None => repos
+                .partitions()
+                .list_by_table_id(table.id)
+                .await
+                .map_err(internal)?
+                .into_iter()
+                .find(|p| p.partition_key == partition_key),
+        }
+        .ok_or_else(|| Status::not_found(format!("partition {partition_key} not found")))?;
+
+        if repos
+            .parquet_files()
+            .get_by_object_store_id(object_store_id)
+            .await
+            .map_err(internal)?
+            .is_some()
+        {
+            return Err(Status::already_exists(format!(
+                "parquet file {object_store_id} is already registered"
+            )));
+        }
+
+        let columns = ColumnsByName::new(
+            repos
+                .columns()
+                .list_by_table_id(table.id)
+                .await
+                .map_err(internal)?,
+        );
+        let column_set = ColumnSet::new(column_ids(&columns, &req.column_names)?);
+
+        let params = ParquetFileParams {
+            namespace_id: namespace.id,
+            table_id: table.id,
+            partition_id: partition.transition_partition_id(),
+            object_store_id,
+            min_time: Timestamp::new(req.min_time),
+            max_time: Timestamp::new(req.max_time),
+            file_size_bytes: req.file_size_bytes,
+            row_count: req.row_count,
+            compaction_level,
+            created_at: Timestamp::new(req.created_at),
+            column_set,
+            max_l0_created_at: Timestamp::new(req.max_l0_created_at),
+        };
+        if req.dry_run {
+            return Ok(data_types::ParquetFile::from_params(params, ParquetFileId::new(0)).into());
+        }
+
+        let res = repos
+            .parquet_files()
+            .create(params)
+            .await
+            .map(ParquetFile::from)
+            .map_err(|e| match e {
+                iox_catalog::interface::Error::FileExists { .. } => Status::already_exists(
+                    format!("parquet file {object_store_id} is already registered"),
+                ),
+                e => internal(e),
+            });
+
+        if res.is_ok() {
+            info!(
+                namespace_name=%namespace.name,
+                table_name=%table.name,
+                %object_store_id,
+                "registered parquet file"
+            );
+        }
+        self.audit(
+            "register_parquet_file",
+            token.as_deref(),
+            &namespace.name,
+            Some(&table.name),
+            res.as_ref()
+                .map(|after| (None, files_json(&[after.clone()]))),
+        )
+        .await;
+        res
+    }
+}
+
+fn parse_object_store_id(id: &str) -> Result<Uuid, Status> {
+    Uuid::parse_str(id)
+        .map_err(|e| Status::invalid_argument(format!("invalid object store id {id}: {e}")))
+}
+
+/// Resolve the IDs of the (distinct) column `names`.
+fn column_ids(
+    columns: &ColumnsByName,
+    names: &[String],
+) -> Result<Vec<data_types::ColumnId>, Status> {
+    let mut seen = HashSet::with_capacity(names.len());
+    names
+        .iter()
+        .map(|name| {
+            if !seen.insert(name) {
+                return Err(Status::invalid_argument(format!(
+                    "column {name} specified more than once"
+                )));
+            }
+            columns
+                .get(name)
+                .map(|c| c.id)
+                .ok_or_else(|| Status::invalid_argument(format!("column {name} not found")))
+        })
+        .collect()
+}
+
+async fn namespace_name(
+    repos: &mut dyn RepoCollection,
+    namespace_id: NamespaceId,
+) -> Result<String, Status> {
+    Ok(repos
+        .namespaces()
+        .get_by_id(namespace_id, SoftDeletedRows::AllRows)
+        .await
+        .map_err(internal)?
+        .ok_or_else(|| Status::not_found(format!("namespace {namespace_id} not found")))?
+        .name)
+}
+
+async fn get_partition(
+    repos: &mut dyn RepoCollection,
+    partition_id: &TransitionPartitionId,
+) -> Result<data_types::Partition, Status> {
+    match partition_id {
+        TransitionPartitionId::Deprecated(id) => repos.partitions().get_by_id(*id).await,
+        TransitionPartitionId::Deterministic(hash_id) => {
+            repos.partitions().get_by_hash_id(hash_id).await
+        }
+    }
+    .map_err(internal)?
+    .ok_or_else(|| Status::not_found(format!("partition {partition_id} not found")))
+}
+
+/// Look up the parquet files with the given `object_store_ids`, which must
+/// all belong to the same namespace, returning them along with the name of
+/// that namespace.
+async fn files_in_namespace(
+    repos: &mut dyn RepoCollection,
+    object_store_ids: &[String],
+) -> Result<(String, Vec<data_types::ParquetFile>), Status> {
+    if object_store_ids.is_empty() {
+        return Err(Status::invalid_argument("no object store ids specified"));
+    }
+
+    let mut files = Vec::with_capacity(object_store_ids.len());
+    let mut seen = HashSet::with_capacity(object_store_ids.len());
+    for id in object_store_ids {
+        let object_store_id = parse_object_store_id(id)?;
+        if !seen.insert(object_store_id) {
+            continue;
+        }
+        let file = repos
+            .parquet_files()
+            .get_by_object_store_id(object_store_id)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| Status::not_found(format!("parquet file {id} not found")))?;
+        files.push(file);
+    }
+
+    let namespace_id = files[0].namespace_id;
+    if files.iter().any(|f| f.namespace_id != namespace_id) {
+        return Err(Status::invalid_argument(
+            "all parquet files must belong to the same namespace",
+        ));
+    }
+
+    Ok((namespace_name(repos, namespace_id).await?, files))
+}
+
+/// Read the current state of `files` from the catalog.
+async fn reload_files(
+    repos: &mut dyn RepoCollection,
+    files: &[data_types::ParquetFile],
+) -> Result<Vec<ParquetFile>, Status> {
+    let mut out = Vec::with_capacity(files.len());
+    for f in files {
+        let file = repos
+            .parquet_files()
+            .get_by_object_store_id(f.object_store_id)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| {
+                Status::internal(format!("parquet file {} disappeared", f.object_store_id))
+            })?;
+        out.push(file.into());
+    }
+    Ok(out)
+}
@@ -0,0 +1,148 @@
+//! gRPC service exposing the cluster members discovered through gossip
+//! membership announcements. Used in the router and querier, but can be
+//! included in any gRPC server with gossip enabled.
+
+#![deny(rustdoc::broken_intra_doc_links, rustdoc::bare_urls, rust_2018_idioms)]
+#![warn(
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    clippy::explicit_iter_loop,
+    // See https://github.com/influxdata/influxdb_iox/pull/1671
+    clippy::future_not_send,
+    clippy::use_self,
+    clippy::clone_on_ref_ptr,
+    clippy::todo,
+    clippy::dbg_macro,
+    unused_crate_dependencies
+)]
+
+// Workaround for "unused crate" lint false positives.
+use workspace_hack as _;
+
+use std::sync::Arc;
+
+use generated_types::influxdata::iox::cluster::v1::*;
+use gossip_membership::ClusterMembers;
+use tonic::{Request, Response, Status};
+
+/// Implementation of the Cluster gRPC service
+#[derive(Debug)]
+pub struct ClusterService {
+    /// The view of the cluster membership.
+    members: Arc<ClusterMembers>,
+}
+
+impl ClusterService {
+    /// Create a new cluster service reporting the content of `members`.
+    pub fn new(members: Arc<ClusterMembers>) -> Self {
+        Self { members }
+    }
+}
+
+#[tonic::async_trait]
+impl cluster_service_server::ClusterService for ClusterService {
+    async fn get_cluster_members(
+        &self,
+        _request: Request<GetClusterMembersRequest>,
+    ) -> Result<Response<GetClusterMembersResponse>, Status> {
+        let members = self
+            .members
+            .members()
+            .into_iter()
+            .map(|m| ClusterMember {
+                identity: m.identity.to_string(),
+                role: to_proto_role(m.info.role).into(),
+                rpc_address: m.info.rpc_address,
+                version: m.info.version,
+                started_at_ns: m.info.started_at.timestamp_nanos(),
+                last_seen_ns: m.last_seen.timestamp_nanos(),
+                alive: m.alive,
+                local: m.local,
+            })
+            .collect();
+
+        Ok(Response::new(GetClusterMembersResponse { members }))
+    }
+}
+
+fn to_proto_role(role: gossip_membership::Role) -> Role {
+    match role {
+        gossip_membership::Role::Router => Role::Router,
+        gossip_membership::Role::Ingester => Role::Ingester,
+        gossip_membership::Role::Querier => Role::Querier,
+        gossip_membership::Role::Compactor => Role::Compactor,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use generated_types::influxdata::iox::cluster::v1::cluster_service_server::ClusterService as _;
+    use gossip::Identity;
+    use gossip_membership::MemberInfo;
+    use iox_time::{MockProvider, Time};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_cluster_members() {
+        let time = Arc::new(MockProvider::new(Time::from_timestamp_nanos(1_000)));
+        let members = Arc::new(ClusterMembers::new(Arc::clone(&time) as _));
+
+        let local = Identity::try_from(vec![1; 16]).unwrap();
+        let remote = Identity::try_from(vec![2; 16]).unwrap();
+
+        members.set_local(
+            local.clone(),
+            MemberInfo {
+                role: gossip_membership::Role::Router,
+                rpc_address: "http://router:8080".to_string(),
+                version: "v1".to_string(),
+                started_at: Time::from_timestamp_nanos(1),
+            },
+        );
+        members.observe(
+            remote.clone(),
+            MemberInfo {
+                role: gossip_membership::Role::Ingester,
+                rpc_address: "http://ingester:8082".to_string(),
+                version: "v2".to_string(),
+                started_at: Time::from_timestamp_nanos(2),
+            },
+        );
+
+        let service = ClusterService::new(members);
+        let got = service
+            .get_cluster_members(Request::new(GetClusterMembersRequest {}))
+            .await
+            .expect("rpc should succeed")
+            .into_inner()
+            .members;
+
+        assert_eq!(
+            got,
+            [
+                ClusterMember {
+                    identity: local.to_string(),
+                    role: Role::Router.into(),
+                    rpc_address: "http://router:8080".to_string(),
+                    version: "v1".to_string(),
+                    started_at_ns: 1,
+                    last_seen_ns: 1_000,
+                    alive: true,
+                    local: true,
+                },
+                ClusterMember {
+                    identity: remote.to_string(),
+                    role: Role::Ingester.into(),
+                    rpc_address: "http://ingester:8082".to_string(),
+                    version: "v2".to_string(),
+                    started_at_ns: 2,
+                    last_seen_ns: 1_000,
+                    alive: true,
+                    local: false,
+                },
+            ]
+        );
+    }
+}
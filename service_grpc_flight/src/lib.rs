@@ -41,7 +41,10 @@ use iox_query::{exec::IOxSessionContext, QueryCompletedToken, QueryNamespace};
 use observability_deps::tracing::{debug, info, warn};
 use prost::Message;
 use request::{IoxGetRequest, RunQuery};
-use service_common::{datafusion_error_to_tonic_code, planner::Planner, QueryNamespaceProvider};
+use service_common::{
+    admission::QueryAdmission, authorize_tables, datafusion_error_to_tonic_code, planner::Planner,
+    QueryNamespaceProvider, TableFilter,
+};
 use snafu::{OptionExt, ResultExt, Snafu};
 use std::{
     fmt::Debug,
@@ -56,7 +59,6 @@ use tonic::{
 };
 use trace::{ctx::SpanContext, span::SpanExt};
 use trace_http::ctx::{RequestLogContext, RequestLogContextExt};
-use tracker::InstrumentedAsyncOwnedSemaphorePermit;
 
 /// The supported names of the grpc header that contain the target database
 /// for FlightSQL requests.
@@ -491,10 +493,11 @@ where
         &self,
         span_ctx: Option<SpanContext>,
         external_span_ctx: Option<RequestLogContext>,
-        permit: InstrumentedAsyncOwnedSemaphorePermit,
+        permit: QueryAdmission,
         query: RunQuery,
         namespace_name: String,
         is_debug: bool,
+        table_filter: &TableFilter,
     ) -> Result<Response<TonicStream<FlightData>>, tonic::Status> {
         let db = self
             .server
@@ -502,6 +505,7 @@ where
                 &namespace_name,
                 span_ctx.child_span("get namespace"),
                 is_debug,
+                table_filter,
             )
             .await
             .context(DatabaseNotFoundSnafu {
@@ -532,7 +536,7 @@ where
                     Box::new(sql_query.clone()),
                 );
                 let plan = Planner::new(&ctx)
-                    .influxql(sql_query)
+                    .influxql(sql_query, db)
                     .await
                     .context(PlanningSnafu {
                         namespace_name: &namespace_name,
@@ -630,22 +634,34 @@ where
         let query = request.query();
         is_debug |= request.is_debug();
 
-        let perms = match query {
-            RunQuery::FlightSQL(cmd) => flightsql_permissions(namespace_name, cmd),
-            RunQuery::Sql(_) | RunQuery::InfluxQL(_) => vec![authz::Permission::ResourceAction(
-                authz::Resource::Database(namespace_name.to_string()),
-                authz::Action::Read,
-            )],
+        let action = match query {
+            RunQuery::FlightSQL(cmd) => flightsql_action(cmd),
+            RunQuery::Sql(_) | RunQuery::InfluxQL(_) => authz::Action::Read,
         };
-        self.authz
-            .permissions(authz_token, &perms)
-            .await
-            .map_err(Error::from)?;
+        let table_filter = authorize_tables(
+            &*self.server,
+            &self.authz,
+            authz_token.clone(),
+            namespace_name,
+            action,
+            span_ctx.child_span("authorize tables"),
+        )
+        .await
+        .map_err(Error::from)?;
 
-        let permit = self
+        let admission = self
             .server
-            .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
-            .await;
+            .admit_query(
+                namespace_name,
+                authz_token.as_deref(),
+                span_ctx.child_span("query admission"),
+            )
+            .await?;
+        let permit = admission.with_permit(
+            self.server
+                .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
+                .await,
+        );
 
         // Log after we acquire the permit and are about to start execution
         let start = Instant::now();
@@ -665,6 +681,7 @@ where
                 query.clone(),
                 namespace_name.to_string(),
                 is_debug,
+                &table_filter,
             )
             .await;
 
@@ -760,11 +777,16 @@ where
         let cmd = cmd_from_descriptor(flight_descriptor.clone())?;
         info!(%namespace_name, %cmd, %trace, "GetFlightInfo request");
 
-        let perms = flightsql_permissions(&namespace_name, &cmd);
-        self.authz
-            .permissions(authz_token, &perms)
-            .await
-            .map_err(Error::from)?;
+        let table_filter = authorize_tables(
+            &*self.server,
+            &self.authz,
+            authz_token,
+            &namespace_name,
+            flightsql_action(&cmd),
+            span_ctx.child_span("authorize tables"),
+        )
+        .await
+        .map_err(Error::from)?;
 
         let db = self
             .server
@@ -772,6 +794,7 @@ where
                 &namespace_name,
                 span_ctx.child_span("get namespace"),
                 is_debug,
+                &table_filter,
             )
             .await
             .context(DatabaseNotFoundSnafu {
@@ -841,11 +864,16 @@ where
 
         info!(%namespace_name, %action_type, %cmd, %trace, "DoAction request");
 
-        let perms = flightsql_permissions(&namespace_name, &cmd);
-        self.authz
-            .permissions(authz_token, &perms)
-            .await
-            .map_err(Error::from)?;
+        let table_filter = authorize_tables(
+            &*self.server,
+            &self.authz,
+            authz_token,
+            &namespace_name,
+            flightsql_action(&cmd),
+            span_ctx.child_span("authorize tables"),
+        )
+        .await
+        .map_err(Error::from)?;
 
         let db = self
             .server
@@ -853,6 +881,7 @@ where
                 &namespace_name,
                 span_ctx.child_span("get namespace"),
                 is_debug,
+                &table_filter,
             )
             .await
             .context(DatabaseNotFoundSnafu {
@@ -951,9 +980,8 @@ fn get_flight_authz(metadata: &MetadataMap) -> Option<Vec<u8>> {
     extract_token(metadata.get("authorization"))
 }
 
-fn flightsql_permissions(namespace_name: &str, cmd: &FlightSQLCommand) -> Vec<authz::Permission> {
-    let resource = authz::Resource::Database(namespace_name.to_string());
-    let action = match cmd {
+fn flightsql_action(cmd: &FlightSQLCommand) -> authz::Action {
+    match cmd {
         FlightSQLCommand::CommandStatementQuery(_) => authz::Action::Read,
         FlightSQLCommand::CommandPreparedStatementQuery(_) => authz::Action::Read,
         FlightSQLCommand::CommandGetSqlInfo(_) => authz::Action::ReadSchema,
@@ -968,8 +996,7 @@ fn flightsql_permissions(namespace_name: &str, cmd: &FlightSQLCommand) -> Vec<au
         FlightSQLCommand::CommandGetXdbcTypeInfo(_) => authz::Action::ReadSchema,
         FlightSQLCommand::ActionCreatePreparedStatementRequest(_) => authz::Action::Read,
         FlightSQLCommand::ActionClosePreparedStatementRequest(_) => authz::Action::Read,
-    };
-    vec![authz::Permission::ResourceAction(resource, action)]
+    }
 }
 
 /// Check if request has IOx debug header set.
@@ -987,7 +1014,7 @@ fn has_debug_header(metadata: &MetadataMap) -> bool {
 struct GetStream {
     inner: KeepAliveStream,
     #[allow(dead_code)]
-    permit: InstrumentedAsyncOwnedSemaphorePermit,
+    permit: QueryAdmission,
     query_completed_token: QueryCompletedToken,
     done: bool,
 }
@@ -999,7 +1026,7 @@ impl GetStream {
         namespace_name: String,
         query: &RunQuery,
         query_completed_token: QueryCompletedToken,
-        permit: InstrumentedAsyncOwnedSemaphorePermit,
+        permit: QueryAdmission,
     ) -> Result<Self, tonic::Status> {
         let app_metadata = proto::AppMetadata {};
 
@@ -1071,6 +1098,7 @@ mod tests {
     use async_trait::async_trait;
     use authz::Permission;
     use futures::Future;
+    use iox_query::test::TestChunk;
     use metric::{Attributes, Metric, U64Gauge};
     use service_common::test_util::TestDatabaseStore;
     use tokio::pin;
@@ -1250,6 +1278,23 @@ mod tests {
             match token {
                 Some(token) => match (&token as &dyn AsRef<[u8]>).as_ref() {
                     b"GOOD" => Ok(perms.to_vec()),
+                    b"CPU" => {
+                        let got = perms
+                            .iter()
+                            .filter(|p| {
+                                matches!(
+                                    p,
+                                    Permission::ResourceAction(authz::Resource::Table { table, .. }, _)
+                                        if table == "cpu"
+                                )
+                            })
+                            .cloned()
+                            .collect::<Vec<_>>();
+                        if got.is_empty() {
+                            return Err(authz::Error::Forbidden);
+                        }
+                        Ok(got)
+                    }
                     b"BAD" => Err(authz::Error::Forbidden),
                     b"INVALID" => Err(authz::Error::InvalidToken),
                     b"UGLY" => Err(authz::Error::verification("test", "test error")),
@@ -1368,6 +1413,43 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn do_get_table_authz() {
+        let test_storage = Arc::new(TestDatabaseStore::default());
+        let db = test_storage.db_or_create("bananas").await;
+        db.add_chunk("p1", Arc::new(TestChunk::new("cpu")));
+        test_storage.db_or_create("platanos").await;
+
+        let svc = FlightService {
+            server: Arc::clone(&test_storage),
+            authz: Some(Arc::new(MockAuthorizer {})),
+        };
+
+        let request = |namespace: &str| {
+            let mut req = tonic::Request::new(
+                IoxGetRequest::new(
+                    namespace.to_string(),
+                    RunQuery::Sql("SELECT 1".to_string()),
+                    false,
+                )
+                .try_encode()
+                .unwrap(),
+            );
+            req.metadata_mut().insert(
+                MetadataKey::from_static("authorization"),
+                MetadataValue::from_static("Bearer CPU"),
+            );
+            req
+        };
+
+        // A grant for a single table is sufficient to query the namespace.
+        svc.do_get(request("bananas")).await.unwrap();
+
+        // But not for a namespace without any permitted table.
+        let err = svc.do_get(request("platanos")).await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+    }
+
     #[tokio::test]
     async fn get_flight_info_authz() {
         let test_storage = Arc::new(TestDatabaseStore::default());
@@ -42,6 +42,7 @@ pub mod service;
 #[cfg(any(test, feature = "test-util"))]
 pub mod test_util;
 
+use authz::Authorizer;
 use generated_types::storage_server::{Storage, StorageServer};
 use service_common::QueryNamespaceProvider;
 use std::sync::Arc;
@@ -50,10 +51,12 @@ use std::sync::Arc;
 #[derive(Debug)]
 struct StorageService<T: QueryNamespaceProvider> {
     pub db_store: Arc<T>,
+    pub authz: Option<Arc<dyn Authorizer>>,
 }
 
 pub fn make_server<T: QueryNamespaceProvider + 'static>(
     db_store: Arc<T>,
+    authz: Option<Arc<dyn Authorizer>>,
 ) -> StorageServer<impl Storage> {
-    StorageServer::new(StorageService { db_store })
+    StorageServer::new(StorageService { db_store, authz })
 }
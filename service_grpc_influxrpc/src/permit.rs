@@ -1,19 +1,19 @@
 use futures::Stream;
 use pin_project::pin_project;
-use tracker::InstrumentedAsyncOwnedSemaphorePermit;
+use service_common::admission::QueryAdmission;
 
-/// Helper to keep a semaphore permit attached to a stream.
+/// Helper to keep the query admission (incl. semaphore permits) attached to a stream.
 #[derive(Debug)]
 #[pin_project]
 pub struct StreamWithPermit<S> {
     #[pin]
     stream: S,
     #[allow(dead_code)]
-    permit: InstrumentedAsyncOwnedSemaphorePermit,
+    permit: QueryAdmission,
 }
 
 impl<S> StreamWithPermit<S> {
-    pub fn new(stream: S, permit: InstrumentedAsyncOwnedSemaphorePermit) -> Self {
+    pub fn new(stream: S, permit: QueryAdmission) -> Self {
         Self { stream, permit }
     }
 }
@@ -13,6 +13,8 @@ use crate::{
     response_chunking::ChunkReadResponses,
     StorageService,
 };
+use arrow::{array::as_primitive_array, compute::sum, datatypes::Int64Type};
+use authz::{extract_token, Action};
 use data_types::NamespaceName;
 use datafusion::error::DataFusionError;
 use futures::{stream::BoxStream, Stream, StreamExt, TryStreamExt};
@@ -22,6 +24,7 @@ use generated_types::{
     literal_or_regex::Value as RegexOrLiteralValue,
     offsets_response::PartitionOffsetResponse,
     read_response::Frame,
+    read_series_cardinality_request,
     storage_server::Storage,
     tag_key_predicate, CapabilitiesResponse, Capability, Int64ValuesResponse, LiteralOrRegex,
     MeasurementFieldsRequest, MeasurementFieldsResponse, MeasurementNamesRequest,
@@ -39,18 +42,21 @@ use iox_query::{
 };
 use observability_deps::tracing::{error, info, trace};
 use prost::{bytes::BytesMut, Message};
-use service_common::{datafusion_error_to_tonic_code, planner::Planner, QueryNamespaceProvider};
+use service_common::{
+    admission::{AdmissionError, QueryAdmission},
+    authorize_tables, datafusion_error_to_tonic_code,
+    planner::{Planner, SeriesCardinalityMode},
+    QueryNamespaceProvider,
+};
 use snafu::{OptionExt, ResultExt, Snafu};
 use std::{
     collections::{BTreeSet, HashMap},
     fmt::{Display, Formatter, Result as FmtResult},
     sync::Arc,
 };
-use tokio_stream::wrappers::ReceiverStream;
 use tonic::{metadata::MetadataMap, Response, Status};
 use trace::{ctx::SpanContext, span::SpanExt};
 use trace_http::ctx::{RequestLogContext, RequestLogContextExt};
-use tracker::InstrumentedAsyncOwnedSemaphorePermit;
 
 /// The size to which we limit our [`ReadResponse`] payloads.
 ///
@@ -109,6 +115,29 @@ pub enum Error {
         source: DataFusionError,
     },
 
+    #[snafu(display(
+        "Error creating series cardinality plans for namespace '{}': {}",
+        db_name,
+        source
+    ))]
+    PlanningSeriesCardinality {
+        db_name: String,
+        source: DataFusionError,
+    },
+
+    #[snafu(display(
+        "Error computing series cardinality for namespace '{}': {}",
+        db_name,
+        source
+    ))]
+    ComputingSeriesCardinality {
+        db_name: String,
+        source: DataFusionError,
+    },
+
+    #[snafu(display("Invalid series cardinality mode: {}", mode))]
+    InvalidSeriesCardinalityMode { mode: i32 },
+
     #[snafu(display(
         "Can not retrieve tag values for '{}' in namespace '{}': {}",
         tag_name,
@@ -190,10 +219,33 @@ pub enum Error {
 
     #[snafu(display("Operation not yet implemented:  {}", operation))]
     NotYetImplemented { operation: String },
+
+    #[snafu(display("Unauthenticated"))]
+    Unauthenticated,
+
+    #[snafu(display("Permission denied"))]
+    PermissionDenied,
+
+    #[snafu(display("Authz error: {}", source))]
+    Authz { source: authz::Error },
+
+    #[snafu(display("Query not admitted: {}", source))]
+    Admission { source: AdmissionError },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
+impl From<authz::Error> for Error {
+    fn from(source: authz::Error) -> Self {
+        match source {
+            authz::Error::Forbidden => Self::PermissionDenied,
+            authz::Error::InvalidToken => Self::PermissionDenied,
+            authz::Error::NoToken => Self::Unauthenticated,
+            source => Self::Authz { source },
+        }
+    }
+}
+
 impl From<Error> for Status {
     /// Converts a result from the business logic into the appropriate tonic
     /// status
@@ -218,6 +270,8 @@ impl Error {
             | Self::PlanningGroupSeries { source, .. }
             | Self::FilteringSeries { source, .. }
             | Self::GroupingSeries { source, .. }
+            | Self::PlanningSeriesCardinality { source, .. }
+            | Self::ComputingSeriesCardinality { source, .. }
             | Self::ListingTagValues { source, .. } => datafusion_error_to_tonic_code(&source),
             Self::ConvertingPredicate { source, .. }
             | Self::ConvertingReadGroupType { source, .. }
@@ -242,11 +296,15 @@ impl Error {
             | Self::SettingPredicateTable { .. }
             | Self::MeasurementLiteralOrRegex { .. }
             | Self::MissingTagKeyPredicate {}
+            | Self::InvalidSeriesCardinalityMode { .. }
             | Self::InvalidTagKeyRegex { .. } => tonic::Code::InvalidArgument,
-            Self::SendingResults { .. } | Self::InternalHintsFieldNotSupported { .. } => {
-                tonic::Code::Internal
-            }
+            Self::SendingResults { .. }
+            | Self::InternalHintsFieldNotSupported { .. }
+            | Self::Authz { .. } => tonic::Code::Internal,
             Self::NotYetImplemented { .. } => tonic::Code::Unimplemented,
+            Self::Unauthenticated => tonic::Code::Unauthenticated,
+            Self::PermissionDenied => tonic::Code::PermissionDenied,
+            Self::Admission { .. } => tonic::Code::ResourceExhausted,
         };
 
         // InfluxRPC clients expect an instance of InfluxDbError
@@ -337,6 +395,7 @@ impl From<tonic::Code> for InfluxCode {
             tonic::Code::NotFound => InfluxCode::ENotFound,
             tonic::Code::AlreadyExists => InfluxCode::EConflict,
             tonic::Code::PermissionDenied => InfluxCode::EUnauthorized,
+            tonic::Code::Unauthenticated => InfluxCode::EUnauthorized,
             tonic::Code::ResourceExhausted => InfluxCode::ETooLarge,
             tonic::Code::FailedPrecondition => InfluxCode::EInvalid,
             tonic::Code::OutOfRange => InfluxCode::EInvalid,
@@ -357,6 +416,63 @@ fn add_headers(metadata: &mut MetadataMap) {
     metadata.insert("storage-type", "iox".parse().unwrap());
 }
 
+impl<T> StorageService<T>
+where
+    T: QueryNamespaceProvider + 'static,
+{
+    /// Authorize `action` on the namespace `db_name` for the request's
+    /// `token` and admit the query, returning the namespace restricted to the
+    /// tables the token is permitted to access.
+    ///
+    /// The returned [`QueryAdmission`] (which includes the global query
+    /// semaphore permit) must be held until the response has been streamed.
+    async fn authorized_db(
+        &self,
+        db_name: &NamespaceName<'_>,
+        token: Option<Vec<u8>>,
+        action: Action,
+        span_ctx: &Option<SpanContext>,
+    ) -> Result<(Arc<T::Db>, QueryAdmission), Error> {
+        let table_filter = authorize_tables(
+            &*self.db_store,
+            &self.authz,
+            token.clone(),
+            db_name,
+            action,
+            span_ctx.child_span("authorize tables"),
+        )
+        .await?;
+
+        let admission = self
+            .db_store
+            .admit_query(
+                db_name,
+                token.as_deref(),
+                span_ctx.child_span("query admission"),
+            )
+            .await
+            .context(AdmissionSnafu)?;
+        let admission = admission.with_permit(
+            self.db_store
+                .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
+                .await,
+        );
+
+        let db = self
+            .db_store
+            .db(
+                db_name,
+                span_ctx.child_span("get namespace"),
+                false,
+                &table_filter,
+            )
+            .await
+            .context(NamespaceNotFoundSnafu { db_name })?;
+
+        Ok((db, admission))
+    }
+}
+
 /// Implements the protobuf defined Storage service for a [`QueryNamespaceProvider`]
 #[tonic::async_trait]
 impl<T> Storage for StorageService<T>
@@ -373,11 +489,9 @@ where
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
+        let authz_token = extract_token(req.metadata().get("authorization"));
+
         let req = req.into_inner();
-        let permit = self
-            .db_store
-            .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
-            .await;
         let db_name = get_namespace_name(&req)?;
         info!(
             %db_name,
@@ -387,11 +501,9 @@ where
             "read filter",
         );
 
-        let db = self
-            .db_store
-            .db(&db_name, span_ctx.child_span("get namespace"), false)
-            .await
-            .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
+        let (db, permit) = self
+            .authorized_db(&db_name, authz_token, Action::Read, &span_ctx)
+            .await?;
 
         let ctx = db.new_query_context(span_ctx);
         let query_completed_token = db.record_query(
@@ -420,11 +532,9 @@ where
     ) -> Result<Response<Self::ReadGroupStream>, Status> {
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
+        let authz_token = extract_token(req.metadata().get("authorization"));
+
         let req = req.into_inner();
-        let permit = self
-            .db_store
-            .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
-            .await;
 
         let db_name = get_namespace_name(&req)?;
 
@@ -439,11 +549,9 @@ where
             "read_group",
         );
 
-        let db = self
-            .db_store
-            .db(&db_name, span_ctx.child_span("get namespace"), false)
-            .await
-            .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
+        let (db, permit) = self
+            .authorized_db(&db_name, authz_token, Action::Read, &span_ctx)
+            .await?;
 
         let ctx = db.new_query_context(span_ctx);
         let query_completed_token = db.record_query(
@@ -500,11 +608,9 @@ where
     ) -> Result<Response<Self::ReadGroupStream>, Status> {
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
+        let authz_token = extract_token(req.metadata().get("authorization"));
+
         let req = req.into_inner();
-        let permit = self
-            .db_store
-            .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
-            .await;
 
         let db_name = get_namespace_name(&req)?;
         info!(
@@ -519,11 +625,9 @@ where
             "read_window_aggregate",
         );
 
-        let db = self
-            .db_store
-            .db(&db_name, span_ctx.child_span("get namespace"), false)
-            .await
-            .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
+        let (db, permit) = self
+            .authorized_db(&db_name, authz_token, Action::Read, &span_ctx)
+            .await?;
 
         let ctx = db.new_query_context(span_ctx);
         let query_completed_token = db.record_query(
@@ -585,11 +689,9 @@ where
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
+        let authz_token = extract_token(req.metadata().get("authorization"));
+
         let req = req.into_inner();
-        let permit = self
-            .db_store
-            .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
-            .await;
 
         let db_name = get_namespace_name(&req)?;
         info!(
@@ -600,11 +702,9 @@ where
             "tag_keys",
         );
 
-        let db = self
-            .db_store
-            .db(&db_name, span_ctx.child_span("get namespace"), false)
-            .await
-            .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
+        let (db, permit) = self
+            .authorized_db(&db_name, authz_token, Action::ReadSchema, &span_ctx)
+            .await?;
 
         let ctx = db.new_query_context(span_ctx);
         let query_completed_token = db.record_query(
@@ -654,11 +754,9 @@ where
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
+        let authz_token = extract_token(req.metadata().get("authorization"));
+
         let req = req.into_inner();
-        let permit = self
-            .db_store
-            .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
-            .await;
 
         let db_name = get_namespace_name(&req)?;
         let tag_key = DecodedTagKey::try_from(req.tag_key.clone())
@@ -672,11 +770,9 @@ where
             "tag_values",
         );
 
-        let db = self
-            .db_store
-            .db(&db_name, span_ctx.child_span("get namespace"), false)
-            .await
-            .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
+        let (db, permit) = self
+            .authorized_db(&db_name, authz_token, Action::ReadSchema, &span_ctx)
+            .await?;
 
         let ctx = db.new_query_context(span_ctx);
         let query_completed_token = db.record_query(
@@ -760,11 +856,9 @@ where
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
+        let authz_token = extract_token(req.metadata().get("authorization"));
+
         let req = req.into_inner();
-        let permit = self
-            .db_store
-            .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
-            .await;
 
         let db_name = get_namespace_name(&req)?;
         info!(
@@ -776,11 +870,9 @@ where
             "tag_values_grouped_by_measurement_and_tag_key",
         );
 
-        let db = self
-            .db_store
-            .db(&db_name, span_ctx.child_span("get namespace"), false)
-            .await
-            .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
+        let (db, permit) = self
+            .authorized_db(&db_name, authz_token, Action::ReadSchema, &span_ctx)
+            .await?;
 
         let ctx = db.new_query_context(span_ctx);
         let query_completed_token = db.record_query(
@@ -804,13 +896,63 @@ where
         )
     }
 
-    type ReadSeriesCardinalityStream = ReceiverStream<Result<Int64ValuesResponse, Status>>;
+    type ReadSeriesCardinalityStream = StreamWithPermit<
+        QueryCompletedTokenStream<
+            BoxStream<'static, Result<Int64ValuesResponse, Status>>,
+            Int64ValuesResponse,
+            Status,
+        >,
+    >;
 
     async fn read_series_cardinality(
         &self,
-        _req: tonic::Request<ReadSeriesCardinalityRequest>,
+        req: tonic::Request<ReadSeriesCardinalityRequest>,
     ) -> Result<Response<Self::ReadSeriesCardinalityStream>, Status> {
-        unimplemented!("read_series_cardinality not yet implemented. https://github.com/influxdata/influxdb_iox/issues/447");
+        let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
+        let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
+
+        let authz_token = extract_token(req.metadata().get("authorization"));
+
+        let req = req.into_inner();
+
+        let db_name = get_namespace_name(&req)?;
+        info!(
+            %db_name,
+            ?req.range,
+            ?req.mode,
+            predicate=%req.predicate.loggable(),
+            trace=%external_span_ctx.format_jaeger(),
+            "read_series_cardinality",
+        );
+
+        let (db, permit) = self
+            .authorized_db(&db_name, authz_token, Action::Read, &span_ctx)
+            .await?;
+
+        let ctx = db.new_query_context(span_ctx);
+        let query_completed_token = db.record_query(
+            external_span_ctx.as_ref().map(RequestLogContext::ctx),
+            "read_series_cardinality",
+            defer_json(&req),
+        );
+
+        let ReadSeriesCardinalityRequest {
+            read_series_cardinality_source: _source,
+            range,
+            predicate,
+            mode,
+        } = req;
+
+        let response =
+            series_cardinality_impl(Arc::clone(&db), db_name, range, predicate, mode, &ctx)
+                .await
+                .map_err(|e| e.into_status());
+
+        make_response(
+            futures::stream::once(async move { response }).boxed(),
+            query_completed_token,
+            permit,
+        )
     }
 
     async fn capabilities(
@@ -834,10 +976,7 @@ where
             (
                 "WindowAggregate",
                 vec![
-                    "Count", "Sum", // "First"
-                    // "Last",
-                    "Min", "Max", "Mean",
-                    // "Offset"
+                    "Count", "Sum", "First", "Last", "Min", "Max", "Mean", "Offset",
                 ],
             ),
         ];
@@ -870,11 +1009,9 @@ where
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
+        let authz_token = extract_token(req.metadata().get("authorization"));
+
         let req = req.into_inner();
-        let permit = self
-            .db_store
-            .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
-            .await;
 
         let db_name = get_namespace_name(&req)?;
         info!(
@@ -885,11 +1022,9 @@ where
             "measurement_names",
         );
 
-        let db = self
-            .db_store
-            .db(&db_name, span_ctx.child_span("get namespace"), false)
-            .await
-            .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
+        let (db, permit) = self
+            .authorized_db(&db_name, authz_token, Action::ReadSchema, &span_ctx)
+            .await?;
 
         let ctx = db.new_query_context(span_ctx);
         let query_completed_token = db.record_query(
@@ -930,11 +1065,9 @@ where
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
+        let authz_token = extract_token(req.metadata().get("authorization"));
+
         let req = req.into_inner();
-        let permit = self
-            .db_store
-            .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
-            .await;
 
         let db_name = get_namespace_name(&req)?;
         info!(
@@ -946,11 +1079,9 @@ where
             "measurement_tag_keys",
         );
 
-        let db = self
-            .db_store
-            .db(&db_name, span_ctx.child_span("get namespace"), false)
-            .await
-            .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
+        let (db, permit) = self
+            .authorized_db(&db_name, authz_token, Action::ReadSchema, &span_ctx)
+            .await?;
 
         let ctx = db.new_query_context(span_ctx);
         let query_completed_token = db.record_query(
@@ -1001,11 +1132,9 @@ where
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
+        let authz_token = extract_token(req.metadata().get("authorization"));
+
         let req = req.into_inner();
-        let permit = self
-            .db_store
-            .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
-            .await;
 
         let db_name = get_namespace_name(&req)?;
         info!(
@@ -1018,11 +1147,9 @@ where
             "measurement_tag_values",
         );
 
-        let db = self
-            .db_store
-            .db(&db_name, span_ctx.child_span("get namespace"), false)
-            .await
-            .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
+        let (db, permit) = self
+            .authorized_db(&db_name, authz_token, Action::ReadSchema, &span_ctx)
+            .await?;
 
         let ctx = db.new_query_context(span_ctx);
         let query_completed_token = db.record_query(
@@ -1075,11 +1202,9 @@ where
         let external_span_ctx: Option<RequestLogContext> = req.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
+        let authz_token = extract_token(req.metadata().get("authorization"));
+
         let req = req.into_inner();
-        let permit = self
-            .db_store
-            .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
-            .await;
 
         let db_name = get_namespace_name(&req)?;
         info!(
@@ -1091,11 +1216,9 @@ where
             "measurement_fields",
         );
 
-        let db = self
-            .db_store
-            .db(&db_name, span_ctx.child_span("get namespace"), false)
-            .await
-            .context(NamespaceNotFoundSnafu { db_name: &db_name })?;
+        let (db, permit) = self
+            .authorized_db(&db_name, authz_token, Action::ReadSchema, &span_ctx)
+            .await?;
 
         let ctx = db.new_query_context(span_ctx);
         let query_completed_token = db.record_query(
@@ -1369,6 +1492,66 @@ where
     Ok(responses)
 }
 
+/// Computes the number of series matching the (optional) range and
+/// predicate, see [`Planner::series_cardinality`].
+async fn series_cardinality_impl<N>(
+    db: Arc<N>,
+    db_name: NamespaceName<'static>,
+    range: Option<TimestampRange>,
+    rpc_predicate: Option<Predicate>,
+    mode: i32,
+    ctx: &IOxSessionContext,
+) -> Result<Int64ValuesResponse>
+where
+    N: QueryNamespace + 'static,
+{
+    let rpc_predicate_string = format!("{rpc_predicate:?}");
+    let db_name = db_name.as_str();
+
+    let mode = match read_series_cardinality_request::Mode::try_from(mode) {
+        Ok(read_series_cardinality_request::Mode::Exact) => SeriesCardinalityMode::Exact,
+        Ok(read_series_cardinality_request::Mode::Estimate) => SeriesCardinalityMode::Estimate,
+        Err(_) => return InvalidSeriesCardinalityModeSnafu { mode }.fail(),
+    };
+
+    let predicate = InfluxRpcPredicateBuilder::default()
+        .set_range(range)
+        .rpc_predicate(rpc_predicate)
+        .context(ConvertingPredicateSnafu {
+            rpc_predicate_string,
+        })?
+        .build();
+
+    let plans = Planner::new(ctx)
+        .series_cardinality(db, predicate, mode)
+        .await
+        .context(PlanningSeriesCardinalitySnafu { db_name })?;
+
+    // Each plan produces a single row with the cardinality of one table
+    let mut cardinality = 0;
+    for plan in plans {
+        let physical_plan = ctx
+            .create_physical_plan(&plan)
+            .await
+            .context(ComputingSeriesCardinalitySnafu { db_name })?;
+
+        let batches = ctx
+            .collect(physical_plan)
+            .await
+            .context(ComputingSeriesCardinalitySnafu { db_name })
+            .log_if_error("Running series cardinality plan")?;
+
+        cardinality += batches
+            .iter()
+            .filter_map(|batch| sum(as_primitive_array::<Int64Type>(batch.column(0))))
+            .sum::<i64>();
+    }
+
+    Ok(Int64ValuesResponse {
+        values: vec![cardinality],
+    })
+}
+
 /// Launch async tasks that materialises the result of executing read_filter.
 async fn read_filter_impl<N>(
     db: Arc<N>,
@@ -1739,7 +1922,7 @@ impl<T, E: std::error::Error> ErrorLogger for Result<T, E> {
 pub fn make_response<S, T, E>(
     stream: S,
     token: QueryCompletedToken,
-    permit: InstrumentedAsyncOwnedSemaphorePermit,
+    permit: QueryAdmission,
 ) -> Result<Response<StreamWithPermit<QueryCompletedTokenStream<S, T, E>>>, Status>
 where
     S: Stream<Item = Result<T, E>> + Unpin + Send,
@@ -1818,7 +2001,9 @@ mod tests {
         expected_capabilities.insert("Group".into(), to_str_vec(&["First", "Last", "Min", "Max"]));
         expected_capabilities.insert(
             "WindowAggregate".into(),
-            to_str_vec(&["Count", "Sum", "Min", "Max", "Mean"]),
+            to_str_vec(&[
+                "Count", "Sum", "First", "Last", "Min", "Max", "Mean", "Offset",
+            ]),
         );
 
         assert_eq!(
@@ -2686,6 +2871,96 @@ mod tests {
         grpc_request_metric_has_count(&fixture, "ReadFilter", "ok", 1);
     }
 
+    #[tokio::test]
+    async fn test_read_series_cardinality() {
+        test_helpers::maybe_start_logging();
+        // Start a test gRPC server on a randomally allocated port
+        let mut fixture = Fixture::new().await.expect("Connecting to test server");
+
+        let db_info = org_and_bucket();
+
+        // 4 distinct tag sets with 2 fields each
+        let chunk = TestChunk::new("TheMeasurement")
+            .with_time_column()
+            .with_tag_column("tag1")
+            .with_tag_column("tag2")
+            .with_i64_field_column("field_int")
+            .with_i64_field_column("field_int2")
+            .with_five_rows_of_data();
+
+        fixture
+            .test_storage
+            .db_or_create(db_info.db_name())
+            .await
+            .add_chunk("my_partition_key", Arc::new(chunk));
+
+        let source = Some(StorageClient::read_source(&db_info, 1));
+
+        let request = ReadSeriesCardinalityRequest {
+            read_series_cardinality_source: source.clone(),
+            range: Some(make_timestamp_range(0, 10000)),
+            predicate: None,
+            mode: read_series_cardinality_request::Mode::Exact as i32,
+        };
+        let cardinality = fixture
+            .storage_client
+            .read_series_cardinality(request.clone())
+            .await
+            .unwrap();
+        assert_eq!(cardinality, 8);
+
+        let request = ReadSeriesCardinalityRequest {
+            mode: read_series_cardinality_request::Mode::Estimate as i32,
+            ..request
+        };
+        let cardinality = fixture
+            .storage_client
+            .read_series_cardinality(request.clone())
+            .await
+            .unwrap();
+        assert_eq!(cardinality, 8);
+
+        // (MT, CT) and (MT, AL) with 2 fields each
+        let request = ReadSeriesCardinalityRequest {
+            predicate: Some(make_tag_predicate("tag1", "MT", node::Comparison::Equal)),
+            mode: read_series_cardinality_request::Mode::Exact as i32,
+            ..request
+        };
+        let cardinality = fixture
+            .storage_client
+            .read_series_cardinality(request.clone())
+            .await
+            .unwrap();
+        assert_eq!(cardinality, 4);
+
+        // nothing in range
+        let request = ReadSeriesCardinalityRequest {
+            range: Some(make_timestamp_range(10000, 20000)),
+            ..request
+        };
+        let cardinality = fixture
+            .storage_client
+            .read_series_cardinality(request.clone())
+            .await
+            .unwrap();
+        assert_eq!(cardinality, 0);
+
+        grpc_request_metric_has_count(&fixture, "ReadSeriesCardinality", "ok", 4);
+
+        // unknown mode
+        let request = ReadSeriesCardinalityRequest {
+            mode: 42,
+            ..request
+        };
+        let err = fixture
+            .storage_client
+            .read_series_cardinality(request)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+        assert_contains!(err.message(), "Invalid series cardinality mode: 42");
+    }
+
     #[tokio::test]
     async fn test_read_filter_empty_string() {
         test_helpers::maybe_start_logging();
@@ -3360,6 +3635,7 @@ mod tests {
             println!("Testing with request: {t:?}");
             let service = StorageService {
                 db_store: Arc::clone(&test_storage),
+                authz: None,
             };
 
             assert_semaphore_metric(
@@ -3682,6 +3958,76 @@ mod tests {
         generated_types::Predicate { root: Some(root) }
     }
 
+    #[derive(Debug)]
+    struct MockAuthorizer;
+
+    #[tonic::async_trait]
+    impl authz::Authorizer for MockAuthorizer {
+        async fn permissions(
+            &self,
+            token: Option<Vec<u8>>,
+            perms: &[authz::Permission],
+        ) -> Result<Vec<authz::Permission>, authz::Error> {
+            match token.as_deref() {
+                Some(b"GOOD") => Ok(perms.to_vec()),
+                Some(b"BAD") => Err(authz::Error::Forbidden),
+                Some(_) => Err(authz::Error::InvalidToken),
+                None => Err(authz::Error::NoToken),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_authz() {
+        let test_storage = Arc::new(TestDatabaseStore::default());
+        let db_info = org_and_bucket();
+        test_storage
+            .db_or_create(db_info.db_name())
+            .await
+            .add_chunk(
+                "my_partition_key",
+                Arc::new(TestChunk::new("h2o").with_time_column()),
+            );
+
+        let service = StorageService {
+            db_store: Arc::clone(&test_storage),
+            authz: Some(Arc::new(MockAuthorizer)),
+        };
+
+        let request = |authorization: &'static str| {
+            let mut req = tonic::Request::new(MeasurementNamesRequest {
+                source: Some(StorageClient::read_source(&db_info, 1)),
+                range: None,
+                predicate: None,
+            });
+            if !authorization.is_empty() {
+                req.metadata_mut()
+                    .insert("authorization", authorization.parse().unwrap());
+            }
+            req
+        };
+
+        service
+            .measurement_names(request("Bearer GOOD"))
+            .await
+            .unwrap();
+
+        let err = service.measurement_names(request("")).await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unauthenticated);
+
+        let err = service
+            .measurement_names(request("Bearer BAD"))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+
+        let err = service
+            .measurement_names(request("Bearer INVALID"))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+    }
+
     /// Convert to a Vec<String> to facilitate comparison with results of client
     fn to_string_vec(v: &[&str]) -> Vec<String> {
         v.iter().map(|s| s.to_string()).collect()
@@ -66,7 +66,7 @@ impl Fixture {
                 "test server",
             ))
             .add_service(service_grpc_testing::make_server())
-            .add_service(crate::make_server(Arc::clone(&test_storage)));
+            .add_service(crate::make_server(Arc::clone(&test_storage), None));
 
         let server = async move {
             let stream = TcpListenerStream::new(socket);
@@ -18,6 +18,10 @@ use workspace_hack as _;
 
 use std::sync::Arc;
 
+use authz::{
+    audit::{AuditEvent, AuditOutcome, AuditSink},
+    extract_token,
+};
 use data_types::{
     partition_template::NamespacePartitionTemplateOverride, Namespace as CatalogNamespace,
     NamespaceName, NamespaceServiceProtectionLimitsOverride, ServiceLimitUpdate,
@@ -32,40 +36,28 @@ use tonic::{Request, Response, Status};
 pub struct NamespaceService {
     /// Catalog.
     catalog: Arc<dyn Catalog>,
+
+    /// Destination for the audit trail of administrative operations, if
+    /// auditing is enabled.
+    audit_sink: Option<Arc<dyn AuditSink>>,
 }
 
 impl NamespaceService {
     pub fn new(catalog: Arc<dyn Catalog>) -> Self {
-        Self { catalog }
+        Self {
+            catalog,
+            audit_sink: None,
+        }
     }
-}
 
-#[tonic::async_trait]
-impl namespace_service_server::NamespaceService for NamespaceService {
-    async fn get_namespaces(
-        &self,
-        _request: Request<GetNamespacesRequest>,
-    ) -> Result<Response<GetNamespacesResponse>, Status> {
-        let mut repos = self.catalog.repositories().await;
-
-        let namespaces = repos
-            .namespaces()
-            .list(SoftDeletedRows::ExcludeDeleted)
-            .await
-            .map_err(|e| {
-                warn!(error=%e, "failed to retrieve namespaces from catalog");
-                Status::not_found(e.to_string())
-            })?;
-        Ok(Response::new(GetNamespacesResponse {
-            namespaces: namespaces.iter().map(namespace_to_proto).collect(),
-        }))
+    /// Record an [`AuditEvent`] to `sink` for each namespace mutation,
+    /// including the state of the namespace before and after the change.
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = Some(sink);
+        self
     }
 
-    // create a namespace
-    async fn create_namespace(
-        &self,
-        request: Request<CreateNamespaceRequest>,
-    ) -> Result<Response<CreateNamespaceResponse>, Status> {
+    async fn create(&self, request: CreateNamespaceRequest) -> Result<CatalogNamespace, Status> {
         let mut repos = self.catalog.repositories().await;
 
         let CreateNamespaceRequest {
@@ -73,7 +65,7 @@ impl namespace_service_server::NamespaceService for NamespaceService {
             retention_period_ns,
             partition_template,
             service_protection_limits,
-        } = request.into_inner();
+        } = request;
 
         // Ensure the namespace name is consistently processed within IOx - this
         // is handled by the NamespaceName type.
@@ -119,22 +111,15 @@ impl namespace_service_server::NamespaceService for NamespaceService {
             "created namespace"
         );
 
-        Ok(Response::new(CreateNamespaceResponse {
-            namespace: Some(namespace_to_proto(&namespace)),
-        }))
+        Ok(namespace)
     }
 
-    async fn delete_namespace(
-        &self,
-        request: Request<DeleteNamespaceRequest>,
-    ) -> Result<Response<DeleteNamespaceResponse>, Status> {
-        let namespace_name = request.into_inner().name;
-
+    async fn delete(&self, namespace_name: &str) -> Result<(), Status> {
         self.catalog
             .repositories()
             .await
             .namespaces()
-            .soft_delete(&namespace_name)
+            .soft_delete(namespace_name)
             .await
             .map_err(|e| {
                 warn!(error=%e, %namespace_name, "failed to soft-delete namespace");
@@ -143,19 +128,19 @@ impl namespace_service_server::NamespaceService for NamespaceService {
 
         info!(namespace_name, "soft-deleted namespace");
 
-        Ok(Response::new(Default::default()))
+        Ok(())
     }
 
-    async fn update_namespace_retention(
+    async fn update_retention(
         &self,
-        request: Request<UpdateNamespaceRetentionRequest>,
-    ) -> Result<Response<UpdateNamespaceRetentionResponse>, Status> {
+        request: UpdateNamespaceRetentionRequest,
+    ) -> Result<CatalogNamespace, Status> {
         let mut repos = self.catalog.repositories().await;
 
         let UpdateNamespaceRetentionRequest {
             name: namespace_name,
             retention_period_ns,
-        } = request.into_inner();
+        } = request;
 
         let retention_period_ns = map_retention_period(retention_period_ns)?;
 
@@ -181,21 +166,19 @@ impl namespace_service_server::NamespaceService for NamespaceService {
             "updated namespace retention"
         );
 
-        Ok(Response::new(UpdateNamespaceRetentionResponse {
-            namespace: Some(namespace_to_proto(&namespace)),
-        }))
+        Ok(namespace)
     }
 
-    async fn update_namespace_service_protection_limit(
+    async fn update_service_protection_limit(
         &self,
-        request: Request<UpdateNamespaceServiceProtectionLimitRequest>,
-    ) -> Result<Response<UpdateNamespaceServiceProtectionLimitResponse>, Status> {
+        request: UpdateNamespaceServiceProtectionLimitRequest,
+    ) -> Result<CatalogNamespace, Status> {
         let mut repos = self.catalog.repositories().await;
 
         let UpdateNamespaceServiceProtectionLimitRequest {
             name: namespace_name,
             limit_update,
-        } = request.into_inner();
+        } = request;
 
         debug!(
             %namespace_name,
@@ -243,12 +226,248 @@ impl namespace_service_server::NamespaceService for NamespaceService {
             "updated namespace service protection limits",
         );
 
+        Ok(namespace)
+    }
+
+    async fn update_query_quota(
+        &self,
+        request: UpdateNamespaceQueryQuotaRequest,
+    ) -> Result<CatalogNamespace, Status> {
+        let UpdateNamespaceQueryQuotaRequest {
+            name: namespace_name,
+            max_concurrent_queries,
+            max_query_memory_bytes,
+        } = request;
+
+        if max_concurrent_queries.is_some_and(|v| v < 1) {
+            return Err(Status::invalid_argument(
+                "max concurrent queries must be greater than 0",
+            ));
+        }
+        if max_query_memory_bytes.is_some_and(|v| v < 1) {
+            return Err(Status::invalid_argument(
+                "max query memory bytes must be greater than 0",
+            ));
+        }
+
+        debug!(
+            %namespace_name,
+            ?max_concurrent_queries,
+            ?max_query_memory_bytes,
+            "updating namespace query quota",
+        );
+
+        let namespace = self
+            .catalog
+            .repositories()
+            .await
+            .namespaces()
+            .update_query_quota(
+                &namespace_name,
+                max_concurrent_queries,
+                max_query_memory_bytes,
+            )
+            .await
+            .map_err(|e| {
+                warn!(error=%e, %namespace_name, "failed to update namespace query quota");
+                status_from_catalog_namespace_error(e)
+            })?;
+
+        info!(
+            %namespace_name,
+            namespace_id = %namespace.id,
+            ?max_concurrent_queries,
+            ?max_query_memory_bytes,
+            "updated namespace query quota",
+        );
+
+        Ok(namespace)
+    }
+
+    /// Read the current state of `namespace_name` to record in the audit
+    /// trail, if auditing is enabled.
+    async fn audit_state(&self, namespace_name: &str) -> Option<serde_json::Value> {
+        self.audit_sink.as_ref()?;
+
+        self.catalog
+            .repositories()
+            .await
+            .namespaces()
+            .get_by_name(namespace_name, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .ok()
+            .flatten()
+            .map(|ns| namespace_to_json(&ns))
+    }
+
+    /// Record the result of `operation` in the audit trail, if auditing is
+    /// enabled.
+    async fn audit(
+        &self,
+        operation: &str,
+        token: Option<Vec<u8>>,
+        namespace_name: &str,
+        before: Option<serde_json::Value>,
+        res: Result<Option<&CatalogNamespace>, &Status>,
+    ) {
+        let Some(sink) = &self.audit_sink else {
+            return;
+        };
+
+        let event = match res {
+            Ok(after) => AuditEvent::new(operation, AuditOutcome::Succeeded)
+                .with_change(before, after.map(namespace_to_json)),
+            Err(_) => AuditEvent::new(operation, AuditOutcome::Failed).with_change(before, None),
+        };
+        sink.record(
+            event
+                .with_token(token.as_deref())
+                .with_namespace(namespace_name),
+        )
+        .await;
+    }
+}
+
+#[tonic::async_trait]
+impl namespace_service_server::NamespaceService for NamespaceService {
+    async fn get_namespaces(
+        &self,
+        _request: Request<GetNamespacesRequest>,
+    ) -> Result<Response<GetNamespacesResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let namespaces = repos
+            .namespaces()
+            .list(SoftDeletedRows::ExcludeDeleted)
+            .await
+            .map_err(|e| {
+                warn!(error=%e, "failed to retrieve namespaces from catalog");
+                Status::not_found(e.to_string())
+            })?;
+        Ok(Response::new(GetNamespacesResponse {
+            namespaces: namespaces.iter().map(namespace_to_proto).collect(),
+        }))
+    }
+
+    // create a namespace
+    async fn create_namespace(
+        &self,
+        request: Request<CreateNamespaceRequest>,
+    ) -> Result<Response<CreateNamespaceResponse>, Status> {
+        let token = extract_token(request.metadata().get("authorization"));
+        let request = request.into_inner();
+        let namespace_name = request.name.clone();
+
+        let res = self.create(request).await;
+        self.audit(
+            "create_namespace",
+            token,
+            &namespace_name,
+            None,
+            res.as_ref().map(Some),
+        )
+        .await;
+
+        Ok(Response::new(CreateNamespaceResponse {
+            namespace: Some(namespace_to_proto(&res?)),
+        }))
+    }
+
+    async fn delete_namespace(
+        &self,
+        request: Request<DeleteNamespaceRequest>,
+    ) -> Result<Response<DeleteNamespaceResponse>, Status> {
+        let token = extract_token(request.metadata().get("authorization"));
+        let namespace_name = request.into_inner().name;
+
+        let before = self.audit_state(&namespace_name).await;
+        let res = self.delete(&namespace_name).await;
+        self.audit(
+            "delete_namespace",
+            token,
+            &namespace_name,
+            before,
+            res.as_ref().map(|_| None),
+        )
+        .await;
+        res?;
+
+        Ok(Response::new(Default::default()))
+    }
+
+    async fn update_namespace_retention(
+        &self,
+        request: Request<UpdateNamespaceRetentionRequest>,
+    ) -> Result<Response<UpdateNamespaceRetentionResponse>, Status> {
+        let token = extract_token(request.metadata().get("authorization"));
+        let request = request.into_inner();
+        let namespace_name = request.name.clone();
+
+        let before = self.audit_state(&namespace_name).await;
+        let res = self.update_retention(request).await;
+        self.audit(
+            "update_namespace_retention",
+            token,
+            &namespace_name,
+            before,
+            res.as_ref().map(Some),
+        )
+        .await;
+
+        Ok(Response::new(UpdateNamespaceRetentionResponse {
+            namespace: Some(namespace_to_proto(&res?)),
+        }))
+    }
+
+    async fn update_namespace_service_protection_limit(
+        &self,
+        request: Request<UpdateNamespaceServiceProtectionLimitRequest>,
+    ) -> Result<Response<UpdateNamespaceServiceProtectionLimitResponse>, Status> {
+        let token = extract_token(request.metadata().get("authorization"));
+        let request = request.into_inner();
+        let namespace_name = request.name.clone();
+
+        let before = self.audit_state(&namespace_name).await;
+        let res = self.update_service_protection_limit(request).await;
+        self.audit(
+            "update_namespace_service_protection_limit",
+            token,
+            &namespace_name,
+            before,
+            res.as_ref().map(Some),
+        )
+        .await;
+
         Ok(Response::new(
             UpdateNamespaceServiceProtectionLimitResponse {
-                namespace: Some(namespace_to_proto(&namespace)),
+                namespace: Some(namespace_to_proto(&res?)),
             },
         ))
     }
+
+    async fn update_namespace_query_quota(
+        &self,
+        request: Request<UpdateNamespaceQueryQuotaRequest>,
+    ) -> Result<Response<UpdateNamespaceQueryQuotaResponse>, Status> {
+        let token = extract_token(request.metadata().get("authorization"));
+        let request = request.into_inner();
+        let namespace_name = request.name.clone();
+
+        let before = self.audit_state(&namespace_name).await;
+        let res = self.update_query_quota(request).await;
+        self.audit(
+            "update_namespace_query_quota",
+            token,
+            &namespace_name,
+            before,
+            res.as_ref().map(Some),
+        )
+        .await;
+
+        Ok(Response::new(UpdateNamespaceQueryQuotaResponse {
+            namespace: Some(namespace_to_proto(&res?)),
+        }))
+    }
 }
 
 /// Convert the namespace record from the catalog into its protobuf representation.
@@ -260,9 +479,16 @@ pub fn namespace_to_proto(namespace: &CatalogNamespace) -> Namespace {
         max_tables: namespace.max_tables.get_i32(),
         max_columns_per_table: namespace.max_columns_per_table.get_i32(),
         partition_template: namespace.partition_template.as_proto().cloned(),
+        max_concurrent_queries: namespace.max_concurrent_queries,
+        max_query_memory_bytes: namespace.max_query_memory_bytes,
     }
 }
 
+/// Render the namespace record as the JSON recorded in the audit trail.
+fn namespace_to_json(namespace: &CatalogNamespace) -> serde_json::Value {
+    serde_json::to_value(namespace_to_proto(namespace)).expect("namespace serialises to JSON")
+}
+
 /// Map a user-submitted retention period value to the correct internal
 /// encoding.
 ///
@@ -294,6 +520,7 @@ mod tests {
     use std::time::Duration;
 
     use assert_matches::assert_matches;
+    use authz::audit::mock::MockAuditSink;
     use data_types::partition_template::PARTITION_BY_DAY_PROTO;
     use generated_types::influxdata::iox::{
         namespace::v1::{
@@ -469,6 +696,48 @@ mod tests {
         assert_eq!(updated_ns.max_tables, want_max_tables);
         assert_eq!(updated_ns.max_columns_per_table, want_max_columns_per_table);
 
+        // Set, and then remove, the query quota
+        assert_eq!(created_ns.max_concurrent_queries, None);
+        assert_eq!(created_ns.max_query_memory_bytes, None);
+        let updated_ns = handler
+            .update_namespace_query_quota(Request::new(UpdateNamespaceQueryQuotaRequest {
+                name: NS_NAME.to_string(),
+                max_concurrent_queries: Some(4),
+                max_query_memory_bytes: Some(1024),
+            }))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.id, created_ns.id);
+        assert_eq!(updated_ns.max_concurrent_queries, Some(4));
+        assert_eq!(updated_ns.max_query_memory_bytes, Some(1024));
+
+        let updated_ns = handler
+            .update_namespace_query_quota(Request::new(UpdateNamespaceQueryQuotaRequest {
+                name: NS_NAME.to_string(),
+                max_concurrent_queries: None,
+                max_query_memory_bytes: None,
+            }))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.max_concurrent_queries, None);
+        assert_eq!(updated_ns.max_query_memory_bytes, None);
+
+        let err = handler
+            .update_namespace_query_quota(Request::new(UpdateNamespaceQueryQuotaRequest {
+                name: NS_NAME.to_string(),
+                max_concurrent_queries: Some(0),
+                max_query_memory_bytes: None,
+            }))
+            .await
+            .expect_err("zero concurrency limit must be rejected");
+        assert_eq!(err.code(), Code::InvalidArgument);
+
         // Deleting the namespace should cause it to disappear
         handler
             .delete_namespace(Request::new(DeleteNamespaceRequest {
@@ -489,6 +758,69 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_audit() {
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+        let sink = Arc::new(MockAuditSink::default());
+        let handler = NamespaceService::new(catalog).with_audit_sink(Arc::clone(&sink) as _);
+
+        let mut req = Request::new(CreateNamespaceRequest {
+            name: NS_NAME.to_string(),
+            retention_period_ns: Some(RETENTION),
+            partition_template: None,
+            service_protection_limits: None,
+        });
+        req.metadata_mut()
+            .insert("authorization", "Token s3cr3t".parse().unwrap());
+        handler.create_namespace(req).await.expect("must create");
+
+        handler
+            .update_namespace_retention(Request::new(UpdateNamespaceRetentionRequest {
+                name: NS_NAME.to_string(),
+                retention_period_ns: None,
+            }))
+            .await
+            .expect("must update");
+
+        handler
+            .delete_namespace(Request::new(DeleteNamespaceRequest {
+                name: "platanos".to_string(),
+            }))
+            .await
+            .expect_err("must fail to delete unknown namespace");
+
+        let events = sink.events();
+        assert_matches!(events.as_slice(), [create, update, delete] => {
+            assert_eq!(create.operation, "create_namespace");
+            assert_eq!(create.outcome, AuditOutcome::Succeeded);
+            assert_eq!(create.namespace.as_deref(), Some(NS_NAME));
+            assert_eq!(
+                create.subject,
+                Some(authz::audit::token_subject(b"s3cr3t"))
+            );
+            assert_eq!(create.before, None);
+            assert_matches!(&create.after, Some(after) => {
+                assert_eq!(after["name"], NS_NAME);
+            });
+
+            assert_eq!(update.operation, "update_namespace_retention");
+            assert_eq!(update.outcome, AuditOutcome::Succeeded);
+            assert_eq!(update.subject, None);
+            assert_matches!(&update.before, Some(before) => {
+                assert_eq!(before["retentionPeriodNs"], RETENTION.to_string());
+            });
+            assert_matches!(&update.after, Some(after) => {
+                assert_eq!(after.get("retentionPeriodNs"), None);
+            });
+
+            assert_eq!(delete.operation, "delete_namespace");
+            assert_eq!(delete.outcome, AuditOutcome::Failed);
+            assert_eq!(delete.namespace.as_deref(), Some("platanos"));
+            assert_eq!(delete.before, None);
+        });
+    }
+
     #[tokio::test]
     async fn creating_same_namespace_twice_fails() {
         let catalog: Arc<dyn Catalog> =
@@ -24,7 +24,13 @@ use workspace_hack as _;
 
 use std::sync::Arc;
 
-use data_types::{partition_template::TablePartitionTemplateOverride, NamespaceName};
+use authz::{
+    audit::{AuditEvent, AuditOutcome, AuditSink},
+    extract_token,
+};
+use data_types::{
+    partition_template::TablePartitionTemplateOverride, NamespaceName, Table as CatalogTable,
+};
 use generated_types::influxdata::iox::table::v1::*;
 use iox_catalog::interface::{Catalog, SoftDeletedRows};
 use observability_deps::tracing::{debug, error, info, warn};
@@ -35,60 +41,35 @@ use tonic::{Request, Response, Status};
 pub struct TableService {
     /// Catalog.
     catalog: Arc<dyn Catalog>,
+
+    /// Destination for the audit trail of administrative operations, if
+    /// auditing is enabled.
+    audit_sink: Option<Arc<dyn AuditSink>>,
 }
 
 impl TableService {
     /// Create a new `TableService` instance
     pub fn new(catalog: Arc<dyn Catalog>) -> Self {
-        Self { catalog }
+        Self {
+            catalog,
+            audit_sink: None,
+        }
     }
-}
-
-#[tonic::async_trait]
-impl table_service_server::TableService for TableService {
-    // List tables for a namespace
-    async fn get_tables(
-        &self,
-        request: Request<GetTablesRequest>,
-    ) -> Result<Response<GetTablesResponse>, Status> {
-        let mut repos = self.catalog.repositories().await;
 
-        let namespace_name = NamespaceName::try_from(request.into_inner().namespace_name)
-            .map_err(|e| Status::invalid_argument(e.to_string()))?;
-
-        debug!(%namespace_name, "listing tables for namespace");
-
-        let namespace = repos
-            .namespaces()
-            .get_by_name(&namespace_name, SoftDeletedRows::ExcludeDeleted)
-            .await
-            .map_err(|e| Status::internal(e.to_string()))?
-            .ok_or_else(|| {
-                Status::not_found(format!(
-                    "Could not find a namespace with name {namespace_name}"
-                ))
-            })?;
-
-        let tables = repos.tables().list_by_namespace_id(namespace.id).await.map_err(|e| {
-            error!(error=%e, namespace_id=%namespace.id, %namespace_name, "failed to list tables for namespace");
-            Status::internal(e.to_string())
-        })?.into_iter().map(Table::from).collect::<Vec<_>>();
-
-        Ok(Response::new(GetTablesResponse { tables }))
+    /// Record an [`AuditEvent`] to `sink` for each table creation.
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = Some(sink);
+        self
     }
 
-    // create a table
-    async fn create_table(
-        &self,
-        request: Request<CreateTableRequest>,
-    ) -> Result<Response<CreateTableResponse>, Status> {
+    async fn create(&self, request: CreateTableRequest) -> Result<CatalogTable, Status> {
         let mut repos = self.catalog.repositories().await;
 
         let CreateTableRequest {
             name,
             namespace,
             partition_template,
-        } = request.into_inner();
+        } = request;
 
         let namespace_name = NamespaceName::try_from(namespace)
             .map_err(|e| Status::invalid_argument(e.to_string()))?;
@@ -139,14 +120,82 @@ impl table_service_server::TableService for TableService {
             "created table"
         );
 
+        Ok(table)
+    }
+}
+
+#[tonic::async_trait]
+impl table_service_server::TableService for TableService {
+    // List tables for a namespace
+    async fn get_tables(
+        &self,
+        request: Request<GetTablesRequest>,
+    ) -> Result<Response<GetTablesResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let namespace_name = NamespaceName::try_from(request.into_inner().namespace_name)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        debug!(%namespace_name, "listing tables for namespace");
+
+        let namespace = repos
+            .namespaces()
+            .get_by_name(&namespace_name, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| {
+                Status::not_found(format!(
+                    "Could not find a namespace with name {namespace_name}"
+                ))
+            })?;
+
+        let tables = repos.tables().list_by_namespace_id(namespace.id).await.map_err(|e| {
+            error!(error=%e, namespace_id=%namespace.id, %namespace_name, "failed to list tables for namespace");
+            Status::internal(e.to_string())
+        })?.into_iter().map(Table::from).collect::<Vec<_>>();
+
+        Ok(Response::new(GetTablesResponse { tables }))
+    }
+
+    // create a table
+    async fn create_table(
+        &self,
+        request: Request<CreateTableRequest>,
+    ) -> Result<Response<CreateTableResponse>, Status> {
+        let token = extract_token(request.metadata().get("authorization"));
+        let request = request.into_inner();
+        let (namespace_name, table_name) = (request.namespace.clone(), request.name.clone());
+
+        let res = self.create(request).await;
+        if let Some(sink) = &self.audit_sink {
+            let event = match &res {
+                Ok(table) => AuditEvent::new("create_table", AuditOutcome::Succeeded).with_change(
+                    None,
+                    Some(
+                        serde_json::to_value(Table::from(table.clone()))
+                            .expect("table serialises to JSON"),
+                    ),
+                ),
+                Err(_) => AuditEvent::new("create_table", AuditOutcome::Failed),
+            };
+            sink.record(
+                event
+                    .with_token(token.as_deref())
+                    .with_namespace(namespace_name)
+                    .with_table(table_name),
+            )
+            .await;
+        }
+
         Ok(Response::new(CreateTableResponse {
-            table: Some(table.into()),
+            table: Some(res?.into()),
         }))
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use authz::audit::mock::MockAuditSink;
     use data_types::{partition_template::NamespacePartitionTemplateOverride, TableId};
     use generated_types::influxdata::iox::{
         partition_template::v1::{template_part, PartitionTemplate, TemplatePart},
@@ -466,4 +515,51 @@ mod tests {
         let all_tables = catalog.repositories().await.tables().list().await.unwrap();
         assert!(all_tables.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_audit() {
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+        let sink = Arc::new(MockAuditSink::default());
+        let handler =
+            TableService::new(Arc::clone(&catalog)).with_audit_sink(Arc::clone(&sink) as _);
+
+        let namespace = arbitrary_namespace(&mut *catalog.repositories().await, "grapes").await;
+        let request = CreateTableRequest {
+            name: "varietals".to_string(),
+            namespace: namespace.name.clone(),
+            partition_template: None,
+        };
+
+        let mut req = Request::new(request.clone());
+        req.metadata_mut()
+            .insert("authorization", "Token s3cr3t".parse().unwrap());
+        handler.create_table(req).await.expect("must create");
+
+        // Creating the same table again fails.
+        handler
+            .create_table(Request::new(request))
+            .await
+            .expect_err("must not create duplicate table");
+
+        let events = sink.events();
+        let [created, failed] = events.as_slice() else {
+            panic!("unexpected events: {events:?}");
+        };
+
+        assert_eq!(created.operation, "create_table");
+        assert_eq!(created.outcome, AuditOutcome::Succeeded);
+        assert_eq!(created.namespace.as_deref(), Some("grapes"));
+        assert_eq!(created.table.as_deref(), Some("varietals"));
+        assert_eq!(
+            created.subject,
+            Some(authz::audit::token_subject(b"s3cr3t"))
+        );
+        assert_eq!(created.before, None);
+        assert_eq!(created.after.as_ref().unwrap()["name"], "varietals");
+
+        assert_eq!(failed.outcome, AuditOutcome::Failed);
+        assert_eq!(failed.subject, None);
+        assert_eq!(failed.after, None);
+    }
 }
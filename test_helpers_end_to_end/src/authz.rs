@@ -84,6 +84,7 @@ impl Authorizer {
                         resource_type: ResourceType::Database.into(),
                         resource_id: Some(namespace_name.to_string()),
                         action: a.into(),
+                        table_name: None,
                     },
                 )),
             })
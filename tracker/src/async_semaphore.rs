@@ -1,5 +1,14 @@
 //! Tooling to track/instrument [`tokio::sync::Semaphore`]s.
-use std::{future::Future, marker::PhantomData, sync::Arc, task::Poll, time::Instant};
+use std::{
+    future::Future,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::Poll,
+    time::Instant,
+};
 
 use futures::{future::BoxFuture, FutureExt};
 use metric::{Attributes, DurationHistogram, MakeMetricObserver, U64Counter, U64Gauge};
@@ -108,7 +117,7 @@ impl AsyncSemaphoreMetrics {
 
         InstrumentedAsyncSemaphore {
             inner: Arc::new(Semaphore::new(permits)),
-            permits,
+            permits: AtomicUsize::new(permits),
             metrics: Arc::clone(self),
         }
     }
@@ -130,7 +139,7 @@ pub struct InstrumentedAsyncSemaphore {
     inner: Arc<Semaphore>,
 
     /// Number of total permits (acquired and available).
-    permits: usize,
+    permits: AtomicUsize,
 
     /// Metrics.
     metrics: Arc<AsyncSemaphoreMetrics>,
@@ -198,9 +207,41 @@ impl InstrumentedAsyncSemaphore {
         }
     }
 
+    /// Add `n` permits to the semaphore.
+    ///
+    /// See [`tokio::sync::Semaphore::add_permits`] for details.
+    pub fn add_permits(&self, n: usize) {
+        self.inner.add_permits(n);
+        self.permits.fetch_add(n, Ordering::Relaxed);
+        self.metrics.permits_total.inc(n as u64);
+    }
+
+    /// Remove `n` permits from the semaphore.
+    ///
+    /// The returned future waits until `n` permits are available, e.g. because their holders
+    /// released them, and then removes them. The semaphore is fair, so once the future was polled,
+    /// subsequent acquires queue up behind it and are only granted once the semaphore shrank.
+    pub fn forget_permits(self: &Arc<Self>, n: u32) -> impl Future<Output = ()> + Send + 'static {
+        let this = Arc::clone(self);
+        async move {
+            Arc::clone(&this.inner)
+                .acquire_many_owned(n)
+                .await
+                .expect("Semaphore should not be closed by anyone")
+                .forget();
+            this.permits.fetch_sub(n as usize, Ordering::Relaxed);
+            this.metrics.permits_total.dec(n as u64);
+        }
+    }
+
     /// return the total number of permits (available + already acquired).
     pub fn total_permits(self: &Arc<Self>) -> usize {
-        self.permits
+        self.permits.load(Ordering::Relaxed)
+    }
+
+    /// return the number of permits that can currently be acquired without waiting.
+    pub fn available_permits(self: &Arc<Self>) -> usize {
+        self.inner.available_permits()
     }
 
     /// return the number of pending permits
@@ -226,7 +267,9 @@ impl InstrumentedAsyncSemaphore {
 
 impl Drop for InstrumentedAsyncSemaphore {
     fn drop(&mut self) {
-        self.metrics.permits_total.dec(self.permits as u64);
+        self.metrics
+            .permits_total
+            .dec(self.permits.load(Ordering::Relaxed) as u64);
     }
 }
 
@@ -481,6 +524,39 @@ mod tests {
         assert_eq!(metrics.permits_total.fetch(), 0);
     }
 
+    #[tokio::test]
+    async fn test_resize() {
+        let metrics = Arc::new(AsyncSemaphoreMetrics::new_unregistered());
+        let semaphore = Arc::new(metrics.new_semaphore(2));
+
+        semaphore.add_permits(2);
+        assert_eq!(semaphore.total_permits(), 4);
+        assert_eq!(semaphore.available_permits(), 4);
+        assert_eq!(metrics.permits_total.fetch(), 4);
+
+        let p1 = semaphore.acquire_many(3, None).await.unwrap();
+
+        // shrinking waits for the permits to be released
+        let mut shrink = Box::pin(semaphore.forget_permits(2));
+        assert!(futures::poll!(shrink.as_mut()).is_pending());
+
+        // later acquires queue up behind the shrinking
+        let mut acquire = Box::pin(semaphore.acquire(None));
+        assert!(futures::poll!(acquire.as_mut()).is_pending());
+
+        drop(p1);
+        shrink.await;
+        assert_eq!(semaphore.total_permits(), 2);
+        assert_eq!(metrics.permits_total.fetch(), 2);
+
+        let p2 = acquire.await.unwrap();
+        assert_eq!(semaphore.available_permits(), 1);
+
+        drop(p2);
+        drop(semaphore);
+        assert_eq!(metrics.permits_total.fetch(), 0);
+    }
+
     #[tokio::test]
     async fn test_permits_acquired_and_holders_acquired() {
         let metrics = Arc::new(AsyncSemaphoreMetrics::new_unregistered());
@@ -34,19 +34,23 @@ use generated_types::{
 use hashbrown::HashMap;
 use mutable_batch::MutableBatch;
 use mutable_batch_pb::decode::decode_database_batch;
-use observability_deps::tracing::info;
+use observability_deps::tracing::{info, warn};
 use parking_lot::Mutex;
 use snafu::prelude::*;
 use tokio::{sync::watch, task::JoinHandle};
 use writer_thread::WriterIoThreadHandle;
 
-use crate::blocking::{
-    ClosedSegmentFileReader as RawClosedSegmentFileReader, OpenSegmentFileWriter,
+use crate::{
+    blocking::{ClosedSegmentFileReader as RawClosedSegmentFileReader, OpenSegmentFileWriter},
+    repair::REPAIRED_FILE_EXTENSION,
 };
 
 pub mod blocking;
+mod repair;
 mod writer_thread;
 
+pub use repair::{CorruptRegion, LostSequenceNumbers, RepairReport, SegmentRepair};
+
 const WAL_FLUSH_INTERVAL: Duration = Duration::from_millis(10);
 
 // TODO: Should have more variants / error types to avoid reusing these
@@ -141,6 +145,11 @@ pub enum Error {
     UnableToCreateSegmentFile {
         source: blocking::WriterError,
     },
+
+    UnableToRepairSegment {
+        source: io::Error,
+        path: PathBuf,
+    },
 }
 
 /// Errors that occur when decoding internal types from a WAL file.
@@ -204,6 +213,9 @@ type FileTypeIdentifier = [u8; 8];
 const FILE_TYPE_IDENTIFIER: &FileTypeIdentifier = b"INFLUXV3";
 /// File extension for segment files.
 const SEGMENT_FILE_EXTENSION: &str = "dat";
+/// The directory within the WAL directory that repaired segment files are
+/// moved to.
+const QUARANTINE_DIRECTORY: &str = "quarantine";
 
 /// The main type representing one WAL for one ingester instance.
 ///
@@ -268,6 +280,14 @@ impl Wal {
                 .context(UnableToReadFileMetadataSnafu)?;
             if metadata.is_file() {
                 let child_path = child.path();
+                if child_path.extension() == Some(REPAIRED_FILE_EXTENSION.as_ref()) {
+                    // Left behind by an interrupted repair, before it replaced
+                    // the original segment.
+                    warn!(path=?child_path, "removing incomplete repaired wal segment");
+                    std::fs::remove_file(&child_path)
+                        .context(UnableToRepairSegmentSnafu { path: &child_path })?;
+                    continue;
+                }
                 let filename = child_path
                     .file_stem()
                     .expect("WAL files created by IOx should have a file stem");
@@ -413,6 +433,25 @@ impl Wal {
         }
     }
 
+    /// Replaces the closed segment `id` with one containing only its readable
+    /// entries, reporting the corrupt regions dropped.
+    ///
+    /// The original segment file is moved to the "quarantine" directory
+    /// within the WAL directory, which is otherwise ignored by the WAL.
+    pub fn repair_segment(&self, id: SegmentId) -> Result<RepairReport> {
+        let path = build_segment_path(&self.root, id);
+        let report = SegmentRepair::new(&path)?.apply(&self.root.join(QUARANTINE_DIRECTORY))?;
+
+        let size = std::fs::metadata(&path)
+            .context(UnableToReadFileMetadataSnafu)?
+            .len();
+        if let Some(segment) = self.segments.lock().closed_segments.get_mut(&id) {
+            segment.size = size;
+        }
+
+        Ok(report)
+    }
+
     /// Deletes the specified segment from disk.
     pub async fn delete(&self, id: SegmentId) -> Result<()> {
         let closed = self
@@ -809,6 +848,28 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn open_removes_incomplete_repaired_segment() {
+        let dir = test_helpers::tmp_dir().unwrap();
+
+        let wal = Wal::new(dir.path()).await.unwrap();
+        let (closed, _) = wal.rotate().unwrap();
+        drop(wal);
+
+        // Simulate a repair interrupted before it replaced the segment.
+        let segment_path = build_segment_path(dir.path(), closed.id());
+        let mut repaired_path = segment_path.clone().into_os_string();
+        repaired_path.push(".");
+        repaired_path.push(REPAIRED_FILE_EXTENSION);
+        std::fs::write(&repaired_path, b"partial").unwrap();
+
+        let wal = Wal::new(dir.path()).await.unwrap();
+        let closed_segment_ids: Vec<_> = wal.closed_segments().iter().map(|c| c.id()).collect();
+        assert!(closed_segment_ids.contains(&closed.id()));
+        assert!(segment_path.exists());
+        assert!(!std::path::Path::new(&repaired_path).exists());
+    }
+
     #[tokio::test]
     async fn decode_write_op_entries() {
         let dir = test_helpers::tmp_dir().unwrap();
@@ -0,0 +1,541 @@
+//! Salvaging the readable entries of a corrupt or truncated segment file.
+//!
+//! The [`ClosedSegmentFileReader`] stops at the first entry it cannot read,
+//! as a bad length prefix means the start of the next entry is unknown. A
+//! [`SegmentRepair`] instead scans past a corrupt region for the next entry
+//! with a valid checksum, recovering every readable entry in the file.
+//!
+//! [`ClosedSegmentFileReader`]: crate::ClosedSegmentFileReader
+
+use std::{
+    fmt::Display,
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use generated_types::influxdata::iox::wal::v1::WalOpBatch as ProtoWalOpBatch;
+use observability_deps::tracing::{info, warn};
+use prost::Message;
+use snafu::prelude::*;
+use snap::read::FrameDecoder;
+
+use crate::{
+    Result, SegmentFileIdentifierMismatchSnafu, SegmentId, SegmentIdBytes, SequencedWalOp,
+    UnableToRepairSegmentSnafu, FILE_TYPE_IDENTIFIER,
+};
+
+/// The length of the file type identifier and segment ID at the start of a
+/// segment file.
+const FILE_HEADER_LEN: usize = FILE_TYPE_IDENTIFIER.len() + std::mem::size_of::<SegmentIdBytes>();
+
+/// The length of the checksum and length prefixing each entry.
+const ENTRY_HEADER_LEN: usize = 2 * std::mem::size_of::<u32>();
+
+/// The extension appended to the segment file name of a repaired segment
+/// while it is being written.
+pub(crate) const REPAIRED_FILE_EXTENSION: &str = "repaired";
+
+/// The sequence numbers lost to a corrupt region: those greater than `after`
+/// and less than `before`.
+///
+/// Sequence numbers are assigned in the order ops are written to the WAL, so
+/// the ops lost are those between the last readable op before the corrupt
+/// region and the first readable op after it. A bound is `None` if there is
+/// no readable op on that side of the region in this segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LostSequenceNumbers {
+    /// The highest sequence number read before the corrupt region.
+    pub after: Option<u64>,
+    /// The lowest sequence number read after the corrupt region.
+    pub before: Option<u64>,
+}
+
+impl LostSequenceNumbers {
+    /// Returns true if the corrupt region provably contained no ops.
+    pub fn is_empty(&self) -> bool {
+        matches!((self.after, self.before), (Some(after), Some(before)) if after + 1 >= before)
+    }
+}
+
+impl Display for LostSequenceNumbers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.after, self.before) {
+            _ if self.is_empty() => write!(f, "none"),
+            (Some(after), Some(before)) => write!(f, "{}..={}", after + 1, before - 1),
+            (Some(after), None) => write!(f, "{}..", after + 1),
+            (None, Some(before)) => write!(f, "..={}", before.saturating_sub(1)),
+            (None, None) => write!(f, "unknown"),
+        }
+    }
+}
+
+/// A region of a segment file containing no readable entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorruptRegion {
+    /// The byte offset of the region in the segment file.
+    pub offset: u64,
+    /// The length of the region in bytes.
+    pub len: u64,
+    /// The sequence numbers of the ops lost with the region.
+    pub lost_sequence_numbers: LostSequenceNumbers,
+}
+
+/// The outcome of salvaging a segment file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepairReport {
+    /// The segment the file belongs to.
+    pub segment_id: SegmentId,
+    /// The number of readable entries.
+    pub salvaged_entries: usize,
+    /// The regions of the file containing no readable entries, in file order.
+    pub corrupt_regions: Vec<CorruptRegion>,
+    /// Where the original file was moved to, if it was repaired.
+    pub quarantined_path: Option<PathBuf>,
+}
+
+impl RepairReport {
+    /// Returns true if the segment file had no corrupt regions.
+    pub fn is_intact(&self) -> bool {
+        self.corrupt_regions.is_empty()
+    }
+}
+
+/// A readable entry of a segment file.
+#[derive(Debug)]
+struct SalvagedEntry {
+    /// The bytes of the entry, including its header, in the segment file.
+    range: Range<usize>,
+    /// The lowest and highest sequence numbers of the ops in the entry.
+    sequence_numbers: Option<(u64, u64)>,
+}
+
+/// The readable entries of a segment file, found by scanning past any corrupt
+/// regions.
+#[derive(Debug)]
+pub struct SegmentRepair {
+    path: PathBuf,
+    data: Vec<u8>,
+    entries: Vec<SalvagedEntry>,
+    report: RepairReport,
+}
+
+impl SegmentRepair {
+    /// Reads the segment file at `path`, finding its readable entries.
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let data = fs::read(&path).context(UnableToRepairSegmentSnafu { path: &path })?;
+
+        ensure!(
+            data.len() >= FILE_HEADER_LEN && data.starts_with(FILE_TYPE_IDENTIFIER),
+            SegmentFileIdentifierMismatchSnafu,
+        );
+        let segment_id = SegmentId::from_bytes(
+            data[FILE_TYPE_IDENTIFIER.len()..FILE_HEADER_LEN]
+                .try_into()
+                .expect("header slice is the size of a segment id"),
+        );
+
+        let (entries, corrupt) = scan_entries(&data);
+        let corrupt_regions = corrupt
+            .into_iter()
+            .map(|range| {
+                let after = entries
+                    .iter()
+                    .take_while(|e| e.range.start < range.start)
+                    .filter_map(|e| e.sequence_numbers)
+                    .map(|(_, max)| max)
+                    .max();
+                let before = entries
+                    .iter()
+                    .skip_while(|e| e.range.start < range.start)
+                    .filter_map(|e| e.sequence_numbers)
+                    .map(|(min, _)| min)
+                    .min();
+                CorruptRegion {
+                    offset: range.start as u64,
+                    len: range.len() as u64,
+                    lost_sequence_numbers: LostSequenceNumbers { after, before },
+                }
+            })
+            .collect();
+
+        let report = RepairReport {
+            segment_id,
+            salvaged_entries: entries.len(),
+            corrupt_regions,
+            quarantined_path: None,
+        };
+
+        Ok(Self {
+            path,
+            data,
+            entries,
+            report,
+        })
+    }
+
+    /// The outcome of the scan, before any changes are made.
+    pub fn report(&self) -> &RepairReport {
+        &self.report
+    }
+
+    /// Replaces the segment file with one containing only its readable
+    /// entries, moving the original into `quarantine_dir`.
+    ///
+    /// The segment file is left untouched if it has no corrupt regions.
+    pub fn apply(self, quarantine_dir: &Path) -> Result<RepairReport> {
+        let Self {
+            path,
+            data,
+            entries,
+            mut report,
+        } = self;
+
+        if report.is_intact() {
+            info!(
+                ?path,
+                "wal segment has no corrupt regions, nothing to repair"
+            );
+            return Ok(report);
+        }
+
+        let file_name = path
+            .file_name()
+            .expect("segment file paths have a file name")
+            .to_string_lossy()
+            .to_string();
+        let segment_dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let repaired_path = segment_dir.join(format!("{file_name}.{REPAIRED_FILE_EXTENSION}"));
+        let context = || UnableToRepairSegmentSnafu { path: &path };
+
+        // Write the repaired segment alongside the original so it can be
+        // atomically renamed over it. The WAL discards any such file left
+        // behind by an interrupted repair when it is opened.
+        let quarantined_path = write_repaired(&data, &entries, &repaired_path)
+            .and_then(|_| quarantine(&path, quarantine_dir, &file_name))
+            .and_then(|quarantined_path| {
+                fs::rename(&repaired_path, &path)?;
+                sync_dir(segment_dir)?;
+                Ok(quarantined_path)
+            })
+            .map_err(|e| {
+                // Best effort - the file may not have been created.
+                let _ = fs::remove_file(&repaired_path);
+                e
+            })
+            .context(context())?;
+
+        for region in &report.corrupt_regions {
+            warn!(
+                ?path,
+                offset = region.offset,
+                len = region.len,
+                lost_sequence_numbers = %region.lost_sequence_numbers,
+                "dropped corrupt region of wal segment"
+            );
+        }
+        info!(
+            ?path,
+            ?quarantined_path,
+            salvaged_entries = report.salvaged_entries,
+            "repaired wal segment"
+        );
+
+        report.quarantined_path = Some(quarantined_path);
+        Ok(report)
+    }
+}
+
+/// Writes the segment file header of `data` and its readable `entries` to a
+/// new file at `path`, syncing it to disk.
+fn write_repaired(data: &[u8], entries: &[SalvagedEntry], path: &Path) -> io::Result<()> {
+    let mut repaired = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    repaired.write_all(&data[..FILE_HEADER_LEN])?;
+    for entry in entries {
+        repaired.write_all(&data[entry.range.clone()])?;
+    }
+    repaired.sync_all()
+}
+
+/// Preserves the segment file at `path` in `quarantine_dir`, returning the
+/// path of the quarantined copy.
+///
+/// A segment quarantined by an earlier repair is never overwritten - a
+/// numeric suffix is appended to the file name instead. The file is hard
+/// linked into the quarantine directory where possible, falling back to a
+/// copy if it is on another filesystem.
+fn quarantine(path: &Path, quarantine_dir: &Path, file_name: &str) -> io::Result<PathBuf> {
+    fs::create_dir_all(quarantine_dir)?;
+
+    let quarantined_path = (0_usize..)
+        .map(|n| match n {
+            0 => quarantine_dir.join(file_name),
+            n => quarantine_dir.join(format!("{file_name}.{n}")),
+        })
+        .find_map(|candidate| {
+            let res = fs::hard_link(path, &candidate).or_else(|e| match e.kind() {
+                io::ErrorKind::AlreadyExists => Err(e),
+                // Most likely EXDEV, as the quarantine directory is on
+                // another filesystem.
+                _ => copy_new(path, &candidate),
+            });
+            match res {
+                Ok(()) => Some(Ok(candidate)),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => None,
+                Err(e) => Some(Err(e)),
+            }
+        })
+        .expect("unbounded candidate iterator")?;
+
+    sync_dir(quarantine_dir)?;
+    Ok(quarantined_path)
+}
+
+/// Copies the file at `from` to a new file at `to`, syncing it to disk.
+///
+/// Fails with [`io::ErrorKind::AlreadyExists`] if `to` exists.
+fn copy_new(from: &Path, to: &Path) -> io::Result<()> {
+    let mut dst = OpenOptions::new().write(true).create_new(true).open(to)?;
+    let res = File::open(from)
+        .and_then(|mut src| io::copy(&mut src, &mut dst))
+        .and_then(|_| dst.sync_all());
+    if res.is_err() {
+        // Don't leave a partial copy behind to be mistaken for the original.
+        let _ = fs::remove_file(to);
+    }
+    res
+}
+
+/// Syncs the directory entries of `dir` to disk, persisting files created
+/// in, or renamed into, it.
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+/// Splits the entries of the segment file `data` into those that are
+/// readable and the byte ranges of the regions between them that are not.
+fn scan_entries(data: &[u8]) -> (Vec<SalvagedEntry>, Vec<Range<usize>>) {
+    let mut entries = vec![];
+    let mut corrupt = vec![];
+    let mut corrupt_start = None;
+
+    let mut offset = FILE_HEADER_LEN;
+    while offset < data.len() {
+        match read_entry(&data[offset..]) {
+            Some((len, ops)) => {
+                if let Some(start) = corrupt_start.take() {
+                    corrupt.push(start..offset);
+                }
+                let sequence_numbers = ops
+                    .iter()
+                    .flat_map(|op| op.table_write_sequence_numbers.values().copied())
+                    .fold(None, |range, seq| match range {
+                        Some((min, max)) => Some((seq.min(min), seq.max(max))),
+                        None => Some((seq, seq)),
+                    });
+                entries.push(SalvagedEntry {
+                    range: offset..offset + len,
+                    sequence_numbers,
+                });
+                offset += len;
+            }
+            None => {
+                // Look for the next entry at every subsequent byte offset.
+                corrupt_start.get_or_insert(offset);
+                offset += 1;
+            }
+        }
+    }
+
+    if let Some(start) = corrupt_start {
+        corrupt.push(start..data.len());
+    }
+
+    (entries, corrupt)
+}
+
+/// Reads the entry at the start of `data`, returning its length (including
+/// its header) and ops, or `None` if there is no readable entry there.
+///
+/// An entry is only accepted if its checksum matches and it decodes to at
+/// least one op, making it vanishingly unlikely that a scan through a
+/// corrupt region finds an entry that was never written.
+fn read_entry(data: &[u8]) -> Option<(usize, Vec<SequencedWalOp>)> {
+    let header = data.get(..ENTRY_HEADER_LEN)?;
+    let checksum = u32::from_be_bytes(header[..4].try_into().expect("4 byte slice"));
+    let len = u32::from_be_bytes(header[4..].try_into().expect("4 byte slice")) as usize;
+    if len == 0 {
+        return None;
+    }
+
+    let compressed = data.get(ENTRY_HEADER_LEN..ENTRY_HEADER_LEN + len)?;
+    if crc32fast::hash(compressed) != checksum {
+        return None;
+    }
+
+    let mut decompressed = vec![];
+    FrameDecoder::new(compressed)
+        .read_to_end(&mut decompressed)
+        .ok()?;
+    let batch = ProtoWalOpBatch::decode(decompressed.as_slice()).ok()?;
+    let ops = batch
+        .ops
+        .into_iter()
+        .map(SequencedWalOp::try_from)
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+    if ops.is_empty() {
+        return None;
+    }
+
+    Some((ENTRY_HEADER_LEN + len, ops))
+}
+
+#[cfg(test)]
+mod tests {
+    use data_types::TableId;
+    use generated_types::influxdata::iox::wal::v1::{sequenced_wal_op::Op as WalOp, PersistOp};
+
+    use super::*;
+    use crate::{ClosedSegmentFileReader, Wal};
+
+    fn op(seq: u64) -> SequencedWalOp {
+        SequencedWalOp {
+            table_write_sequence_numbers: [(TableId::new(1), seq)].into(),
+            op: WalOp::Persist(PersistOp {
+                namespace_id: 1,
+                table_id: 1,
+                partition_id: 1,
+                parquet_file_uuid: "bananas".into(),
+            }),
+        }
+    }
+
+    /// Returns the offset of the entry following the one at `offset`.
+    fn next_entry_offset(data: &[u8], offset: usize) -> usize {
+        let len = u32::from_be_bytes(data[offset + 4..offset + 8].try_into().unwrap());
+        offset + ENTRY_HEADER_LEN + len as usize
+    }
+
+    #[tokio::test]
+    async fn test_repair_corrupt_entry() {
+        let dir = test_helpers::tmp_dir().unwrap();
+        let wal = Wal::new(dir.path()).await.unwrap();
+
+        // Write each op as its own entry
+        for seq in 1..=4 {
+            wal.write_op(op(seq)).changed().await.unwrap();
+        }
+        let (closed, _) = wal.rotate().unwrap();
+        let path = crate::build_segment_path(dir.path(), closed.id());
+
+        // Corrupt the length of the second entry, and the payload of the
+        // third, so the reader can make no progress past the first.
+        let mut data = fs::read(&path).unwrap();
+        let second = next_entry_offset(&data, FILE_HEADER_LEN);
+        let third = next_entry_offset(&data, second);
+        data[second + 4] ^= 0xFF;
+        data[third + ENTRY_HEADER_LEN + 1] ^= 0xFF;
+        fs::write(&path, &data).unwrap();
+
+        let repair = SegmentRepair::new(&path).unwrap();
+        let report = repair.report().clone();
+        assert_eq!(report.segment_id, closed.id());
+        assert_eq!(report.salvaged_entries, 2);
+        assert_eq!(report.corrupt_regions.len(), 1);
+        let region = &report.corrupt_regions[0];
+        assert_eq!(region.offset, second as u64);
+        assert_eq!(
+            region.lost_sequence_numbers,
+            LostSequenceNumbers {
+                after: Some(1),
+                before: Some(4)
+            }
+        );
+        assert_eq!(region.lost_sequence_numbers.to_string(), "2..=3");
+
+        let quarantine_dir = dir.path().join("quarantine");
+        let report = repair.apply(&quarantine_dir).unwrap();
+        let quarantined_path = report.quarantined_path.unwrap();
+        assert_eq!(fs::read(quarantined_path).unwrap(), data);
+
+        let ops = ClosedSegmentFileReader::from_path(&path)
+            .unwrap()
+            .flat_map(|batch| batch.expect("repaired segment should be readable"))
+            .collect::<Vec<_>>();
+        assert_eq!(ops, vec![op(1), op(4)]);
+
+        // Repairing an intact segment changes nothing
+        let report = SegmentRepair::new(&path)
+            .unwrap()
+            .apply(&quarantine_dir)
+            .unwrap();
+        assert!(report.is_intact());
+        assert_eq!(report.quarantined_path, None);
+    }
+
+    #[tokio::test]
+    async fn test_repair_preserves_earlier_quarantine() {
+        let dir = test_helpers::tmp_dir().unwrap();
+        let wal = Wal::new(dir.path()).await.unwrap();
+
+        for seq in 1..=3 {
+            wal.write_op(op(seq)).changed().await.unwrap();
+        }
+        let (closed, _) = wal.rotate().unwrap();
+        let path = crate::build_segment_path(dir.path(), closed.id());
+        let quarantine_dir = dir.path().join("quarantine");
+
+        // Truncate the last entry, repair, then truncate the last entry of
+        // the repaired segment and repair again.
+        let mut quarantined = vec![];
+        for _ in 0..2 {
+            let mut data = fs::read(&path).unwrap();
+            data.truncate(data.len() - 1);
+            fs::write(&path, &data).unwrap();
+
+            let report = SegmentRepair::new(&path)
+                .unwrap()
+                .apply(&quarantine_dir)
+                .unwrap();
+            quarantined.push((report.quarantined_path.unwrap(), data));
+        }
+
+        assert_ne!(quarantined[0].0, quarantined[1].0);
+        for (quarantined_path, data) in quarantined {
+            assert_eq!(fs::read(quarantined_path).unwrap(), data);
+        }
+
+        let ops = ClosedSegmentFileReader::from_path(&path)
+            .unwrap()
+            .flat_map(|batch| batch.expect("repaired segment should be readable"))
+            .collect::<Vec<_>>();
+        assert_eq!(ops, vec![op(1)]);
+
+        // No temporary file is left in the WAL directory.
+        let files = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|v| v.unwrap().file_name())
+            .filter(|v| v != "quarantine")
+            .collect::<Vec<_>>();
+        assert_eq!(files, vec![path.file_name().unwrap().to_owned()]);
+    }
+
+    #[test]
+    fn test_lost_sequence_numbers_display() {
+        let lost = |after, before| LostSequenceNumbers { after, before }.to_string();
+        assert_eq!(lost(Some(3), Some(4)), "none");
+        assert_eq!(lost(Some(3), None), "4..");
+        assert_eq!(lost(None, Some(4)), "..=3");
+        assert_eq!(lost(None, None), "unknown");
+    }
+}